@@ -0,0 +1,15 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // The Speex decoder links to the system libspeex. There is no libspeex on
+    // web, so the decoder is left out there.
+    #[cfg(feature = "speex")]
+    if std::env::var("CARGO_CFG_TARGET_ARCH").as_deref() != Ok("wasm32") {
+        if let Err(e) = pkg_config::probe_library("speex") {
+            panic!(
+                "The `speex` feature requires libspeex and its pkg-config file: {}",
+                e
+            );
+        }
+    }
+}
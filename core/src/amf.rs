@@ -0,0 +1,130 @@
+//! Action Message Format (AMF) serialization
+//!
+//! This implements the subset of AMF0 and AMF3 needed to read and write local
+//! shared objects in the `.sol` format used by Flash Player. Values are held
+//! in a GC-free graph: complex values are stored once in `Lso::objects` and
+//! referred to by index, so that shared references and cycles survive a trip
+//! through storage.
+
+mod read;
+mod write;
+
+#[cfg(test)]
+mod tests;
+
+use thiserror::Error;
+
+pub use read::read_lso;
+pub use write::write_lso;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Unexpected end of AMF data")]
+    EndOfData,
+
+    #[error("Not a shared object file")]
+    InvalidHeader,
+
+    #[error("Unsupported AMF version {0}")]
+    UnsupportedVersion(u32),
+
+    #[error("Unsupported AMF marker {0:#04x}")]
+    UnsupportedMarker(u8),
+
+    #[error("Invalid AMF reference {0}")]
+    InvalidReference(usize),
+
+    #[error("AMF values are nested too deeply")]
+    TooDeep,
+
+    #[error("Cannot read externalizable class {0}")]
+    Externalizable(String),
+
+    #[error("Invalid UTF-8 in AMF string")]
+    InvalidString(#[from] std::str::Utf8Error),
+}
+
+/// The encoding used for the body of a shared object.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmfVersion {
+    Amf0,
+    Amf3,
+}
+
+/// The index of a complex value within `Lso::objects`.
+pub type ObjectId = usize;
+
+/// A single value stored in a shared object.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AmfValue {
+    Undefined,
+    Null,
+    Bool(bool),
+    Number(f64),
+    Integer(i32),
+    String(String),
+
+    /// A date, in milliseconds since the Unix epoch.
+    Date(f64),
+
+    /// An XML document, in its serialized form.
+    Xml(String),
+
+    /// A reference to a complex value in `Lso::objects`.
+    Object(ObjectId),
+}
+
+/// A complex value, which may be referenced by any number of other values.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AmfObject {
+    /// An anonymous object, or an instance of a registered class.
+    Object {
+        class_name: Option<String>,
+        properties: Vec<(String, AmfValue)>,
+    },
+
+    /// An array of a given length.
+    ///
+    /// Elements are keyed by their index, and stored alongside any other
+    /// properties of the array. Indices without an element are holes.
+    Array {
+        length: usize,
+        elements: Vec<(String, AmfValue)>,
+    },
+
+    /// Raw binary data.
+    ByteArray(Vec<u8>),
+}
+
+/// A local shared object, as stored in a `.sol` file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lso {
+    /// The name of the shared object.
+    pub name: String,
+
+    /// The encoding used for the body.
+    pub version: AmfVersion,
+
+    /// The properties of the shared object's `data`.
+    pub body: Vec<(String, AmfValue)>,
+
+    /// All complex values held within the body.
+    pub objects: Vec<AmfObject>,
+}
+
+impl Lso {
+    pub fn new(name: String, version: AmfVersion) -> Self {
+        Self {
+            name,
+            version,
+            body: Vec::new(),
+            objects: Vec::new(),
+        }
+    }
+
+    /// Add a complex value to this shared object, returning its ID.
+    pub fn add_object(&mut self, object: AmfObject) -> ObjectId {
+        self.objects.push(object);
+        self.objects.len() - 1
+    }
+}
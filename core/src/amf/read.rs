@@ -0,0 +1,498 @@
+//! AMF decoding
+
+use crate::amf::{AmfObject, AmfValue, AmfVersion, Error, Lso, ObjectId};
+
+/// How deeply values may be nested inside objects and arrays.
+///
+/// Values are read recursively, so this keeps crafted files from
+/// overflowing the stack.
+const MAX_DEPTH: usize = 256;
+
+/// The class information of an AMF3 object.
+#[derive(Clone)]
+struct Traits {
+    class_name: Option<String>,
+    sealed: Vec<String>,
+    is_dynamic: bool,
+}
+
+/// The reference tables used by AMF3.
+#[derive(Default)]
+struct Amf3Tables {
+    strings: Vec<String>,
+    objects: Vec<AmfValue>,
+    traits: Vec<Traits>,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    lso: Lso,
+
+    /// Objects which may be referenced by AMF0 values.
+    amf0_objects: Vec<ObjectId>,
+
+    amf3: Amf3Tables,
+
+    /// The number of values currently being read.
+    depth: usize,
+}
+
+/// Read a local shared object from the contents of a `.sol` file.
+pub fn read_lso(data: &[u8]) -> Result<Lso, Error> {
+    let mut reader = Reader {
+        data,
+        lso: Lso::new(String::new(), AmfVersion::Amf0),
+        amf0_objects: Vec::new(),
+        amf3: Amf3Tables::default(),
+        depth: 0,
+    };
+
+    if reader.read_u16()? != 0x00BF {
+        return Err(Error::InvalidHeader);
+    }
+
+    // The length of the file is redundant, so it isn't checked.
+    reader.read_u32()?;
+
+    if reader.read_bytes(4)? != b"TCSO" || reader.read_bytes(6)? != [0, 4, 0, 0, 0, 0] {
+        return Err(Error::InvalidHeader);
+    }
+
+    let name_length = reader.read_u16()?;
+    reader.lso.name = reader.read_utf8(name_length as usize)?;
+    reader.lso.version = match reader.read_u32()? {
+        0 => AmfVersion::Amf0,
+        3 => AmfVersion::Amf3,
+        version => return Err(Error::UnsupportedVersion(version)),
+    };
+
+    while !reader.data.is_empty() {
+        let (name, value) = match reader.lso.version {
+            AmfVersion::Amf0 => {
+                let name_length = reader.read_u16()?;
+                let name = reader.read_utf8(name_length as usize)?;
+                (name, reader.read_amf0_value()?)
+            }
+            AmfVersion::Amf3 => {
+                let name = reader.read_amf3_string()?;
+                (name, reader.read_amf3_value()?)
+            }
+        };
+
+        // Each entry is followed by a padding byte.
+        reader.read_u8()?;
+        reader.lso.body.push((name, value));
+    }
+
+    Ok(reader.lso)
+}
+
+impl<'a> Reader<'a> {
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() < length {
+            return Err(Error::EndOfData);
+        }
+
+        let (bytes, rest) = self.data.split_at(length);
+        self.data = rest;
+
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, Error> {
+        let bytes = self.read_bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_f64(&mut self) -> Result<f64, Error> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.read_bytes(8)?);
+        Ok(f64::from_be_bytes(bytes))
+    }
+
+    fn read_utf8(&mut self, length: usize) -> Result<String, Error> {
+        Ok(std::str::from_utf8(self.read_bytes(length)?)?.to_string())
+    }
+
+    /// Reserve a slot for a complex value, so that it can be referenced
+    /// before its contents have been read.
+    fn reserve_object(&mut self) -> ObjectId {
+        self.lso.add_object(AmfObject::Object {
+            class_name: None,
+            properties: Vec::new(),
+        })
+    }
+
+    /// Run `read` to read a value nested one level deeper than the current one.
+    fn nested(
+        &mut self,
+        read: impl FnOnce(&mut Self) -> Result<AmfValue, Error>,
+    ) -> Result<AmfValue, Error> {
+        if self.depth >= MAX_DEPTH {
+            return Err(Error::TooDeep);
+        }
+
+        self.depth += 1;
+        let value = read(self);
+        self.depth -= 1;
+
+        value
+    }
+
+    fn read_amf0_value(&mut self) -> Result<AmfValue, Error> {
+        self.nested(Self::read_amf0_value_inner)
+    }
+
+    fn read_amf0_value_inner(&mut self) -> Result<AmfValue, Error> {
+        Ok(match self.read_u8()? {
+            0x00 => AmfValue::Number(self.read_f64()?),
+            0x01 => AmfValue::Bool(self.read_u8()? != 0),
+            0x02 => {
+                let length = self.read_u16()?;
+                AmfValue::String(self.read_utf8(length as usize)?)
+            }
+            0x03 => {
+                let id = self.reserve_object();
+                self.amf0_objects.push(id);
+
+                let properties = self.read_amf0_properties()?;
+                self.lso.objects[id] = AmfObject::Object {
+                    class_name: None,
+                    properties,
+                };
+
+                AmfValue::Object(id)
+            }
+            0x05 => AmfValue::Null,
+            0x06 => AmfValue::Undefined,
+            0x07 => {
+                let index = self.read_u16()? as usize;
+                let id = self
+                    .amf0_objects
+                    .get(index)
+                    .ok_or(Error::InvalidReference(index))?;
+
+                AmfValue::Object(*id)
+            }
+            0x08 => {
+                let id = self.reserve_object();
+                self.amf0_objects.push(id);
+
+                let length = self.read_u32()? as usize;
+                let elements = self.read_amf0_properties()?;
+                self.lso.objects[id] = AmfObject::Array { length, elements };
+
+                AmfValue::Object(id)
+            }
+            0x0A => {
+                let id = self.reserve_object();
+                self.amf0_objects.push(id);
+
+                let length = self.read_u32()? as usize;
+                let mut elements = Vec::new();
+                for i in 0..length {
+                    elements.push((i.to_string(), self.read_amf0_value()?));
+                }
+
+                self.lso.objects[id] = AmfObject::Array { length, elements };
+
+                AmfValue::Object(id)
+            }
+            0x0B => {
+                let time = self.read_f64()?;
+
+                // The timezone is reserved, and always zero.
+                self.read_u16()?;
+
+                AmfValue::Date(time)
+            }
+            0x0C => {
+                let length = self.read_u32()?;
+                AmfValue::String(self.read_utf8(length as usize)?)
+            }
+            0x0F => {
+                let length = self.read_u32()?;
+                AmfValue::Xml(self.read_utf8(length as usize)?)
+            }
+            0x10 => {
+                let id = self.reserve_object();
+                self.amf0_objects.push(id);
+
+                let name_length = self.read_u16()?;
+                let class_name = self.read_utf8(name_length as usize)?;
+                let properties = self.read_amf0_properties()?;
+                self.lso.objects[id] = AmfObject::Object {
+                    class_name: Some(class_name),
+                    properties,
+                };
+
+                AmfValue::Object(id)
+            }
+            0x11 => {
+                // Each switch to AMF3 starts with fresh reference tables.
+                let tables = std::mem::take(&mut self.amf3);
+                let value = self.read_amf3_value();
+                self.amf3 = tables;
+
+                value?
+            }
+            marker => return Err(Error::UnsupportedMarker(marker)),
+        })
+    }
+
+    /// Read the properties of an AMF0 object, up to the object end marker.
+    fn read_amf0_properties(&mut self) -> Result<Vec<(String, AmfValue)>, Error> {
+        let mut properties = Vec::new();
+
+        loop {
+            let name_length = self.read_u16()?;
+            let name = self.read_utf8(name_length as usize)?;
+
+            if name.is_empty() && self.data.first() == Some(&0x09) {
+                self.read_u8()?;
+                return Ok(properties);
+            }
+
+            let value = self.read_amf0_value()?;
+            properties.push((name, value));
+        }
+    }
+
+    fn read_u29(&mut self) -> Result<u32, Error> {
+        let mut result = 0;
+
+        for _ in 0..3 {
+            let byte = self.read_u8()?;
+            result = (result << 7) | (byte & 0x7F) as u32;
+
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+
+        // The fourth byte uses all 8 bits.
+        Ok((result << 8) | self.read_u8()? as u32)
+    }
+
+    fn read_amf3_string(&mut self) -> Result<String, Error> {
+        let header = self.read_u29()? as usize;
+
+        if header & 1 == 0 {
+            let index = header >> 1;
+            return self
+                .amf3
+                .strings
+                .get(index)
+                .cloned()
+                .ok_or(Error::InvalidReference(index));
+        }
+
+        let string = self.read_utf8(header >> 1)?;
+
+        // The empty string is never sent by reference.
+        if !string.is_empty() {
+            self.amf3.strings.push(string.clone());
+        }
+
+        Ok(string)
+    }
+
+    /// Read the header of an AMF3 complex value.
+    ///
+    /// This yields `Err` with the referenced value if the header is a
+    /// reference, and `Ok` with the remaining header bits otherwise.
+    fn read_amf3_header(&mut self) -> Result<Result<u32, AmfValue>, Error> {
+        let header = self.read_u29()?;
+
+        if header & 1 == 0 {
+            let index = (header >> 1) as usize;
+            return self
+                .amf3
+                .objects
+                .get(index)
+                .cloned()
+                .map(Err)
+                .ok_or(Error::InvalidReference(index));
+        }
+
+        Ok(Ok(header >> 1))
+    }
+
+    /// Reserve a slot for a complex AMF3 value, and add it to the object
+    /// reference table.
+    fn reserve_amf3_object(&mut self) -> ObjectId {
+        let id = self.reserve_object();
+        self.amf3.objects.push(AmfValue::Object(id));
+
+        id
+    }
+
+    fn read_amf3_value(&mut self) -> Result<AmfValue, Error> {
+        self.nested(Self::read_amf3_value_inner)
+    }
+
+    fn read_amf3_value_inner(&mut self) -> Result<AmfValue, Error> {
+        let marker = self.read_u8()?;
+
+        match marker {
+            0x00 => return Ok(AmfValue::Undefined),
+            0x01 => return Ok(AmfValue::Null),
+            0x02 => return Ok(AmfValue::Bool(false)),
+            0x03 => return Ok(AmfValue::Bool(true)),
+            0x04 => {
+                // Integers are 29 bits wide, and must be sign-extended.
+                let value = self.read_u29()?;
+                return Ok(AmfValue::Integer(((value << 3) as i32) >> 3));
+            }
+            0x05 => return Ok(AmfValue::Number(self.read_f64()?)),
+            0x06 => return Ok(AmfValue::String(self.read_amf3_string()?)),
+            0x07..=0x10 => {}
+            marker => return Err(Error::UnsupportedMarker(marker)),
+        }
+
+        let header = match self.read_amf3_header()? {
+            Ok(header) => header,
+            Err(value) => return Ok(value),
+        };
+
+        match marker {
+            0x07 | 0x0B => {
+                let value = AmfValue::Xml(self.read_utf8(header as usize)?);
+                self.amf3.objects.push(value.clone());
+
+                Ok(value)
+            }
+            0x08 => {
+                let value = AmfValue::Date(self.read_f64()?);
+                self.amf3.objects.push(value.clone());
+
+                Ok(value)
+            }
+            0x09 => {
+                let id = self.reserve_amf3_object();
+
+                let mut associative = Vec::new();
+                loop {
+                    let name = self.read_amf3_string()?;
+                    if name.is_empty() {
+                        break;
+                    }
+
+                    let value = self.read_amf3_value()?;
+                    associative.push((name, value));
+                }
+
+                let length = header as usize;
+                let mut elements = Vec::new();
+                for i in 0..length {
+                    elements.push((i.to_string(), self.read_amf3_value()?));
+                }
+                elements.extend(associative);
+
+                self.lso.objects[id] = AmfObject::Array { length, elements };
+
+                Ok(AmfValue::Object(id))
+            }
+            0x0A => {
+                let traits = if header & 1 == 0 {
+                    let index = (header >> 1) as usize;
+                    self.amf3
+                        .traits
+                        .get(index)
+                        .cloned()
+                        .ok_or(Error::InvalidReference(index))?
+                } else if header & 2 != 0 {
+                    return Err(Error::Externalizable(self.read_amf3_string()?));
+                } else {
+                    let is_dynamic = header & 4 != 0;
+                    let class_name = Some(self.read_amf3_string()?).filter(|n| !n.is_empty());
+                    let mut sealed = Vec::new();
+                    for _ in 0..(header >> 3) {
+                        sealed.push(self.read_amf3_string()?);
+                    }
+
+                    let traits = Traits {
+                        class_name,
+                        sealed,
+                        is_dynamic,
+                    };
+                    self.amf3.traits.push(traits.clone());
+
+                    traits
+                };
+
+                let id = self.reserve_amf3_object();
+
+                let mut properties = Vec::new();
+                for name in traits.sealed {
+                    let value = self.read_amf3_value()?;
+                    properties.push((name, value));
+                }
+
+                if traits.is_dynamic {
+                    loop {
+                        let name = self.read_amf3_string()?;
+                        if name.is_empty() {
+                            break;
+                        }
+
+                        let value = self.read_amf3_value()?;
+                        properties.push((name, value));
+                    }
+                }
+
+                self.lso.objects[id] = AmfObject::Object {
+                    class_name: traits.class_name,
+                    properties,
+                };
+
+                Ok(AmfValue::Object(id))
+            }
+            0x0C => {
+                let bytes = self.read_bytes(header as usize)?.to_vec();
+                let id = self.lso.add_object(AmfObject::ByteArray(bytes));
+                self.amf3.objects.push(AmfValue::Object(id));
+
+                Ok(AmfValue::Object(id))
+            }
+            _ => {
+                // AS3 vectors have no equivalent here, so they are read as
+                // arrays instead.
+                let id = self.reserve_amf3_object();
+
+                // Whether or not the vector is fixed-length.
+                self.read_u8()?;
+
+                if marker == 0x10 {
+                    // The type of the vector's values.
+                    self.read_amf3_string()?;
+                }
+
+                let length = header as usize;
+                let mut elements = Vec::new();
+                for i in 0..length {
+                    let value = match marker {
+                        0x0D => AmfValue::Integer(self.read_u32()? as i32),
+                        0x0E => AmfValue::Number(self.read_u32()? as f64),
+                        0x0F => AmfValue::Number(self.read_f64()?),
+                        _ => self.read_amf3_value()?,
+                    };
+                    elements.push((i.to_string(), value));
+                }
+
+                self.lso.objects[id] = AmfObject::Array { length, elements };
+
+                Ok(AmfValue::Object(id))
+            }
+        }
+    }
+}
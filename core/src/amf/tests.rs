@@ -0,0 +1,197 @@
+//! AMF tests
+
+use crate::amf::{read_lso, write_lso, AmfObject, AmfValue, AmfVersion, Error, Lso};
+
+/// Build a shared object exercising every kind of value, including shared
+/// references and cycles.
+fn sample_lso(version: AmfVersion) -> Lso {
+    let mut lso = Lso::new("test".to_string(), version);
+
+    let child = lso.add_object(AmfObject::Object {
+        class_name: None,
+        properties: vec![("name".to_string(), AmfValue::String("child".to_string()))],
+    });
+    let array = lso.add_object(AmfObject::Array {
+        length: 3,
+        elements: vec![
+            ("0".to_string(), AmfValue::Number(1.5)),
+            ("1".to_string(), AmfValue::Undefined),
+            ("2".to_string(), AmfValue::Object(child)),
+            ("label".to_string(), AmfValue::String("name".to_string())),
+        ],
+    });
+    let typed = lso.add_object(AmfObject::Object {
+        class_name: Some("flash.geom.Point".to_string()),
+        properties: vec![("x".to_string(), AmfValue::Number(3.0))],
+    });
+
+    // The root refers to itself, and to `child` through two paths.
+    let root = lso.add_object(AmfObject::Object {
+        class_name: None,
+        properties: Vec::new(),
+    });
+    lso.objects[root] = AmfObject::Object {
+        class_name: None,
+        properties: vec![
+            ("self".to_string(), AmfValue::Object(root)),
+            ("child".to_string(), AmfValue::Object(child)),
+            ("array".to_string(), AmfValue::Object(array)),
+        ],
+    };
+
+    lso.body = vec![
+        ("undefined".to_string(), AmfValue::Undefined),
+        ("null".to_string(), AmfValue::Null),
+        ("bool".to_string(), AmfValue::Bool(true)),
+        ("number".to_string(), AmfValue::Number(-0.25)),
+        ("string".to_string(), AmfValue::String("name".to_string())),
+        ("date".to_string(), AmfValue::Date(1_600_000_000_000.0)),
+        (
+            "xml".to_string(),
+            AmfValue::Xml("<a b=\"c\" />".to_string()),
+        ),
+        ("root".to_string(), AmfValue::Object(root)),
+        ("typed".to_string(), AmfValue::Object(typed)),
+    ];
+
+    lso
+}
+
+/// Objects are renumbered by the order in which they appear, so compare the
+/// result of a second round-trip against the first.
+fn assert_round_trip(lso: &Lso) -> Lso {
+    let first = read_lso(&write_lso(lso)).expect("Read written shared object");
+    let second = read_lso(&write_lso(&first)).expect("Read rewritten shared object");
+
+    assert_eq!(first, second);
+    assert_eq!(first.name, lso.name);
+    assert_eq!(first.version, lso.version);
+    assert_eq!(first.body.len(), lso.body.len());
+    assert_eq!(first.objects.len(), lso.objects.len());
+
+    first
+}
+
+#[test]
+fn amf0_round_trip() {
+    let lso = assert_round_trip(&sample_lso(AmfVersion::Amf0));
+
+    let root = match lso.body[7] {
+        (_, AmfValue::Object(root)) => root,
+        _ => panic!("Root should be an object"),
+    };
+    match &lso.objects[root] {
+        AmfObject::Object { properties, .. } => {
+            assert_eq!(properties[0].1, AmfValue::Object(root));
+        }
+        _ => panic!("Root should be an anonymous object"),
+    }
+}
+
+#[test]
+fn amf3_round_trip() {
+    let lso = assert_round_trip(&sample_lso(AmfVersion::Amf3));
+
+    assert_eq!(lso.body[5].1, AmfValue::Date(1_600_000_000_000.0));
+    assert_eq!(lso.body[6].1, AmfValue::Xml("<a b=\"c\" />".to_string()));
+}
+
+#[test]
+fn amf0_sparse_array() {
+    let mut lso = Lso::new("sparse".to_string(), AmfVersion::Amf0);
+    let array = lso.add_object(AmfObject::Array {
+        length: 5,
+        elements: vec![
+            ("0".to_string(), AmfValue::String("elem0".to_string())),
+            ("4".to_string(), AmfValue::String("elem4".to_string())),
+            ("-1".to_string(), AmfValue::Bool(false)),
+        ],
+    });
+    lso.body
+        .push(("array".to_string(), AmfValue::Object(array)));
+
+    // Holes are preserved, as only present elements are written.
+    assert_eq!(
+        read_lso(&write_lso(&lso)).expect("Read written shared object"),
+        lso
+    );
+}
+
+#[test]
+fn amf3_integers() {
+    let mut lso = Lso::new("ints".to_string(), AmfVersion::Amf3);
+    for (i, value) in [0, 127, 128, 16383, 16384, 0x0FFF_FFFF, -1, -0x1000_0000]
+        .iter()
+        .enumerate()
+    {
+        lso.body.push((i.to_string(), AmfValue::Integer(*value)));
+    }
+
+    // Integers outside of the 29-bit range are written as numbers.
+    lso.body
+        .push(("big".to_string(), AmfValue::Integer(0x1000_0000)));
+
+    let read = read_lso(&write_lso(&lso)).expect("Read written shared object");
+    assert_eq!(read.body[..8], lso.body[..8]);
+    assert_eq!(read.body[8].1, AmfValue::Number(268_435_456.0));
+}
+
+#[test]
+fn amf0_known_file() {
+    let data = [
+        0x00, 0xBF, 0x00, 0x00, 0x00, 0x21, b'T', b'C', b'S', b'O', 0x00, 0x04, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x04, b't', b'e', b's', b't', 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, b'a', 0x00,
+        0x3F, 0xF0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    let mut lso = Lso::new("test".to_string(), AmfVersion::Amf0);
+    lso.body.push(("a".to_string(), AmfValue::Number(1.0)));
+
+    assert_eq!(read_lso(&data).expect("Read shared object"), lso);
+    assert_eq!(write_lso(&lso), &data[..]);
+}
+
+#[test]
+fn invalid_data() {
+    assert!(read_lso(b"{\"a\":1}").is_err());
+    assert!(read_lso(&[0x00, 0xBF, 0x00]).is_err());
+}
+
+#[test]
+fn deeply_nested() {
+    let header = [
+        0x00, 0xBF, 0x00, 0x00, 0x00, 0x00, b'T', b'C', b'S', b'O', 0x00, 0x04, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x04, b't', b'e', b's', b't',
+    ];
+
+    // An entry `a` holding an object, whose property `a` holds another object, and so on.
+    let nested = |version: u32, depth: usize| {
+        let mut data = header.to_vec();
+        data.extend_from_slice(&version.to_be_bytes());
+        for _ in 0..depth {
+            match version {
+                0 => data.extend_from_slice(&[0x00, 0x01, b'a', 0x03]),
+                // A dynamic anonymous object, with the name `a` sent inline first
+                // and by reference after that.
+                _ if data.len() == header.len() + 4 => {
+                    data.extend_from_slice(&[0x03, b'a', 0x0A, 0x0B, 0x01])
+                }
+                _ => data.extend_from_slice(&[0x00, 0x0A, 0x0B, 0x01]),
+            }
+        }
+        data
+    };
+
+    for &version in &[0, 3] {
+        assert!(matches!(
+            read_lso(&nested(version, 10_000)),
+            Err(Error::TooDeep)
+        ));
+
+        // Shallower files are only cut off at the end.
+        assert!(matches!(
+            read_lso(&nested(version, 100)),
+            Err(Error::EndOfData)
+        ));
+    }
+}
@@ -0,0 +1,346 @@
+//! AMF encoding
+
+use crate::amf::{AmfObject, AmfValue, AmfVersion, Lso, ObjectId};
+use std::collections::HashMap;
+
+/// The reference tables used by AMF3.
+#[derive(Default)]
+struct Amf3Tables {
+    strings: HashMap<String, u32>,
+    objects: HashMap<ObjectId, u32>,
+
+    /// The number of entries in the object reference table.
+    ///
+    /// Dates and XML documents also occupy entries in the table, so this is
+    /// not necessarily the same as the length of `objects`.
+    object_count: u32,
+}
+
+struct Writer<'a> {
+    lso: &'a Lso,
+    output: Vec<u8>,
+
+    /// The reference indices of objects already written as AMF0.
+    amf0_objects: HashMap<ObjectId, usize>,
+
+    amf3: Amf3Tables,
+}
+
+/// Write a local shared object into the contents of a `.sol` file.
+pub fn write_lso(lso: &Lso) -> Vec<u8> {
+    let mut writer = Writer {
+        lso,
+        output: Vec::new(),
+        amf0_objects: HashMap::new(),
+        amf3: Amf3Tables::default(),
+    };
+
+    writer.write_u16(0x00BF);
+
+    // The length of the file is filled in once the body has been written.
+    writer.write_u32(0);
+
+    writer.output.extend_from_slice(b"TCSO");
+    writer.output.extend_from_slice(&[0, 4, 0, 0, 0, 0]);
+    writer.write_amf0_utf8(&lso.name);
+    writer.write_u32(match lso.version {
+        AmfVersion::Amf0 => 0,
+        AmfVersion::Amf3 => 3,
+    });
+
+    for (name, value) in &lso.body {
+        match lso.version {
+            AmfVersion::Amf0 => {
+                writer.write_amf0_utf8(name);
+                writer.write_amf0_value(value);
+            }
+            AmfVersion::Amf3 => {
+                writer.write_amf3_string(name);
+                writer.write_amf3_value(value);
+            }
+        }
+
+        // Each entry is followed by a padding byte.
+        writer.write_u8(0);
+    }
+
+    let length = (writer.output.len() - 6) as u32;
+    writer.output[2..6].copy_from_slice(&length.to_be_bytes());
+
+    writer.output
+}
+
+impl<'a> Writer<'a> {
+    fn write_u8(&mut self, value: u8) {
+        self.output.push(value);
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.output.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.output.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn write_f64(&mut self, value: f64) {
+        self.output.extend_from_slice(&value.to_be_bytes());
+    }
+
+    /// Write a string with a 16-bit length prefix.
+    fn write_amf0_utf8(&mut self, value: &str) {
+        self.write_u16(value.len() as u16);
+        self.output.extend_from_slice(value.as_bytes());
+    }
+
+    fn write_amf0_value(&mut self, value: &AmfValue) {
+        match value {
+            AmfValue::Undefined => self.write_u8(0x06),
+            AmfValue::Null => self.write_u8(0x05),
+            AmfValue::Bool(value) => {
+                self.write_u8(0x01);
+                self.write_u8(*value as u8);
+            }
+            AmfValue::Number(value) => {
+                self.write_u8(0x00);
+                self.write_f64(*value);
+            }
+            AmfValue::Integer(value) => {
+                self.write_u8(0x00);
+                self.write_f64(*value as f64);
+            }
+            AmfValue::String(value) if value.len() > u16::MAX as usize => {
+                self.write_u8(0x0C);
+                self.write_u32(value.len() as u32);
+                self.output.extend_from_slice(value.as_bytes());
+            }
+            AmfValue::String(value) => {
+                self.write_u8(0x02);
+                self.write_amf0_utf8(value);
+            }
+            AmfValue::Date(time) => {
+                self.write_u8(0x0B);
+                self.write_f64(*time);
+                self.write_u16(0);
+            }
+            AmfValue::Xml(value) => {
+                self.write_u8(0x0F);
+                self.write_u32(value.len() as u32);
+                self.output.extend_from_slice(value.as_bytes());
+            }
+            AmfValue::Object(id) => self.write_amf0_object(*id),
+        }
+    }
+
+    fn write_amf0_object(&mut self, id: ObjectId) {
+        let lso = self.lso;
+        let object = if let Some(object) = lso.objects.get(id) {
+            object
+        } else {
+            // Dangling references can't be written, so they become undefined.
+            self.write_u8(0x06);
+            return;
+        };
+
+        if let Some(index) = self.amf0_objects.get(&id).copied() {
+            if index <= u16::MAX as usize {
+                self.write_u8(0x07);
+                self.write_u16(index as u16);
+            } else {
+                // Too many objects to reference this one.
+                self.write_u8(0x05);
+            }
+
+            return;
+        }
+
+        match object {
+            AmfObject::Object {
+                class_name,
+                properties,
+            } => {
+                self.amf0_objects.insert(id, self.amf0_objects.len());
+
+                if let Some(class_name) = class_name {
+                    self.write_u8(0x10);
+                    self.write_amf0_utf8(class_name);
+                } else {
+                    self.write_u8(0x03);
+                }
+
+                for (name, value) in properties {
+                    self.write_amf0_utf8(name);
+                    self.write_amf0_value(value);
+                }
+
+                self.output.extend_from_slice(&[0, 0, 0x09]);
+            }
+            AmfObject::Array { length, elements } => {
+                self.amf0_objects.insert(id, self.amf0_objects.len());
+
+                self.write_u8(0x08);
+                self.write_u32(*length as u32);
+
+                for (name, value) in elements {
+                    self.write_amf0_utf8(name);
+                    self.write_amf0_value(value);
+                }
+
+                self.output.extend_from_slice(&[0, 0, 0x09]);
+            }
+            AmfObject::ByteArray(_) => {
+                // Byte arrays can only be represented in AMF3.
+                let tables = std::mem::take(&mut self.amf3);
+                self.write_u8(0x11);
+                self.write_amf3_value(&AmfValue::Object(id));
+                self.amf3 = tables;
+            }
+        }
+    }
+
+    fn write_u29(&mut self, value: u32) {
+        let value = value & 0x1FFF_FFFF;
+
+        if value < 0x80 {
+            self.write_u8(value as u8);
+        } else if value < 0x4000 {
+            self.write_u8((value >> 7) as u8 | 0x80);
+            self.write_u8(value as u8 & 0x7F);
+        } else if value < 0x20_0000 {
+            self.write_u8((value >> 14) as u8 | 0x80);
+            self.write_u8((value >> 7) as u8 | 0x80);
+            self.write_u8(value as u8 & 0x7F);
+        } else {
+            self.write_u8((value >> 22) as u8 | 0x80);
+            self.write_u8((value >> 15) as u8 | 0x80);
+            self.write_u8((value >> 8) as u8 | 0x80);
+            self.write_u8(value as u8);
+        }
+    }
+
+    fn write_amf3_string(&mut self, value: &str) {
+        if let Some(index) = self.amf3.strings.get(value).copied() {
+            self.write_u29(index << 1);
+            return;
+        }
+
+        // The empty string is never sent by reference.
+        if !value.is_empty() {
+            let index = self.amf3.strings.len() as u32;
+            self.amf3.strings.insert(value.to_string(), index);
+        }
+
+        self.write_u29(((value.len() as u32) << 1) | 1);
+        self.output.extend_from_slice(value.as_bytes());
+    }
+
+    /// Write dynamic properties, followed by the empty string that ends them.
+    fn write_amf3_properties(&mut self, properties: &[(String, AmfValue)]) {
+        for (name, value) in properties {
+            // An empty name would end the properties early.
+            if !name.is_empty() {
+                self.write_amf3_string(name);
+                self.write_amf3_value(value);
+            }
+        }
+
+        self.write_amf3_string("");
+    }
+
+    fn write_amf3_value(&mut self, value: &AmfValue) {
+        match value {
+            AmfValue::Undefined => self.write_u8(0x00),
+            AmfValue::Null => self.write_u8(0x01),
+            AmfValue::Bool(false) => self.write_u8(0x02),
+            AmfValue::Bool(true) => self.write_u8(0x03),
+            AmfValue::Integer(value) if (-0x1000_0000..0x1000_0000).contains(value) => {
+                self.write_u8(0x04);
+                self.write_u29(*value as u32);
+            }
+            AmfValue::Integer(value) => {
+                self.write_u8(0x05);
+                self.write_f64(*value as f64);
+            }
+            AmfValue::Number(value) => {
+                self.write_u8(0x05);
+                self.write_f64(*value);
+            }
+            AmfValue::String(value) => {
+                self.write_u8(0x06);
+                self.write_amf3_string(value);
+            }
+            AmfValue::Date(time) => {
+                self.amf3.object_count += 1;
+                self.write_u8(0x08);
+                self.write_u29(1);
+                self.write_f64(*time);
+            }
+            AmfValue::Xml(value) => {
+                self.amf3.object_count += 1;
+                self.write_u8(0x0B);
+                self.write_u29(((value.len() as u32) << 1) | 1);
+                self.output.extend_from_slice(value.as_bytes());
+            }
+            AmfValue::Object(id) => self.write_amf3_object(*id),
+        }
+    }
+
+    fn write_amf3_object(&mut self, id: ObjectId) {
+        let lso = self.lso;
+        let object = if let Some(object) = lso.objects.get(id) {
+            object
+        } else {
+            // Dangling references can't be written, so they become undefined.
+            self.write_u8(0x00);
+            return;
+        };
+
+        let marker = match object {
+            AmfObject::Object { .. } => 0x0A,
+            AmfObject::Array { .. } => 0x09,
+            AmfObject::ByteArray(_) => 0x0C,
+        };
+        self.write_u8(marker);
+
+        if let Some(index) = self.amf3.objects.get(&id).copied() {
+            self.write_u29(index << 1);
+            return;
+        }
+
+        self.amf3.objects.insert(id, self.amf3.object_count);
+        self.amf3.object_count += 1;
+
+        match object {
+            AmfObject::Object {
+                class_name,
+                properties,
+            } => {
+                // Objects are always written with inline, dynamic traits.
+                self.write_u29(0b1011);
+                self.write_amf3_string(class_name.as_deref().unwrap_or(""));
+                self.write_amf3_properties(properties);
+            }
+            AmfObject::Array { elements, .. } => {
+                // Only leading elements can be written densely; any others,
+                // such as those after a hole, are written by name instead.
+                let dense_length = elements
+                    .iter()
+                    .enumerate()
+                    .take_while(|(i, (name, _))| *name == i.to_string())
+                    .count();
+                let (dense, associative) = elements.split_at(dense_length);
+
+                self.write_u29(((dense_length as u32) << 1) | 1);
+                self.write_amf3_properties(associative);
+
+                for (_, value) in dense {
+                    self.write_amf3_value(value);
+                }
+            }
+            AmfObject::ByteArray(bytes) => {
+                self.write_u29(((bytes.len() as u32) << 1) | 1);
+                self.output.extend_from_slice(bytes);
+            }
+        }
+    }
+}
@@ -21,12 +21,14 @@ mod fscommand;
 #[macro_use]
 pub mod function;
 pub mod globals;
+mod local_connection;
 pub mod object;
 pub mod property;
 mod scope;
 mod string;
 mod timer;
 mod value;
+mod xml_socket;
 
 #[cfg(test)]
 mod tests;
@@ -36,6 +38,7 @@ pub use crate::avm1::error::Error;
 use crate::avm1::globals::as_broadcaster;
 use crate::avm1::globals::as_broadcaster::BroadcasterFunctions;
 pub use globals::SystemPrototypes;
+pub use local_connection::LocalConnections;
 pub use object::script_object::ScriptObject;
 pub use object::sound_object::SoundObject;
 pub use object::stage_object::StageObject;
@@ -43,8 +46,9 @@ pub use object::{Object, ObjectPtr, TObject};
 use scope::Scope;
 use smallvec::alloc::borrow::Cow;
 pub use string::AvmString;
-pub use timer::Timers;
+pub use timer::{TimerCallback, Timers};
 pub use value::Value;
+pub use xml_socket::XmlSockets;
 
 macro_rules! avm_debug {
     ($avm: expr, $($arg:tt)*) => (
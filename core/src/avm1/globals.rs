@@ -36,6 +36,7 @@ pub mod gradient_bevel_filter;
 pub mod gradient_glow_filter;
 mod key;
 mod load_vars;
+mod local_connection;
 mod math;
 mod matrix;
 pub(crate) mod mouse;
@@ -47,9 +48,10 @@ mod point;
 mod rectangle;
 mod selection;
 pub(crate) mod shared_object;
-mod sound;
+pub(crate) mod sound;
 mod stage;
 pub(crate) mod string;
+mod style_sheet;
 pub(crate) mod system;
 pub(crate) mod system_capabilities;
 pub(crate) mod system_ime;
@@ -58,6 +60,7 @@ pub(crate) mod text_field;
 mod text_format;
 mod transform;
 mod xml;
+mod xml_socket;
 
 pub fn random<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
@@ -534,6 +537,8 @@ pub fn create_globals<'gc>(
         text_field::create_proto(gc_context, object_proto, function_proto);
     let text_format_proto: Object<'gc> =
         text_format::create_proto(gc_context, object_proto, function_proto);
+    let style_sheet_proto: Object<'gc> =
+        style_sheet::create_proto(gc_context, object_proto, function_proto);
 
     let array_proto: Object<'gc> = array::create_proto(gc_context, object_proto, function_proto);
 
@@ -545,6 +550,8 @@ pub fn create_globals<'gc>(
         xml::create_xmlnode_proto(gc_context, object_proto, function_proto);
 
     let xml_proto: Object<'gc> = xml::create_xml_proto(gc_context, xmlnode_proto, function_proto);
+    let xml_socket_proto: Object<'gc> =
+        xml_socket::create_proto(gc_context, object_proto, function_proto);
 
     let string_proto: Object<'gc> = string::create_proto(gc_context, object_proto, function_proto);
     let number_proto: Object<'gc> = number::create_proto(gc_context, object_proto, function_proto);
@@ -552,6 +559,8 @@ pub fn create_globals<'gc>(
         boolean::create_proto(gc_context, object_proto, function_proto);
     let load_vars_proto: Object<'gc> =
         load_vars::create_proto(gc_context, object_proto, function_proto);
+    let local_connection_proto: Object<'gc> =
+        local_connection::create_proto(gc_context, object_proto, function_proto);
     let matrix_proto: Object<'gc> = matrix::create_proto(gc_context, object_proto, function_proto);
     let point_proto: Object<'gc> = point::create_proto(gc_context, object_proto, function_proto);
     let rectangle_proto: Object<'gc> =
@@ -626,6 +635,13 @@ pub fn create_globals<'gc>(
         Some(function_proto),
         load_vars_proto,
     );
+    let local_connection = FunctionObject::constructor(
+        gc_context,
+        Executable::Native(local_connection::constructor),
+        constructor_to_fn!(local_connection::constructor),
+        Some(function_proto),
+        local_connection_proto,
+    );
     let movie_clip = FunctionObject::constructor(
         gc_context,
         Executable::Native(movie_clip::constructor),
@@ -655,6 +671,19 @@ pub fn create_globals<'gc>(
         Some(function_proto),
         text_format_proto,
     );
+    let style_sheet = FunctionObject::constructor(
+        gc_context,
+        Executable::Native(style_sheet::constructor),
+        constructor_to_fn!(style_sheet::constructor),
+        Some(function_proto),
+        style_sheet_proto,
+    );
+    text_field.define_value(
+        gc_context,
+        "StyleSheet",
+        style_sheet.into(),
+        Attribute::DONT_ENUM,
+    );
     let array = array::create_array_object(gc_context, array_proto, Some(function_proto));
     let xmlnode = FunctionObject::constructor(
         gc_context,
@@ -670,6 +699,13 @@ pub fn create_globals<'gc>(
         Some(function_proto),
         xml_proto,
     );
+    let xml_socket = FunctionObject::constructor(
+        gc_context,
+        Executable::Native(xml_socket::constructor),
+        constructor_to_fn!(xml_socket::constructor),
+        Some(function_proto),
+        xml_socket_proto,
+    );
     let string = string::create_string_object(gc_context, string_proto, Some(function_proto));
     let number = number::create_number_object(gc_context, number_proto, Some(function_proto));
     let boolean = boolean::create_boolean_object(gc_context, boolean_proto, Some(function_proto));
@@ -937,6 +973,12 @@ pub fn create_globals<'gc>(
         load_vars.into(),
         Attribute::DONT_ENUM,
     );
+    globals.define_value(
+        gc_context,
+        "LocalConnection",
+        local_connection.into(),
+        Attribute::DONT_ENUM,
+    );
     globals.define_value(
         gc_context,
         "MovieClip",
@@ -964,6 +1006,12 @@ pub fn create_globals<'gc>(
     );
     globals.define_value(gc_context, "XMLNode", xmlnode.into(), Attribute::DONT_ENUM);
     globals.define_value(gc_context, "XML", xml.into(), Attribute::DONT_ENUM);
+    globals.define_value(
+        gc_context,
+        "XMLSocket",
+        xml_socket.into(),
+        Attribute::DONT_ENUM,
+    );
     globals.define_value(gc_context, "String", string.into(), Attribute::DONT_ENUM);
     globals.define_value(gc_context, "Number", number.into(), Attribute::DONT_ENUM);
     globals.define_value(gc_context, "Boolean", boolean.into(), Attribute::DONT_ENUM);
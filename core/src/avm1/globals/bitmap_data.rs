@@ -2,13 +2,95 @@
 
 use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
-use crate::avm1::object::bitmap_data::{BitmapDataObject, ChannelOptions, Color};
+use crate::avm1::globals::{bitmap_filter, color_transform, matrix};
+use crate::avm1::object::bitmap_data::{
+    BitmapDataObject, ChannelOptions, Color, ThresholdOperation,
+};
 use crate::avm1::property::Attribute;
 use crate::avm1::{activation::Activation, object::bitmap_data::BitmapData};
 use crate::avm1::{Object, TObject, Value};
+use crate::bitmap::filters;
+use crate::bounding_box::BoundingBox;
 use crate::character::Character;
-use crate::display_object::TDisplayObject;
+use crate::color_transform::ColorTransform;
+use crate::context::RenderContext;
+use crate::display_object::{blend_mode_from_index, blend_mode_from_str, TDisplayObject};
+use crate::transform::{Transform, TransformStack};
 use gc_arena::{GcCell, MutationContext};
+use swf::{BlendMode, Matrix, Twips};
+
+/// Reads a `flash.geom.Rectangle` as `(x, y, width, height)` in pixels.
+fn object_to_rect<'gc>(
+    object: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<(i32, i32, i32, i32), Error<'gc>> {
+    let x = object.get("x", activation)?.coerce_to_i32(activation)?;
+    let y = object.get("y", activation)?.coerce_to_i32(activation)?;
+    let width = object.get("width", activation)?.coerce_to_i32(activation)?;
+    let height = object
+        .get("height", activation)?
+        .coerce_to_i32(activation)?;
+    Ok((x, y, width, height))
+}
+
+/// Reads a `flash.geom.Point` as `(x, y)` in pixels.
+fn object_to_point<'gc>(
+    object: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<(i32, i32), Error<'gc>> {
+    let x = object.get("x", activation)?.coerce_to_i32(activation)?;
+    let y = object.get("y", activation)?.coerce_to_i32(activation)?;
+    Ok((x, y))
+}
+
+/// Reads the source bitmap, source rectangle and destination point arguments shared by the
+/// methods that copy pixels from another bitmap.
+#[allow(clippy::type_complexity)]
+fn copy_args<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Option<(BitmapDataObject<'gc>, (i32, i32, i32, i32), (i32, i32))>, Error<'gc>> {
+    let source_bitmap = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_object(activation)
+        .as_bitmap_data_object();
+    let source_bitmap = match source_bitmap {
+        Some(source_bitmap) if !source_bitmap.disposed() => source_bitmap,
+        _ => return Ok(None),
+    };
+
+    let src_rect = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_object(activation);
+    let src_rect = object_to_rect(src_rect, activation)?;
+
+    let dest_point = args
+        .get(2)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_object(activation);
+    let dest_point = object_to_point(dest_point, activation)?;
+
+    Ok(Some((source_bitmap, src_rect, dest_point)))
+}
+
+/// Calls `f` with the pixels of `bitmap_data` and `source_bitmap`, which may be the same bitmap.
+fn with_source<'gc, R>(
+    gc_context: MutationContext<'gc, '_>,
+    bitmap_data: BitmapDataObject<'gc>,
+    source_bitmap: BitmapDataObject<'gc>,
+    f: impl FnOnce(&mut BitmapData, &BitmapData) -> R,
+) -> R {
+    let dest = bitmap_data.bitmap_data();
+    let source = source_bitmap.bitmap_data();
+    if GcCell::ptr_eq(dest, source) {
+        let source = source.read().clone();
+        f(&mut dest.write(gc_context), &source)
+    } else {
+        f(&mut dest.write(gc_context), &source.read())
+    }
+}
 
 pub fn constructor<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
@@ -466,22 +548,213 @@ pub fn noise<'gc>(
 }
 
 pub fn apply_filter<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("BitmapData.applyFilter - not yet implemented");
+    if let Some(bitmap_data) = this.as_bitmap_data_object() {
+        if !bitmap_data.disposed() {
+            let (source_bitmap, src_rect, dest_point) = match copy_args(activation, args)? {
+                Some(copy_args) => copy_args,
+                None => return Ok((-1).into()),
+            };
+            let filter = args
+                .get(3)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let gc_context = activation.context.gc_context;
+
+            if let Some(filter) = filter.as_displacement_map_filter_object() {
+                let map = match filter
+                    .map_bitmap()
+                    .and_then(|map| map.as_bitmap_data_object())
+                {
+                    Some(map) if !map.disposed() => map.bitmap_data().read().clone(),
+                    _ => return Ok((-1).into()),
+                };
+                with_source(gc_context, bitmap_data, source_bitmap, |dest, source| {
+                    dest.apply_displacement_map(source, src_rect, dest_point, filter, &map)
+                });
+            } else if let Some(filter) = bitmap_filter::object_to_filter(filter) {
+                with_source(gc_context, bitmap_data, source_bitmap, |dest, source| {
+                    dest.apply_filter(source, src_rect, dest_point, &filter)
+                });
+            } else {
+                return Ok((-1).into());
+            }
+
+            return Ok(0.into());
+        }
+    }
+
     Ok((-1).into())
 }
 
 pub fn draw<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(bitmap_data) = this.as_bitmap_data_object() {
         if !bitmap_data.disposed() {
-            log::warn!("BitmapData.draw - not yet implemented");
+            let source = match args.get(0) {
+                Some(Value::Object(source)) => *source,
+                _ => return Ok(Value::Undefined),
+            };
+
+            let matrix = match args.get(1) {
+                Some(Value::Object(matrix)) => matrix::object_to_matrix(*matrix, activation)?,
+                _ => Matrix::identity(),
+            };
+
+            let color_transform = match args.get(2) {
+                Some(Value::Object(color_transform))
+                    if color_transform.as_color_transform_object().is_some() =>
+                {
+                    color_transform::object_to_color_transform(*color_transform, activation)?
+                }
+                _ => ColorTransform::default(),
+            };
+
+            // Blend modes can be given either by name or by index; anything else is ignored.
+            let blend_mode = match args.get(3) {
+                Some(Value::Number(index)) => blend_mode_from_index(*index as i32),
+                Some(Value::String(name)) => blend_mode_from_str(name),
+                _ => None,
+            }
+            .unwrap_or(BlendMode::Normal);
+
+            // The clip rectangle is in the coordinate space of this bitmap, unaffected by `matrix`.
+            let clip_rect = match args.get(4) {
+                Some(Value::Object(rectangle)) => {
+                    let x = rectangle.get("x", activation)?.coerce_to_f64(activation)?;
+                    let y = rectangle.get("y", activation)?.coerce_to_f64(activation)?;
+                    let width = rectangle
+                        .get("width", activation)?
+                        .coerce_to_f64(activation)?;
+                    let height = rectangle
+                        .get("height", activation)?
+                        .coerce_to_f64(activation)?;
+                    Some(Matrix::create_box(
+                        width as f32,
+                        height as f32,
+                        0.0,
+                        Twips::from_pixels(x),
+                        Twips::from_pixels(y),
+                    ))
+                }
+                _ => None,
+            };
+
+            let smoothing = args
+                .get(5)
+                .unwrap_or(&Value::Bool(false))
+                .as_bool(activation.current_swf_version());
+
+            let source_bitmap = source.as_bitmap_data_object();
+            let source_display_object = source.as_display_object();
+            if source_bitmap.is_none() && source_display_object.is_none() {
+                return Ok(Value::Undefined);
+            }
+
+            let renderer = &mut *activation.context.renderer;
+            let gc_context = activation.context.gc_context;
+
+            // The renderer draws on top of the texture of each bitmap, so bring them up to date.
+            let handle = {
+                let mut bitmap_data = bitmap_data.bitmap_data().write(gc_context);
+                let handle = bitmap_data.bitmap_handle(renderer);
+                if let Some(handle) = handle {
+                    let _ = renderer.update_texture(
+                        handle,
+                        bitmap_data.width(),
+                        bitmap_data.height(),
+                        bitmap_data.pixels_rgba(),
+                    );
+                }
+                handle
+            };
+            let source_handle = source_bitmap.and_then(|source_bitmap| {
+                let mut source_data = source_bitmap.bitmap_data().write(gc_context);
+                let handle = source_data.bitmap_handle(renderer)?;
+                let _ = renderer.update_texture(
+                    handle,
+                    source_data.width(),
+                    source_data.height(),
+                    source_data.pixels_rgba(),
+                );
+                Some(handle)
+            });
+
+            let handle = match handle {
+                Some(handle) => handle,
+                None => return Ok(Value::Undefined),
+            };
+            if !renderer.begin_offscreen(handle) {
+                log::warn!("BitmapData.draw - not supported by this renderer");
+                return Ok(Value::Undefined);
+            }
+
+            let mut transform_stack = TransformStack::new();
+            transform_stack.push(&Transform {
+                matrix,
+                color_transform,
+            });
+
+            if blend_mode != BlendMode::Normal {
+                renderer.push_blend_mode(blend_mode);
+            }
+            if let Some(clip_rect) = &clip_rect {
+                renderer.push_mask();
+                renderer.draw_rect(swf::Color::from_rgb(0, 255), clip_rect);
+                renderer.activate_mask();
+            }
+
+            if let Some(source_handle) = source_handle {
+                // Smoothing only applies when drawing another bitmap.
+                renderer.render_bitmap(source_handle, transform_stack.transform(), smoothing);
+            } else if let Some(source_display_object) = source_display_object {
+                let mut render_context = RenderContext {
+                    renderer,
+                    library: &*activation.context.library,
+                    transform_stack: &mut transform_stack,
+                    // Nothing should be culled, as the source is drawn regardless of where it
+                    // is on the stage.
+                    view_bounds: BoundingBox {
+                        x_min: Twips::new(i32::MIN),
+                        y_min: Twips::new(i32::MIN),
+                        x_max: Twips::new(i32::MAX),
+                        y_max: Twips::new(i32::MAX),
+                        valid: true,
+                    },
+                    clip_depth_stack: vec![],
+                    allow_mask: true,
+                };
+
+                // The transform of the source itself is ignored, so only its contents are drawn.
+                source_display_object.render_self(&mut render_context);
+            }
+
+            let renderer = &mut *activation.context.renderer;
+            if let Some(clip_rect) = &clip_rect {
+                renderer.deactivate_mask();
+                renderer.draw_rect(swf::Color::from_rgb(0, 255), clip_rect);
+                renderer.pop_mask();
+            }
+            if blend_mode != BlendMode::Normal {
+                renderer.pop_blend_mode();
+            }
+            renderer.end_offscreen();
+
+            if let Some(bitmap) = renderer.get_bitmap_pixels(handle) {
+                let mut bitmap_data = bitmap_data.bitmap_data().write(gc_context);
+                if bitmap.width == bitmap_data.width() && bitmap.height == bitmap_data.height() {
+                    let pixels: Vec<i32> = bitmap.data.into();
+                    bitmap_data.set_pixels(pixels.into_iter().map(|p| p.into()).collect());
+                    bitmap_data.set_dirty(true);
+                }
+            }
+
             return Ok(Value::Undefined);
         }
     }
@@ -490,14 +763,35 @@ pub fn draw<'gc>(
 }
 
 pub fn generate_filter_rect<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(bitmap_data) = this.as_bitmap_data_object() {
         if !bitmap_data.disposed() {
-            log::warn!("BitmapData.generateFilterRect - not yet implemented");
-            return Ok(Value::Undefined);
+            let src_rect = args
+                .get(0)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let src_rect = object_to_rect(src_rect, activation)?;
+            let filter = args
+                .get(1)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+
+            // Filters without a SWF equivalent, such as `DisplacementMapFilter`, don't change
+            // the bounds of the source.
+            let (x, y, width, height) = match bitmap_filter::object_to_filter(filter) {
+                Some(filter) => filters::filter_rect(&filter, src_rect),
+                None => src_rect,
+            };
+
+            let proto = activation.context.avm1.prototypes.rectangle_constructor;
+            let rect = proto.construct(
+                activation,
+                &[x.into(), y.into(), width.into(), height.into()],
+            )?;
+            return Ok(rect);
         }
     }
 
@@ -656,14 +950,69 @@ pub fn perlin_noise<'gc>(
 }
 
 pub fn hit_test<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(bitmap_data) = this.as_bitmap_data_object() {
         if !bitmap_data.disposed() {
-            log::warn!("BitmapData.hitTest - not yet implemented");
-            return Ok(Value::Undefined);
+            let first_point = args
+                .get(0)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let first_point = object_to_point(first_point, activation)?;
+            let first_alpha_threshold = args
+                .get(1)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_i32(activation)?
+                .max(0)
+                .min(255) as u8;
+            let second_object = args
+                .get(2)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+
+            let result = if let Some(other) = second_object.as_bitmap_data_object() {
+                if other.disposed() {
+                    return Ok(Value::Undefined);
+                }
+
+                let second_point = args
+                    .get(3)
+                    .unwrap_or(&Value::Undefined)
+                    .coerce_to_object(activation);
+                let second_point = object_to_point(second_point, activation)?;
+                let second_alpha_threshold = args
+                    .get(4)
+                    .unwrap_or(&Value::Number(1.0))
+                    .coerce_to_i32(activation)?
+                    .max(0)
+                    .min(255) as u8;
+
+                bitmap_data.bitmap_data().read().hit_test_bitmap(
+                    first_point,
+                    first_alpha_threshold,
+                    &other.bitmap_data().read(),
+                    second_point,
+                    second_alpha_threshold,
+                )
+            } else if second_object.has_property(activation, "width") {
+                let rect = object_to_rect(second_object, activation)?;
+                bitmap_data.bitmap_data().read().hit_test_rectangle(
+                    first_point,
+                    first_alpha_threshold,
+                    rect,
+                )
+            } else {
+                let point = object_to_point(second_object, activation)?;
+                bitmap_data.bitmap_data().read().hit_test_point(
+                    first_point,
+                    first_alpha_threshold,
+                    point,
+                )
+            };
+
+            return Ok(result.into());
         }
     }
 
@@ -800,13 +1149,40 @@ pub fn copy_pixels<'gc>(
 }
 
 pub fn merge<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(bitmap_data) = this.as_bitmap_data_object() {
         if !bitmap_data.disposed() {
-            log::warn!("BitmapData.merge - not yet implemented");
+            let (source_bitmap, src_rect, dest_point) = match copy_args(activation, args)? {
+                Some(copy_args) => copy_args,
+                None => return Ok(Value::Undefined),
+            };
+
+            let mut multipliers = [0; 4];
+            for (i, multiplier) in multipliers.iter_mut().enumerate() {
+                *multiplier = args
+                    .get(3 + i)
+                    .unwrap_or(&Value::Undefined)
+                    .coerce_to_i32(activation)?;
+            }
+            let [red_mult, green_mult, blue_mult, alpha_mult] = multipliers;
+
+            with_source(
+                activation.context.gc_context,
+                bitmap_data,
+                source_bitmap,
+                |dest, source| {
+                    dest.merge(
+                        source,
+                        src_rect,
+                        dest_point,
+                        (red_mult, green_mult, blue_mult, alpha_mult),
+                    )
+                },
+            );
+
             return Ok(Value::Undefined);
         }
     }
@@ -815,13 +1191,38 @@ pub fn merge<'gc>(
 }
 
 pub fn palette_map<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(bitmap_data) = this.as_bitmap_data_object() {
         if !bitmap_data.disposed() {
-            log::warn!("BitmapData.paletteMap - not yet implemented");
+            let (source_bitmap, src_rect, dest_point) = match copy_args(activation, args)? {
+                Some(copy_args) => copy_args,
+                None => return Ok(Value::Undefined),
+            };
+
+            // Channels without a palette are copied unchanged.
+            let mut palettes = [[0; 256]; 4];
+            for (i, (palette, shift)) in palettes.iter_mut().zip(&[16, 8, 0, 24]).enumerate() {
+                if let Some(Value::Object(array)) = args.get(3 + i) {
+                    for (index, value) in palette.iter_mut().enumerate() {
+                        *value = array.array_element(index).coerce_to_i32(activation)? as u32;
+                    }
+                } else {
+                    for (index, value) in palette.iter_mut().enumerate() {
+                        *value = (index as u32) << shift;
+                    }
+                }
+            }
+
+            with_source(
+                activation.context.gc_context,
+                bitmap_data,
+                source_bitmap,
+                |dest, source| dest.palette_map(source, src_rect, dest_point, &palettes),
+            );
+
             return Ok(Value::Undefined);
         }
     }
@@ -830,14 +1231,55 @@ pub fn palette_map<'gc>(
 }
 
 pub fn pixel_dissolve<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(bitmap_data) = this.as_bitmap_data_object() {
         if !bitmap_data.disposed() {
-            log::warn!("BitmapData.pixelDissolve - not yet implemented");
-            return Ok(Value::Undefined);
+            let (source_bitmap, src_rect, dest_point) = match copy_args(activation, args)? {
+                Some(copy_args) => copy_args,
+                None => return Ok(Value::Undefined),
+            };
+
+            let random_seed = args
+                .get(3)
+                .unwrap_or(&Value::Number(0.0))
+                .coerce_to_i32(activation)?;
+            let num_pixels = match args.get(4) {
+                Some(num_pixels) => num_pixels.coerce_to_i32(activation)?,
+                // By default, a thirtieth of the area is dissolved.
+                None => (src_rect.2.max(0) * src_rect.3.max(0)) / 30,
+            };
+            let fill_color = args
+                .get(5)
+                .unwrap_or(&Value::Number(0.0))
+                .coerce_to_i32(activation)?;
+
+            let gc_context = activation.context.gc_context;
+            let bitmap_data_cell = bitmap_data.bitmap_data();
+            let new_seed = if GcCell::ptr_eq(bitmap_data_cell, source_bitmap.bitmap_data()) {
+                // Dissolving a bitmap into itself fills it with the fill color instead.
+                bitmap_data_cell.write(gc_context).pixel_dissolve(
+                    None,
+                    src_rect,
+                    dest_point,
+                    random_seed,
+                    num_pixels,
+                    fill_color.into(),
+                )
+            } else {
+                bitmap_data_cell.write(gc_context).pixel_dissolve(
+                    Some(&source_bitmap.bitmap_data().read()),
+                    src_rect,
+                    dest_point,
+                    random_seed,
+                    num_pixels,
+                    fill_color.into(),
+                )
+            };
+
+            return Ok(new_seed.into());
         }
     }
 
@@ -873,14 +1315,62 @@ pub fn scroll<'gc>(
 }
 
 pub fn threshold<'gc>(
-    _activation: &mut Activation<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(bitmap_data) = this.as_bitmap_data_object() {
         if !bitmap_data.disposed() {
-            log::warn!("BitmapData.threshold - not yet implemented");
-            return Ok(Value::Undefined);
+            let (source_bitmap, src_rect, dest_point) = match copy_args(activation, args)? {
+                Some(copy_args) => copy_args,
+                None => return Ok(Value::Undefined),
+            };
+
+            let operation = args
+                .get(3)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_string(activation)?;
+            let operation = match ThresholdOperation::from_operator(&operation) {
+                Some(operation) => operation,
+                None => return Ok(0.into()),
+            };
+
+            let threshold = args
+                .get(4)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_u32(activation)?;
+            let color = args
+                .get(5)
+                .unwrap_or(&Value::Number(0.0))
+                .coerce_to_i32(activation)?;
+            let mask = args
+                .get(6)
+                .unwrap_or(&Value::Number(0xFFFFFFFFu32.into()))
+                .coerce_to_u32(activation)?;
+            let copy_source = args
+                .get(7)
+                .unwrap_or(&Value::Bool(false))
+                .as_bool(activation.current_swf_version());
+
+            let modified_count = with_source(
+                activation.context.gc_context,
+                bitmap_data,
+                source_bitmap,
+                |dest, source| {
+                    dest.threshold(
+                        source,
+                        src_rect,
+                        dest_point,
+                        operation,
+                        threshold,
+                        color.into(),
+                        mask,
+                        copy_source,
+                    )
+                },
+            );
+
+            return Ok(modified_count.into());
         }
     }
 
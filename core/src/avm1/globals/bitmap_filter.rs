@@ -2,9 +2,15 @@
 
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
+use crate::avm1::object::bevel_filter::BevelFilterType;
 use crate::avm1::property::Attribute;
 use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::display_object::{DisplayObject, TDisplayObject};
 use gc_arena::MutationContext;
+use swf::{
+    BevelFilter, BlurFilter, Color, ColorMatrixFilter, ConvolutionFilter, DropShadowFilter, Filter,
+    GlowFilter, GradientBevelFilter, GradientGlowFilter, GradientRecord,
+};
 
 pub fn constructor<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
@@ -268,6 +274,384 @@ pub fn clone<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Converts an AVM1 filter object to the equivalent SWF filter.
+/// Returns `None` if `object` is not a filter, or is a filter that can't be rendered.
+pub fn object_to_filter(object: Object<'_>) -> Option<Filter> {
+    if let Some(filter) = object.as_blur_filter_object() {
+        return Some(Filter::BlurFilter(Box::new(BlurFilter {
+            blur_x: filter.blur_x(),
+            blur_y: filter.blur_y(),
+            num_passes: quality_to_passes(filter.quality()),
+        })));
+    }
+
+    if let Some(filter) = object.as_bevel_filter_object() {
+        let (is_inner, is_on_top) = bevel_type_to_flags(filter.get_type());
+        return Some(Filter::BevelFilter(Box::new(BevelFilter {
+            shadow_color: Color::from_rgb(
+                filter.shadow_color(),
+                alpha_to_u8(filter.shadow_alpha()),
+            ),
+            highlight_color: Color::from_rgb(
+                filter.highlight_color(),
+                alpha_to_u8(filter.highlight_alpha()),
+            ),
+            blur_x: filter.blur_x(),
+            blur_y: filter.blur_y(),
+            angle: filter.angle().to_radians(),
+            distance: filter.distance(),
+            strength: filter.strength() as f32,
+            is_inner,
+            is_knockout: filter.knockout(),
+            is_on_top,
+            num_passes: quality_to_passes(filter.quality()),
+        })));
+    }
+
+    if let Some(filter) = object.as_glow_filter_object() {
+        return Some(Filter::GlowFilter(Box::new(GlowFilter {
+            color: Color::from_rgb(filter.color() as u32, alpha_to_u8(filter.alpha())),
+            blur_x: filter.blur_x(),
+            blur_y: filter.blur_y(),
+            strength: filter.strength() as f32,
+            is_inner: filter.inner(),
+            is_knockout: filter.knockout(),
+            num_passes: quality_to_passes(filter.quality()),
+        })));
+    }
+
+    if let Some(filter) = object.as_drop_shadow_filter_object() {
+        return Some(Filter::DropShadowFilter(Box::new(DropShadowFilter {
+            color: Color::from_rgb(filter.color(), alpha_to_u8(filter.alpha())),
+            blur_x: filter.blur_x(),
+            blur_y: filter.blur_y(),
+            angle: filter.angle().to_radians(),
+            distance: filter.distance(),
+            strength: filter.strength() as f32,
+            is_inner: filter.inner(),
+            is_knockout: filter.knockout(),
+            num_passes: quality_to_passes(filter.quality()),
+        })));
+    }
+
+    if let Some(filter) = object.as_color_matrix_filter_object() {
+        return Some(Filter::ColorMatrixFilter(Box::new(ColorMatrixFilter {
+            matrix: filter.matrix(),
+        })));
+    }
+
+    if let Some(filter) = object.as_convolution_filter_object() {
+        let num_matrix_cols = filter.matrix_x();
+        let num_matrix_rows = filter.matrix_y();
+        let mut matrix = filter.matrix();
+        matrix.resize(
+            usize::from(num_matrix_cols) * usize::from(num_matrix_rows),
+            0.0,
+        );
+        return Some(Filter::ConvolutionFilter(Box::new(ConvolutionFilter {
+            num_matrix_rows,
+            num_matrix_cols,
+            matrix,
+            divisor: filter.divisor(),
+            bias: filter.bias(),
+            default_color: Color::from_rgb(filter.color(), alpha_to_u8(filter.alpha())),
+            is_clamped: filter.clamp(),
+            is_preserve_alpha: filter.preserve_alpha(),
+        })));
+    }
+
+    if let Some(filter) = object.as_gradient_bevel_filter_object() {
+        let (is_inner, is_on_top) = bevel_type_to_flags(filter.get_type());
+        return Some(Filter::GradientBevelFilter(Box::new(GradientBevelFilter {
+            colors: gradient_records(filter.colors(), filter.alphas(), filter.ratios()),
+            blur_x: filter.blur_x(),
+            blur_y: filter.blur_y(),
+            angle: filter.angle().to_radians(),
+            distance: filter.distance(),
+            strength: filter.strength() as f32,
+            is_inner,
+            is_knockout: filter.knockout(),
+            is_on_top,
+            num_passes: quality_to_passes(filter.quality()),
+        })));
+    }
+
+    if let Some(filter) = object.as_gradient_glow_filter_object() {
+        let (is_inner, is_on_top) = bevel_type_to_flags(filter.get_type());
+        return Some(Filter::GradientGlowFilter(Box::new(GradientGlowFilter {
+            colors: gradient_records(filter.colors(), filter.alphas(), filter.ratios()),
+            blur_x: filter.blur_x(),
+            blur_y: filter.blur_y(),
+            angle: filter.angle().to_radians(),
+            distance: filter.distance(),
+            strength: filter.strength() as f32,
+            is_inner,
+            is_knockout: filter.knockout(),
+            is_on_top,
+            num_passes: quality_to_passes(filter.quality()),
+        })));
+    }
+
+    // TODO: DisplacementMapFilter has no SWF equivalent and is not rendered yet.
+    None
+}
+
+/// Implements the `filters` property getter of display objects.
+pub fn get_filters<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: DisplayObject<'gc>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    // Flash returns copies of the filters, so modifying them has no effect until reassigned.
+    let filters = this.filters().to_vec();
+    let array = ScriptObject::array(
+        activation.context.gc_context,
+        Some(activation.context.avm1.prototypes.array),
+    );
+    for (i, filter) in filters.iter().enumerate() {
+        let filter = filter_to_object(activation, filter)?;
+        array.set_array_element(i, filter, activation.context.gc_context);
+    }
+    Ok(array.into())
+}
+
+/// Implements the `filters` property setter of display objects.
+/// Anything in the array that isn't a filter is ignored.
+pub fn set_filters<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: DisplayObject<'gc>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let mut filters = Vec::new();
+    if let Value::Object(array) = value {
+        for i in 0..array.length() {
+            if let Value::Object(filter) = array.array_element(i) {
+                if let Some(filter) = object_to_filter(filter) {
+                    filters.push(filter);
+                }
+            }
+        }
+    }
+    this.set_filters(activation.context.gc_context, filters);
+    Ok(())
+}
+
+/// Creates a new AVM1 filter object from a SWF filter.
+pub fn filter_to_object<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    filter: &Filter,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let prototypes = &activation.context.avm1.prototypes;
+    match filter {
+        Filter::BlurFilter(filter) => {
+            let constructor = prototypes.blur_filter_constructor;
+            constructor.construct(
+                activation,
+                &[
+                    filter.blur_x.into(),
+                    filter.blur_y.into(),
+                    filter.num_passes.into(),
+                ],
+            )
+        }
+        Filter::BevelFilter(filter) => {
+            let constructor = prototypes.bevel_filter_constructor;
+            let type_ = bevel_type_from_flags(filter.is_inner, filter.is_on_top);
+            constructor.construct(
+                activation,
+                &[
+                    filter.distance.into(),
+                    filter.angle.to_degrees().into(),
+                    filter.highlight_color.to_rgb().into(),
+                    alpha_to_f64(&filter.highlight_color).into(),
+                    filter.shadow_color.to_rgb().into(),
+                    alpha_to_f64(&filter.shadow_color).into(),
+                    filter.blur_x.into(),
+                    filter.blur_y.into(),
+                    filter.strength.into(),
+                    filter.num_passes.into(),
+                    type_.into(),
+                    filter.is_knockout.into(),
+                ],
+            )
+        }
+        Filter::GlowFilter(filter) => {
+            let constructor = prototypes.glow_filter_constructor;
+            let object = constructor.construct(
+                activation,
+                &[
+                    filter.color.to_rgb().into(),
+                    alpha_to_f64(&filter.color).into(),
+                    filter.blur_x.into(),
+                    filter.blur_y.into(),
+                    filter.strength.into(),
+                    filter.num_passes.into(),
+                ],
+            )?;
+            if let Some(glow) = object.coerce_to_object(activation).as_glow_filter_object() {
+                glow.set_inner(activation.context.gc_context, filter.is_inner);
+                glow.set_knockout(activation.context.gc_context, filter.is_knockout);
+            }
+            Ok(object)
+        }
+        Filter::DropShadowFilter(filter) => {
+            let constructor = prototypes.drop_shadow_filter_constructor;
+            constructor.construct(
+                activation,
+                &[
+                    filter.distance.into(),
+                    filter.angle.to_degrees().into(),
+                    filter.color.to_rgb().into(),
+                    alpha_to_f64(&filter.color).into(),
+                    filter.blur_x.into(),
+                    filter.blur_y.into(),
+                    filter.strength.into(),
+                    filter.num_passes.into(),
+                    filter.is_inner.into(),
+                    filter.is_knockout.into(),
+                    false.into(),
+                ],
+            )
+        }
+        Filter::ColorMatrixFilter(filter) => {
+            let constructor = prototypes.color_matrix_filter_constructor;
+            let matrix = array_of(activation, filter.matrix.iter().map(|&v| v.into()));
+            constructor.construct(activation, &[matrix])
+        }
+        Filter::ConvolutionFilter(filter) => {
+            let constructor = prototypes.convolution_filter_constructor;
+            let matrix = array_of(activation, filter.matrix.iter().map(|&v| v.into()));
+            constructor.construct(
+                activation,
+                &[
+                    filter.num_matrix_cols.into(),
+                    filter.num_matrix_rows.into(),
+                    matrix,
+                    filter.divisor.into(),
+                    filter.bias.into(),
+                    filter.is_preserve_alpha.into(),
+                    filter.is_clamped.into(),
+                    filter.default_color.to_rgb().into(),
+                    alpha_to_f64(&filter.default_color).into(),
+                ],
+            )
+        }
+        Filter::GradientBevelFilter(filter) => {
+            let constructor = prototypes.gradient_bevel_filter_constructor;
+            let type_ = bevel_type_from_flags(filter.is_inner, filter.is_on_top);
+            let (colors, alphas, ratios) = gradient_arrays(activation, &filter.colors);
+            constructor.construct(
+                activation,
+                &[
+                    filter.distance.into(),
+                    filter.angle.to_degrees().into(),
+                    colors,
+                    alphas,
+                    ratios,
+                    filter.blur_x.into(),
+                    filter.blur_y.into(),
+                    filter.strength.into(),
+                    filter.num_passes.into(),
+                    type_.into(),
+                    filter.is_knockout.into(),
+                ],
+            )
+        }
+        Filter::GradientGlowFilter(filter) => {
+            let constructor = prototypes.gradient_glow_filter_constructor;
+            let type_ = bevel_type_from_flags(filter.is_inner, filter.is_on_top);
+            let (colors, alphas, ratios) = gradient_arrays(activation, &filter.colors);
+            constructor.construct(
+                activation,
+                &[
+                    filter.distance.into(),
+                    filter.angle.to_degrees().into(),
+                    colors,
+                    alphas,
+                    ratios,
+                    filter.blur_x.into(),
+                    filter.blur_y.into(),
+                    filter.strength.into(),
+                    filter.num_passes.into(),
+                    type_.into(),
+                    filter.is_knockout.into(),
+                ],
+            )
+        }
+    }
+}
+
+fn quality_to_passes(quality: i32) -> u8 {
+    quality.max(0).min(15) as u8
+}
+
+fn alpha_to_u8(alpha: f64) -> u8 {
+    (alpha * 255.0).round().max(0.0).min(255.0) as u8
+}
+
+fn alpha_to_f64(color: &Color) -> f64 {
+    f64::from(color.a) / 255.0
+}
+
+/// Returns the `is_inner` and `is_on_top` SWF flags for a bevel type.
+fn bevel_type_to_flags(type_: BevelFilterType) -> (bool, bool) {
+    match type_ {
+        BevelFilterType::Inner => (true, false),
+        BevelFilterType::Outer => (false, false),
+        BevelFilterType::Full => (false, true),
+    }
+}
+
+fn bevel_type_from_flags(is_inner: bool, is_on_top: bool) -> &'static str {
+    let type_ = if is_on_top {
+        BevelFilterType::Full
+    } else if is_inner {
+        BevelFilterType::Inner
+    } else {
+        BevelFilterType::Outer
+    };
+    type_.into()
+}
+
+fn gradient_records(colors: Vec<u32>, alphas: Vec<f64>, ratios: Vec<u8>) -> Vec<GradientRecord> {
+    colors
+        .into_iter()
+        .zip(alphas)
+        .zip(ratios)
+        .map(|((color, alpha), ratio)| GradientRecord {
+            ratio,
+            color: Color::from_rgb(color, alpha_to_u8(alpha)),
+        })
+        .collect()
+}
+
+/// Returns the `colors`, `alphas` and `ratios` arrays for a gradient filter.
+fn gradient_arrays<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    records: &[GradientRecord],
+) -> (Value<'gc>, Value<'gc>, Value<'gc>) {
+    let colors = array_of(activation, records.iter().map(|r| r.color.to_rgb().into()));
+    let alphas = array_of(
+        activation,
+        records.iter().map(|r| alpha_to_f64(&r.color).into()),
+    );
+    let ratios = array_of(activation, records.iter().map(|r| r.ratio.into()));
+    (colors, alphas, ratios)
+}
+
+fn array_of<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    values: impl Iterator<Item = Value<'gc>>,
+) -> Value<'gc> {
+    let array = ScriptObject::array(
+        activation.context.gc_context,
+        Some(activation.context.avm1.prototypes.array),
+    );
+    for (i, value) in values.enumerate() {
+        array.set_array_element(i, value, activation.context.gc_context);
+    }
+    array.into()
+}
+
 pub fn create_proto<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Object<'gc>,
@@ -3,7 +3,7 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
-use crate::avm1::globals::display_object;
+use crate::avm1::globals::{bitmap_filter, display_object};
 use crate::avm1::property::Attribute;
 use crate::avm1::{Object, ScriptObject, TObject, Value};
 use crate::display_object::{Button, TDisplayObject};
@@ -79,6 +79,7 @@ pub fn create_proto<'gc>(
     with_button_props!(
         object, gc_context, fn_proto,
         "enabled" => [enabled, set_enabled],
+        "filters" => [filters, set_filters],
     );
 
     object.into()
@@ -109,3 +110,18 @@ fn set_enabled<'gc>(
     this.set_enabled(&mut activation.context, enabled);
     Ok(())
 }
+
+fn filters<'gc>(
+    this: Button<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    bitmap_filter::get_filters(activation, this.into())
+}
+
+fn set_filters<'gc>(
+    this: Button<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    bitmap_filter::set_filters(activation, this.into(), value)
+}
@@ -4,8 +4,13 @@ use crate::avm1::object::TObject;
 use crate::avm1::property::Attribute;
 use crate::avm1::Object;
 use crate::avm1::{ScriptObject, Value};
+use crate::context_menu::{ContextMenuCallback, ContextMenuItem, ContextMenuState};
+use crate::display_object::TDisplayObject;
 use gc_arena::MutationContext;
 
+/// The maximum number of custom items that are displayed in a context menu.
+const MAX_CUSTOM_ITEMS: usize = 15;
+
 pub fn constructor<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
@@ -132,6 +137,113 @@ pub fn hide_builtin_items<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Builds the context menu to display for a `ContextMenu` object.
+///
+/// `menu` is the object that the menu is attached to, along with the `ContextMenu`
+/// itself, or `None` to build the default menu. Custom items come first, followed
+/// by the built-in items enabled by both `Stage.showMenu` and `builtInItems`.
+pub fn make_context_menu_state<'gc>(
+    menu: Option<(Object<'gc>, Object<'gc>)>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<ContextMenuState<'gc>, Error<'gc>> {
+    let mut result = ContextMenuState::new();
+    let swf_version = activation.current_swf_version();
+
+    let mut built_in_items = None;
+    if let Some((target, menu)) = menu {
+        if let Value::Object(custom_items) = menu.get("customItems", activation)? {
+            for i in 0..custom_items.length() {
+                if result.info().len() >= MAX_CUSTOM_ITEMS {
+                    break;
+                }
+
+                if let Value::Object(item) = custom_items.array_element(i) {
+                    if !item.get("visible", activation)?.as_bool(swf_version) {
+                        continue;
+                    }
+
+                    let caption = item
+                        .get("caption", activation)?
+                        .coerce_to_string(activation)?;
+                    let info = ContextMenuItem {
+                        enabled: item.get("enabled", activation)?.as_bool(swf_version),
+                        separator_before: item
+                            .get("separatorBefore", activation)?
+                            .as_bool(swf_version),
+                        checked: false,
+                        caption: caption.to_string(),
+                    };
+                    result.push(info, ContextMenuCallback::Avm1 { item, target });
+                }
+            }
+        }
+
+        if let Value::Object(items) = menu.get("builtInItems", activation)? {
+            built_in_items = Some(items);
+        }
+    }
+
+    let root = activation
+        .context
+        .levels
+        .get(&0)
+        .and_then(|root| root.as_movie_clip());
+    let root = match root {
+        // The playback controls are only offered for movies with more than one frame.
+        Some(root) if *activation.context.show_menu && root.total_frames() > 1 => root,
+        _ => return Ok(result),
+    };
+
+    let mut is_enabled = |name: &str| -> Result<bool, Error<'gc>> {
+        match built_in_items {
+            Some(items) => Ok(items.get(name, activation)?.as_bool(swf_version)),
+            None => Ok(true),
+        }
+    };
+    let play = is_enabled("play")?;
+    let rewind = is_enabled("rewind")?;
+    let forward_back = is_enabled("forward_back")?;
+
+    let current_frame = root.current_frame();
+    let total_frames = root.total_frames();
+    if play {
+        let info = ContextMenuItem {
+            enabled: true,
+            separator_before: !result.info().is_empty(),
+            checked: root.playing(),
+            caption: "Play".to_string(),
+        };
+        result.push(info, ContextMenuCallback::Play);
+    }
+    if rewind {
+        let info = ContextMenuItem {
+            enabled: current_frame > 1,
+            separator_before: !result.info().is_empty(),
+            checked: false,
+            caption: "Rewind".to_string(),
+        };
+        result.push(info, ContextMenuCallback::Rewind);
+    }
+    if forward_back {
+        let info = ContextMenuItem {
+            enabled: current_frame < total_frames,
+            separator_before: !rewind && !result.info().is_empty(),
+            checked: false,
+            caption: "Forward".to_string(),
+        };
+        result.push(info, ContextMenuCallback::Forward);
+        let info = ContextMenuItem {
+            enabled: current_frame > 1,
+            separator_before: false,
+            checked: false,
+            caption: "Back".to_string(),
+        };
+        result.push(info, ContextMenuCallback::Back);
+    }
+
+    Ok(result)
+}
+
 pub fn create_proto<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Object<'gc>,
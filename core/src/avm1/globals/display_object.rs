@@ -5,7 +5,10 @@ use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
 use crate::avm1::property::Attribute;
 use crate::avm1::{AvmString, Object, ScriptObject, TObject, Value};
-use crate::display_object::{DisplayObject, Lists, TDisplayObject, TDisplayObjectContainer};
+use crate::display_object::{
+    blend_mode_from_index, blend_mode_from_str, blend_mode_to_str, DisplayObject, Lists,
+    TDisplayObject, TDisplayObjectContainer,
+};
 use gc_arena::MutationContext;
 
 /// Depths used/returned by ActionScript are offset by this amount from depths used inside the SWF/by the VM.
@@ -110,6 +113,24 @@ pub fn define_display_object_proto<'gc>(
         )),
         Attribute::DONT_DELETE | Attribute::READ_ONLY | Attribute::DONT_ENUM,
     );
+
+    object.add_property(
+        gc_context,
+        "blendMode",
+        FunctionObject::function(
+            gc_context,
+            Executable::Native(blend_mode),
+            Some(fn_proto),
+            fn_proto,
+        ),
+        Some(FunctionObject::function(
+            gc_context,
+            Executable::Native(set_blend_mode),
+            Some(fn_proto),
+            fn_proto,
+        )),
+        Attribute::DONT_DELETE | Attribute::DONT_ENUM,
+    );
 }
 
 pub fn get_parent<'gc>(
@@ -203,6 +224,37 @@ pub fn overwrite_parent<'gc>(
     Ok(Value::Undefined)
 }
 
+pub fn blend_mode<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this
+        .as_display_object()
+        .map(|display_object| blend_mode_to_str(display_object.blend_mode()).into())
+        .unwrap_or(Value::Undefined))
+}
+
+pub fn set_blend_mode<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        // Blend modes can be set either by name or by index; anything else is ignored.
+        let blend_mode = match args.get(0) {
+            Some(Value::Number(index)) => blend_mode_from_index(*index as i32),
+            Some(Value::String(name)) => blend_mode_from_str(name),
+            _ => None,
+        };
+        if let Some(blend_mode) = blend_mode {
+            display_object.set_blend_mode(activation.context.gc_context, blend_mode);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
 pub fn remove_display_object<'gc>(
     this: DisplayObject<'gc>,
     activation: &mut Activation<'_, 'gc, '_>,
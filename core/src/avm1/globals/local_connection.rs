@@ -0,0 +1,206 @@
+//! LocalConnection object
+//!
+//! `allowDomain` and `allowInsecureDomain` are not methods of the prototype,
+//! but handlers defined by the receiving movie. They are called with the
+//! sender's domain before a message from another domain is delivered, and the
+//! message is dropped unless they return `true`.
+
+use crate::amf::{self, AmfValue, AmfVersion, Lso};
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::globals::shared_object::serialize_value;
+use crate::avm1::property::Attribute;
+use crate::avm1::{AvmString, LocalConnections, Object, ScriptObject, Value};
+use crate::backend::local_connection::LocalConnectionMessage;
+use crate::display_object::TDisplayObject;
+use gc_arena::MutationContext;
+
+/// The largest message, in bytes, that may be sent.
+const MAX_MESSAGE_SIZE: usize = 40 * 1024;
+
+/// Methods which may not be called through a connection.
+const RESERVED_METHODS: &[&str] = &[
+    "send",
+    "connect",
+    "close",
+    "allowDomain",
+    "allowInsecureDomain",
+    "domain",
+];
+
+/// Implements `LocalConnection`
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.into())
+}
+
+/// The domain of the movie running the current code, and whether that movie
+/// was loaded over HTTPS.
+///
+/// Local movies belong to the `localhost` domain.
+fn movie_domain<'gc>(activation: &mut Activation<'_, 'gc, '_>) -> (String, bool) {
+    let url = activation
+        .base_clip()
+        .movie()
+        .and_then(|movie| movie.url().and_then(|url| url::Url::parse(url).ok()));
+
+    let url = match url {
+        Some(url) if url.scheme() != "file" => url,
+        _ => return ("localhost".to_string(), false),
+    };
+
+    let secure = url.scheme() == "https";
+    let host = url.host_str().unwrap_or("localhost");
+
+    // SWF 6 and earlier use the superdomain, e.g. `example.com` rather than
+    // `www.example.com`.
+    let host = match url.host() {
+        Some(url::Host::Domain(_)) if activation.current_swf_version() < 7 => host
+            .match_indices('.')
+            .rev()
+            .nth(1)
+            .map(|(i, _)| &host[i + 1..])
+            .unwrap_or(host),
+        _ => host,
+    };
+
+    (host.to_string(), secure)
+}
+
+/// The full name of a connection, as passed to the backend.
+///
+/// Names beginning with an underscore are shared by every domain. Any other
+/// name belongs to the given domain, unless it explicitly names a domain
+/// (`domain:name`). Names are case-insensitive.
+fn connection_name(domain: &str, name: &str) -> String {
+    let name = if name.starts_with('_') || name.contains(':') {
+        name.to_string()
+    } else {
+        format!("{}:{}", domain, name)
+    };
+
+    name.to_lowercase()
+}
+
+pub fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = match args.get(0) {
+        Some(Value::String(name)) if !name.is_empty() && !name.contains(':') => *name,
+        _ => return Ok(false.into()),
+    };
+
+    let (domain, secure) = movie_domain(activation);
+    let name = connection_name(&domain, &name);
+
+    Ok(LocalConnections::connect(&mut activation.context, this, name, domain, secure).into())
+}
+
+pub fn send<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (name, method) = match (args.get(0), args.get(1)) {
+        (Some(Value::String(name)), Some(Value::String(method))) => (*name, *method),
+        _ => return Ok(false.into()),
+    };
+
+    if name.is_empty() || method.is_empty() || RESERVED_METHODS.contains(&method.as_str()) {
+        return Ok(false.into());
+    }
+
+    // The method call is encoded as a shared object, named after the method,
+    // with one entry for each argument.
+    let mut lso = Lso::new(method.to_string(), AmfVersion::Amf0);
+    let mut visited = Vec::new();
+    for (i, arg) in args.iter().skip(2).enumerate() {
+        let value = serialize_value(activation, *arg, &mut lso, &mut visited)
+            .unwrap_or(AmfValue::Undefined);
+        lso.body.push((i.to_string(), value));
+    }
+
+    let data = amf::write_lso(&lso);
+    if data.len() > MAX_MESSAGE_SIZE {
+        return Ok(false.into());
+    }
+
+    let (domain, secure) = movie_domain(activation);
+    let message = LocalConnectionMessage {
+        connection_name: connection_name(&domain, &name),
+        sender_domain: domain,
+        sender_secure: secure,
+        data,
+    };
+
+    LocalConnections::send(&mut activation.context, this, message);
+
+    Ok(true.into())
+}
+
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    LocalConnections::close(&mut activation.context, this);
+
+    Ok(Value::Undefined)
+}
+
+pub fn domain<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (domain, _) = movie_domain(activation);
+
+    Ok(AvmString::new(activation.context.gc_context, domain).into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut object = ScriptObject::object(gc_context, Some(proto));
+
+    object.force_set_function(
+        "connect",
+        connect,
+        gc_context,
+        Attribute::DONT_DELETE | Attribute::READ_ONLY | Attribute::DONT_ENUM,
+        Some(fn_proto),
+    );
+
+    object.force_set_function(
+        "send",
+        send,
+        gc_context,
+        Attribute::DONT_DELETE | Attribute::READ_ONLY | Attribute::DONT_ENUM,
+        Some(fn_proto),
+    );
+
+    object.force_set_function(
+        "close",
+        close,
+        gc_context,
+        Attribute::DONT_DELETE | Attribute::READ_ONLY | Attribute::DONT_ENUM,
+        Some(fn_proto),
+    );
+
+    object.force_set_function(
+        "domain",
+        domain,
+        gc_context,
+        Attribute::DONT_DELETE | Attribute::READ_ONLY | Attribute::DONT_ENUM,
+        Some(fn_proto),
+    );
+
+    object.into()
+}
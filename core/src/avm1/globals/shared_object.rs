@@ -1,16 +1,34 @@
+use crate::amf::{self, AmfObject, AmfValue, AmfVersion, Lso, ObjectId};
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
+use crate::avm1::object::date_object::DateObject;
 use crate::avm1::property::Attribute;
 use crate::avm1::{AvmString, Object, TObject, Value};
 use crate::avm_warn;
 use crate::display_object::TDisplayObject;
+use chrono::{TimeZone, Utc};
 use gc_arena::MutationContext;
 
 use crate::avm1::object::shared_object::SharedObject;
 
 use json::JsonValue;
 
+/// How many times its number of elements a deserialized array may be long.
+///
+/// AVM1 arrays store their elements densely, so the length claimed by a
+/// shared object file can't be trusted: a small crafted file could otherwise
+/// exhaust memory. Sparse arrays still fit, as long as they aren't mostly holes.
+const MAX_ARRAY_SPARSENESS: usize = 16;
+
+/// The longest an array deserialized with the given elements may be.
+fn max_array_length(elements: &[(String, AmfValue)]) -> usize {
+    elements
+        .len()
+        .saturating_add(1)
+        .saturating_mul(MAX_ARRAY_SPARSENESS)
+}
+
 pub fn delete_all<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     _this: Object<'gc>,
@@ -29,42 +47,238 @@ pub fn get_disk_usage<'gc>(
     Ok(Value::Undefined)
 }
 
-/// Serialize an Object and any children to a JSON object
-/// It would be best if this was implemented via serde but due to avm and context it can't
-/// Undefined fields aren't serialized
-fn recursive_serialize<'gc>(
+/// Serialize the properties of an Object and any children into a shared object.
+///
+/// Objects that have already been visited are serialized as references, which
+/// allows object graphs with cycles to be stored.
+fn serialize_properties<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     obj: Object<'gc>,
-    json_obj: &mut JsonValue,
-) {
-    for k in &obj.get_keys(activation) {
-        if let Ok(elem) = obj.get(k, activation) {
-            match elem {
-                Value::Undefined => {}
-                Value::Null => json_obj[k] = JsonValue::Null,
-                Value::Bool(b) => json_obj[k] = b.into(),
-                Value::Number(f) => json_obj[k] = f.into(),
-                Value::String(s) => json_obj[k] = s.to_string().into(),
-                Value::Object(o) => {
-                    // Don't attempt to serialize functions
-                    let function = activation.context.avm1.prototypes.function;
-                    let array = activation.context.avm1.prototypes.array;
-                    if !o
-                        .is_instance_of(activation, o, function)
-                        .unwrap_or_default()
-                    {
-                        let mut sub_data_json = JsonValue::new_object();
-                        recursive_serialize(activation, o, &mut sub_data_json);
-                        if o.is_instance_of(activation, o, array).unwrap_or_default() {
-                            sub_data_json["__proto__"] = "Array".into();
-                            sub_data_json["length"] = o.length().into();
+    lso: &mut Lso,
+    visited: &mut Vec<(Object<'gc>, ObjectId)>,
+) -> Vec<(String, AmfValue)> {
+    let mut properties = Vec::new();
+
+    for k in obj.get_keys(activation) {
+        if let Ok(elem) = obj.get(&k, activation) {
+            if let Some(value) = serialize_value(activation, elem, lso, visited) {
+                properties.push((k, value));
+            }
+        }
+    }
+
+    properties
+}
+
+/// Serialize a single value into a shared object.
+///
+/// Functions can't be serialized, and yield `None`.
+pub(crate) fn serialize_value<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    elem: Value<'gc>,
+    lso: &mut Lso,
+    visited: &mut Vec<(Object<'gc>, ObjectId)>,
+) -> Option<AmfValue> {
+    let o = match elem {
+        Value::Undefined => return Some(AmfValue::Undefined),
+        Value::Null => return Some(AmfValue::Null),
+        Value::Bool(b) => return Some(AmfValue::Bool(b)),
+        Value::Number(f) => return Some(AmfValue::Number(f)),
+        Value::String(s) => return Some(AmfValue::String(s.to_string())),
+        Value::Object(o) => o,
+    };
+
+    // Don't attempt to serialize functions
+    let function = activation.context.avm1.prototypes.function;
+    if o.is_instance_of(activation, o, function)
+        .unwrap_or_default()
+    {
+        return None;
+    }
+
+    if let Some((_, id)) = visited.iter().find(|(v, _)| Object::ptr_eq(*v, o)) {
+        return Some(AmfValue::Object(*id));
+    }
+
+    if let Some(date) = o.as_date_object() {
+        let time = date
+            .date_time()
+            .map(|date_time| date_time.timestamp_millis() as f64)
+            .unwrap_or(f64::NAN);
+        return Some(AmfValue::Date(time));
+    }
+
+    if o.as_xml_node().is_some() {
+        return Value::Object(o)
+            .coerce_to_string(activation)
+            .ok()
+            .map(|xml| AmfValue::Xml(xml.to_string()));
+    }
+
+    // Register the object before serializing its children, so that they may refer back to it.
+    let id = lso.add_object(AmfObject::Object {
+        class_name: None,
+        properties: Vec::new(),
+    });
+    visited.push((o, id));
+
+    let array = activation.context.avm1.prototypes.array;
+    let properties = serialize_properties(activation, o, lso, visited);
+    let object = if o.is_instance_of(activation, o, array).unwrap_or_default() {
+        AmfObject::Array {
+            length: o.length(),
+            elements: properties,
+        }
+    } else {
+        AmfObject::Object {
+            class_name: None,
+            properties,
+        }
+    };
+    lso.objects[id] = object;
+
+    Some(AmfValue::Object(id))
+}
+
+/// Deserialize the body of a shared object into a new data object.
+fn deserialize_lso<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    lso: &Lso,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let obj_proto = activation.context.avm1.prototypes.object;
+    let objects = deserialize_objects(activation, lso)?;
+
+    let data = obj_proto.create_bare_object(activation, obj_proto)?;
+    for (name, value) in &lso.body {
+        let value = deserialize_value(activation, value, &objects)?;
+        data.define_value(
+            activation.context.gc_context,
+            name,
+            value,
+            Attribute::empty(),
+        );
+    }
+
+    Ok(data)
+}
+
+/// Deserialize every complex value of a shared object.
+///
+/// The returned list holds the created object for each entry of `lso.objects`,
+/// for use with `deserialize_value`.
+pub(crate) fn deserialize_objects<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    lso: &Lso,
+) -> Result<Vec<Value<'gc>>, Error<'gc>> {
+    let obj_proto = activation.context.avm1.prototypes.object;
+    let array_constructor = activation.context.avm1.prototypes.array_constructor;
+
+    // Create all objects up front, so that references to them can be resolved
+    // regardless of the order they appear in.
+    let mut objects = Vec::with_capacity(lso.objects.len());
+    for object in &lso.objects {
+        objects.push(match object {
+            AmfObject::Object { .. } => obj_proto.create_bare_object(activation, obj_proto)?.into(),
+            AmfObject::Array { length, elements } => {
+                let length = (*length).min(max_array_length(elements));
+                array_constructor.construct(activation, &[(length as f64).into()])?
+            }
+            // AVM1 has no representation of raw binary data.
+            AmfObject::ByteArray(_) => Value::Undefined,
+        });
+    }
+
+    for (object, target) in lso.objects.iter().zip(objects.iter()) {
+        let target = if let Value::Object(target) = target {
+            *target
+        } else {
+            continue;
+        };
+
+        match object {
+            AmfObject::Object { properties, .. } => {
+                for (name, value) in properties {
+                    let value = deserialize_value(activation, value, &objects)?;
+                    target.define_value(
+                        activation.context.gc_context,
+                        name,
+                        value,
+                        Attribute::empty(),
+                    );
+                }
+            }
+            AmfObject::Array { elements, .. } => {
+                // Elements too far out are kept as plain properties.
+                let max_length = max_array_length(elements);
+                for (name, value) in elements {
+                    let value = deserialize_value(activation, value, &objects)?;
+                    match name.parse::<usize>() {
+                        Ok(i) if i < max_length => {
+                            target.set_array_element(i, value, activation.context.gc_context);
+                        }
+                        _ => {
+                            target.define_value(
+                                activation.context.gc_context,
+                                name,
+                                value,
+                                Attribute::empty(),
+                            );
                         }
-                        json_obj[k] = sub_data_json;
                     }
                 }
             }
+            AmfObject::ByteArray(_) => {}
         }
     }
+
+    Ok(objects)
+}
+
+/// Deserialize a single value from a shared object.
+///
+/// Complex values are looked up in `objects`, which holds the already
+/// created object for each of the shared object's complex values.
+pub(crate) fn deserialize_value<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: &AmfValue,
+    objects: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(match value {
+        AmfValue::Undefined => Value::Undefined,
+        AmfValue::Null => Value::Null,
+        AmfValue::Bool(b) => Value::Bool(*b),
+        AmfValue::Number(f) => Value::Number(*f),
+        AmfValue::Integer(i) => Value::Number(*i as f64),
+        AmfValue::String(s) => {
+            Value::String(AvmString::new(activation.context.gc_context, s.clone()))
+        }
+        AmfValue::Date(time) => {
+            let date_time = if time.is_finite() {
+                Utc.timestamp_millis_opt(*time as i64).single()
+            } else {
+                None
+            };
+            let date = DateObject::with_date_time(
+                activation.context.gc_context,
+                Some(activation.context.avm1.prototypes.date),
+                date_time,
+            );
+
+            Object::from(date).into()
+        }
+        AmfValue::Xml(xml) => {
+            let xml_constructor = activation
+                .context
+                .avm1
+                .global_object_cell()
+                .get("XML", activation)?
+                .coerce_to_object(activation);
+            let xml = AvmString::new(activation.context.gc_context, xml.clone());
+
+            xml_constructor.construct(activation, &[xml.into()])?
+        }
+        AmfValue::Object(id) => objects.get(*id).cloned().unwrap_or(Value::Undefined),
+    })
 }
 
 fn recursive_deserialize<'gc>(
@@ -267,9 +481,25 @@ pub fn get_local<'gc>(
     let mut data = Value::Undefined;
 
     // Load the data object from storage if it existed prior
-    if let Some(saved) = activation.context.storage.get_string(&full_name) {
-        if let Ok(json_data) = json::parse(&saved) {
-            data = recursive_deserialize(json_data, activation);
+    if let Some(saved) = activation.context.storage.get(&full_name) {
+        match amf::read_lso(&saved) {
+            Ok(lso) => data = deserialize_lso(activation, &lso)?.into(),
+            Err(e) => {
+                // Older versions of Ruffle stored shared objects as JSON.
+                // These are read as-is, and replaced by a `.sol` file on the next flush.
+                let json_data = std::str::from_utf8(&saved)
+                    .ok()
+                    .and_then(|saved| json::parse(saved).ok());
+                if let Some(json_data) = json_data {
+                    data = recursive_deserialize(json_data, activation);
+                } else {
+                    log::warn!(
+                        "SharedObject.get_local: Unable to read {}: {}",
+                        full_name,
+                        e
+                    );
+                }
+            }
         }
     }
 
@@ -443,16 +673,19 @@ pub fn flush<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     let data = this.get("data", activation)?.coerce_to_object(activation);
 
-    let mut data_json = JsonValue::new_object();
-    recursive_serialize(activation, data, &mut data_json);
-
     let this_obj = this.as_shared_object().unwrap();
     let name = this_obj.get_name();
 
+    // The file only records the name of the shared object, not its full path.
+    let short_name = name.rsplit('/').next().unwrap_or_default();
+    let mut lso = Lso::new(short_name.to_string(), AmfVersion::Amf0);
+    let body = serialize_properties(activation, data, &mut lso, &mut Vec::new());
+    lso.body = body;
+
     Ok(activation
         .context
         .storage
-        .put_string(&name, data_json.dump())
+        .put(&name, &amf::write_lso(&lso))
         .into())
 }
 
@@ -1,5 +1,5 @@
 //! AVM1 Sound object
-//! TODO: Sound position, transform, loadSound
+//! TODO: Sound position, transform
 
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
@@ -7,7 +7,9 @@ use crate::avm1::function::{Executable, FunctionObject};
 use crate::avm1::property::Attribute;
 use crate::avm1::{Object, ScriptObject, SoundObject, TObject, Value};
 use crate::avm_warn;
+use crate::backend::audio::SoundHandle;
 use crate::character::Character;
+use crate::context::UpdateContext;
 use crate::display_object::{SoundTransform, TDisplayObject};
 use gc_arena::MutationContext;
 
@@ -241,28 +243,28 @@ fn duration<'gc>(
 
 fn get_bytes_loaded<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
-    _this: Object<'gc>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if activation.current_swf_version() >= 6 {
-        avm_warn!(activation, "Sound.getBytesLoaded: Unimplemented");
-        Ok(1.into())
-    } else {
-        Ok(Value::Undefined)
+        if let Some(bytes_loaded) = this.as_sound_object().and_then(|o| o.bytes_loaded()) {
+            return Ok(bytes_loaded.into());
+        }
     }
+    Ok(Value::Undefined)
 }
 
 fn get_bytes_total<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
-    _this: Object<'gc>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if activation.current_swf_version() >= 6 {
-        avm_warn!(activation, "Sound.getBytesTotal: Unimplemented");
-        Ok(1.into())
-    } else {
-        Ok(Value::Undefined)
+        if let Some(bytes_total) = this.as_sound_object().and_then(|o| o.bytes_total()) {
+            return Ok(bytes_total.into());
+        }
     }
+    Ok(Value::Undefined)
 }
 
 fn get_pan<'gc>(
@@ -333,22 +335,53 @@ fn get_volume<'gc>(
 
 fn id3<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
-    _this: Object<'gc>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if activation.current_swf_version() >= 6 {
-        avm_warn!(activation, "Sound.id3: Unimplemented");
+        if let Some(id3) = this.as_sound_object().and_then(|o| o.id3()) {
+            return Ok(id3.into());
+        }
     }
     Ok(Value::Undefined)
 }
 
 fn load_sound<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if activation.current_swf_version() >= 6 {
-        avm_warn!(activation, "Sound.loadSound: Unimplemented");
+        if let Some(sound_object) = this.as_sound_object() {
+            let url = args
+                .get(0)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_string(activation)?;
+            let is_streaming = args
+                .get(1)
+                .unwrap_or(&Value::Undefined)
+                .as_bool(activation.current_swf_version());
+
+            // Loading a new sound stops the one that is playing.
+            if let Some(sound_instance) = sound_object.sound_instance() {
+                activation.context.stop_sound(sound_instance);
+                sound_object.set_sound_instance(activation.context.gc_context, None);
+            }
+            sound_object.set_id3(activation.context.gc_context, None);
+            sound_object.set_bytes_loaded(activation.context.gc_context, Some(0));
+            sound_object.set_bytes_total(activation.context.gc_context, None);
+
+            let process = activation.context.load_manager.load_sound_into_object(
+                activation.context.player.clone().unwrap(),
+                sound_object,
+                is_streaming,
+                activation.context.navigator,
+                &url,
+            );
+            activation.context.navigator.spawn_future(process);
+        } else {
+            avm_warn!(activation, "Sound.loadSound: this is not a Sound");
+        }
     }
     Ok(Value::Undefined)
 }
@@ -360,11 +393,10 @@ fn position<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     if activation.current_swf_version() >= 6 {
         if let Some(sound_object) = this.as_sound_object() {
-            // TODO: The position is "sticky"; even if the sound is no longer playing, it should return
-            // the previous valid position.
-            // Needs some audio backend work for this.
+            // The position is "sticky"; once the sound is no longer playing, this returns the
+            // last valid position.
             if sound_object.sound().is_some() {
-                avm_warn!(activation, "Sound.position: Unimplemented");
+                update_position(&mut activation.context, sound_object);
                 return Ok(sound_object.position().into());
             }
         } else {
@@ -481,29 +513,15 @@ fn start<'gc>(
     // TODO: Handle loops > std::u16::MAX.
     let loops = (loops as u16).max(1);
 
-    use swf::{SoundEvent, SoundInfo};
     if let Some(sound_object) = this.as_sound_object() {
         if let Some(sound) = sound_object.sound() {
-            let sound_instance = activation.context.start_sound(
+            start_sound(
+                &mut activation.context,
+                sound_object,
                 sound,
-                &SoundInfo {
-                    event: SoundEvent::Start,
-                    in_sample: if start_offset > 0.0 {
-                        Some((start_offset * 44100.0) as u32)
-                    } else {
-                        None
-                    },
-                    out_sample: None,
-                    num_loops: loops,
-                    envelope: None,
-                },
-                sound_object.owner(),
-                Some(sound_object),
+                start_offset,
+                loops,
             );
-            if let Some(sound_instance) = sound_instance {
-                sound_object
-                    .set_sound_instance(activation.context.gc_context, Some(sound_instance));
-            }
         } else {
             avm_warn!(activation, "Sound.start: No sound is attached");
         }
@@ -514,12 +532,54 @@ fn start<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Plays a sound on behalf of a `Sound` object, starting `start_offset`
+/// seconds in.
+pub(crate) fn start_sound<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    sound_object: SoundObject<'gc>,
+    sound: SoundHandle,
+    start_offset: f64,
+    loops: u16,
+) {
+    use swf::{SoundEvent, SoundInfo};
+    let sound_instance = context.start_sound(
+        sound,
+        &SoundInfo {
+            event: SoundEvent::Start,
+            in_sample: if start_offset > 0.0 {
+                Some((start_offset * 44100.0) as u32)
+            } else {
+                None
+            },
+            out_sample: None,
+            num_loops: loops,
+            envelope: None,
+        },
+        sound_object.owner(),
+        Some(sound_object),
+    );
+    if let Some(sound_instance) = sound_instance {
+        sound_object.set_sound_instance(context.gc_context, Some(sound_instance));
+    }
+}
+
+/// Updates the position of a `Sound` object from its playing sound, if any.
+fn update_position<'gc>(context: &mut UpdateContext<'_, 'gc, '_>, sound_object: SoundObject<'gc>) {
+    if let Some(sound_instance) = sound_object.sound_instance() {
+        if let Some(position) = context.audio.get_sound_position(sound_instance) {
+            sound_object.set_position(context.gc_context, position);
+        }
+    }
+}
+
 fn stop<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Object<'gc>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(sound) = this.as_sound_object() {
+        update_position(&mut activation.context, sound);
+
         if let Some(name) = args.get(0) {
             // Usage 1: Stop all instances of a particular sound, using the name parameter.
             let name = name.coerce_to_string(activation)?;
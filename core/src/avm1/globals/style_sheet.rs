@@ -0,0 +1,230 @@
+//! TextField.StyleSheet object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::object::style_sheet::StyleSheetObject;
+use crate::avm1::property::Attribute;
+use crate::avm1::{AvmString, Object, ScriptObject, TObject, Value};
+use crate::html::Style;
+use gc_arena::MutationContext;
+
+/// `TextField.StyleSheet` constructor
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.into())
+}
+
+/// Convert an ActionScript style object into a `Style`.
+///
+/// Every enumerable property of the object becomes a property of the style.
+/// Returns `None` if the value is not an object.
+fn value_to_style<'gc>(
+    value: Value<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Option<Style>, Error<'gc>> {
+    let object = match value {
+        Value::Object(object) => object,
+        _ => return Ok(None),
+    };
+
+    let mut style = Style::new();
+    for name in object.get_keys(activation) {
+        let value = object
+            .get(&name, activation)?
+            .coerce_to_string(activation)?;
+        style.set_property(&name, &value);
+    }
+
+    Ok(Some(style))
+}
+
+fn parse_css<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(style_sheet) = this.as_style_sheet_object() {
+        let css = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation)?;
+
+        return Ok(style_sheet
+            .style_sheet_mut(activation.context.gc_context)
+            .parse_css(&css)
+            .into());
+    }
+
+    Ok(false.into())
+}
+
+fn set_style<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(style_sheet) = this.as_style_sheet_object() {
+        let name = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation)?;
+        let style = value_to_style(args.get(1).cloned().unwrap_or(Value::Undefined), activation)?;
+
+        style_sheet
+            .style_sheet_mut(activation.context.gc_context)
+            .set_style(&name, style);
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn get_style<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(style_sheet) = this.as_style_sheet_object() {
+        let name = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation)?;
+        let style = style_sheet.style_sheet().style(&name).cloned();
+
+        if let Some(style) = style {
+            let object = ScriptObject::object(
+                activation.context.gc_context,
+                Some(activation.context.avm1.prototypes.object),
+            );
+
+            for (name, value) in style.properties() {
+                let value = AvmString::new(activation.context.gc_context, value.to_string());
+                object.set(name, value.into(), activation)?;
+            }
+
+            return Ok(object.into());
+        }
+    }
+
+    Ok(Value::Null)
+}
+
+fn get_style_names<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array = ScriptObject::array(
+        activation.context.gc_context,
+        Some(activation.context.avm1.prototypes.array),
+    );
+
+    if let Some(style_sheet) = this.as_style_sheet_object() {
+        let names: Vec<String> = style_sheet
+            .style_sheet()
+            .style_names()
+            .map(|name| name.to_string())
+            .collect();
+
+        array.set_length(activation.context.gc_context, names.len());
+        for (i, name) in names.into_iter().enumerate() {
+            array.set_array_element(
+                i,
+                AvmString::new(activation.context.gc_context, name).into(),
+                activation.context.gc_context,
+            );
+        }
+    }
+
+    Ok(array.into())
+}
+
+fn clear<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(style_sheet) = this.as_style_sheet_object() {
+        style_sheet
+            .style_sheet_mut(activation.context.gc_context)
+            .clear();
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn transform<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let style = value_to_style(args.get(0).cloned().unwrap_or(Value::Undefined), activation)?;
+
+    if let Some(style) = style {
+        return Ok(style.text_format().as_avm1_object(activation)?.into());
+    }
+
+    Ok(Value::Null)
+}
+
+/// `TextField.StyleSheet.prototype` constructor
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let style_sheet = StyleSheetObject::empty_style_sheet_object(gc_context, Some(proto));
+    let mut object = style_sheet.as_script_object().unwrap();
+
+    object.force_set_function(
+        "parseCSS",
+        parse_css,
+        gc_context,
+        Attribute::empty(),
+        Some(fn_proto),
+    );
+
+    object.force_set_function(
+        "setStyle",
+        set_style,
+        gc_context,
+        Attribute::empty(),
+        Some(fn_proto),
+    );
+
+    object.force_set_function(
+        "getStyle",
+        get_style,
+        gc_context,
+        Attribute::empty(),
+        Some(fn_proto),
+    );
+
+    object.force_set_function(
+        "getStyleNames",
+        get_style_names,
+        gc_context,
+        Attribute::empty(),
+        Some(fn_proto),
+    );
+
+    object.force_set_function(
+        "clear",
+        clear,
+        gc_context,
+        Attribute::empty(),
+        Some(fn_proto),
+    );
+
+    object.force_set_function(
+        "transform",
+        transform,
+        gc_context,
+        Attribute::empty(),
+        Some(fn_proto),
+    );
+
+    style_sheet.into()
+}
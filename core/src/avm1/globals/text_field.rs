@@ -1,7 +1,7 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
-use crate::avm1::globals::display_object;
+use crate::avm1::globals::{bitmap_filter, display_object};
 use crate::avm1::property::Attribute;
 use crate::avm1::{AvmString, Object, ScriptObject, TObject, Value};
 use crate::avm_error;
@@ -139,6 +139,16 @@ pub fn create_proto<'gc>(
         "variable" => [variable, set_variable],
         "wordWrap" => [word_wrap, set_word_wrap],
         "password" => [password, set_password],
+        "scroll" => [scroll, set_scroll],
+        "maxscroll" => [maxscroll],
+        "bottomScroll" => [bottom_scroll],
+        "hscroll" => [hscroll, set_hscroll],
+        "maxhscroll" => [maxhscroll],
+        "mouseWheelEnabled" => [mouse_wheel_enabled, set_mouse_wheel_enabled],
+        "maxChars" => [max_chars, set_max_chars],
+        "restrict" => [restrict, set_restrict],
+        "styleSheet" => [style_sheet, set_style_sheet],
+        "filters" => [filters, set_filters],
     );
 
     object.into()
@@ -160,6 +170,21 @@ pub fn set_password<'gc>(
     Ok(())
 }
 
+pub fn filters<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    bitmap_filter::get_filters(activation, this.into())
+}
+
+pub fn set_filters<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    bitmap_filter::set_filters(activation, this.into(), value)
+}
+
 fn get_new_text_format<'gc>(
     text_field: EditText<'gc>,
     activation: &mut Activation<'_, 'gc, '_>,
@@ -616,3 +641,148 @@ pub fn set_type<'gc>(
     };
     Ok(())
 }
+
+pub fn scroll<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok((this.scroll() as f64).into())
+}
+
+pub fn set_scroll<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let scroll = value.coerce_to_i32(activation)?;
+    this.set_scroll(scroll.into(), &mut activation.context);
+    Ok(())
+}
+
+pub fn maxscroll<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok((this.maxscroll() as f64).into())
+}
+
+pub fn bottom_scroll<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok((this.bottom_scroll() as f64).into())
+}
+
+pub fn hscroll<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.hscroll().into())
+}
+
+pub fn set_hscroll<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let hscroll = value.coerce_to_i32(activation)?;
+    this.set_hscroll(hscroll.into(), &mut activation.context);
+    Ok(())
+}
+
+pub fn maxhscroll<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.maxhscroll().into())
+}
+
+pub fn mouse_wheel_enabled<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.is_mouse_wheel_enabled().into())
+}
+
+pub fn set_mouse_wheel_enabled<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let is_enabled = value.as_bool(activation.current_swf_version());
+    this.set_mouse_wheel_enabled(is_enabled, activation.context.gc_context);
+    Ok(())
+}
+
+pub fn max_chars<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    // Unlimited `maxChars` is null, not 0
+    Ok(this
+        .max_chars()
+        .map_or(Value::Null, |max_chars| (max_chars as f64).into()))
+}
+
+pub fn set_max_chars<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let max_chars = match value {
+        Value::Undefined | Value::Null => None,
+        v => Some(v.coerce_to_i32(activation)?).filter(|&max_chars| max_chars > 0),
+    };
+    this.set_max_chars(
+        max_chars.map(|max_chars| max_chars as usize),
+        activation.context.gc_context,
+    );
+    Ok(())
+}
+
+pub fn restrict<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(restrict) = this.restrict() {
+        return Ok(AvmString::new(activation.context.gc_context, restrict.to_string()).into());
+    }
+
+    // Unset `restrict` returns null, not undefined
+    Ok(Value::Null)
+}
+
+pub fn set_restrict<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let restrict = match value {
+        Value::Undefined | Value::Null => None,
+        v => Some(v.coerce_to_string(activation)?.to_string()),
+    };
+    this.set_restrict(restrict, activation.context.gc_context);
+    Ok(())
+}
+
+pub fn style_sheet<'gc>(
+    this: EditText<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.style_sheet().map_or(Value::Undefined, |style_sheet| {
+        Object::from(style_sheet).into()
+    }))
+}
+
+pub fn set_style_sheet<'gc>(
+    this: EditText<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    let style_sheet = match value {
+        Value::Object(object) => object.as_style_sheet_object(),
+        _ => None,
+    };
+    this.set_style_sheet(style_sheet, &mut activation.context);
+    Ok(())
+}
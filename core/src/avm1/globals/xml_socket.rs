@@ -0,0 +1,142 @@
+//! XMLSocket object
+//!
+//! `onConnect`, `onXML` and `onClose` are handlers defined by the movie. The
+//! default `onData` handler parses each received message as XML and passes it
+//! to `onXML`.
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::property::Attribute;
+use crate::avm1::{Object, ScriptObject, TObject, Value, XmlSockets};
+use crate::display_object::TDisplayObject;
+use gc_arena::MutationContext;
+
+/// Implements `XMLSocket`
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.into())
+}
+
+pub fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // A missing host refers to the host that the movie was loaded from.
+    let host = match args.get(0) {
+        None | Some(Value::Undefined) | Some(Value::Null) => activation
+            .base_clip()
+            .movie()
+            .and_then(|movie| movie.url().and_then(|url| url::Url::parse(url).ok()))
+            .and_then(|url| url.host_str().map(|host| host.to_string()))
+            .unwrap_or_else(|| "localhost".to_string()),
+        Some(host) => host.coerce_to_string(activation)?.to_string(),
+    };
+
+    // Only unprivileged ports may be connected to.
+    let port = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_f64(activation)?;
+    if !(1024.0..=65535.0).contains(&port) {
+        return Ok(false.into());
+    }
+
+    XmlSockets::connect(&mut activation.context, this, &host, port as u16);
+
+    Ok(true.into())
+}
+
+pub fn send<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let data = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+
+    let mut message = data.as_bytes().to_vec();
+    message.push(0);
+    XmlSockets::send(&mut activation.context, this, &message);
+
+    Ok(Value::Undefined)
+}
+
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    XmlSockets::close(&mut activation.context, this);
+
+    Ok(Value::Undefined)
+}
+
+pub fn on_data<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let src = args.get(0).cloned().unwrap_or(Value::Undefined);
+
+    let xml_constructor = activation
+        .context
+        .avm1
+        .global_object_cell()
+        .get("XML", activation)?
+        .coerce_to_object(activation);
+    let xml = xml_constructor.construct(activation, &[src])?;
+
+    if let Value::Object(_) = this.get("onXML", activation)? {
+        this.call_method("onXML", &[xml], activation)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut object = ScriptObject::object(gc_context, Some(proto));
+
+    object.force_set_function(
+        "connect",
+        connect,
+        gc_context,
+        Attribute::DONT_DELETE | Attribute::READ_ONLY | Attribute::DONT_ENUM,
+        Some(fn_proto),
+    );
+
+    object.force_set_function(
+        "send",
+        send,
+        gc_context,
+        Attribute::DONT_DELETE | Attribute::READ_ONLY | Attribute::DONT_ENUM,
+        Some(fn_proto),
+    );
+
+    object.force_set_function(
+        "close",
+        close,
+        gc_context,
+        Attribute::DONT_DELETE | Attribute::READ_ONLY | Attribute::DONT_ENUM,
+        Some(fn_proto),
+    );
+
+    object.force_set_function(
+        "onData",
+        on_data,
+        gc_context,
+        Attribute::DONT_ENUM,
+        Some(fn_proto),
+    );
+
+    object.into()
+}
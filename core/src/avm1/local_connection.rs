@@ -0,0 +1,229 @@
+//! `LocalConnection` message handling.
+//!
+//! Messages are passed through the player's `LocalConnectionBackend`, which
+//! may connect it to other players. Both messages and the results of sending
+//! them are delivered at the start of the next frame, as in Flash Player.
+
+use crate::amf;
+use crate::avm1::globals::shared_object::{deserialize_objects, deserialize_value};
+use crate::avm1::property::Attribute;
+use crate::avm1::{
+    Activation, ActivationIdentifier, AvmString, Error, Object, ScriptObject, TObject, Value,
+};
+use crate::backend::local_connection::LocalConnectionMessage;
+use crate::context::UpdateContext;
+use gc_arena::Collect;
+use std::collections::HashMap;
+
+/// An object listening for messages on a connection name.
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+struct Receiver<'gc> {
+    /// The `LocalConnection` object that called `connect`.
+    object: Object<'gc>,
+
+    /// The domain of the movie that made the connection.
+    domain: String,
+
+    /// Whether the movie that made the connection was loaded over HTTPS.
+    secure: bool,
+}
+
+/// Manages the connected `LocalConnection` objects of a player.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct LocalConnections<'gc> {
+    /// The object listening on each connection name.
+    receivers: HashMap<String, Receiver<'gc>>,
+
+    /// Objects that sent a message since the last update, along with whether
+    /// the message found a listener.
+    sent: Vec<(Object<'gc>, bool)>,
+}
+
+impl<'gc> LocalConnections<'gc> {
+    pub fn new() -> Self {
+        Self {
+            receivers: HashMap::new(),
+            sent: Vec::new(),
+        }
+    }
+
+    /// The connection name that an object is listening on, if any.
+    pub fn connection_name(&self, object: Object<'gc>) -> Option<&str> {
+        self.receivers
+            .iter()
+            .find(|(_, receiver)| Object::ptr_eq(receiver.object, object))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Start listening for messages sent to a connection name.
+    ///
+    /// Returns `false` if the object is already connected, or if the name is
+    /// in use by any player sharing this player's backend.
+    pub fn connect(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        object: Object<'gc>,
+        connection_name: String,
+        domain: String,
+        secure: bool,
+    ) -> bool {
+        if context.local_connections.connection_name(object).is_some()
+            || !context.local_connection.connect(&connection_name)
+        {
+            return false;
+        }
+
+        context.local_connections.receivers.insert(
+            connection_name,
+            Receiver {
+                object,
+                domain,
+                secure,
+            },
+        );
+        true
+    }
+
+    /// Stop listening for messages, if the object is connected.
+    pub fn close(context: &mut UpdateContext<'_, 'gc, '_>, object: Object<'gc>) {
+        let connection_name = context
+            .local_connections
+            .connection_name(object)
+            .map(|name| name.to_string());
+
+        if let Some(connection_name) = connection_name {
+            context.local_connections.receivers.remove(&connection_name);
+            context.local_connection.close(&connection_name);
+        }
+    }
+
+    /// Send a message on behalf of an object.
+    ///
+    /// The object's `onStatus` handler is called on the next update.
+    pub fn send(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        object: Object<'gc>,
+        message: LocalConnectionMessage,
+    ) {
+        let delivered = context.local_connection.send(message);
+        context.local_connections.sent.push((object, delivered));
+    }
+
+    /// Deliver all received messages, then notify senders of whether their
+    /// messages were delivered.
+    ///
+    /// Messages stay queued until a movie has been loaded into `_level0`.
+    pub fn update_local_connections(context: &mut UpdateContext<'_, 'gc, '_>) {
+        let level0 = if let Some(level0) = context.levels.get(&0).copied() {
+            level0
+        } else {
+            return;
+        };
+
+        let messages = context.local_connection.receive();
+        let sent = std::mem::take(&mut context.local_connections.sent);
+
+        if messages.is_empty() && sent.is_empty() {
+            return;
+        }
+
+        let version = context.swf.header().version;
+        let globals = context.avm1.global_object_cell();
+
+        let mut activation = Activation::from_nothing(
+            context.reborrow(),
+            ActivationIdentifier::root("[LocalConnection]"),
+            version,
+            globals,
+            level0,
+        );
+
+        for message in messages {
+            let _ = Self::deliver(&mut activation, message);
+        }
+
+        for (object, delivered) in sent {
+            let info = ScriptObject::object(
+                activation.context.gc_context,
+                Some(activation.context.avm1.prototypes.object),
+            );
+            let level = if delivered { "status" } else { "error" };
+            info.define_value(
+                activation.context.gc_context,
+                "level",
+                level.into(),
+                Attribute::empty(),
+            );
+
+            let _ = object.call_method("onStatus", &[info.into()], &mut activation);
+        }
+    }
+
+    /// Call the method described by a message on the object listening for it.
+    fn deliver(
+        activation: &mut Activation<'_, 'gc, '_>,
+        message: LocalConnectionMessage,
+    ) -> Result<(), Error<'gc>> {
+        let receiver = match activation
+            .context
+            .local_connections
+            .receivers
+            .get(&message.connection_name)
+        {
+            Some(receiver) => receiver.clone(),
+            // The connection was closed after the message was sent.
+            None => return Ok(()),
+        };
+
+        let lso = match amf::read_lso(&message.data) {
+            Ok(lso) => lso,
+            Err(e) => {
+                log::warn!("Unable to read LocalConnection message: {}", e);
+                return Ok(());
+            }
+        };
+
+        // Messages from other domains, or from HTTP movies to HTTPS movies,
+        // must be explicitly allowed by the receiver.
+        let permission = if receiver.secure && !message.sender_secure {
+            Some("allowInsecureDomain")
+        } else if receiver.domain != message.sender_domain {
+            Some("allowDomain")
+        } else {
+            None
+        };
+
+        if let Some(permission) = permission {
+            let handler = receiver.object.get(permission, activation)?;
+            if !matches!(handler, Value::Object(_)) {
+                return Ok(());
+            }
+
+            let domain = AvmString::new(activation.context.gc_context, message.sender_domain);
+            let allowed = receiver
+                .object
+                .call_method(permission, &[domain.into()], activation)?
+                .as_bool(activation.current_swf_version());
+            if !allowed {
+                return Ok(());
+            }
+        }
+
+        let objects = deserialize_objects(activation, &lso)?;
+        let mut args = Vec::with_capacity(lso.body.len());
+        for (_, value) in &lso.body {
+            args.push(deserialize_value(activation, value, &objects)?);
+        }
+
+        receiver.object.call_method(&lso.name, &args, activation)?;
+
+        Ok(())
+    }
+}
+
+impl Default for LocalConnections<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -3,6 +3,7 @@
 use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
 use crate::avm1::object::shared_object::SharedObject;
+use crate::avm1::object::style_sheet::StyleSheetObject;
 use crate::avm1::object::super_object::SuperObject;
 use crate::avm1::object::value_object::ValueObject;
 use crate::avm1::property::Attribute;
@@ -50,6 +51,7 @@ pub mod script_object;
 pub mod shared_object;
 pub mod sound_object;
 pub mod stage_object;
+pub mod style_sheet;
 pub mod super_object;
 pub mod transform_object;
 pub mod value_object;
@@ -86,6 +88,7 @@ pub mod xml_object;
         GradientGlowFilterObject(GradientGlowFilterObject<'gc>),
         DateObject(DateObject<'gc>),
         BitmapData(BitmapDataObject<'gc>),
+        StyleSheetObject(StyleSheetObject<'gc>),
     }
 )]
 pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy {
@@ -511,6 +514,11 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         None
     }
 
+    /// Get the underlying `StyleSheetObject`, if it exists
+    fn as_style_sheet_object(&self) -> Option<StyleSheetObject<'gc>> {
+        None
+    }
+
     fn as_ptr(&self) -> *const ObjectPtr;
 
     /// Check if this object is in the prototype chain of the specified test object.
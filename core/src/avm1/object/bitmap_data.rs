@@ -6,11 +6,16 @@ use gc_arena::{Collect, GcCell, MutationContext};
 
 use crate::avm1::activation::Activation;
 use crate::avm1::object::color_transform_object::ColorTransformObject;
+use crate::avm1::object::displacement_map_filter::{
+    DisplacementMapFilterMode, DisplacementMapFilterObject,
+};
 use crate::backend::render::{BitmapHandle, RenderBackend};
+use crate::bitmap::filters;
 use crate::bitmap::turbulence::Turbulence;
 use downcast_rs::__std::fmt::Formatter;
 use std::fmt;
 use std::ops::Range;
+use swf::Filter;
 
 /// An implementation of the Lehmer/Park-Miller random number generator
 /// Uses the fixed parameters m = 2,147,483,647 and a = 16,807
@@ -150,6 +155,61 @@ impl From<u32> for ChannelOptions {
     }
 }
 
+/// The comparison made by `BitmapData.threshold`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ThresholdOperation {
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl ThresholdOperation {
+    pub fn from_operator(operator: &str) -> Option<Self> {
+        match operator {
+            "<" => Some(ThresholdOperation::LessThan),
+            "<=" => Some(ThresholdOperation::LessThanOrEqual),
+            ">" => Some(ThresholdOperation::GreaterThan),
+            ">=" => Some(ThresholdOperation::GreaterThanOrEqual),
+            "==" => Some(ThresholdOperation::Equal),
+            "!=" => Some(ThresholdOperation::NotEqual),
+            _ => None,
+        }
+    }
+
+    fn matches(self, value: u32, threshold: u32) -> bool {
+        match self {
+            ThresholdOperation::LessThan => value < threshold,
+            ThresholdOperation::LessThanOrEqual => value <= threshold,
+            ThresholdOperation::GreaterThan => value > threshold,
+            ThresholdOperation::GreaterThanOrEqual => value >= threshold,
+            ThresholdOperation::Equal => value == threshold,
+            ThresholdOperation::NotEqual => value != threshold,
+        }
+    }
+}
+
+/// Clips a copy of `len` pixels from `src` in a bitmap of size `src_size` to `dest` in a bitmap
+/// of size `dest_size`, returning the clipped `(src, dest, len)`.
+fn clip_copy_axis(src: i32, dest: i32, len: i32, src_size: u32, dest_size: u32) -> (u32, u32, u32) {
+    let skip = 0.max(-src).max(-dest);
+    let (src, dest) = (src + skip, dest + skip);
+    let len = (len - skip)
+        .min(src_size as i32 - src)
+        .min(dest_size as i32 - dest)
+        .max(0);
+    (src.max(0) as u32, dest.max(0) as u32, len as u32)
+}
+
+/// Feedback masks of maximal length Galois LFSRs, indexed by their number of bits.
+const LFSR_TAPS: [u32; 33] = [
+    0, 0, 0x3, 0x6, 0xC, 0x14, 0x30, 0x60, 0xB8, 0x110, 0x240, 0x500, 0x829, 0x100D, 0x2015,
+    0x6000, 0xD008, 0x12000, 0x20400, 0x40023, 0x90000, 0x140000, 0x300000, 0x420000, 0xE10000,
+    0x1200000, 0x2000023, 0x4000013, 0x9000000, 0x14000000, 0x20000029, 0x48000000, 0x80200003,
+];
+
 #[derive(Clone, Collect, Default, Debug)]
 #[collect(no_drop)]
 pub struct BitmapData {
@@ -712,6 +772,372 @@ impl BitmapData {
         }
     }
 
+    /// Clips a copy of `src_rect` from `source_bitmap` to `dest_point` in this bitmap to the
+    /// bounds of both, returning the source position, destination position and size of the
+    /// pixels that can be copied.
+    fn clip_copy_rect(
+        &self,
+        source_bitmap: &Self,
+        src_rect: (i32, i32, i32, i32),
+        dest_point: (i32, i32),
+    ) -> ((u32, u32), (u32, u32), (u32, u32)) {
+        let (src_x, src_y, width, height) = src_rect;
+        let (dest_x, dest_y) = dest_point;
+        let (src_x, dest_x, width) =
+            clip_copy_axis(src_x, dest_x, width, source_bitmap.width(), self.width());
+        let (src_y, dest_y, height) =
+            clip_copy_axis(src_y, dest_y, height, source_bitmap.height(), self.height());
+        ((src_x, src_y), (dest_x, dest_y), (width, height))
+    }
+
+    /// Sets each pixel copied from `source_bitmap` to `color` if it passes the test, or to its
+    /// source pixel if `copy_source` is set.
+    /// Returns the number of pixels that passed the test.
+    #[allow(clippy::too_many_arguments)]
+    pub fn threshold(
+        &mut self,
+        source_bitmap: &Self,
+        src_rect: (i32, i32, i32, i32),
+        dest_point: (i32, i32),
+        operation: ThresholdOperation,
+        threshold: u32,
+        color: Color,
+        mask: u32,
+        copy_source: bool,
+    ) -> u32 {
+        let ((src_x, src_y), (dest_x, dest_y), (width, height)) =
+            self.clip_copy_rect(source_bitmap, src_rect, dest_point);
+        let threshold = threshold & mask;
+        let mut modified_count = 0;
+
+        for y in 0..height {
+            for x in 0..width {
+                let source_color =
+                    source_bitmap.get_pixel32((src_x + x) as i32, (src_y + y) as i32);
+                let dest_color = if operation.matches(u32::from(source_color) & mask, threshold) {
+                    modified_count += 1;
+                    color
+                } else if copy_source {
+                    source_color
+                } else {
+                    continue;
+                };
+
+                self.set_pixel32((dest_x + x) as i32, (dest_y + y) as i32, dest_color);
+            }
+        }
+
+        modified_count
+    }
+
+    /// Remaps the channels of each pixel copied from `source_bitmap` through the given palettes,
+    /// in the order red, green, blue, alpha. The resulting pixel is the sum of the four values.
+    pub fn palette_map(
+        &mut self,
+        source_bitmap: &Self,
+        src_rect: (i32, i32, i32, i32),
+        dest_point: (i32, i32),
+        palettes: &[[u32; 256]; 4],
+    ) {
+        let ((src_x, src_y), (dest_x, dest_y), (width, height)) =
+            self.clip_copy_rect(source_bitmap, src_rect, dest_point);
+
+        for y in 0..height {
+            for x in 0..width {
+                let source_color =
+                    source_bitmap.get_pixel32((src_x + x) as i32, (src_y + y) as i32);
+                let dest_color = palettes[0][usize::from(source_color.red())]
+                    .wrapping_add(palettes[1][usize::from(source_color.green())])
+                    .wrapping_add(palettes[2][usize::from(source_color.blue())])
+                    .wrapping_add(palettes[3][usize::from(source_color.alpha())]);
+
+                self.set_pixel32(
+                    (dest_x + x) as i32,
+                    (dest_y + y) as i32,
+                    (dest_color as i32).into(),
+                );
+            }
+        }
+    }
+
+    /// Blends each pixel copied from `source_bitmap` with the existing pixel, using the given
+    /// multipliers out of 256 for the red, green, blue and alpha channels of the source.
+    pub fn merge(
+        &mut self,
+        source_bitmap: &Self,
+        src_rect: (i32, i32, i32, i32),
+        dest_point: (i32, i32),
+        multipliers: (i32, i32, i32, i32),
+    ) {
+        let ((src_x, src_y), (dest_x, dest_y), (width, height)) =
+            self.clip_copy_rect(source_bitmap, src_rect, dest_point);
+        let clamp = |multiplier: i32| multiplier.max(0).min(256) as u32;
+        let (red_mult, green_mult, blue_mult, alpha_mult) = (
+            clamp(multipliers.0),
+            clamp(multipliers.1),
+            clamp(multipliers.2),
+            clamp(multipliers.3),
+        );
+        let blend = |source: u8, dest: u8, multiplier: u32| {
+            ((u32::from(source) * multiplier + u32::from(dest) * (256 - multiplier)) / 256) as u8
+        };
+
+        for y in 0..height {
+            for x in 0..width {
+                let (x_dest, y_dest) = ((dest_x + x) as i32, (dest_y + y) as i32);
+                let source_color =
+                    source_bitmap.get_pixel32((src_x + x) as i32, (src_y + y) as i32);
+                let dest_color = self.get_pixel32(x_dest, y_dest);
+
+                let merged = Color::argb(
+                    blend(source_color.alpha(), dest_color.alpha(), alpha_mult),
+                    blend(source_color.red(), dest_color.red(), red_mult),
+                    blend(source_color.green(), dest_color.green(), green_mult),
+                    blend(source_color.blue(), dest_color.blue(), blue_mult),
+                );
+                self.set_pixel32(x_dest, y_dest, merged);
+            }
+        }
+    }
+
+    /// Replaces `num_pixels` pixels in a pseudo-random order with those copied from
+    /// `source_bitmap`, or with `fill_color` if there is no source.
+    /// Returns the seed to pass to the next call to continue the dissolve.
+    ///
+    /// The pixels are visited in the order of a Galois LFSR whose state holds the column in
+    /// its low bits and the row in its high bits, with each dimension rounded up to a power
+    /// of two. States outside of the area are skipped, so every pixel is visited exactly
+    /// once before the order repeats and repeated calls eventually replace the whole area.
+    #[allow(clippy::too_many_arguments)]
+    pub fn pixel_dissolve(
+        &mut self,
+        source_bitmap: Option<&Self>,
+        src_rect: (i32, i32, i32, i32),
+        dest_point: (i32, i32),
+        random_seed: i32,
+        num_pixels: i32,
+        fill_color: Color,
+    ) -> i32 {
+        let ((src_x, src_y), (dest_x, dest_y), (width, height)) =
+            self.clip_copy_rect(source_bitmap.unwrap_or(self), src_rect, dest_point);
+        let area = width * height;
+        if area == 0 {
+            return random_seed;
+        }
+
+        // An LFSR never reaches a state of 0, so each state is one more than the position
+        // it visits. The register needs enough bits to reach the last row of the area.
+        let column_bits = 32 - (width - 1).leading_zeros();
+        let last_position = ((height - 1) << column_bits) | (width - 1);
+        let bits = (2..LFSR_TAPS.len())
+            .find(|bits| (1u64 << bits) - 1 > u64::from(last_position))
+            .unwrap_or(LFSR_TAPS.len() - 1);
+        let taps = LFSR_TAPS[bits];
+        let period = (1u64 << bits) - 1;
+        let mut state = (u64::from(random_seed as u32) % period) as u32 + 1;
+
+        let mut remaining = num_pixels.max(0).min(area as i32);
+        while remaining > 0 {
+            let position = state - 1;
+            let (x, y) = (position & ((1 << column_bits) - 1), position >> column_bits);
+            if x < width && y < height {
+                let color = if let Some(source_bitmap) = source_bitmap {
+                    source_bitmap.get_pixel32((src_x + x) as i32, (src_y + y) as i32)
+                } else {
+                    fill_color
+                };
+                self.set_pixel32((dest_x + x) as i32, (dest_y + y) as i32, color);
+                remaining -= 1;
+            }
+
+            state = if state & 1 != 0 {
+                (state >> 1) ^ taps
+            } else {
+                state >> 1
+            };
+        }
+
+        (state - 1) as i32
+    }
+
+    /// Whether the pixel at the given position has an alpha of at least `alpha_threshold`.
+    fn is_pixel_opaque(&self, x: i32, y: i32, alpha_threshold: u8) -> bool {
+        self.is_point_in_bounds(x, y)
+            && self
+                .get_pixel_raw(x as u32, y as u32)
+                .map(|color| color.alpha() >= alpha_threshold)
+                .unwrap_or(false)
+    }
+
+    /// Whether the given point, relative to the top-left corner of the bitmap at `position`,
+    /// hits a pixel with an alpha of at least `alpha_threshold`.
+    pub fn hit_test_point(
+        &self,
+        position: (i32, i32),
+        alpha_threshold: u8,
+        point: (i32, i32),
+    ) -> bool {
+        self.is_pixel_opaque(point.0 - position.0, point.1 - position.1, alpha_threshold)
+    }
+
+    /// Whether the given rectangle, relative to the top-left corner of the bitmap at `position`,
+    /// contains any pixel with an alpha of at least `alpha_threshold`.
+    pub fn hit_test_rectangle(
+        &self,
+        position: (i32, i32),
+        alpha_threshold: u8,
+        rect: (i32, i32, i32, i32),
+    ) -> bool {
+        let (x, y, width, height) = rect;
+        let x_min = (x - position.0).max(0);
+        let y_min = (y - position.1).max(0);
+        let x_max = (x - position.0 + width).min(self.width() as i32);
+        let y_max = (y - position.1 + height).min(self.height() as i32);
+
+        (y_min..y_max).any(|y| (x_min..x_max).any(|x| self.is_pixel_opaque(x, y, alpha_threshold)))
+    }
+
+    /// Whether this bitmap at `position` overlaps `other` at `other_position` with pixels that
+    /// have an alpha of at least the respective thresholds.
+    pub fn hit_test_bitmap(
+        &self,
+        position: (i32, i32),
+        alpha_threshold: u8,
+        other: &Self,
+        other_position: (i32, i32),
+        other_alpha_threshold: u8,
+    ) -> bool {
+        let x_min = position.0.max(other_position.0);
+        let y_min = position.1.max(other_position.1);
+        let x_max = (position.0 + self.width() as i32).min(other_position.0 + other.width() as i32);
+        let y_max =
+            (position.1 + self.height() as i32).min(other_position.1 + other.height() as i32);
+
+        (y_min..y_max).any(|y| {
+            (x_min..x_max).any(|x| {
+                self.is_pixel_opaque(x - position.0, y - position.1, alpha_threshold)
+                    && other.is_pixel_opaque(
+                        x - other_position.0,
+                        y - other_position.1,
+                        other_alpha_threshold,
+                    )
+            })
+        })
+    }
+
+    /// Writes a premultiplied pixel produced by a filter, dropping its alpha if this bitmap is
+    /// not transparent.
+    fn set_filtered_pixel(&mut self, x: u32, y: u32, color: Color) {
+        let color = if self.transparency {
+            color
+        } else {
+            color.to_un_multiplied_alpha().with_alpha(255)
+        };
+        self.set_pixel32_raw(x, y, color);
+    }
+
+    /// Applies `filter` to the pixels copied from `source_bitmap`, replacing the existing pixels.
+    pub fn apply_filter(
+        &mut self,
+        source_bitmap: &Self,
+        src_rect: (i32, i32, i32, i32),
+        dest_point: (i32, i32),
+        filter: &Filter,
+    ) {
+        let ((src_x, src_y), (dest_x, dest_y), (width, height)) =
+            self.clip_copy_rect(source_bitmap, src_rect, dest_point);
+
+        let mut rgba = Vec::with_capacity((width * height * 4) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let color = source_bitmap
+                    .get_pixel_raw(src_x + x, src_y + y)
+                    .unwrap_or_else(|| 0.into());
+                rgba.extend_from_slice(&[color.red(), color.green(), color.blue(), color.alpha()]);
+            }
+        }
+
+        filters::apply_filter(width, height, &mut rgba, filter);
+
+        for (i, pixel) in rgba.chunks_exact(4).enumerate() {
+            let (x, y) = (i as u32 % width, i as u32 / width);
+            let color = Color::argb(pixel[3], pixel[0], pixel[1], pixel[2]);
+            self.set_filtered_pixel(dest_x + x, dest_y + y, color);
+        }
+    }
+
+    /// Displaces the pixels copied from `source_bitmap` by the colors of `map`, as described by
+    /// a `DisplacementMapFilter`, replacing the existing pixels.
+    pub fn apply_displacement_map(
+        &mut self,
+        source_bitmap: &Self,
+        src_rect: (i32, i32, i32, i32),
+        dest_point: (i32, i32),
+        filter: DisplacementMapFilterObject<'_>,
+        map: &Self,
+    ) {
+        let ((src_x, src_y), (dest_x, dest_y), (width, height)) =
+            self.clip_copy_rect(source_bitmap, src_rect, dest_point);
+        let (width, height) = (width as i32, height as i32);
+        let (map_x, map_y) = filter.map_point();
+        let mode = filter.mode();
+        let outside_color = Color::from(filter.color() as i32)
+            .with_alpha((filter.alpha().max(0.0).min(1.0) * 255.0) as u8)
+            .to_premultiplied_alpha(true);
+
+        let component = |color: Color, channel: i32| match channel {
+            1 => color.red(),
+            2 => color.green(),
+            4 => color.blue(),
+            8 => color.alpha(),
+            _ => 128,
+        };
+        let offset = |value: u8, scale: f64| ((f64::from(value) - 128.0) * scale / 256.0).floor();
+
+        for y in 0..height {
+            for x in 0..width {
+                // Pixels outside of the map are not displaced.
+                let (offset_x, offset_y) = if map.is_point_in_bounds(x - map_x, y - map_y) {
+                    let map_color = map.get_pixel32(x - map_x, y - map_y);
+                    (
+                        offset(component(map_color, filter.component_x()), filter.scale_x()),
+                        offset(component(map_color, filter.component_y()), filter.scale_y()),
+                    )
+                } else {
+                    (0.0, 0.0)
+                };
+
+                let displaced_x = x + offset_x as i32;
+                let displaced_y = y + offset_y as i32;
+                let in_bounds = displaced_x >= 0
+                    && displaced_x < width
+                    && displaced_y >= 0
+                    && displaced_y < height;
+                let source = match mode {
+                    _ if in_bounds => Some((displaced_x, displaced_y)),
+                    DisplacementMapFilterMode::Wrap => Some((
+                        displaced_x.rem_euclid(width),
+                        displaced_y.rem_euclid(height),
+                    )),
+                    DisplacementMapFilterMode::Clamp => Some((
+                        displaced_x.max(0).min(width - 1),
+                        displaced_y.max(0).min(height - 1),
+                    )),
+                    DisplacementMapFilterMode::Ignore => Some((x, y)),
+                    DisplacementMapFilterMode::Color => None,
+                };
+
+                let color = match source {
+                    Some((x, y)) => source_bitmap
+                        .get_pixel_raw(src_x + x as u32, src_y + y as u32)
+                        .unwrap_or_else(|| 0.into()),
+                    None => outside_color,
+                };
+                self.set_filtered_pixel(dest_x + x as u32, dest_y + y as u32, color);
+            }
+        }
+    }
+
     pub fn scroll(&mut self, x: i32, y: i32) {
         let width = self.width() as i32;
         let height = self.height() as i32;
@@ -833,15 +833,18 @@ mod tests {
     use crate::avm1::function::Executable;
     use crate::avm1::globals::system::SystemProperties;
     use crate::avm1::property::Attribute;
-    use crate::avm1::{Avm1, Timers};
+    use crate::avm1::{Avm1, LocalConnections, Timers, XmlSockets};
     use crate::avm2::Avm2;
     use crate::backend::audio::{AudioManager, NullAudioBackend};
+    use crate::backend::local_connection::MemoryLocalConnectionBackend;
     use crate::backend::locale::NullLocaleBackend;
     use crate::backend::log::NullLogBackend;
     use crate::backend::navigator::NullNavigatorBackend;
     use crate::backend::render::NullRenderer;
+    use crate::backend::socket::NullSocketBackend;
     use crate::backend::storage::MemoryStorageBackend;
     use crate::backend::ui::NullUiBackend;
+    use crate::backend::video::NullVideoBackend;
     use crate::context::UpdateContext;
     use crate::display_object::MovieClip;
     use crate::focus_tracker::FocusTracker;
@@ -887,6 +890,8 @@ mod tests {
                 background_color: &mut None,
                 library: &mut Library::empty(gc_context),
                 navigator: &mut NullNavigatorBackend::new(),
+                sockets: &mut NullSocketBackend::new(),
+                video: &mut NullVideoBackend::new(),
                 renderer: &mut NullRenderer::new(),
                 locale: &mut NullLocaleBackend::new(),
                 log: &mut NullLogBackend::new(),
@@ -894,12 +899,20 @@ mod tests {
                 mouse_position: &(Twips::new(0), Twips::new(0)),
                 drag_object: &mut None,
                 stage_size: (Twips::from_pixels(550.0), Twips::from_pixels(400.0)),
+                viewport_size: (Twips::from_pixels(550.0), Twips::from_pixels(400.0)),
+                scale_mode: &mut Default::default(),
+                stage_align: &mut Default::default(),
+                show_menu: &mut true,
+                current_context_menu: &mut None,
                 player: None,
                 load_manager: &mut LoadManager::new(),
                 system: &mut SystemProperties::default(),
                 instance_counter: &mut 0,
                 storage: &mut MemoryStorageBackend::default(),
+                local_connection: &mut MemoryLocalConnectionBackend::default(),
                 shared_objects: &mut HashMap::new(),
+                local_connections: &mut LocalConnections::new(),
+                xml_sockets: &mut XmlSockets::new(),
                 unbound_text_fields: &mut Vec::new(),
                 timers: &mut Timers::new(),
                 needs_render: &mut false,
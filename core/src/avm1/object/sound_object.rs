@@ -35,12 +35,22 @@ pub struct SoundObjectData<'gc> {
 
     /// Duration of the currently attached sound in milliseconds.
     duration: u32,
+
+    /// The ID3 metadata of a sound loaded by `loadSound`.
+    id3: Option<Object<'gc>>,
+
+    /// Bytes of the sound loaded by `loadSound` so far.
+    bytes_loaded: Option<u32>,
+
+    /// Total bytes of the sound being loaded by `loadSound`.
+    bytes_total: Option<u32>,
 }
 
 unsafe impl<'gc> Collect for SoundObjectData<'gc> {
     fn trace(&self, cc: gc_arena::CollectionContext) {
         self.base.trace(cc);
         self.owner.trace(cc);
+        self.id3.trace(cc);
     }
 }
 
@@ -69,6 +79,9 @@ impl<'gc> SoundObject<'gc> {
                 owner: None,
                 position: 0,
                 duration: 0,
+                id3: None,
+                bytes_loaded: None,
+                bytes_total: None,
             },
         ))
     }
@@ -120,6 +133,30 @@ impl<'gc> SoundObject<'gc> {
     pub fn set_position(self, gc_context: MutationContext<'gc, '_>, position: u32) {
         self.0.write(gc_context).position = position;
     }
+
+    pub fn id3(self) -> Option<Object<'gc>> {
+        self.0.read().id3
+    }
+
+    pub fn set_id3(self, gc_context: MutationContext<'gc, '_>, id3: Option<Object<'gc>>) {
+        self.0.write(gc_context).id3 = id3;
+    }
+
+    pub fn bytes_loaded(self) -> Option<u32> {
+        self.0.read().bytes_loaded
+    }
+
+    pub fn set_bytes_loaded(self, gc_context: MutationContext<'gc, '_>, bytes_loaded: Option<u32>) {
+        self.0.write(gc_context).bytes_loaded = bytes_loaded;
+    }
+
+    pub fn bytes_total(self) -> Option<u32> {
+        self.0.read().bytes_total
+    }
+
+    pub fn set_bytes_total(self, gc_context: MutationContext<'gc, '_>, bytes_total: Option<u32>) {
+        self.0.write(gc_context).bytes_total = bytes_total;
+    }
 }
 
 impl<'gc> TObject<'gc> for SoundObject<'gc> {
@@ -866,19 +866,28 @@ fn set_high_quality<'gc>(
 }
 
 fn focus_rect<'gc>(
-    activation: &mut Activation<'_, 'gc, '_>,
-    _this: DisplayObject<'gc>,
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: DisplayObject<'gc>,
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm_warn!(activation, "Unimplemented property _focusrect");
-    Ok(Value::Null)
+    // The root movie holds the global `_focusrect`, which defaults to `true`.
+    let focus_rect = match this.focus_rect() {
+        Some(focus_rect) => focus_rect.into(),
+        None if this.parent().is_none() => true.into(),
+        None => Value::Null,
+    };
+    Ok(focus_rect)
 }
 
 fn set_focus_rect<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
-    _this: DisplayObject<'gc>,
-    _val: Value<'gc>,
+    this: DisplayObject<'gc>,
+    val: Value<'gc>,
 ) -> Result<(), Error<'gc>> {
-    avm_warn!(activation, "Unimplemented property _focusrect");
+    let focus_rect = match val {
+        Value::Undefined | Value::Null => None,
+        _ => Some(val.as_bool(activation.current_swf_version())),
+    };
+    this.set_focus_rect(activation.context.gc_context, focus_rect);
     Ok(())
 }
 
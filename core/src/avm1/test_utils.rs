@@ -1,15 +1,18 @@
 use crate::avm1::activation::{Activation, ActivationIdentifier};
 use crate::avm1::error::Error;
 use crate::avm1::globals::system::SystemProperties;
-use crate::avm1::{Avm1, Object, Timers, UpdateContext};
+use crate::avm1::{Avm1, LocalConnections, Object, Timers, UpdateContext, XmlSockets};
 use crate::avm2::Avm2;
 use crate::backend::audio::{AudioManager, NullAudioBackend};
+use crate::backend::local_connection::MemoryLocalConnectionBackend;
 use crate::backend::locale::NullLocaleBackend;
 use crate::backend::log::NullLogBackend;
 use crate::backend::navigator::NullNavigatorBackend;
 use crate::backend::render::NullRenderer;
+use crate::backend::socket::NullSocketBackend;
 use crate::backend::storage::MemoryStorageBackend;
 use crate::backend::ui::NullUiBackend;
+use crate::backend::video::NullVideoBackend;
 use crate::context::ActionQueue;
 use crate::display_object::{MovieClip, TDisplayObject};
 use crate::focus_tracker::FocusTracker;
@@ -56,6 +59,8 @@ where
             background_color: &mut None,
             library: &mut Library::empty(gc_context),
             navigator: &mut NullNavigatorBackend::new(),
+            sockets: &mut NullSocketBackend::new(),
+            video: &mut NullVideoBackend::new(),
             renderer: &mut NullRenderer::new(),
             locale: &mut NullLocaleBackend::new(),
             log: &mut NullLogBackend::new(),
@@ -63,12 +68,20 @@ where
             mouse_position: &(Twips::new(0), Twips::new(0)),
             drag_object: &mut None,
             stage_size: (Twips::from_pixels(550.0), Twips::from_pixels(400.0)),
+            viewport_size: (Twips::from_pixels(550.0), Twips::from_pixels(400.0)),
+            scale_mode: &mut Default::default(),
+            stage_align: &mut Default::default(),
+            show_menu: &mut true,
+            current_context_menu: &mut None,
             player: None,
             load_manager: &mut LoadManager::new(),
             system: &mut SystemProperties::default(),
             instance_counter: &mut 0,
             storage: &mut MemoryStorageBackend::default(),
+            local_connection: &mut MemoryLocalConnectionBackend::default(),
             shared_objects: &mut HashMap::new(),
+            local_connections: &mut LocalConnections::new(),
+            xml_sockets: &mut XmlSockets::new(),
             unbound_text_fields: &mut Vec::new(),
             timers: &mut Timers::new(),
             needs_render: &mut false,
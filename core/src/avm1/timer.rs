@@ -4,10 +4,11 @@
 //! The timers are stored in a priority queue, where we check if the nearest timer
 //! is ready to tick each frame.
 //!
-//! TODO: Could we use this for AVM2 timers as well?
+//! AVM2 `flash.utils.Timer` objects are scheduled in the same queue.
 
 use crate::avm1::object::search_prototype;
 use crate::avm1::{Activation, ActivationIdentifier, Object, TObject, Value};
+use crate::avm2::{Avm2, Object as Avm2Object};
 use crate::context::UpdateContext;
 use gc_arena::Collect;
 use std::collections::{binary_heap::PeekMut, BinaryHeap};
@@ -101,6 +102,13 @@ impl<'gc> Timers<'gc> {
                         None
                     }
                 }
+                TimerCallback::Avm2Callback(timer) => {
+                    if let Err(e) = Avm2::run_timer(&mut activation.context, timer) {
+                        log::error!("Unhandled AVM2 exception in timer: {}", e);
+                    }
+
+                    None
+                }
             };
 
             if let Some((this, base_proto, function)) = callback {
@@ -278,4 +286,7 @@ pub enum TimerCallback<'gc> {
         this: Object<'gc>,
         method_name: String,
     },
+
+    /// An AVM2 `Timer` object, which is ticked instead of called.
+    Avm2Callback(Avm2Object<'gc>),
 }
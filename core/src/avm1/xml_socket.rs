@@ -0,0 +1,162 @@
+//! `XMLSocket` connection handling.
+//!
+//! Sockets are provided by the player's `SocketBackend`, which is polled once
+//! per frame. Messages are delimited by null bytes.
+
+use crate::avm1::{Activation, ActivationIdentifier, AvmString, Object, TObject, Value};
+use crate::backend::socket::{SocketEvent, SocketHandle};
+use crate::context::UpdateContext;
+use gc_arena::Collect;
+use std::collections::HashMap;
+
+/// An open socket, and the `XMLSocket` object it belongs to.
+#[derive(Collect)]
+#[collect(no_drop)]
+struct XmlSocket<'gc> {
+    object: Object<'gc>,
+
+    /// Received data that does not yet form a complete message.
+    buffer: Vec<u8>,
+}
+
+/// Manages the open `XMLSocket` objects of a player.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct XmlSockets<'gc> {
+    sockets: HashMap<SocketHandle, XmlSocket<'gc>>,
+}
+
+impl<'gc> XmlSockets<'gc> {
+    pub fn new() -> Self {
+        Self {
+            sockets: HashMap::new(),
+        }
+    }
+
+    /// The socket belonging to an object, if it is open.
+    fn handle(&self, object: Object<'gc>) -> Option<SocketHandle> {
+        self.sockets
+            .iter()
+            .find(|(_, socket)| Object::ptr_eq(socket.object, object))
+            .map(|(handle, _)| *handle)
+    }
+
+    /// Start connecting an object to a host, closing any socket it already
+    /// has open.
+    pub fn connect(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        object: Object<'gc>,
+        host: &str,
+        port: u16,
+    ) {
+        Self::close(context, object);
+
+        let handle = context.sockets.connect(host, port);
+        context.xml_sockets.sockets.insert(
+            handle,
+            XmlSocket {
+                object,
+                buffer: Vec::new(),
+            },
+        );
+    }
+
+    /// Send data on an object's socket, if it is open.
+    pub fn send(context: &mut UpdateContext<'_, 'gc, '_>, object: Object<'gc>, data: &[u8]) {
+        if let Some(handle) = context.xml_sockets.handle(object) {
+            context.sockets.send(handle, data);
+        }
+    }
+
+    /// Close an object's socket, if it is open.
+    pub fn close(context: &mut UpdateContext<'_, 'gc, '_>, object: Object<'gc>) {
+        if let Some(handle) = context.xml_sockets.handle(object) {
+            context.xml_sockets.sockets.remove(&handle);
+            context.sockets.close(handle);
+        }
+    }
+
+    /// Handle everything that happened to open sockets since the last update.
+    ///
+    /// Events stay queued until a movie has been loaded into `_level0`.
+    pub fn update_sockets(context: &mut UpdateContext<'_, 'gc, '_>) {
+        let level0 = if let Some(level0) = context.levels.get(&0).copied() {
+            level0
+        } else {
+            return;
+        };
+
+        let events = context.sockets.poll();
+
+        if events.is_empty() {
+            return;
+        }
+
+        let version = context.swf.header().version;
+        let globals = context.avm1.global_object_cell();
+
+        let mut activation = Activation::from_nothing(
+            context.reborrow(),
+            ActivationIdentifier::root("[XMLSocket]"),
+            version,
+            globals,
+            level0,
+        );
+
+        for (handle, event) in events {
+            let sockets = &mut activation.context.xml_sockets.sockets;
+            let object = match sockets.get(&handle) {
+                Some(socket) => socket.object,
+                // The socket was closed by the movie.
+                None => continue,
+            };
+
+            match event {
+                SocketEvent::Connect(connected) => {
+                    if !connected {
+                        sockets.remove(&handle);
+                    }
+
+                    Self::call_handler(&mut activation, object, "onConnect", &[connected.into()]);
+                }
+                SocketEvent::Data(data) => {
+                    let mut messages = Vec::new();
+                    if let Some(socket) = sockets.get_mut(&handle) {
+                        socket.buffer.extend(data);
+                        while let Some(end) = socket.buffer.iter().position(|b| *b == 0) {
+                            let message: Vec<u8> = socket.buffer.drain(..=end).collect();
+                            messages.push(String::from_utf8_lossy(&message[..end]).into_owned());
+                        }
+                    }
+
+                    for message in messages {
+                        let message = AvmString::new(activation.context.gc_context, message);
+                        Self::call_handler(&mut activation, object, "onData", &[message.into()]);
+                    }
+                }
+                SocketEvent::Close => {
+                    sockets.remove(&handle);
+                    Self::call_handler(&mut activation, object, "onClose", &[]);
+                }
+            }
+        }
+    }
+
+    /// Call an event handler, if the object defines it.
+    fn call_handler(
+        activation: &mut Activation<'_, 'gc, '_>,
+        object: Object<'gc>,
+        name: &str,
+        args: &[Value<'gc>],
+    ) {
+        if let Ok(Value::Object(_)) = object.get(name, activation) {
+            let _ = object.call_method(name, args, activation);
+        }
+    }
+}
+
+impl Default for XmlSockets<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
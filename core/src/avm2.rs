@@ -6,6 +6,7 @@ use crate::avm2::script::{Script, TranslationUnit};
 use crate::context::UpdateContext;
 use crate::tag_utils::SwfSlice;
 use gc_arena::{Collect, MutationContext};
+use std::fmt;
 use std::rc::Rc;
 use swf::avm2::read::Reader;
 
@@ -20,6 +21,7 @@ macro_rules! avm_debug {
 
 mod activation;
 mod array;
+mod bytearray;
 mod class;
 mod domain;
 mod events;
@@ -37,6 +39,7 @@ mod slot;
 mod string;
 mod traits;
 mod value;
+mod vector;
 
 pub use crate::avm2::activation::Activation;
 pub use crate::avm2::domain::Domain;
@@ -50,6 +53,22 @@ pub use crate::avm2::value::Value;
 /// with a proper Avm2Error enum.
 pub type Error = Box<dyn std::error::Error>;
 
+/// An error signalling that an ActionScript value has been thrown.
+///
+/// Boxed errors cannot hold garbage-collected values, so the thrown value
+/// itself is held by the interpreter until a handler takes it. See
+/// `Avm2::throw` and `Avm2::take_exception`.
+#[derive(Debug)]
+pub struct ThrownValue;
+
+impl fmt::Display for ThrownValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "An ActionScript value was thrown")
+    }
+}
+
+impl std::error::Error for ThrownValue {}
+
 /// The state of an AVM2 interpreter.
 #[derive(Collect)]
 #[collect(no_drop)]
@@ -63,6 +82,10 @@ pub struct Avm2<'gc> {
     /// System prototypes.
     system_prototypes: Option<SystemPrototypes<'gc>>,
 
+    /// The value most recently thrown by ActionScript code, if it has not
+    /// been caught yet.
+    exception: Option<Value<'gc>>,
+
     #[cfg(feature = "avm_debug")]
     pub debug_output: bool,
 }
@@ -76,6 +99,7 @@ impl<'gc> Avm2<'gc> {
             stack: Vec::new(),
             globals,
             system_prototypes: None,
+            exception: None,
 
             #[cfg(feature = "avm_debug")]
             debug_output: false,
@@ -105,10 +129,13 @@ impl<'gc> Avm2<'gc> {
         let (method, scope) = script.init();
         match method {
             Method::Native(nf) => {
-                nf(&mut init_activation, Some(scope), &[])?;
+                nf(&mut init_activation, Some(scope), &[])
+                    .map_err(|e| Self::uncaught_error(&mut init_activation, e))?;
             }
             Method::Entry(_) => {
-                init_activation.run_stack_frame_for_script(script)?;
+                init_activation
+                    .run_stack_frame_for_script(script)
+                    .map_err(|e| Self::uncaught_error(&mut init_activation, e))?;
             }
         };
 
@@ -122,16 +149,86 @@ impl<'gc> Avm2<'gc> {
         context: &mut UpdateContext<'_, 'gc, '_>,
     ) -> Result<(), Error> {
         let mut evt_activation = Activation::from_nothing(context.reborrow());
-        callable.call(
-            reciever,
-            args,
-            &mut evt_activation,
-            reciever.and_then(|r| r.proto()),
-        )?;
+        callable
+            .call(
+                reciever,
+                args,
+                &mut evt_activation,
+                reciever.and_then(|r| r.proto()),
+            )
+            .map_err(|e| Self::uncaught_error(&mut evt_activation, e))?;
+
+        Ok(())
+    }
+
+    /// Run a single tick of a `flash.utils.Timer` object.
+    pub fn run_timer(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        timer: Object<'gc>,
+    ) -> Result<(), Error> {
+        let mut timer_activation = Activation::from_nothing(context.reborrow());
+        globals::flash::utils::timer::tick(&mut timer_activation, timer)
+            .map_err(|e| Self::uncaught_error(&mut timer_activation, e))?;
+
+        Ok(())
+    }
+
+    /// Dispatch a `flash.events.TextEvent` carrying some text to an object.
+    pub fn dispatch_text_event(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: Object<'gc>,
+        event_type: &'static str,
+        text: &str,
+    ) -> Result<(), Error> {
+        let mut activation = Activation::from_nothing(context.reborrow());
+        globals::flash::events::textevent::text_event(&mut activation, event_type, text)
+            .and_then(|event| {
+                globals::flash::events::eventdispatcher::dispatch_event(
+                    &mut activation,
+                    Some(target),
+                    &[event.into()],
+                )
+            })
+            .map_err(|e| Self::uncaught_error(&mut activation, e))?;
 
         Ok(())
     }
 
+    /// Describe an error that escaped ActionScript code entirely.
+    ///
+    /// Uncaught thrown values are replaced with an error carrying their
+    /// string representation, so that they can be reported after the
+    /// interpreter has moved on. All other errors are returned unchanged.
+    pub fn uncaught_error(activation: &mut Activation<'_, 'gc, '_>, error: Error) -> Error {
+        let value = match activation.avm2().take_exception(&error) {
+            Some(value) => value,
+            None => return error,
+        };
+
+        match value.coerce_to_string(activation) {
+            Ok(description) => description.to_string().into(),
+            Err(e) => format!("Uncaught exception that could not be described: {}", e).into(),
+        }
+    }
+
+    /// Throw an ActionScript value.
+    ///
+    /// The returned error should be propagated until a handler catches it.
+    pub fn throw(&mut self, value: Value<'gc>) -> Error {
+        self.exception = Some(value);
+
+        Box::new(ThrownValue)
+    }
+
+    /// Take the value thrown by ActionScript code, if `error` signals one.
+    pub fn take_exception(&mut self, error: &Error) -> Option<Value<'gc>> {
+        if error.is::<ThrownValue>() {
+            self.exception.take()
+        } else {
+            None
+        }
+    }
+
     /// Load an ABC file embedded in a `SwfSlice`.
     ///
     /// The `SwfSlice` must resolve to the contents of an ABC file.
@@ -2,23 +2,28 @@
 
 use crate::avm2::array::ArrayStorage;
 use crate::avm2::class::Class;
+use crate::avm2::domain::Domain;
+use crate::avm2::globals::error::error_from_native;
 use crate::avm2::method::BytecodeMethod;
 use crate::avm2::method::Method;
 use crate::avm2::names::{Multiname, Namespace, QName};
-use crate::avm2::object::{ArrayObject, FunctionObject, NamespaceObject, ScriptObject};
+use crate::avm2::object::{
+    ArrayObject, DictionaryObject, FunctionObject, NamespaceObject, ScriptObject, XmlObject,
+};
 use crate::avm2::object::{Object, TObject};
 use crate::avm2::scope::Scope;
 use crate::avm2::script::Script;
 use crate::avm2::string::AvmString;
 use crate::avm2::value::Value;
-use crate::avm2::{value, Avm2, Error};
+use crate::avm2::{value, Avm2, Error, ThrownValue};
 use crate::context::UpdateContext;
 use gc_arena::{Collect, Gc, GcCell, MutationContext};
 use smallvec::SmallVec;
+use std::convert::TryFrom;
 use std::io::Cursor;
 use swf::avm2::read::Reader;
 use swf::avm2::types::{
-    Class as AbcClass, Index, Method as AbcMethod, Multiname as AbcMultiname,
+    Class as AbcClass, Exception, Index, Method as AbcMethod, Multiname as AbcMultiname,
     Namespace as AbcNamespace, Op,
 };
 
@@ -102,6 +107,11 @@ pub struct Activation<'a, 'gc: 'a, 'gc_context: 'a> {
     /// This will not be available if this is not a method call.
     base_proto: Option<Object<'gc>>,
 
+    /// The default XML namespace, as set by the `dxns` family of ops.
+    ///
+    /// A value of `None` indicates that the method has not set one.
+    default_xml_namespace: Option<Namespace<'gc>>,
+
     pub context: UpdateContext<'a, 'gc, 'gc_context>,
 }
 
@@ -126,6 +136,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             local_scope: ScriptObject::bare_object(context.gc_context),
             scope: None,
             base_proto: None,
+            default_xml_namespace: None,
             context,
         }
     }
@@ -165,6 +176,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             local_scope: ScriptObject::bare_object(context.gc_context),
             scope,
             base_proto: None,
+            default_xml_namespace: None,
             context,
         })
     }
@@ -213,6 +225,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             local_scope: ScriptObject::bare_object(context.gc_context),
             scope,
             base_proto,
+            default_xml_namespace: None,
             context,
         };
 
@@ -286,6 +299,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             local_scope: ScriptObject::bare_object(context.gc_context),
             scope,
             base_proto,
+            default_xml_namespace: None,
             context,
         })
     }
@@ -398,6 +412,21 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         self.context.avm2
     }
 
+    /// Get the domain that the currently executing code was loaded into.
+    ///
+    /// This is taken from the global object at the bottom of the scope stack.
+    /// Activations without a scope use the player's global domain.
+    pub fn domain(&self) -> Domain<'gc> {
+        self.scope
+            .and_then(|scope| scope.read().globals().as_application_domain())
+            .unwrap_or_else(|| self.context.avm2.global_domain())
+    }
+
+    /// Get the default XML namespace set by this activation, if any.
+    pub fn default_xml_namespace(&self) -> Option<&Namespace<'gc>> {
+        self.default_xml_namespace.as_ref()
+    }
+
     /// Set the return value.
     pub fn set_return_value(&mut self, value: Value<'gc>) {
         self.return_value = Some(value);
@@ -476,6 +505,177 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         Multiname::from_abc_multiname_static(method.translation_unit(), index, mc)
     }
 
+    /// Pop the dictionary and object key of a late-bound property access.
+    ///
+    /// Dictionaries compare object keys by identity, so such keys must not be
+    /// coerced to a string as they would be when resolving the multiname. If
+    /// the top of the stack is not a dictionary and an object key accessed by
+    /// the late-bound multiname at `index`, the stack is left untouched.
+    fn pop_dictionary_key(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        index: Index<AbcMultiname>,
+    ) -> Option<(DictionaryObject<'gc>, Object<'gc>)> {
+        let abc = method.translation_unit().abc();
+        let multiname = abc
+            .constant_pool
+            .multinames
+            .get((index.0 as usize).checked_sub(1)?)?;
+        if !matches!(
+            multiname,
+            AbcMultiname::MultinameL { .. } | AbcMultiname::MultinameLA { .. }
+        ) {
+            return None;
+        }
+
+        let stack = &self.context.avm2.stack;
+        let key = match stack.last()? {
+            Value::Object(key) => *key,
+            _ => return None,
+        };
+        let dictionary = match stack.get(stack.len().checked_sub(2)?)? {
+            Value::Object(object) => object.as_dictionary_object()?,
+            _ => return None,
+        };
+
+        self.context.avm2.pop();
+        self.context.avm2.pop();
+
+        Some((dictionary, key))
+    }
+
+    /// Resolve a class name from the current constant pool into its class
+    /// object.
+    ///
+    /// Class names are looked up in the domain that the current method was
+    /// loaded into.
+    fn resolve_class(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        index: Index<AbcMultiname>,
+    ) -> Result<Object<'gc>, Error> {
+        let name = self.pool_multiname_static(method, index, self.context.gc_context)?;
+        let domain = method.translation_unit().domain();
+
+        self.resolve_type(domain, &name)
+    }
+
+    /// Resolve a type name to its class object.
+    ///
+    /// Parameterized type names, such as `Vector.<int>`, resolve their
+    /// parameters and apply them to the base type.
+    fn resolve_type(
+        &mut self,
+        domain: Domain<'gc>,
+        name: &Multiname<'gc>,
+    ) -> Result<Object<'gc>, Error> {
+        let definition: Result<_, Error> = domain.get_defining_script(name)?.ok_or_else(|| {
+            format!(
+                "ReferenceError: Error #1065: Variable {} is not defined.",
+                name.local_name().unwrap_or_else(|| "*".into())
+            )
+            .into()
+        });
+        let (qname, mut script) = definition?;
+        let mut globals = script.globals(&mut self.context)?;
+
+        let class = globals
+            .get_property(globals, &qname, self)?
+            .coerce_to_object(self)?;
+
+        if name.params().is_empty() {
+            return Ok(class);
+        }
+
+        let mut params = Vec::with_capacity(name.params().len());
+        for param in name.params() {
+            if param.is_any() {
+                params.push(Value::Null);
+            } else {
+                params.push(self.resolve_type(domain, param)?.into());
+            }
+        }
+
+        class.apply(self, &params)
+    }
+
+    /// Determine if a value is a member of a given class.
+    ///
+    /// Numbers are members of every numeric class that can represent them
+    /// exactly, regardless of how they are stored.
+    fn is_of_type(&mut self, value: &Value<'gc>, mut class: Object<'gc>) -> Result<bool, Error> {
+        let proto = class
+            .get_property(class, &QName::new(Namespace::public(), "prototype"), self)?
+            .coerce_to_object(self)?;
+
+        if let Value::Number(_) | Value::Integer(_) | Value::Unsigned(_) = value {
+            let number = value.coerce_to_number(self)?;
+            let prototypes = self.avm2().prototypes();
+
+            if Object::ptr_eq(proto, prototypes.number) {
+                return Ok(true);
+            } else if Object::ptr_eq(proto, prototypes.int) {
+                return Ok(number.fract() == 0.0
+                    && number >= i32::MIN as f64
+                    && number <= i32::MAX as f64);
+            } else if Object::ptr_eq(proto, prototypes.uint) {
+                return Ok(number.fract() == 0.0 && number >= 0.0 && number <= u32::MAX as f64);
+            }
+        }
+
+        match value {
+            Value::Undefined | Value::Null => Ok(false),
+            _ => value
+                .coerce_to_object(self)?
+                .has_prototype_in_chain(proto, true),
+        }
+    }
+
+    /// Coerce a value to a given class.
+    ///
+    /// Primitive classes convert the value to the primitive type; all other
+    /// classes accept `null`, `undefined`, or their own members.
+    pub fn coerce_to_type(
+        &mut self,
+        value: Value<'gc>,
+        mut class: Object<'gc>,
+    ) -> Result<Value<'gc>, Error> {
+        let proto = class
+            .get_property(class, &QName::new(Namespace::public(), "prototype"), self)?
+            .coerce_to_object(self)?;
+        let prototypes = self.avm2().prototypes().clone();
+
+        if Object::ptr_eq(proto, prototypes.int) {
+            return Ok(value.coerce_to_i32(self)?.into());
+        } else if Object::ptr_eq(proto, prototypes.uint) {
+            return Ok(value.coerce_to_u32(self)?.into());
+        } else if Object::ptr_eq(proto, prototypes.number) {
+            return Ok(value.coerce_to_number(self)?.into());
+        } else if Object::ptr_eq(proto, prototypes.boolean) {
+            return Ok(value.coerce_to_boolean().into());
+        } else if Object::ptr_eq(proto, prototypes.string) {
+            return Ok(match value {
+                Value::Undefined | Value::Null => Value::Null,
+                _ => value.coerce_to_string(self)?.into(),
+            });
+        }
+
+        if let Value::Undefined | Value::Null = value {
+            return Ok(Value::Null);
+        }
+
+        if self.is_of_type(&value, class)? {
+            return Ok(value);
+        }
+
+        Err(format!(
+            "TypeError: Error #1034: Type Coercion failed: cannot convert {} to {}.",
+            type_name_of(&value),
+            type_name_of(&class.into())
+        )
+        .into())
+    }
+
     /// Retrieve a method entry from the current ABC file's method table.
     fn table_method(
         &mut self,
@@ -507,21 +707,113 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             .ok_or_else(|| "Cannot execute non-native method without body".into());
         let mut read = Reader::new(Cursor::new(body?.code.as_ref()));
 
+        // Exception handlers start with an empty operand stack and the scope
+        // stack this method was entered with.
+        let stack_depth = self.context.avm2.stack.len();
+        let scope = self.scope;
+
         loop {
-            let result = self.do_next_opcode(method, &mut read);
+            let op_offset = read.seek(0)?;
+            let result = self.do_next_opcode(method, &mut read, op_offset);
             match result {
                 Ok(FrameControl::Return(value)) => break Ok(value),
                 Ok(FrameControl::Continue) => {}
-                Err(e) => break Err(e),
+                Err(e) => {
+                    let (target_offset, value) =
+                        self.find_exception_handler(method, op_offset, e)?;
+
+                    self.context.avm2.stack.truncate(stack_depth);
+                    self.context.avm2.push(value);
+                    self.scope = scope;
+
+                    let current_offset = read.seek(0)?;
+                    read.seek(target_offset as i64 - current_offset as i64)?;
+                }
+            }
+        }
+    }
+
+    /// Find the exception handler for an error raised by the op at a given
+    /// offset.
+    ///
+    /// Handlers are searched in the order the method body lists them. This
+    /// yields the offset of the first matching handler and the value it
+    /// catches. If no handler matches, the error is returned so that it can
+    /// propagate to the caller.
+    fn find_exception_handler(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        op_offset: u64,
+        error: Error,
+    ) -> Result<(u32, Value<'gc>), Error> {
+        let body = match method.body() {
+            Some(body) => body,
+            None => return Err(error),
+        };
+        let is_covered = |handler: &Exception| {
+            u64::from(handler.from_offset) <= op_offset && op_offset < u64::from(handler.to_offset)
+        };
+
+        if !body.exceptions.iter().any(is_covered) {
+            return Err(error);
+        }
+
+        let value = match self.context.avm2.take_exception(&error) {
+            Some(value) => value,
+            None => match error_from_native(self, &error) {
+                Ok(Some(error_object)) => error_object.into(),
+                Ok(None) | Err(_) => return Err(error),
+            },
+        };
+
+        for handler in body.exceptions.iter().filter(|h| is_covered(h)) {
+            if self.exception_matches(method, handler, value.clone()) {
+                return Ok((handler.target_offset, value));
+            }
+        }
+
+        Err(self.context.avm2.throw(value))
+    }
+
+    /// Determine if a thrown value can be caught by a given handler.
+    ///
+    /// A handler whose type can't be looked up doesn't match, so that the
+    /// original value keeps propagating rather than the lookup error.
+    fn exception_matches(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        handler: &Exception,
+        value: Value<'gc>,
+    ) -> bool {
+        // A handler without a type, such as a `finally` block, catches
+        // everything.
+        if handler.type_name.0 == 0 {
+            return true;
+        }
+
+        let matches = match self.resolve_class(method, handler.type_name.clone()) {
+            Ok(class) => self.is_of_type(&value, class),
+            Err(e) => Err(e),
+        };
+        match matches {
+            Ok(matches) => matches,
+            Err(e) => {
+                // Discard any value thrown by the lookup itself.
+                self.context.avm2.take_exception(&e);
+                log::warn!("Couldn't check the type of an exception handler: {}", e);
+                false
             }
         }
     }
 
     /// Run a single action from a given action reader.
+    ///
+    /// `op_offset` is the position of the action within the method body.
     fn do_next_opcode(
         &mut self,
         method: Gc<'gc, BytecodeMethod<'gc>>,
         reader: &mut Reader<Cursor<&[u8]>>,
+        op_offset: u64,
     ) -> Result<FrameControl<'gc>, Error> {
         if self.context.update_start.elapsed() >= self.context.max_execution_duration {
             return Err(
@@ -599,6 +891,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                 Op::NewFunction { index } => self.op_new_function(method, index),
                 Op::NewClass { index } => self.op_new_class(method, index),
                 Op::NewArray { num_args } => self.op_new_array(num_args),
+                Op::Coerce { index } => self.op_coerce(method, index),
                 Op::CoerceA => self.op_coerce_a(),
                 Op::CoerceS => self.op_coerce_s(),
                 Op::ConvertB => self.op_convert_b(),
@@ -609,6 +902,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                 Op::ConvertS => self.op_convert_s(),
                 Op::Add => self.op_add(),
                 Op::AddI => self.op_add_i(),
+                Op::ApplyType { num_types } => self.op_apply_type(num_types),
                 Op::BitAnd => self.op_bitand(),
                 Op::BitNot => self.op_bitnot(),
                 Op::BitOr => self.op_bitor(),
@@ -648,6 +942,10 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                 Op::IfNgt { offset } => self.op_if_ngt(offset, reader),
                 Op::IfNle { offset } => self.op_if_nle(offset, reader),
                 Op::IfNlt { offset } => self.op_if_nlt(offset, reader),
+                Op::LookupSwitch {
+                    default_offset,
+                    case_offsets,
+                } => self.op_lookup_switch(default_offset, &case_offsets, op_offset, reader),
                 Op::StrictEquals => self.op_strict_equals(),
                 Op::Equals => self.op_equals(),
                 Op::GreaterEquals => self.op_greater_equals(),
@@ -664,8 +962,20 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
                 Op::NextValue => self.op_next_value(),
                 Op::IsType { index } => self.op_is_type(method, index),
                 Op::IsTypeLate => self.op_is_type_late(),
+                Op::AsType { type_name } => self.op_as_type(method, type_name),
+                Op::AsTypeLate => self.op_as_type_late(),
                 Op::InstanceOf => self.op_instance_of(),
+                Op::TypeOf => self.op_type_of(),
+                Op::Throw => self.op_throw(),
+                Op::NewCatch { index } => self.op_new_catch(method, index),
                 Op::Label => Ok(FrameControl::Continue),
+                Op::Nop => Ok(FrameControl::Continue),
+                Op::Dxns { index } => self.op_dxns(method, index),
+                Op::DxnsLate => self.op_dxns_late(),
+                Op::EscXElem => self.op_esc_xelem(),
+                Op::EscXAttr => self.op_esc_xattr(),
+                Op::GetDescendants { index } => self.op_get_descendants(method, index),
+                Op::CheckFilter => self.op_check_filter(),
                 Op::Debug {
                     is_local_register,
                     register_name,
@@ -677,7 +987,10 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
             };
 
             if let Err(e) = result {
-                log::error!("AVM2 error: {}", e);
+                // Thrown values are reported if they go uncaught.
+                if !e.is::<ThrownValue>() {
+                    log::error!("AVM2 error: {}", e);
+                }
                 return Err(e);
             }
             result
@@ -1025,6 +1338,15 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         method: Gc<'gc, BytecodeMethod<'gc>>,
         index: Index<AbcMultiname>,
     ) -> Result<FrameControl<'gc>, Error> {
+        if let Some((dictionary, key)) = self.pop_dictionary_key(method, index) {
+            let value = dictionary
+                .get_property_by_object(key)
+                .unwrap_or(Value::Undefined);
+            self.context.avm2.push(value);
+
+            return Ok(FrameControl::Continue);
+        }
+
         let multiname = self.pool_multiname(method, index)?;
         let mut object = self.context.avm2.pop().coerce_to_object(self)?;
 
@@ -1056,6 +1378,12 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         index: Index<AbcMultiname>,
     ) -> Result<FrameControl<'gc>, Error> {
         let value = self.context.avm2.pop();
+        if let Some((dictionary, key)) = self.pop_dictionary_key(method, index) {
+            dictionary.set_property_by_object(self.context.gc_context, key, value);
+
+            return Ok(FrameControl::Continue);
+        }
+
         let multiname = self.pool_multiname(method, index)?;
         let mut object = self.context.avm2.pop().coerce_to_object(self)?;
 
@@ -1080,6 +1408,12 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         index: Index<AbcMultiname>,
     ) -> Result<FrameControl<'gc>, Error> {
         let value = self.context.avm2.pop();
+        if let Some((dictionary, key)) = self.pop_dictionary_key(method, index) {
+            dictionary.set_property_by_object(self.context.gc_context, key, value);
+
+            return Ok(FrameControl::Continue);
+        }
+
         let multiname = self.pool_multiname(method, index)?;
         let mut object = self.context.avm2.pop().coerce_to_object(self)?;
 
@@ -1103,6 +1437,13 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         method: Gc<'gc, BytecodeMethod<'gc>>,
         index: Index<AbcMultiname>,
     ) -> Result<FrameControl<'gc>, Error> {
+        if let Some((dictionary, key)) = self.pop_dictionary_key(method, index) {
+            dictionary.delete_property_by_object(self.context.gc_context, key);
+            self.context.avm2.push(true);
+
+            return Ok(FrameControl::Continue);
+        }
+
         let multiname = self.pool_multiname(method, index)?;
         let object = self.context.avm2.pop().coerce_to_object(self)?;
 
@@ -1182,7 +1523,17 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
 
     fn op_in(&mut self) -> Result<FrameControl<'gc>, Error> {
         let obj = self.context.avm2.pop().coerce_to_object(self)?;
-        let name = self.context.avm2.pop().coerce_to_string(self)?;
+        let name = self.context.avm2.pop();
+
+        if let (Some(dictionary), Value::Object(key)) = (obj.as_dictionary_object(), &name) {
+            self.context
+                .avm2
+                .push(dictionary.has_property_by_object(*key));
+
+            return Ok(FrameControl::Continue);
+        }
+
+        let name = name.coerce_to_string(self)?;
 
         let qname = QName::new(Namespace::public(), name);
         let has_prop = obj.has_property(&qname)?;
@@ -1409,6 +1760,39 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         Ok(FrameControl::Continue)
     }
 
+    fn op_new_catch(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        index: Index<Exception>,
+    ) -> Result<FrameControl<'gc>, Error> {
+        let body: Result<_, Error> = method
+            .body()
+            .ok_or_else(|| "Cannot create catch scope in method without body".into());
+        let handler: Result<_, Error> = body?
+            .exceptions
+            .get(index.0 as usize)
+            .ok_or_else(|| format!("Exception handler {} does not exist", index.0).into());
+        let variable_name = handler?.variable_name.clone();
+
+        // The catch scope holds the caught value in its first slot.
+        let name = if variable_name.0 == 0 {
+            "".into()
+        } else {
+            self.pool_string(&method, variable_name, self.context.gc_context)?
+        };
+        let mut scope = ScriptObject::bare_object(self.context.gc_context);
+        scope.install_slot(
+            self.context.gc_context,
+            QName::new(Namespace::public(), name),
+            1,
+            Value::Undefined,
+        );
+
+        self.context.avm2.push(scope);
+
+        Ok(FrameControl::Continue)
+    }
+
     fn op_new_object(&mut self, num_args: u32) -> Result<FrameControl<'gc>, Error> {
         let mut object = ScriptObject::object(
             self.context.gc_context,
@@ -1503,6 +1887,20 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         Ok(FrameControl::Continue)
     }
 
+    fn op_coerce(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        index: Index<AbcMultiname>,
+    ) -> Result<FrameControl<'gc>, Error> {
+        let value = self.context.avm2.pop();
+        let class = self.resolve_class(method, index)?;
+        let coerced = self.coerce_to_type(value, class)?;
+
+        self.context.avm2.push(coerced);
+
+        Ok(FrameControl::Continue)
+    }
+
     fn op_coerce_a(&mut self) -> Result<FrameControl<'gc>, Error> {
         Ok(FrameControl::Continue)
     }
@@ -1625,6 +2023,17 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         Ok(FrameControl::Continue)
     }
 
+    fn op_apply_type(&mut self, num_types: u32) -> Result<FrameControl<'gc>, Error> {
+        let params = self.context.avm2.pop_args(num_types);
+        let base = self.context.avm2.pop().coerce_to_object(self)?;
+
+        let applied = base.apply(self, &params)?;
+
+        self.context.avm2.push(applied);
+
+        Ok(FrameControl::Continue)
+    }
+
     fn op_bitand(&mut self) -> Result<FrameControl<'gc>, Error> {
         let value2 = self.context.avm2.pop().coerce_to_i32(self)?;
         let value1 = self.context.avm2.pop().coerce_to_i32(self)?;
@@ -2049,6 +2458,28 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         Ok(FrameControl::Continue)
     }
 
+    fn op_lookup_switch(
+        &mut self,
+        default_offset: i32,
+        case_offsets: &[i32],
+        op_offset: u64,
+        reader: &mut Reader<Cursor<&[u8]>>,
+    ) -> Result<FrameControl<'gc>, Error> {
+        let index = self.context.avm2.pop().coerce_to_i32(self)?;
+        let offset = usize::try_from(index)
+            .ok()
+            .and_then(|index| case_offsets.get(index))
+            .copied()
+            .unwrap_or(default_offset);
+
+        // Unlike other branches, switch offsets are relative to the start of
+        // the switch itself.
+        let current_offset = reader.seek(0)?;
+        reader.seek(op_offset as i64 + offset as i64 - current_offset as i64)?;
+
+        Ok(FrameControl::Continue)
+    }
+
     fn op_strict_equals(&mut self) -> Result<FrameControl<'gc>, Error> {
         let value2 = self.context.avm2.pop();
         let value1 = self.context.avm2.pop();
@@ -2127,7 +2558,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
 
         let next_index = cur_index + 1;
 
-        if object.get_enumerant_name(next_index).is_some() {
+        if object.get_enumerant_key(next_index).is_some() {
             self.context.avm2.push(next_index);
         } else {
             self.context.avm2.push(0.0);
@@ -2142,15 +2573,23 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         index_register: u32,
     ) -> Result<FrameControl<'gc>, Error> {
         let cur_index = self.local_register(index_register)?.coerce_to_u32(self)?;
-        let mut object = Some(
-            self.local_register(object_register)?
-                .coerce_to_object(self)?,
-        );
+        let object = self.local_register(object_register)?;
+
+        // Enumerating `null` or `undefined` yields nothing, rather than an
+        // error.
+        if let Value::Undefined | Value::Null = object {
+            self.context.avm2.push(false);
+            self.set_local_register(index_register, 0, self.context.gc_context)?;
+
+            return Ok(FrameControl::Continue);
+        }
+
+        let mut object = Some(object.coerce_to_object(self)?);
 
         let mut next_index = cur_index + 1;
 
         while let Some(cur_object) = object {
-            if cur_object.get_enumerant_name(next_index).is_none() {
+            if cur_object.get_enumerant_key(next_index).is_none() {
                 next_index = 1;
                 object = cur_object.proto();
             } else {
@@ -2177,9 +2616,7 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         let cur_index = self.context.avm2.pop().coerce_to_number(self)?;
         let object = self.context.avm2.pop().coerce_to_object(self)?;
 
-        let name = object
-            .get_enumerant_name(cur_index as u32)
-            .map(|n| n.local_name().into());
+        let name = object.get_enumerant_key(cur_index as u32);
 
         self.context.avm2.push(name.unwrap_or(Value::Undefined));
 
@@ -2188,14 +2625,9 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
 
     fn op_next_value(&mut self) -> Result<FrameControl<'gc>, Error> {
         let cur_index = self.context.avm2.pop().coerce_to_number(self)?;
-        let mut object = self.context.avm2.pop().coerce_to_object(self)?;
+        let object = self.context.avm2.pop().coerce_to_object(self)?;
 
-        let name = object.get_enumerant_name(cur_index as u32);
-        let value = if let Some(name) = name {
-            object.get_property(object, &name, self)?
-        } else {
-            Value::Undefined
-        };
+        let value = object.get_enumerant_value(cur_index as u32, self)?;
 
         self.context.avm2.push(value);
 
@@ -2207,37 +2639,51 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         method: Gc<'gc, BytecodeMethod<'gc>>,
         type_name_index: Index<AbcMultiname>,
     ) -> Result<FrameControl<'gc>, Error> {
-        let value = self.context.avm2.pop().coerce_to_object(self)?;
+        let value = self.context.avm2.pop();
+        let class = self.resolve_class(method, type_name_index)?;
+        let is_of_type = self.is_of_type(&value, class)?;
 
-        let type_name =
-            self.pool_multiname_static(method, type_name_index, self.context.gc_context)?;
-        let type_object = if let Some(scope) = self.scope() {
-            scope.read().find(&type_name, self)?
-        } else {
-            None
-        };
+        self.context.avm2.push(is_of_type);
+
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_is_type_late(&mut self) -> Result<FrameControl<'gc>, Error> {
+        let class = self.context.avm2.pop().coerce_to_object(self)?;
+        let value = self.context.avm2.pop();
+        let is_of_type = self.is_of_type(&value, class)?;
+
+        self.context.avm2.push(is_of_type);
+
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_as_type(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        type_name_index: Index<AbcMultiname>,
+    ) -> Result<FrameControl<'gc>, Error> {
+        let value = self.context.avm2.pop();
+        let class = self.resolve_class(method, type_name_index)?;
 
-        if let Some(type_object) = type_object {
-            let is_instance_of = value.is_instance_of(self, type_object, true)?;
-            self.context.avm2.push(is_instance_of);
+        if self.is_of_type(&value, class)? {
+            self.context.avm2.push(value);
         } else {
-            return Err(format!(
-                "Attempted to check against nonexistent type {:?}",
-                type_name
-            )
-            .into());
+            self.context.avm2.push(Value::Null);
         }
 
         Ok(FrameControl::Continue)
     }
 
-    fn op_is_type_late(&mut self) -> Result<FrameControl<'gc>, Error> {
-        let type_object = self.context.avm2.pop().coerce_to_object(self)?;
-        let value = self.context.avm2.pop().coerce_to_object(self)?;
-
-        let is_instance_of = value.is_instance_of(self, type_object, true)?;
+    fn op_as_type_late(&mut self) -> Result<FrameControl<'gc>, Error> {
+        let class = self.context.avm2.pop().coerce_to_object(self)?;
+        let value = self.context.avm2.pop();
 
-        self.context.avm2.push(is_instance_of);
+        if self.is_of_type(&value, class)? {
+            self.context.avm2.push(value);
+        } else {
+            self.context.avm2.push(Value::Null);
+        }
 
         Ok(FrameControl::Continue)
     }
@@ -2253,6 +2699,184 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         Ok(FrameControl::Continue)
     }
 
+    fn op_type_of(&mut self) -> Result<FrameControl<'gc>, Error> {
+        let value = self.context.avm2.pop();
+
+        let type_name = match value {
+            Value::Undefined => "undefined",
+            Value::Null => "object",
+            Value::Bool(_) => "boolean",
+            Value::Number(_) | Value::Integer(_) | Value::Unsigned(_) => "number",
+            Value::String(_) => "string",
+            // Classes are executable too, but are not functions.
+            Value::Object(o) if o.as_executable().is_some() && o.as_class().is_none() => "function",
+            Value::Object(_) => "object",
+        };
+
+        self.context.avm2.push(AvmString::from(type_name));
+
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_dxns(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        index: Index<String>,
+    ) -> Result<FrameControl<'gc>, Error> {
+        let uri = self.pool_string(&method, index, self.context.gc_context)?;
+
+        self.default_xml_namespace = Some(Namespace::Namespace(uri));
+
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_dxns_late(&mut self) -> Result<FrameControl<'gc>, Error> {
+        let uri = self.context.avm2.pop().coerce_to_string(self)?;
+
+        self.default_xml_namespace = Some(Namespace::Namespace(uri));
+
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_esc_xelem(&mut self) -> Result<FrameControl<'gc>, Error> {
+        let value = self.context.avm2.pop().coerce_to_string(self)?;
+
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                c => escaped.push(c),
+            }
+        }
+
+        self.context
+            .avm2
+            .push(AvmString::new(self.context.gc_context, escaped));
+
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_esc_xattr(&mut self) -> Result<FrameControl<'gc>, Error> {
+        let value = self.context.avm2.pop().coerce_to_string(self)?;
+
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\n' => escaped.push_str("&#xA;"),
+                '\r' => escaped.push_str("&#xD;"),
+                '\t' => escaped.push_str("&#x9;"),
+                c => escaped.push(c),
+            }
+        }
+
+        self.context
+            .avm2
+            .push(AvmString::new(self.context.gc_context, escaped));
+
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_get_descendants(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        index: Index<AbcMultiname>,
+    ) -> Result<FrameControl<'gc>, Error> {
+        let multiname = self.pool_multiname(method, index)?;
+        let object = self.context.avm2.pop();
+
+        let xml = match &object {
+            Value::Undefined | Value::Null => {
+                return Err(
+                    "TypeError: Error #1009: Cannot access a property or method of a null object reference."
+                        .into(),
+                )
+            }
+            Value::Object(object) => object.as_xml_object(),
+            _ => None,
+        };
+        let xml = xml.ok_or_else(|| {
+            format!(
+                "TypeError: Error #1016: Descendants operator (..) not supported on type {}.",
+                type_name_of(&object)
+            )
+        })?;
+
+        // Unqualified names select elements in the default XML namespace, as
+        // set by `dxns`. A `None` here matches any namespace.
+        let uris: Option<Vec<String>> = if multiname.namespace_set().any(|ns| ns.is_any()) {
+            None
+        } else {
+            let default_uri = self
+                .default_xml_namespace()
+                .map(|ns| ns.as_uri().to_string())
+                .unwrap_or_default();
+
+            Some(
+                multiname
+                    .namespace_set()
+                    .filter_map(|ns| match ns {
+                        Namespace::Namespace(uri) => Some(uri.to_string()),
+                        ns if ns.is_public() => Some(default_uri.clone()),
+                        _ => None,
+                    })
+                    .collect(),
+            )
+        };
+        let local_name = multiname.local_name().filter(|name| &**name != "*");
+
+        let nodes = xml.descendants(|node| {
+            if local_name.is_none() && uris.is_none() {
+                return true;
+            }
+
+            let tag_name = match node.tag_name() {
+                Some(tag_name) if node.is_element() => tag_name,
+                _ => return false,
+            };
+
+            local_name
+                .map(|name| tag_name.local_name() == &*name)
+                .unwrap_or(true)
+                && uris
+                    .as_ref()
+                    .map(|uris| uris.contains(&XmlObject::namespace_uri(node)))
+                    .unwrap_or(true)
+        });
+
+        let xml_list_proto = self.context.avm2.prototypes().xml_list;
+        let xml_list = XmlObject::from_list(nodes, xml_list_proto, self.context.gc_context);
+
+        self.context.avm2.push(xml_list);
+
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_check_filter(&mut self) -> Result<FrameControl<'gc>, Error> {
+        let value = self.context.avm2.pop();
+
+        // Only XML and XMLList can be filtered; they are left on the stack.
+        let is_xml = match &value {
+            Value::Object(object) => object.as_xml_object().is_some(),
+            _ => false,
+        };
+        if !is_xml {
+            return Err(format!(
+                "TypeError: Error #1123: Filter operator not supported on type {}.",
+                type_name_of(&value)
+            )
+            .into());
+        }
+
+        self.context.avm2.push(value);
+
+        Ok(FrameControl::Continue)
+    }
+
     #[allow(unused_variables)]
     #[cfg(avm_debug)]
     fn op_debug(
@@ -2317,3 +2941,28 @@ impl<'a, 'gc, 'gc_context> Activation<'a, 'gc, 'gc_context> {
         Ok(FrameControl::Continue)
     }
 }
+
+/// Describe the type of a value for use in error messages.
+fn type_name_of(value: &Value<'_>) -> String {
+    match value {
+        Value::Undefined => "undefined".to_string(),
+        Value::Null => "null".to_string(),
+        Value::Bool(_) => "Boolean".to_string(),
+        Value::Number(_) => "Number".to_string(),
+        Value::Integer(_) => "int".to_string(),
+        Value::Unsigned(_) => "uint".to_string(),
+        Value::String(_) => "String".to_string(),
+        Value::Object(object) => {
+            let mut proto = Some(*object);
+            while let Some(this_proto) = proto {
+                if let Some(class) = this_proto.as_class() {
+                    return class.read().name().local_name().to_string();
+                }
+
+                proto = this_proto.proto();
+            }
+
+            "Object".to_string()
+        }
+    }
+}
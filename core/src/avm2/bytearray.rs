@@ -0,0 +1,343 @@
+//! ByteArray support types
+
+use crate::avm2::Error;
+use flate2::read::{DeflateDecoder, DeflateEncoder, ZlibDecoder, ZlibEncoder};
+use flate2::Compression;
+use gc_arena::Collect;
+use std::convert::TryInto;
+use std::io::Read;
+
+/// Declares methods to read and write a number in the byte order of the
+/// array.
+macro_rules! impl_primitive_io {
+    ($($read:ident, $write:ident => $type:ty),* $(,)?) => {
+        $(
+            pub fn $read(&mut self) -> Result<$type, Error> {
+                let endian = self.endian;
+                let bytes = self
+                    .read_bytes(std::mem::size_of::<$type>())?
+                    .try_into()
+                    .unwrap();
+
+                Ok(match endian {
+                    Endian::Big => <$type>::from_be_bytes(bytes),
+                    Endian::Little => <$type>::from_le_bytes(bytes),
+                })
+            }
+
+            pub fn $write(&mut self, value: $type) {
+                let bytes = match self.endian {
+                    Endian::Big => value.to_be_bytes(),
+                    Endian::Little => value.to_le_bytes(),
+                };
+
+                self.write_bytes(&bytes);
+            }
+        )*
+    };
+}
+
+/// The byte order used by a `ByteArray` to read and write multi-byte values.
+#[derive(Clone, Collect, Copy, Debug, PartialEq, Eq)]
+#[collect(require_static)]
+pub enum Endian {
+    Big,
+    Little,
+}
+
+impl Endian {
+    /// Parse the name of an endianness, as found in `flash.utils.Endian`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "bigEndian" => Some(Self::Big),
+            "littleEndian" => Some(Self::Little),
+            _ => None,
+        }
+    }
+
+    /// The name of this endianness, as found in `flash.utils.Endian`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Big => "bigEndian",
+            Self::Little => "littleEndian",
+        }
+    }
+}
+
+/// A compression format supported by `ByteArray.compress`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompressionAlgorithm {
+    Zlib,
+    Deflate,
+    Lzma,
+}
+
+impl CompressionAlgorithm {
+    /// Parse the name of a compression algorithm, as found in
+    /// `flash.utils.CompressionAlgorithm`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "zlib" => Some(Self::Zlib),
+            "deflate" => Some(Self::Deflate),
+            "lzma" => Some(Self::Lzma),
+            _ => None,
+        }
+    }
+}
+
+/// The data held by a `ByteArray` object.
+///
+/// Alongside the bytes themselves, a byte array holds a read/write cursor and
+/// the byte order that multi-byte values are encoded with.
+#[derive(Clone, Collect, Debug)]
+#[collect(require_static)]
+pub struct ByteArrayStorage {
+    /// The bytes of this array.
+    bytes: Vec<u8>,
+
+    /// The position that the next read or write will occur at.
+    ///
+    /// This may lie past the end of the array, in which case writes will
+    /// extend the array with zeroes and reads will fail.
+    position: usize,
+
+    /// The byte order of multi-byte values.
+    endian: Endian,
+
+    /// The AMF version used to serialize objects.
+    object_encoding: u32,
+}
+
+impl ByteArrayStorage {
+    /// Construct an empty byte array.
+    pub fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            position: 0,
+            endian: Endian::Big,
+            object_encoding: 3,
+        }
+    }
+
+    /// Wrap an existing set of bytes.
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            ..Self::new()
+        }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Resize the array, filling any new space with zeroes.
+    ///
+    /// The position is clamped to the new length.
+    pub fn set_len(&mut self, len: usize) {
+        self.bytes.resize(len, 0);
+        self.position = self.position.min(len);
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: usize) {
+        self.position = position;
+    }
+
+    /// The number of bytes between the position and the end of the array.
+    pub fn bytes_available(&self) -> usize {
+        self.bytes.len().saturating_sub(self.position)
+    }
+
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    pub fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+    }
+
+    pub fn object_encoding(&self) -> u32 {
+        self.object_encoding
+    }
+
+    pub fn set_object_encoding(&mut self, object_encoding: u32) {
+        self.object_encoding = object_encoding;
+    }
+
+    /// Remove all bytes from the array and reset the position.
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+        self.position = 0;
+    }
+
+    /// Retrieve a single byte by index.
+    pub fn get(&self, index: usize) -> Option<u8> {
+        self.bytes.get(index).copied()
+    }
+
+    /// Set a single byte by index, extending the array with zeroes if needed.
+    pub fn set(&mut self, index: usize, byte: u8) {
+        if self.bytes.len() <= index {
+            self.bytes.resize(index + 1, 0);
+        }
+
+        self.bytes[index] = byte;
+    }
+
+    /// Read a number of bytes from the current position, advancing it.
+    pub fn read_bytes(&mut self, len: usize) -> Result<&[u8], Error> {
+        if self.bytes_available() < len {
+            return Err("EOFError: Error #2030: End of file was encountered.".into());
+        }
+
+        let start = self.position;
+        self.position += len;
+
+        Ok(&self.bytes[start..self.position])
+    }
+
+    /// Write bytes at the current position, advancing it.
+    ///
+    /// Existing bytes are overwritten and the array grows as needed.
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        let end = self.position + bytes.len();
+        if self.bytes.len() < end {
+            self.bytes.resize(end, 0);
+        }
+
+        self.bytes[self.position..end].copy_from_slice(bytes);
+        self.position = end;
+    }
+
+    pub fn read_boolean(&mut self) -> Result<bool, Error> {
+        Ok(self.read_bytes(1)?[0] != 0)
+    }
+
+    pub fn read_byte(&mut self) -> Result<i8, Error> {
+        Ok(self.read_bytes(1)?[0] as i8)
+    }
+
+    pub fn read_unsigned_byte(&mut self) -> Result<u8, Error> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    impl_primitive_io!(
+        read_short, write_short => i16,
+        read_unsigned_short, write_unsigned_short => u16,
+        read_int, write_int => i32,
+        read_unsigned_int, write_unsigned_int => u32,
+        read_float, write_float => f32,
+        read_double, write_double => f64,
+    );
+
+    /// Read a UTF-8 string prefixed with its length as an unsigned short.
+    pub fn read_utf(&mut self) -> Result<String, Error> {
+        let len = self.read_unsigned_short()?;
+        self.read_utf_bytes(len as usize)
+    }
+
+    /// Read a given number of bytes as a UTF-8 string.
+    ///
+    /// A leading byte order mark is skipped, and the string ends at the first
+    /// null byte.
+    pub fn read_utf_bytes(&mut self, len: usize) -> Result<String, Error> {
+        let mut bytes = self.read_bytes(len)?;
+        if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+            bytes = &bytes[3..];
+        }
+        if let Some(null) = bytes.iter().position(|b| *b == 0) {
+            bytes = &bytes[..null];
+        }
+
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    pub fn write_boolean(&mut self, value: bool) {
+        self.write_bytes(&[value as u8]);
+    }
+
+    pub fn write_byte(&mut self, value: u8) {
+        self.write_bytes(&[value]);
+    }
+
+    /// Write a UTF-8 string prefixed with its length as an unsigned short.
+    pub fn write_utf(&mut self, value: &str) -> Result<(), Error> {
+        if value.len() > u16::MAX as usize {
+            return Err("RangeError: Error #2006: The supplied index is out of bounds.".into());
+        }
+
+        self.write_unsigned_short(value.len() as u16);
+        self.write_bytes(value.as_bytes());
+
+        Ok(())
+    }
+
+    /// Compress the entire array in place, leaving the position at the end.
+    pub fn compress(&mut self, algorithm: CompressionAlgorithm) -> Result<(), Error> {
+        let mut compressed = Vec::new();
+        match algorithm {
+            CompressionAlgorithm::Zlib => {
+                ZlibEncoder::new(&self.bytes[..], Compression::default())
+                    .read_to_end(&mut compressed)?;
+            }
+            CompressionAlgorithm::Deflate => {
+                DeflateEncoder::new(&self.bytes[..], Compression::default())
+                    .read_to_end(&mut compressed)?;
+            }
+            CompressionAlgorithm::Lzma => {
+                return Err("LZMA compression of ByteArrays is not yet supported".into())
+            }
+        }
+
+        self.position = compressed.len();
+        self.bytes = compressed;
+
+        Ok(())
+    }
+
+    /// Decompress the entire array in place, resetting the position.
+    ///
+    /// The array is left untouched if its contents are not valid compressed
+    /// data.
+    pub fn uncompress(&mut self, algorithm: CompressionAlgorithm) -> Result<(), Error> {
+        let mut uncompressed = Vec::new();
+        let result = match algorithm {
+            CompressionAlgorithm::Zlib => {
+                ZlibDecoder::new(&self.bytes[..]).read_to_end(&mut uncompressed)
+            }
+            CompressionAlgorithm::Deflate => {
+                DeflateDecoder::new(&self.bytes[..]).read_to_end(&mut uncompressed)
+            }
+            CompressionAlgorithm::Lzma => {
+                return Err("LZMA decompression of ByteArrays is not yet supported".into())
+            }
+        };
+
+        if result.is_err() {
+            return Err("IOError: Error #2058: There was an error decompressing the data.".into());
+        }
+
+        self.position = 0;
+        self.bytes = uncompressed;
+
+        Ok(())
+    }
+}
+
+impl Default for ByteArrayStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::avm2::method::Method;
 use crate::avm2::names::{Multiname, Namespace, QName};
+use crate::avm2::object::{Object, TObject};
 use crate::avm2::script::TranslationUnit;
 use crate::avm2::string::AvmString;
 use crate::avm2::traits::{Trait, TraitKind};
@@ -24,6 +25,10 @@ bitflags! {
 
         /// Class is an interface.
         const INTERFACE = 1 << 2;
+
+        /// Class is generic, and must be specialized with type parameters
+        /// before it can be used.
+        const GENERIC   = 1 << 3;
     }
 }
 
@@ -70,6 +75,12 @@ pub struct Class<'gc> {
 
     /// Whether or not this `Class` has loaded its traits or not.
     traits_loaded: bool,
+
+    /// The type parameters this class was specialized with.
+    ///
+    /// Each parameter is the class object of the type, or `None` for the any
+    /// type. Only specializations of generic classes have parameters.
+    params: Vec<Option<Object<'gc>>>,
 }
 
 /// Find traits in a list of traits matching a name.
@@ -137,10 +148,48 @@ impl<'gc> Class<'gc> {
                 class_init,
                 class_traits: Vec::new(),
                 traits_loaded: true,
+                params: Vec::new(),
             },
         )
     }
 
+    /// Create a specialization of a generic class.
+    ///
+    /// The specialized class shares the traits and initializers of the
+    /// generic class, and is named after the parameters it was given, as in
+    /// `Vector.<int>`.
+    pub fn with_type_params(
+        this: GcCell<'gc, Self>,
+        params: &[Option<Object<'gc>>],
+        mc: MutationContext<'gc, '_>,
+    ) -> GcCell<'gc, Self> {
+        let mut specialized = this.read().clone();
+
+        let param_names = params
+            .iter()
+            .map(|param| {
+                param
+                    .and_then(|param| param.as_class())
+                    .map(|class| class.read().name().to_qualified_name())
+                    .unwrap_or_else(|| "*".to_string())
+            })
+            .collect::<Vec<String>>();
+        let name = format!(
+            "{}.<{}>",
+            specialized.name.local_name(),
+            param_names.join(",")
+        );
+
+        specialized.name = QName::new(
+            specialized.name.namespace().clone(),
+            AvmString::new(mc, name),
+        );
+        specialized.attributes.0.remove(ClassAttributes::GENERIC);
+        specialized.params = params.to_vec();
+
+        GcCell::allocate(mc, specialized)
+    }
+
     /// Set the attributes of the class (sealed/final/interface status).
     pub fn set_attributes(&mut self, attributes: ClassAttributes) {
         self.attributes = CollectWrapper(attributes);
@@ -221,6 +270,7 @@ impl<'gc> Class<'gc> {
                 class_init,
                 class_traits: Vec::new(),
                 traits_loaded: false,
+                params: Vec::new(),
             },
         ))
     }
@@ -405,4 +455,29 @@ impl<'gc> Class<'gc> {
     pub fn is_sealed(&self) -> bool {
         self.attributes.0.contains(ClassAttributes::SEALED)
     }
+
+    /// Determine if this class is final (cannot be subclassed)
+    pub fn is_final(&self) -> bool {
+        self.attributes.0.contains(ClassAttributes::FINAL)
+    }
+
+    /// Determine if this class is generic (must be specialized before use)
+    pub fn is_generic(&self) -> bool {
+        self.attributes.0.contains(ClassAttributes::GENERIC)
+    }
+
+    /// Get the type parameters this class was specialized with.
+    pub fn params(&self) -> &[Option<Object<'gc>>] {
+        &self.params
+    }
+
+    /// Get the traits this class defines on itself.
+    pub fn class_traits(&self) -> &[Trait<'gc>] {
+        &self.class_traits
+    }
+
+    /// Get the traits this class defines on its instances.
+    pub fn instance_traits(&self) -> &[Trait<'gc>] {
+        &self.instance_traits
+    }
 }
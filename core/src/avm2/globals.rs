@@ -6,8 +6,9 @@ use crate::avm2::domain::Domain;
 use crate::avm2::method::NativeMethod;
 use crate::avm2::names::{Namespace, QName};
 use crate::avm2::object::{
-    implicit_deriver, ArrayObject, DomainObject, FunctionObject, NamespaceObject, Object,
-    PrimitiveObject, ScriptObject, StageObject, TObject,
+    implicit_deriver, ArrayObject, ByteArrayObject, DictionaryObject, DomainObject, FunctionObject,
+    NamespaceObject, Object, PrimitiveObject, ScriptObject, StageObject, TObject, VectorObject,
+    XmlContent, XmlObject,
 };
 use crate::avm2::scope::Scope;
 use crate::avm2::script::Script;
@@ -20,7 +21,8 @@ use std::f64::NAN;
 mod array;
 mod boolean;
 mod class;
-mod flash;
+pub mod error;
+pub mod flash;
 mod function;
 mod global_scope;
 mod int;
@@ -30,6 +32,9 @@ mod number;
 mod object;
 mod string;
 mod r#uint;
+mod vector;
+mod xml;
+mod xml_list;
 
 const NS_RUFFLE_INTERNAL: &str = "https://ruffle.rs/AS3/impl/";
 
@@ -96,6 +101,11 @@ pub struct SystemPrototypes<'gc> {
     pub scene: Object<'gc>,
     pub application_domain: Object<'gc>,
     pub event: Object<'gc>,
+    pub timerevent: Object<'gc>,
+    pub textevent: Object<'gc>,
+    pub textfield: Object<'gc>,
+    pub xml: Object<'gc>,
+    pub xml_list: Object<'gc>,
 }
 
 impl<'gc> SystemPrototypes<'gc> {
@@ -129,6 +139,11 @@ impl<'gc> SystemPrototypes<'gc> {
             scene: empty,
             application_domain: empty,
             event: empty,
+            timerevent: empty,
+            textevent: empty,
+            textfield: empty,
+            xml: empty,
+            xml_list: empty,
         }
     }
 }
@@ -273,6 +288,59 @@ fn array_deriver<'gc>(
     ArrayObject::derive(base_proto, activation.context.gc_context, class, scope)
 }
 
+fn bytearray_deriver<'gc>(
+    base_proto: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    class: GcCell<'gc, Class<'gc>>,
+    scope: Option<GcCell<'gc, Scope<'gc>>>,
+) -> Result<Object<'gc>, Error> {
+    ByteArrayObject::derive(base_proto, activation.context.gc_context, class, scope)
+}
+
+fn vector_deriver<'gc>(
+    base_proto: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    class: GcCell<'gc, Class<'gc>>,
+    scope: Option<GcCell<'gc, Scope<'gc>>>,
+) -> Result<Object<'gc>, Error> {
+    VectorObject::derive(base_proto, activation, class, scope)
+}
+
+fn dictionary_deriver<'gc>(
+    base_proto: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    class: GcCell<'gc, Class<'gc>>,
+    scope: Option<GcCell<'gc, Scope<'gc>>>,
+) -> Result<Object<'gc>, Error> {
+    DictionaryObject::derive(base_proto, activation.context.gc_context, class, scope)
+}
+
+fn xml_deriver<'gc>(
+    base_proto: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    class: GcCell<'gc, Class<'gc>>,
+    scope: Option<GcCell<'gc, Scope<'gc>>>,
+) -> Result<Object<'gc>, Error> {
+    let mc = activation.context.gc_context;
+
+    XmlObject::derive(base_proto, mc, class, scope, XmlContent::empty_node(mc))
+}
+
+fn xml_list_deriver<'gc>(
+    base_proto: Object<'gc>,
+    activation: &mut Activation<'_, 'gc, '_>,
+    class: GcCell<'gc, Class<'gc>>,
+    scope: Option<GcCell<'gc, Scope<'gc>>>,
+) -> Result<Object<'gc>, Error> {
+    XmlObject::derive(
+        base_proto,
+        activation.context.gc_context,
+        class,
+        scope,
+        XmlContent::List(Vec::new()),
+    )
+}
+
 fn stage_deriver<'gc>(
     base_proto: Object<'gc>,
     activation: &mut Activation<'_, 'gc, '_>,
@@ -441,6 +509,58 @@ pub fn load_player_globals<'gc>(
         domain,
         script,
     )?;
+    class(
+        activation,
+        error::create_class(mc),
+        implicit_deriver,
+        domain,
+        script,
+    )?;
+    for (_, name, _) in error::ERROR_SUBCLASSES {
+        class(
+            activation,
+            error::create_subclass(mc, name),
+            implicit_deriver,
+            domain,
+            script,
+        )?;
+    }
+
+    activation
+        .context
+        .avm2
+        .system_prototypes
+        .as_mut()
+        .unwrap()
+        .xml = class(
+        activation,
+        xml::create_class(mc),
+        xml_deriver,
+        domain,
+        script,
+    )?;
+    activation
+        .context
+        .avm2
+        .system_prototypes
+        .as_mut()
+        .unwrap()
+        .xml_list = class(
+        activation,
+        xml_list::create_class(mc),
+        xml_list_deriver,
+        domain,
+        script,
+    )?;
+
+    // package `__AS3__.vec`
+    class(
+        activation,
+        vector::create_class(mc),
+        vector_deriver,
+        domain,
+        script,
+    )?;
 
     // package `flash.system`
     activation
@@ -485,6 +605,114 @@ pub fn load_player_globals<'gc>(
         domain,
         script,
     )?;
+    activation
+        .context
+        .avm2
+        .system_prototypes
+        .as_mut()
+        .unwrap()
+        .timerevent = class(
+        activation,
+        flash::events::timerevent::create_class(mc),
+        flash::events::event::event_deriver,
+        domain,
+        script,
+    )?;
+    activation
+        .context
+        .avm2
+        .system_prototypes
+        .as_mut()
+        .unwrap()
+        .textevent = class(
+        activation,
+        flash::events::textevent::create_class(mc),
+        flash::events::event::event_deriver,
+        domain,
+        script,
+    )?;
+
+    // package `flash.utils`
+    class(
+        activation,
+        flash::utils::bytearray::create_class(mc),
+        bytearray_deriver,
+        domain,
+        script,
+    )?;
+    class(
+        activation,
+        flash::utils::dictionary::create_class(mc),
+        dictionary_deriver,
+        domain,
+        script,
+    )?;
+    class(
+        activation,
+        flash::utils::endian::create_class(mc),
+        implicit_deriver,
+        domain,
+        script,
+    )?;
+    class(
+        activation,
+        flash::utils::compression_algorithm::create_class(mc),
+        implicit_deriver,
+        domain,
+        script,
+    )?;
+    class(
+        activation,
+        flash::utils::timer::create_class(mc),
+        implicit_deriver,
+        domain,
+        script,
+    )?;
+    function(
+        mc,
+        "flash.utils",
+        "getTimer",
+        flash::utils::get_timer,
+        fn_proto,
+        domain,
+        script,
+    )?;
+    function(
+        mc,
+        "flash.utils",
+        "getDefinitionByName",
+        flash::utils::get_definition_by_name,
+        fn_proto,
+        domain,
+        script,
+    )?;
+    function(
+        mc,
+        "flash.utils",
+        "getQualifiedClassName",
+        flash::utils::get_qualified_class_name,
+        fn_proto,
+        domain,
+        script,
+    )?;
+    function(
+        mc,
+        "flash.utils",
+        "getQualifiedSuperclassName",
+        flash::utils::get_qualified_superclass_name,
+        fn_proto,
+        domain,
+        script,
+    )?;
+    function(
+        mc,
+        "flash.utils",
+        "describeType",
+        flash::utils::describe_type,
+        fn_proto,
+        domain,
+        script,
+    )?;
 
     // package `flash.display`
     class(
@@ -555,5 +783,20 @@ pub fn load_player_globals<'gc>(
         script,
     )?;
 
+    // package `flash.text`
+    activation
+        .context
+        .avm2
+        .system_prototypes
+        .as_mut()
+        .unwrap()
+        .textfield = class(
+        activation,
+        flash::text::textfield::create_class(mc),
+        implicit_deriver,
+        domain,
+        script,
+    )?;
+
     Ok(())
 }
@@ -265,7 +265,7 @@ pub fn value_of<'gc>(
 /// mutate the array under iteration. Normally, holding an `Iterator` on the
 /// array while this happens would cause a panic; this code exists to prevent
 /// that.
-struct ArrayIter<'gc> {
+pub struct ArrayIter<'gc> {
     array_object: Object<'gc>,
     index: u32,
     length: u32,
@@ -296,7 +296,7 @@ impl<'gc> ArrayIter<'gc> {
     ///
     /// Since this isn't a real iterator, this comes pre-enumerated; it yields
     /// a pair of the index and then the value.
-    fn next(
+    pub fn next(
         &mut self,
         activation: &mut Activation<'_, 'gc, '_>,
     ) -> Option<Result<(u32, Value<'gc>), Error>> {
@@ -798,7 +798,7 @@ bitflags! {
     /// The array options that a given sort operation may use.
     ///
     /// These are provided as a number by the VM and converted into bitflags.
-    struct SortOptions: u8 {
+    pub struct SortOptions: u8 {
         /// Request case-insensitive string value sort.
         const CASE_INSENSITIVE     = 1 << 0;
 
@@ -818,7 +818,7 @@ bitflags! {
 
 /// Identity closure shim which exists purely to decorate closure types with
 /// the HRTB necessary to accept an activation.
-fn constrain<'a, 'gc, 'ctxt, F>(f: F) -> F
+pub fn constrain<'a, 'gc, 'ctxt, F>(f: F) -> F
 where
     F: FnMut(&mut Activation<'a, 'gc, 'ctxt>, Value<'gc>, Value<'gc>) -> Result<Ordering, Error>,
 {
@@ -838,7 +838,7 @@ where
 /// this case, you should cancel the in-place sorting operation and return 0 to
 /// the caller. In the event that this function yields a runtime error, the
 /// contents of the `values` array will be sorted in a random order.
-fn sort_inner<'a, 'gc, 'ctxt, C>(
+pub fn sort_inner<'a, 'gc, 'ctxt, C>(
     activation: &mut Activation<'a, 'gc, 'ctxt>,
     values: &mut [(usize, Value<'gc>)],
     options: SortOptions,
@@ -882,7 +882,7 @@ where
     Ok(!options.contains(SortOptions::UNIQUE_SORT) || unique_sort_satisfied)
 }
 
-fn compare_string_case_sensitive<'gc>(
+pub fn compare_string_case_sensitive<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     a: Value<'gc>,
     b: Value<'gc>,
@@ -893,7 +893,7 @@ fn compare_string_case_sensitive<'gc>(
     Ok(string_a.cmp(&string_b))
 }
 
-fn compare_string_case_insensitive<'gc>(
+pub fn compare_string_case_insensitive<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     a: Value<'gc>,
     b: Value<'gc>,
@@ -904,7 +904,7 @@ fn compare_string_case_insensitive<'gc>(
     Ok(string_a.cmp(&string_b))
 }
 
-fn compare_numeric<'gc>(
+pub fn compare_numeric<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     a: Value<'gc>,
     b: Value<'gc>,
@@ -0,0 +1,362 @@
+//! `Error` and its builtin subclasses
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::globals::NS_RUFFLE_INTERNAL;
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::string::AvmString;
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Every builtin subclass of `Error`, as `(package, name, superclass)`.
+///
+/// Superclasses are always declared before their subclasses.
+pub const ERROR_SUBCLASSES: &[(&str, &str, &str)] = &[
+    ("", "ArgumentError", "Error"),
+    ("", "DefinitionError", "Error"),
+    ("", "EvalError", "Error"),
+    ("", "RangeError", "Error"),
+    ("", "ReferenceError", "Error"),
+    ("", "SecurityError", "Error"),
+    ("", "SyntaxError", "Error"),
+    ("", "TypeError", "Error"),
+    ("", "URIError", "Error"),
+    ("", "VerifyError", "Error"),
+    ("flash.errors", "IllegalOperationError", "Error"),
+    ("flash.errors", "IOError", "Error"),
+    ("flash.errors", "EOFError", "IOError"),
+];
+
+/// Look up the fully qualified name of a builtin error class.
+fn error_class_name<'gc>(name: &str) -> Option<QName<'gc>> {
+    if name == "Error" {
+        return Some(QName::new(Namespace::public(), "Error"));
+    }
+
+    ERROR_SUBCLASSES
+        .iter()
+        .find(|(_, class_name, _)| *class_name == name)
+        .map(|(package, class_name, _)| QName::new(Namespace::package(*package), *class_name))
+}
+
+/// Implements `Error`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let message = args
+            .get(0)
+            .cloned()
+            .unwrap_or_else(|| "".into())
+            .coerce_to_string(activation)?;
+        let error_id = args
+            .get(1)
+            .cloned()
+            .unwrap_or_else(|| 0.into())
+            .coerce_to_i32(activation)?;
+
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "message"),
+            message.into(),
+            activation,
+        )?;
+        this.set_property(
+            this,
+            &QName::new(Namespace::public(), "name"),
+            "Error".into(),
+            activation,
+        )?;
+        this.init_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "errorID"),
+            error_id.into(),
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Error`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `Error.errorID`.
+pub fn error_id<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        return this.get_property(
+            this,
+            &QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "errorID"),
+            activation,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Error.getStackTrace`.
+///
+/// Stack traces are only available in debugger players, which we are not.
+pub fn get_stack_trace<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Null)
+}
+
+/// Implements `Error.toString`.
+pub fn to_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        let name = this
+            .get_property(this, &QName::new(Namespace::public(), "name"), activation)?
+            .coerce_to_string(activation)?;
+        let message = this
+            .get_property(
+                this,
+                &QName::new(Namespace::public(), "message"),
+                activation,
+            )?
+            .coerce_to_string(activation)?;
+
+        if message.is_empty() {
+            return Ok(name.into());
+        }
+
+        return Ok(AvmString::new(
+            activation.context.gc_context,
+            format!("{}: {}", name, message),
+        )
+        .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `Error`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::public(), "Error"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init),
+        Method::from_builtin(class_init),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "message"),
+        QName::new(Namespace::public(), "String").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::public(), "name"),
+        QName::new(Namespace::public(), "String").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_slot(
+        QName::new(Namespace::private(NS_RUFFLE_INTERNAL), "errorID"),
+        QName::new(Namespace::public(), "int").into(),
+        None,
+    ));
+    write.define_instance_trait(Trait::from_getter(
+        QName::new(Namespace::public(), "errorID"),
+        Method::from_builtin(error_id),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::public(), "getStackTrace"),
+        Method::from_builtin(get_stack_trace),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::public(), "toString"),
+        Method::from_builtin(to_string),
+    ));
+
+    class
+}
+
+/// Declares the instance constructor of a builtin `Error` subclass.
+///
+/// Subclasses differ from `Error` only in the `name` given to their
+/// instances.
+macro_rules! error_subclass_init {
+    ($($(#[$attr:meta])* $init:ident => $name:literal),* $(,)?) => {
+        $(
+            $(#[$attr])*
+            fn $init<'gc>(
+                activation: &mut Activation<'_, 'gc, '_>,
+                this: Option<Object<'gc>>,
+                args: &[Value<'gc>],
+            ) -> Result<Value<'gc>, Error> {
+                if let Some(mut this) = this {
+                    activation.super_init(this, args)?;
+
+                    this.set_property(
+                        this,
+                        &QName::new(Namespace::public(), "name"),
+                        $name.into(),
+                        activation,
+                    )?;
+                }
+
+                Ok(Value::Undefined)
+            }
+        )*
+    };
+}
+
+error_subclass_init!(
+    /// Implements `ArgumentError`'s instance constructor.
+    argument_error_init => "ArgumentError",
+    /// Implements `DefinitionError`'s instance constructor.
+    definition_error_init => "DefinitionError",
+    /// Implements `EvalError`'s instance constructor.
+    eval_error_init => "EvalError",
+    /// Implements `RangeError`'s instance constructor.
+    range_error_init => "RangeError",
+    /// Implements `ReferenceError`'s instance constructor.
+    reference_error_init => "ReferenceError",
+    /// Implements `SecurityError`'s instance constructor.
+    security_error_init => "SecurityError",
+    /// Implements `SyntaxError`'s instance constructor.
+    syntax_error_init => "SyntaxError",
+    /// Implements `TypeError`'s instance constructor.
+    type_error_init => "TypeError",
+    /// Implements `URIError`'s instance constructor.
+    uri_error_init => "URIError",
+    /// Implements `VerifyError`'s instance constructor.
+    verify_error_init => "VerifyError",
+    /// Implements `flash.errors.IllegalOperationError`'s instance constructor.
+    illegal_operation_error_init => "IllegalOperationError",
+    /// Implements `flash.errors.IOError`'s instance constructor.
+    io_error_init => "IOError",
+    /// Implements `flash.errors.EOFError`'s instance constructor.
+    eof_error_init => "EOFError",
+);
+
+/// Construct the class of a builtin `Error` subclass.
+///
+/// `name` must be the name of one of `ERROR_SUBCLASSES`.
+pub fn create_subclass<'gc>(
+    mc: MutationContext<'gc, '_>,
+    name: &'static str,
+) -> GcCell<'gc, Class<'gc>> {
+    let instance_init = match name {
+        "ArgumentError" => argument_error_init,
+        "DefinitionError" => definition_error_init,
+        "EvalError" => eval_error_init,
+        "RangeError" => range_error_init,
+        "ReferenceError" => reference_error_init,
+        "SecurityError" => security_error_init,
+        "SyntaxError" => syntax_error_init,
+        "TypeError" => type_error_init,
+        "URIError" => uri_error_init,
+        "VerifyError" => verify_error_init,
+        "IllegalOperationError" => illegal_operation_error_init,
+        "IOError" => io_error_init,
+        "EOFError" => eof_error_init,
+        _ => unreachable!("{} is not a builtin error class", name),
+    };
+    let (package, _, superclass) = ERROR_SUBCLASSES
+        .iter()
+        .find(|(_, class_name, _)| *class_name == name)
+        .unwrap();
+
+    Class::new(
+        QName::new(Namespace::package(*package), name),
+        Some(error_class_name(superclass).unwrap().into()),
+        Method::from_builtin(instance_init),
+        Method::from_builtin(class_init),
+        mc,
+    )
+}
+
+/// Construct an instance of a builtin error class with the given message and
+/// error ID.
+pub fn construct_error<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    class_name: &str,
+    message: &str,
+    error_id: i32,
+) -> Result<Object<'gc>, Error> {
+    let class_name = error_class_name(class_name)
+        .ok_or_else(|| format!("{} is not a builtin error class", class_name))?;
+    let mut constr = activation
+        .avm2()
+        .global_domain()
+        .get_defined_value(activation, class_name)?
+        .coerce_to_object(activation)?;
+    let proto = constr
+        .get_property(
+            constr,
+            &QName::new(Namespace::public(), "prototype"),
+            activation,
+        )?
+        .coerce_to_object(activation)?;
+
+    let args = [
+        AvmString::new(activation.context.gc_context, message.to_string()).into(),
+        error_id.into(),
+    ];
+    let object = proto.construct(activation, &args)?;
+    constr.call(Some(object), &args, activation, object.proto())?;
+
+    Ok(object)
+}
+
+/// Convert an error raised by native code into the ActionScript error it
+/// represents.
+///
+/// Native code signals ActionScript errors with a message prefixed by the
+/// name of the error class, such as `TypeError: null is not an Object`. If
+/// the message starts with a Flash Player error number, such as
+/// `Error #1009: ...`, it becomes the error's `errorID`. Any other error is
+/// internal to the player and yields `None`, as it cannot be caught by user
+/// code.
+pub fn error_from_native<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    error: &Error,
+) -> Result<Option<Object<'gc>>, Error> {
+    let description = error.to_string();
+    let (class_name, message) = match description.find(": ") {
+        Some(split) => (&description[..split], &description[split + 2..]),
+        None => return Ok(None),
+    };
+
+    if error_class_name(class_name).is_none() {
+        return Ok(None);
+    }
+
+    let error_id = message
+        .strip_prefix("Error #")
+        .and_then(|id| id.split(':').next())
+        .and_then(|id| id.parse().ok())
+        .unwrap_or(0);
+
+    Ok(Some(construct_error(
+        activation, class_name, message, error_id,
+    )?))
+}
@@ -9,7 +9,7 @@ use crate::avm2::string::AvmString;
 use crate::avm2::traits::Trait;
 use crate::avm2::value::Value;
 use crate::avm2::Error;
-use crate::display_object::TDisplayObject;
+use crate::display_object::{blend_mode_from_str, blend_mode_to_str, TDisplayObject};
 use crate::types::{Degrees, Percent};
 use gc_arena::{GcCell, MutationContext};
 use swf::Twips;
@@ -408,6 +408,44 @@ pub fn set_visible<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `blendMode`'s getter.
+pub fn blend_mode<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        return Ok(blend_mode_to_str(dobj.blend_mode()).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `blendMode`'s setter.
+pub fn set_blend_mode<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let name = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+        let blend_mode = blend_mode_from_str(&name).ok_or_else(|| {
+            format!(
+                "ArgumentError: Parameter blendMode must be one of the accepted values, got {}",
+                name
+            )
+        })?;
+
+        dobj.set_blend_mode(activation.context.gc_context, blend_mode);
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// Implements `mouseX`.
 pub fn mouse_x<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
@@ -594,6 +632,14 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         QName::new(Namespace::public(), "visible"),
         Method::from_builtin(set_visible),
     ));
+    write.define_instance_trait(Trait::from_getter(
+        QName::new(Namespace::public(), "blendMode"),
+        Method::from_builtin(blend_mode),
+    ));
+    write.define_instance_trait(Trait::from_setter(
+        QName::new(Namespace::public(), "blendMode"),
+        Method::from_builtin(set_blend_mode),
+    ));
     write.define_instance_trait(Trait::from_getter(
         QName::new(Namespace::public(), "mouseX"),
         Method::from_builtin(mouse_x),
@@ -3,3 +3,5 @@
 pub mod event;
 pub mod eventdispatcher;
 pub mod ieventdispatcher;
+pub mod textevent;
+pub mod timerevent;
@@ -0,0 +1,3 @@
+//! `flash.text` namespace
+
+pub mod textfield;
@@ -0,0 +1,142 @@
+//! `flash.text.TextField` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::string::AvmString;
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.text.TextField`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.text.TextField`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `text`'s getter.
+pub fn text<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(edit_text) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        return Ok(AvmString::new(activation.context.gc_context, edit_text.text()).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `text`'s setter.
+pub fn set_text<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(edit_text) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        let text = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+        edit_text.set_text(text.to_string(), &mut activation.context)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `htmlText`'s getter.
+pub fn html_text<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(edit_text) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        let html_text = edit_text.html_text(&mut activation.context)?;
+        return Ok(AvmString::new(activation.context.gc_context, html_text).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `htmlText`'s setter.
+pub fn set_html_text<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(edit_text) = this
+        .and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        let html_text = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(activation)?;
+        edit_text.set_is_html(&mut activation.context, true);
+        edit_text.set_html_text(html_text.to_string(), &mut activation.context)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `TextField`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.text"), "TextField"),
+        Some(QName::new(Namespace::package("flash.display"), "InteractiveObject").into()),
+        Method::from_builtin(instance_init),
+        Method::from_builtin(class_init),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.define_instance_trait(Trait::from_getter(
+        QName::new(Namespace::public(), "text"),
+        Method::from_builtin(text),
+    ));
+    write.define_instance_trait(Trait::from_setter(
+        QName::new(Namespace::public(), "text"),
+        Method::from_builtin(set_text),
+    ));
+    write.define_instance_trait(Trait::from_getter(
+        QName::new(Namespace::public(), "htmlText"),
+        Method::from_builtin(html_text),
+    ));
+    write.define_instance_trait(Trait::from_setter(
+        QName::new(Namespace::public(), "htmlText"),
+        Method::from_builtin(set_html_text),
+    ));
+
+    class
+}
@@ -0,0 +1,428 @@
+//! `flash.utils` namespace
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::names::{Multiname, Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::string::AvmString;
+use crate::avm2::traits::TraitKind;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::GcCell;
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+use quick_xml::Writer;
+use std::io::{Cursor, Write};
+
+pub mod bytearray;
+pub mod compression_algorithm;
+pub mod dictionary;
+pub mod endian;
+pub mod timer;
+
+/// Implements `flash.utils.getTimer`
+pub fn get_timer<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    activation.context.times_get_time_called += 1;
+    // heuristic to detect busy loops used for delays and slowly progress fake time
+    if activation.context.times_get_time_called >= 20
+        && activation.context.times_get_time_called % 5 == 0
+    {
+        *activation.context.time_offset += 1;
+    }
+
+    let time = activation.context.navigator.time_since_launch().as_millis() as u32;
+
+    Ok(time.wrapping_add(*activation.context.time_offset).into())
+}
+
+/// Format a type name from a trait or class definition.
+///
+/// Names that could refer to any type are formatted as `*`.
+fn type_name(name: &Multiname<'_>) -> String {
+    match (name.namespace_set().next(), name.local_name()) {
+        (Some(ns), Some(local_name)) if !ns.is_any() => {
+            QName::new(ns.clone(), local_name).to_qualified_name()
+        }
+        (_, Some(local_name)) => local_name.to_string(),
+        (_, None) => "*".to_string(),
+    }
+}
+
+/// Get the class of an object, along with every class it inherits from.
+///
+/// Classes are listed from the most derived class up to `Object`. Class
+/// constructors yield the classes of their instances.
+fn class_chain<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<Vec<GcCell<'gc, Class<'gc>>>, Error> {
+    let mut proto = if is_class_constructor(object) {
+        let mut constr = object;
+        Some(
+            constr
+                .get_property(
+                    constr,
+                    &QName::new(Namespace::public(), "prototype"),
+                    activation,
+                )?
+                .coerce_to_object(activation)?,
+        )
+    } else if object.as_class().is_some() {
+        Some(object)
+    } else {
+        object.proto()
+    };
+
+    let mut classes = Vec::new();
+    while let Some(p) = proto {
+        if let Some(class) = p.as_class() {
+            if !classes.iter().any(|c| GcCell::ptr_eq(*c, class)) {
+                classes.push(class);
+            }
+        }
+
+        proto = p.proto();
+    }
+
+    Ok(classes)
+}
+
+/// Determine if an object is the constructor of a class.
+fn is_class_constructor(object: Object<'_>) -> bool {
+    object.as_class().is_some() && object.as_executable().is_some()
+}
+
+/// Get the name of the class of a primitive value.
+fn primitive_class_name(value: &Value<'_>) -> Option<&'static str> {
+    match value {
+        Value::Undefined => Some("void"),
+        Value::Null => Some("null"),
+        Value::Bool(_) => Some("Boolean"),
+        Value::Number(n) if *n == (*n as i32) as f64 => Some("int"),
+        Value::Number(_) => Some("Number"),
+        Value::Unsigned(u) if *u <= i32::MAX as u32 => Some("int"),
+        Value::Unsigned(_) => Some("uint"),
+        Value::Integer(_) => Some("int"),
+        Value::String(_) => Some("String"),
+        Value::Object(_) => None,
+    }
+}
+
+/// Implements `flash.utils.getQualifiedClassName`
+pub fn get_qualified_class_name<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+    if let Some(name) = primitive_class_name(&value) {
+        return Ok(name.into());
+    }
+
+    let object = value.coerce_to_object(activation)?;
+    let class = match object.as_proto_class() {
+        Some(class) => class,
+        None => return Ok("Object".into()),
+    };
+    let name = class.read().name().to_qualified_name();
+
+    Ok(AvmString::new(activation.context.gc_context, name).into())
+}
+
+/// Implements `flash.utils.getQualifiedSuperclassName`
+pub fn get_qualified_superclass_name<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let class = match value {
+        Value::Object(object) => object.as_proto_class(),
+        Value::Undefined | Value::Null => None,
+        primitive => {
+            let name = primitive_class_name(&primitive).unwrap_or("Object");
+            let constr = activation
+                .avm2()
+                .global_domain()
+                .get_defined_value(activation, QName::new(Namespace::public(), name))?
+                .coerce_to_object(activation)?;
+
+            constr.as_class()
+        }
+    };
+
+    let super_name =
+        class.and_then(|class| class.read().super_class_name().as_ref().map(type_name));
+
+    Ok(match super_name {
+        Some(name) => AvmString::new(activation.context.gc_context, name).into(),
+        None => Value::Null,
+    })
+}
+
+/// Implements `flash.utils.getDefinitionByName`
+///
+/// Names may be given either as `package.Name` or `package::Name`.
+pub fn get_definition_by_name<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let name = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    let qname = QName::from_symbol_class(&name.replace("::", "."), activation.context.gc_context)
+        .ok_or_else(|| {
+        format!(
+            "ReferenceError: Error #1065: Variable {} is not defined.",
+            name
+        )
+    })?;
+
+    let domain = activation.domain();
+    if domain.get_defining_script(&qname.clone().into())?.is_none() {
+        return Err(format!(
+            "ReferenceError: Error #1065: Variable {} is not defined.",
+            name
+        )
+        .into());
+    }
+
+    domain.get_defined_value(activation, qname)
+}
+
+/// A single member of a type, as listed by `describeType`.
+struct MemberDescription {
+    /// The XML element that describes this member.
+    element: &'static str,
+    name: String,
+    access: Option<&'static str>,
+    type_name: Option<String>,
+    declared_by: Option<String>,
+}
+
+/// List the public members defined by a set of traits.
+///
+/// Classes should be provided from the most derived class upwards, so that
+/// overridden members are only listed once. Getters and setters for the same
+/// name are merged into a single accessor.
+fn describe_members<'gc>(
+    classes: &[GcCell<'gc, Class<'gc>>],
+    is_static: bool,
+) -> Vec<MemberDescription> {
+    let mut members: Vec<MemberDescription> = Vec::new();
+
+    for class in classes {
+        let class = class.read();
+        let class_name = class.name().to_qualified_name();
+        let traits = if is_static {
+            class.class_traits()
+        } else {
+            class.instance_traits()
+        };
+
+        for class_trait in traits {
+            if !class_trait.name().namespace().is_public() {
+                continue;
+            }
+
+            let name = class_trait.name().local_name().to_string();
+            let (element, access, member_type) = match class_trait.kind() {
+                TraitKind::Slot { type_name: t, .. } => ("variable", None, Some(type_name(t))),
+                TraitKind::Const { type_name: t, .. } => ("constant", None, Some(type_name(t))),
+                TraitKind::Getter { .. } => ("accessor", Some("readonly"), None),
+                TraitKind::Setter { .. } => ("accessor", Some("writeonly"), None),
+                TraitKind::Method { .. } | TraitKind::Function { .. } => ("method", None, None),
+                TraitKind::Class { .. } => continue,
+            };
+
+            if let Some(existing) = members.iter_mut().find(|m| m.name == name) {
+                if existing.element == "accessor"
+                    && element == "accessor"
+                    && existing.access != access
+                {
+                    existing.access = Some("readwrite");
+                }
+
+                continue;
+            }
+
+            let declared_by = match element {
+                "accessor" | "method" => Some(class_name.clone()),
+                _ => None,
+            };
+
+            members.push(MemberDescription {
+                element,
+                name,
+                access,
+                type_name: member_type,
+                declared_by,
+            });
+        }
+    }
+
+    members
+}
+
+/// Write the description of a type's inheritance and members.
+fn write_type_body<'gc, W: Write>(
+    writer: &mut Writer<W>,
+    classes: &[GcCell<'gc, Class<'gc>>],
+    is_static: bool,
+) -> Result<(), Error> {
+    let extends: Vec<String> = if is_static {
+        vec!["Class".to_string(), "Object".to_string()]
+    } else {
+        classes
+            .get(1..)
+            .unwrap_or(&[])
+            .iter()
+            .map(|class| class.read().name().to_qualified_name())
+            .collect()
+    };
+    for class_name in extends {
+        let mut element = BytesStart::borrowed_name(b"extendsClass");
+        element.push_attribute(("type", class_name.as_str()));
+        writer.write_event(Event::Empty(element))?;
+    }
+
+    let members = if is_static {
+        // Class objects also have the `prototype` accessor inherited from `Class`.
+        let mut members = describe_members(classes.get(..1).unwrap_or(&[]), true);
+        members.push(MemberDescription {
+            element: "accessor",
+            name: "prototype".to_string(),
+            access: Some("readonly"),
+            type_name: Some("*".to_string()),
+            declared_by: Some("Class".to_string()),
+        });
+        members
+    } else {
+        describe_members(classes, false)
+    };
+    for member in members {
+        let mut element = BytesStart::borrowed_name(member.element.as_bytes());
+        element.push_attribute(("name", member.name.as_str()));
+        if let Some(access) = member.access {
+            element.push_attribute(("access", access));
+        }
+        if let Some(type_name) = &member.type_name {
+            element.push_attribute(("type", type_name.as_str()));
+        }
+        if let Some(declared_by) = &member.declared_by {
+            element.push_attribute(("declaredBy", declared_by.as_str()));
+        }
+
+        writer.write_event(Event::Empty(element))?;
+    }
+
+    Ok(())
+}
+
+/// Implements `flash.utils.describeType`
+///
+/// Only public members are listed, and the types of methods and accessors are
+/// omitted, as builtin methods do not declare them.
+///
+/// TODO: Flash returns an `XML` object here, but the description is returned
+/// as a string of XML. Our XML tree stores attributes sorted by name, so
+/// parsing the description would reorder the attributes of every element.
+pub fn describe_type<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
+
+    let object = match value {
+        Value::Object(object) => Some(object),
+        Value::Undefined | Value::Null => None,
+        primitive => {
+            let name = primitive_class_name(&primitive).unwrap_or("Object");
+            let mut constr = activation
+                .avm2()
+                .global_domain()
+                .get_defined_value(activation, QName::new(Namespace::public(), name))?
+                .coerce_to_object(activation)?;
+
+            Some(
+                constr
+                    .get_property(
+                        constr,
+                        &QName::new(Namespace::public(), "prototype"),
+                        activation,
+                    )?
+                    .coerce_to_object(activation)?,
+            )
+        }
+    };
+
+    let mut element = BytesStart::borrowed_name(b"type");
+    let object = match object {
+        Some(object) => object,
+        None => {
+            element.push_attribute(("name", "null"));
+            element.push_attribute(("isDynamic", "false"));
+            element.push_attribute(("isFinal", "true"));
+            element.push_attribute(("isStatic", "false"));
+            writer.write_event(Event::Empty(element))?;
+
+            let xml = String::from_utf8(writer.into_inner().into_inner())?;
+            return Ok(AvmString::new(activation.context.gc_context, xml).into());
+        }
+    };
+
+    let is_static = is_class_constructor(object);
+    let classes = class_chain(activation, object)?;
+    let class = match classes.first() {
+        Some(class) => class.read(),
+        None => return Err("Cannot describe an object without a class".into()),
+    };
+
+    let name = class.name().to_qualified_name();
+    let base = if is_static {
+        Some("Class".to_string())
+    } else {
+        classes
+            .get(1)
+            .map(|super_class| super_class.read().name().to_qualified_name())
+    };
+    let (is_dynamic, is_final) = if is_static {
+        (true, true)
+    } else {
+        (!class.is_sealed(), class.is_final())
+    };
+
+    element.push_attribute(("name", name.as_str()));
+    if let Some(base) = &base {
+        element.push_attribute(("base", base.as_str()));
+    }
+    element.push_attribute(("isDynamic", if is_dynamic { "true" } else { "false" }));
+    element.push_attribute(("isFinal", if is_final { "true" } else { "false" }));
+    element.push_attribute(("isStatic", if is_static { "true" } else { "false" }));
+    drop(class);
+
+    writer.write_event(Event::Start(element))?;
+    write_type_body(&mut writer, &classes, is_static)?;
+
+    if is_static {
+        let mut factory = BytesStart::borrowed_name(b"factory");
+        factory.push_attribute(("type", name.as_str()));
+        writer.write_event(Event::Start(factory))?;
+        write_type_body(&mut writer, &classes, false)?;
+        writer.write_event(Event::End(BytesEnd::borrowed(b"factory")))?;
+    }
+
+    writer.write_event(Event::End(BytesEnd::borrowed(b"type")))?;
+
+    let xml = String::from_utf8(writer.into_inner().into_inner())?;
+
+    Ok(AvmString::new(activation.context.gc_context, xml).into())
+}
@@ -0,0 +1,949 @@
+//! `flash.utils.ByteArray` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::bytearray::{CompressionAlgorithm, Endian};
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::method::{Method, NativeMethod};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::string::AvmString;
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use encoding_rs::{Encoding, UTF_8};
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.utils.ByteArray`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.utils.ByteArray`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Look up the text encoding named by an ActionScript character set.
+///
+/// Unknown character sets fall back to UTF-8.
+fn encoding_for_charset(charset: &str) -> &'static Encoding {
+    Encoding::for_label(charset.as_bytes()).unwrap_or(UTF_8)
+}
+
+/// Implements `ByteArray.length`'s getter.
+pub fn length<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(bytearray) = this.as_bytearray() {
+            return Ok(bytearray.len().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.length`'s setter.
+pub fn set_length<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let len = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_u32(activation)?;
+
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.set_len(len as usize);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.position`'s getter.
+pub fn position<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(bytearray) = this.as_bytearray() {
+            return Ok(bytearray.position().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.position`'s setter.
+pub fn set_position<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let position = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_u32(activation)?;
+
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.set_position(position as usize);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.bytesAvailable`.
+pub fn bytes_available<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(bytearray) = this.as_bytearray() {
+            return Ok(bytearray.bytes_available().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.endian`'s getter.
+pub fn endian<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(bytearray) = this.as_bytearray() {
+            return Ok(bytearray.endian().name().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.endian`'s setter.
+pub fn set_endian<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let name = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    let endian = Endian::from_name(&name).ok_or(
+        "ArgumentError: Error #2008: Parameter endian must be one of the accepted values.",
+    )?;
+
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.set_endian(endian);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.objectEncoding`'s getter.
+pub fn object_encoding<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(bytearray) = this.as_bytearray() {
+            return Ok(bytearray.object_encoding().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.objectEncoding`'s setter.
+pub fn set_object_encoding<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let object_encoding = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_u32(activation)?;
+    if object_encoding != 0 && object_encoding != 3 {
+        return Err(
+            "ArgumentError: Error #2008: Parameter objectEncoding must be one of the accepted values."
+                .into(),
+        );
+    }
+
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.set_object_encoding(object_encoding);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.clear`.
+pub fn clear<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.clear();
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.readBoolean`.
+pub fn read_boolean<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            return Ok(bytearray.read_boolean()?.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.readByte`.
+pub fn read_byte<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            return Ok((bytearray.read_byte()? as i32).into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.readUnsignedByte`.
+pub fn read_unsigned_byte<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            return Ok(bytearray.read_unsigned_byte()?.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.readShort`.
+pub fn read_short<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            return Ok(bytearray.read_short()?.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.readUnsignedShort`.
+pub fn read_unsigned_short<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            return Ok(bytearray.read_unsigned_short()?.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.readInt`.
+pub fn read_int<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            return Ok(bytearray.read_int()?.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.readUnsignedInt`.
+pub fn read_unsigned_int<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            return Ok(bytearray.read_unsigned_int()?.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.readFloat`.
+pub fn read_float<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            return Ok(bytearray.read_float()?.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.readDouble`.
+pub fn read_double<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            return Ok(bytearray.read_double()?.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.readUTF`.
+pub fn read_utf<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let string = match this
+        .as_ref()
+        .and_then(|this| this.as_bytearray_mut(activation.context.gc_context))
+    {
+        Some(mut bytearray) => bytearray.read_utf()?,
+        None => return Ok(Value::Undefined),
+    };
+
+    Ok(AvmString::new(activation.context.gc_context, string).into())
+}
+
+/// Implements `ByteArray.readUTFBytes`.
+pub fn read_utf_bytes<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let len = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_u32(activation)?;
+
+    let string = match this
+        .as_ref()
+        .and_then(|this| this.as_bytearray_mut(activation.context.gc_context))
+    {
+        Some(mut bytearray) => bytearray.read_utf_bytes(len as usize)?,
+        None => return Ok(Value::Undefined),
+    };
+
+    Ok(AvmString::new(activation.context.gc_context, string).into())
+}
+
+/// Implements `ByteArray.readMultiByte`.
+pub fn read_multi_byte<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let len = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_u32(activation)?;
+    let charset = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    let encoding = encoding_for_charset(&charset);
+
+    let string = match this
+        .as_ref()
+        .and_then(|this| this.as_bytearray_mut(activation.context.gc_context))
+    {
+        Some(mut bytearray) => {
+            let bytes = bytearray.read_bytes(len as usize)?;
+            let bytes = match bytes.iter().position(|b| *b == 0) {
+                Some(null) => &bytes[..null],
+                None => bytes,
+            };
+
+            encoding.decode_without_bom_handling(bytes).0.into_owned()
+        }
+        None => return Ok(Value::Undefined),
+    };
+
+    Ok(AvmString::new(activation.context.gc_context, string).into())
+}
+
+/// Implements `ByteArray.readBytes`.
+///
+/// Bytes are read from this array's position and written into the target
+/// array at the given offset, without changing the target's position.
+pub fn read_bytes<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let target = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?;
+    let offset = args
+        .get(1)
+        .cloned()
+        .unwrap_or_else(|| 0.into())
+        .coerce_to_u32(activation)? as usize;
+    let len = args
+        .get(2)
+        .cloned()
+        .unwrap_or_else(|| 0.into())
+        .coerce_to_u32(activation)? as usize;
+
+    // The target may be this very array, so the bytes are copied out before
+    // writing them back.
+    let bytes = match this
+        .as_ref()
+        .and_then(|this| this.as_bytearray_mut(activation.context.gc_context))
+    {
+        Some(mut bytearray) => {
+            let len = if len == 0 {
+                bytearray.bytes_available()
+            } else {
+                len
+            };
+
+            bytearray.read_bytes(len)?.to_vec()
+        }
+        None => return Ok(Value::Undefined),
+    };
+
+    let mut target = target
+        .as_bytearray_mut(activation.context.gc_context)
+        .ok_or("TypeError: Error #1034: Type Coercion failed: cannot convert to ByteArray.")?;
+    let position = target.position();
+    target.set_position(offset);
+    target.write_bytes(&bytes);
+    target.set_position(position);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.writeBoolean`.
+pub fn write_boolean<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_boolean();
+
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.write_boolean(value);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.writeByte`.
+pub fn write_byte<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(activation)?;
+
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.write_byte(value as u8);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.writeShort`.
+pub fn write_short<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(activation)?;
+
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.write_short(value as i16);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.writeInt`.
+pub fn write_int<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(activation)?;
+
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.write_int(value);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.writeUnsignedInt`.
+pub fn write_unsigned_int<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_u32(activation)?;
+
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.write_unsigned_int(value);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.writeFloat`.
+pub fn write_float<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_number(activation)?;
+
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.write_float(value as f32);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.writeDouble`.
+pub fn write_double<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_number(activation)?;
+
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.write_double(value);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.writeUTF`.
+pub fn write_utf<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.write_utf(&value)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.writeUTFBytes`.
+pub fn write_utf_bytes<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.write_bytes(value.as_bytes());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.writeMultiByte`.
+pub fn write_multi_byte<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let value = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    let charset = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(activation)?;
+    let encoding = encoding_for_charset(&charset);
+
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.write_bytes(&encoding.encode(&value).0);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.writeBytes`.
+///
+/// Bytes are read from the source array, starting at the given offset, and
+/// written at this array's position.
+pub fn write_bytes<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let source = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?;
+    let offset = args
+        .get(1)
+        .cloned()
+        .unwrap_or_else(|| 0.into())
+        .coerce_to_u32(activation)? as usize;
+    let len = args
+        .get(2)
+        .cloned()
+        .unwrap_or_else(|| 0.into())
+        .coerce_to_u32(activation)? as usize;
+
+    // The source may be this very array, so the bytes are copied out before
+    // writing them back.
+    let bytes = {
+        let source = source
+            .as_bytearray()
+            .ok_or("TypeError: Error #1034: Type Coercion failed: cannot convert to ByteArray.")?;
+        let available = source.len().saturating_sub(offset);
+        let len = if len == 0 { available } else { len };
+        if offset > source.len() || len > available {
+            return Err("RangeError: Error #2006: The supplied index is out of bounds.".into());
+        }
+
+        source.bytes()[offset..offset + len].to_vec()
+    };
+
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.write_bytes(&bytes);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Parse the compression algorithm argument of `compress` and `uncompress`.
+fn algorithm_arg<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<CompressionAlgorithm, Error> {
+    let name = args
+        .get(0)
+        .cloned()
+        .unwrap_or_else(|| "zlib".into())
+        .coerce_to_string(activation)?;
+
+    CompressionAlgorithm::from_name(&name).ok_or_else(|| {
+        "ArgumentError: Error #2008: Parameter algorithm must be one of the accepted values.".into()
+    })
+}
+
+/// Implements `ByteArray.compress`.
+pub fn compress<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let algorithm = algorithm_arg(activation, args)?;
+
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.compress(algorithm)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.uncompress`.
+pub fn uncompress<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    let algorithm = algorithm_arg(activation, args)?;
+
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.uncompress(algorithm)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.deflate`.
+pub fn deflate<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.compress(CompressionAlgorithm::Deflate)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.inflate`.
+pub fn inflate<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(mut bytearray) = this.as_bytearray_mut(activation.context.gc_context) {
+            bytearray.uncompress(CompressionAlgorithm::Deflate)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `ByteArray.toString`.
+///
+/// The entire array is decoded as UTF-8, regardless of its position.
+pub fn to_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(bytearray) = this.as_bytearray() {
+            let string = UTF_8.decode(bytearray.bytes()).0.into_owned();
+
+            return Ok(AvmString::new(activation.context.gc_context, string).into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `ByteArray`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.utils"), "ByteArray"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init),
+        Method::from_builtin(class_init),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    write.define_instance_trait(Trait::from_getter(
+        QName::new(Namespace::public(), "length"),
+        Method::from_builtin(length),
+    ));
+    write.define_instance_trait(Trait::from_setter(
+        QName::new(Namespace::public(), "length"),
+        Method::from_builtin(set_length),
+    ));
+    write.define_instance_trait(Trait::from_getter(
+        QName::new(Namespace::public(), "position"),
+        Method::from_builtin(position),
+    ));
+    write.define_instance_trait(Trait::from_setter(
+        QName::new(Namespace::public(), "position"),
+        Method::from_builtin(set_position),
+    ));
+    write.define_instance_trait(Trait::from_getter(
+        QName::new(Namespace::public(), "bytesAvailable"),
+        Method::from_builtin(bytes_available),
+    ));
+    write.define_instance_trait(Trait::from_getter(
+        QName::new(Namespace::public(), "endian"),
+        Method::from_builtin(endian),
+    ));
+    write.define_instance_trait(Trait::from_setter(
+        QName::new(Namespace::public(), "endian"),
+        Method::from_builtin(set_endian),
+    ));
+    write.define_instance_trait(Trait::from_getter(
+        QName::new(Namespace::public(), "objectEncoding"),
+        Method::from_builtin(object_encoding),
+    ));
+    write.define_instance_trait(Trait::from_setter(
+        QName::new(Namespace::public(), "objectEncoding"),
+        Method::from_builtin(set_object_encoding),
+    ));
+
+    let methods: &[(&str, NativeMethod<'gc>)] = &[
+        ("clear", clear),
+        ("readBoolean", read_boolean),
+        ("readByte", read_byte),
+        ("readUnsignedByte", read_unsigned_byte),
+        ("readShort", read_short),
+        ("readUnsignedShort", read_unsigned_short),
+        ("readInt", read_int),
+        ("readUnsignedInt", read_unsigned_int),
+        ("readFloat", read_float),
+        ("readDouble", read_double),
+        ("readUTF", read_utf),
+        ("readUTFBytes", read_utf_bytes),
+        ("readMultiByte", read_multi_byte),
+        ("readBytes", read_bytes),
+        ("writeBoolean", write_boolean),
+        ("writeByte", write_byte),
+        ("writeShort", write_short),
+        ("writeInt", write_int),
+        ("writeUnsignedInt", write_unsigned_int),
+        ("writeFloat", write_float),
+        ("writeDouble", write_double),
+        ("writeUTF", write_utf),
+        ("writeUTFBytes", write_utf_bytes),
+        ("writeMultiByte", write_multi_byte),
+        ("writeBytes", write_bytes),
+        ("compress", compress),
+        ("uncompress", uncompress),
+        ("deflate", deflate),
+        ("inflate", inflate),
+        ("toString", to_string),
+    ];
+    for (name, method) in methods {
+        write.define_instance_trait(Trait::from_method(
+            QName::new(Namespace::public(), *name),
+            Method::from_builtin(*method),
+        ));
+    }
+
+    class
+}
@@ -0,0 +1,51 @@
+//! `flash.utils.Dictionary` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::Object;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.utils.Dictionary`'s instance constructor.
+///
+/// `weakKeys` is not supported: our garbage collector has no weak references,
+/// so keys are always held strongly and a dictionary keeps its keys alive.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+
+        let weak_keys = args.get(0).map(|weak_keys| weak_keys.coerce_to_boolean());
+        if weak_keys == Some(true) {
+            log::warn!("Dictionary: weakKeys is not supported, keys will be held strongly");
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.utils.Dictionary`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Construct `Dictionary`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    Class::new(
+        QName::new(Namespace::package("flash.utils"), "Dictionary"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init),
+        Method::from_builtin(class_init),
+        mc,
+    )
+}
@@ -0,0 +1,389 @@
+//! `flash.utils.Timer` builtin/prototype
+
+use crate::avm1::TimerCallback;
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::events::Event;
+use crate::avm2::globals::flash::events::eventdispatcher::dispatch_event;
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{EventObject, Object, TObject};
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+
+const NS_TIMER: &str = "https://ruffle.rs/AS3/impl/Timer/";
+
+/// Coerce a timer delay, rejecting values that the timer cannot run with.
+fn coerce_delay<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<f64, Error> {
+    let delay = value.coerce_to_number(activation)?;
+    if delay < 0.0 || !delay.is_finite() {
+        return Err("RangeError: Error #2066: The Timer delay specified is out of range.".into());
+    }
+
+    Ok(delay)
+}
+
+/// Implements `flash.utils.Timer`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(mut this) = this {
+        activation.super_init(this, &[])?;
+
+        let delay = coerce_delay(activation, args.get(0).cloned().unwrap_or(Value::Undefined))?;
+        let repeat_count = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Integer(0))
+            .coerce_to_i32(activation)?;
+
+        this.init_property(
+            this,
+            &QName::new(Namespace::private(NS_TIMER), "delay"),
+            delay.into(),
+            activation,
+        )?;
+        this.init_property(
+            this,
+            &QName::new(Namespace::private(NS_TIMER), "repeatCount"),
+            repeat_count.into(),
+            activation,
+        )?;
+        this.init_property(
+            this,
+            &QName::new(Namespace::private(NS_TIMER), "currentCount"),
+            0.into(),
+            activation,
+        )?;
+        this.init_property(
+            this,
+            &QName::new(Namespace::private(NS_TIMER), "timerId"),
+            Value::Null,
+            activation,
+        )?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.utils.Timer`'s class constructor.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Retrieve one of the timer's internal properties.
+fn get_internal<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    mut this: Object<'gc>,
+    name: &'static str,
+) -> Result<Value<'gc>, Error> {
+    this.get_property(
+        this,
+        &QName::new(Namespace::private(NS_TIMER), name),
+        activation,
+    )
+}
+
+/// Change one of the timer's internal properties.
+fn set_internal<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    mut this: Object<'gc>,
+    name: &'static str,
+    value: Value<'gc>,
+) -> Result<(), Error> {
+    this.set_property(
+        this,
+        &QName::new(Namespace::private(NS_TIMER), name),
+        value,
+        activation,
+    )
+}
+
+/// Retrieve the ID of the player timer driving this object, if it is running.
+fn timer_id<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<Option<i32>, Error> {
+    match get_internal(activation, this, "timerId")? {
+        Value::Null | Value::Undefined => Ok(None),
+        id => Ok(Some(id.coerce_to_i32(activation)?)),
+    }
+}
+
+/// Schedule this timer with the player, unless it is already running.
+fn start_timer<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<(), Error> {
+    if timer_id(activation, this)?.is_some() {
+        return Ok(());
+    }
+
+    let delay = get_internal(activation, this, "delay")?.coerce_to_number(activation)?;
+    let id = activation.context.timers.add_timer(
+        TimerCallback::Avm2Callback(this),
+        delay.min(i32::MAX as f64) as i32,
+        Vec::new(),
+        false,
+    );
+
+    set_internal(activation, this, "timerId", id.into())
+}
+
+/// Remove this timer from the player's schedule, if it is running.
+fn stop_timer<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<(), Error> {
+    if let Some(id) = timer_id(activation, this)? {
+        activation.context.timers.remove(id);
+    }
+
+    set_internal(activation, this, "timerId", Value::Null)
+}
+
+/// Construct a `TimerEvent` of the given type.
+fn timer_event<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    event_type: &'static str,
+) -> Object<'gc> {
+    let proto = activation
+        .avm2()
+        .system_prototypes
+        .as_ref()
+        .unwrap()
+        .timerevent;
+
+    EventObject::from_event(
+        activation.context.gc_context,
+        Some(proto),
+        Event::new(event_type),
+    )
+}
+
+/// Run a single tick of a running timer.
+///
+/// This advances the timer's count and dispatches `timer` to it. Once the
+/// timer reaches its repeat count, it stops and dispatches `timerComplete`.
+pub fn tick<'gc>(activation: &mut Activation<'_, 'gc, '_>, this: Object<'gc>) -> Result<(), Error> {
+    if timer_id(activation, this)?.is_none() {
+        return Ok(());
+    }
+
+    let current_count =
+        get_internal(activation, this, "currentCount")?.coerce_to_i32(activation)?;
+    let current_count = current_count.wrapping_add(1);
+    set_internal(activation, this, "currentCount", current_count.into())?;
+
+    let repeat_count = get_internal(activation, this, "repeatCount")?.coerce_to_i32(activation)?;
+    let is_complete = repeat_count > 0 && current_count >= repeat_count;
+    if is_complete {
+        stop_timer(activation, this)?;
+    }
+
+    let event = timer_event(activation, "timer");
+    dispatch_event(activation, Some(this), &[event.into()])?;
+
+    if is_complete {
+        let event = timer_event(activation, "timerComplete");
+        dispatch_event(activation, Some(this), &[event.into()])?;
+    }
+
+    Ok(())
+}
+
+/// Implements `Timer.start`.
+pub fn start<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        start_timer(activation, this)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Timer.stop`.
+pub fn stop<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        stop_timer(activation, this)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Timer.reset`.
+pub fn reset<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        stop_timer(activation, this)?;
+        set_internal(activation, this, "currentCount", 0.into())?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Timer.running`.
+pub fn running<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return Ok(timer_id(activation, this)?.is_some().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Timer.currentCount`.
+pub fn current_count<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return get_internal(activation, this, "currentCount");
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Timer.delay`'s getter.
+pub fn delay<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return get_internal(activation, this, "delay");
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Timer.delay`'s setter.
+///
+/// A running timer is rescheduled to use the new delay.
+pub fn set_delay<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let delay = coerce_delay(activation, args.get(0).cloned().unwrap_or(Value::Undefined))?;
+        set_internal(activation, this, "delay", delay.into())?;
+
+        if timer_id(activation, this)?.is_some() {
+            stop_timer(activation, this)?;
+            start_timer(activation, this)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Timer.repeatCount`'s getter.
+pub fn repeat_count<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        return get_internal(activation, this, "repeatCount");
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Timer.repeatCount`'s setter.
+pub fn set_repeat_count<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let repeat_count = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation)?;
+        set_internal(activation, this, "repeatCount", repeat_count.into())?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `Timer`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.utils"), "Timer"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::from_builtin(instance_init),
+        Method::from_builtin(class_init),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::SEALED);
+
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::public(), "start"),
+        Method::from_builtin(start),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::public(), "stop"),
+        Method::from_builtin(stop),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::public(), "reset"),
+        Method::from_builtin(reset),
+    ));
+    write.define_instance_trait(Trait::from_getter(
+        QName::new(Namespace::public(), "running"),
+        Method::from_builtin(running),
+    ));
+    write.define_instance_trait(Trait::from_getter(
+        QName::new(Namespace::public(), "currentCount"),
+        Method::from_builtin(current_count),
+    ));
+    write.define_instance_trait(Trait::from_getter(
+        QName::new(Namespace::public(), "delay"),
+        Method::from_builtin(delay),
+    ));
+    write.define_instance_trait(Trait::from_setter(
+        QName::new(Namespace::public(), "delay"),
+        Method::from_builtin(set_delay),
+    ));
+    write.define_instance_trait(Trait::from_getter(
+        QName::new(Namespace::public(), "repeatCount"),
+        Method::from_builtin(repeat_count),
+    ));
+    write.define_instance_trait(Trait::from_setter(
+        QName::new(Namespace::public(), "repeatCount"),
+        Method::from_builtin(set_repeat_count),
+    ));
+
+    class
+}
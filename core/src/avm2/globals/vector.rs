@@ -0,0 +1,945 @@
+//! `Vector` class
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{Class, ClassAttributes};
+use crate::avm2::globals::array::{
+    compare_numeric, compare_string_case_insensitive, compare_string_case_sensitive, constrain,
+    resolve_index, sort_inner, ArrayIter, SortOptions,
+};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject, VectorObject};
+use crate::avm2::string::AvmString;
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::vector::VectorStorage;
+use crate::avm2::Error;
+use gc_arena::{GcCell, MutationContext};
+use std::cmp::{min, Ordering};
+
+/// Implements `Vector`'s instance initializer.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+
+        let length = args
+            .get(0)
+            .cloned()
+            .unwrap_or_else(|| 0.into())
+            .coerce_to_u32(activation)? as usize;
+        let is_fixed = args
+            .get(1)
+            .cloned()
+            .unwrap_or_else(|| false.into())
+            .coerce_to_boolean();
+
+        if let Some(mut vector) = this.as_vector_storage_mut(activation.context.gc_context) {
+            let mut new_vector = vector.empty_like();
+
+            new_vector.set_length(length)?;
+            new_vector.set_is_fixed(is_fixed);
+
+            *vector = new_vector;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector`'s class initializer.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Wrap vector storage in a new vector object of the same type as `this`.
+fn build_vector<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    vector: VectorStorage<'gc>,
+) -> Result<Value<'gc>, Error> {
+    let proto = this.proto().ok_or(
+        "TypeError: Error #1009: Cannot access a property or method of a null object reference.",
+    )?;
+
+    Ok(VectorObject::from_vector(vector, proto, activation.context.gc_context).into())
+}
+
+/// Copy the values of a vector out of its storage.
+///
+/// Most vector methods that run user code or coerce values need to release
+/// their borrow on the vector first, as that code may access the vector.
+fn vector_values<'gc>(this: Object<'gc>) -> Option<Vec<Value<'gc>>> {
+    this.as_vector_storage().map(|v| v.iter().collect())
+}
+
+/// Coerce a list of values to the value type of a vector.
+fn coerce_values<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Object<'gc>,
+    values: &[Value<'gc>],
+) -> Result<Vec<Value<'gc>>, Error> {
+    let value_type = this.as_vector_storage().and_then(|v| v.value_type());
+    let mut coerced = Vec::with_capacity(values.len());
+
+    for value in values {
+        coerced.push(VectorStorage::coerce(
+            activation,
+            value_type,
+            value.clone(),
+        )?);
+    }
+
+    Ok(coerced)
+}
+
+/// Implements `Vector.length`'s getter
+pub fn length<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(vector) = this.as_vector_storage() {
+            return Ok((vector.length() as u32).into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.length`'s setter
+pub fn set_length<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let new_length = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_u32(activation)? as usize;
+
+        if let Some(mut vector) = this.as_vector_storage_mut(activation.context.gc_context) {
+            vector.set_length(new_length)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.fixed`'s getter
+pub fn fixed<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(vector) = this.as_vector_storage() {
+            return Ok(vector.is_fixed().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.fixed`'s setter
+pub fn set_fixed<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let is_fixed = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_boolean();
+
+        if let Some(mut vector) = this.as_vector_storage_mut(activation.context.gc_context) {
+            vector.set_is_fixed(is_fixed);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.concat`
+pub fn concat<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(values) = vector_values(this) {
+            let mut new_vector = this.as_vector_storage().unwrap().empty_like();
+
+            for value in values {
+                new_vector.push(value)?;
+            }
+
+            for arg in args {
+                let other_values = arg
+                    .coerce_to_object(activation)
+                    .ok()
+                    .and_then(vector_values)
+                    .ok_or(
+                        "TypeError: Error #1034: Type Coercion failed: cannot convert value to Vector.",
+                    )?;
+
+                for value in coerce_values(activation, this, &other_values)? {
+                    new_vector.push(value)?;
+                }
+            }
+
+            return build_vector(activation, this, new_vector);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Join the values of a vector together with a separator.
+///
+/// Each value is first passed through `conv`, then coerced to a string.
+fn join_inner<'gc, 'a, 'ctxt, C>(
+    activation: &mut Activation<'a, 'gc, 'ctxt>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+    mut conv: C,
+) -> Result<Value<'gc>, Error>
+where
+    C: for<'b> FnMut(Value<'gc>, &'b mut Activation<'a, 'gc, 'ctxt>) -> Result<Value<'gc>, Error>,
+{
+    let mut separator = args.get(0).cloned().unwrap_or(Value::Undefined);
+    if separator == Value::Undefined {
+        separator = ",".into();
+    }
+
+    if let Some(values) = this.and_then(vector_values) {
+        let string_separator = separator.coerce_to_string(activation)?;
+        let mut accum = Vec::with_capacity(values.len());
+
+        for item in values {
+            if matches!(item, Value::Undefined) || matches!(item, Value::Null) {
+                accum.push(item.coerce_to_string(activation)?.to_string());
+            } else {
+                accum.push(
+                    conv(item, activation)?
+                        .coerce_to_string(activation)?
+                        .to_string(),
+                );
+            }
+        }
+
+        return Ok(
+            AvmString::new(activation.context.gc_context, accum.join(&string_separator)).into(),
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.join`
+pub fn join<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    join_inner(activation, this, args, |v, _act| Ok(v))
+}
+
+/// Implements `Vector.toString`
+pub fn to_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    join_inner(activation, this, &[",".into()], |v, _act| Ok(v))
+}
+
+/// Implements `Vector.toLocaleString`
+pub fn to_locale_string<'gc>(
+    act: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    join_inner(act, this, &[",".into()], |v, activation| {
+        let mut o = v.coerce_to_object(activation)?;
+
+        let tls = o.get_property(
+            o,
+            &QName::new(Namespace::public(), "toLocaleString"),
+            activation,
+        )?;
+
+        tls.coerce_to_object(activation)?
+            .call(Some(o), &[], activation, o.proto())
+    })
+}
+
+/// Extract the callback and receiver arguments of an iteration method.
+fn callback_args<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<(Object<'gc>, Option<Object<'gc>>), Error> {
+    let callback = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_object(activation)?;
+    let receiver = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Null)
+        .coerce_to_object(activation)
+        .ok();
+
+    Ok((callback, receiver))
+}
+
+/// Implements `Vector.every`
+pub fn every<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let (callback, receiver) = callback_args(activation, args)?;
+        let mut iter = ArrayIter::new(activation, this)?;
+
+        while let Some(r) = iter.next(activation) {
+            let (i, item) = r?;
+
+            let result = callback
+                .call(
+                    receiver,
+                    &[item, i.into(), this.into()],
+                    activation,
+                    receiver.and_then(|r| r.proto()),
+                )?
+                .coerce_to_boolean();
+
+            if !result {
+                return Ok(false.into());
+            }
+        }
+
+        return Ok(true.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.some`
+pub fn some<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let (callback, receiver) = callback_args(activation, args)?;
+        let mut iter = ArrayIter::new(activation, this)?;
+
+        while let Some(r) = iter.next(activation) {
+            let (i, item) = r?;
+
+            let result = callback
+                .call(
+                    receiver,
+                    &[item, i.into(), this.into()],
+                    activation,
+                    receiver.and_then(|r| r.proto()),
+                )?
+                .coerce_to_boolean();
+
+            if result {
+                return Ok(true.into());
+            }
+        }
+
+        return Ok(false.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.forEach`
+pub fn for_each<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let (callback, receiver) = callback_args(activation, args)?;
+        let mut iter = ArrayIter::new(activation, this)?;
+
+        while let Some(r) = iter.next(activation) {
+            let (i, item) = r?;
+
+            callback.call(
+                receiver,
+                &[item, i.into(), this.into()],
+                activation,
+                receiver.and_then(|r| r.proto()),
+            )?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.map`
+pub fn map<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let (callback, receiver) = callback_args(activation, args)?;
+        let value_type = this.as_vector_storage().and_then(|v| v.value_type());
+        let mut new_values = Vec::new();
+        let mut iter = ArrayIter::new(activation, this)?;
+
+        while let Some(r) = iter.next(activation) {
+            let (i, item) = r?;
+            let new_item = callback.call(
+                receiver,
+                &[item, i.into(), this.into()],
+                activation,
+                receiver.and_then(|r| r.proto()),
+            )?;
+
+            new_values.push(VectorStorage::coerce(activation, value_type, new_item)?);
+        }
+
+        if let Some(mut new_vector) = this.as_vector_storage().map(|v| v.empty_like()) {
+            new_vector.replace_storage(new_values);
+
+            return build_vector(activation, this, new_vector);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.filter`
+pub fn filter<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let (callback, receiver) = callback_args(activation, args)?;
+        let mut new_values = Vec::new();
+        let mut iter = ArrayIter::new(activation, this)?;
+
+        while let Some(r) = iter.next(activation) {
+            let (i, item) = r?;
+
+            let is_allowed = callback
+                .call(
+                    receiver,
+                    &[item.clone(), i.into(), this.into()],
+                    activation,
+                    receiver.and_then(|r| r.proto()),
+                )?
+                .coerce_to_boolean();
+
+            if is_allowed {
+                new_values.push(item);
+            }
+        }
+
+        if let Some(mut new_vector) = this.as_vector_storage().map(|v| v.empty_like()) {
+            new_vector.replace_storage(new_values);
+
+            return build_vector(activation, this, new_vector);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.indexOf`
+pub fn index_of<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(values) = vector_values(this) {
+            let search_val = args.get(0).cloned().unwrap_or(Value::Undefined);
+            let from = resolve_index(
+                activation,
+                args.get(1).cloned().unwrap_or_else(|| 0.into()),
+                values.len(),
+            )?;
+
+            for (i, val) in values.iter().enumerate().skip(from) {
+                if *val == search_val {
+                    return Ok((i as i32).into());
+                }
+            }
+
+            return Ok((-1).into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.lastIndexOf`
+pub fn last_index_of<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(values) = vector_values(this) {
+            let search_val = args.get(0).cloned().unwrap_or(Value::Undefined);
+            let from = resolve_index(
+                activation,
+                args.get(1).cloned().unwrap_or_else(|| i32::MAX.into()),
+                values.len(),
+            )?;
+
+            for (i, val) in values.iter().enumerate().rev() {
+                if i <= from && *val == search_val {
+                    return Ok((i as i32).into());
+                }
+            }
+
+            return Ok((-1).into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.pop`
+pub fn pop<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(mut vector) = this.as_vector_storage_mut(activation.context.gc_context) {
+            return vector.pop();
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.push`
+pub fn push<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let values = coerce_values(activation, this, args)?;
+
+        if let Some(mut vector) = this.as_vector_storage_mut(activation.context.gc_context) {
+            for value in values {
+                vector.push(value)?;
+            }
+
+            return Ok((vector.length() as u32).into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.reverse`
+pub fn reverse<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(mut vector) = this.as_vector_storage_mut(activation.context.gc_context) {
+            vector.reverse();
+
+            return Ok(this.into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.shift`
+pub fn shift<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(mut vector) = this.as_vector_storage_mut(activation.context.gc_context) {
+            return vector.shift();
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.unshift`
+pub fn unshift<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let values = coerce_values(activation, this, args)?;
+
+        if let Some(mut vector) = this.as_vector_storage_mut(activation.context.gc_context) {
+            for value in values.into_iter().rev() {
+                vector.insert(0, value)?;
+            }
+
+            return Ok((vector.length() as u32).into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.insertAt`
+pub fn insert_at<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(length) = this.as_vector_storage().map(|v| v.length()) {
+            let index = resolve_index(
+                activation,
+                args.get(0).cloned().unwrap_or_else(|| 0.into()),
+                length,
+            )?;
+            let value = coerce_values(
+                activation,
+                this,
+                &[args.get(1).cloned().unwrap_or(Value::Undefined)],
+            )?
+            .remove(0);
+
+            if let Some(mut vector) = this.as_vector_storage_mut(activation.context.gc_context) {
+                vector.insert(index, value)?;
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.removeAt`
+pub fn remove_at<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(length) = this.as_vector_storage().map(|v| v.length()) {
+            let index = resolve_index(
+                activation,
+                args.get(0).cloned().unwrap_or_else(|| 0.into()),
+                length,
+            )?;
+
+            if let Some(mut vector) = this.as_vector_storage_mut(activation.context.gc_context) {
+                return vector.remove(index);
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.slice`
+pub fn slice<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(values) = vector_values(this) {
+            let actual_start = resolve_index(
+                activation,
+                args.get(0).cloned().unwrap_or_else(|| 0.into()),
+                values.len(),
+            )?;
+            let actual_end = resolve_index(
+                activation,
+                args.get(1).cloned().unwrap_or_else(|| 0xFFFFFF.into()),
+                values.len(),
+            )?;
+            let actual_end = min(actual_end, values.len());
+            let actual_start = min(actual_start, actual_end);
+
+            let mut new_vector = this.as_vector_storage().unwrap().empty_like();
+            new_vector.replace_storage(values[actual_start..actual_end].to_vec());
+
+            return build_vector(activation, this, new_vector);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.splice`
+pub fn splice<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(length) = this.as_vector_storage().map(|v| v.length()) {
+            let actual_start = min(
+                resolve_index(
+                    activation,
+                    args.get(0).cloned().unwrap_or_else(|| 0.into()),
+                    length,
+                )?,
+                length,
+            );
+            let delete_count = args
+                .get(1)
+                .cloned()
+                .unwrap_or_else(|| (length as u32).into())
+                .coerce_to_u32(activation)? as usize;
+            let actual_end = min(length, actual_start.saturating_add(delete_count));
+            let replace_with = if args.len() > 2 {
+                coerce_values(activation, this, &args[2..])?
+            } else {
+                Vec::new()
+            };
+
+            let removed = if let Some(mut vector) =
+                this.as_vector_storage_mut(activation.context.gc_context)
+            {
+                vector.splice(actual_start..actual_end, replace_with)?
+            } else {
+                return Ok(Value::Undefined);
+            };
+
+            let mut removed_vector = this.as_vector_storage().unwrap().empty_like();
+            removed_vector.replace_storage(removed);
+
+            return build_vector(activation, this, removed_vector);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Vector.sort`
+///
+/// Vectors accept either a comparison function or a set of `Array` sort
+/// options, but not both.
+pub fn sort<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        let sort_behavior = args.get(0).cloned().unwrap_or_else(|| 0.into());
+        let (compare_fnc, options) = match sort_behavior {
+            Value::Object(o) if o.as_executable().is_some() => (Some(o), SortOptions::empty()),
+            _ => (
+                None,
+                SortOptions::from_bits_truncate(sort_behavior.coerce_to_u32(activation)? as u8),
+            ),
+        };
+
+        let mut values = if let Some(values) = vector_values(this) {
+            values
+                .into_iter()
+                .enumerate()
+                .collect::<Vec<(usize, Value<'gc>)>>()
+        } else {
+            return Ok(Value::Undefined);
+        };
+
+        let unique_satisfied = if let Some(v) = compare_fnc {
+            sort_inner(
+                activation,
+                &mut values,
+                options,
+                constrain(|activation, a, b| {
+                    let order = v
+                        .call(None, &[a, b], activation, None)?
+                        .coerce_to_number(activation)?;
+
+                    if order > 0.0 {
+                        Ok(Ordering::Greater)
+                    } else if order < 0.0 {
+                        Ok(Ordering::Less)
+                    } else {
+                        Ok(Ordering::Equal)
+                    }
+                }),
+            )?
+        } else if options.contains(SortOptions::NUMERIC) {
+            sort_inner(activation, &mut values, options, compare_numeric)?
+        } else if options.contains(SortOptions::CASE_INSENSITIVE) {
+            sort_inner(
+                activation,
+                &mut values,
+                options,
+                compare_string_case_insensitive,
+            )?
+        } else {
+            sort_inner(
+                activation,
+                &mut values,
+                options,
+                compare_string_case_sensitive,
+            )?
+        };
+
+        if !unique_satisfied {
+            return Ok(0.into());
+        }
+
+        if let Some(mut vector) = this.as_vector_storage_mut(activation.context.gc_context) {
+            vector.replace_storage(values.into_iter().map(|(_i, v)| v).collect());
+        }
+
+        return Ok(this.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `Vector`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("__AS3__.vec"), "Vector"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init),
+        Method::from_builtin(class_init),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    write.set_attributes(ClassAttributes::FINAL | ClassAttributes::GENERIC);
+
+    write.define_instance_trait(Trait::from_getter(
+        QName::new(Namespace::public(), "length"),
+        Method::from_builtin(length),
+    ));
+    write.define_instance_trait(Trait::from_setter(
+        QName::new(Namespace::public(), "length"),
+        Method::from_builtin(set_length),
+    ));
+    write.define_instance_trait(Trait::from_getter(
+        QName::new(Namespace::public(), "fixed"),
+        Method::from_builtin(fixed),
+    ));
+    write.define_instance_trait(Trait::from_setter(
+        QName::new(Namespace::public(), "fixed"),
+        Method::from_builtin(set_fixed),
+    ));
+
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::public(), "toString"),
+        Method::from_builtin(to_string),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::public(), "toLocaleString"),
+        Method::from_builtin(to_locale_string),
+    ));
+
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "concat"),
+        Method::from_builtin(concat),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "every"),
+        Method::from_builtin(every),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "filter"),
+        Method::from_builtin(filter),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "forEach"),
+        Method::from_builtin(for_each),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "indexOf"),
+        Method::from_builtin(index_of),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "insertAt"),
+        Method::from_builtin(insert_at),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "join"),
+        Method::from_builtin(join),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "lastIndexOf"),
+        Method::from_builtin(last_index_of),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "map"),
+        Method::from_builtin(map),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "pop"),
+        Method::from_builtin(pop),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "push"),
+        Method::from_builtin(push),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "removeAt"),
+        Method::from_builtin(remove_at),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "reverse"),
+        Method::from_builtin(reverse),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "shift"),
+        Method::from_builtin(shift),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "slice"),
+        Method::from_builtin(slice),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "some"),
+        Method::from_builtin(some),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "sort"),
+        Method::from_builtin(sort),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "splice"),
+        Method::from_builtin(splice),
+    ));
+    write.define_instance_trait(Trait::from_method(
+        QName::new(Namespace::as3_namespace(), "unshift"),
+        Method::from_builtin(unshift),
+    ));
+
+    drop(write);
+
+    class
+}
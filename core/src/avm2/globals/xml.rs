@@ -0,0 +1,259 @@
+//! `XML` impl
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::method::{Method, NativeMethod};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject, XmlObject};
+use crate::avm2::string::AvmString;
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::xml::{XMLDocument, XMLNode};
+use gc_arena::{GcCell, MutationContext};
+
+/// Parse XML markup into a list of its top-level nodes.
+pub fn parse<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    markup: &str,
+) -> Result<Vec<XMLNode<'gc>>, Error> {
+    let mc = activation.context.gc_context;
+    let mut root = XMLDocument::new(mc).as_node();
+
+    if root.replace_with_str(mc, markup, true, true).is_err() {
+        return Err("TypeError: Error #1090: XML parser failure: element is malformed.".into());
+    }
+
+    Ok(root
+        .children()
+        .map(|children| children.collect())
+        .unwrap_or_default())
+}
+
+/// Get the nodes that a constructor argument represents.
+///
+/// `XML` and `XMLList` objects share their nodes; anything else is coerced to
+/// a string and parsed as markup.
+pub fn nodes_from_value<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    value: &Value<'gc>,
+) -> Result<Vec<XMLNode<'gc>>, Error> {
+    match value {
+        Value::Undefined | Value::Null => Ok(Vec::new()),
+        Value::Object(object) if object.as_xml_object().is_some() => {
+            Ok(object.as_xml_object().unwrap().nodes())
+        }
+        value => {
+            let markup = value.coerce_to_string(activation)?;
+            parse(activation, &markup)
+        }
+    }
+}
+
+/// Determine if a node has simple content, i.e. contains no elements.
+fn has_simple_content(node: XMLNode<'_>) -> bool {
+    !node.is_element()
+        || node
+            .children()
+            .map(|mut children| children.all(|child| !child.is_element()))
+            .unwrap_or(true)
+}
+
+/// Convert a list of nodes to a string, as `toString` does.
+///
+/// Lists with simple content yield their text; anything else is serialized
+/// as markup.
+pub fn nodes_to_string(nodes: &[XMLNode<'_>]) -> Result<String, Error> {
+    let is_simple = match nodes {
+        [node] => has_simple_content(*node),
+        nodes => nodes.iter().all(|node| !node.is_element()),
+    };
+
+    if !is_simple {
+        return nodes_to_xml_string(nodes);
+    }
+
+    let mut text = String::new();
+    for node in nodes {
+        if node.is_element() {
+            for child in node.children().into_iter().flatten() {
+                if child.is_text() {
+                    text.push_str(&child.node_value().unwrap_or_default());
+                }
+            }
+        } else if node.is_text() {
+            text.push_str(&node.node_value().unwrap_or_default());
+        }
+    }
+
+    Ok(text)
+}
+
+/// Serialize a list of nodes as markup, one node per line.
+pub fn nodes_to_xml_string(nodes: &[XMLNode<'_>]) -> Result<String, Error> {
+    let mut lines = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        lines.push(node.into_string(&mut |_| true)?);
+    }
+
+    Ok(lines.join("\n"))
+}
+
+/// Implements `XML`'s instance initializer.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        activation.super_init(this, &[])?;
+
+        if let Some(xml) = this.as_xml_object() {
+            let nodes = nodes_from_value(activation, args.get(0).unwrap_or(&Value::Undefined))?;
+
+            if nodes.iter().filter(|node| node.is_element()).count() > 1 {
+                return Err("TypeError: Error #1088: The markup in the document following the root element must be well-formed.".into());
+            }
+
+            // Surrounding whitespace and comments don't count as the node.
+            let node = nodes
+                .iter()
+                .find(|node| node.is_element())
+                .or_else(|| nodes.first())
+                .copied();
+            xml.set_nodes(activation.context.gc_context, node.into_iter().collect());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML`'s class initializer.
+pub fn class_init<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.toString` and `XMLList.toString`.
+pub fn to_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(xml) = this.and_then(|this| this.as_xml_object()) {
+        let string = nodes_to_string(&xml.nodes())?;
+
+        return Ok(AvmString::new(activation.context.gc_context, string).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.toXMLString` and `XMLList.toXMLString`.
+pub fn to_xml_string<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(xml) = this.and_then(|this| this.as_xml_object()) {
+        let string = nodes_to_xml_string(&xml.nodes())?;
+
+        return Ok(AvmString::new(activation.context.gc_context, string).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.length` and `XMLList.length`.
+pub fn length<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(xml) = this.and_then(|this| this.as_xml_object()) {
+        return Ok(xml.nodes().len().into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.descendants` and `XMLList.descendants`.
+///
+/// Descendants are only matched by local name, in any namespace.
+pub fn descendants<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(xml) = this.and_then(|this| this.as_xml_object()) {
+        let name = match args.get(0) {
+            Some(name) => name.coerce_to_string(activation)?,
+            None => "*".into(),
+        };
+        let nodes = xml.descendants(|node| {
+            &*name == "*"
+                || node
+                    .tag_name()
+                    .map(|tag_name| tag_name.local_name() == &*name)
+                    .unwrap_or(false)
+        });
+        let xml_list_proto = activation.context.avm2.prototypes().xml_list;
+
+        return Ok(
+            XmlObject::from_list(nodes, xml_list_proto, activation.context.gc_context).into(),
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `XML.localName`.
+pub fn local_name<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(xml) = this.and_then(|this| this.as_xml_object()) {
+        if let Some(tag_name) = xml.nodes().first().and_then(|node| node.tag_name()) {
+            return Ok(AvmString::new(
+                activation.context.gc_context,
+                tag_name.local_name().to_string(),
+            )
+            .into());
+        }
+    }
+
+    Ok(Value::Null)
+}
+
+/// Construct `XML`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::public(), "XML"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::from_builtin(instance_init),
+        Method::from_builtin(class_init),
+        mc,
+    );
+
+    let mut write = class.write(mc);
+
+    let methods: &[(&str, NativeMethod<'gc>)] = &[
+        ("toString", to_string),
+        ("toXMLString", to_xml_string),
+        ("length", length),
+        ("descendants", descendants),
+        ("localName", local_name),
+    ];
+    for (name, method) in methods {
+        write.define_instance_trait(Trait::from_method(
+            QName::new(Namespace::public(), *name),
+            Method::from_builtin(*method),
+        ));
+    }
+
+    class
+}
@@ -197,6 +197,20 @@ impl<'gc> QName<'gc> {
     pub fn namespace(&self) -> &Namespace<'gc> {
         &self.ns
     }
+
+    /// Format this name the way `flash.utils.getQualifiedClassName` does.
+    ///
+    /// Names in a package are qualified with the package name, separated by
+    /// two colons. Names in the public namespace are left as-is.
+    pub fn to_qualified_name(&self) -> String {
+        let uri = self.ns.as_uri();
+
+        if uri.is_empty() {
+            self.name.to_string()
+        } else {
+            format!("{}::{}", uri, self.name)
+        }
+    }
 }
 
 /// A `Multiname` consists of a name which could be resolved in one or more
@@ -206,11 +220,15 @@ impl<'gc> QName<'gc> {
 /// process consists of searching each name space for a given name.
 ///
 /// The existence of a `name` of `None` indicates the `Any` name.
+///
+/// Names of parameterized types, such as `Vector.<int>`, also carry the names
+/// of the type parameters that should be applied to the named type.
 #[derive(Clone, Debug, Collect)]
 #[collect(no_drop)]
 pub struct Multiname<'gc> {
     ns: Vec<Namespace<'gc>>,
     name: Option<AvmString<'gc>>,
+    params: Vec<Multiname<'gc>>,
 }
 
 impl<'gc> Multiname<'gc> {
@@ -276,6 +294,7 @@ impl<'gc> Multiname<'gc> {
                     )?],
                     name: translation_unit
                         .pool_string_option(name.0, activation.context.gc_context)?,
+                    params: Vec::new(),
                 }
             }
             AbcMultiname::RTQName { name } | AbcMultiname::RTQNameA { name } => {
@@ -284,6 +303,7 @@ impl<'gc> Multiname<'gc> {
                     ns: vec![ns],
                     name: translation_unit
                         .pool_string_option(name.0, activation.context.gc_context)?,
+                    params: Vec::new(),
                 }
             }
             AbcMultiname::RTQNameL | AbcMultiname::RTQNameLA => {
@@ -292,6 +312,7 @@ impl<'gc> Multiname<'gc> {
                 Self {
                     ns: vec![ns],
                     name: Some(name),
+                    params: Vec::new(),
                 }
             }
             AbcMultiname::Multiname {
@@ -308,6 +329,7 @@ impl<'gc> Multiname<'gc> {
                     activation.context.gc_context,
                 )?,
                 name: translation_unit.pool_string_option(name.0, activation.context.gc_context)?,
+                params: Vec::new(),
             },
             AbcMultiname::MultinameL { namespace_set }
             | AbcMultiname::MultinameLA { namespace_set } => {
@@ -319,8 +341,14 @@ impl<'gc> Multiname<'gc> {
                         activation.context.gc_context,
                     )?,
                     name: Some(name),
+                    params: Vec::new(),
                 }
             }
+            AbcMultiname::TypeName { .. } => Self::from_abc_multiname_static(
+                translation_unit,
+                multiname_index,
+                activation.context.gc_context,
+            )?,
         })
     }
 
@@ -354,6 +382,7 @@ impl<'gc> Multiname<'gc> {
                         mc,
                     )?],
                     name: translation_unit.pool_string_option(name.0, mc)?,
+                    params: Vec::new(),
                 }
             }
             AbcMultiname::Multiname {
@@ -366,7 +395,28 @@ impl<'gc> Multiname<'gc> {
             } => Self {
                 ns: Self::abc_namespace_set(translation_unit, namespace_set.clone(), mc)?,
                 name: translation_unit.pool_string_option(name.0, mc)?,
+                params: Vec::new(),
             },
+            AbcMultiname::TypeName {
+                base_type,
+                parameters,
+            } => {
+                let mut base =
+                    Self::from_abc_multiname_static(translation_unit, base_type.clone(), mc)?;
+
+                for parameter in parameters {
+                    // Parameter zero is the any type, as in `Vector.<*>`.
+                    let param = if parameter.0 == 0 {
+                        Self::any()
+                    } else {
+                        Self::from_abc_multiname_static(translation_unit, parameter.clone(), mc)?
+                    };
+
+                    base.params.push(param);
+                }
+
+                base
+            }
             _ => return Err(format!("Multiname {} is not static", multiname_index.0).into()),
         })
     }
@@ -376,6 +426,7 @@ impl<'gc> Multiname<'gc> {
         Self {
             ns: vec![Namespace::Any],
             name: None,
+            params: Vec::new(),
         }
     }
 
@@ -387,6 +438,16 @@ impl<'gc> Multiname<'gc> {
         self.name
     }
 
+    /// Indicates if this multiname is the any name, as in the `*` type.
+    pub fn is_any(&self) -> bool {
+        self.name.is_none()
+    }
+
+    /// The type parameters that this name should be specialized with.
+    pub fn params(&self) -> &[Multiname<'gc>] {
+        &self.params
+    }
+
     pub fn includes_dynamic_namespace(&self) -> bool {
         for ns in self.ns.iter() {
             if ns.is_dynamic() {
@@ -403,6 +464,7 @@ impl<'gc> From<QName<'gc>> for Multiname<'gc> {
         Self {
             ns: vec![q.ns],
             name: Some(q.name),
+            params: Vec::new(),
         }
     }
 }
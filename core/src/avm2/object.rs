@@ -2,6 +2,7 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::array::ArrayStorage;
+use crate::avm2::bytearray::ByteArrayStorage;
 use crate::avm2::class::Class;
 use crate::avm2::domain::Domain;
 use crate::avm2::events::{DispatchList, Event};
@@ -11,6 +12,7 @@ use crate::avm2::scope::Scope;
 use crate::avm2::string::AvmString;
 use crate::avm2::traits::{Trait, TraitKind};
 use crate::avm2::value::{Hint, Value};
+use crate::avm2::vector::VectorStorage;
 use crate::avm2::Error;
 use crate::display_object::DisplayObject;
 use gc_arena::{Collect, GcCell, MutationContext};
@@ -19,7 +21,9 @@ use std::cell::{Ref, RefMut};
 use std::fmt::Debug;
 
 mod array_object;
+mod bytearray_object;
 mod custom_object;
+mod dictionary_object;
 mod dispatch_object;
 mod domain_object;
 mod event_object;
@@ -28,8 +32,12 @@ mod namespace_object;
 mod primitive_object;
 mod script_object;
 mod stage_object;
+mod vector_object;
+mod xml_object;
 
 pub use crate::avm2::object::array_object::ArrayObject;
+pub use crate::avm2::object::bytearray_object::ByteArrayObject;
+pub use crate::avm2::object::dictionary_object::DictionaryObject;
 pub use crate::avm2::object::dispatch_object::DispatchObject;
 pub use crate::avm2::object::domain_object::DomainObject;
 pub use crate::avm2::object::event_object::EventObject;
@@ -38,6 +46,8 @@ pub use crate::avm2::object::namespace_object::NamespaceObject;
 pub use crate::avm2::object::primitive_object::PrimitiveObject;
 pub use crate::avm2::object::script_object::ScriptObject;
 pub use crate::avm2::object::stage_object::StageObject;
+pub use crate::avm2::object::vector_object::VectorObject;
+pub use crate::avm2::object::xml_object::{XmlContent, XmlObject};
 
 /// Represents an object that can be directly interacted with by the AVM2
 /// runtime.
@@ -53,7 +63,11 @@ pub use crate::avm2::object::stage_object::StageObject;
         StageObject(StageObject<'gc>),
         DomainObject(DomainObject<'gc>),
         EventObject(EventObject<'gc>),
-        DispatchObject(DispatchObject<'gc>)
+        DispatchObject(DispatchObject<'gc>),
+        ByteArrayObject(ByteArrayObject<'gc>),
+        DictionaryObject(DictionaryObject<'gc>),
+        VectorObject(VectorObject<'gc>),
+        XmlObject(XmlObject<'gc>)
     }
 )]
 pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy {
@@ -362,6 +376,30 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     /// mechanism.
     fn get_enumerant_name(&self, index: u32) -> Option<QName<'gc>>;
 
+    /// Retrieve the key of a given enumerant, as yielded by `for..in` loops.
+    ///
+    /// This is usually the enumerant's name, but objects which can be keyed
+    /// by other values (such as `Dictionary`) may yield those values instead.
+    fn get_enumerant_key(&self, index: u32) -> Option<Value<'gc>> {
+        self.get_enumerant_name(index)
+            .map(|name| name.local_name().into())
+    }
+
+    /// Retrieve the value of a given enumerant, as yielded by `for each..in`
+    /// loops.
+    fn get_enumerant_value(
+        self,
+        index: u32,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        if let Some(name) = self.get_enumerant_name(index) {
+            let mut this: Object<'gc> = self.into();
+            this.get_property(this, &name, activation)
+        } else {
+            Ok(Value::Undefined)
+        }
+    }
+
     /// Determine if a property is currently enumerable.
     ///
     /// Properties that do not exist are also not enumerable.
@@ -645,6 +683,22 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         Err("Object is not callable".into())
     }
 
+    /// Apply type parameters to this object.
+    ///
+    /// This is only supported on the constructors of generic classes, such
+    /// as `Vector`, where it yields a constructor for the class specialized
+    /// to the given parameters. A `null` parameter stands for the any type.
+    fn apply(
+        &self,
+        _activation: &mut Activation<'_, 'gc, '_>,
+        _params: &[Value<'gc>],
+    ) -> Result<Object<'gc>, Error> {
+        Err(
+            "TypeError: Error #1127: Type application attempted on a non-parameterized type."
+                .into(),
+        )
+    }
+
     /// Construct a host object of some kind and return its cell.
     ///
     /// As the first step in object construction, the `construct` method is
@@ -840,6 +894,39 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         None
     }
 
+    /// Unwrap this object as byte array storage.
+    fn as_bytearray(&self) -> Option<Ref<ByteArrayStorage>> {
+        None
+    }
+
+    /// Unwrap this object as mutable byte array storage.
+    fn as_bytearray_mut(&self, _mc: MutationContext<'gc, '_>) -> Option<RefMut<ByteArrayStorage>> {
+        None
+    }
+
+    /// Unwrap this object as vector storage.
+    fn as_vector_storage(&self) -> Option<Ref<VectorStorage<'gc>>> {
+        None
+    }
+
+    /// Unwrap this object as mutable vector storage.
+    fn as_vector_storage_mut(
+        &self,
+        _mc: MutationContext<'gc, '_>,
+    ) -> Option<RefMut<VectorStorage<'gc>>> {
+        None
+    }
+
+    /// Unwrap this object as a dictionary.
+    fn as_dictionary_object(self) -> Option<DictionaryObject<'gc>> {
+        None
+    }
+
+    /// Unwrap this object as an `XML` or `XMLList`.
+    fn as_xml_object(self) -> Option<XmlObject<'gc>> {
+        None
+    }
+
     /// Get this object's `DisplayObject`, if it has one.
     fn as_display_object(&self) -> Option<DisplayObject<'gc>> {
         None
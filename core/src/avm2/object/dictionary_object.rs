@@ -0,0 +1,205 @@
+//! Object representation for dictionaries
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::script_object::{ScriptObjectClass, ScriptObjectData};
+use crate::avm2::object::{Object, ObjectPtr, TObject};
+use crate::avm2::scope::Scope;
+use crate::avm2::string::AvmString;
+use crate::avm2::traits::Trait;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::{impl_avm2_custom_object, impl_avm2_custom_object_properties};
+use fnv::FnvBuildHasher;
+use gc_arena::{Collect, GcCell, MutationContext};
+use indexmap::IndexMap;
+use std::hash::{Hash, Hasher};
+
+type FnvIndexMap<K, V> = IndexMap<K, V, FnvBuildHasher>;
+
+/// An object that can be keyed by other objects, as well as by name.
+///
+/// Object keys are compared by identity rather than by their string value.
+/// Keys which are not objects are stored as ordinary dynamic properties.
+#[derive(Collect, Debug, Clone, Copy)]
+#[collect(no_drop)]
+pub struct DictionaryObject<'gc>(GcCell<'gc, DictionaryObjectData<'gc>>);
+
+#[derive(Debug, Clone)]
+pub struct DictionaryObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// Values keyed by objects, in insertion order.
+    ///
+    /// Our garbage collector cannot yet express weak references, so keys are
+    /// always held strongly, even in dictionaries constructed with
+    /// `weakKeys`.
+    object_space: FnvIndexMap<DictionaryKey<'gc>, Value<'gc>>,
+}
+
+unsafe impl<'gc> Collect for DictionaryObjectData<'gc> {
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        self.base.trace(cc);
+
+        for (key, value) in self.object_space.iter() {
+            key.trace(cc);
+            value.trace(cc);
+        }
+    }
+}
+
+/// An object used as a dictionary key, hashed and compared by identity.
+#[derive(Collect, Debug, Clone, Copy)]
+#[collect(no_drop)]
+struct DictionaryKey<'gc>(Object<'gc>);
+
+impl<'gc> PartialEq for DictionaryKey<'gc> {
+    fn eq(&self, other: &Self) -> bool {
+        Object::ptr_eq(self.0, other.0)
+    }
+}
+
+impl<'gc> Eq for DictionaryKey<'gc> {}
+
+impl<'gc> Hash for DictionaryKey<'gc> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_ptr().hash(state);
+    }
+}
+
+impl<'gc> DictionaryObject<'gc> {
+    /// Instantiate a dictionary subclass.
+    pub fn derive(
+        base_proto: Object<'gc>,
+        mc: MutationContext<'gc, '_>,
+        class: GcCell<'gc, Class<'gc>>,
+        scope: Option<GcCell<'gc, Scope<'gc>>>,
+    ) -> Result<Object<'gc>, Error> {
+        let base = ScriptObjectData::base_new(
+            Some(base_proto),
+            ScriptObjectClass::InstancePrototype(class, scope),
+        );
+
+        Ok(DictionaryObject(GcCell::allocate(
+            mc,
+            DictionaryObjectData {
+                base,
+                object_space: FnvIndexMap::default(),
+            },
+        ))
+        .into())
+    }
+
+    /// Retrieve the value stored under an object key.
+    pub fn get_property_by_object(self, key: Object<'gc>) -> Option<Value<'gc>> {
+        self.0.read().object_space.get(&DictionaryKey(key)).cloned()
+    }
+
+    /// Store a value under an object key.
+    pub fn set_property_by_object(
+        self,
+        mc: MutationContext<'gc, '_>,
+        key: Object<'gc>,
+        value: Value<'gc>,
+    ) {
+        self.0
+            .write(mc)
+            .object_space
+            .insert(DictionaryKey(key), value);
+    }
+
+    /// Remove the value stored under an object key.
+    ///
+    /// Returns `true` if the key was present.
+    pub fn delete_property_by_object(self, mc: MutationContext<'gc, '_>, key: Object<'gc>) -> bool {
+        // Shifting keeps the remaining keys in insertion order.
+        self.0
+            .write(mc)
+            .object_space
+            .shift_remove(&DictionaryKey(key))
+            .is_some()
+    }
+
+    /// Determine if a value is stored under an object key.
+    pub fn has_property_by_object(self, key: Object<'gc>) -> bool {
+        self.0.read().object_space.contains_key(&DictionaryKey(key))
+    }
+}
+
+impl<'gc> TObject<'gc> for DictionaryObject<'gc> {
+    impl_avm2_custom_object!(base);
+    impl_avm2_custom_object_properties!(base);
+
+    fn get_enumerant_key(&self, index: u32) -> Option<Value<'gc>> {
+        // Named properties are enumerated first, followed by object keys.
+        let read = self.0.read();
+        if let Some(name) = read.base.get_enumerant_name(index) {
+            return Some(name.local_name().into());
+        }
+
+        let object_index = (index as usize).checked_sub(read.base.enumerant_count() + 1)?;
+
+        read.object_space
+            .get_index(object_index)
+            .map(|(key, _)| key.0.into())
+    }
+
+    fn get_enumerant_value(
+        self,
+        index: u32,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        let name = self.0.read().base.get_enumerant_name(index);
+        if let Some(name) = name {
+            let mut this: Object<'gc> = self.into();
+            return this.get_property(this, &name, activation);
+        }
+
+        let read = self.0.read();
+        let object_index = (index as usize).checked_sub(read.base.enumerant_count() + 1);
+
+        Ok(object_index
+            .and_then(|i| read.object_space.get_index(i))
+            .map(|(_, value)| value.clone())
+            .unwrap_or(Value::Undefined))
+    }
+
+    fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error> {
+        Ok(Value::Object(Object::from(*self)))
+    }
+
+    fn as_dictionary_object(self) -> Option<DictionaryObject<'gc>> {
+        Some(self)
+    }
+
+    fn construct(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        _args: &[Value<'gc>],
+    ) -> Result<Object<'gc>, Error> {
+        let this: Object<'gc> = Object::DictionaryObject(*self);
+        let base = ScriptObjectData::base_new(Some(this), ScriptObjectClass::NoClass);
+
+        Ok(DictionaryObject(GcCell::allocate(
+            activation.context.gc_context,
+            DictionaryObjectData {
+                base,
+                object_space: FnvIndexMap::default(),
+            },
+        ))
+        .into())
+    }
+
+    fn derive(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        class: GcCell<'gc, Class<'gc>>,
+        scope: Option<GcCell<'gc, Scope<'gc>>>,
+    ) -> Result<Object<'gc>, Error> {
+        let this: Object<'gc> = Object::DictionaryObject(*self);
+
+        Self::derive(this, activation.context.gc_context, class, scope)
+    }
+}
@@ -28,6 +28,10 @@ pub struct FunctionObjectData<'gc> {
 
     /// Executable code
     exec: Option<Executable<'gc>>,
+
+    /// Specializations of this class, if it is a generic class, keyed by the
+    /// type parameters they were applied with.
+    applications: Vec<(Vec<Option<Object<'gc>>>, Object<'gc>)>,
 }
 
 pub fn implicit_deriver<'gc>(
@@ -174,6 +178,7 @@ impl<'gc> FunctionObject<'gc> {
                     None,
                     activation.context.gc_context,
                 )),
+                applications: Vec::new(),
             },
         ))
         .into();
@@ -219,6 +224,7 @@ impl<'gc> FunctionObject<'gc> {
             FunctionObjectData {
                 base: ScriptObjectData::base_new(Some(fn_proto), ScriptObjectClass::NoClass),
                 exec,
+                applications: Vec::new(),
             },
         ))
         .into()
@@ -235,6 +241,7 @@ impl<'gc> FunctionObject<'gc> {
             FunctionObjectData {
                 base: ScriptObjectData::base_new(Some(fn_proto), ScriptObjectClass::NoClass),
                 exec: Some(Executable::from_method(nf.into(), None, None, mc)),
+                applications: Vec::new(),
             },
         ))
         .into()
@@ -257,6 +264,7 @@ impl<'gc> FunctionObject<'gc> {
             FunctionObjectData {
                 base: ScriptObjectData::base_new(Some(fn_proto), class),
                 exec: Some(Executable::from_method(constr.into(), None, None, mc)),
+                applications: Vec::new(),
             },
         ))
         .into();
@@ -314,6 +322,84 @@ impl<'gc> TObject<'gc> for FunctionObject<'gc> {
         }
     }
 
+    fn apply(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        params: &[Value<'gc>],
+    ) -> Result<Object<'gc>, Error> {
+        let (class, scope) = match self.0.read().base.class() {
+            ScriptObjectClass::ClassConstructor(class, scope) if class.read().is_generic() => {
+                (*class, *scope)
+            }
+            _ => return Err(
+                "TypeError: Error #1127: Type application attempted on a non-parameterized type."
+                    .into(),
+            ),
+        };
+
+        if params.len() != 1 {
+            return Err(format!(
+                "TypeError: Error #1128: Incorrect number of type parameters for {}. Expected 1, got {}.",
+                class.read().name().local_name(),
+                params.len()
+            )
+            .into());
+        }
+
+        let mut object_params = Vec::with_capacity(params.len());
+        for param in params {
+            object_params.push(match param {
+                Value::Undefined | Value::Null => None,
+                param => Some(param.coerce_to_object(activation)?),
+            });
+        }
+
+        for (applied_params, applied) in self.0.read().applications.iter() {
+            let is_match = applied_params
+                .iter()
+                .zip(object_params.iter())
+                .all(|(a, b)| match (a, b) {
+                    (Some(a), Some(b)) => Object::ptr_eq(*a, *b),
+                    (None, None) => true,
+                    _ => false,
+                });
+
+            if is_match {
+                return Ok(*applied);
+            }
+        }
+
+        // Specializations are not subclasses of the generic class, so the
+        // specialized prototype is rebased onto the generic class's base.
+        let mut this: Object<'gc> = Object::FunctionObject(*self);
+        let generic_proto = this
+            .get_property(
+                this,
+                &QName::new(Namespace::public(), "prototype"),
+                activation,
+            )?
+            .coerce_to_object(activation)?;
+        let applied_class =
+            Class::with_type_params(class, &object_params, activation.context.gc_context);
+        let applied_proto = generic_proto.derive(activation, applied_class, scope)?;
+
+        if let Some(base_proto) = generic_proto.proto() {
+            applied_proto.set_proto(activation.context.gc_context, base_proto);
+        }
+
+        let (applied, class_init) =
+            FunctionObject::from_class_and_proto(activation, applied_class, applied_proto, scope)?;
+
+        self.0
+            .write(activation.context.gc_context)
+            .applications
+            .push((object_params, applied));
+
+        class_init.call(Some(applied), &[], activation, None)?;
+
+        Ok(applied)
+    }
+
     fn construct(
         &self,
         activation: &mut Activation<'_, 'gc, '_>,
@@ -324,7 +410,11 @@ impl<'gc> TObject<'gc> for FunctionObject<'gc> {
 
         Ok(FunctionObject(GcCell::allocate(
             activation.context.gc_context,
-            FunctionObjectData { base, exec: None },
+            FunctionObjectData {
+                base,
+                exec: None,
+                applications: Vec::new(),
+            },
         ))
         .into())
     }
@@ -343,7 +433,11 @@ impl<'gc> TObject<'gc> for FunctionObject<'gc> {
 
         Ok(FunctionObject(GcCell::allocate(
             activation.context.gc_context,
-            FunctionObjectData { base, exec: None },
+            FunctionObjectData {
+                base,
+                exec: None,
+                applications: Vec::new(),
+            },
         ))
         .into())
     }
@@ -742,6 +742,11 @@ impl<'gc> ScriptObjectData<'gc> {
         self.enumerants.get(true_index).cloned()
     }
 
+    /// The number of properties that can be enumerated on this object.
+    pub fn enumerant_count(&self) -> usize {
+        self.enumerants.len()
+    }
+
     pub fn property_is_enumerable(&self, name: &QName<'gc>) -> bool {
         self.enumerants.contains(name)
     }
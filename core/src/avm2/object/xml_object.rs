@@ -0,0 +1,394 @@
+//! Object representation for XML and XMLList
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::script_object::{ScriptObjectClass, ScriptObjectData};
+use crate::avm2::object::{Object, ObjectPtr, TObject};
+use crate::avm2::scope::Scope;
+use crate::avm2::string::AvmString;
+use crate::avm2::traits::Trait;
+use crate::avm2::value::{Hint, Value};
+use crate::avm2::Error;
+use crate::impl_avm2_custom_object;
+use crate::xml::{XMLDocument, XMLNode};
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// An object that holds an XML node, or an ordered list of them.
+///
+/// The nodes themselves are shared with the XML tree they were parsed into,
+/// so multiple objects may refer to the same node.
+#[derive(Collect, Debug, Clone, Copy)]
+#[collect(no_drop)]
+pub struct XmlObject<'gc>(GcCell<'gc, XmlObjectData<'gc>>);
+
+#[derive(Collect, Debug, Clone)]
+#[collect(no_drop)]
+pub struct XmlObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// The node or nodes this object represents.
+    content: XmlContent<'gc>,
+}
+
+/// The contents of an `XML` or `XMLList` object.
+#[derive(Collect, Debug, Clone)]
+#[collect(no_drop)]
+pub enum XmlContent<'gc> {
+    /// A single node, as held by `XML`.
+    Node(XMLNode<'gc>),
+
+    /// Any number of nodes, as held by `XMLList`.
+    List(Vec<XMLNode<'gc>>),
+}
+
+impl<'gc> XmlContent<'gc> {
+    /// Construct empty content of the same kind as this content.
+    fn empty_like(&self, mc: MutationContext<'gc, '_>) -> Self {
+        match self {
+            Self::Node(_) => Self::empty_node(mc),
+            Self::List(_) => Self::List(Vec::new()),
+        }
+    }
+
+    /// Construct a single empty text node.
+    pub fn empty_node(mc: MutationContext<'gc, '_>) -> Self {
+        Self::Node(XMLNode::new_text(mc, "", XMLDocument::new(mc)))
+    }
+}
+
+impl<'gc> XmlObject<'gc> {
+    /// Wrap an existing node in an `XML` object.
+    pub fn from_node(
+        node: XMLNode<'gc>,
+        base_proto: Object<'gc>,
+        mc: MutationContext<'gc, '_>,
+    ) -> Object<'gc> {
+        Self::from_content(XmlContent::Node(node), base_proto, mc)
+    }
+
+    /// Wrap a list of nodes in an `XMLList` object.
+    pub fn from_list(
+        nodes: Vec<XMLNode<'gc>>,
+        base_proto: Object<'gc>,
+        mc: MutationContext<'gc, '_>,
+    ) -> Object<'gc> {
+        Self::from_content(XmlContent::List(nodes), base_proto, mc)
+    }
+
+    fn from_content(
+        content: XmlContent<'gc>,
+        base_proto: Object<'gc>,
+        mc: MutationContext<'gc, '_>,
+    ) -> Object<'gc> {
+        let base = ScriptObjectData::base_new(Some(base_proto), ScriptObjectClass::NoClass);
+
+        XmlObject(GcCell::allocate(mc, XmlObjectData { base, content })).into()
+    }
+
+    /// Instantiate an `XML` or `XMLList` subclass holding the given (empty)
+    /// content.
+    pub fn derive(
+        base_proto: Object<'gc>,
+        mc: MutationContext<'gc, '_>,
+        class: GcCell<'gc, Class<'gc>>,
+        scope: Option<GcCell<'gc, Scope<'gc>>>,
+        content: XmlContent<'gc>,
+    ) -> Result<Object<'gc>, Error> {
+        let base = ScriptObjectData::base_new(
+            Some(base_proto),
+            ScriptObjectClass::InstancePrototype(class, scope),
+        );
+
+        Ok(XmlObject(GcCell::allocate(mc, XmlObjectData { base, content })).into())
+    }
+
+    /// Determine if this object is an `XMLList`.
+    pub fn is_list(self) -> bool {
+        matches!(self.0.read().content, XmlContent::List(_))
+    }
+
+    /// List the nodes this object holds.
+    ///
+    /// An `XML` object behaves as a list holding its own node.
+    pub fn nodes(self) -> Vec<XMLNode<'gc>> {
+        match &self.0.read().content {
+            XmlContent::Node(node) => vec![*node],
+            XmlContent::List(nodes) => nodes.clone(),
+        }
+    }
+
+    /// Replace the nodes this object holds.
+    ///
+    /// An `XML` object keeps the first node given, or becomes an empty text
+    /// node if there is none.
+    pub fn set_nodes(self, mc: MutationContext<'gc, '_>, nodes: Vec<XMLNode<'gc>>) {
+        let mut write = self.0.write(mc);
+
+        write.content = match write.content {
+            XmlContent::Node(_) => match nodes.first() {
+                Some(node) => XmlContent::Node(*node),
+                None => XmlContent::empty_node(mc),
+            },
+            XmlContent::List(_) => XmlContent::List(nodes),
+        };
+    }
+
+    /// Collect every descendant node of the held nodes, in document order,
+    /// that satisfies `matches`.
+    pub fn descendants(self, mut matches: impl FnMut(XMLNode<'gc>) -> bool) -> Vec<XMLNode<'gc>> {
+        let mut found = Vec::new();
+
+        for node in self.nodes() {
+            if let Some(walk) = node.walk() {
+                for step in walk {
+                    if !step.stepped_out() && matches(step.unwrap()) {
+                        found.push(step.unwrap());
+                    }
+                }
+            }
+        }
+
+        found
+    }
+
+    /// Get the namespace URI of an element node.
+    ///
+    /// Elements without a prefix, or whose prefix is not declared, are in the
+    /// default namespace of the document, which is usually empty.
+    pub fn namespace_uri(node: XMLNode<'gc>) -> String {
+        let prefix = node
+            .tag_name()
+            .and_then(|name| name.prefix().map(|prefix| prefix.to_string()))
+            .unwrap_or_default();
+
+        node.lookup_uri_for_namespace(&prefix).unwrap_or_default()
+    }
+
+    /// Wrap the node at `index` in an `XML` object.
+    fn node_object(
+        self,
+        index: usize,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Option<Object<'gc>> {
+        let node = self.nodes().get(index).copied()?;
+        let xml_proto = activation.context.avm2.prototypes().xml;
+
+        Some(XmlObject::from_node(
+            node,
+            xml_proto,
+            activation.context.gc_context,
+        ))
+    }
+}
+
+impl<'gc> TObject<'gc> for XmlObject<'gc> {
+    impl_avm2_custom_object!(base);
+
+    fn get_property_local(
+        self,
+        receiver: Object<'gc>,
+        name: &QName<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        if name.namespace().is_public() {
+            if let Ok(index) = name.local_name().parse::<usize>() {
+                return Ok(self
+                    .node_object(index, activation)
+                    .map(|object| object.into())
+                    .unwrap_or(Value::Undefined));
+            }
+        }
+
+        let read = self.0.read();
+        let rv = read.base.get_property_local(receiver, name, activation)?;
+
+        drop(read);
+
+        rv.resolve(activation)
+    }
+
+    fn set_property_local(
+        self,
+        receiver: Object<'gc>,
+        name: &QName<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        if name.namespace().is_public() && self.is_list() {
+            if let Ok(index) = name.local_name().parse::<usize>() {
+                let mc = activation.context.gc_context;
+                let xml = match &value {
+                    Value::Object(object) => object.as_xml_object(),
+                    _ => None,
+                };
+                let new_nodes = if let Some(xml) = xml {
+                    xml.nodes()
+                } else {
+                    let text = value.coerce_to_string(activation)?;
+                    vec![XMLNode::new_text(mc, &text, XMLDocument::new(mc))]
+                };
+
+                // Assigning past the end of a list appends to it.
+                let mut nodes = self.nodes();
+                if index < nodes.len() {
+                    nodes.splice(index..=index, new_nodes);
+                } else {
+                    nodes.extend(new_nodes);
+                }
+                self.set_nodes(mc, nodes);
+
+                return Ok(());
+            }
+        }
+
+        let mut write = self.0.write(activation.context.gc_context);
+        let rv = write
+            .base
+            .set_property_local(receiver, name, value, activation)?;
+
+        drop(write);
+
+        rv.resolve(activation)?;
+
+        Ok(())
+    }
+
+    fn init_property_local(
+        self,
+        receiver: Object<'gc>,
+        name: &QName<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        let mut write = self.0.write(activation.context.gc_context);
+        let rv = write
+            .base
+            .init_property_local(receiver, name, value, activation)?;
+
+        drop(write);
+
+        rv.resolve(activation)?;
+
+        Ok(())
+    }
+
+    fn is_property_overwritable(
+        self,
+        gc_context: MutationContext<'gc, '_>,
+        name: &QName<'gc>,
+    ) -> bool {
+        self.0.write(gc_context).base.is_property_overwritable(name)
+    }
+
+    fn delete_property(&self, gc_context: MutationContext<'gc, '_>, name: &QName<'gc>) -> bool {
+        self.0.write(gc_context).base.delete_property(name)
+    }
+
+    fn has_own_property(self, name: &QName<'gc>) -> Result<bool, Error> {
+        if name.namespace().is_public() {
+            if let Ok(index) = name.local_name().parse::<usize>() {
+                return Ok(index < self.nodes().len());
+            }
+        }
+
+        self.0.read().base.has_own_property(name)
+    }
+
+    fn resolve_any(self, local_name: AvmString<'gc>) -> Result<Option<Namespace<'gc>>, Error> {
+        if let Ok(index) = local_name.parse::<usize>() {
+            if index < self.nodes().len() || self.is_list() {
+                return Ok(Some(Namespace::public()));
+            }
+        }
+
+        self.0.read().base.resolve_any(local_name)
+    }
+
+    fn resolve_any_trait(
+        self,
+        local_name: AvmString<'gc>,
+    ) -> Result<Option<Namespace<'gc>>, Error> {
+        self.0.read().base.resolve_any_trait(local_name)
+    }
+
+    fn get_enumerant_key(&self, index: u32) -> Option<Value<'gc>> {
+        let length = self.nodes().len() as u32;
+
+        if index == 0 {
+            None
+        } else if index <= length {
+            Some(Value::Integer((index - 1) as i32))
+        } else {
+            self.0
+                .read()
+                .base
+                .get_enumerant_name(index - length)
+                .map(|name| name.local_name().into())
+        }
+    }
+
+    fn get_enumerant_value(
+        self,
+        index: u32,
+        activation: &mut Activation<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        let length = self.nodes().len() as u32;
+
+        if index == 0 {
+            return Ok(Value::Undefined);
+        } else if index <= length {
+            return Ok(self
+                .node_object(index as usize - 1, activation)
+                .map(|object| object.into())
+                .unwrap_or(Value::Undefined));
+        }
+
+        let name = self.0.read().base.get_enumerant_name(index - length);
+
+        if let Some(name) = name {
+            let mut this: Object<'gc> = self.into();
+            this.get_property(this, &name, activation)
+        } else {
+            Ok(Value::Undefined)
+        }
+    }
+
+    fn default_hint(&self) -> Hint {
+        Hint::String
+    }
+
+    fn value_of(&self, _mc: MutationContext<'gc, '_>) -> Result<Value<'gc>, Error> {
+        Ok(Value::Object(Object::from(*self)))
+    }
+
+    fn as_xml_object(self) -> Option<XmlObject<'gc>> {
+        Some(self)
+    }
+
+    fn construct(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        _args: &[Value<'gc>],
+    ) -> Result<Object<'gc>, Error> {
+        let this: Object<'gc> = Object::XmlObject(*self);
+        let mc = activation.context.gc_context;
+        let content = self.0.read().content.empty_like(mc);
+
+        Ok(XmlObject::from_content(content, this, mc))
+    }
+
+    fn derive(
+        &self,
+        activation: &mut Activation<'_, 'gc, '_>,
+        class: GcCell<'gc, Class<'gc>>,
+        scope: Option<GcCell<'gc, Scope<'gc>>>,
+    ) -> Result<Object<'gc>, Error> {
+        let this: Object<'gc> = Object::XmlObject(*self);
+        let mc = activation.context.gc_context;
+        let content = self.0.read().content.empty_like(mc);
+
+        Self::derive(this, mc, class, scope, content)
+    }
+}
@@ -74,6 +74,11 @@ impl<'gc> TranslationUnit<'gc> {
         ))
     }
 
+    /// Retrieve the domain that this translation unit's scripts export to.
+    pub fn domain(self) -> Domain<'gc> {
+        self.0.read().domain
+    }
+
     /// Retrieve the underlying `AbcFile` for this translation unit.
     pub fn abc(self) -> Rc<AbcFile> {
         self.0.read().abc.0.clone()
@@ -0,0 +1,241 @@
+//! Storage for AS3 Vectors
+
+use crate::avm2::activation::Activation;
+use crate::avm2::object::Object;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use gc_arena::Collect;
+use std::ops::RangeBounds;
+
+/// The vector storage portion of a vector object.
+///
+/// Vector values are restricted to a single type, decided upon the
+/// construction of the vector. No type checks are done by the storage itself;
+/// values must be coerced with `coerce` before they are stored. Vectors also
+/// cannot have holes: all values below the length are present, and newly
+/// created space is filled with the default value of the vector's type.
+///
+/// Vectors may also be fixed-length, in which case any operation that would
+/// change the length of the vector fails with a `RangeError`.
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub struct VectorStorage<'gc> {
+    /// The values stored in the vector.
+    storage: Vec<Value<'gc>>,
+
+    /// Whether or not the length of the vector is fixed.
+    is_fixed: bool,
+
+    /// The class of values stored in this vector, or `None` for the any type.
+    value_type: Option<Object<'gc>>,
+
+    /// The value that new space in the vector is filled with.
+    default_value: Value<'gc>,
+}
+
+impl<'gc> VectorStorage<'gc> {
+    /// Construct new vector storage of a given type.
+    ///
+    /// The default value is what new space in the vector will be filled with,
+    /// which is usually the value type's coercion of `undefined`.
+    pub fn new(
+        length: usize,
+        is_fixed: bool,
+        value_type: Option<Object<'gc>>,
+        default_value: Value<'gc>,
+    ) -> Self {
+        let mut storage = Vec::new();
+
+        storage.resize(length, default_value.clone());
+
+        Self {
+            storage,
+            is_fixed,
+            value_type,
+            default_value,
+        }
+    }
+
+    /// Construct empty vector storage of the same type as this one.
+    pub fn empty_like(&self) -> Self {
+        Self::new(0, false, self.value_type, self.default_value.clone())
+    }
+
+    /// Coerce a value to a vector's value type, so that it can be stored.
+    ///
+    /// This is not a method on the storage, as coercions may run user code
+    /// that accesses the vector. Callers should retrieve the `value_type` of
+    /// the storage first.
+    pub fn coerce(
+        activation: &mut Activation<'_, 'gc, '_>,
+        value_type: Option<Object<'gc>>,
+        value: Value<'gc>,
+    ) -> Result<Value<'gc>, Error> {
+        match value_type {
+            Some(value_type) => activation.coerce_to_type(value, value_type),
+            None => Ok(value),
+        }
+    }
+
+    /// Check if a range of indices exists in this vector.
+    fn check_range(&self, index: usize) -> Result<(), Error> {
+        if index >= self.storage.len() {
+            return Err(format!(
+                "RangeError: Error #1125: The index {} is out of range {}.",
+                index,
+                self.storage.len()
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Check if the length of this vector may change.
+    fn check_fixed(&self) -> Result<(), Error> {
+        if self.is_fixed {
+            return Err(
+                "RangeError: Error #1126: Cannot change the length of a fixed Vector.".into(),
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn is_fixed(&self) -> bool {
+        self.is_fixed
+    }
+
+    pub fn set_is_fixed(&mut self, is_fixed: bool) {
+        self.is_fixed = is_fixed;
+    }
+
+    pub fn value_type(&self) -> Option<Object<'gc>> {
+        self.value_type
+    }
+
+    /// Get the length of the vector.
+    pub fn length(&self) -> usize {
+        self.storage.len()
+    }
+
+    /// Set the length of the vector.
+    ///
+    /// New space is filled with the vector's default value.
+    pub fn set_length(&mut self, length: usize) -> Result<(), Error> {
+        self.check_fixed()?;
+        self.storage.resize(length, self.default_value.clone());
+
+        Ok(())
+    }
+
+    /// Retrieve a value from the vector.
+    ///
+    /// Indices beyond the end of the vector yield a `RangeError`.
+    pub fn get(&self, index: usize) -> Result<Value<'gc>, Error> {
+        self.check_range(index)?;
+
+        Ok(self.storage[index].clone())
+    }
+
+    /// Store an already-coerced value in the vector.
+    ///
+    /// Values may be stored at any existing index, or one past the end of the
+    /// vector to append them, as long as the vector is not fixed.
+    pub fn set(&mut self, index: usize, value: Value<'gc>) -> Result<(), Error> {
+        if index == self.storage.len() {
+            return self.push(value);
+        }
+
+        self.check_range(index)?;
+        self.storage[index] = value;
+
+        Ok(())
+    }
+
+    /// Push an already-coerced value onto the end of the vector.
+    pub fn push(&mut self, value: Value<'gc>) -> Result<(), Error> {
+        self.check_fixed()?;
+        self.storage.push(value);
+
+        Ok(())
+    }
+
+    /// Pop a value from the end of the vector.
+    ///
+    /// Popping from an empty vector yields the default value.
+    pub fn pop(&mut self) -> Result<Value<'gc>, Error> {
+        self.check_fixed()?;
+
+        Ok(self
+            .storage
+            .pop()
+            .unwrap_or_else(|| self.default_value.clone()))
+    }
+
+    /// Shift a value from the start of the vector.
+    ///
+    /// Shifting from an empty vector yields the default value.
+    pub fn shift(&mut self) -> Result<Value<'gc>, Error> {
+        self.check_fixed()?;
+
+        if self.storage.is_empty() {
+            return Ok(self.default_value.clone());
+        }
+
+        Ok(self.storage.remove(0))
+    }
+
+    /// Insert an already-coerced value into the vector.
+    ///
+    /// Indices beyond the end of the vector insert at the end.
+    pub fn insert(&mut self, index: usize, value: Value<'gc>) -> Result<(), Error> {
+        self.check_fixed()?;
+
+        let index = index.min(self.storage.len());
+        self.storage.insert(index, value);
+
+        Ok(())
+    }
+
+    /// Remove a value from the vector.
+    pub fn remove(&mut self, index: usize) -> Result<Value<'gc>, Error> {
+        self.check_fixed()?;
+        self.check_range(index)?;
+
+        Ok(self.storage.remove(index))
+    }
+
+    /// Replace a range of the vector with already-coerced values, returning
+    /// the values that were removed.
+    pub fn splice<R>(
+        &mut self,
+        range: R,
+        replace_with: Vec<Value<'gc>>,
+    ) -> Result<Vec<Value<'gc>>, Error>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.check_fixed()?;
+
+        Ok(self.storage.splice(range, replace_with).collect())
+    }
+
+    /// Reverse the vector in place.
+    pub fn reverse(&mut self) {
+        self.storage.reverse();
+    }
+
+    /// Replace the contents of the vector with already-coerced values.
+    ///
+    /// This is used to commit the result of operations that do not change
+    /// the length of the vector, such as sorting.
+    pub fn replace_storage(&mut self, storage: Vec<Value<'gc>>) {
+        self.storage = storage;
+    }
+
+    /// Iterate over vector values.
+    pub fn iter<'a>(&'a self) -> impl DoubleEndedIterator<Item = Value<'gc>> + 'a {
+        self.storage.iter().cloned()
+    }
+}
@@ -1,7 +1,10 @@
 pub mod audio;
+pub mod local_connection;
 pub mod locale;
 pub mod log;
 pub mod navigator;
 pub mod render;
+pub mod socket;
 pub mod storage;
 pub mod ui;
+pub mod video;
@@ -9,6 +9,7 @@ use downcast_rs::Downcast;
 use gc_arena::{Collect, CollectionContext};
 use generational_arena::{Arena, Index};
 
+pub mod capture;
 pub mod decoders;
 pub mod swf {
     pub use swf::{
@@ -82,7 +83,10 @@ pub trait AudioBackend: Downcast {
     fn stop_all_sounds(&mut self);
 
     /// Get the position of a sound instance in milliseconds.
-    /// Returns `None` if ther sound is not/no longer playing
+    /// Returns `None` if ther sound is not/no longer playing.
+    ///
+    /// Event sounds report their position within the current loop, which
+    /// `SoundPlayback` can be used to calculate.
     fn get_sound_position(&self, instance: SoundInstanceHandle) -> Option<u32>;
 
     /// Get the duration of a sound in milliseconds.
@@ -96,7 +100,10 @@ pub trait AudioBackend: Downcast {
     fn is_loading_complete(&self) -> bool {
         true
     }
-    fn tick(&mut self) {}
+
+    /// Called by `Player::tick` with the number of milliseconds that have
+    /// passed since the last tick.
+    fn tick(&mut self, _dt: f64) {}
 
     /// Inform the audio backend of the current stage frame rate.
     ///
@@ -207,6 +214,11 @@ impl<'gc> AudioManager<'gc> {
             } else {
                 // Sound ended; fire end event.
                 if let Some(object) = sound.avm1_object {
+                    // The position stays at the end of the finished sound.
+                    object.set_position(gc_context, object.duration());
+                    if object.sound_instance() == Some(sound.instance) {
+                        object.set_sound_instance(gc_context, None);
+                    }
                     action_queue.queue_actions(
                         root,
                         crate::context::ActionType::Method {
@@ -401,6 +413,65 @@ unsafe impl<'gc> Collect for SoundInstance<'gc> {
     }
 }
 
+/// The portion of a sound played by an event sound instance.
+///
+/// Used by audio backends to calculate the position of an instance from how
+/// long it has been playing.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct SoundPlayback {
+    /// The position each loop starts from, in milliseconds.
+    pub start: f64,
+
+    /// The position each loop ends at, in milliseconds.
+    pub end: f64,
+
+    /// The number of times the sound plays.
+    pub num_loops: u16,
+}
+
+impl SoundPlayback {
+    /// Creates the playback of a sound with the given settings.
+    ///
+    /// `num_sample_frames` is the length of the sound at `sample_rate`, while
+    /// the settings are in 44.1kHz samples regardless of the sound's sample
+    /// rate.
+    pub fn new(settings: &swf::SoundInfo, sample_rate: u16, num_sample_frames: u32) -> Self {
+        let start = f64::from(settings.in_sample.unwrap_or(0)) * 1000.0 / 44100.0;
+        let end = match settings.out_sample {
+            Some(out_sample) => f64::from(out_sample) * 1000.0 / 44100.0,
+            None => f64::from(num_sample_frames) * 1000.0 / f64::from(sample_rate),
+        };
+        Self {
+            start,
+            end,
+            num_loops: settings.num_loops.max(1),
+        }
+    }
+
+    /// The length of a single loop in milliseconds.
+    pub fn loop_duration(&self) -> f64 {
+        (self.end - self.start).max(0.0)
+    }
+
+    /// The length of every loop in milliseconds.
+    pub fn total_duration(&self) -> f64 {
+        self.loop_duration() * f64::from(self.num_loops)
+    }
+
+    /// The position in milliseconds after playing for `elapsed` milliseconds.
+    ///
+    /// Once every loop has played, the position stays at the end of the sound.
+    pub fn position(&self, elapsed: f64) -> u32 {
+        let loop_duration = self.loop_duration();
+        let position = if loop_duration <= 0.0 || elapsed >= self.total_duration() {
+            self.end.max(self.start)
+        } else {
+            self.start + elapsed % loop_duration
+        };
+        position as u32
+    }
+}
+
 /// A sound transform for a playing sound, for use by audio backends.
 /// This differs from `display_object::SoundTranform` by being
 /// already converted to `f32` and having `volume` baked in.
@@ -436,3 +507,38 @@ impl Default for SoundTransform {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(in_sample: Option<u32>, out_sample: Option<u32>, num_loops: u16) -> swf::SoundInfo {
+        swf::SoundInfo {
+            event: swf::SoundEvent::Start,
+            in_sample,
+            out_sample,
+            num_loops,
+            envelope: None,
+        }
+    }
+
+    #[test]
+    fn sound_playback_position() {
+        // A one second sound at 22kHz.
+        let playback = SoundPlayback::new(&settings(None, None, 1), 22050, 22050);
+        assert_eq!(playback.position(0.0), 0);
+        assert_eq!(playback.position(250.5), 250);
+        assert_eq!(playback.position(1500.0), 1000);
+
+        // Started half a second in, and looped twice.
+        let playback = SoundPlayback::new(&settings(Some(22050), None, 2), 22050, 22050);
+        assert_eq!(playback.total_duration() as u32, 1000);
+        assert_eq!(playback.position(100.0), 600);
+        assert_eq!(playback.position(600.0), 600);
+        assert_eq!(playback.position(1000.0), 1000);
+
+        // Started past the end.
+        let playback = SoundPlayback::new(&settings(Some(88200), None, 1), 22050, 22050);
+        assert_eq!(playback.position(0.0), 2000);
+    }
+}
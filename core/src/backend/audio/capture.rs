@@ -0,0 +1,518 @@
+//! Audio backend that mixes sound into memory.
+
+use super::decoders;
+use super::{swf, AudioBackend, SoundHandle, SoundInstanceHandle, SoundPlayback, SoundTransform};
+use crate::tag_utils::SwfSlice;
+use generational_arena::Arena;
+use std::io::{Cursor, Write};
+use std::sync::Arc;
+
+type Error = Box<dyn std::error::Error>;
+
+/// A registered sound, decoded in full.
+struct Sound {
+    format: swf::SoundFormat,
+
+    /// The decoded sample frames, not including any encoder delay.
+    samples: Arc<[[i16; 2]]>,
+}
+
+/// An actively playing instance of a sound.
+struct SoundInstance {
+    /// The decoded audio, resampled to the output sample rate.
+    signal: Resampler<Box<dyn Iterator<Item = [i16; 2]> + Send>>,
+
+    /// The envelope of an event sound, if it has one.
+    envelope: Option<swf::SoundEnvelope>,
+
+    /// The volume transform for this sound instance.
+    transform: SoundTransform,
+
+    /// The number of sample frames output so far.
+    frames_played: u64,
+
+    /// The portion of the sound being played.
+    /// `None` if this is a stream sound.
+    playback: Option<SoundPlayback>,
+}
+
+/// Audio backend that mixes every playing sound into an in-memory buffer of
+/// 16-bit stereo PCM, rather than playing it.
+///
+/// Time only passes for this backend when `Player::tick` is called, or when
+/// `advance` is called directly. This makes the output deterministic, which
+/// is useful for exporting the audio of a movie or for testing.
+pub struct CaptureAudioBackend {
+    sounds: Arena<Sound>,
+    sound_instances: Arena<SoundInstance>,
+    sample_rate: u32,
+    is_playing: bool,
+
+    /// The mixed output, as interleaved left and right samples.
+    samples: Vec<i16>,
+
+    /// Time that has passed, in milliseconds, that is too short to produce a
+    /// whole sample frame.
+    time_remainder: f64,
+}
+
+impl CaptureAudioBackend {
+    /// Creates a backend that mixes audio at the given sample rate.
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sounds: Arena::new(),
+            sound_instances: Arena::new(),
+            sample_rate,
+            is_playing: true,
+            samples: Vec::new(),
+            time_remainder: 0.0,
+        }
+    }
+
+    /// The sample rate of the output.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// The audio mixed so far, as interleaved left and right samples.
+    pub fn samples(&self) -> &[i16] {
+        &self.samples
+    }
+
+    /// Takes the audio mixed so far, leaving the buffer empty.
+    pub fn take_samples(&mut self) -> Vec<i16> {
+        std::mem::take(&mut self.samples)
+    }
+
+    /// Mixes `dt` milliseconds of audio into the buffer.
+    ///
+    /// Nothing is mixed while the backend is paused.
+    pub fn advance(&mut self, dt: f64) {
+        if !self.is_playing {
+            return;
+        }
+
+        let time = self.time_remainder + dt;
+        let num_frames = (time * f64::from(self.sample_rate) / 1000.0).floor();
+        self.time_remainder = time - num_frames * 1000.0 / f64::from(self.sample_rate);
+        self.mix(num_frames as usize);
+    }
+
+    /// Writes the audio mixed so far as a WAV file.
+    pub fn write_wav<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        const NUM_CHANNELS: u16 = 2;
+        const BITS_PER_SAMPLE: u16 = 16;
+        let block_align = NUM_CHANNELS * BITS_PER_SAMPLE / 8;
+        let data_len = (self.samples.len() * 2) as u32;
+
+        writer.write_all(b"RIFF")?;
+        writer.write_all(&(36 + data_len).to_le_bytes())?;
+        writer.write_all(b"WAVE")?;
+
+        writer.write_all(b"fmt ")?;
+        writer.write_all(&16u32.to_le_bytes())?;
+        writer.write_all(&1u16.to_le_bytes())?; // PCM
+        writer.write_all(&NUM_CHANNELS.to_le_bytes())?;
+        writer.write_all(&self.sample_rate.to_le_bytes())?;
+        writer.write_all(&(self.sample_rate * u32::from(block_align)).to_le_bytes())?;
+        writer.write_all(&block_align.to_le_bytes())?;
+        writer.write_all(&BITS_PER_SAMPLE.to_le_bytes())?;
+
+        writer.write_all(b"data")?;
+        writer.write_all(&data_len.to_le_bytes())?;
+        for sample in &self.samples {
+            writer.write_all(&sample.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Mixes the given number of sample frames from every playing sound into
+    /// the buffer, and removes any sounds that have finished.
+    fn mix(&mut self, num_frames: usize) {
+        self.samples.reserve(num_frames * 2);
+        for _ in 0..num_frames {
+            let mut output = [0.0f32; 2];
+            for (_, instance) in self.sound_instances.iter_mut() {
+                if let Some(frame) = instance.next_frame() {
+                    output[0] += frame[0];
+                    output[1] += frame[1];
+                }
+            }
+
+            for sample in &output {
+                let sample = sample.max(f32::from(i16::MIN)).min(f32::from(i16::MAX));
+                self.samples.push(sample as i16);
+            }
+        }
+
+        self.sound_instances
+            .retain(|_, instance| !instance.signal.is_exhausted());
+    }
+
+    fn insert_instance(
+        &mut self,
+        sample_rate: u16,
+        signal: Box<dyn Iterator<Item = [i16; 2]> + Send>,
+        envelope: Option<swf::SoundEnvelope>,
+        playback: Option<SoundPlayback>,
+    ) -> SoundInstanceHandle {
+        self.sound_instances.insert(SoundInstance {
+            signal: Resampler::new(signal, u32::from(sample_rate), self.sample_rate),
+            envelope,
+            transform: SoundTransform {
+                left_to_left: 1.0,
+                left_to_right: 0.0,
+                right_to_left: 0.0,
+                right_to_right: 1.0,
+            },
+            frames_played: 0,
+            playback,
+        })
+    }
+}
+
+impl Default for CaptureAudioBackend {
+    fn default() -> Self {
+        CaptureAudioBackend::new(44100)
+    }
+}
+
+impl AudioBackend for CaptureAudioBackend {
+    fn play(&mut self) {
+        self.is_playing = true;
+    }
+
+    fn pause(&mut self) {
+        self.is_playing = false;
+    }
+
+    fn register_sound(&mut self, swf_sound: &swf::Sound) -> Result<SoundHandle, Error> {
+        // Slice off latency seek for MP3 data.
+        let (skip_sample_frames, data) =
+            if swf_sound.format.compression == swf::AudioCompression::Mp3 {
+                let skip_sample_frames =
+                    u16::from(swf_sound.data[0]) | (u16::from(swf_sound.data[1]) << 8);
+                (skip_sample_frames, &swf_sound.data[2..])
+            } else {
+                (0, swf_sound.data)
+            };
+
+        let decoder = decoders::make_decoder(&swf_sound.format, Cursor::new(data))?;
+
+        // Some codecs, such as Speex, ignore the sample rate of the sound format.
+        let mut format = swf_sound.format.clone();
+        format.sample_rate = decoder.sample_rate();
+
+        let samples: Vec<[i16; 2]> = decoder
+            .skip(skip_sample_frames.into())
+            .take(swf_sound.num_samples as usize)
+            .collect();
+
+        Ok(self.sounds.insert(Sound {
+            format,
+            samples: samples.into(),
+        }))
+    }
+
+    fn start_sound(
+        &mut self,
+        sound: SoundHandle,
+        settings: &swf::SoundInfo,
+    ) -> Result<SoundInstanceHandle, Error> {
+        let sound = self.sounds.get(sound).ok_or("Sound not registered")?;
+        let format = sound.format.clone();
+        let playback = SoundPlayback::new(settings, format.sample_rate, sound.samples.len() as u32);
+
+        // In and out points are in 44.1kHz samples regardless of the sound's sample rate.
+        let to_sample_frame =
+            |n: u32| (u64::from(n) * u64::from(format.sample_rate) / 44100) as usize;
+        let len = sound.samples.len();
+        let start = to_sample_frame(settings.in_sample.unwrap_or(0)).min(len);
+        let end = settings
+            .out_sample
+            .map(to_sample_frame)
+            .unwrap_or(len)
+            .min(len)
+            .max(start);
+
+        let samples = Arc::clone(&sound.samples);
+        let num_loops = usize::from(settings.num_loops.max(1));
+        let signal = Box::new(
+            (0..num_loops)
+                .flat_map(move |_| start..end)
+                .map(move |i| samples[i]),
+        );
+
+        Ok(self.insert_instance(
+            format.sample_rate,
+            signal,
+            settings.envelope.clone(),
+            Some(playback),
+        ))
+    }
+
+    fn start_stream(
+        &mut self,
+        _stream_handle: Option<SoundHandle>,
+        _clip_frame: u16,
+        clip_data: SwfSlice,
+        stream_info: &swf::SoundStreamHead,
+    ) -> Result<SoundInstanceHandle, Error> {
+        let format = &stream_info.stream_format;
+        let decoder = decoders::make_stream_decoder(format, clip_data)?;
+        let sample_rate = decoder.sample_rate();
+        let signal: Box<dyn Iterator<Item = [i16; 2]> + Send> = Box::new(decoder);
+        Ok(self.insert_instance(sample_rate, signal, None, None))
+    }
+
+    fn stop_sound(&mut self, sound: SoundInstanceHandle) {
+        self.sound_instances.remove(sound);
+    }
+
+    fn stop_all_sounds(&mut self) {
+        // This is a workaround for a bug in generational-arena:
+        // Arena::clear does not properly bump the generational index, allowing for stale references
+        // to continue to work (this caused #1315). Arena::remove will force a generation bump.
+        // See https://github.com/fitzgen/generational-arena/issues/30
+        if let Some((i, _)) = self.sound_instances.iter().next() {
+            self.sound_instances.remove(i);
+        }
+        self.sound_instances.clear();
+    }
+
+    fn get_sound_position(&self, instance: SoundInstanceHandle) -> Option<u32> {
+        self.sound_instances.get(instance).map(|instance| {
+            let elapsed = instance.frames_played as f64 * 1000.0 / f64::from(self.sample_rate);
+            match &instance.playback {
+                Some(playback) => playback.position(elapsed),
+                None => elapsed as u32,
+            }
+        })
+    }
+
+    fn get_sound_duration(&self, sound: SoundHandle) -> Option<u32> {
+        self.sounds.get(sound).map(|sound| {
+            let num_sample_frames = sound.samples.len() as u64;
+            (num_sample_frames * 1000 / u64::from(sound.format.sample_rate)) as u32
+        })
+    }
+
+    fn set_sound_transform(&mut self, instance: SoundInstanceHandle, transform: SoundTransform) {
+        if let Some(instance) = self.sound_instances.get_mut(instance) {
+            instance.transform = transform;
+        }
+    }
+
+    fn tick(&mut self, dt: f64) {
+        self.advance(dt);
+    }
+}
+
+impl SoundInstance {
+    /// Returns the next sample frame of this sound, with its envelope and
+    /// transform applied.
+    fn next_frame(&mut self) -> Option<[f32; 2]> {
+        let [left, right] = self.signal.next()?;
+        let (mut left, mut right) = (f32::from(left), f32::from(right));
+
+        if let Some(envelope) = &self.envelope {
+            // Envelope points are in 44.1kHz samples regardless of the output sample rate.
+            let sample = self.frames_played as f64 * 44100.0 / self.signal.output_rate;
+            let [left_volume, right_volume] = envelope_volume(envelope, sample);
+            left *= left_volume;
+            right *= right_volume;
+        }
+        self.frames_played += 1;
+
+        let transform = &self.transform;
+        Some([
+            left * transform.left_to_left + right * transform.right_to_left,
+            left * transform.left_to_right + right * transform.right_to_right,
+        ])
+    }
+}
+
+/// The volume of each channel at a point in a sound envelope.
+fn envelope_volume(envelope: &[swf::SoundEnvelopePoint], sample: f64) -> [f32; 2] {
+    let next = envelope
+        .iter()
+        .position(|point| f64::from(point.sample) > sample);
+    match next {
+        // Before the first point, the volume is that of the first point.
+        Some(0) => [envelope[0].left_volume, envelope[0].right_volume],
+        Some(i) => {
+            let (prev, next) = (&envelope[i - 1], &envelope[i]);
+            let t =
+                ((sample - f64::from(prev.sample)) / f64::from(next.sample - prev.sample)) as f32;
+            [
+                prev.left_volume + (next.left_volume - prev.left_volume) * t,
+                prev.right_volume + (next.right_volume - prev.right_volume) * t,
+            ]
+        }
+        // After the last point, the volume is that of the last point.
+        None => envelope
+            .last()
+            .map(|point| [point.left_volume, point.right_volume])
+            .unwrap_or([1.0, 1.0]),
+    }
+}
+
+/// Converts a signal between sample rates using linear interpolation.
+struct Resampler<I> {
+    source: I,
+
+    /// The number of source frames per output frame.
+    step: f64,
+
+    /// The position between `current` and `next`, from 0 to 1.
+    position: f64,
+
+    current: Option<[i16; 2]>,
+    next: Option<[i16; 2]>,
+    output_rate: f64,
+}
+
+impl<I: Iterator<Item = [i16; 2]>> Resampler<I> {
+    fn new(mut source: I, source_rate: u32, output_rate: u32) -> Self {
+        let current = source.next();
+        let next = source.next();
+        Self {
+            source,
+            step: f64::from(source_rate) / f64::from(output_rate),
+            position: 0.0,
+            current,
+            next,
+            output_rate: f64::from(output_rate),
+        }
+    }
+
+    fn is_exhausted(&self) -> bool {
+        self.current.is_none()
+    }
+}
+
+impl<I: Iterator<Item = [i16; 2]>> Iterator for Resampler<I> {
+    type Item = [i16; 2];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.position >= 1.0 {
+            self.current = self.next;
+            self.next = self.source.next();
+            self.position -= 1.0;
+        }
+
+        let current = self.current?;
+        let next = self.next.unwrap_or(current);
+        let t = self.position;
+        let lerp = |a: i16, b: i16| (f64::from(a) + (f64::from(b) - f64::from(a)) * t) as i16;
+
+        self.position += self.step;
+        if self.position >= 1.0 && self.next.is_none() {
+            // This was the last frame.
+            self.current = None;
+        }
+
+        Some([lerp(current[0], next[0]), lerp(current[1], next[1])])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn register(audio: &mut CaptureAudioBackend, samples: &[i16], sample_rate: u16) -> SoundHandle {
+        let data: Vec<u8> = samples
+            .iter()
+            .flat_map(|s| s.to_le_bytes().to_vec())
+            .collect();
+        audio
+            .register_sound(&swf::Sound {
+                id: 1,
+                format: swf::SoundFormat {
+                    compression: swf::AudioCompression::Uncompressed,
+                    sample_rate,
+                    is_stereo: false,
+                    is_16_bit: true,
+                },
+                num_samples: samples.len() as u32,
+                data: &data,
+            })
+            .unwrap()
+    }
+
+    fn sound_info(num_loops: u16) -> swf::SoundInfo {
+        swf::SoundInfo {
+            event: swf::SoundEvent::Start,
+            in_sample: None,
+            out_sample: None,
+            num_loops,
+            envelope: None,
+        }
+    }
+
+    #[test]
+    fn mix_sounds() {
+        let mut audio = CaptureAudioBackend::new(11025);
+        let a = register(&mut audio, &[100, 200, 300], 11025);
+        let b = register(&mut audio, &[1000; 2], 11025);
+
+        let instance = audio.start_sound(a, &sound_info(2)).unwrap();
+        let panned = audio.start_sound(b, &sound_info(1)).unwrap();
+        audio.set_sound_transform(
+            panned,
+            SoundTransform {
+                left_to_left: 0.0,
+                left_to_right: 0.0,
+                right_to_left: 0.0,
+                right_to_right: 0.5,
+            },
+        );
+
+        // One millisecond is 11 sample frames at 11kHz.
+        audio.advance(1.0);
+        assert_eq!(
+            audio.samples(),
+            &[
+                100, 600, 200, 700, 300, 300, 100, 100, 200, 200, 300, 300, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0
+            ][..]
+        );
+        assert_eq!(audio.get_sound_position(instance), None);
+        assert!(audio.sound_instances.is_empty());
+    }
+
+    #[test]
+    fn resample() {
+        let mut audio = CaptureAudioBackend::new(22050);
+        let sound = register(&mut audio, &[0, 100, 200], 11025);
+        let instance = audio.start_sound(sound, &sound_info(1)).unwrap();
+
+        audio.advance(0.1);
+        assert_eq!(audio.samples(), &[0, 0, 50, 50][..]);
+        assert_eq!(audio.get_sound_position(instance), Some(0));
+
+        audio.advance(1.0);
+        assert_eq!(
+            &audio.samples()[..12],
+            &[0, 0, 50, 50, 100, 100, 150, 150, 200, 200, 200, 200][..]
+        );
+    }
+
+    #[test]
+    fn write_wav() {
+        let mut audio = CaptureAudioBackend::new(44100);
+        let sound = register(&mut audio, &[1, -1], 44100);
+        audio.start_sound(sound, &sound_info(1)).unwrap();
+        audio.advance(0.05);
+
+        let mut wav = Vec::new();
+        audio.write_wav(&mut wav).unwrap();
+        assert_eq!(wav.len(), 44 + 8);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(&wav[24..28], &44100u32.to_le_bytes());
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(&wav[44..], &[1, 0, 1, 0, 255, 255, 255, 255]);
+    }
+}
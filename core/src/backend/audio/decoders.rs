@@ -1,14 +1,22 @@
 //! Audio decoders.
 
+#[cfg(feature = "aac")]
+mod aac;
 mod adpcm;
 mod mp3;
 mod nellymoser;
 mod pcm;
+#[cfg(all(feature = "speex", not(target_arch = "wasm32")))]
+mod speex;
 
+#[cfg(feature = "aac")]
+pub use aac::AacDecoder;
 pub use adpcm::AdpcmDecoder;
 pub use mp3::Mp3Decoder;
 pub use nellymoser::NellymoserDecoder;
 pub use pcm::PcmDecoder;
+#[cfg(all(feature = "speex", not(target_arch = "wasm32")))]
+pub use speex::SpeexDecoder;
 
 use crate::tag_utils::SwfSlice;
 use std::io::{Cursor, Read};
@@ -23,6 +31,7 @@ pub trait Decoder: Iterator<Item = [i16; 2]> {
     fn num_channels(&self) -> u8;
 
     /// The sample rate of this audio decoder.
+    /// This may differ from the sample rate of the `SoundFormat`; Speex is always 16kHz.
     fn sample_rate(&self) -> u16;
 }
 
@@ -61,6 +70,10 @@ pub fn make_decoder<'a, R: 'a + Send + Read>(
         AudioCompression::Nellymoser => {
             Box::new(NellymoserDecoder::new(data, format.sample_rate.into()))
         }
+        #[cfg(feature = "aac")]
+        AudioCompression::Aac => Box::new(AacDecoder::new(data)?),
+        #[cfg(all(feature = "speex", not(target_arch = "wasm32")))]
+        AudioCompression::Speex => Box::new(SpeexDecoder::new(data)?),
         _ => {
             let msg = format!(
                 "make_decoder: Unhandled audio compression {:?}",
@@ -0,0 +1,229 @@
+use super::{Decoder, SeekableDecoder};
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use symphonia_core::audio::{Channels, SampleBuffer};
+use symphonia_core::codecs::{CodecParameters, Decoder as _, DecoderOptions, CODEC_TYPE_AAC};
+use symphonia_core::formats::Packet;
+
+type Error = Box<dyn std::error::Error>;
+
+/// Sample rates indexed by the sampling frequency index of an ADTS header.
+const SAMPLE_RATES: [u32; 13] = [
+    96000, 88200, 64000, 48000, 44100, 32000, 24000, 22050, 16000, 12000, 11025, 8000, 7350,
+];
+
+/// Decoder for AAC-LC audio in an ADTS stream.
+///
+/// Each ADTS frame carries a header describing the sample rate and channels,
+/// followed by a single raw AAC frame of 1024 sample frames.
+pub struct AacDecoder<R: Read> {
+    reader: R,
+    decoder: symphonia_codec_aac::AacDecoder,
+    sample_rate: u32,
+    num_channels: u8,
+    frame: Vec<u8>,
+    samples: Vec<i16>,
+    cur_sample: usize,
+}
+
+/// The fields of an ADTS header that are needed for decoding.
+struct AdtsHeader {
+    sample_rate: u32,
+    num_channels: u8,
+    header_len: usize,
+    frame_len: usize,
+}
+
+impl AdtsHeader {
+    /// Reads an ADTS header, or returns `None` at the end of the stream or if
+    /// the header is invalid.
+    fn read<R: Read>(reader: &mut R) -> Option<Self> {
+        let mut header = [0u8; 7];
+        reader.read_exact(&mut header).ok()?;
+        if header[0] != 0xff || header[1] & 0xf6 != 0xf0 {
+            log::warn!("AacDecoder: Invalid ADTS sync word");
+            return None;
+        }
+
+        let protection_absent = header[1] & 0x01 != 0;
+        let profile = header[2] >> 6;
+        if profile != 1 {
+            // The ADTS profile is the MPEG-4 audio object type minus one.
+            log::warn!("AacDecoder: Unsupported audio object type {}", profile + 1);
+            return None;
+        }
+
+        let sample_rate = *SAMPLE_RATES.get(usize::from((header[2] >> 2) & 0x0f))?;
+        let num_channels = ((header[2] & 0x01) << 2) | (header[3] >> 6);
+        let header_len = if protection_absent { 7 } else { 9 };
+        let frame_len = (usize::from(header[3] & 0x03) << 11)
+            | (usize::from(header[4]) << 3)
+            | usize::from(header[5] >> 5);
+        if frame_len < header_len {
+            return None;
+        }
+
+        if !protection_absent {
+            // Skip the CRC.
+            reader.read_exact(&mut [0u8; 2]).ok()?;
+        }
+
+        Some(Self {
+            sample_rate,
+            num_channels,
+            header_len,
+            frame_len,
+        })
+    }
+}
+
+impl<R: Read> AacDecoder<R> {
+    /// Creates a decoder, reading the first ADTS header to determine the format.
+    pub fn new(mut reader: R) -> Result<Self, Error> {
+        let header = AdtsHeader::read(&mut reader).ok_or("Invalid ADTS header")?;
+        let channels = match header.num_channels {
+            1 => Channels::FRONT_LEFT,
+            2 => Channels::FRONT_LEFT | Channels::FRONT_RIGHT,
+            n => return Err(format!("Unsupported AAC channel configuration {}", n).into()),
+        };
+
+        let mut params = CodecParameters::new();
+        params
+            .for_codec(CODEC_TYPE_AAC)
+            .with_sample_rate(header.sample_rate)
+            .with_channels(channels);
+        let decoder =
+            symphonia_codec_aac::AacDecoder::try_new(&params, &DecoderOptions::default())?;
+
+        let mut decoder = Self {
+            reader,
+            decoder,
+            sample_rate: header.sample_rate,
+            num_channels: header.num_channels,
+            frame: vec![],
+            samples: vec![],
+            cur_sample: 0,
+        };
+        decoder.decode_frame(&header);
+        Ok(decoder)
+    }
+
+    /// Reads the frame following `header` and decodes it into `self.samples`.
+    /// Returns `false` if the frame could not be read.
+    fn decode_frame(&mut self, header: &AdtsHeader) -> bool {
+        self.samples.clear();
+        self.cur_sample = 0;
+
+        self.frame.resize(header.frame_len - header.header_len, 0);
+        if self.reader.read_exact(&mut self.frame).is_err() {
+            return false;
+        }
+
+        let packet = Packet::new_from_slice(0, 0, 0, &self.frame);
+        match self.decoder.decode(&packet) {
+            Ok(decoded) => {
+                let mut buffer =
+                    SampleBuffer::<i16>::new(decoded.capacity() as u64, *decoded.spec());
+                buffer.copy_interleaved_ref(decoded);
+                self.samples.extend_from_slice(buffer.samples());
+            }
+            Err(e) => {
+                // Output silence for a corrupt frame rather than ending the sound.
+                log::warn!("AacDecoder: Error decoding frame: {}", e);
+                let num_samples = 1024 * usize::from(self.num_channels);
+                self.samples.resize(num_samples, 0);
+            }
+        }
+        true
+    }
+
+    fn next_frame(&mut self) -> bool {
+        match AdtsHeader::read(&mut self.reader) {
+            Some(header) => self.decode_frame(&header),
+            None => false,
+        }
+    }
+}
+
+impl<R: Read> Iterator for AacDecoder<R> {
+    type Item = [i16; 2];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.cur_sample >= self.samples.len() {
+            if !self.next_frame() {
+                return None;
+            }
+        }
+
+        let left = self.samples[self.cur_sample];
+        let sample_frame = if self.num_channels == 2 {
+            [left, self.samples[self.cur_sample + 1]]
+        } else {
+            [left, left]
+        };
+        self.cur_sample += usize::from(self.num_channels);
+        Some(sample_frame)
+    }
+}
+
+impl<R: Read> Decoder for AacDecoder<R> {
+    #[inline]
+    fn num_channels(&self) -> u8 {
+        self.num_channels
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> u16 {
+        self.sample_rate as u16
+    }
+}
+
+impl<R: AsRef<[u8]>> SeekableDecoder for AacDecoder<Cursor<R>> {
+    fn reset(&mut self) {
+        let _ = self.reader.seek(SeekFrom::Start(0));
+        self.decoder.reset();
+        self.samples.clear();
+        self.cur_sample = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::audio::decoders::make_decoder;
+
+    /// An ADTS frame containing a silent mono AAC-LC frame at 44.1kHz.
+    const SILENT_FRAME: [u8; 11] = [
+        0xff, 0xf1, 0x50, 0x40, 0x01, 0x7f, 0xfc, 0x00, 0x00, 0x00, 0x07,
+    ];
+
+    #[test]
+    fn decode_adts() {
+        let data: Vec<u8> = SILENT_FRAME.iter().chain(&SILENT_FRAME).copied().collect();
+        let mut decoder = AacDecoder::new(Cursor::new(data)).unwrap();
+        assert_eq!(decoder.sample_rate(), 44100);
+        assert_eq!(decoder.num_channels(), 1);
+
+        let samples: Vec<[i16; 2]> = decoder.by_ref().collect();
+        assert_eq!(samples.len(), 2048);
+        assert!(samples.iter().all(|&frame| frame == [0, 0]));
+
+        decoder.seek_to_sample_frame(1500);
+        assert_eq!(decoder.count(), 548);
+    }
+
+    #[test]
+    fn make_aac_decoder() {
+        // The sample rate and channels come from the ADTS header, not the sound format.
+        let format = swf::SoundFormat {
+            compression: swf::AudioCompression::Aac,
+            sample_rate: 22050,
+            is_stereo: true,
+            is_16_bit: true,
+        };
+        let decoder = make_decoder(&format, &SILENT_FRAME[..]).unwrap();
+        assert_eq!(decoder.sample_rate(), 44100);
+        assert_eq!(decoder.num_channels(), 1);
+        assert_eq!(decoder.count(), 1024);
+    }
+}
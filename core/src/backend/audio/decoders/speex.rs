@@ -0,0 +1,215 @@
+use super::{Decoder, SeekableDecoder};
+use std::io::Read;
+use std::os::raw::{c_char, c_int, c_void};
+
+type Error = Box<dyn std::error::Error>;
+
+/// Bit-packing state of libspeex (`SpeexBits` in `speex_bits.h`).
+#[repr(C)]
+struct SpeexBits {
+    chars: *mut c_char,
+    nb_bits: c_int,
+    char_ptr: c_int,
+    bit_ptr: c_int,
+    owner: c_int,
+    overflow: c_int,
+    buf_size: c_int,
+    reserved1: c_int,
+    reserved2: *mut c_void,
+}
+
+/// An opaque `SpeexMode`.
+#[repr(C)]
+struct SpeexMode {
+    _private: [u8; 0],
+}
+
+const SPEEX_MODEID_WB: c_int = 1;
+const SPEEX_GET_FRAME_SIZE: c_int = 3;
+const SPEEX_RESET_STATE: c_int = 26;
+
+// Linked to the system libspeex by the build script.
+extern "C" {
+    fn speex_lib_get_mode(mode: c_int) -> *const SpeexMode;
+    fn speex_decoder_init(mode: *const SpeexMode) -> *mut c_void;
+    fn speex_decoder_destroy(state: *mut c_void);
+    fn speex_decoder_ctl(state: *mut c_void, request: c_int, ptr: *mut c_void) -> c_int;
+    fn speex_decode_int(state: *mut c_void, bits: *mut SpeexBits, out: *mut i16) -> c_int;
+    fn speex_bits_init(bits: *mut SpeexBits);
+    fn speex_bits_destroy(bits: *mut SpeexBits);
+    fn speex_bits_read_from(bits: *mut SpeexBits, bytes: *const c_char, len: c_int);
+    fn speex_bits_remaining(bits: *mut SpeexBits) -> c_int;
+    fn speex_bits_advance(bits: *mut SpeexBits, n: c_int);
+}
+
+/// Decoder for wideband Speex audio, as recorded by the Flash microphone.
+///
+/// Speex audio is always 16kHz mono; the sample rate in the SWF sound format
+/// is ignored.
+pub struct SpeexDecoder {
+    state: *mut c_void,
+    bits: Box<SpeexBits>,
+    data: Vec<u8>,
+    frame: Vec<i16>,
+    cur_sample: usize,
+}
+
+// The decoder state is owned exclusively by this decoder.
+unsafe impl Send for SpeexDecoder {}
+
+impl SpeexDecoder {
+    /// The sample rate of wideband Speex.
+    const SAMPLE_RATE: u16 = 16000;
+
+    /// Creates a decoder for the Speex frames read from `reader`.
+    /// The entire stream is read upfront.
+    pub fn new<R: Read>(mut reader: R) -> Result<Self, Error> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        // SAFETY: The decoder state and bits are freed in `drop`, and `frame_size`
+        // outlives the `speex_decoder_ctl` call that writes to it.
+        unsafe {
+            let state = speex_decoder_init(speex_lib_get_mode(SPEEX_MODEID_WB));
+            if state.is_null() {
+                return Err("Unable to initialize Speex decoder".into());
+            }
+
+            let mut frame_size: c_int = 0;
+            speex_decoder_ctl(
+                state,
+                SPEEX_GET_FRAME_SIZE,
+                &mut frame_size as *mut c_int as *mut c_void,
+            );
+
+            let mut bits = Box::new(std::mem::zeroed::<SpeexBits>());
+            speex_bits_init(&mut *bits);
+
+            let mut decoder = Self {
+                state,
+                bits,
+                data,
+                frame: vec![0; frame_size.max(0) as usize],
+                cur_sample: 0,
+            };
+            decoder.read_bits();
+            Ok(decoder)
+        }
+    }
+
+    /// Loads the audio data into the bit reader, starting from the beginning.
+    fn read_bits(&mut self) {
+        // SAFETY: libspeex copies the data into its own buffer.
+        unsafe {
+            speex_bits_read_from(
+                &mut *self.bits,
+                self.data.as_ptr() as *const c_char,
+                self.data.len() as c_int,
+            );
+        }
+        self.cur_sample = self.frame.len();
+    }
+
+    /// Decodes the next Speex frame. Returns `false` at the end of the stream.
+    fn next_frame(&mut self) -> bool {
+        // SAFETY: `self.frame` is the frame size reported by the decoder.
+        unsafe {
+            loop {
+                let remaining = speex_bits_remaining(&mut *self.bits);
+                if remaining < 5 || self.frame.is_empty() {
+                    // Not enough data left for another frame.
+                    return false;
+                }
+
+                match speex_decode_int(self.state, &mut *self.bits, self.frame.as_mut_ptr()) {
+                    0 => {
+                        self.cur_sample = 0;
+                        return true;
+                    }
+                    -1 => {
+                        // A terminator: Flash pads each packet of frames to a whole byte,
+                        // so skip the padding and continue with the next packet.
+                        let padding = speex_bits_remaining(&mut *self.bits) % 8;
+                        if padding == 0 && speex_bits_remaining(&mut *self.bits) == remaining {
+                            return false;
+                        }
+                        speex_bits_advance(&mut *self.bits, padding);
+                    }
+                    _ => {
+                        log::warn!("SpeexDecoder: Corrupt stream");
+                        return false;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Drop for SpeexDecoder {
+    fn drop(&mut self) {
+        // SAFETY: These were initialized in `new` and are not used after this.
+        unsafe {
+            speex_bits_destroy(&mut *self.bits);
+            speex_decoder_destroy(self.state);
+        }
+    }
+}
+
+impl Iterator for SpeexDecoder {
+    type Item = [i16; 2];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cur_sample >= self.frame.len() && !self.next_frame() {
+            return None;
+        }
+
+        let sample = self.frame[self.cur_sample];
+        self.cur_sample += 1;
+        Some([sample, sample])
+    }
+}
+
+impl Decoder for SpeexDecoder {
+    #[inline]
+    fn num_channels(&self) -> u8 {
+        1
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> u16 {
+        Self::SAMPLE_RATE
+    }
+}
+
+impl SeekableDecoder for SpeexDecoder {
+    fn reset(&mut self) {
+        // SAFETY: The decoder state is valid for the lifetime of `self`.
+        unsafe {
+            speex_decoder_ctl(self.state, SPEEX_RESET_STATE, std::ptr::null_mut());
+        }
+        self.read_bits();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_wideband() {
+        // A null frame is five zero bits. The packets are separated by a
+        // terminator (mode 15), padded to a whole byte.
+        let mut data = vec![0; 5];
+        data.push(0b0111_1000);
+        data.extend(&[0; 5]);
+
+        let mut decoder = SpeexDecoder::new(&data[..]).unwrap();
+        assert_eq!(decoder.sample_rate(), 16000);
+        assert_eq!(decoder.num_channels(), 1);
+        assert_eq!(decoder.by_ref().count(), 16 * 320);
+
+        decoder.seek_to_sample_frame(5000);
+        assert_eq!(decoder.count(), 120);
+    }
+}
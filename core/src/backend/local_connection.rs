@@ -0,0 +1,197 @@
+use downcast_rs::Downcast;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// A message sent over a `LocalConnection`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocalConnectionMessage {
+    /// The full name of the connection that this message is sent to.
+    pub connection_name: String,
+
+    /// The domain of the movie that sent this message.
+    pub sender_domain: String,
+
+    /// Whether the movie that sent this message was loaded over HTTPS.
+    pub sender_secure: bool,
+
+    /// The method call, encoded as an AMF0 shared object.
+    ///
+    /// The shared object is named after the method being called, and its body
+    /// holds one entry for each argument, in order.
+    pub data: Vec<u8>,
+}
+
+/// A transport for `LocalConnection` messages.
+///
+/// Connection names are unique across every player sharing a transport, and
+/// are compared exactly; the player is responsible for normalizing them.
+pub trait LocalConnectionBackend: Downcast {
+    /// Start listening for messages sent to a connection name.
+    ///
+    /// Returns `false` if the name is already in use.
+    fn connect(&mut self, connection_name: &str) -> bool;
+
+    /// Stop listening for messages sent to a connection name.
+    fn close(&mut self, connection_name: &str);
+
+    /// Send a message.
+    ///
+    /// Returns `false` if nothing is listening on the message's connection
+    /// name, in which case the message is dropped.
+    fn send(&mut self, message: LocalConnectionMessage) -> bool;
+
+    /// Take every message sent to this player's connections, oldest first.
+    fn receive(&mut self) -> Vec<LocalConnectionMessage>;
+}
+impl_downcast!(LocalConnectionBackend);
+
+#[derive(Default)]
+struct HubData {
+    /// The ID of the backend listening on each connection name.
+    listeners: HashMap<String, usize>,
+
+    /// Messages waiting to be received, keyed by backend ID.
+    inboxes: HashMap<usize, Vec<LocalConnectionMessage>>,
+
+    /// The ID to assign to the next backend.
+    next_id: usize,
+}
+
+/// A set of connections shared by players in the same process.
+///
+/// Cloning a hub yields another handle to the same set of connections.
+#[derive(Clone, Default)]
+pub struct LocalConnectionHub(Arc<Mutex<HubData>>);
+
+/// A `LocalConnection` transport which exchanges messages in memory with any
+/// other backend created from the same hub.
+pub struct MemoryLocalConnectionBackend {
+    hub: LocalConnectionHub,
+    id: usize,
+}
+
+impl MemoryLocalConnectionBackend {
+    pub fn new(hub: &LocalConnectionHub) -> Self {
+        let id = {
+            let mut data = hub.0.lock().unwrap();
+            data.next_id += 1;
+            data.next_id
+        };
+
+        Self {
+            hub: hub.clone(),
+            id,
+        }
+    }
+}
+
+impl Default for MemoryLocalConnectionBackend {
+    /// Create a backend which may only exchange messages with itself.
+    fn default() -> Self {
+        Self::new(&LocalConnectionHub::default())
+    }
+}
+
+impl LocalConnectionBackend for MemoryLocalConnectionBackend {
+    fn connect(&mut self, connection_name: &str) -> bool {
+        let mut data = self.hub.0.lock().unwrap();
+        if data.listeners.contains_key(connection_name) {
+            return false;
+        }
+
+        data.listeners.insert(connection_name.to_string(), self.id);
+        true
+    }
+
+    fn close(&mut self, connection_name: &str) {
+        let mut data = self.hub.0.lock().unwrap();
+        if data.listeners.get(connection_name) == Some(&self.id) {
+            data.listeners.remove(connection_name);
+        }
+    }
+
+    fn send(&mut self, message: LocalConnectionMessage) -> bool {
+        let mut data = self.hub.0.lock().unwrap();
+        if let Some(id) = data.listeners.get(&message.connection_name).copied() {
+            data.inboxes.entry(id).or_default().push(message);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn receive(&mut self) -> Vec<LocalConnectionMessage> {
+        let mut data = self.hub.0.lock().unwrap();
+        data.inboxes.remove(&self.id).unwrap_or_default()
+    }
+}
+
+impl Drop for MemoryLocalConnectionBackend {
+    fn drop(&mut self) {
+        if let Ok(mut data) = self.hub.0.lock() {
+            let id = self.id;
+            data.listeners.retain(|_, listener| *listener != id);
+            data.inboxes.remove(&id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(connection_name: &str) -> LocalConnectionMessage {
+        LocalConnectionMessage {
+            connection_name: connection_name.to_string(),
+            sender_domain: "localhost".to_string(),
+            sender_secure: false,
+            data: vec![1, 2, 3],
+        }
+    }
+
+    #[test]
+    fn send_between_backends() {
+        let hub = LocalConnectionHub::default();
+        let mut sender = MemoryLocalConnectionBackend::new(&hub);
+        let mut receiver = MemoryLocalConnectionBackend::new(&hub);
+
+        assert!(!sender.send(message("_test")));
+        assert!(receiver.connect("_test"));
+        assert!(!sender.connect("_test"));
+
+        assert!(sender.send(message("_test")));
+        assert!(sender.receive().is_empty());
+        assert_eq!(receiver.receive(), vec![message("_test")]);
+        assert!(receiver.receive().is_empty());
+
+        receiver.close("_test");
+        assert!(!sender.send(message("_test")));
+    }
+
+    #[test]
+    fn dropped_backend_releases_names() {
+        let hub = LocalConnectionHub::default();
+        let mut sender = MemoryLocalConnectionBackend::new(&hub);
+        let mut receiver = MemoryLocalConnectionBackend::new(&hub);
+
+        assert!(receiver.connect("_test"));
+        assert!(sender.send(message("_test")));
+        drop(receiver);
+
+        assert!(!sender.send(message("_test")));
+        assert!(sender.connect("_test"));
+    }
+
+    #[test]
+    fn separate_hubs_are_isolated() {
+        let mut a = MemoryLocalConnectionBackend::default();
+        let mut b = MemoryLocalConnectionBackend::default();
+
+        assert!(a.connect("_test"));
+        assert!(b.connect("_test"));
+        assert!(!MemoryLocalConnectionBackend::default().send(message("_test")));
+        assert!(a.send(message("_test")));
+        assert!(b.receive().is_empty());
+        assert_eq!(a.receive().len(), 1);
+    }
+}
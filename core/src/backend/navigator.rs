@@ -4,8 +4,9 @@ use crate::loader::Error;
 use indexmap::IndexMap;
 use std::borrow::Cow;
 use std::collections::VecDeque;
-use std::fs;
+use std::fs::{self, File};
 use std::future::Future;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::ptr::null;
@@ -141,6 +142,29 @@ impl RequestOptions {
 /// result of type `Result<T, E>`.
 pub type OwnedFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + 'static>>;
 
+/// A callback which receives each chunk of a download as it arrives, along
+/// with the total length of the download, if known.
+///
+/// Returning an error cancels the download.
+pub type ChunkHandler = Box<dyn FnMut(&[u8], Option<u64>) -> Result<(), Error>>;
+
+/// The size of the chunks in which local files are read.
+const FILE_CHUNK_SIZE: usize = 8192;
+
+/// Read a local file, handing it to `on_chunk` in fixed-size chunks.
+pub fn read_file_chunked<P: AsRef<Path>>(path: P, mut on_chunk: ChunkHandler) -> Result<(), Error> {
+    let mut file = File::open(path).map_err(Error::NetworkError)?;
+    let total = file.metadata().map_err(Error::NetworkError)?.len();
+    let mut buffer = vec![0; FILE_CHUNK_SIZE];
+    loop {
+        let len = file.read(&mut buffer).map_err(Error::NetworkError)?;
+        if len == 0 {
+            return Ok(());
+        }
+        on_chunk(&buffer[..len], Some(total))?;
+    }
+}
+
 /// A backend interacting with a browser environment.
 pub trait NavigatorBackend {
     /// Cause a browser navigation to a given URL.
@@ -175,6 +199,24 @@ pub trait NavigatorBackend {
     /// Fetch data at a given URL and return it some time in the future.
     fn fetch(&self, url: &str, request_options: RequestOptions) -> OwnedFuture<Vec<u8>, Error>;
 
+    /// Fetch data at a given URL, handing it to `on_chunk` as it arrives.
+    ///
+    /// The returned future completes once the whole response has been
+    /// handled. Backends which cannot stream responses hand over the whole
+    /// response as a single chunk.
+    fn fetch_chunked(
+        &self,
+        url: &str,
+        request_options: RequestOptions,
+        mut on_chunk: ChunkHandler,
+    ) -> OwnedFuture<(), Error> {
+        let fetch = self.fetch(url, request_options);
+        Box::pin(async move {
+            let data = fetch.await?;
+            on_chunk(&data, Some(data.len() as u64))
+        })
+    }
+
     /// Get the amount of time since the SWF was launched.
     /// Used by the `getTimer` ActionScript call.
     fn time_since_launch(&mut self) -> Duration;
@@ -366,6 +408,18 @@ impl NavigatorBackend for NullNavigatorBackend {
         Box::pin(async move { fs::read(path).map_err(Error::NetworkError) })
     }
 
+    fn fetch_chunked(
+        &self,
+        url: &str,
+        _opts: RequestOptions,
+        on_chunk: ChunkHandler,
+    ) -> OwnedFuture<(), Error> {
+        let mut path = self.relative_base_path.clone();
+        path.push(url);
+
+        Box::pin(async move { read_file_chunked(path, on_chunk) })
+    }
+
     fn time_since_launch(&mut self) -> Duration {
         Duration::from_millis(0)
     }
@@ -4,7 +4,7 @@ use downcast_rs::Downcast;
 use gc_arena::Collect;
 use std::io::Read;
 pub use swf;
-use swf::Matrix;
+use swf::{BlendMode, Filter, Matrix};
 
 pub trait RenderBackend: Downcast {
     fn set_viewport_dimensions(&mut self, width: u32, height: u32);
@@ -46,6 +46,33 @@ pub trait RenderBackend: Downcast {
     fn deactivate_mask(&mut self);
     fn pop_mask(&mut self);
 
+    /// Begins rendering into a new offscreen layer.
+    /// Everything drawn until the matching `pop_filter_layer` call is rendered
+    /// into this layer instead of the current render target.
+    fn push_filter_layer(&mut self);
+
+    /// Finishes the current offscreen layer, applies `filters` to it in order,
+    /// and composites the result onto the previous render target.
+    fn pop_filter_layer(&mut self, filters: &[Filter]);
+
+    /// Begins rendering into a new offscreen layer that will be composited
+    /// onto the previous render target using `blend_mode`.
+    fn push_blend_mode(&mut self, blend_mode: BlendMode);
+
+    /// Finishes the current blend mode layer and composites it onto the previous render target.
+    fn pop_blend_mode(&mut self);
+
+    /// Begins rendering into `bitmap` instead of the screen, on top of its existing contents.
+    /// Everything drawn until the matching `end_offscreen` call is rendered into the bitmap,
+    /// with the origin at its top-left corner.
+    /// This must not be called during a frame.
+    /// Returns `false` if the bitmap can't be rendered into, in which case nothing is drawn.
+    fn begin_offscreen(&mut self, bitmap: BitmapHandle) -> bool;
+
+    /// Finishes rendering into the bitmap passed to `begin_offscreen`.
+    /// The new contents of the bitmap can then be read with `get_bitmap_pixels`.
+    fn end_offscreen(&mut self);
+
     fn get_bitmap_pixels(&mut self, bitmap: BitmapHandle) -> Option<Bitmap>;
     fn register_bitmap_raw(
         &mut self,
@@ -161,6 +188,14 @@ impl RenderBackend for NullRenderer {
     fn activate_mask(&mut self) {}
     fn deactivate_mask(&mut self) {}
     fn pop_mask(&mut self) {}
+    fn push_filter_layer(&mut self) {}
+    fn pop_filter_layer(&mut self, _filters: &[Filter]) {}
+    fn push_blend_mode(&mut self, _blend_mode: BlendMode) {}
+    fn pop_blend_mode(&mut self) {}
+    fn begin_offscreen(&mut self, _bitmap: BitmapHandle) -> bool {
+        false
+    }
+    fn end_offscreen(&mut self) {}
 
     fn get_bitmap_pixels(&mut self, _bitmap: BitmapHandle) -> Option<Bitmap> {
         None
@@ -578,6 +613,15 @@ pub fn unmultiply_alpha_rgba(rgba: &mut [u8]) {
     })
 }
 
+pub fn premultiply_alpha_rgba(rgba: &mut [u8]) {
+    rgba.chunks_exact_mut(4).for_each(|rgba| {
+        let a = f32::from(rgba[3]) / 255.0;
+        rgba[0] = (f32::from(rgba[0]) * a) as u8;
+        rgba[1] = (f32::from(rgba[1]) * a) as u8;
+        rgba[2] = (f32::from(rgba[2]) * a) as u8;
+    })
+}
+
 /// Converts an RGBA color from sRGB space to linear color space.
 pub fn srgb_to_linear(color: [f32; 4]) -> [f32; 4] {
     fn to_linear_channel(n: f32) -> f32 {
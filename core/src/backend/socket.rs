@@ -0,0 +1,87 @@
+//! Network socket platform functions
+
+use downcast_rs::Downcast;
+
+/// A handle to a socket created by a `SocketBackend`.
+pub type SocketHandle = u32;
+
+/// Something that happened to a socket since it was last polled.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SocketEvent {
+    /// A connection attempt finished, and either succeeded or failed.
+    ///
+    /// A socket which failed to connect is closed, and reports no further
+    /// events.
+    Connect(bool),
+
+    /// Data was received from the remote end.
+    Data(Vec<u8>),
+
+    /// The connection was closed by the remote end, or was lost.
+    Close,
+}
+
+/// A platform-provided source of TCP sockets.
+pub trait SocketBackend: Downcast {
+    /// Start connecting to a host.
+    ///
+    /// The result of the attempt is reported through `poll` as a
+    /// `SocketEvent::Connect`.
+    fn connect(&mut self, host: &str, port: u16) -> SocketHandle;
+
+    /// Send data on a connected socket.
+    ///
+    /// Data sent before the socket has connected is discarded.
+    fn send(&mut self, handle: SocketHandle, data: &[u8]);
+
+    /// Close a socket.
+    ///
+    /// No further events are reported for a socket closed by the player.
+    fn close(&mut self, handle: SocketHandle);
+
+    /// Take every event that happened since the last poll, oldest first.
+    fn poll(&mut self) -> Vec<(SocketHandle, SocketEvent)>;
+}
+impl_downcast!(SocketBackend);
+
+/// Socket backend that cannot connect to anything.
+///
+/// Every connection attempt fails.
+pub struct NullSocketBackend {
+    next_handle: SocketHandle,
+    events: Vec<(SocketHandle, SocketEvent)>,
+}
+
+impl NullSocketBackend {
+    pub fn new() -> Self {
+        Self {
+            next_handle: 0,
+            events: Vec::new(),
+        }
+    }
+}
+
+impl SocketBackend for NullSocketBackend {
+    fn connect(&mut self, _host: &str, _port: u16) -> SocketHandle {
+        let handle = self.next_handle;
+        self.next_handle = self.next_handle.wrapping_add(1);
+        self.events.push((handle, SocketEvent::Connect(false)));
+        handle
+    }
+
+    fn send(&mut self, _handle: SocketHandle, _data: &[u8]) {}
+
+    fn close(&mut self, handle: SocketHandle) {
+        self.events.retain(|(h, _)| *h != handle);
+    }
+
+    fn poll(&mut self) -> Vec<(SocketHandle, SocketEvent)> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+impl Default for NullSocketBackend {
+    fn default() -> Self {
+        NullSocketBackend::new()
+    }
+}
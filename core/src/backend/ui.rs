@@ -1,3 +1,4 @@
+use crate::context_menu::ContextMenuItem;
 use crate::events::KeyCode;
 use downcast_rs::Downcast;
 
@@ -18,6 +19,12 @@ pub trait UiBackend: Downcast {
     /// Set the clipboard to the given content
     fn set_clipboard_content(&mut self, content: String);
 
+    /// Get the current content of the clipboard
+    ///
+    /// Frontends that can't read the clipboard synchronously return an empty
+    /// string, and deliver pasted text with `PlayerEvent::TextPaste` instead.
+    fn clipboard_content(&mut self) -> String;
+
     fn is_fullscreen(&self) -> bool;
 
     /// Displays a warning about unsupported content in Ruffle.
@@ -25,6 +32,13 @@ pub trait UiBackend: Downcast {
     fn display_unsupported_message(&self);
     // Unused, but kept in case we need it later
     fn message(&self, message: &str);
+
+    /// Displays a context menu with the items from `Player::prepare_context_menu`.
+    ///
+    /// This must not block. Once the user chooses an item or dismisses the menu,
+    /// the frontend reports it with `Player::run_context_menu_callback` or
+    /// `Player::clear_custom_menu_items`.
+    fn display_context_menu(&mut self, items: &[ContextMenuItem]);
 }
 impl_downcast!(UiBackend);
 
@@ -81,6 +95,10 @@ impl UiBackend for NullUiBackend {
 
     fn set_clipboard_content(&mut self, _content: String) {}
 
+    fn clipboard_content(&mut self) -> String {
+        String::new()
+    }
+
     fn is_fullscreen(&self) -> bool {
         false
     }
@@ -88,6 +106,8 @@ impl UiBackend for NullUiBackend {
     fn display_unsupported_message(&self) {}
 
     fn message(&self, _message: &str) {}
+
+    fn display_context_menu(&mut self, _items: &[ContextMenuItem]) {}
 }
 
 impl Default for NullUiBackend {
@@ -0,0 +1,162 @@
+//! Video decoding backends.
+
+use crate::backend::render::{BitmapInfo, RenderBackend};
+use generational_arena::{Arena, Index};
+use swf::{VideoCodec, VideoDeblocking};
+
+pub mod decoders;
+pub mod software;
+
+pub use software::SoftwareVideoBackend;
+
+pub type VideoStreamHandle = Index;
+
+type Error = Box<dyn std::error::Error>;
+
+/// An encoded video frame of some video codec.
+pub struct EncodedFrame<'a> {
+    /// The codec used to encode the frame.
+    pub codec: VideoCodec,
+
+    /// The raw bitstream data to funnel into the codec.
+    pub data: &'a [u8],
+
+    /// A caller-specified frame ID. Frame IDs must be consistent between
+    /// subsequent uses of the same data stream.
+    pub frame_id: u32,
+}
+
+/// A decoded frame of video in RGBA format.
+pub struct DecodedFrame {
+    pub width: u16,
+    pub height: u16,
+    pub rgba: Vec<u8>,
+}
+
+/// What dependencies a given video frame has on any previous frames.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrameDependency {
+    /// This frame has no reference frames and can be seeked to at any time.
+    None,
+
+    /// This frame has some number of reference frames that prohibit any
+    /// out-of-order decoding.
+    ///
+    /// The only legal way to decode a `Past` frame is to decode every prior
+    /// frame from the last `None` frame. In the event that there is no prior
+    /// `None` frame, then video decoding should start from the beginning.
+    Past,
+}
+
+impl FrameDependency {
+    /// Determine if this given frame is a keyframe.
+    ///
+    /// A keyframe is a frame that can be independently seeked to.
+    pub fn is_keyframe(self) -> bool {
+        matches!(self, FrameDependency::None)
+    }
+}
+
+/// A backend that provides access to some video decoding machinery.
+pub trait VideoBackend {
+    /// Register a new video stream.
+    ///
+    /// The returned `VideoStreamHandle` must be used for all further calls to
+    /// this stream.
+    fn register_video_stream(
+        &mut self,
+        num_frames: u32,
+        size: (u16, u16),
+        codec: VideoCodec,
+        filter: VideoDeblocking,
+    ) -> Result<VideoStreamHandle, Error>;
+
+    /// Preload a frame.
+    ///
+    /// No decoding is intended to happen at this point in time. Instead, the
+    /// video data should be inspected to determine inter-frame dependencies
+    /// between this and any previous frames in the stream.
+    ///
+    /// Frames should be preloaded in the order that they are received.
+    ///
+    /// Any dependencies listed here are inherent to the video bitstream. The
+    /// containing video stream is also permitted to introduce additional
+    /// interframe dependencies.
+    fn preload_video_stream_frame(
+        &mut self,
+        stream: VideoStreamHandle,
+        encoded_frame: EncodedFrame<'_>,
+    ) -> Result<FrameDependency, Error>;
+
+    /// Decode a frame of a given video stream.
+    ///
+    /// The frame must either be the frame following the last decoded frame of
+    /// this stream, or a keyframe as reported by `preload_video_stream_frame`.
+    ///
+    /// Decoded frames are uploaded to the given renderer, and the bitmap that
+    /// now holds the frame is returned.
+    fn decode_video_stream_frame(
+        &mut self,
+        stream: VideoStreamHandle,
+        encoded_frame: EncodedFrame<'_>,
+        renderer: &mut dyn RenderBackend,
+    ) -> Result<BitmapInfo, Error>;
+
+    /// Unregister a video stream that is no longer used, freeing its decoder.
+    ///
+    /// The handle must not be used again. The bitmap that the stream's frames
+    /// were uploaded to remains registered with the renderer.
+    fn unregister_video_stream(&mut self, stream: VideoStreamHandle);
+}
+
+/// A video backend that does not support any video codecs.
+pub struct NullVideoBackend {
+    streams: Arena<()>,
+}
+
+impl NullVideoBackend {
+    pub fn new() -> Self {
+        Self {
+            streams: Arena::new(),
+        }
+    }
+}
+
+impl Default for NullVideoBackend {
+    fn default() -> Self {
+        NullVideoBackend::new()
+    }
+}
+
+impl VideoBackend for NullVideoBackend {
+    fn register_video_stream(
+        &mut self,
+        _num_frames: u32,
+        _size: (u16, u16),
+        _codec: VideoCodec,
+        _filter: VideoDeblocking,
+    ) -> Result<VideoStreamHandle, Error> {
+        Ok(self.streams.insert(()))
+    }
+
+    fn preload_video_stream_frame(
+        &mut self,
+        _stream: VideoStreamHandle,
+        _encoded_frame: EncodedFrame<'_>,
+    ) -> Result<FrameDependency, Error> {
+        Ok(FrameDependency::None)
+    }
+
+    fn decode_video_stream_frame(
+        &mut self,
+        _stream: VideoStreamHandle,
+        _encoded_frame: EncodedFrame<'_>,
+        _renderer: &mut dyn RenderBackend,
+    ) -> Result<BitmapInfo, Error> {
+        Err("Video decoding is not supported by this video backend".into())
+    }
+
+    fn unregister_video_stream(&mut self, stream: VideoStreamHandle) {
+        self.streams.remove(stream);
+    }
+}
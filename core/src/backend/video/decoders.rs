@@ -0,0 +1,31 @@
+//! Video decoders.
+
+mod h263;
+mod screen;
+
+pub use h263::H263Decoder;
+pub use screen::ScreenVideoDecoder;
+
+use crate::backend::video::{DecodedFrame, EncodedFrame, FrameDependency};
+use swf::VideoCodec;
+
+type Error = Box<dyn std::error::Error>;
+
+/// A video decoder for a single stream. Frames are fed in order starting from a keyframe.
+pub trait VideoDecoder {
+    /// Inspects a frame without decoding it, and returns which frames it depends on.
+    fn preload_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<FrameDependency, Error>;
+
+    /// Decodes a frame into RGBA pixels.
+    fn decode_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<DecodedFrame, Error>;
+}
+
+/// Instantiate a decoder for the given video codec.
+pub fn make_decoder(codec: VideoCodec) -> Result<Box<dyn VideoDecoder>, Error> {
+    let decoder: Box<dyn VideoDecoder> = match codec {
+        VideoCodec::H263 => Box::new(H263Decoder::new()),
+        VideoCodec::ScreenVideo => Box::new(ScreenVideoDecoder::new()),
+        _ => return Err(format!("Unsupported video codec {:?}", codec).into()),
+    };
+    Ok(decoder)
+}
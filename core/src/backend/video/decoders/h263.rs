@@ -0,0 +1,997 @@
+//! Sorenson H.263 (Sorenson Spark) video decoder.
+//!
+//! Sorenson H.263 is baseline H.263 with a simplified picture header, no GOB headers and,
+//! in version 1 of the bitstream, a different escape code for transform coefficients.
+//! None of the optional H.263 annexes are supported.
+
+use crate::backend::video::decoders::VideoDecoder;
+use crate::backend::video::{DecodedFrame, EncodedFrame, FrameDependency};
+
+type Error = Box<dyn std::error::Error>;
+
+/// Sorenson H.263 decoder.
+pub struct H263Decoder {
+    /// The last decoded reference picture, used to predict inter pictures.
+    reference: Option<Picture>,
+
+    /// `IDCT_TABLE[x][u]` is the contribution of frequency `u` to sample `x`.
+    idct_table: [[f32; 8]; 8],
+}
+
+impl H263Decoder {
+    pub fn new() -> Self {
+        let mut idct_table = [[0.0; 8]; 8];
+        for (x, row) in idct_table.iter_mut().enumerate() {
+            for (u, value) in row.iter_mut().enumerate() {
+                let scale = if u == 0 {
+                    std::f32::consts::FRAC_1_SQRT_2
+                } else {
+                    1.0
+                };
+                *value =
+                    scale * ((2 * x + 1) as f32 * u as f32 * std::f32::consts::PI / 16.0).cos();
+            }
+        }
+
+        Self {
+            reference: None,
+            idct_table,
+        }
+    }
+}
+
+impl Default for H263Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VideoDecoder for H263Decoder {
+    fn preload_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<FrameDependency, Error> {
+        let header = PictureHeader::read(&mut BitReader::new(encoded_frame.data))?;
+        Ok(match header.picture_type {
+            PictureType::Intra => FrameDependency::None,
+            PictureType::Inter | PictureType::DisposableInter => FrameDependency::Past,
+        })
+    }
+
+    fn decode_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<DecodedFrame, Error> {
+        let mut reader = BitReader::new(encoded_frame.data);
+        let header = PictureHeader::read(&mut reader)?;
+        if header.width == 0 || header.height == 0 {
+            return Err("H.263 picture has no size".into());
+        }
+
+        let reference = match header.picture_type {
+            PictureType::Intra => None,
+            PictureType::Inter | PictureType::DisposableInter => Some(
+                self.reference
+                    .as_ref()
+                    .filter(|reference| {
+                        reference.width == header.width && reference.height == header.height
+                    })
+                    .ok_or("H.263 inter picture has no reference picture")?,
+            ),
+        };
+
+        let mut picture = Picture::new(header.width, header.height);
+        let mut decoder = PictureDecoder {
+            reader,
+            version: header.version,
+            quantizer: i32::from(header.quantizer),
+            picture: &mut picture,
+            reference,
+            motion_vectors: vec![
+                MotionVector::default();
+                header.mb_width() * header.mb_height() * 4
+            ],
+            idct_table: &self.idct_table,
+        };
+        for mb_y in 0..header.mb_height() {
+            for mb_x in 0..header.mb_width() {
+                decoder.decode_macroblock(mb_x, mb_y)?;
+            }
+        }
+
+        let frame = DecodedFrame {
+            width: header.width,
+            height: header.height,
+            rgba: picture.to_rgba(),
+        };
+
+        // Disposable pictures are never used as a reference.
+        if header.picture_type != PictureType::DisposableInter {
+            self.reference = Some(picture);
+        }
+
+        Ok(frame)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PictureType {
+    Intra,
+    Inter,
+    DisposableInter,
+}
+
+struct PictureHeader {
+    version: u8,
+    width: u16,
+    height: u16,
+    picture_type: PictureType,
+    quantizer: u8,
+}
+
+impl PictureHeader {
+    fn read(reader: &mut BitReader<'_>) -> Result<Self, Error> {
+        if reader.read(17)? != 1 {
+            return Err("Invalid H.263 picture start code".into());
+        }
+
+        let version = reader.read(5)? as u8;
+        if version > 1 {
+            return Err(format!("Unsupported Sorenson H.263 version {}", version).into());
+        }
+
+        let _temporal_reference = reader.read(8)?;
+        let (width, height) = match reader.read(3)? {
+            0 => (reader.read(8)? as u16, reader.read(8)? as u16),
+            1 => (reader.read(16)? as u16, reader.read(16)? as u16),
+            2 => (352, 288),
+            3 => (176, 144),
+            4 => (128, 96),
+            5 => (320, 240),
+            6 => (160, 120),
+            _ => return Err("Invalid H.263 picture size".into()),
+        };
+        let picture_type = match reader.read(2)? {
+            0 => PictureType::Intra,
+            1 => PictureType::Inter,
+            2 => PictureType::DisposableInter,
+            _ => return Err("Invalid H.263 picture type".into()),
+        };
+        // Deblocking is an optional post-processing step, which we don't do.
+        let _deblocking = reader.read_bit()?;
+        let quantizer = reader.read(5)? as u8;
+
+        // Skip any extra information.
+        while reader.read_bit()? {
+            reader.read(8)?;
+        }
+
+        Ok(Self {
+            version,
+            width,
+            height,
+            picture_type,
+            quantizer,
+        })
+    }
+
+    fn mb_width(&self) -> usize {
+        (usize::from(self.width) + 15) / 16
+    }
+
+    fn mb_height(&self) -> usize {
+        (usize::from(self.height) + 15) / 16
+    }
+}
+
+/// A YUV 4:2:0 picture. The planes are padded to a whole number of macroblocks.
+struct Picture {
+    width: u16,
+    height: u16,
+    luma: Plane,
+    chroma_b: Plane,
+    chroma_r: Plane,
+}
+
+impl Picture {
+    fn new(width: u16, height: u16) -> Self {
+        let mb_width = (usize::from(width) + 15) / 16;
+        let mb_height = (usize::from(height) + 15) / 16;
+        Self {
+            width,
+            height,
+            luma: Plane::new(mb_width * 16, mb_height * 16),
+            chroma_b: Plane::new(mb_width * 8, mb_height * 8),
+            chroma_r: Plane::new(mb_width * 8, mb_height * 8),
+        }
+    }
+
+    /// Converts the visible area of the picture to RGBA using the BT.601 color space.
+    fn to_rgba(&self) -> Vec<u8> {
+        let width = usize::from(self.width);
+        let height = usize::from(self.height);
+        let mut rgba = Vec::with_capacity(width * height * 4);
+        for y in 0..height {
+            for x in 0..width {
+                let luma = f32::from(self.luma.get(x, y)) - 16.0;
+                let cb = f32::from(self.chroma_b.get(x / 2, y / 2)) - 128.0;
+                let cr = f32::from(self.chroma_r.get(x / 2, y / 2)) - 128.0;
+                let luma = 1.164 * luma;
+                let r = luma + 1.596 * cr;
+                let g = luma - 0.813 * cr - 0.391 * cb;
+                let b = luma + 2.018 * cb;
+                rgba.extend_from_slice(&[clamp_color(r), clamp_color(g), clamp_color(b), 255]);
+            }
+        }
+        rgba
+    }
+}
+
+fn clamp_color(value: f32) -> u8 {
+    value.round().max(0.0).min(255.0) as u8
+}
+
+struct Plane {
+    width: usize,
+    height: usize,
+    data: Vec<u8>,
+}
+
+impl Plane {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![0; width * height],
+        }
+    }
+
+    fn get(&self, x: usize, y: usize) -> u8 {
+        self.data[y * self.width + x]
+    }
+
+    /// Returns a sample, clamping coordinates outside of the plane to its edges.
+    fn get_clamped(&self, x: isize, y: isize) -> i32 {
+        let x = x.max(0).min(self.width as isize - 1) as usize;
+        let y = y.max(0).min(self.height as isize - 1) as usize;
+        i32::from(self.data[y * self.width + x])
+    }
+
+    /// Writes an 8x8 block of samples at the given position.
+    fn put_block(&mut self, x: usize, y: usize, block: &[u8; 64]) {
+        for (row, samples) in block.chunks_exact(8).enumerate() {
+            let start = (y + row) * self.width + x;
+            self.data[start..start + 8].copy_from_slice(samples);
+        }
+    }
+
+    /// Predicts an 8x8 block from this plane, displaced by a motion vector in half-samples.
+    fn predict_block(&self, x: usize, y: usize, motion_vector: MotionVector) -> [u8; 64] {
+        let half_x = x as isize * 2 + isize::from(motion_vector.x);
+        let half_y = y as isize * 2 + isize::from(motion_vector.y);
+        let (x, y) = (half_x >> 1, half_y >> 1);
+        let (frac_x, frac_y) = (half_x & 1 != 0, half_y & 1 != 0);
+
+        let mut block = [0; 64];
+        for row in 0..8 {
+            for column in 0..8 {
+                let (sx, sy) = (x + column as isize, y + row as isize);
+                let a = self.get_clamped(sx, sy);
+                let value = match (frac_x, frac_y) {
+                    (false, false) => a,
+                    (true, false) => (a + self.get_clamped(sx + 1, sy) + 1) >> 1,
+                    (false, true) => (a + self.get_clamped(sx, sy + 1) + 1) >> 1,
+                    (true, true) => {
+                        (a + self.get_clamped(sx + 1, sy)
+                            + self.get_clamped(sx, sy + 1)
+                            + self.get_clamped(sx + 1, sy + 1)
+                            + 2)
+                            >> 2
+                    }
+                };
+                block[row * 8 + column] = value as u8;
+            }
+        }
+        block
+    }
+}
+
+/// A motion vector in half-sample units.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct MotionVector {
+    x: i16,
+    y: i16,
+}
+
+impl MotionVector {
+    fn median(a: Self, b: Self, c: Self) -> Self {
+        fn median(a: i16, b: i16, c: i16) -> i16 {
+            a.max(b).min(a.min(b).max(c))
+        }
+        Self {
+            x: median(a.x, b.x, c.x),
+            y: median(a.y, b.y, c.y),
+        }
+    }
+
+    /// Derives the chroma motion vector for a macroblock with a single luma motion vector.
+    fn to_chroma(self) -> Self {
+        // Quarter-sample positions are rounded to the half-sample position.
+        fn halve(value: i16) -> i16 {
+            (value >> 1) | (value & 1)
+        }
+        Self {
+            x: halve(self.x),
+            y: halve(self.y),
+        }
+    }
+
+    /// Derives the chroma motion vector for a macroblock with four luma motion vectors.
+    fn to_chroma_from_sum(sum_x: i16, sum_y: i16) -> Self {
+        const ROUNDING: [i16; 16] = [0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2];
+        fn divide(sum: i16) -> i16 {
+            let magnitude = sum.abs();
+            let value = ROUNDING[(magnitude & 15) as usize] + (magnitude >> 4) * 2;
+            if sum < 0 {
+                -value
+            } else {
+                value
+            }
+        }
+        Self {
+            x: divide(sum_x),
+            y: divide(sum_y),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MacroblockType {
+    Inter,
+    InterQ,
+    Inter4V,
+    Intra,
+    IntraQ,
+}
+
+/// State used while decoding the macroblocks of a single picture.
+struct PictureDecoder<'a> {
+    reader: BitReader<'a>,
+    version: u8,
+    quantizer: i32,
+    picture: &'a mut Picture,
+    reference: Option<&'a Picture>,
+
+    /// The motion vector of each 8x8 luma block of the picture, in raster order.
+    motion_vectors: Vec<MotionVector>,
+
+    idct_table: &'a [[f32; 8]; 8],
+}
+
+impl<'a> PictureDecoder<'a> {
+    fn decode_macroblock(&mut self, mb_x: usize, mb_y: usize) -> Result<(), Error> {
+        let (mb_type, cbpc) = loop {
+            if let Some(reference) = self.reference {
+                // Uncoded macroblocks are copied from the reference picture.
+                if self.reader.read_bit()? {
+                    self.copy_macroblock(reference, mb_x, mb_y);
+                    return Ok(());
+                }
+                if let Some(mcbpc) = self.reader.read_vlc(&MCBPC_INTER)? {
+                    break mcbpc;
+                }
+            } else if let Some(mcbpc) = self.reader.read_vlc(&MCBPC_INTRA)? {
+                break mcbpc;
+            }
+        };
+
+        let is_intra = matches!(mb_type, MacroblockType::Intra | MacroblockType::IntraQ);
+        let mut cbpy = self.reader.read_vlc(&CBPY)?;
+        if !is_intra {
+            cbpy ^= 0xf;
+        }
+        let coded_blocks = (cbpy << 2) | cbpc;
+
+        if matches!(mb_type, MacroblockType::InterQ | MacroblockType::IntraQ) {
+            const DQUANT: [i32; 4] = [-1, -2, 1, 2];
+            let dquant = DQUANT[self.reader.read(2)? as usize];
+            self.quantizer = (self.quantizer + dquant).max(1).min(31);
+        }
+
+        let blocks_wide = self.blocks_wide();
+        let (block_x, block_y) = (mb_x * 2, mb_y * 2);
+        match mb_type {
+            MacroblockType::Inter | MacroblockType::InterQ => {
+                let predictor = self.predict_motion_vector(block_x, block_y, 0);
+                let motion_vector = self.read_motion_vector(predictor)?;
+                for i in 0..4 {
+                    let index = (block_y + i / 2) * blocks_wide + block_x + i % 2;
+                    self.motion_vectors[index] = motion_vector;
+                }
+            }
+            MacroblockType::Inter4V => {
+                for i in 0..4 {
+                    let (x, y) = (block_x + i % 2, block_y + i / 2);
+                    let predictor = self.predict_motion_vector(x, y, i);
+                    self.motion_vectors[y * blocks_wide + x] =
+                        self.read_motion_vector(predictor)?;
+                }
+            }
+            MacroblockType::Intra | MacroblockType::IntraQ => {
+                for i in 0..4 {
+                    let index = (block_y + i / 2) * blocks_wide + block_x + i % 2;
+                    self.motion_vectors[index] = MotionVector::default();
+                }
+            }
+        }
+
+        // Read all six blocks: four luma blocks, then the blue and red chroma blocks.
+        let mut blocks = [[0i32; 64]; 6];
+        for (i, block) in blocks.iter_mut().enumerate() {
+            let is_coded = coded_blocks & (32 >> i) != 0;
+            let mut index = 0;
+            if is_intra {
+                let dc = self.reader.read(8)? as i32;
+                block[0] = if dc == 255 { 1024 } else { dc * 8 };
+                index = 1;
+            }
+            if is_coded {
+                self.read_coefficients(block, index)?;
+            }
+        }
+
+        // Reconstruct the macroblock.
+        for (i, block) in blocks.iter().enumerate() {
+            let is_coded = is_intra || coded_blocks & (32 >> i) != 0;
+            let (x, y) = match i {
+                0..=3 => (mb_x * 16 + (i % 2) * 8, mb_y * 16 + (i / 2) * 8),
+                _ => (mb_x * 8, mb_y * 8),
+            };
+
+            let mut samples = match self.reference {
+                Some(reference) if !is_intra => {
+                    let (reference_plane, motion_vector) = match i {
+                        0..=3 => {
+                            let index = (block_y + i / 2) * blocks_wide + block_x + i % 2;
+                            (&reference.luma, self.motion_vectors[index])
+                        }
+                        _ => {
+                            let motion_vector = self.chroma_motion_vector(block_x, block_y);
+                            if i == 4 {
+                                (&reference.chroma_b, motion_vector)
+                            } else {
+                                (&reference.chroma_r, motion_vector)
+                            }
+                        }
+                    };
+                    reference_plane.predict_block(x, y, motion_vector)
+                }
+                _ => [0; 64],
+            };
+
+            if is_coded {
+                let residual = idct(block, self.idct_table);
+                for (sample, residual) in samples.iter_mut().zip(residual.iter()) {
+                    *sample = (i32::from(*sample) + residual).max(0).min(255) as u8;
+                }
+            }
+            let plane = match i {
+                0..=3 => &mut self.picture.luma,
+                4 => &mut self.picture.chroma_b,
+                _ => &mut self.picture.chroma_r,
+            };
+            plane.put_block(x, y, &samples);
+        }
+
+        Ok(())
+    }
+
+    fn blocks_wide(&self) -> usize {
+        self.picture.luma.width / 8
+    }
+
+    /// Copies a macroblock from the reference picture without motion compensation.
+    fn copy_macroblock(&mut self, reference: &Picture, mb_x: usize, mb_y: usize) {
+        let blocks_wide = self.blocks_wide();
+        for i in 0..4 {
+            let index = (mb_y * 2 + i / 2) * blocks_wide + mb_x * 2 + i % 2;
+            self.motion_vectors[index] = MotionVector::default();
+        }
+
+        let mut planes = [
+            (&reference.luma, &mut self.picture.luma, 16),
+            (&reference.chroma_b, &mut self.picture.chroma_b, 8),
+            (&reference.chroma_r, &mut self.picture.chroma_r, 8),
+        ];
+        for (source, destination, size) in planes.iter_mut() {
+            let size = *size;
+            for row in mb_y * size..(mb_y + 1) * size {
+                let start = row * source.width + mb_x * size;
+                destination.data[start..start + size]
+                    .copy_from_slice(&source.data[start..start + size]);
+            }
+        }
+    }
+
+    /// Predicts the motion vector of an 8x8 luma block from the median of its neighbours.
+    /// `block` is the index of the block within its macroblock.
+    fn predict_motion_vector(&self, x: usize, y: usize, block: usize) -> MotionVector {
+        let blocks_wide = self.blocks_wide();
+        let get = |x: isize, y: isize| {
+            if x < 0 || y < 0 || x >= blocks_wide as isize {
+                MotionVector::default()
+            } else {
+                self.motion_vectors[y as usize * blocks_wide + x as usize]
+            }
+        };
+        let (x, y) = (x as isize, y as isize);
+
+        let left = get(x - 1, y);
+        if y == 0 {
+            // On the top row of the picture, the only candidate is the left block.
+            return left;
+        }
+        let above = get(x, y - 1);
+        const ABOVE_RIGHT_OFFSET: [isize; 4] = [2, 1, 1, -1];
+        let above_right = get(x + ABOVE_RIGHT_OFFSET[block], y - 1);
+        MotionVector::median(left, above, above_right)
+    }
+
+    fn read_motion_vector(&mut self, predictor: MotionVector) -> Result<MotionVector, Error> {
+        Ok(MotionVector {
+            x: self.read_motion_vector_component(predictor.x)?,
+            y: self.read_motion_vector_component(predictor.y)?,
+        })
+    }
+
+    fn read_motion_vector_component(&mut self, predictor: i16) -> Result<i16, Error> {
+        let mut delta = self.reader.read_vlc(&MVD)?;
+        if delta != 0 && self.reader.read_bit()? {
+            delta = -delta;
+        }
+
+        // Vectors wrap around to stay within [-16, 15.5] samples.
+        let mut value = predictor + delta;
+        if value < -32 {
+            value += 64;
+        } else if value > 31 {
+            value -= 64;
+        }
+        Ok(value)
+    }
+
+    /// Derives the chroma motion vector of the macroblock whose top-left luma block is given.
+    fn chroma_motion_vector(&self, block_x: usize, block_y: usize) -> MotionVector {
+        let blocks_wide = self.blocks_wide();
+        let vectors = [
+            self.motion_vectors[block_y * blocks_wide + block_x],
+            self.motion_vectors[block_y * blocks_wide + block_x + 1],
+            self.motion_vectors[(block_y + 1) * blocks_wide + block_x],
+            self.motion_vectors[(block_y + 1) * blocks_wide + block_x + 1],
+        ];
+        if vectors.iter().all(|vector| *vector == vectors[0]) {
+            vectors[0].to_chroma()
+        } else {
+            MotionVector::to_chroma_from_sum(
+                vectors.iter().map(|vector| vector.x).sum(),
+                vectors.iter().map(|vector| vector.y).sum(),
+            )
+        }
+    }
+
+    /// Reads and dequantizes the transform coefficients of a block, starting at the given
+    /// index in zigzag order.
+    fn read_coefficients(&mut self, block: &mut [i32; 64], mut index: usize) -> Result<(), Error> {
+        loop {
+            let (last, run, level) = match self.reader.read_vlc(&TCOEF)? {
+                Some((last, run, level)) => {
+                    let level = i32::from(level);
+                    let level = if self.reader.read_bit()? {
+                        -level
+                    } else {
+                        level
+                    };
+                    (last, usize::from(run), level)
+                }
+                None if self.version == 0 => {
+                    let last = self.reader.read_bit()?;
+                    let run = self.reader.read(6)? as usize;
+                    let level = self.reader.read_signed(8)?;
+                    (last, run, level)
+                }
+                None => {
+                    let is_long = self.reader.read_bit()?;
+                    let last = self.reader.read_bit()?;
+                    let run = self.reader.read(6)? as usize;
+                    let level = self.reader.read_signed(if is_long { 11 } else { 7 })?;
+                    (last, run, level)
+                }
+            };
+
+            index += run;
+            if index >= 64 {
+                return Err("Invalid H.263 transform coefficient run".into());
+            }
+            block[ZIGZAG[index]] = dequantize(level, self.quantizer);
+            index += 1;
+
+            if last {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn dequantize(level: i32, quantizer: i32) -> i32 {
+    if level == 0 {
+        return 0;
+    }
+    let mut magnitude = quantizer * (2 * level.abs() + 1);
+    if quantizer % 2 == 0 {
+        magnitude -= 1;
+    }
+    (magnitude * level.signum()).max(-2048).min(2047)
+}
+
+/// Performs an 8x8 inverse discrete cosine transform.
+fn idct(block: &[i32; 64], table: &[[f32; 8]; 8]) -> [i32; 64] {
+    let mut rows = [0.0f32; 64];
+    for v in 0..8 {
+        for x in 0..8 {
+            rows[v * 8 + x] = (0..8).map(|u| block[v * 8 + u] as f32 * table[x][u]).sum();
+        }
+    }
+
+    let mut output = [0; 64];
+    for x in 0..8 {
+        for y in 0..8 {
+            let value: f32 = (0..8).map(|v| rows[v * 8 + x] * table[y][v]).sum();
+            output[y * 8 + x] = (value / 4.0).round() as i32;
+        }
+    }
+    output
+}
+
+/// Reads bits from a byte slice, most significant bit first.
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    /// Returns the next `bits` bits without consuming them.
+    /// Bits past the end of the data read as zero.
+    fn peek(&self, bits: u32) -> u32 {
+        let mut value = 0;
+        for i in 0..bits as usize {
+            let position = self.position + i;
+            let bit = self
+                .data
+                .get(position / 8)
+                .map(|byte| (byte >> (7 - position % 8)) & 1)
+                .unwrap_or(0);
+            value = (value << 1) | u32::from(bit);
+        }
+        value
+    }
+
+    fn skip(&mut self, bits: u32) -> Result<(), Error> {
+        self.position += bits as usize;
+        if self.position > self.data.len() * 8 {
+            return Err("Unexpected end of H.263 bitstream".into());
+        }
+        Ok(())
+    }
+
+    fn read(&mut self, bits: u32) -> Result<u32, Error> {
+        let value = self.peek(bits);
+        self.skip(bits)?;
+        Ok(value)
+    }
+
+    fn read_bit(&mut self) -> Result<bool, Error> {
+        Ok(self.read(1)? != 0)
+    }
+
+    /// Reads a two's complement signed integer.
+    fn read_signed(&mut self, bits: u32) -> Result<i32, Error> {
+        let value = self.read(bits)?;
+        Ok(((value << (32 - bits)) as i32) >> (32 - bits))
+    }
+
+    /// Reads a variable-length code from the given table.
+    fn read_vlc<T: Copy>(&mut self, table: &[Vlc<T>]) -> Result<T, Error> {
+        const MAX_BITS: u32 = 13;
+        let bits = self.peek(MAX_BITS);
+        for &(code, length, value) in table {
+            if bits >> (MAX_BITS - u32::from(length)) == u32::from(code) {
+                self.skip(length.into())?;
+                return Ok(value);
+            }
+        }
+        Err("Invalid H.263 variable-length code".into())
+    }
+}
+
+/// A variable-length code: the code bits, the number of bits, and the decoded value.
+type Vlc<T> = (u16, u8, T);
+
+/// Zigzag scan order of the transform coefficients.
+const ZIGZAG: [usize; 64] = [
+    0, 1, 8, 16, 9, 2, 3, 10, 17, 24, 32, 25, 18, 11, 4, 5, 12, 19, 26, 33, 40, 48, 41, 34, 27, 20,
+    13, 6, 7, 14, 21, 28, 35, 42, 49, 56, 57, 50, 43, 36, 29, 22, 15, 23, 30, 37, 44, 51, 58, 59,
+    52, 45, 38, 31, 39, 46, 53, 60, 61, 54, 47, 55, 62, 63,
+];
+
+/// Macroblock type and chroma coded block pattern for intra pictures.
+/// `None` is macroblock stuffing.
+const MCBPC_INTRA: [Vlc<Option<(MacroblockType, u8)>>; 9] = [
+    (0b1, 1, Some((MacroblockType::Intra, 0))),
+    (0b001, 3, Some((MacroblockType::Intra, 1))),
+    (0b010, 3, Some((MacroblockType::Intra, 2))),
+    (0b011, 3, Some((MacroblockType::Intra, 3))),
+    (0b0001, 4, Some((MacroblockType::IntraQ, 0))),
+    (0b000001, 6, Some((MacroblockType::IntraQ, 1))),
+    (0b000010, 6, Some((MacroblockType::IntraQ, 2))),
+    (0b000011, 6, Some((MacroblockType::IntraQ, 3))),
+    (0b000000001, 9, None),
+];
+
+/// Macroblock type and chroma coded block pattern for inter pictures.
+/// `None` is macroblock stuffing.
+const MCBPC_INTER: [Vlc<Option<(MacroblockType, u8)>>; 21] = [
+    (0b1, 1, Some((MacroblockType::Inter, 0))),
+    (0b0011, 4, Some((MacroblockType::Inter, 1))),
+    (0b0010, 4, Some((MacroblockType::Inter, 2))),
+    (0b000101, 6, Some((MacroblockType::Inter, 3))),
+    (0b011, 3, Some((MacroblockType::InterQ, 0))),
+    (0b0000111, 7, Some((MacroblockType::InterQ, 1))),
+    (0b0000110, 7, Some((MacroblockType::InterQ, 2))),
+    (0b000000101, 9, Some((MacroblockType::InterQ, 3))),
+    (0b010, 3, Some((MacroblockType::Inter4V, 0))),
+    (0b0000101, 7, Some((MacroblockType::Inter4V, 1))),
+    (0b0000100, 7, Some((MacroblockType::Inter4V, 2))),
+    (0b00000101, 8, Some((MacroblockType::Inter4V, 3))),
+    (0b00011, 5, Some((MacroblockType::Intra, 0))),
+    (0b00000100, 8, Some((MacroblockType::Intra, 1))),
+    (0b00000011, 8, Some((MacroblockType::Intra, 2))),
+    (0b0000011, 7, Some((MacroblockType::Intra, 3))),
+    (0b000100, 6, Some((MacroblockType::IntraQ, 0))),
+    (0b000000100, 9, Some((MacroblockType::IntraQ, 1))),
+    (0b000000011, 9, Some((MacroblockType::IntraQ, 2))),
+    (0b000000010, 9, Some((MacroblockType::IntraQ, 3))),
+    (0b000000001, 9, None),
+];
+
+/// Luma coded block pattern, as used by intra macroblocks.
+const CBPY: [Vlc<u8>; 16] = [
+    (0b0011, 4, 0),
+    (0b00101, 5, 1),
+    (0b00100, 5, 2),
+    (0b1001, 4, 3),
+    (0b00011, 5, 4),
+    (0b0111, 4, 5),
+    (0b000010, 6, 6),
+    (0b1011, 4, 7),
+    (0b00010, 5, 8),
+    (0b000011, 6, 9),
+    (0b0101, 4, 10),
+    (0b1010, 4, 11),
+    (0b0100, 4, 12),
+    (0b1000, 4, 13),
+    (0b0110, 4, 14),
+    (0b11, 2, 15),
+];
+
+/// Motion vector difference magnitudes, in half-samples. A sign bit follows non-zero values.
+const MVD: [Vlc<i16>; 33] = [
+    (0b1, 1, 0),
+    (0b01, 2, 1),
+    (0b001, 3, 2),
+    (0b0001, 4, 3),
+    (0b000011, 6, 4),
+    (0b0000101, 7, 5),
+    (0b0000100, 7, 6),
+    (0b0000011, 7, 7),
+    (0b000001011, 9, 8),
+    (0b000001010, 9, 9),
+    (0b000001001, 9, 10),
+    (0b0000010001, 10, 11),
+    (0b0000010000, 10, 12),
+    (0b0000001111, 10, 13),
+    (0b0000001110, 10, 14),
+    (0b0000001101, 10, 15),
+    (0b0000001100, 10, 16),
+    (0b0000001011, 10, 17),
+    (0b0000001010, 10, 18),
+    (0b0000001001, 10, 19),
+    (0b0000001000, 10, 20),
+    (0b0000000111, 10, 21),
+    (0b0000000110, 10, 22),
+    (0b0000000101, 10, 23),
+    (0b0000000100, 10, 24),
+    (0b00000000111, 11, 25),
+    (0b00000000110, 11, 26),
+    (0b00000000101, 11, 27),
+    (0b00000000100, 11, 28),
+    (0b00000000011, 11, 29),
+    (0b00000000010, 11, 30),
+    (0b000000000011, 12, 31),
+    (0b000000000010, 12, 32),
+];
+
+/// Transform coefficients as `(last, run, level)`. A sign bit follows each code.
+/// `None` is the escape code, which is followed by a fixed-length coefficient.
+#[rustfmt::skip]
+const TCOEF: [Vlc<Option<(bool, u8, u8)>>; 103] = [
+    (0x2, 2, Some((false, 0, 1))), (0xf, 4, Some((false, 0, 2))),
+    (0x15, 6, Some((false, 0, 3))), (0x17, 7, Some((false, 0, 4))),
+    (0x1f, 8, Some((false, 0, 5))), (0x25, 9, Some((false, 0, 6))),
+    (0x24, 9, Some((false, 0, 7))), (0x21, 10, Some((false, 0, 8))),
+    (0x20, 10, Some((false, 0, 9))), (0x7, 11, Some((false, 0, 10))),
+    (0x6, 11, Some((false, 0, 11))), (0x20, 11, Some((false, 0, 12))),
+    (0x6, 3, Some((false, 1, 1))), (0x14, 6, Some((false, 1, 2))),
+    (0x1e, 8, Some((false, 1, 3))), (0xf, 10, Some((false, 1, 4))),
+    (0x21, 11, Some((false, 1, 5))), (0x50, 12, Some((false, 1, 6))),
+    (0xe, 4, Some((false, 2, 1))), (0x1d, 8, Some((false, 2, 2))),
+    (0xe, 10, Some((false, 2, 3))), (0x51, 12, Some((false, 2, 4))),
+    (0xd, 5, Some((false, 3, 1))), (0x23, 9, Some((false, 3, 2))),
+    (0xd, 10, Some((false, 3, 3))), (0xc, 5, Some((false, 4, 1))),
+    (0x22, 9, Some((false, 4, 2))), (0x52, 12, Some((false, 4, 3))),
+    (0xb, 5, Some((false, 5, 1))), (0xc, 10, Some((false, 5, 2))),
+    (0x53, 12, Some((false, 5, 3))), (0x13, 6, Some((false, 6, 1))),
+    (0xb, 10, Some((false, 6, 2))), (0x54, 12, Some((false, 6, 3))),
+    (0x12, 6, Some((false, 7, 1))), (0xa, 10, Some((false, 7, 2))),
+    (0x11, 6, Some((false, 8, 1))), (0x9, 10, Some((false, 8, 2))),
+    (0x10, 6, Some((false, 9, 1))), (0x8, 10, Some((false, 9, 2))),
+    (0x16, 7, Some((false, 10, 1))), (0x55, 12, Some((false, 10, 2))),
+    (0x15, 7, Some((false, 11, 1))), (0x14, 7, Some((false, 12, 1))),
+    (0x1c, 8, Some((false, 13, 1))), (0x1b, 8, Some((false, 14, 1))),
+    (0x21, 9, Some((false, 15, 1))), (0x20, 9, Some((false, 16, 1))),
+    (0x1f, 9, Some((false, 17, 1))), (0x1e, 9, Some((false, 18, 1))),
+    (0x1d, 9, Some((false, 19, 1))), (0x1c, 9, Some((false, 20, 1))),
+    (0x1b, 9, Some((false, 21, 1))), (0x1a, 9, Some((false, 22, 1))),
+    (0x22, 11, Some((false, 23, 1))), (0x23, 11, Some((false, 24, 1))),
+    (0x56, 12, Some((false, 25, 1))), (0x57, 12, Some((false, 26, 1))),
+    (0x7, 4, Some((true, 0, 1))), (0x19, 9, Some((true, 0, 2))),
+    (0x5, 11, Some((true, 0, 3))), (0xf, 6, Some((true, 1, 1))),
+    (0x4, 11, Some((true, 1, 2))), (0xe, 6, Some((true, 2, 1))),
+    (0xd, 6, Some((true, 3, 1))), (0xc, 6, Some((true, 4, 1))),
+    (0x13, 7, Some((true, 5, 1))), (0x12, 7, Some((true, 6, 1))),
+    (0x11, 7, Some((true, 7, 1))), (0x10, 7, Some((true, 8, 1))),
+    (0x1a, 8, Some((true, 9, 1))), (0x19, 8, Some((true, 10, 1))),
+    (0x18, 8, Some((true, 11, 1))), (0x17, 8, Some((true, 12, 1))),
+    (0x16, 8, Some((true, 13, 1))), (0x15, 8, Some((true, 14, 1))),
+    (0x14, 8, Some((true, 15, 1))), (0x13, 8, Some((true, 16, 1))),
+    (0x18, 9, Some((true, 17, 1))), (0x17, 9, Some((true, 18, 1))),
+    (0x16, 9, Some((true, 19, 1))), (0x15, 9, Some((true, 20, 1))),
+    (0x14, 9, Some((true, 21, 1))), (0x13, 9, Some((true, 22, 1))),
+    (0x12, 9, Some((true, 23, 1))), (0x11, 9, Some((true, 24, 1))),
+    (0x7, 10, Some((true, 25, 1))), (0x6, 10, Some((true, 26, 1))),
+    (0x5, 10, Some((true, 27, 1))), (0x4, 10, Some((true, 28, 1))),
+    (0x24, 11, Some((true, 29, 1))), (0x25, 11, Some((true, 30, 1))),
+    (0x26, 11, Some((true, 31, 1))), (0x27, 11, Some((true, 32, 1))),
+    (0x58, 12, Some((true, 33, 1))), (0x59, 12, Some((true, 34, 1))),
+    (0x5a, 12, Some((true, 35, 1))), (0x5b, 12, Some((true, 36, 1))),
+    (0x5c, 12, Some((true, 37, 1))), (0x5d, 12, Some((true, 38, 1))),
+    (0x5e, 12, Some((true, 39, 1))), (0x5f, 12, Some((true, 40, 1))),
+    (0x3, 7, None),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swf::VideoCodec;
+
+    /// Asserts that no code in a table is a prefix of another.
+    fn assert_prefix_free<T: Copy>(table: &[Vlc<T>]) {
+        for (i, &(code, length, _)) in table.iter().enumerate() {
+            for &(other_code, other_length, _) in &table[i + 1..] {
+                let shortest = length.min(other_length);
+                assert_ne!(
+                    code >> (length - shortest),
+                    other_code >> (other_length - shortest),
+                    "{:b}/{} conflicts with {:b}/{}",
+                    code,
+                    length,
+                    other_code,
+                    other_length
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn vlc_tables_are_prefix_free() {
+        assert_prefix_free(&MCBPC_INTRA);
+        assert_prefix_free(&MCBPC_INTER);
+        assert_prefix_free(&CBPY);
+        assert_prefix_free(&MVD);
+        assert_prefix_free(&TCOEF);
+    }
+
+    /// Packs `(value, bits)` pairs into bytes, most significant bit first.
+    fn pack_bits(fields: &[(u32, u32)]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut position = 0;
+        for &(value, bits) in fields {
+            for i in (0..bits).rev() {
+                if position % 8 == 0 {
+                    data.push(0);
+                }
+                if (value >> i) & 1 != 0 {
+                    *data.last_mut().unwrap() |= 0x80 >> (position % 8);
+                }
+                position += 1;
+            }
+        }
+        data
+    }
+
+    /// A 16x16 picture header followed by the given macroblock fields.
+    fn picture(picture_type: u32, macroblock: &[(u32, u32)]) -> Vec<u8> {
+        let mut fields = vec![
+            (1, 17), // Start code
+            (0, 5),  // Version
+            (0, 8),  // Temporal reference
+            (0, 3),  // Custom 8-bit size
+            (16, 8), // Width
+            (16, 8), // Height
+            (picture_type, 2),
+            (0, 1), // Deblocking
+            (4, 5), // Quantizer
+            (0, 1), // No extra information
+        ];
+        fields.extend_from_slice(macroblock);
+        pack_bits(&fields)
+    }
+
+    fn encoded(data: &[u8]) -> EncodedFrame<'_> {
+        EncodedFrame {
+            codec: VideoCodec::H263,
+            data,
+            frame_id: 0,
+        }
+    }
+
+    #[test]
+    fn decode_flat_pictures() {
+        let mut decoder = H263Decoder::new();
+
+        // An intra macroblock with only DC coefficients: mid-gray luma, neutral chroma.
+        let mut macroblock = vec![(0b1, 1), (0b0011, 4)];
+        macroblock.extend_from_slice(&[(128, 8); 6]);
+        let intra = picture(0, &macroblock);
+        assert_eq!(
+            decoder.preload_frame(encoded(&intra)).unwrap(),
+            FrameDependency::None
+        );
+        let frame = decoder.decode_frame(encoded(&intra)).unwrap();
+        assert_eq!((frame.width, frame.height), (16, 16));
+        assert!(frame
+            .rgba
+            .chunks_exact(4)
+            .all(|pixel| pixel == [130, 130, 130, 255]));
+
+        // An inter picture with one uncoded macroblock repeats the reference.
+        let inter = picture(1, &[(1, 1)]);
+        assert_eq!(
+            decoder.preload_frame(encoded(&inter)).unwrap(),
+            FrameDependency::Past
+        );
+        let frame = decoder.decode_frame(encoded(&inter)).unwrap();
+        assert!(frame
+            .rgba
+            .chunks_exact(4)
+            .all(|pixel| pixel == [130, 130, 130, 255]));
+
+        // An inter macroblock with a coded DC coefficient brightens the luma blocks.
+        // COD, MCBPC (inter, no chroma), CBPY (all luma coded, inverted), MVD (0, 0),
+        // then a last coefficient of level 1 in each luma block.
+        let mut macroblock = vec![(0, 1), (0b1, 1), (0b0011, 4), (0b1, 1), (0b1, 1)];
+        macroblock.extend_from_slice(&[(0b0111, 4), (0, 1)].repeat(4));
+        let inter = picture(1, &macroblock);
+        let frame = decoder.decode_frame(encoded(&inter)).unwrap();
+        let brighter = frame.rgba[0];
+        assert!(brighter > 130);
+        assert!(frame.rgba.chunks_exact(4).all(|pixel| pixel[0] == brighter));
+    }
+}
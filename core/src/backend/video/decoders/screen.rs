@@ -0,0 +1,221 @@
+//! Screen Video (version 1) decoder.
+//!
+//! Each frame is split into a grid of blocks, starting at the bottom-left of the image.
+//! Each block is either skipped (unchanged from the previous frame) or a zlib-compressed
+//! run of BGR pixels, stored bottom-up.
+
+use crate::backend::video::decoders::VideoDecoder;
+use crate::backend::video::{DecodedFrame, EncodedFrame, FrameDependency};
+use flate2::read::ZlibDecoder;
+use std::io::Read;
+
+type Error = Box<dyn std::error::Error>;
+
+/// Screen Video decoder.
+pub struct ScreenVideoDecoder {
+    width: usize,
+    height: usize,
+
+    /// The RGBA pixels of the last decoded frame, top-down.
+    last_frame: Option<Vec<u8>>,
+}
+
+/// The header of a Screen Video frame.
+struct FrameHeader {
+    block_width: usize,
+    image_width: usize,
+    block_height: usize,
+    image_height: usize,
+}
+
+impl FrameHeader {
+    fn read(data: &[u8]) -> Result<Self, Error> {
+        if data.len() < 4 {
+            return Err("Screen Video frame is too short".into());
+        }
+        let width_field = u16::from_be_bytes([data[0], data[1]]);
+        let height_field = u16::from_be_bytes([data[2], data[3]]);
+        Ok(Self {
+            block_width: (usize::from(width_field >> 12) + 1) * 16,
+            image_width: usize::from(width_field & 0xfff),
+            block_height: (usize::from(height_field >> 12) + 1) * 16,
+            image_height: usize::from(height_field & 0xfff),
+        })
+    }
+
+    fn blocks_wide(&self) -> usize {
+        (self.image_width + self.block_width - 1) / self.block_width
+    }
+
+    fn blocks_high(&self) -> usize {
+        (self.image_height + self.block_height - 1) / self.block_height
+    }
+}
+
+impl ScreenVideoDecoder {
+    pub fn new() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            last_frame: None,
+        }
+    }
+}
+
+impl Default for ScreenVideoDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VideoDecoder for ScreenVideoDecoder {
+    fn preload_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<FrameDependency, Error> {
+        // A keyframe is a frame that doesn't skip any blocks.
+        let data = encoded_frame.data;
+        let header = FrameHeader::read(data)?;
+        let mut pos = 4;
+        for _ in 0..header.blocks_wide() * header.blocks_high() {
+            let size = data
+                .get(pos..pos + 2)
+                .map(|size| usize::from(u16::from_be_bytes([size[0], size[1]])))
+                .ok_or("Screen Video frame is truncated")?;
+            if size == 0 {
+                return Ok(FrameDependency::Past);
+            }
+            pos += 2 + size;
+        }
+        Ok(FrameDependency::None)
+    }
+
+    fn decode_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<DecodedFrame, Error> {
+        let data = encoded_frame.data;
+        let header = FrameHeader::read(data)?;
+        let (width, height) = (header.image_width, header.image_height);
+
+        // Start from the previous frame; skipped blocks keep their old pixels.
+        let mut rgba = match self.last_frame.take() {
+            Some(rgba) if self.width == width && self.height == height => rgba,
+            _ => vec![0; width * height * 4],
+        };
+
+        let mut pos = 4;
+        let mut block_data = Vec::with_capacity(header.block_width * header.block_height * 3);
+        for block_y in 0..header.blocks_high() {
+            for block_x in 0..header.blocks_wide() {
+                let size = data
+                    .get(pos..pos + 2)
+                    .map(|size| usize::from(u16::from_be_bytes([size[0], size[1]])))
+                    .ok_or("Screen Video frame is truncated")?;
+                pos += 2;
+                if size == 0 {
+                    continue;
+                }
+
+                let compressed = data
+                    .get(pos..pos + size)
+                    .ok_or("Screen Video frame is truncated")?;
+                pos += size;
+
+                // Blocks on the right and top edges may be cut off by the image bounds.
+                let x_start = block_x * header.block_width;
+                let y_start = block_y * header.block_height;
+                let block_width = header.block_width.min(width - x_start);
+                let block_height = header.block_height.min(height - y_start);
+
+                block_data.clear();
+                ZlibDecoder::new(compressed).read_to_end(&mut block_data)?;
+                if block_data.len() < block_width * block_height * 3 {
+                    return Err("Screen Video block is too short".into());
+                }
+
+                // Rows are stored bottom-up.
+                for (row, bgr_row) in block_data
+                    .chunks_exact(block_width * 3)
+                    .take(block_height)
+                    .enumerate()
+                {
+                    let y = height - 1 - (y_start + row);
+                    let out_row = &mut rgba[(y * width + x_start) * 4..][..block_width * 4];
+                    for (out, bgr) in out_row.chunks_exact_mut(4).zip(bgr_row.chunks_exact(3)) {
+                        out.copy_from_slice(&[bgr[2], bgr[1], bgr[0], 255]);
+                    }
+                }
+            }
+        }
+
+        self.width = width;
+        self.height = height;
+        self.last_frame = Some(rgba.clone());
+
+        Ok(DecodedFrame {
+            width: width as u16,
+            height: height as u16,
+            rgba,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use swf::VideoCodec;
+
+    /// Builds a frame of a 16x32 image made of two 16x16 blocks.
+    fn frame(bottom: Option<[u8; 3]>, top: Option<[u8; 3]>) -> Vec<u8> {
+        let mut data = vec![0x00, 16, 0x00, 32];
+        for block in [bottom, top].iter() {
+            if let Some(bgr) = block {
+                let pixels: Vec<u8> = bgr.iter().cycle().take(16 * 16 * 3).cloned().collect();
+                let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(&pixels).unwrap();
+                let compressed = encoder.finish().unwrap();
+                data.extend_from_slice(&(compressed.len() as u16).to_be_bytes());
+                data.extend_from_slice(&compressed);
+            } else {
+                data.extend_from_slice(&[0, 0]);
+            }
+        }
+        data
+    }
+
+    fn encoded(data: &[u8]) -> EncodedFrame<'_> {
+        EncodedFrame {
+            codec: VideoCodec::ScreenVideo,
+            data,
+            frame_id: 0,
+        }
+    }
+
+    fn pixel(frame: &DecodedFrame, x: usize, y: usize) -> &[u8] {
+        let i = (y * usize::from(frame.width) + x) * 4;
+        &frame.rgba[i..i + 4]
+    }
+
+    #[test]
+    fn decode_keyframe_and_interframe() {
+        let mut decoder = ScreenVideoDecoder::new();
+
+        let keyframe = frame(Some([255, 0, 0]), Some([0, 0, 255]));
+        assert_eq!(
+            decoder.preload_frame(encoded(&keyframe)).unwrap(),
+            FrameDependency::None
+        );
+        let decoded = decoder.decode_frame(encoded(&keyframe)).unwrap();
+        assert_eq!((decoded.width, decoded.height), (16, 32));
+        // The first block is the bottom of the image.
+        assert_eq!(pixel(&decoded, 0, 31), &[0, 0, 255, 255]);
+        assert_eq!(pixel(&decoded, 15, 0), &[255, 0, 0, 255]);
+
+        let interframe = frame(Some([0, 255, 0]), None);
+        assert_eq!(
+            decoder.preload_frame(encoded(&interframe)).unwrap(),
+            FrameDependency::Past
+        );
+        let decoded = decoder.decode_frame(encoded(&interframe)).unwrap();
+        assert_eq!(pixel(&decoded, 0, 31), &[0, 255, 0, 255]);
+        assert_eq!(pixel(&decoded, 15, 0), &[255, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,103 @@
+//! Pure software video decoding backend.
+
+use crate::backend::render::{BitmapInfo, RenderBackend};
+use crate::backend::video::decoders::{self, VideoDecoder};
+use crate::backend::video::{EncodedFrame, FrameDependency, VideoBackend, VideoStreamHandle};
+use generational_arena::Arena;
+use swf::{VideoCodec, VideoDeblocking};
+
+type Error = Box<dyn std::error::Error>;
+
+/// Software video backend that proxies to CPU-only codec implementations that
+/// ship with Ruffle.
+pub struct SoftwareVideoBackend {
+    streams: Arena<VideoStream>,
+}
+
+impl Default for SoftwareVideoBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SoftwareVideoBackend {
+    pub fn new() -> Self {
+        Self {
+            streams: Arena::new(),
+        }
+    }
+}
+
+impl VideoBackend for SoftwareVideoBackend {
+    fn register_video_stream(
+        &mut self,
+        _num_frames: u32,
+        _size: (u16, u16),
+        codec: VideoCodec,
+        _filter: VideoDeblocking,
+    ) -> Result<VideoStreamHandle, Error> {
+        let decoder = decoders::make_decoder(codec)?;
+        let stream = VideoStream {
+            bitmap: None,
+            decoder,
+        };
+        Ok(self.streams.insert(stream))
+    }
+
+    fn preload_video_stream_frame(
+        &mut self,
+        stream: VideoStreamHandle,
+        encoded_frame: EncodedFrame<'_>,
+    ) -> Result<FrameDependency, Error> {
+        let stream = self
+            .streams
+            .get_mut(stream)
+            .ok_or("Unregistered video stream")?;
+
+        stream.decoder.preload_frame(encoded_frame)
+    }
+
+    fn decode_video_stream_frame(
+        &mut self,
+        stream: VideoStreamHandle,
+        encoded_frame: EncodedFrame<'_>,
+        renderer: &mut dyn RenderBackend,
+    ) -> Result<BitmapInfo, Error> {
+        let stream = self
+            .streams
+            .get_mut(stream)
+            .ok_or("Unregistered video stream")?;
+
+        let frame = stream.decoder.decode_frame(encoded_frame)?;
+        let width = u32::from(frame.width);
+        let height = u32::from(frame.height);
+
+        // Reuse the stream's texture unless the frame size changed.
+        let handle = match stream.bitmap {
+            Some(bitmap) if bitmap.width == frame.width && bitmap.height == frame.height => {
+                renderer.update_texture(bitmap.handle, width, height, frame.rgba)?
+            }
+            _ => renderer.register_bitmap_raw(width, height, frame.rgba)?,
+        };
+
+        let bitmap = BitmapInfo {
+            handle,
+            width: frame.width,
+            height: frame.height,
+        };
+        stream.bitmap = Some(bitmap);
+        Ok(bitmap)
+    }
+
+    fn unregister_video_stream(&mut self, stream: VideoStreamHandle) {
+        self.streams.remove(stream);
+    }
+}
+
+/// A single preloaded video stream.
+struct VideoStream {
+    /// The bitmap that the most recently decoded frame was uploaded to.
+    bitmap: Option<BitmapInfo>,
+
+    decoder: Box<dyn VideoDecoder>,
+}
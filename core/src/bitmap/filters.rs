@@ -0,0 +1,894 @@
+//! Software implementation of the Flash display object filters.
+//!
+//! Renderers that can't run the filters on the GPU render the filtered
+//! display object to an offscreen surface, read the pixels back, and run
+//! them through `apply_filters`.
+//!
+//! All pixel data handled here is RGBA with premultiplied alpha, matching the
+//! format of the bitmaps produced by `backend::render`.
+
+use swf::{
+    BevelFilter, BlurFilter, Color, ColorMatrixFilter, ConvolutionFilter, DropShadowFilter, Filter,
+    GlowFilter, GradientBevelFilter, GradientGlowFilter, GradientRecord,
+};
+
+/// A premultiplied RGBA pixel with components in the range 0.0 to 1.0.
+pub type Pixel = [f32; 4];
+
+/// An image that filters are applied to.
+struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Pixel>,
+}
+
+impl Image {
+    fn from_rgba(width: u32, height: u32, rgba: &[u8]) -> Self {
+        let pixels = rgba
+            .chunks_exact(4)
+            .map(|p| {
+                [
+                    f32::from(p[0]) / 255.0,
+                    f32::from(p[1]) / 255.0,
+                    f32::from(p[2]) / 255.0,
+                    f32::from(p[3]) / 255.0,
+                ]
+            })
+            .collect();
+        Self {
+            width: width as usize,
+            height: height as usize,
+            pixels,
+        }
+    }
+
+    fn write_rgba(&self, rgba: &mut [u8]) {
+        for (out, pixel) in rgba.chunks_exact_mut(4).zip(&self.pixels) {
+            let alpha = pixel[3].max(0.0).min(1.0);
+            out[3] = (alpha * 255.0).round() as u8;
+            for (out, component) in out[..3].iter_mut().zip(&pixel[..3]) {
+                // Color components may never exceed the alpha in premultiplied space.
+                *out = (component.max(0.0).min(alpha) * 255.0).round() as u8;
+            }
+        }
+    }
+
+    fn alpha_plane(&self) -> Vec<f32> {
+        self.pixels.iter().map(|p| p[3]).collect()
+    }
+
+    /// Samples a plane at the given position, returning `outside` for positions
+    /// outside of the image.
+    fn sample(&self, plane: &[f32], x: isize, y: isize, outside: f32) -> f32 {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            plane[y as usize * self.width + x as usize]
+        } else {
+            outside
+        }
+    }
+}
+
+/// Applies each filter in `filters` in order to the given premultiplied RGBA
+/// pixel data.
+pub fn apply_filters(width: u32, height: u32, rgba: &mut [u8], filters: &[Filter]) {
+    if filters.is_empty() || width == 0 || height == 0 {
+        return;
+    }
+
+    let mut image = Image::from_rgba(width, height, rgba);
+    for filter in filters {
+        apply_filter_to_image(&mut image, filter);
+    }
+    image.write_rgba(rgba);
+}
+
+/// Applies a single filter to the given premultiplied RGBA pixel data.
+pub fn apply_filter(width: u32, height: u32, rgba: &mut [u8], filter: &Filter) {
+    apply_filters(width, height, rgba, std::slice::from_ref(filter));
+}
+
+/// Calculates the area covered by applying `filter` to the rectangle `(x, y, width, height)`,
+/// in pixels.
+pub fn filter_rect(filter: &Filter, rect: (i32, i32, i32, i32)) -> (i32, i32, i32, i32) {
+    let (blur_x, blur_y, passes, offsets) = match filter {
+        Filter::BlurFilter(filter) => (filter.blur_x, filter.blur_y, filter.num_passes, vec![]),
+        Filter::GlowFilter(filter) if filter.is_inner => return rect,
+        Filter::GlowFilter(filter) => (filter.blur_x, filter.blur_y, filter.num_passes, vec![]),
+        Filter::DropShadowFilter(filter) if filter.is_inner => return rect,
+        Filter::DropShadowFilter(filter) => (
+            filter.blur_x,
+            filter.blur_y,
+            filter.num_passes,
+            vec![offset(filter.angle, filter.distance)],
+        ),
+        Filter::BevelFilter(filter) if filter.is_inner => return rect,
+        Filter::BevelFilter(filter) => {
+            let (dx, dy) = offset(filter.angle, filter.distance);
+            (
+                filter.blur_x,
+                filter.blur_y,
+                filter.num_passes,
+                vec![(dx, dy), (-dx, -dy)],
+            )
+        }
+        Filter::GradientGlowFilter(filter) if filter.is_inner => return rect,
+        Filter::GradientGlowFilter(filter) => (
+            filter.blur_x,
+            filter.blur_y,
+            filter.num_passes,
+            vec![offset(filter.angle, filter.distance)],
+        ),
+        Filter::GradientBevelFilter(filter) if filter.is_inner => return rect,
+        Filter::GradientBevelFilter(filter) => {
+            let (dx, dy) = offset(filter.angle, filter.distance);
+            (
+                filter.blur_x,
+                filter.blur_y,
+                filter.num_passes,
+                vec![(dx, dy), (-dx, -dy)],
+            )
+        }
+        // These only change the existing pixels.
+        Filter::ConvolutionFilter(_) | Filter::ColorMatrixFilter(_) => return rect,
+    };
+
+    // Each pass of the box blur spreads by its radius, as in `blur_plane`.
+    let spread_x = (blur_x.max(0.0) / 2.0) as i32 * i32::from(passes);
+    let spread_y = (blur_y.max(0.0) / 2.0) as i32 * i32::from(passes);
+
+    let (x, y, width, height) = rect;
+    let (mut x_min, mut y_min, mut x_max, mut y_max) = (x, y, x + width, y + height);
+    for (dx, dy) in offsets {
+        x_min = x_min.min(x + dx as i32);
+        y_min = y_min.min(y + dy as i32);
+        x_max = x_max.max(x + width + dx as i32);
+        y_max = y_max.max(y + height + dy as i32);
+    }
+    x_min -= spread_x;
+    y_min -= spread_y;
+    x_max += spread_x;
+    y_max += spread_y;
+
+    (x_min, y_min, x_max - x_min, y_max - y_min)
+}
+
+fn apply_filter_to_image(image: &mut Image, filter: &Filter) {
+    match filter {
+        Filter::BlurFilter(filter) => blur(image, filter),
+        Filter::GlowFilter(filter) => glow(image, filter),
+        Filter::DropShadowFilter(filter) => drop_shadow(image, filter),
+        Filter::BevelFilter(filter) => bevel(image, filter),
+        Filter::GradientGlowFilter(filter) => gradient_glow(image, filter),
+        Filter::GradientBevelFilter(filter) => gradient_bevel(image, filter),
+        Filter::ConvolutionFilter(filter) => convolution(image, filter),
+        Filter::ColorMatrixFilter(filter) => color_matrix(image, filter),
+    }
+}
+
+/// Blurs a single channel in place using `passes` iterations of a box blur.
+/// Flash specifies the blur amount as the width of the box in pixels.
+fn blur_plane(
+    plane: &mut [f32],
+    width: usize,
+    height: usize,
+    blur_x: f64,
+    blur_y: f64,
+    passes: u8,
+) {
+    let radius_x = (blur_x.max(0.0) / 2.0) as usize;
+    let radius_y = (blur_y.max(0.0) / 2.0) as usize;
+    let mut line = vec![0.0; width.max(height)];
+    for _ in 0..passes {
+        if radius_x > 0 {
+            for y in 0..height {
+                blur_line(plane, y * width, 1, width, radius_x, &mut line);
+            }
+        }
+        if radius_y > 0 {
+            for x in 0..width {
+                blur_line(plane, x, width, height, radius_y, &mut line);
+            }
+        }
+    }
+}
+
+/// Box blurs a line of `len` samples starting at `start` with the given stride.
+fn blur_line(
+    plane: &mut [f32],
+    start: usize,
+    stride: usize,
+    len: usize,
+    radius: usize,
+    line: &mut [f32],
+) {
+    for (i, value) in line.iter_mut().enumerate().take(len) {
+        *value = plane[start + i * stride];
+    }
+
+    let window = (radius * 2 + 1) as f32;
+    let mut sum: f32 = line[..radius.min(len)].iter().sum();
+    for i in 0..len {
+        if i + radius < len {
+            sum += line[i + radius];
+        }
+        if i > radius {
+            sum -= line[i - radius - 1];
+        }
+        plane[start + i * stride] = (sum / window).max(0.0);
+    }
+}
+
+/// Returns the blurred alpha channel of the image, multiplied by `strength`.
+/// If `invert` is set, the inverted alpha is used (for inner glows and shadows).
+fn blurred_alpha(
+    image: &Image,
+    blur_x: f64,
+    blur_y: f64,
+    passes: u8,
+    strength: f32,
+    invert: bool,
+) -> Vec<f32> {
+    let mut plane = image.alpha_plane();
+    if invert {
+        plane.iter_mut().for_each(|a| *a = 1.0 - *a);
+    }
+    blur_plane(
+        &mut plane,
+        image.width,
+        image.height,
+        blur_x,
+        blur_y,
+        passes,
+    );
+    plane.iter_mut().for_each(|a| *a = (*a * strength).min(1.0));
+    plane
+}
+
+/// The offset in pixels of a filter with the given angle (in radians) and distance.
+fn offset(angle: f64, distance: f64) -> (isize, isize) {
+    (
+        (angle.cos() * distance).round() as isize,
+        (angle.sin() * distance).round() as isize,
+    )
+}
+
+fn color_to_pixel(color: &Color) -> Pixel {
+    let a = f32::from(color.a) / 255.0;
+    [
+        f32::from(color.r) / 255.0 * a,
+        f32::from(color.g) / 255.0 * a,
+        f32::from(color.b) / 255.0 * a,
+        a,
+    ]
+}
+
+fn scale(pixel: Pixel, amount: f32) -> Pixel {
+    [
+        pixel[0] * amount,
+        pixel[1] * amount,
+        pixel[2] * amount,
+        pixel[3] * amount,
+    ]
+}
+
+/// Porter-Duff "over": draws `top` over `bottom`.
+fn over(top: Pixel, bottom: Pixel) -> Pixel {
+    let inv = 1.0 - top[3];
+    [
+        top[0] + bottom[0] * inv,
+        top[1] + bottom[1] * inv,
+        top[2] + bottom[2] * inv,
+        top[3] + bottom[3] * inv,
+    ]
+}
+
+/// How a generated effect (glow, shadow or bevel) is combined with the source image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum EffectType {
+    /// The effect is drawn inside the source and on top of it.
+    Inner,
+    /// The effect is drawn outside the source and behind it.
+    Outer,
+    /// The effect is drawn on top of the source without masking.
+    Full,
+}
+
+/// Composites an effect layer with the source image.
+/// `effect` is called with the pixel index and returns the premultiplied effect color.
+fn composite_effect(
+    image: &mut Image,
+    effect_type: EffectType,
+    knockout: bool,
+    effect: impl Fn(usize) -> Pixel,
+) {
+    for (i, src) in image.pixels.iter_mut().enumerate() {
+        let color = effect(i);
+        let src_alpha = src[3];
+        *src = match (effect_type, knockout) {
+            (EffectType::Inner, false) => over(scale(color, src_alpha), *src),
+            (EffectType::Inner, true) => scale(color, src_alpha),
+            (EffectType::Outer, false) => over(*src, color),
+            (EffectType::Outer, true) => scale(color, 1.0 - src_alpha),
+            (EffectType::Full, false) => over(color, *src),
+            (EffectType::Full, true) => color,
+        };
+    }
+}
+
+fn blur(image: &mut Image, filter: &BlurFilter) {
+    let mut planes: Vec<Vec<f32>> = (0..4)
+        .map(|c| image.pixels.iter().map(|p| p[c]).collect())
+        .collect();
+    for plane in &mut planes {
+        blur_plane(
+            plane,
+            image.width,
+            image.height,
+            filter.blur_x,
+            filter.blur_y,
+            filter.num_passes,
+        );
+    }
+    for (i, pixel) in image.pixels.iter_mut().enumerate() {
+        *pixel = [planes[0][i], planes[1][i], planes[2][i], planes[3][i]];
+    }
+}
+
+fn glow(image: &mut Image, filter: &GlowFilter) {
+    let shadow = DropShadowFilter {
+        color: filter.color.clone(),
+        blur_x: filter.blur_x,
+        blur_y: filter.blur_y,
+        angle: 0.0,
+        distance: 0.0,
+        strength: filter.strength,
+        is_inner: filter.is_inner,
+        is_knockout: filter.is_knockout,
+        num_passes: filter.num_passes,
+    };
+    drop_shadow(image, &shadow);
+}
+
+fn drop_shadow(image: &mut Image, filter: &DropShadowFilter) {
+    let alpha = blurred_alpha(
+        image,
+        filter.blur_x,
+        filter.blur_y,
+        filter.num_passes,
+        filter.strength,
+        filter.is_inner,
+    );
+    let (dx, dy) = offset(filter.angle, filter.distance);
+    let color = color_to_pixel(&filter.color);
+    let width = image.width;
+    let effect_type = if filter.is_inner {
+        EffectType::Inner
+    } else {
+        EffectType::Outer
+    };
+    // Outside of the image counts as fully transparent, so an inner shadow is at full strength.
+    let outside = if filter.is_inner {
+        filter.strength.min(1.0)
+    } else {
+        0.0
+    };
+    // The shadow at a pixel comes from the source pixel `distance` away in the opposite direction.
+    let samples: Vec<f32> = (0..image.pixels.len())
+        .map(|i| {
+            let x = (i % width) as isize;
+            let y = (i / width) as isize;
+            image.sample(&alpha, x - dx, y - dy, outside)
+        })
+        .collect();
+    composite_effect(image, effect_type, filter.is_knockout, |i| {
+        scale(color, samples[i])
+    });
+}
+
+fn bevel_type(is_inner: bool, is_on_top: bool) -> EffectType {
+    if is_on_top {
+        EffectType::Full
+    } else if is_inner {
+        EffectType::Inner
+    } else {
+        EffectType::Outer
+    }
+}
+
+/// Calculates the bevel intensity for each pixel, in the range -1.0 (full shadow)
+/// to 1.0 (full highlight).
+#[allow(clippy::too_many_arguments)]
+fn bevel_intensity(
+    image: &Image,
+    blur_x: f64,
+    blur_y: f64,
+    passes: u8,
+    strength: f32,
+    angle: f64,
+    distance: f64,
+) -> Vec<f32> {
+    let mut alpha = image.alpha_plane();
+    blur_plane(
+        &mut alpha,
+        image.width,
+        image.height,
+        blur_x,
+        blur_y,
+        passes,
+    );
+    let (dx, dy) = offset(angle, distance);
+    let width = image.width;
+    (0..image.pixels.len())
+        .map(|i| {
+            let x = (i % width) as isize;
+            let y = (i / width) as isize;
+            // The light comes from the direction opposite to `angle`, so edges facing
+            // away from the angle are highlighted.
+            let lit = image.sample(&alpha, x + dx, y + dy, 0.0);
+            let shaded = image.sample(&alpha, x - dx, y - dy, 0.0);
+            ((lit - shaded) * strength).max(-1.0).min(1.0)
+        })
+        .collect()
+}
+
+fn bevel(image: &mut Image, filter: &BevelFilter) {
+    let intensity = bevel_intensity(
+        image,
+        filter.blur_x,
+        filter.blur_y,
+        filter.num_passes,
+        filter.strength,
+        filter.angle,
+        filter.distance,
+    );
+    let highlight = color_to_pixel(&filter.highlight_color);
+    let shadow = color_to_pixel(&filter.shadow_color);
+    composite_effect(
+        image,
+        bevel_type(filter.is_inner, filter.is_on_top),
+        filter.is_knockout,
+        |i| {
+            let amount = intensity[i];
+            if amount >= 0.0 {
+                scale(highlight, amount)
+            } else {
+                scale(shadow, -amount)
+            }
+        },
+    );
+}
+
+/// Builds a 256-entry lookup table of premultiplied colors from gradient records.
+fn gradient_lookup_table(records: &[GradientRecord]) -> Vec<Pixel> {
+    let mut table = vec![[0.0; 4]; 256];
+    if records.is_empty() {
+        return table;
+    }
+
+    for (i, entry) in table.iter_mut().enumerate() {
+        let ratio = i as u8;
+        let next = records
+            .iter()
+            .position(|r| r.ratio >= ratio)
+            .unwrap_or(records.len());
+        let color = if next == 0 {
+            color_to_pixel(&records[0].color)
+        } else if next == records.len() {
+            color_to_pixel(&records[records.len() - 1].color)
+        } else {
+            let a = &records[next - 1];
+            let b = &records[next];
+            let span = f32::from(b.ratio.saturating_sub(a.ratio));
+            let t = if span > 0.0 {
+                f32::from(ratio.saturating_sub(a.ratio)) / span
+            } else {
+                0.0
+            };
+            let a = color_to_pixel(&a.color);
+            let b = color_to_pixel(&b.color);
+            [
+                a[0] + (b[0] - a[0]) * t,
+                a[1] + (b[1] - a[1]) * t,
+                a[2] + (b[2] - a[2]) * t,
+                a[3] + (b[3] - a[3]) * t,
+            ]
+        };
+        *entry = color;
+    }
+    table
+}
+
+fn gradient_glow(image: &mut Image, filter: &GradientGlowFilter) {
+    let effect_type = bevel_type(filter.is_inner, filter.is_on_top);
+    let alpha = blurred_alpha(
+        image,
+        filter.blur_x,
+        filter.blur_y,
+        filter.num_passes,
+        filter.strength,
+        effect_type == EffectType::Inner,
+    );
+    let (dx, dy) = offset(filter.angle, filter.distance);
+    let table = gradient_lookup_table(&filter.colors);
+    let width = image.width;
+    let outside = if effect_type == EffectType::Inner {
+        filter.strength.min(1.0)
+    } else {
+        0.0
+    };
+    let samples: Vec<f32> = (0..image.pixels.len())
+        .map(|i| {
+            let x = (i % width) as isize;
+            let y = (i / width) as isize;
+            image.sample(&alpha, x - dx, y - dy, outside)
+        })
+        .collect();
+    composite_effect(image, effect_type, filter.is_knockout, |i| {
+        table[(samples[i] * 255.0) as usize]
+    });
+}
+
+fn gradient_bevel(image: &mut Image, filter: &GradientBevelFilter) {
+    let intensity = bevel_intensity(
+        image,
+        filter.blur_x,
+        filter.blur_y,
+        filter.num_passes,
+        filter.strength,
+        filter.angle,
+        filter.distance,
+    );
+    let table = gradient_lookup_table(&filter.colors);
+    composite_effect(
+        image,
+        bevel_type(filter.is_inner, filter.is_on_top),
+        filter.is_knockout,
+        |i| {
+            // The middle of the gradient is the neutral, unlit color.
+            let index = ((intensity[i] + 1.0) * 127.5) as usize;
+            table[index.min(255)]
+        },
+    );
+}
+
+fn unmultiply(pixel: Pixel) -> Pixel {
+    let a = pixel[3];
+    if a > 0.0 {
+        [pixel[0] / a, pixel[1] / a, pixel[2] / a, a]
+    } else {
+        [0.0; 4]
+    }
+}
+
+fn premultiply(pixel: Pixel) -> Pixel {
+    let a = pixel[3].max(0.0).min(1.0);
+    [
+        pixel[0].max(0.0).min(1.0) * a,
+        pixel[1].max(0.0).min(1.0) * a,
+        pixel[2].max(0.0).min(1.0) * a,
+        a,
+    ]
+}
+
+fn convolution(image: &mut Image, filter: &ConvolutionFilter) {
+    let cols = filter.num_matrix_cols as isize;
+    let rows = filter.num_matrix_rows as isize;
+    if cols == 0 || rows == 0 || filter.matrix.len() < (cols * rows) as usize {
+        return;
+    }
+
+    let divisor = if filter.divisor != 0.0 {
+        filter.divisor as f32
+    } else {
+        1.0
+    };
+    let bias = filter.bias as f32 / 255.0;
+    let default_color = {
+        let c = &filter.default_color;
+        [
+            f32::from(c.r) / 255.0,
+            f32::from(c.g) / 255.0,
+            f32::from(c.b) / 255.0,
+            f32::from(c.a) / 255.0,
+        ]
+    };
+
+    let source: Vec<Pixel> = image.pixels.iter().copied().map(unmultiply).collect();
+    let (width, height) = (image.width as isize, image.height as isize);
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = [0.0f32; 4];
+            for row in 0..rows {
+                for col in 0..cols {
+                    let weight = filter.matrix[(row * cols + col) as usize] as f32;
+                    let sx = x + col - cols / 2;
+                    let sy = y + row - rows / 2;
+                    let sample = if sx >= 0 && sy >= 0 && sx < width && sy < height {
+                        source[(sy * width + sx) as usize]
+                    } else if filter.is_clamped {
+                        let sx = sx.max(0).min(width - 1);
+                        let sy = sy.max(0).min(height - 1);
+                        source[(sy * width + sx) as usize]
+                    } else {
+                        default_color
+                    };
+                    for (sum, component) in sum.iter_mut().zip(&sample) {
+                        *sum += component * weight;
+                    }
+                }
+            }
+
+            let index = (y * width + x) as usize;
+            let mut result = sum;
+            result.iter_mut().for_each(|c| *c = *c / divisor + bias);
+            if filter.is_preserve_alpha {
+                result[3] = source[index][3];
+            }
+            image.pixels[index] = premultiply(result);
+        }
+    }
+}
+
+fn color_matrix(image: &mut Image, filter: &ColorMatrixFilter) {
+    let m = &filter.matrix;
+    for pixel in &mut image.pixels {
+        let [r, g, b, a] = unmultiply(*pixel);
+        let mut result = [0.0; 4];
+        for (c, out) in result.iter_mut().enumerate() {
+            let row = &m[c * 5..c * 5 + 5];
+            // The offset column is in the 0-255 range.
+            *out = (row[0] * f64::from(r)
+                + row[1] * f64::from(g)
+                + row[2] * f64::from(b)
+                + row[3] * f64::from(a)
+                + row[4] / 255.0) as f32;
+        }
+        *pixel = premultiply(result);
+    }
+}
+
+/// A glow, drop shadow or bevel, described in the form used by the GPU filter shaders of
+/// the render backends.
+///
+/// The effect is made from the blurred alpha of the object, sampled `offset` pixels away.
+/// Bevels instead use the difference between the alpha on the lit side and the shaded side.
+/// The resulting strength, from 0.0 to 1.0, is then looked up in `colors`.
+#[derive(Clone, Debug)]
+pub struct EffectParams {
+    pub blur_x: f64,
+    pub blur_y: f64,
+    pub passes: u8,
+
+    /// The distance between the object and its shadow, in pixels.
+    pub offset: (isize, isize),
+    pub strength: f32,
+
+    pub is_bevel: bool,
+
+    /// Whether the effect is made from the inverted alpha of the object, as inner
+    /// glows and shadows are.
+    pub invert: bool,
+
+    /// Whether the effect is drawn inside the object, on top of it.
+    pub is_inner: bool,
+
+    /// Whether the effect is drawn on top of the object without being masked by it.
+    pub is_on_top: bool,
+    pub is_knockout: bool,
+
+    /// The premultiplied colors of the effect, indexed by its strength.
+    pub colors: Vec<Pixel>,
+}
+
+impl EffectParams {
+    /// Returns the parameters of an effect filter, or `None` for other filters.
+    pub fn from_filter(filter: &Filter) -> Option<Self> {
+        let params = match filter {
+            Filter::DropShadowFilter(filter) => Self {
+                blur_x: filter.blur_x,
+                blur_y: filter.blur_y,
+                passes: filter.num_passes,
+                offset: offset(filter.angle, filter.distance),
+                strength: filter.strength,
+                is_bevel: false,
+                invert: filter.is_inner,
+                is_inner: filter.is_inner,
+                is_on_top: false,
+                is_knockout: filter.is_knockout,
+                colors: vec![[0.0; 4], color_to_pixel(&filter.color)],
+            },
+            Filter::GlowFilter(filter) => Self {
+                blur_x: filter.blur_x,
+                blur_y: filter.blur_y,
+                passes: filter.num_passes,
+                offset: (0, 0),
+                strength: filter.strength,
+                is_bevel: false,
+                invert: filter.is_inner,
+                is_inner: filter.is_inner,
+                is_on_top: false,
+                is_knockout: filter.is_knockout,
+                colors: vec![[0.0; 4], color_to_pixel(&filter.color)],
+            },
+            Filter::BevelFilter(filter) => Self {
+                blur_x: filter.blur_x,
+                blur_y: filter.blur_y,
+                passes: filter.num_passes,
+                offset: offset(filter.angle, filter.distance),
+                strength: filter.strength,
+                is_bevel: true,
+                invert: false,
+                is_inner: bevel_type(filter.is_inner, filter.is_on_top) == EffectType::Inner,
+                is_on_top: filter.is_on_top,
+                is_knockout: filter.is_knockout,
+                // The middle of the gradient is the neutral, unlit color.
+                colors: vec![
+                    color_to_pixel(&filter.shadow_color),
+                    [0.0; 4],
+                    color_to_pixel(&filter.highlight_color),
+                ],
+            },
+            Filter::GradientGlowFilter(filter) => {
+                let is_inner = bevel_type(filter.is_inner, filter.is_on_top) == EffectType::Inner;
+                Self {
+                    blur_x: filter.blur_x,
+                    blur_y: filter.blur_y,
+                    passes: filter.num_passes,
+                    offset: offset(filter.angle, filter.distance),
+                    strength: filter.strength,
+                    is_bevel: false,
+                    invert: is_inner,
+                    is_inner,
+                    is_on_top: filter.is_on_top,
+                    is_knockout: filter.is_knockout,
+                    colors: gradient_lookup_table(&filter.colors),
+                }
+            }
+            Filter::GradientBevelFilter(filter) => Self {
+                blur_x: filter.blur_x,
+                blur_y: filter.blur_y,
+                passes: filter.num_passes,
+                offset: offset(filter.angle, filter.distance),
+                strength: filter.strength,
+                is_bevel: true,
+                invert: false,
+                is_inner: bevel_type(filter.is_inner, filter.is_on_top) == EffectType::Inner,
+                is_on_top: filter.is_on_top,
+                is_knockout: filter.is_knockout,
+                colors: gradient_lookup_table(&filter.colors),
+            },
+            Filter::BlurFilter(_) | Filter::ConvolutionFilter(_) | Filter::ColorMatrixFilter(_) => {
+                return None
+            }
+        };
+        Some(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, color: [u8; 4]) -> Vec<u8> {
+        color
+            .iter()
+            .copied()
+            .cycle()
+            .take((width * height * 4) as usize)
+            .collect()
+    }
+
+    #[test]
+    fn blur_spreads_pixel() {
+        let mut rgba = vec![0; 5 * 4];
+        rgba[8..12].copy_from_slice(&[255, 255, 255, 255]);
+        let filter = Filter::BlurFilter(Box::new(BlurFilter {
+            blur_x: 3.0,
+            blur_y: 0.0,
+            num_passes: 1,
+        }));
+        apply_filter(5, 1, &mut rgba, &filter);
+        assert_eq!(&rgba[4..8], &[85, 85, 85, 85]);
+        assert_eq!(&rgba[8..12], &[85, 85, 85, 85]);
+        assert_eq!(&rgba[12..16], &[85, 85, 85, 85]);
+        assert_eq!(&rgba[0..4], &[0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn color_matrix_swaps_channels() {
+        let mut rgba = solid(2, 2, [255, 0, 0, 255]);
+        #[rustfmt::skip]
+        let matrix = [
+            0.0, 0.0, 1.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, 0.0, 0.0,
+            1.0, 0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0, 0.0,
+        ];
+        let filter = Filter::ColorMatrixFilter(Box::new(ColorMatrixFilter { matrix }));
+        apply_filter(2, 2, &mut rgba, &filter);
+        assert_eq!(rgba, solid(2, 2, [0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn knockout_glow_removes_source() {
+        let mut rgba = solid(3, 3, [0, 0, 0, 0]);
+        rgba[16..20].copy_from_slice(&[255, 255, 255, 255]);
+        let filter = Filter::GlowFilter(Box::new(GlowFilter {
+            color: Color::from_rgb(0xFF0000, 255),
+            blur_x: 3.0,
+            blur_y: 3.0,
+            strength: 1.0,
+            is_inner: false,
+            is_knockout: true,
+            num_passes: 1,
+        }));
+        apply_filter(3, 3, &mut rgba, &filter);
+        // The source pixel is knocked out, leaving only the glow around it.
+        assert_eq!(&rgba[16..20], &[0, 0, 0, 0]);
+        assert_eq!(&rgba[0..4], &[28, 0, 0, 28]);
+    }
+
+    #[test]
+    fn effect_params_of_bevel_on_top() {
+        let filter = Filter::BevelFilter(Box::new(BevelFilter {
+            shadow_color: Color::from_rgb(0, 255),
+            highlight_color: Color::from_rgb(0xFFFFFF, 255),
+            blur_x: 4.0,
+            blur_y: 4.0,
+            angle: 0.0,
+            distance: 2.0,
+            strength: 1.0,
+            is_inner: true,
+            is_knockout: false,
+            is_on_top: true,
+            num_passes: 1,
+        }));
+        let params = EffectParams::from_filter(&filter).unwrap();
+        assert_eq!(params.offset, (2, 0));
+        // Bevels drawn on top of the object aren't masked by it.
+        assert!(params.is_bevel && params.is_on_top && !params.is_inner && !params.invert);
+        assert_eq!(
+            params.colors,
+            vec![[0.0, 0.0, 0.0, 1.0], [0.0; 4], [1.0, 1.0, 1.0, 1.0]]
+        );
+        let blur = Filter::BlurFilter(Box::new(BlurFilter {
+            blur_x: 4.0,
+            blur_y: 4.0,
+            num_passes: 1,
+        }));
+        assert!(EffectParams::from_filter(&blur).is_none());
+    }
+
+    #[test]
+    fn drop_shadow_rect() {
+        let filter = Filter::DropShadowFilter(Box::new(DropShadowFilter {
+            color: Color::from_rgb(0, 255),
+            blur_x: 4.0,
+            blur_y: 4.0,
+            angle: 0.0,
+            distance: 4.0,
+            strength: 1.0,
+            is_inner: false,
+            is_knockout: false,
+            num_passes: 1,
+        }));
+        assert_eq!(filter_rect(&filter, (0, 0, 10, 10)), (-2, -2, 18, 14));
+    }
+
+    #[test]
+    fn convolution_identity() {
+        let mut rgba = solid(3, 3, [10, 20, 30, 255]);
+        let filter = Filter::ConvolutionFilter(Box::new(ConvolutionFilter {
+            num_matrix_rows: 3,
+            num_matrix_cols: 3,
+            matrix: vec![0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0],
+            divisor: 1.0,
+            bias: 0.0,
+            default_color: Color::from_rgb(0, 0),
+            is_clamped: true,
+            is_preserve_alpha: true,
+        }));
+        apply_filter(3, 3, &mut rgba, &filter);
+        assert_eq!(rgba, solid(3, 3, [10, 20, 30, 255]));
+    }
+}
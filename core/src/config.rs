@@ -1,5 +1,10 @@
+use bitflags::bitflags;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
 
 /// Controls whether the content is letterboxed or pillarboxed when the
 /// player's aspect ratio does not match the movie's aspect ratio.
@@ -28,3 +33,135 @@ impl Default for Letterbox {
         Letterbox::Fullscreen
     }
 }
+
+/// Controls how the movie is scaled to fit the player's viewport.
+///
+/// This corresponds to the `scale` embed parameter and `Stage.scaleMode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StageScaleMode {
+    /// The movie is scaled uniformly to fit the viewport, showing the entire
+    /// movie with margins if the aspect ratios differ.
+    ShowAll,
+
+    /// The movie is scaled uniformly to fill the viewport, cropping the movie
+    /// if the aspect ratios differ.
+    NoBorder,
+
+    /// The movie is stretched to fill the viewport exactly, ignoring its
+    /// aspect ratio.
+    ExactFit,
+
+    /// The movie is not scaled, and the stage is the size of the viewport.
+    NoScale,
+}
+
+impl Default for StageScaleMode {
+    fn default() -> Self {
+        StageScaleMode::ShowAll
+    }
+}
+
+impl fmt::Display for StageScaleMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // These are the values returned by `Stage.scaleMode`.
+        let s = match self {
+            StageScaleMode::ShowAll => "showAll",
+            StageScaleMode::NoBorder => "noBorder",
+            StageScaleMode::ExactFit => "exactFit",
+            StageScaleMode::NoScale => "noScale",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for StageScaleMode {
+    type Err = ParseStageScaleModeError;
+
+    /// Parses a scale mode case-insensitively, as Flash does for both
+    /// `Stage.scaleMode` and the `scale` embed parameter.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "showall" | "default" => Ok(StageScaleMode::ShowAll),
+            "noborder" => Ok(StageScaleMode::NoBorder),
+            "exactfit" => Ok(StageScaleMode::ExactFit),
+            "noscale" => Ok(StageScaleMode::NoScale),
+            _ => Err(ParseStageScaleModeError),
+        }
+    }
+}
+
+/// The error returned when parsing an unknown `StageScaleMode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("unknown scale mode; expected showAll, noBorder, exactFit or noScale")]
+pub struct ParseStageScaleModeError;
+
+bitflags! {
+    /// Controls which edges of the viewport the movie is aligned to.
+    /// The movie is centered along any axis that has no alignment.
+    ///
+    /// This corresponds to the `salign` embed parameter and `Stage.align`.
+    #[derive(Default)]
+    pub struct StageAlign: u8 {
+        const TOP    = 1 << 0;
+        const BOTTOM = 1 << 1;
+        const LEFT   = 1 << 2;
+        const RIGHT  = 1 << 3;
+    }
+}
+
+impl fmt::Display for StageAlign {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // This is the order used by `Stage.align`.
+        for (flag, c) in &[
+            (StageAlign::LEFT, "L"),
+            (StageAlign::TOP, "T"),
+            (StageAlign::RIGHT, "R"),
+            (StageAlign::BOTTOM, "B"),
+        ] {
+            if self.contains(*flag) {
+                f.write_str(c)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for StageAlign {
+    type Err = Infallible;
+
+    /// Parses an alignment such as `"TL"`. Each letter sets the corresponding
+    /// edge, case-insensitively, and any other characters are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut align = StageAlign::empty();
+        for c in s.chars() {
+            match c.to_ascii_uppercase() {
+                'T' => align.insert(StageAlign::TOP),
+                'B' => align.insert(StageAlign::BOTTOM),
+                'L' => align.insert(StageAlign::LEFT),
+                'R' => align.insert(StageAlign::RIGHT),
+                _ => (),
+            }
+        }
+        Ok(align)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_stage_layout() {
+        assert_eq!("NOSCALE".parse(), Ok(StageScaleMode::NoScale));
+        assert_eq!("exactFit".parse(), Ok(StageScaleMode::ExactFit));
+        assert_eq!(
+            "stretch".parse::<StageScaleMode>(),
+            Err(ParseStageScaleModeError)
+        );
+
+        let align: StageAlign = "tl".parse().unwrap();
+        assert_eq!(align, StageAlign::TOP | StageAlign::LEFT);
+        assert_eq!(align.to_string(), "LT");
+        assert_eq!("".parse(), Ok(StageAlign::empty()));
+    }
+}
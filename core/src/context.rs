@@ -1,17 +1,24 @@
 //! Contexts and helper types passed between functions.
 
 use crate::avm1::globals::system::SystemProperties;
-use crate::avm1::{Avm1, Object as Avm1Object, Timers, Value as Avm1Value};
+use crate::avm1::{
+    Avm1, LocalConnections, Object as Avm1Object, Timers, Value as Avm1Value, XmlSockets,
+};
 use crate::avm2::{Avm2, Object as Avm2Object, Value as Avm2Value};
 use crate::backend::{
     audio::{AudioBackend, AudioManager, SoundHandle, SoundInstanceHandle},
+    local_connection::LocalConnectionBackend,
     locale::LocaleBackend,
     log::LogBackend,
     navigator::NavigatorBackend,
     render::RenderBackend,
+    socket::SocketBackend,
     storage::StorageBackend,
     ui::UiBackend,
+    video::VideoBackend,
 };
+use crate::config::{StageAlign, StageScaleMode};
+use crate::context_menu::ContextMenuState;
 use crate::display_object::{EditText, MovieClip, SoundTransform};
 use crate::external::ExternalInterface;
 use crate::focus_tracker::FocusTracker;
@@ -70,6 +77,12 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     /// The navigator backend, used by the AVM to make HTTP requests and visit webpages.
     pub navigator: &'a mut (dyn NavigatorBackend + 'a),
 
+    /// The socket backend, used to open network connections.
+    pub sockets: &'a mut dyn SocketBackend,
+
+    /// The video backend, used by video display objects to decode frames.
+    pub video: &'a mut dyn VideoBackend,
+
     /// The renderer, used by the display objects to draw themselves.
     pub renderer: &'a mut dyn RenderBackend,
 
@@ -79,6 +92,9 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     /// The storage backend, used for storing persistent state
     pub storage: &'a mut dyn StorageBackend,
 
+    /// The local connection backend, used to pass `LocalConnection` messages
+    pub local_connection: &'a mut dyn LocalConnectionBackend,
+
     /// The locale backend, used for localisation and personalisation
     pub locale: &'a mut dyn LocaleBackend,
 
@@ -103,6 +119,22 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     /// The dimensions of the stage.
     pub stage_size: (Twips, Twips),
 
+    /// The dimensions of the player's viewport.
+    /// This is the size of the stage in `noScale` mode.
+    pub viewport_size: (Twips, Twips),
+
+    /// How the stage is scaled to fit the viewport. Changed by `Stage.scaleMode`.
+    pub scale_mode: &'a mut StageScaleMode,
+
+    /// How the stage is aligned within the viewport. Changed by `Stage.align`.
+    pub stage_align: &'a mut StageAlign,
+
+    /// Whether the built-in items of the context menu are shown. Changed by `Stage.showMenu`.
+    pub show_menu: &'a mut bool,
+
+    /// The context menu that is currently being displayed, if any.
+    pub current_context_menu: &'a mut Option<ContextMenuState<'gc>>,
+
     /// Weak reference to the player.
     ///
     /// Recipients of an update context may upgrade the reference to ensure
@@ -124,6 +156,12 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     /// Shared objects cache
     pub shared_objects: &'a mut HashMap<String, Avm1Object<'gc>>,
 
+    /// Connected `LocalConnection` objects, and messages awaiting a result.
+    pub local_connections: &'a mut LocalConnections<'gc>,
+
+    /// Open `XMLSocket` objects.
+    pub xml_sockets: &'a mut XmlSockets<'gc>,
+
     /// Text fields with unbound variable bindings.
     pub unbound_text_fields: &'a mut Vec<EditText<'gc>>,
 
@@ -286,22 +324,32 @@ impl<'a, 'gc, 'gc_context> UpdateContext<'a, 'gc, 'gc_context> {
             audio: self.audio,
             audio_manager: self.audio_manager,
             navigator: self.navigator,
+            sockets: self.sockets,
+            video: self.video,
             renderer: self.renderer,
             locale: self.locale,
             log: self.log,
             ui: self.ui,
             storage: self.storage,
+            local_connection: self.local_connection,
             rng: self.rng,
             levels: self.levels,
             mouse_hovered_object: self.mouse_hovered_object,
             mouse_position: self.mouse_position,
             drag_object: self.drag_object,
             stage_size: self.stage_size,
+            viewport_size: self.viewport_size,
+            scale_mode: self.scale_mode,
+            stage_align: self.stage_align,
+            show_menu: self.show_menu,
+            current_context_menu: self.current_context_menu,
             player: self.player.clone(),
             load_manager: self.load_manager,
             system: self.system,
             instance_counter: self.instance_counter,
             shared_objects: self.shared_objects,
+            local_connections: self.local_connections,
+            xml_sockets: self.xml_sockets,
             unbound_text_fields: self.unbound_text_fields,
             timers: self.timers,
             avm1: self.avm1,
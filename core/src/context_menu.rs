@@ -0,0 +1,90 @@
+//! Context menu shown when right-clicking the player.
+//!
+//! The menu is built by `Player::prepare_context_menu` from the built-in
+//! player items and the custom items of the AVM1 `ContextMenu` object
+//! attached to the clicked display object. The frontend displays the items
+//! and reports the chosen one back via `Player::run_context_menu_callback`.
+
+use crate::avm1::Object as Avm1Object;
+use gc_arena::Collect;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// The items of the currently displayed context menu, along with the action
+/// to run when each item is chosen.
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct ContextMenuState<'gc> {
+    info: Vec<ContextMenuItem>,
+    callbacks: Vec<ContextMenuCallback<'gc>>,
+}
+
+impl<'gc> ContextMenuState<'gc> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn push(&mut self, item: ContextMenuItem, callback: ContextMenuCallback<'gc>) {
+        self.info.push(item);
+        self.callbacks.push(callback);
+    }
+
+    /// The items to display to the user.
+    pub fn info(&self) -> &[ContextMenuItem] {
+        &self.info
+    }
+
+    /// The action for the item at `index`, or `None` if the item is disabled.
+    pub fn callback(&self, index: usize) -> Option<ContextMenuCallback<'gc>> {
+        if self.info.get(index)?.enabled {
+            self.callbacks.get(index).copied()
+        } else {
+            None
+        }
+    }
+}
+
+/// A single item of a context menu, as displayed by the frontend.
+#[derive(Clone, Debug, Default, Collect)]
+#[collect(require_static)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ContextMenuItem {
+    /// Whether the item can be chosen. Disabled items are displayed grayed out.
+    pub enabled: bool,
+
+    /// Whether a separator line is drawn above this item.
+    pub separator_before: bool,
+
+    /// Whether a check mark is displayed next to this item.
+    pub checked: bool,
+
+    /// The text of the item.
+    pub caption: String,
+}
+
+/// The action to run when a context menu item is chosen.
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+pub enum ContextMenuCallback<'gc> {
+    /// Toggles playback of the root movie.
+    Play,
+
+    /// Rewinds the root movie to its first frame and stops.
+    Rewind,
+
+    /// Advances the root movie to the next frame.
+    Forward,
+
+    /// Moves the root movie back to the previous frame.
+    Back,
+
+    /// Calls `onSelect` of an AVM1 `ContextMenuItem`.
+    Avm1 {
+        /// The `ContextMenuItem` that was chosen.
+        item: Avm1Object<'gc>,
+
+        /// The object that the menu is attached to.
+        target: Avm1Object<'gc>,
+    },
+}
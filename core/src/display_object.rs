@@ -16,6 +16,7 @@ use std::cell::{Ref, RefMut};
 use std::cmp::min;
 use std::fmt::Debug;
 use std::sync::Arc;
+use swf::BlendMode;
 
 mod bitmap;
 mod button;
@@ -25,6 +26,7 @@ mod graphic;
 mod morph_shape;
 mod movie_clip;
 mod text;
+mod video;
 
 use crate::avm1::activation::Activation;
 use crate::backend::ui::MouseCursor;
@@ -39,6 +41,7 @@ pub use graphic::Graphic;
 pub use morph_shape::{MorphShape, MorphShapeStatic};
 pub use movie_clip::{MovieClip, Scene};
 pub use text::Text;
+pub use video::Video;
 
 #[derive(Clone, Debug)]
 pub struct DisplayObjectBase<'gc> {
@@ -76,6 +79,16 @@ pub struct DisplayObjectBase<'gc> {
     /// The display object we are currently masking.
     maskee: Option<DisplayObject<'gc>>,
 
+    /// The bitmap filters applied to this display object when rendering.
+    filters: Vec<swf::Filter>,
+
+    /// The blend mode used when compositing this display object onto its background.
+    blend_mode: BlendMode,
+
+    /// Whether a focus rectangle is drawn around this display object when it has
+    /// keyboard focus, overriding the global `_focusrect` if set.
+    focus_rect: Option<bool>,
+
     /// Bit flags for various display object properites.
     flags: DisplayObjectFlags,
 }
@@ -97,6 +110,9 @@ impl<'gc> Default for DisplayObjectBase<'gc> {
             next_sibling: None,
             masker: None,
             maskee: None,
+            filters: Vec::new(),
+            blend_mode: BlendMode::Normal,
+            focus_rect: None,
             sound_transform: Default::default(),
             flags: DisplayObjectFlags::VISIBLE,
         }
@@ -381,6 +397,30 @@ impl<'gc> DisplayObjectBase<'gc> {
         self.sound_transform = sound_transform;
     }
 
+    fn filters(&self) -> &[swf::Filter] {
+        &self.filters
+    }
+
+    fn set_filters(&mut self, filters: Vec<swf::Filter>) {
+        self.filters = filters;
+    }
+
+    fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
+    fn focus_rect(&self) -> Option<bool> {
+        self.focus_rect
+    }
+
+    fn set_focus_rect(&mut self, focus_rect: Option<bool>) {
+        self.focus_rect = focus_rect;
+    }
+
     fn visible(&self) -> bool {
         self.flags.contains(DisplayObjectFlags::VISIBLE)
     }
@@ -479,6 +519,7 @@ impl<'gc> DisplayObjectBase<'gc> {
         MorphShape(MorphShape<'gc>),
         MovieClip(MovieClip<'gc>),
         Text(Text<'gc>),
+        Video(Video<'gc>),
     }
 )]
 pub trait TDisplayObject<'gc>:
@@ -824,6 +865,31 @@ pub trait TDisplayObject<'gc>:
         sound_transform: SoundTransform,
     );
 
+    /// The bitmap filters applied to this display object when it is rendered.
+    /// Returned by the `filters` ActionScript property.
+    fn filters(&self) -> Ref<[swf::Filter]>;
+
+    /// Sets the bitmap filters applied to this display object when it is rendered.
+    /// Set by the `filters` ActionScript property or the `PlaceObject3` tag.
+    fn set_filters(&self, context: MutationContext<'gc, '_>, filters: Vec<swf::Filter>);
+
+    /// The blend mode used when compositing this display object onto its background.
+    /// Returned by the `blendMode` ActionScript property.
+    fn blend_mode(&self) -> BlendMode;
+
+    /// Sets the blend mode used when compositing this display object onto its background.
+    /// Set by the `blendMode` ActionScript property or the `PlaceObject3` tag.
+    fn set_blend_mode(&self, context: MutationContext<'gc, '_>, blend_mode: BlendMode);
+
+    /// Whether a focus rectangle is drawn around this display object when it has keyboard focus.
+    /// `None` uses the setting of the root movie.
+    /// Returned by the `_focusrect` ActionScript property.
+    fn focus_rect(&self) -> Option<bool>;
+
+    /// Sets whether a focus rectangle is drawn around this display object when it has keyboard focus.
+    /// Set by the `_focusrect` ActionScript property.
+    fn set_focus_rect(&self, context: MutationContext<'gc, '_>, focus_rect: Option<bool>);
+
     /// Whether this display object is used as the _root of itself and its children.
     /// Returned by the `_lockroot` ActionScript property.
     fn lock_root(&self) -> bool;
@@ -890,6 +956,19 @@ pub trait TDisplayObject<'gc>:
         }
         context.transform_stack.push(&*self.transform());
 
+        // Blend modes and filters are not applied while drawing into a mask.
+        let (blend_mode, filters) = if context.allow_mask {
+            (self.blend_mode(), self.filters().to_vec())
+        } else {
+            (BlendMode::Normal, Vec::new())
+        };
+        if blend_mode != BlendMode::Normal {
+            context.renderer.push_blend_mode(blend_mode);
+        }
+        if !filters.is_empty() {
+            context.renderer.push_filter_layer();
+        }
+
         let mask = self.masker();
         let mut mask_transform = crate::transform::Transform::default();
         if let Some(m) = mask {
@@ -914,6 +993,13 @@ pub trait TDisplayObject<'gc>:
             context.renderer.pop_mask();
         }
 
+        if !filters.is_empty() {
+            context.renderer.pop_filter_layer(&filters);
+        }
+        if blend_mode != BlendMode::Normal {
+            context.renderer.pop_blend_mode();
+        }
+
         context.transform_stack.pop();
     }
 
@@ -947,35 +1033,40 @@ pub trait TDisplayObject<'gc>:
     fn as_morph_shape(&self) -> Option<MorphShape<'gc>> {
         None
     }
+    fn as_video(&self) -> Option<Video<'gc>> {
+        None
+    }
     fn as_container(self) -> Option<DisplayObjectContainer<'gc>> {
         None
     }
 
     fn apply_place_object(
         &self,
-        gc_context: MutationContext<'gc, '_>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
         placing_movie: Option<Arc<SwfMovie>>,
         place_object: &swf::PlaceObject,
     ) {
         // PlaceObject tags only apply if this onject has not been dynamically moved by AS code.
         if !self.transformed_by_script() {
             if let Some(matrix) = &place_object.matrix {
-                self.set_matrix(gc_context, &matrix);
+                self.set_matrix(context.gc_context, &matrix);
             }
             if let Some(color_transform) = &place_object.color_transform {
-                self.set_color_transform(gc_context, &color_transform.clone().into());
+                self.set_color_transform(context.gc_context, &color_transform.clone().into());
             }
             if let Some(name) = &place_object.name {
                 let encoding = swf::SwfStr::encoding_for_version(self.swf_version());
                 let name = name.to_str_lossy(encoding);
-                self.set_name(gc_context, &name);
+                self.set_name(context.gc_context, &name);
             }
             if let Some(clip_depth) = place_object.clip_depth {
-                self.set_clip_depth(gc_context, clip_depth.into());
+                self.set_clip_depth(context.gc_context, clip_depth.into());
             }
             if let Some(ratio) = place_object.ratio {
                 if let Some(mut morph_shape) = self.as_morph_shape() {
-                    morph_shape.set_ratio(gc_context, ratio);
+                    morph_shape.set_ratio(context.gc_context, ratio);
+                } else if let Some(video) = self.as_video() {
+                    video.seek(context, ratio.into());
                 }
             }
             // Clip events only apply to movie clips.
@@ -986,7 +1077,7 @@ pub trait TDisplayObject<'gc>:
                 use crate::display_object::movie_clip::ClipAction;
                 if let Some(placing_movie) = placing_movie {
                     clip.set_clip_actions(
-                        gc_context,
+                        context.gc_context,
                         clip_actions
                             .iter()
                             .cloned()
@@ -1001,6 +1092,12 @@ pub trait TDisplayObject<'gc>:
                     log::error!("No movie when trying to set clip event");
                 }
             }
+            if let Some(filters) = &place_object.filters {
+                self.set_filters(context.gc_context, filters.clone());
+            }
+            if let Some(blend_mode) = place_object.blend_mode {
+                self.set_blend_mode(context.gc_context, blend_mode);
+            }
             // TODO: Others will go here eventually.
         }
     }
@@ -1014,6 +1111,8 @@ pub trait TDisplayObject<'gc>:
         self.set_color_transform(gc_context, &*other.color_transform());
         self.set_clip_depth(gc_context, other.clip_depth());
         self.set_name(gc_context, &*other.name());
+        self.set_filters(gc_context, other.filters().to_vec());
+        self.set_blend_mode(gc_context, other.blend_mode());
         if let (Some(mut me), Some(other)) = (self.as_morph_shape(), other.as_morph_shape()) {
             me.set_ratio(gc_context, other.ratio());
         }
@@ -1356,6 +1455,36 @@ macro_rules! impl_display_object_sansbounds {
                 .set_sound_transform(value);
             context.set_sound_transforms_dirty();
         }
+        fn filters(&self) -> std::cell::Ref<[swf::Filter]> {
+            std::cell::Ref::map(self.0.read(), |r| r.$field.filters())
+        }
+        fn set_filters(
+            &self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            filters: Vec<swf::Filter>,
+        ) {
+            self.0.write(context).$field.set_filters(filters)
+        }
+        fn blend_mode(&self) -> swf::BlendMode {
+            self.0.read().$field.blend_mode()
+        }
+        fn set_blend_mode(
+            &self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            blend_mode: swf::BlendMode,
+        ) {
+            self.0.write(context).$field.set_blend_mode(blend_mode)
+        }
+        fn focus_rect(&self) -> Option<bool> {
+            self.0.read().$field.focus_rect()
+        }
+        fn set_focus_rect(
+            &self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            focus_rect: Option<bool>,
+        ) {
+            self.0.write(context).$field.set_focus_rect(focus_rect)
+        }
         fn visible(&self) -> bool {
             self.0.read().$field.visible()
         }
@@ -1556,3 +1685,66 @@ impl Default for SoundTransform {
         }
     }
 }
+
+/// Returns the name of a blend mode as used by the ActionScript `blendMode` property.
+pub fn blend_mode_to_str(blend_mode: BlendMode) -> &'static str {
+    match blend_mode {
+        BlendMode::Normal => "normal",
+        BlendMode::Layer => "layer",
+        BlendMode::Multiply => "multiply",
+        BlendMode::Screen => "screen",
+        BlendMode::Lighten => "lighten",
+        BlendMode::Darken => "darken",
+        BlendMode::Difference => "difference",
+        BlendMode::Add => "add",
+        BlendMode::Subtract => "subtract",
+        BlendMode::Invert => "invert",
+        BlendMode::Alpha => "alpha",
+        BlendMode::Erase => "erase",
+        BlendMode::Overlay => "overlay",
+        BlendMode::HardLight => "hardlight",
+    }
+}
+
+/// Parses a blend mode from its ActionScript `blendMode` name.
+pub fn blend_mode_from_str(name: &str) -> Option<BlendMode> {
+    Some(match name {
+        "normal" => BlendMode::Normal,
+        "layer" => BlendMode::Layer,
+        "multiply" => BlendMode::Multiply,
+        "screen" => BlendMode::Screen,
+        "lighten" => BlendMode::Lighten,
+        "darken" => BlendMode::Darken,
+        "difference" => BlendMode::Difference,
+        "add" => BlendMode::Add,
+        "subtract" => BlendMode::Subtract,
+        "invert" => BlendMode::Invert,
+        "alpha" => BlendMode::Alpha,
+        "erase" => BlendMode::Erase,
+        "overlay" => BlendMode::Overlay,
+        "hardlight" => BlendMode::HardLight,
+        _ => return None,
+    })
+}
+
+/// Returns the blend mode with the given index, as stored in `PlaceObject3` tags.
+/// AVM1 also accepts these indices when setting `blendMode`.
+pub fn blend_mode_from_index(index: i32) -> Option<BlendMode> {
+    Some(match index {
+        1 => BlendMode::Normal,
+        2 => BlendMode::Layer,
+        3 => BlendMode::Multiply,
+        4 => BlendMode::Screen,
+        5 => BlendMode::Lighten,
+        6 => BlendMode::Darken,
+        7 => BlendMode::Difference,
+        8 => BlendMode::Add,
+        9 => BlendMode::Subtract,
+        10 => BlendMode::Invert,
+        11 => BlendMode::Alpha,
+        12 => BlendMode::Erase,
+        13 => BlendMode::Overlay,
+        14 => BlendMode::HardLight,
+        _ => return None,
+    })
+}
@@ -179,6 +179,7 @@ impl<'gc> Button<'gc> {
                     context.gc_context,
                     &record.color_transform.clone().into(),
                 );
+                child.set_blend_mode(context.gc_context, record.blend_mode);
             }
         }
         drop(write);
@@ -1,21 +1,27 @@
 //! `EditText` display object and support code.
 
 use crate::avm1::activation::{Activation, ActivationIdentifier};
+use crate::avm1::object::style_sheet::StyleSheetObject;
 use crate::avm1::{Avm1, AvmString, Object, StageObject, TObject, Value};
+use crate::avm2::{
+    Activation as Avm2Activation, Avm2, Error as Avm2Error, Namespace as Avm2Namespace,
+    Object as Avm2Object, QName as Avm2QName, StageObject as Avm2StageObject,
+    TObject as Avm2TObject, Value as Avm2Value,
+};
 use crate::backend::ui::MouseCursor;
-use crate::context::{RenderContext, UpdateContext};
+use crate::context::{ActionType, RenderContext, UpdateContext};
 use crate::display_object::{DisplayObjectBase, TDisplayObject};
 use crate::drawing::Drawing;
 use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult, KeyCode};
 use crate::font::{round_down_to_pixel, Glyph, TextRenderSettings};
-use crate::html::{BoxBounds, FormatSpans, LayoutBox, LayoutContent, TextFormat};
+use crate::html::{BoxBounds, FormatSpans, LayoutBox, LayoutContent, LayoutLine, TextFormat};
 use crate::prelude::*;
 use crate::shape_utils::DrawCommand;
 use crate::string_utils;
 use crate::tag_utils::SwfMovie;
 use crate::transform::Transform;
 use crate::types::{Degrees, Percent};
-use crate::vminterface::Instantiator;
+use crate::vminterface::{AvmObject, AvmType, Instantiator};
 use crate::xml::XMLDocument;
 use chrono::Utc;
 use gc_arena::{Collect, Gc, GcCell, MutationContext};
@@ -119,11 +125,26 @@ pub struct EditTextData<'gc> {
     /// The intrinsic bounds of the laid-out text.
     intrinsic_bounds: BoxBounds<Twips>,
 
+    /// The lines of the laid-out text.
+    lines: Vec<LayoutLine>,
+
+    /// The index of the topmost visible line, starting from 1.
+    scroll: usize,
+
+    /// The horizontal scroll offset of the text, in pixels.
+    hscroll: f64,
+
+    /// Whether this text field scrolls in response to the mouse wheel.
+    is_mouse_wheel_enabled: bool,
+
     /// The current intrinsic bounds of the text field.
     bounds: BoundingBox,
 
-    /// The AVM1 object handle
-    object: Option<Object<'gc>>,
+    /// The underlying script object.
+    ///
+    /// This is either an AVM1 `TextField` or an AVM2 `flash.text.TextField`,
+    /// depending on the VM of the movie this text field belongs to.
+    object: Option<AvmObject<'gc>>,
 
     /// The variable path that this text field is bound to (AVM1 only).
     variable: Option<String>,
@@ -142,6 +163,30 @@ pub struct EditTextData<'gc> {
 
     /// Which rendering engine this text field will use.
     render_settings: TextRenderSettings,
+
+    /// The maximum number of characters that the user can enter, if limited.
+    max_chars: Option<usize>,
+
+    /// The characters that the user is allowed to enter, if restricted.
+    ///
+    /// This uses the syntax of `TextField.restrict`.
+    restrict: Option<String>,
+
+    /// The states to return to when the user undoes their edits, with the
+    /// most recent last.
+    undo_stack: Vec<UndoState>,
+
+    /// The position after the characters most recently typed by the user, if
+    /// the last edit was typing.
+    ///
+    /// Characters typed at this position are undone together with them.
+    typing_end: Option<usize>,
+
+    /// The style sheet applied to HTML text, if any.
+    style_sheet: Option<StyleSheetObject<'gc>>,
+
+    /// The range of text covered by the link under the mouse, if any.
+    hovered_link: Option<(usize, usize)>,
 }
 
 impl<'gc> EditText<'gc> {
@@ -157,6 +202,10 @@ impl<'gc> EditText<'gc> {
         let is_password = swf_tag.is_password;
         let is_editable = !swf_tag.is_read_only;
         let is_html = swf_tag.is_html;
+        let max_chars = swf_tag
+            .max_length
+            .filter(|&max_length| max_length > 0)
+            .map(usize::from);
         let document = XMLDocument::new(context.gc_context);
         let text = swf_tag.initial_text.clone().unwrap_or_default();
         let default_format = TextFormat::from_swf_tag(swf_tag.clone(), swf_movie.clone(), context);
@@ -170,7 +219,7 @@ impl<'gc> EditText<'gc> {
             let _ = document
                 .as_node()
                 .replace_with_str(context.gc_context, &text, false, false);
-            text_spans.lower_from_html(document);
+            text_spans.lower_from_html(document, None);
         } else {
             text_spans.replace_text(0, text_spans.text().len(), &text, Some(&default_format));
         }
@@ -186,7 +235,7 @@ impl<'gc> EditText<'gc> {
 
         let bounds: BoundingBox = swf_tag.bounds.clone().into();
 
-        let (layout, intrinsic_bounds) = LayoutBox::lower_from_text_spans(
+        let (layout, intrinsic_bounds, lines) = LayoutBox::lower_from_text_spans(
             &text_spans,
             context,
             swf_movie.clone(),
@@ -261,6 +310,10 @@ impl<'gc> EditText<'gc> {
                 object: None,
                 layout,
                 intrinsic_bounds,
+                lines,
+                scroll: 1,
+                hscroll: 0.0,
+                is_mouse_wheel_enabled: true,
                 bounds,
                 autosize: AutoSizeMode::None,
                 variable: variable.map(|s| s.to_string_lossy(encoding)),
@@ -269,6 +322,12 @@ impl<'gc> EditText<'gc> {
                 selection: None,
                 has_focus: false,
                 render_settings: Default::default(),
+                max_chars,
+                restrict: None,
+                undo_stack: Vec::new(),
+                typing_end: None,
+                style_sheet: None,
+                hovered_link: None,
             },
         ));
 
@@ -303,7 +362,7 @@ impl<'gc> EditText<'gc> {
                 b: 0,
                 a: 0xFF,
             }),
-            max_length: Some(width as u16),
+            max_length: None,
             layout: Some(swf::TextLayout {
                 align: swf::TextAlign::Left,
                 left_margin: Twips::from_pixels(0.0),
@@ -350,6 +409,8 @@ impl<'gc> EditText<'gc> {
         let tf = edit_text.text_spans.default_format().clone();
 
         edit_text.text_spans.replace_text(0, len, &text, Some(&tf));
+        edit_text.undo_stack.clear();
+        edit_text.typing_end = None;
 
         drop(edit_text);
 
@@ -402,7 +463,12 @@ impl<'gc> EditText<'gc> {
     }
 
     pub fn html_tree(self, context: &mut UpdateContext<'_, 'gc, '_>) -> XMLDocument<'gc> {
-        self.0.read().text_spans.raise_to_html(context.gc_context)
+        let edit_text = self.0.read();
+        if edit_text.style_sheet.is_some() {
+            edit_text.document
+        } else {
+            edit_text.text_spans.raise_to_html(context.gc_context)
+        }
     }
 
     /// Set the HTML tree for the given display object.
@@ -419,13 +485,37 @@ impl<'gc> EditText<'gc> {
         let mut write = self.0.write(context.gc_context);
 
         write.document = doc;
-        write.text_spans.lower_from_html(doc);
+        write.hovered_link = None;
+        let style_sheet = write.style_sheet;
+        write.text_spans.lower_from_html(
+            doc,
+            style_sheet
+                .map(|style_sheet| style_sheet.style_sheet())
+                .as_deref(),
+        );
+        write.undo_stack.clear();
+        write.typing_end = None;
 
         drop(write);
 
         self.relayout(context);
     }
 
+    pub fn style_sheet(self) -> Option<StyleSheetObject<'gc>> {
+        self.0.read().style_sheet
+    }
+
+    /// Set the style sheet used to style HTML text.
+    ///
+    /// Only HTML text that is set afterwards will be styled.
+    pub fn set_style_sheet(
+        self,
+        style_sheet: Option<StyleSheetObject<'gc>>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) {
+        self.0.write(context.gc_context).style_sheet = style_sheet;
+    }
+
     pub fn text_length(self) -> usize {
         self.0.read().text_spans.text().len()
     }
@@ -727,7 +817,7 @@ impl<'gc> EditText<'gc> {
             edit_text.text_spans.clear_displayed_text();
         }
 
-        let (new_layout, intrinsic_bounds) = LayoutBox::lower_from_text_spans(
+        let (new_layout, intrinsic_bounds, lines) = LayoutBox::lower_from_text_spans(
             &edit_text.text_spans,
             context,
             movie,
@@ -738,6 +828,7 @@ impl<'gc> EditText<'gc> {
 
         edit_text.layout = new_layout;
         edit_text.intrinsic_bounds = intrinsic_bounds;
+        edit_text.lines = lines;
 
         match autosize {
             AutoSizeMode::None => {}
@@ -777,6 +868,159 @@ impl<'gc> EditText<'gc> {
                 edit_text.base.set_transformed_by_script(true);
             }
         }
+
+        drop(edit_text);
+
+        // The text may have shrunk, so make sure we haven't scrolled past it.
+        let (scroll, hscroll) = (self.scroll(), self.hscroll());
+        self.set_scroll(scroll as f64, context);
+        self.set_hscroll(hscroll, context);
+    }
+
+    /// The height of the area that text is displayed within.
+    fn visible_height(edit_text: &EditTextData<'gc>) -> Twips {
+        edit_text.bounds.height() - Twips::from_pixels(Self::INTERNAL_PADDING * 2.0)
+    }
+
+    /// The index of the topmost visible line, starting from 1.
+    pub fn scroll(self) -> usize {
+        self.0.read().scroll
+    }
+
+    /// Scroll vertically so that the given line is the topmost visible one.
+    ///
+    /// The line is clamped to the range of lines that can be scrolled to.
+    pub fn set_scroll(self, scroll: f64, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let scroll = if scroll.is_nan() {
+            1
+        } else {
+            scroll.max(1.0).min(self.maxscroll() as f64) as usize
+        };
+
+        let mut edit_text = self.0.write(context.gc_context);
+        if edit_text.scroll != scroll {
+            edit_text.scroll = scroll;
+            drop(edit_text);
+            self.on_scroller(context);
+        }
+    }
+
+    /// The largest value of `scroll`, at which the last line is visible.
+    pub fn maxscroll(self) -> usize {
+        let edit_text = self.0.read();
+        let visible_height = Self::visible_height(&edit_text);
+
+        if let Some(last_line) = edit_text.lines.last() {
+            let bottom = last_line.bounds().extent_y();
+            edit_text
+                .lines
+                .iter()
+                .position(|line| bottom - line.bounds().offset_y() <= visible_height)
+                .unwrap_or(edit_text.lines.len() - 1)
+                + 1
+        } else {
+            1
+        }
+    }
+
+    /// The index of the bottommost visible line, starting from 1.
+    pub fn bottom_scroll(self) -> usize {
+        let edit_text = self.0.read();
+        let visible_height = Self::visible_height(&edit_text);
+        let scroll = edit_text.scroll;
+
+        let top = edit_text
+            .lines
+            .get(scroll - 1)
+            .map(|line| line.bounds().offset_y())
+            .unwrap_or_default();
+        let visible_lines = edit_text
+            .lines
+            .iter()
+            .skip(scroll - 1)
+            .take_while(|line| line.bounds().extent_y() - top <= visible_height)
+            .count();
+
+        scroll + visible_lines.max(1) - 1
+    }
+
+    /// The horizontal scroll offset of the text, in pixels.
+    pub fn hscroll(self) -> f64 {
+        self.0.read().hscroll
+    }
+
+    /// Scroll horizontally to the given offset, in pixels.
+    ///
+    /// The offset is clamped to the range that can be scrolled to.
+    pub fn set_hscroll(self, hscroll: f64, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let hscroll = if hscroll.is_nan() {
+            0.0
+        } else {
+            hscroll.max(0.0).min(self.maxhscroll()).floor()
+        };
+
+        let mut edit_text = self.0.write(context.gc_context);
+        if edit_text.hscroll != hscroll {
+            edit_text.hscroll = hscroll;
+            drop(edit_text);
+            self.on_scroller(context);
+        }
+    }
+
+    /// The largest value of `hscroll`, at which the end of the widest line is
+    /// visible.
+    pub fn maxhscroll(self) -> f64 {
+        let edit_text = self.0.read();
+        let visible_width =
+            edit_text.bounds.width() - Twips::from_pixels(Self::INTERNAL_PADDING * 2.0);
+
+        round_down_to_pixel(edit_text.intrinsic_bounds.width() - visible_width)
+            .to_pixels()
+            .max(0.0)
+    }
+
+    /// Clamp the scroll position to the text after the field has been resized.
+    fn clamp_scroll(self, gc_context: MutationContext<'gc, '_>) {
+        let (maxscroll, maxhscroll) = (self.maxscroll(), self.maxhscroll());
+        let mut edit_text = self.0.write(gc_context);
+        edit_text.scroll = edit_text.scroll.min(maxscroll);
+        edit_text.hscroll = edit_text.hscroll.min(maxhscroll);
+    }
+
+    /// The offset of the laid-out text caused by scrolling.
+    fn scroll_offset(edit_text: &EditTextData<'gc>) -> (Twips, Twips) {
+        let top = edit_text
+            .lines
+            .get(edit_text.scroll - 1)
+            .map(|line| line.bounds().offset_y())
+            .unwrap_or_default();
+
+        (Twips::from_pixels(edit_text.hscroll), top)
+    }
+
+    pub fn is_mouse_wheel_enabled(self) -> bool {
+        self.0.read().is_mouse_wheel_enabled
+    }
+
+    pub fn set_mouse_wheel_enabled(self, is_enabled: bool, context: MutationContext<'gc, '_>) {
+        self.0.write(context).is_mouse_wheel_enabled = is_enabled;
+    }
+
+    /// Scroll in response to the mouse wheel being turned by `lines`.
+    ///
+    /// Positive values scroll towards the start of the text.
+    pub fn mouse_wheel(self, lines: f64, context: &mut UpdateContext<'_, 'gc, '_>) {
+        if !self.is_mouse_wheel_enabled() {
+            return;
+        }
+
+        // Always scroll by at least one line, even for small deltas.
+        let lines = if lines > 0.0 {
+            lines.ceil()
+        } else {
+            lines.floor()
+        };
+        self.set_scroll(self.scroll() as f64 - lines, context);
     }
 
     /// Measure the width and height of the `EditText`'s current text load.
@@ -1074,14 +1318,29 @@ impl<'gc> EditText<'gc> {
     }
 
     pub fn screen_position_to_index(self, position: (Twips, Twips)) -> Option<usize> {
+        let (start, end, is_nearer_end) = self.character_at_position(position)?;
+        Some(if is_nearer_end { end } else { start })
+    }
+
+    /// Find the character at a position on the stage.
+    ///
+    /// Returns the range of the character within the text, and whether the
+    /// position is nearer to the end of the character than its start.
+    fn character_at_position(self, position: (Twips, Twips)) -> Option<(usize, usize, bool)> {
         let text = self.0.read();
         let position = self.global_to_local(position);
+        let (scroll_x, scroll_y) = Self::scroll_offset(&text);
         let position = (
-            position.0 + Twips::from_pixels(Self::INTERNAL_PADDING),
-            position.1 + Twips::from_pixels(Self::INTERNAL_PADDING),
+            position.0 + Twips::from_pixels(Self::INTERNAL_PADDING) + scroll_x,
+            position.1 + Twips::from_pixels(Self::INTERNAL_PADDING) + scroll_y,
         );
 
         for layout_box in text.layout.iter() {
+            let box_start = match layout_box.content() {
+                LayoutContent::Text { start, .. } => *start,
+                _ => continue,
+            };
+
             let transform: Transform = layout_box.bounds().origin().into();
             let mut matrix = transform.matrix;
             matrix.invert();
@@ -1103,11 +1362,11 @@ impl<'gc> EditText<'gc> {
                             && local_position.1 >= Twips::zero()
                             && local_position.1 <= params.height()
                         {
-                            if local_position.0 >= x + (advance / 2) {
-                                result = Some(string_utils::next_char_boundary(text, pos));
-                            } else {
-                                result = Some(pos);
-                            }
+                            result = Some((
+                                box_start + pos,
+                                box_start + string_utils::next_char_boundary(text, pos),
+                                local_position.0 >= x + (advance / 2),
+                            ));
                         }
                     },
                 );
@@ -1120,84 +1379,503 @@ impl<'gc> EditText<'gc> {
         None
     }
 
+    /// The URL and target of the link at a position on the stage, if any.
+    fn link_at_position(self, position: (Twips, Twips)) -> Option<(String, String)> {
+        let (start, end, _) = self.character_at_position(position)?;
+        let format = self.0.read().text_spans.get_text_format(start, end);
+        let url = format.url.filter(|url| !url.is_empty())?;
+        Some((url, format.target.unwrap_or_default()))
+    }
+
+    /// Follow the link under the mouse, if any.
+    ///
+    /// `asfunction:` links call a function on the parent of this text field,
+    /// passing the text after the first comma as its only argument. `event:`
+    /// links dispatch a `TextEvent.LINK` in AVM2 and are ignored in AVM1; they
+    /// are never navigated to. Any other link is opened by the navigator.
+    /// Returns `true` if there was a link.
+    fn click_link(self, context: &mut UpdateContext<'_, 'gc, '_>) -> bool {
+        let (url, target) = if let Some(link) = self.link_at_position(*context.mouse_position) {
+            link
+        } else {
+            return false;
+        };
+
+        if url
+            .get(..11)
+            .map_or(false, |scheme| scheme.eq_ignore_ascii_case("asfunction:"))
+        {
+            let mut call = url[11..].splitn(2, ',');
+            let name = call.next().unwrap_or_default().trim();
+            let arg = call.next().unwrap_or_default();
+
+            if let Some(parent) = self.parent() {
+                if let Value::Object(object) = parent.object() {
+                    let arg = AvmString::new(context.gc_context, arg.to_string());
+                    Avm1::run_stack_frame_for_method(
+                        parent,
+                        object,
+                        self.swf_version(),
+                        context,
+                        name,
+                        &[arg.into()],
+                    );
+                }
+            }
+        } else if url.starts_with("event:") {
+            if let Avm2Value::Object(object) = self.object2() {
+                if let Err(e) = Avm2::dispatch_text_event(context, object, "link", &url[6..]) {
+                    log::error!("Unhandled AVM2 exception in link event handler: {}", e);
+                }
+            }
+        } else {
+            let window = if target.is_empty() {
+                None
+            } else {
+                Some(target)
+            };
+            context.navigator.navigate_to_url(url, window, None);
+        }
+
+        true
+    }
+
+    pub fn max_chars(self) -> Option<usize> {
+        self.0.read().max_chars
+    }
+
+    pub fn set_max_chars(self, max_chars: Option<usize>, context: MutationContext<'gc, '_>) {
+        self.0.write(context).max_chars = max_chars;
+    }
+
+    pub fn restrict(&self) -> Option<Ref<str>> {
+        let text = self.0.read();
+        if text.restrict.is_some() {
+            Some(Ref::map(text, |text| text.restrict.as_deref().unwrap()))
+        } else {
+            None
+        }
+    }
+
+    pub fn set_restrict(self, restrict: Option<String>, context: MutationContext<'gc, '_>) {
+        self.0.write(context).restrict = restrict;
+    }
+
+    /// The index of the line containing the given text position, if there are
+    /// any lines.
+    ///
+    /// A position at the end of a wrapped line belongs to the following line.
+    fn line_index(edit_text: &EditTextData<'gc>, position: usize) -> Option<usize> {
+        let lines = &edit_text.lines;
+        (0..lines.len())
+            .find(|&i| {
+                position <= lines[i].end()
+                    && lines
+                        .get(i + 1)
+                        .map_or(true, |next| next.start() != position)
+            })
+            .or_else(|| lines.len().checked_sub(1))
+    }
+
+    /// The position that the caret moves to from `position` when the given
+    /// navigation key is pressed, or `None` if the key doesn't move the caret.
+    ///
+    /// If `by_word` is set, the caret moves by words rather than characters,
+    /// and to the ends of the text rather than the ends of the line.
+    fn navigate(self, position: usize, key_code: ButtonKeyCode, by_word: bool) -> Option<usize> {
+        let edit_text = self.0.read();
+        let text = edit_text.text_spans.text();
+        let lines = &edit_text.lines;
+        let line = Self::line_index(&edit_text, position);
+
+        Some(match key_code {
+            ButtonKeyCode::Left if by_word => string_utils::prev_word_boundary(text, position),
+            ButtonKeyCode::Left => string_utils::prev_char_boundary(text, position),
+            ButtonKeyCode::Right if by_word => string_utils::next_word_boundary(text, position),
+            ButtonKeyCode::Right => string_utils::next_char_boundary(text, position),
+            ButtonKeyCode::Home if by_word => 0,
+            ButtonKeyCode::Home => line.map_or(0, |line| lines[line].start()),
+            ButtonKeyCode::End if by_word => text.len(),
+            ButtonKeyCode::End => line.map_or(text.len(), |line| lines[line].end()),
+            ButtonKeyCode::Up | ButtonKeyCode::Down => {
+                let line = line?;
+                let target = if key_code == ButtonKeyCode::Up {
+                    line.checked_sub(1)
+                } else {
+                    Some(line + 1).filter(|&target| target < lines.len())
+                };
+
+                match target {
+                    // Keep the caret in the same column, if the target line is long enough.
+                    Some(target) => {
+                        let column = text
+                            .get(lines[line].start()..position)
+                            .map_or(0, |text| text.chars().count());
+                        let target = &lines[target];
+                        text.get(target.start()..target.end())
+                            .and_then(|text| text.char_indices().nth(column))
+                            .map_or(target.end(), |(i, _)| target.start() + i)
+                    }
+                    None if key_code == ButtonKeyCode::Up => 0,
+                    None => text.len(),
+                }
+            }
+            _ => return None,
+        })
+    }
+
+    /// Extend the selection to the mouse position, while the mouse is being
+    /// dragged after being pressed on this text field.
+    pub fn mouse_drag(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        if !self.is_selectable() {
+            return;
+        }
+
+        if let Some(mut selection) = self.selection() {
+            if let Some(position) = self.screen_position_to_index(*context.mouse_position) {
+                selection.to = position;
+                self.set_selection(Some(selection), context.gc_context);
+            }
+        }
+    }
+
+    /// Update the link under the mouse.
+    ///
+    /// The link is highlighted with the `a:hover` style of the style sheet,
+    /// if there is one.
+    pub fn mouse_move(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let hovered_link = self
+            .character_at_position(*context.mouse_position)
+            .and_then(|(position, _, _)| self.0.read().text_spans.link_range(position));
+        self.set_hovered_link(hovered_link, context);
+    }
+
+    fn set_hovered_link(
+        self,
+        hovered_link: Option<(usize, usize)>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) {
+        let mut edit_text = self.0.write(context.gc_context);
+        if edit_text.hovered_link == hovered_link {
+            return;
+        }
+        edit_text.hovered_link = hovered_link;
+
+        let style_sheet = if let Some(style_sheet) = edit_text.style_sheet {
+            style_sheet
+        } else {
+            return;
+        };
+        let style_sheet = style_sheet.style_sheet();
+        let hover_format = if let Some(style) = style_sheet.style("a:hover") {
+            style.text_format()
+        } else {
+            return;
+        };
+
+        // The link is restyled from scratch, so that the previously hovered
+        // link loses its hover style.
+        let document = edit_text.document;
+        edit_text
+            .text_spans
+            .lower_from_html(document, Some(&style_sheet));
+        if let Some((from, to)) = hovered_link {
+            edit_text
+                .text_spans
+                .set_text_format(from, to, &hover_format);
+        }
+
+        drop(style_sheet);
+        drop(edit_text);
+
+        self.relayout(context);
+    }
+
+    /// Handle a key being pressed while this text field has focus.
+    ///
+    /// This handles keyboard shortcuts for the clipboard and undo; text entry
+    /// and navigation are handled by `text_input` and `handle_clip_event`.
+    pub fn key_down(self, key_code: KeyCode, context: &mut UpdateContext<'_, 'gc, '_>) {
+        if !context.ui.is_key_down(KeyCode::Control) {
+            return;
+        }
+
+        let selection = if let Some(selection) = self.selection() {
+            selection
+        } else {
+            return;
+        };
+
+        let (is_editable, is_selectable, is_password) = {
+            let edit_text = self.0.read();
+            (
+                edit_text.is_editable,
+                edit_text.is_selectable,
+                edit_text.is_password,
+            )
+        };
+
+        let changed = match key_code {
+            KeyCode::A if is_selectable => {
+                self.set_selection(
+                    Some(TextSelection::for_range(0, self.text_length())),
+                    context.gc_context,
+                );
+                false
+            }
+            // Password fields can't be copied from.
+            KeyCode::C | KeyCode::X if !is_password && !selection.is_caret() => {
+                let text = self.text();
+                context
+                    .ui
+                    .set_clipboard_content(text[selection.start()..selection.end()].to_string());
+
+                key_code == KeyCode::X
+                    && is_editable
+                    && self.user_replace_text(
+                        selection.start(),
+                        selection.end(),
+                        "",
+                        false,
+                        context,
+                    )
+            }
+            KeyCode::V if is_editable => {
+                let content = context.ui.clipboard_content();
+                !content.is_empty()
+                    && self.user_replace_text(
+                        selection.start(),
+                        selection.end(),
+                        &content,
+                        false,
+                        context,
+                    )
+            }
+            KeyCode::Z if is_editable => self.undo(context),
+            _ => false,
+        };
+
+        if changed {
+            self.notify_user_edit(context);
+        }
+    }
+
+    /// Paste text into this text field, replacing the selection.
+    ///
+    /// Frontends that can only read the clipboard asynchronously deliver the
+    /// pasted text here, rather than through `UiBackend::clipboard_content`.
+    pub fn paste(self, text: &str, context: &mut UpdateContext<'_, 'gc, '_>) {
+        if !self.0.read().is_editable || text.is_empty() {
+            return;
+        }
+
+        if let Some(selection) = self.selection() {
+            if self.user_replace_text(selection.start(), selection.end(), text, false, context) {
+                self.notify_user_edit(context);
+            }
+        }
+    }
+
+    /// The maximum number of edits that can be undone.
+    const UNDO_LIMIT: usize = 100;
+
+    /// Remember the current text, so that the edit about to be made can be
+    /// undone.
+    fn push_undo_state(self, gc_context: MutationContext<'gc, '_>) {
+        let mut edit_text = self.0.write(gc_context);
+        let state = UndoState {
+            text_spans: edit_text.text_spans.clone(),
+            selection: edit_text.selection,
+        };
+
+        if edit_text.undo_stack.len() >= Self::UNDO_LIMIT {
+            edit_text.undo_stack.remove(0);
+        }
+        edit_text.undo_stack.push(state);
+    }
+
+    /// Undo the most recent edit made by the user.
+    ///
+    /// Returns `true` if there was an edit to undo.
+    fn undo(self, context: &mut UpdateContext<'_, 'gc, '_>) -> bool {
+        let mut edit_text = self.0.write(context.gc_context);
+        if let Some(state) = edit_text.undo_stack.pop() {
+            edit_text.typing_end = None;
+            edit_text.text_spans = state.text_spans;
+            edit_text.selection = state.selection;
+            drop(edit_text);
+
+            self.relayout(context);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Replace text as if it were entered by the user, and place the caret
+    /// after it.
+    ///
+    /// The new text is filtered according to `restrict`, `maxChars` and
+    /// `multiline`. Returns `true` if the text was changed.
+    ///
+    /// `is_typing` should be set for characters typed one at a time, so that
+    /// a run of them is undone in one step.
+    fn user_replace_text(
+        self,
+        from: usize,
+        to: usize,
+        text: &str,
+        is_typing: bool,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> bool {
+        let edit_text = self.0.read();
+
+        // Text styled by a style sheet can't be edited by the user.
+        if edit_text.style_sheet.is_some() {
+            return false;
+        }
+
+        let mut new_text: String = text
+            .chars()
+            .filter_map(|c| match c {
+                '\r' | '\n' if edit_text.is_multiline => Some(c),
+                '\r' | '\n' => None,
+                c => match &edit_text.restrict {
+                    Some(restrict) => restrict_char(restrict, c),
+                    None => Some(c),
+                },
+            })
+            .collect();
+
+        if let Some(max_chars) = edit_text.max_chars {
+            let old_text = edit_text.text_spans.text();
+            let remaining = max_chars
+                .saturating_sub(old_text.chars().count() - old_text[from..to].chars().count());
+            if let Some((end, _)) = new_text.char_indices().nth(remaining) {
+                new_text.truncate(end);
+            }
+        }
+        drop(edit_text);
+
+        // Don't delete the selection if none of the new text could be entered.
+        if new_text.is_empty() && (from == to || !text.is_empty()) {
+            return false;
+        }
+
+        let is_continued_typing = is_typing && from == to && self.0.read().typing_end == Some(from);
+        if !is_continued_typing {
+            self.push_undo_state(context.gc_context);
+        }
+
+        self.replace_text(from, to, &new_text, context);
+        let end = from + new_text.len();
+        self.set_selection(Some(TextSelection::for_position(end)), context.gc_context);
+        self.0.write(context.gc_context).typing_end = if is_typing { Some(end) } else { None };
+        true
+    }
+
+    /// Propagate an edit made by the user to the bound variable and any
+    /// `onChanged` listeners.
+    fn notify_user_edit(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let globals = context.avm1.global_object_cell();
+        let swf_version = context.swf.header().version;
+        let mut activation = Activation::from_nothing(
+            context.reborrow(),
+            ActivationIdentifier::root("[Propagate Text Binding]"),
+            swf_version,
+            globals,
+            self.into(),
+        );
+        self.propagate_text_binding(&mut activation);
+        self.on_changed(&mut activation);
+    }
+
     pub fn text_input(self, character: char, context: &mut UpdateContext<'_, 'gc, '_>) {
         if !self.0.read().is_editable {
             return;
         }
 
+        // Characters typed with control held are shortcuts, handled by `key_down`.
+        // AltGr is reported as control and alt together, and does enter text.
+        if context.ui.is_key_down(KeyCode::Control) && !context.ui.is_key_down(KeyCode::Alt) {
+            return;
+        }
+
         if let Some(selection) = self.selection() {
-            let mut changed = false;
-            match character as u8 {
-                8 | 127 if !selection.is_caret() => {
+            let changed = match character {
+                '\u{8}' | '\u{7f}' if !selection.is_caret() => {
                     // Backspace or delete with multiple characters selected
-                    self.replace_text(selection.start(), selection.end(), "", context);
-                    self.set_selection(
-                        Some(TextSelection::for_position(selection.start())),
-                        context.gc_context,
-                    );
-                    changed = true;
+                    self.user_replace_text(selection.start(), selection.end(), "", false, context)
                 }
-                8 => {
-                    // Backspace with caret
-                    if selection.start() > 0 {
-                        // Delete previous character
-                        let text = self.text();
-                        let start = string_utils::prev_char_boundary(&text, selection.start());
-                        self.replace_text(start, selection.start(), "", context);
-                        self.set_selection(
-                            Some(TextSelection::for_position(start)),
-                            context.gc_context,
-                        );
-                        changed = true;
-                    }
+                '\u{8}' => {
+                    // Backspace with caret: delete previous character
+                    let start = string_utils::prev_char_boundary(&self.text(), selection.start());
+                    self.user_replace_text(start, selection.start(), "", false, context)
                 }
-                127 => {
-                    // Delete with caret
-                    if selection.end() < self.text_length() {
-                        // Delete next character
-                        let text = self.text();
-                        let end = string_utils::next_char_boundary(&text, selection.start());
-                        self.replace_text(selection.start(), end, "", context);
-                        // No need to change selection
-                        changed = true;
-                    }
+                '\u{7f}' => {
+                    // Delete with caret: delete next character
+                    let end = string_utils::next_char_boundary(&self.text(), selection.start());
+                    self.user_replace_text(selection.start(), end, "", false, context)
                 }
-                code if !(code as char).is_control() => {
-                    self.replace_text(
-                        selection.start(),
-                        selection.end(),
-                        &character.to_string(),
-                        context,
-                    );
-                    let new_start = selection.start() + character.len_utf8();
-                    self.set_selection(
-                        Some(TextSelection::for_position(new_start)),
-                        context.gc_context,
-                    );
-                    changed = true;
+                '\r' | '\n' if self.is_multiline() => {
+                    self.user_replace_text(selection.start(), selection.end(), "\r", false, context)
                 }
-                _ => {}
-            }
+                c if !c.is_control() => self.user_replace_text(
+                    selection.start(),
+                    selection.end(),
+                    &c.to_string(),
+                    true,
+                    context,
+                ),
+                _ => false,
+            };
 
             if changed {
-                let globals = context.avm1.global_object_cell();
-                let swf_version = context.swf.header().version;
-                let mut activation = Activation::from_nothing(
-                    context.reborrow(),
-                    ActivationIdentifier::root("[Propagate Text Binding]"),
-                    swf_version,
-                    globals,
-                    self.into(),
-                );
-                self.propagate_text_binding(&mut activation);
-                self.on_changed(&mut activation);
+                self.notify_user_edit(context);
             }
         }
     }
 
+    /// Construct the AVM2 side of this text field.
+    fn construct_as_avm2_object(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        display_object: DisplayObject<'gc>,
+    ) {
+        let mut constr_thing = || {
+            let mut activation = Avm2Activation::from_nothing(context.reborrow());
+            let mut proto = activation.context.avm2.prototypes().textfield;
+            let constructor = proto
+                .get_property(
+                    proto,
+                    &Avm2QName::new(Avm2Namespace::public(), "constructor"),
+                    &mut activation,
+                )?
+                .coerce_to_object(&mut activation)?;
+            let object = Avm2StageObject::for_display_object(
+                activation.context.gc_context,
+                display_object,
+                proto,
+            )
+            .into();
+
+            constructor
+                .call(Some(object), &[], &mut activation, Some(proto))
+                .map_err(|e| Avm2::uncaught_error(&mut activation, e))?;
+
+            Ok(object)
+        };
+        let result: Result<Avm2Object<'gc>, Avm2Error> = constr_thing();
+
+        match result {
+            Ok(object) => self.0.write(context.gc_context).object = Some(object.into()),
+            Err(e) => log::error!("Got {} when constructing AVM2 side of text field", e),
+        }
+    }
+
     fn initialize_as_broadcaster(&self, activation: &mut Activation<'_, 'gc, '_>) {
         let write = self.0.write(activation.context.gc_context);
-        if let Some(object) = write.object {
+        if let Some(object) = write.object.and_then(|o| o.as_avm1_object().ok()) {
             activation.context.avm1.broadcaster_functions().initialize(
                 activation.context.gc_context,
                 object,
@@ -1219,8 +1897,23 @@ impl<'gc> EditText<'gc> {
         }
     }
 
+    /// Queue an `onScroller` event, after the scroll position has changed.
+    fn on_scroller(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        if let Some(object) = self.0.read().object.and_then(|o| o.as_avm1_object().ok()) {
+            context.action_queue.queue_actions(
+                self.into(),
+                ActionType::Method {
+                    object,
+                    name: "broadcastMessage",
+                    args: vec!["onScroller".into(), object.into()],
+                },
+                false,
+            );
+        }
+    }
+
     fn on_changed(&self, activation: &mut Activation<'_, 'gc, '_>) {
-        let object = self.0.read().object;
+        let object = self.0.read().object.and_then(|o| o.as_avm1_object().ok());
         if let Some(object) = object {
             let _ = object.call_method(
                 "broadcastMessage",
@@ -1260,18 +1953,10 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
     ) {
         self.set_default_instance_name(context);
 
-        let mut text = self.0.write(context.gc_context);
-        if text.object.is_none() {
-            let object = StageObject::for_display_object(
-                context.gc_context,
-                display_object,
-                Some(context.avm1.prototypes().text_field),
-            )
-            .into();
-
-            text.object = Some(object);
-        }
+        let movie = self.movie().unwrap();
+        let vm_type = context.library.library_for_movie_mut(movie).avm_type();
 
+        let mut text = self.0.write(context.gc_context);
         text.document = text
             .document
             .as_node()
@@ -1282,8 +1967,32 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         for layout_box in text.layout.iter() {
             new_layout.push(layout_box.duplicate(context.gc_context));
         }
+        let needs_object = text.object.is_none();
         drop(text);
 
+        if vm_type == AvmType::Avm2 {
+            if needs_object {
+                self.construct_as_avm2_object(context, display_object);
+            }
+
+            if run_frame {
+                self.run_frame(context);
+            }
+
+            return;
+        }
+
+        if needs_object {
+            let object: Object<'gc> = StageObject::for_display_object(
+                context.gc_context,
+                display_object,
+                Some(context.avm1.prototypes().text_field),
+            )
+            .into();
+
+            self.0.write(context.gc_context).object = Some(object.into());
+        }
+
         Avm1::run_with_stack_frame_for_display_object(
             (*self).into(),
             context.swf.version(),
@@ -1309,10 +2018,20 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         self.0
             .read()
             .object
+            .and_then(|o| o.as_avm1_object().ok())
             .map(Value::from)
             .unwrap_or(Value::Undefined)
     }
 
+    fn object2(&self) -> Avm2Value<'gc> {
+        self.0
+            .read()
+            .object
+            .and_then(|o| o.as_avm2_object().ok())
+            .map(Avm2Value::from)
+            .unwrap_or(Avm2Value::Undefined)
+    }
+
     fn self_bounds(&self) -> BoundingBox {
         self.0.read().bounds.clone()
     }
@@ -1359,6 +2078,7 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         write.base.set_transformed_by_script(true);
 
         drop(write);
+        self.clamp_scroll(gc_context);
         self.redraw_border(gc_context);
     }
 
@@ -1373,6 +2093,7 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         write.base.set_transformed_by_script(true);
 
         drop(write);
+        self.clamp_scroll(gc_context);
         self.redraw_border(gc_context);
     }
 
@@ -1415,10 +2136,11 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
 
         // TODO: Where does this come from? How is this different than INTERNAL_PADDING? Does this apply to y as well?
         // If this is actually right, offset the border in `redraw_border` instead of doing an extra push.
+        let (scroll_x, scroll_y) = Self::scroll_offset(&edit_text);
         context.transform_stack.push(&Transform {
             matrix: Matrix {
-                tx: Twips::from_pixels(Self::INTERNAL_PADDING),
-                ty: Twips::from_pixels(Self::INTERNAL_PADDING),
+                tx: Twips::from_pixels(Self::INTERNAL_PADDING) - scroll_x,
+                ty: Twips::from_pixels(Self::INTERNAL_PADDING) - scroll_y,
                 ..Default::default()
             },
             ..Default::default()
@@ -1503,8 +2225,11 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         self_node: DisplayObject<'gc>,
         point: (Twips, Twips),
     ) -> Option<DisplayObject<'gc>> {
-        // The button is hovered if the mouse is over any child nodes.
-        if self.visible() && self.is_selectable() && self.hit_test_shape(context, point) {
+        // Links can be clicked even if the text can't be selected.
+        if self.visible()
+            && self.hit_test_shape(context, point)
+            && (self.is_selectable() || self.link_at_position(point).is_some())
+        {
             Some(self_node)
         } else {
             None
@@ -1512,7 +2237,11 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
     }
 
     fn mouse_cursor(&self) -> MouseCursor {
-        MouseCursor::IBeam
+        if self.0.read().hovered_link.is_some() {
+            MouseCursor::Hand
+        } else {
+            MouseCursor::IBeam
+        }
     }
 
     fn on_focus_changed(&self, context: MutationContext<'gc, '_>, focused: bool) {
@@ -1549,52 +2278,116 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
                 ClipEventResult::Handled
             }
             ClipEvent::KeyPress { key_code } => {
-                let mut edit_text = self.0.write(context.gc_context);
-                let selection = edit_text.selection;
-                if let Some(mut selection) = selection {
-                    let text = edit_text.text_spans.text();
-                    let length = text.len();
+                if let Some(mut selection) = self.selection() {
+                    let is_shift_down = context.ui.is_key_down(KeyCode::Shift);
+                    let is_ctrl_down = context.ui.is_key_down(KeyCode::Control);
                     match key_code {
-                        ButtonKeyCode::Left => {
-                            if (context.ui.is_key_down(KeyCode::Shift) || selection.is_caret())
-                                && selection.to > 0
-                            {
-                                selection.to = string_utils::prev_char_boundary(text, selection.to);
-                                if !context.ui.is_key_down(KeyCode::Shift) {
-                                    selection.from = selection.to;
-                                }
-                            } else if !context.ui.is_key_down(KeyCode::Shift) {
-                                selection.to = selection.start();
-                                selection.from = selection.to;
-                            }
+                        // Without shift, a selection collapses to the side being moved towards.
+                        ButtonKeyCode::Left if !is_shift_down && !selection.is_caret() => {
+                            selection = TextSelection::for_position(selection.start());
+                        }
+                        ButtonKeyCode::Right if !is_shift_down && !selection.is_caret() => {
+                            selection = TextSelection::for_position(selection.end());
                         }
-                        ButtonKeyCode::Right => {
-                            if (context.ui.is_key_down(KeyCode::Shift) || selection.is_caret())
-                                && selection.to < length
+                        _ => {
+                            if let Some(position) =
+                                self.navigate(selection.to, key_code, is_ctrl_down)
                             {
-                                selection.to = string_utils::next_char_boundary(text, selection.to);
-                                if !context.ui.is_key_down(KeyCode::Shift) {
-                                    selection.from = selection.to;
+                                selection.to = position;
+                                if !is_shift_down {
+                                    selection.from = position;
                                 }
-                            } else if !context.ui.is_key_down(KeyCode::Shift) {
-                                selection.to = selection.end();
-                                selection.from = selection.to;
                             }
                         }
-                        _ => {}
                     }
-                    selection.clamp(length);
-                    edit_text.selection = Some(selection);
+                    self.set_selection(Some(selection), context.gc_context);
+                    ClipEventResult::Handled
+                } else {
+                    ClipEventResult::NotHandled
+                }
+            }
+            ClipEvent::Release => {
+                if self.click_link(context) {
                     ClipEventResult::Handled
                 } else {
                     ClipEventResult::NotHandled
                 }
             }
+            ClipEvent::RollOut => {
+                self.set_hovered_link(None, context);
+                ClipEventResult::NotHandled
+            }
             _ => ClipEventResult::NotHandled,
         }
     }
 }
 
+/// Checks a character entered by the user against a `TextField.restrict`
+/// string, returning the character to enter instead, if any is allowed.
+///
+/// Characters are allowed if they are listed in `restrict`, either alone or
+/// as part of a range like `a-z`. Characters following a `^` are disallowed
+/// instead, and a leading `^` allows any character that isn't listed. A
+/// character that isn't allowed may still be entered in the other case.
+fn restrict_char(restrict: &str, character: char) -> Option<char> {
+    let is_allowed = |character: char| {
+        let mut is_allowed = restrict.starts_with('^');
+        let mut is_including = true;
+
+        let mut chars = restrict.chars().peekable();
+        while let Some(c) = chars.next() {
+            let first = match c {
+                '^' => {
+                    is_including = !is_including;
+                    continue;
+                }
+                '\\' => match chars.next() {
+                    Some(c) => c,
+                    None => break,
+                },
+                c => c,
+            };
+
+            let mut last = first;
+            if chars.peek() == Some(&'-') {
+                let mut range = chars.clone();
+                range.next();
+                if let Some(c) = range.next() {
+                    last = c;
+                    chars = range;
+                }
+            }
+
+            if (first..=last).contains(&character) {
+                is_allowed = is_including;
+            }
+        }
+
+        is_allowed
+    };
+
+    if is_allowed(character) {
+        return Some(character);
+    }
+
+    let other_cases = [
+        string_utils::swf_char_to_uppercase(character),
+        string_utils::swf_char_to_lowercase(character),
+    ];
+    other_cases
+        .iter()
+        .copied()
+        .find(|&c| c != character && is_allowed(c))
+}
+
+/// The state of an `EditText` before an edit made by the user.
+#[derive(Clone, Debug, Collect)]
+#[collect(require_static)]
+struct UndoState {
+    text_spans: FormatSpans,
+    selection: Option<TextSelection>,
+}
+
 /// Static data shared between all instances of a text object.
 #[derive(Debug, Clone, Collect)]
 #[collect(no_drop)]
@@ -1707,3 +2500,66 @@ impl TextSelection {
         self.to == self.from
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::restrict_char;
+
+    #[test]
+    fn restrict_single_characters() {
+        assert_eq!(restrict_char("abc", 'b'), Some('b'));
+        assert_eq!(restrict_char("abc", 'd'), None);
+        assert_eq!(restrict_char("", 'a'), None);
+    }
+
+    #[test]
+    fn restrict_ranges() {
+        assert_eq!(restrict_char("a-z0-9", 'm'), Some('m'));
+        assert_eq!(restrict_char("a-z0-9", '5'), Some('5'));
+        assert_eq!(restrict_char("a-z0-9", '!'), None);
+        assert_eq!(restrict_char("a-c", 'd'), None);
+    }
+
+    #[test]
+    fn restrict_other_case() {
+        assert_eq!(restrict_char("A-Z", 'q'), Some('Q'));
+        assert_eq!(restrict_char("a-z", 'Q'), Some('q'));
+        assert_eq!(restrict_char("a-zA-Z", 'Q'), Some('Q'));
+    }
+
+    #[test]
+    fn restrict_caret() {
+        // A leading `^` allows everything that isn't listed.
+        assert_eq!(restrict_char("^0-9", 'a'), Some('a'));
+        assert_eq!(restrict_char("^0-9", '5'), None);
+
+        // Characters after a `^` are excluded from those before it.
+        assert_eq!(restrict_char("0-9^5", '4'), Some('4'));
+        assert_eq!(restrict_char("0-9^5", '5'), None);
+        assert_eq!(restrict_char("a-z^aeiou", 'e'), None);
+        assert_eq!(restrict_char("a-z^aeiou", 'E'), None);
+
+        // A second `^` includes characters again.
+        assert_eq!(restrict_char("^a-zA-Z^q", 'q'), Some('q'));
+        assert_eq!(restrict_char("^a-zA-Z^q", 'r'), None);
+    }
+
+    #[test]
+    fn restrict_escapes() {
+        assert_eq!(restrict_char("a\\-z", '-'), Some('-'));
+        assert_eq!(restrict_char("a\\-z", 'm'), None);
+        assert_eq!(restrict_char("a\\-z", 'z'), Some('z'));
+        assert_eq!(restrict_char("\\^", '^'), Some('^'));
+        assert_eq!(restrict_char("\\^", 'a'), None);
+        assert_eq!(restrict_char("\\\\", '\\'), Some('\\'));
+        assert_eq!(restrict_char("\\\\", 'a'), None);
+    }
+
+    #[test]
+    fn restrict_trailing_hyphen() {
+        // A `-` that doesn't end a range is a literal.
+        assert_eq!(restrict_char("a-", '-'), Some('-'));
+        assert_eq!(restrict_char("a-", 'b'), None);
+        assert_eq!(restrict_char("-a", '-'), Some('-'));
+    }
+}
@@ -8,6 +8,7 @@ use crate::avm2::{
     StageObject as Avm2StageObject, TObject as Avm2TObject, Value as Avm2Value,
 };
 use crate::backend::audio::{PreloadStreamHandle, SoundHandle, SoundInstanceHandle};
+use crate::backend::navigator::RequestOptions;
 use bitflags::bitflags;
 
 use crate::avm1::activation::{Activation as Avm1Activation, ActivationIdentifier};
@@ -16,6 +17,7 @@ use crate::context::{ActionType, RenderContext, UpdateContext};
 use crate::display_object::container::{ChildContainer, TDisplayObjectContainer};
 use crate::display_object::{
     Bitmap, Button, DisplayObjectBase, EditText, Graphic, MorphShapeStatic, TDisplayObject, Text,
+    Video,
 };
 use crate::drawing::Drawing;
 use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult};
@@ -23,6 +25,7 @@ use crate::font::Font;
 use crate::prelude::*;
 use crate::shape_utils::DrawCommand;
 use crate::tag_utils::{self, DecodeResult, SwfMovie, SwfSlice, SwfStream};
+use crate::transform::Transform;
 use crate::types::{Degrees, Percent};
 use crate::vminterface::{AvmObject, AvmType, Instantiator};
 use gc_arena::{Collect, Gc, GcCell, MutationContext};
@@ -63,6 +66,9 @@ pub struct MovieClipData<'gc> {
     is_focusable: bool,
     has_focus: bool,
     enabled: bool,
+    /// The 9-slice scaling grid of this clip, in its local coordinates.
+    /// Invalid if the clip has no grid.
+    scaling_grid: BoundingBox,
 }
 
 unsafe impl<'gc> Collect for MovieClipData<'gc> {
@@ -99,6 +105,7 @@ impl<'gc> MovieClip<'gc> {
                 is_focusable: false,
                 has_focus: false,
                 enabled: true,
+                scaling_grid: BoundingBox::default(),
             },
         ))
     }
@@ -131,6 +138,7 @@ impl<'gc> MovieClip<'gc> {
                 is_focusable: false,
                 has_focus: false,
                 enabled: true,
+                scaling_grid: BoundingBox::default(),
             },
         ))
     }
@@ -302,12 +310,20 @@ impl<'gc> MovieClip<'gc> {
                 .0
                 .write(context.gc_context)
                 .define_text(context, reader, 2),
+            TagCode::DefineVideoStream => self
+                .0
+                .write(context.gc_context)
+                .define_video_stream(context, reader),
             TagCode::DoInitAction => self.do_init_action(context, reader, tag_len),
             TagCode::DoAbc => self.do_abc(context, reader, tag_len),
             TagCode::SymbolClass => self.symbol_class(context, reader),
             TagCode::DefineSceneAndFrameLabelData => {
                 self.scene_and_frame_labels(reader, &mut static_data)
             }
+            TagCode::DefineScalingGrid => self
+                .0
+                .write(context.gc_context)
+                .define_scaling_grid(context, reader),
             TagCode::ExportAssets => self
                 .0
                 .write(context.gc_context)
@@ -319,6 +335,12 @@ impl<'gc> MovieClip<'gc> {
                 cur_frame,
                 &mut static_data,
             ),
+            TagCode::ImportAssets => {
+                self.import_assets(context, reader, cur_frame, &static_data, 1)
+            }
+            TagCode::ImportAssets2 => {
+                self.import_assets(context, reader, cur_frame, &static_data, 2)
+            }
             TagCode::JpegTables => self
                 .0
                 .write(context.gc_context)
@@ -372,6 +394,10 @@ impl<'gc> MovieClip<'gc> {
                 .0
                 .write(context.gc_context)
                 .script_limits(reader, context.avm1),
+            TagCode::VideoFrame => self
+                .0
+                .write(context.gc_context)
+                .preload_video_frame(context, reader),
             TagCode::SoundStreamHead => self.0.write(context.gc_context).preload_sound_stream_head(
                 context,
                 reader,
@@ -500,6 +526,60 @@ impl<'gc> MovieClip<'gc> {
         Ok(())
     }
 
+    /// Starts loading the shared library referenced by an `ImportAssets` tag.
+    ///
+    /// The frame containing the tag is blocked until the library has loaded.
+    #[inline]
+    fn import_assets(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<'_>,
+        cur_frame: FrameNumber,
+        static_data: &MovieClipStatic,
+        version: u8,
+    ) -> DecodeResult {
+        let (url, imports) = if version == 1 {
+            reader.read_import_assets()?
+        } else {
+            reader.read_import_assets_2()?
+        };
+        let movie = self.movie().unwrap();
+        let url = url.to_str_lossy(reader.encoding());
+        let imports = imports
+            .into_iter()
+            .map(|import| {
+                let name = import.name.to_str_lossy(reader.encoding());
+                (import.id, name.to_string())
+            })
+            .collect();
+
+        let player = match context.player.clone() {
+            Some(player) => player,
+            None => {
+                log::warn!("Can't import assets from {} without a player", url);
+                return Ok(());
+            }
+        };
+
+        // Like `loadMovie`, the navigator resolves the library URL.
+        let url = url.to_string();
+        static_data.pending_imports.borrow_mut().push(cur_frame);
+
+        let fetch = context.navigator.fetch(&url, RequestOptions::get());
+        let process = context.load_manager.load_import_assets(
+            player,
+            self.into(),
+            movie,
+            cur_frame,
+            imports,
+            fetch,
+            url,
+        );
+        context.navigator.spawn_future(process);
+
+        Ok(())
+    }
+
     #[inline]
     fn symbol_class(
         self,
@@ -602,7 +682,6 @@ impl<'gc> MovieClip<'gc> {
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub fn playing(self) -> bool {
         self.0.read().playing()
     }
@@ -837,7 +916,29 @@ impl<'gc> MovieClip<'gc> {
 
     pub fn frames_loaded(self) -> FrameNumber {
         // TODO(Herschel): root needs to progressively stream in frames.
-        self.0.read().static_data.total_frames
+        let static_data = self.0.read().static_data;
+        let first_pending_import = static_data.pending_imports.borrow().iter().min().copied();
+        match first_pending_import {
+            Some(frame) => frame - 1,
+            None => static_data.total_frames,
+        }
+    }
+
+    /// Whether a shared library imported by this clip's movie is still loading.
+    pub fn has_pending_imports(self) -> bool {
+        let static_data = self.0.read().static_data;
+        let pending_imports = static_data.pending_imports.borrow();
+        !pending_imports.is_empty()
+    }
+
+    /// Marks the shared library imported on `frame` as loaded, allowing
+    /// playback to continue past that frame.
+    pub fn finish_import(self, frame: FrameNumber) {
+        let static_data = self.0.read().static_data;
+        let mut pending_imports = static_data.pending_imports.borrow_mut();
+        if let Some(i) = pending_imports.iter().position(|f| *f == frame) {
+            pending_imports.remove(i);
+        }
     }
 
     pub fn set_avm2_constructor(
@@ -998,6 +1099,10 @@ impl<'gc> MovieClip<'gc> {
     ) {
         // Advance frame number.
         if self.current_frame() < self.total_frames() {
+            if self.current_frame() >= self.frames_loaded() {
+                // Wait for the next frame to finish loading.
+                return;
+            }
             self.0.write(context.gc_context).current_frame += 1;
         } else if self.total_frames() > 1 {
             // Looping acts exactly like a gotoAndPlay(1).
@@ -1035,6 +1140,7 @@ impl<'gc> MovieClip<'gc> {
             TagCode::RemoveObject if run_display_actions => self.remove_object(context, reader, 1),
             TagCode::RemoveObject2 if run_display_actions => self.remove_object(context, reader, 2),
             TagCode::SetBackgroundColor => self.set_background_color(context, reader),
+            TagCode::SetTabIndex => self.set_tab_index(context, reader),
             TagCode::StartSound => self.start_sound_1(context, reader),
             TagCode::SoundStreamBlock => {
                 has_stream_block = true;
@@ -1095,7 +1201,7 @@ impl<'gc> MovieClip<'gc> {
                     }
                 }
                 // Run first frame.
-                child.apply_place_object(context.gc_context, self.movie(), place_object);
+                child.apply_place_object(context, self.movie(), place_object);
                 child.post_instantiation(context, child, None, Instantiator::Movie, false);
                 child.run_frame(context);
             }
@@ -1192,15 +1298,11 @@ impl<'gc> MovieClip<'gc> {
         let data = mc.static_data.swf.clone();
         let mut index = 0;
 
-        // Sanity; let's make sure we don't seek way too far.
-        // TODO: This should be self.frames_loaded() when we implement that.
-        let clamped_frame = if frame <= mc.total_frames() {
-            frame
-        } else {
-            mc.total_frames()
-        };
         drop(mc);
 
+        // Sanity; let's make sure we don't seek way too far.
+        let clamped_frame = frame.min(self.frames_loaded());
+
         let mut reader = data.read_from(frame_pos);
         while self.current_frame() < clamped_frame && !reader.get_ref().is_empty() {
             self.0.write(context.gc_context).current_frame += 1;
@@ -1258,11 +1360,7 @@ impl<'gc> MovieClip<'gc> {
                 // If it's a rewind, we removed any dead children above, so we always
                 // modify the previous child.
                 Some(prev_child) if params.id() == 0 || is_rewind => {
-                    prev_child.apply_place_object(
-                        context.gc_context,
-                        self.movie(),
-                        &params.place_object,
-                    );
+                    prev_child.apply_place_object(context, self.movie(), &params.place_object);
                 }
                 _ => {
                     if let Some(child) = clip.instantiate_child(
@@ -1465,7 +1563,9 @@ impl<'gc> MovieClip<'gc> {
             )
             .into();
 
-            constructor.call(Some(object), &[], &mut activation, Some(proto))?;
+            constructor
+                .call(Some(object), &[], &mut activation, Some(proto))
+                .map_err(|e| Avm2::uncaught_error(&mut activation, e))?;
 
             Ok(object)
         };
@@ -1570,6 +1670,72 @@ impl<'gc> MovieClip<'gc> {
     pub fn set_enabled(self, context: &mut UpdateContext<'_, 'gc, '_>, enabled: bool) {
         self.0.write(context.gc_context).enabled = enabled;
     }
+
+    /// The 9-slice scaling grid of this clip, set by `DefineScalingGrid` or `scale9Grid`.
+    /// Invalid if the clip has no grid.
+    pub fn scaling_grid(self) -> BoundingBox {
+        self.0.read().scaling_grid.clone()
+    }
+
+    pub fn set_scaling_grid(self, gc_context: MutationContext<'gc, '_>, scaling_grid: BoundingBox) {
+        self.0.write(gc_context).scaling_grid = scaling_grid;
+    }
+
+    /// The regions to split child shapes into, if this clip is scaled and has a scaling grid.
+    ///
+    /// Clips containing masks are scaled normally instead, as masked children
+    /// can't be split into regions.
+    fn scaling_grid_regions(self) -> Option<Vec<ScalingGridRegion>> {
+        let scaling_grid = self.scaling_grid();
+        if !scaling_grid.valid || self.iter_render_list().any(|child| child.clip_depth() > 0) {
+            return None;
+        }
+        scaling_grid_regions(&self.bounds(), &scaling_grid, &*self.matrix())
+    }
+
+    /// Renders the children of this clip with shapes split into the regions of its scaling grid.
+    fn render_scaling_grid(
+        self,
+        context: &mut RenderContext<'_, 'gc>,
+        regions: &[ScalingGridRegion],
+    ) {
+        for region in regions {
+            region.render(context, |context| self.0.read().drawing.render(context));
+        }
+
+        // Only shapes are split; other children, such as nested clips, are scaled normally.
+        for child in self.iter_render_list() {
+            if !child.visible() {
+                continue;
+            }
+            match child {
+                DisplayObject::Graphic(_) | DisplayObject::MorphShape(_) => {
+                    for region in regions {
+                        region.render(context, |context| child.render(context));
+                    }
+                }
+                _ => child.render(context),
+            }
+        }
+    }
+
+    /// Whether this movieclip operates in "button mode" because it has a mouse handler,
+    /// either via on(..) or via property mc.onRelease, etc.
+    pub fn is_button_mode(self, context: &mut UpdateContext<'_, 'gc, '_>) -> bool {
+        if self.0.read().has_button_clip_event {
+            true
+        } else {
+            let mut activation = Avm1Activation::from_stub(
+                context.reborrow(),
+                ActivationIdentifier::root("[Mouse Pick]"),
+            );
+            let object = self.object().coerce_to_object(&mut activation);
+
+            ClipEvent::BUTTON_EVENT_METHODS
+                .iter()
+                .any(|handler| object.has_property(&mut activation, handler))
+        }
+    }
 }
 
 impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
@@ -1619,6 +1785,14 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
     }
 
     fn render_self(&self, context: &mut RenderContext<'_, 'gc>) {
+        // Masks are drawn unsplit, as the regions are clipped by masks themselves.
+        if context.allow_mask {
+            if let Some(regions) = self.scaling_grid_regions() {
+                self.render_scaling_grid(context, &regions);
+                return;
+            }
+        }
+
         self.0.read().drawing.render(context);
         self.render_children(context);
     }
@@ -1637,15 +1811,36 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
         point: (Twips, Twips),
     ) -> bool {
         if self.world_bounds().contains(point) {
+            let local_matrix = self.global_to_local_matrix();
+            let local_point = local_matrix * point;
+
+            // With a scaling grid, test shapes at the point that is drawn under `point`.
+            let grid_point = self.scaling_grid_regions().and_then(|regions| {
+                regions
+                    .iter()
+                    .find_map(|region| region.source_point(local_point))
+            });
+            let (shape_point, local_shape_point) = match grid_point {
+                Some(grid_point) => (self.local_to_global(grid_point), grid_point),
+                None => (point, local_point),
+            };
+
             for child in self.iter_execution_list() {
+                let point = match child {
+                    DisplayObject::Graphic(_) | DisplayObject::MorphShape(_) => shape_point,
+                    _ => point,
+                };
                 if child.hit_test_shape(context, point) {
                     return true;
                 }
             }
 
-            let local_matrix = self.global_to_local_matrix();
-            let point = local_matrix * point;
-            if self.0.read().drawing.hit_test(point, &local_matrix) {
+            if self
+                .0
+                .read()
+                .drawing
+                .hit_test(local_shape_point, &local_matrix)
+            {
                 return true;
             }
         }
@@ -1661,25 +1856,7 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
     ) -> Option<DisplayObject<'gc>> {
         if self.visible() {
             if self.world_bounds().contains(point) {
-                // This movieclip operates in "button mode" if it has a mouse handler,
-                // either via on(..) or via property mc.onRelease, etc.
-                let is_button_mode = {
-                    if self.0.read().has_button_clip_event {
-                        true
-                    } else {
-                        let mut activation = Avm1Activation::from_stub(
-                            context.reborrow(),
-                            ActivationIdentifier::root("[Mouse Pick]"),
-                        );
-                        let object = self.object().coerce_to_object(&mut activation);
-
-                        ClipEvent::BUTTON_EVENT_METHODS
-                            .iter()
-                            .any(|handler| object.has_property(&mut activation, handler))
-                    }
-                };
-
-                if is_button_mode && self.hit_test_shape(context, point) {
+                if self.is_button_mode(context) && self.hit_test_shape(context, point) {
                     return Some(self_node);
                 }
             }
@@ -2606,6 +2783,44 @@ impl<'gc, 'a> MovieClipData<'gc> {
         Ok(())
     }
 
+    fn define_video_stream(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<'a>,
+    ) -> DecodeResult {
+        let streamdef = reader.read_define_video_stream()?;
+        let id = streamdef.id;
+        let video = Video::from_swf_tag(self.movie(), streamdef, context.gc_context);
+        context
+            .library
+            .library_for_movie_mut(self.movie())
+            .register_character(id, Character::Video(video));
+        Ok(())
+    }
+
+    fn preload_video_frame(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<'a>,
+    ) -> DecodeResult {
+        let vframe = reader.read_video_frame()?;
+        match context
+            .library
+            .library_for_movie_mut(self.movie())
+            .character_by_id(vframe.stream_id)
+        {
+            Some(Character::Video(video)) => {
+                video.preload_swf_frame(vframe, context.gc_context);
+                Ok(())
+            }
+            _ => Err(format!(
+                "Attempted to preload video frames into non-video character {}",
+                vframe.stream_id
+            )
+            .into()),
+        }
+    }
+
     fn define_sprite(
         &mut self,
         context: &mut UpdateContext<'_, 'gc, '_>,
@@ -2666,6 +2881,23 @@ impl<'gc, 'a> MovieClipData<'gc> {
         Ok(())
     }
 
+    #[inline]
+    fn define_scaling_grid(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<'a>,
+    ) -> DecodeResult {
+        let id = reader.read_u16()?;
+        let splitter_rect = reader.read_rectangle()?;
+        let library = context.library.library_for_movie_mut(self.movie());
+        if let Some(Character::MovieClip(movie_clip)) = library.character_by_id(id) {
+            movie_clip.set_scaling_grid(context.gc_context, splitter_rect.into());
+        } else {
+            log::warn!("DefineScalingGrid: Character {} is not a sprite", id);
+        }
+        Ok(())
+    }
+
     #[inline]
     fn export_assets(
         &mut self,
@@ -2830,7 +3062,7 @@ impl<'gc, 'a> MovieClip<'gc> {
             }
             PlaceObjectAction::Modify => {
                 if let Some(child) = self.child_by_depth(place_object.depth.into()) {
-                    child.apply_place_object(context.gc_context, self.movie(), &place_object);
+                    child.apply_place_object(context, self.movie(), &place_object);
                     child
                 } else {
                     return Ok(());
@@ -2882,6 +3114,27 @@ impl<'gc, 'a> MovieClip<'gc> {
         Ok(())
     }
 
+    #[inline]
+    fn set_tab_index(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<'a>,
+    ) -> DecodeResult {
+        let depth = reader.read_u16()?;
+        let tab_index = reader.read_u16()?;
+        if let Some(child) = self.child_by_depth(depth.into()) {
+            // The tag acts like setting the `tabIndex` property of the child.
+            if let Avm1Value::Object(object) = child.object() {
+                let mut activation = Avm1Activation::from_stub(
+                    context.reborrow(),
+                    ActivationIdentifier::root("[SetTabIndex]"),
+                );
+                let _ = object.set("tabIndex", tab_index.into(), &mut activation);
+            }
+        }
+        Ok(())
+    }
+
     #[inline]
     fn sound_stream_block(
         self,
@@ -2994,6 +3247,9 @@ struct MovieClipStatic {
     /// The last known symbol name under which this movie clip was exported.
     /// Used for looking up constructors registered with `Object.registerClass`.
     exported_name: RefCell<Option<String>>,
+    /// The frames containing `ImportAssets` tags whose shared library is still loading.
+    /// Playback can't reach these frames until the library has loaded.
+    pending_imports: RefCell<Vec<FrameNumber>>,
 }
 
 impl MovieClipStatic {
@@ -3011,6 +3267,7 @@ impl MovieClipStatic {
             audio_stream_info: None,
             audio_stream_handle: None,
             exported_name: RefCell::new(None),
+            pending_imports: RefCell::new(Vec::new()),
         }
     }
 }
@@ -3220,3 +3477,292 @@ pub struct Avm2FrameScript<'gc> {
     /// The AVM2 callable object to invoke when the frame script runs.
     pub callable: Avm2Object<'gc>,
 }
+
+/// One of the nine regions of a clip with a scaling grid.
+struct ScalingGridRegion {
+    /// The part of the clip covered by this region, in its local coordinates.
+    bounds: BoundingBox,
+
+    /// Moves and scales this region so that the corners of the grid stay unscaled.
+    matrix: Matrix,
+}
+
+impl ScalingGridRegion {
+    /// Renders the part of the content drawn by `render` that lies within this region.
+    fn render<'gc>(
+        &self,
+        context: &mut RenderContext<'_, 'gc>,
+        render: impl FnOnce(&mut RenderContext<'_, 'gc>),
+    ) {
+        context.transform_stack.push(&Transform {
+            matrix: self.matrix,
+            ..Default::default()
+        });
+
+        let mask = context.transform_stack.transform().matrix
+            * Matrix::create_box(
+                self.bounds.width().to_pixels() as f32,
+                self.bounds.height().to_pixels() as f32,
+                0.0,
+                self.bounds.x_min,
+                self.bounds.y_min,
+            );
+        let color = Color::from_rgb(0, 255);
+        context.renderer.push_mask();
+        context.renderer.draw_rect(color.clone(), &mask);
+        context.renderer.activate_mask();
+        render(context);
+        context.renderer.deactivate_mask();
+        context.renderer.draw_rect(color, &mask);
+        context.renderer.pop_mask();
+
+        context.transform_stack.pop();
+    }
+
+    /// Maps a point where this region is drawn back to the point of the content
+    /// it shows, or returns `None` if the point is outside this region.
+    fn source_point(&self, point: (Twips, Twips)) -> Option<(Twips, Twips)> {
+        if self.matrix.a == 0.0 || self.matrix.d == 0.0 {
+            return None;
+        }
+        if !self.bounds.transform(&self.matrix).contains(point) {
+            return None;
+        }
+        let x = f64::from((point.0 - self.matrix.tx).get()) / f64::from(self.matrix.a);
+        let y = f64::from((point.1 - self.matrix.ty).get()) / f64::from(self.matrix.d);
+        Some((Twips::new(x.round() as i32), Twips::new(y.round() as i32)))
+    }
+}
+
+/// Splits `bounds` into the nine regions of `scaling_grid`, such that the corner regions
+/// stay unscaled when the clip is drawn with `matrix`.
+///
+/// Returns `None` if the clip is not scaled, or if the grid doesn't lie within `bounds`.
+fn scaling_grid_regions(
+    bounds: &BoundingBox,
+    scaling_grid: &BoundingBox,
+    matrix: &Matrix,
+) -> Option<Vec<ScalingGridRegion>> {
+    if !bounds.valid
+        || scaling_grid.x_min < bounds.x_min
+        || scaling_grid.y_min < bounds.y_min
+        || scaling_grid.x_max > bounds.x_max
+        || scaling_grid.y_max > bounds.y_max
+        || scaling_grid.x_min > scaling_grid.x_max
+        || scaling_grid.y_min > scaling_grid.y_max
+    {
+        return None;
+    }
+
+    let scale_x = f64::from(matrix.a).hypot(f64::from(matrix.b));
+    let scale_y = f64::from(matrix.c).hypot(f64::from(matrix.d));
+    if (scale_x == 1.0 && scale_y == 1.0) || scale_x == 0.0 || scale_y == 0.0 {
+        return None;
+    }
+
+    let columns = scaling_grid_slices(
+        bounds.x_min,
+        scaling_grid.x_min,
+        scaling_grid.x_max,
+        bounds.x_max,
+        scale_x,
+    );
+    let rows = scaling_grid_slices(
+        bounds.y_min,
+        scaling_grid.y_min,
+        scaling_grid.y_max,
+        bounds.y_max,
+        scale_y,
+    );
+    let mut regions = Vec::with_capacity(9);
+    for &(y_min, y_max, d, ty) in &rows {
+        for &(x_min, x_max, a, tx) in &columns {
+            regions.push(ScalingGridRegion {
+                bounds: BoundingBox {
+                    x_min,
+                    y_min,
+                    x_max,
+                    y_max,
+                    valid: true,
+                },
+                matrix: Matrix {
+                    a: a as f32,
+                    b: 0.0,
+                    c: 0.0,
+                    d: d as f32,
+                    tx: Twips::new(tx.round() as i32),
+                    ty: Twips::new(ty.round() as i32),
+                },
+            });
+        }
+    }
+    Some(regions)
+}
+
+/// Splits the range from `min` to `max` at the grid lines `grid_min` and `grid_max`.
+///
+/// Returns the start and end of each slice, along with the scale and offset that keep
+/// the outer slices at their original size when drawn with `scale`. The center slice
+/// stretches to fill the remaining space.
+fn scaling_grid_slices(
+    min: Twips,
+    grid_min: Twips,
+    grid_max: Twips,
+    max: Twips,
+    scale: f64,
+) -> [(Twips, Twips, f64, f64); 3] {
+    let start = f64::from(min.get());
+    let first = f64::from((grid_min - min).get());
+    let center = f64::from((grid_max - grid_min).get());
+    let last = f64::from((max - grid_max).get());
+    let total = first + center + last;
+
+    // The outer slices shrink only if the clip is too small to fit them.
+    let outer_scale = if first + last > 0.0 {
+        (1.0 / scale).min(total / (first + last))
+    } else {
+        1.0 / scale
+    };
+    let center_scale = if center > 0.0 {
+        (total - (first + last) * outer_scale) / center
+    } else {
+        0.0
+    };
+
+    let center_start = start + first * outer_scale;
+    let last_start = center_start + center * center_scale;
+    [
+        (min, grid_min, outer_scale, start - start * outer_scale),
+        (
+            grid_min,
+            grid_max,
+            center_scale,
+            center_start - f64::from(grid_min.get()) * center_scale,
+        ),
+        (
+            grid_max,
+            max,
+            outer_scale,
+            last_start - f64::from(grid_max.get()) * outer_scale,
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{scaling_grid_regions, scaling_grid_slices};
+    use crate::prelude::*;
+
+    /// A 100x100 pixel box with a 10 pixel border as its scaling grid.
+    fn grid_box() -> (BoundingBox, BoundingBox) {
+        let bounds = BoundingBox {
+            x_min: Twips::from_pixels(0.0),
+            y_min: Twips::from_pixels(0.0),
+            x_max: Twips::from_pixels(100.0),
+            y_max: Twips::from_pixels(100.0),
+            valid: true,
+        };
+        let scaling_grid = BoundingBox {
+            x_min: Twips::from_pixels(10.0),
+            y_min: Twips::from_pixels(10.0),
+            x_max: Twips::from_pixels(90.0),
+            y_max: Twips::from_pixels(90.0),
+            valid: true,
+        };
+        (bounds, scaling_grid)
+    }
+
+    fn slices(scale: f64) -> [(Twips, Twips, f64, f64); 3] {
+        scaling_grid_slices(
+            Twips::from_pixels(0.0),
+            Twips::from_pixels(10.0),
+            Twips::from_pixels(90.0),
+            Twips::from_pixels(100.0),
+            scale,
+        )
+    }
+
+    #[test]
+    fn slices_upscale() {
+        // The corners stay 10 pixels wide, and the center fills the remaining 180 pixels.
+        assert_eq!(
+            slices(2.0),
+            [
+                (Twips::new(0), Twips::new(200), 0.5, 0.0),
+                (Twips::new(200), Twips::new(1800), 1.125, -125.0),
+                (Twips::new(1800), Twips::new(2000), 0.5, 1000.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn slices_downscale() {
+        // The corners stay 10 pixels wide, and the center shrinks to 30 pixels.
+        assert_eq!(
+            slices(0.5),
+            [
+                (Twips::new(0), Twips::new(200), 2.0, 0.0),
+                (Twips::new(200), Twips::new(1800), 0.75, 250.0),
+                (Twips::new(1800), Twips::new(2000), 2.0, -2000.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn slices_downscale_below_corners() {
+        // At 10 pixels wide, the clip is too small for both corners, which shrink
+        // to 5 pixels each. The center disappears.
+        assert_eq!(
+            slices(0.1),
+            [
+                (Twips::new(0), Twips::new(200), 5.0, 0.0),
+                (Twips::new(200), Twips::new(1800), 0.0, 1000.0),
+                (Twips::new(1800), Twips::new(2000), 5.0, -8000.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn regions_unscaled() {
+        let (bounds, scaling_grid) = grid_box();
+        assert!(scaling_grid_regions(&bounds, &scaling_grid, &Matrix::identity()).is_none());
+    }
+
+    #[test]
+    fn source_point() {
+        let (bounds, scaling_grid) = grid_box();
+        let regions = scaling_grid_regions(&bounds, &scaling_grid, &Matrix::scale(2.0, 2.0))
+            .expect("Scaled clip should be split");
+        assert_eq!(regions.len(), 9);
+        let source_point = |x: i32, y: i32| {
+            regions
+                .iter()
+                .find_map(|region| region.source_point((Twips::new(x), Twips::new(y))))
+        };
+
+        // The top left corner is drawn at half size, before scaling the clip by 2.
+        assert_eq!(
+            source_point(50, 50),
+            Some((Twips::new(100), Twips::new(100)))
+        );
+
+        // The center is stretched to fill the space between the corners.
+        assert_eq!(
+            source_point(500, 1000),
+            Some((Twips::new(556), Twips::new(1000)))
+        );
+
+        // The bottom right corner is moved to the bottom right of the clip.
+        assert_eq!(
+            source_point(1950, 1950),
+            Some((Twips::new(1900), Twips::new(1900)))
+        );
+
+        // Points outside the clip don't map to anything.
+        assert_eq!(source_point(2100, 1000), None);
+        assert_eq!(
+            regions[0].source_point((Twips::new(1950), Twips::new(1950))),
+            None
+        );
+    }
+}
@@ -0,0 +1,345 @@
+//! Video player display object
+
+use crate::avm1::Object as Avm1Object;
+use crate::backend::render::BitmapInfo;
+use crate::backend::video::{EncodedFrame, VideoStreamHandle};
+use crate::context::{RenderContext, UpdateContext};
+use crate::display_object::{DisplayObjectBase, TDisplayObject};
+use crate::prelude::*;
+use crate::tag_utils::{SwfMovie, SwfSlice};
+use crate::transform::Transform;
+use crate::types::{Degrees, Percent};
+use crate::vminterface::Instantiator;
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use swf::{DefineVideoStream, VideoFrame};
+
+/// A Video display object is a high-level interface to a video player.
+///
+/// Video data may be embedded within a variety of container formats, including
+/// a host SWF, or an external video file. In the former case, video frames are
+/// synced to the timeline of the parent movie clip using the `ratio` field of
+/// `PlaceObject` tags.
+#[derive(Clone, Debug, Collect, Copy)]
+#[collect(no_drop)]
+pub struct Video<'gc>(GcCell<'gc, VideoData<'gc>>);
+
+#[derive(Clone, Debug)]
+pub struct VideoData<'gc> {
+    base: DisplayObjectBase<'gc>,
+
+    /// The source of the video data (e.g. an external file, a SWF bitstream)
+    source: GcCell<'gc, VideoSource>,
+
+    /// The decoder stream that this video source is associated to.
+    stream: VideoStream,
+
+    /// The list of frames which can be independently seeked to.
+    keyframes: BTreeSet<u32>,
+
+    /// The last decoded frame in the video stream, and the bitmap it was uploaded to.
+    decoded_frame: Option<(u32, BitmapInfo)>,
+}
+
+/// An optionally-instantiated video stream.
+#[derive(Clone, Debug)]
+pub enum VideoStream {
+    /// An uninstantiated video stream, with the frame it should seek to once instantiated.
+    Uninstantiated(u32),
+
+    /// An instantiated video stream.
+    Instantiated(VideoStreamHandle),
+}
+
+/// A source of video data.
+#[derive(Clone, Debug, Collect)]
+#[collect(require_static)]
+pub enum VideoSource {
+    /// A video bitstream embedded inside of a SWF movie.
+    Swf {
+        /// The movie that defined this video stream.
+        movie: Arc<SwfMovie>,
+
+        /// The video stream definition.
+        streamdef: DefineVideoStream,
+
+        /// The locations of each embedded sub-tag for each frame of the video.
+        frames: BTreeMap<u32, SwfSlice>,
+    },
+}
+
+impl<'gc> Video<'gc> {
+    /// Construct a Video object that is tied to a SWF file's video stream.
+    pub fn from_swf_tag(
+        movie: Arc<SwfMovie>,
+        streamdef: DefineVideoStream,
+        mc: MutationContext<'gc, '_>,
+    ) -> Self {
+        let source = GcCell::allocate(
+            mc,
+            VideoSource::Swf {
+                movie,
+                streamdef,
+                frames: BTreeMap::new(),
+            },
+        );
+
+        Video(GcCell::allocate(
+            mc,
+            VideoData {
+                base: Default::default(),
+                source,
+                stream: VideoStream::Uninstantiated(0),
+                keyframes: BTreeSet::new(),
+                decoded_frame: None,
+            },
+        ))
+    }
+
+    /// Preload a frame from the SWF file containing this video stream.
+    ///
+    /// The frame is only recorded here; it will be inspected by the video
+    /// backend once the video is instantiated.
+    pub fn preload_swf_frame(&self, tag: VideoFrame, gc_context: MutationContext<'gc, '_>) {
+        let read = self.0.read();
+        let mut source = read.source.write(gc_context);
+        match &mut *source {
+            VideoSource::Swf { movie, frames, .. } => {
+                let subslice = SwfSlice::from(movie.clone()).to_subslice(tag.data);
+
+                match subslice {
+                    Some(slice) => {
+                        frames.insert(tag.frame_num.into(), slice);
+                    }
+                    None => {
+                        log::warn!("Invalid bitstream subslice on frame {}", tag.frame_num)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Seek to a particular frame in the video stream.
+    ///
+    /// If the stream has not been instantiated yet, the seek is deferred until it is.
+    pub fn seek(self, context: &mut UpdateContext<'_, 'gc, '_>, frame_id: u32) {
+        let read = self.0.read();
+        let stream = match read.stream {
+            VideoStream::Uninstantiated(_) => {
+                drop(read);
+                self.0.write(context.gc_context).stream = VideoStream::Uninstantiated(frame_id);
+                return;
+            }
+            VideoStream::Instantiated(stream) => stream,
+        };
+
+        let last_frame = read.decoded_frame.map(|(frame_id, _)| frame_id);
+        if last_frame == Some(frame_id) {
+            return;
+        }
+
+        // Frames that depend on earlier frames can only be decoded in order.
+        // Continue from the last decoded frame if no keyframe lies in between,
+        // otherwise start from the closest keyframe at or before the target.
+        let closest_keyframe = read.keyframes.range(..=frame_id).next_back().copied();
+        let start = match (last_frame, closest_keyframe) {
+            (Some(last_frame), Some(keyframe))
+                if last_frame < frame_id && last_frame >= keyframe =>
+            {
+                last_frame + 1
+            }
+            (Some(last_frame), None) if last_frame < frame_id => last_frame + 1,
+            (_, Some(keyframe)) => keyframe,
+            (_, None) => 0,
+        };
+
+        let source = read.source;
+        drop(read);
+
+        let mut decoded_frame = None;
+        for frame_id in start..=frame_id {
+            match &*source.read() {
+                VideoSource::Swf {
+                    streamdef, frames, ..
+                } => {
+                    let frame = match frames.get(&frame_id) {
+                        Some(frame) => frame,
+                        None => continue,
+                    };
+                    let encoded_frame = EncodedFrame {
+                        codec: streamdef.codec,
+                        data: frame.as_ref(),
+                        frame_id,
+                    };
+                    match context.video.decode_video_stream_frame(
+                        stream,
+                        encoded_frame,
+                        context.renderer,
+                    ) {
+                        Ok(bitmap) => decoded_frame = Some((frame_id, bitmap)),
+                        Err(e) => log::error!("Got error when decoding video frame: {}", e),
+                    }
+                }
+            }
+        }
+
+        if let Some(decoded_frame) = decoded_frame {
+            self.0.write(context.gc_context).decoded_frame = Some(decoded_frame);
+        }
+    }
+}
+
+impl<'gc> TDisplayObject<'gc> for Video<'gc> {
+    impl_display_object!(base);
+
+    fn id(&self) -> CharacterId {
+        match &*self.0.read().source.read() {
+            VideoSource::Swf { streamdef, .. } => streamdef.id,
+        }
+    }
+
+    fn as_video(&self) -> Option<Video<'gc>> {
+        Some(*self)
+    }
+
+    fn movie(&self) -> Option<Arc<SwfMovie>> {
+        match &*self.0.read().source.read() {
+            VideoSource::Swf { movie, .. } => Some(movie.clone()),
+        }
+    }
+
+    fn post_instantiation(
+        &self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        _display_object: DisplayObject<'gc>,
+        _init_object: Option<Avm1Object<'gc>>,
+        _instantiated_by: Instantiator,
+        _run_frame: bool,
+    ) {
+        let seek_to = match self.0.read().stream {
+            VideoStream::Uninstantiated(seek_to) => seek_to,
+            VideoStream::Instantiated(_) => return,
+        };
+
+        let source = self.0.read().source;
+        let (stream, keyframes) = match &*source.read() {
+            VideoSource::Swf {
+                streamdef, frames, ..
+            } => {
+                let stream = match context.video.register_video_stream(
+                    streamdef.num_frames.into(),
+                    (streamdef.width, streamdef.height),
+                    streamdef.codec,
+                    streamdef.deblocking,
+                ) {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        log::error!("Got error when registering video stream: {}", e);
+                        return;
+                    }
+                };
+
+                let mut keyframes = BTreeSet::new();
+                for (&frame_id, frame) in frames.iter() {
+                    let encoded_frame = EncodedFrame {
+                        codec: streamdef.codec,
+                        data: frame.as_ref(),
+                        frame_id,
+                    };
+                    match context
+                        .video
+                        .preload_video_stream_frame(stream, encoded_frame)
+                    {
+                        Ok(dependency) if dependency.is_keyframe() => {
+                            keyframes.insert(frame_id);
+                        }
+                        Ok(_) => {}
+                        Err(e) => log::warn!("Got error when preloading video frame: {}", e),
+                    }
+                }
+
+                (stream, keyframes)
+            }
+        };
+
+        let mut write = self.0.write(context.gc_context);
+        write.stream = VideoStream::Instantiated(stream);
+        write.keyframes = keyframes;
+        drop(write);
+
+        self.seek(context, seek_to);
+    }
+
+    fn self_bounds(&self) -> BoundingBox {
+        let (width, height) = match &*self.0.read().source.read() {
+            VideoSource::Swf { streamdef, .. } => (streamdef.width, streamdef.height),
+        };
+
+        BoundingBox {
+            x_min: Twips::new(0),
+            y_min: Twips::new(0),
+            x_max: Twips::from_pixels(width.into()),
+            y_max: Twips::from_pixels(height.into()),
+            valid: true,
+        }
+    }
+
+    fn run_frame(&self, _context: &mut UpdateContext) {
+        // Noop; frames are decoded as the parent clip seeks the video.
+    }
+
+    fn unload(&self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        // Each placement registers its own stream, so free it once the video is removed.
+        let mut write = self.0.write(context.gc_context);
+        if let VideoStream::Instantiated(stream) = write.stream {
+            context.video.unregister_video_stream(stream);
+            write.stream = VideoStream::Uninstantiated(0);
+            write.decoded_frame = None;
+        }
+        drop(write);
+
+        self.set_removed(context.gc_context, true);
+    }
+
+    fn render_self(&self, context: &mut RenderContext) {
+        if !self.world_bounds().intersects(&context.view_bounds) {
+            // Off-screen; culled
+            return;
+        }
+
+        let read = self.0.read();
+        let (_, bitmap) = match read.decoded_frame {
+            Some(decoded_frame) => decoded_frame,
+            None => return,
+        };
+
+        // Frames are stretched to the size of the video object.
+        let (width, height, smoothing) = match &*read.source.read() {
+            VideoSource::Swf { streamdef, .. } => {
+                (streamdef.width, streamdef.height, streamdef.is_smoothed)
+            }
+        };
+        context.transform_stack.push(&Transform {
+            matrix: Matrix::scale(
+                f32::from(width) / f32::from(bitmap.width),
+                f32::from(height) / f32::from(bitmap.height),
+            ),
+            color_transform: Default::default(),
+        });
+        context.renderer.render_bitmap(
+            bitmap.handle,
+            context.transform_stack.transform(),
+            smoothing,
+        );
+        context.transform_stack.pop();
+    }
+}
+
+unsafe impl<'gc> gc_arena::Collect for VideoData<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        self.base.trace(cc);
+        self.source.trace(cc);
+    }
+}
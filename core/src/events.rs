@@ -5,11 +5,23 @@ pub enum PlayerEvent {
     KeyDown { key_code: KeyCode },
     KeyUp { key_code: KeyCode },
     MouseMove { x: f64, y: f64 },
-    MouseUp { x: f64, y: f64 },
-    MouseDown { x: f64, y: f64 },
+    MouseUp { x: f64, y: f64, button: MouseButton },
+    MouseDown { x: f64, y: f64, button: MouseButton },
     MouseLeft,
     MouseWheel { delta: MouseWheelDelta },
     TextInput { codepoint: char },
+    TextPaste { text: String },
+}
+
+/// The button pressed or released in a mouse event.
+///
+/// Only the left button is visible to ActionScript; the right button opens
+/// the context menu.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
 }
 
 /// The distance scrolled by the mouse wheel.
@@ -142,6 +154,7 @@ impl ClipEvent {
 pub enum KeyCode {
     Unknown = 0,
     Backspace = 8,
+    Tab = 9,
     Return = 13,
     Shift = 16,
     Control = 17,
@@ -366,6 +379,7 @@ pub fn key_code_to_button_key_code(key_code: KeyCode) -> Option<ButtonKeyCode> {
         KeyCode::Insert => ButtonKeyCode::Insert,
         KeyCode::Delete => ButtonKeyCode::Delete,
         KeyCode::Backspace => ButtonKeyCode::Backspace,
+        KeyCode::Tab => ButtonKeyCode::Tab,
         KeyCode::Return => ButtonKeyCode::Return,
         KeyCode::Up => ButtonKeyCode::Up,
         KeyCode::Down => ButtonKeyCode::Down,
@@ -1,19 +1,45 @@
-use crate::avm1::{Avm1, Value};
-use crate::context::UpdateContext;
+use crate::avm1::activation::{Activation, ActivationIdentifier};
+use crate::avm1::{Avm1, TObject, Value};
+use crate::context::{RenderContext, UpdateContext};
 pub use crate::display_object::{DisplayObject, TDisplayObject};
+use crate::display_object::{TDisplayObjectContainer, TextSelection};
+use crate::events::ClipEvent;
 use gc_arena::{Collect, GcCell, MutationContext};
+use swf::{Color, Matrix, Twips};
+
+/// The width of the yellow rectangle drawn around the focused object, in pixels.
+const FOCUS_RECT_WIDTH: f64 = 2.0;
 
 #[collect(no_drop)]
 #[derive(Clone, Copy, Collect, Debug)]
-pub struct FocusTracker<'gc>(GcCell<'gc, Option<DisplayObject<'gc>>>);
+pub struct FocusTracker<'gc>(GcCell<'gc, FocusTrackerData<'gc>>);
+
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+struct FocusTrackerData<'gc> {
+    /// The object that currently has focus.
+    focus: Option<DisplayObject<'gc>>,
+
+    /// Whether the focus rectangle is drawn around the focused object.
+    ///
+    /// The rectangle only appears after focus was moved with the keyboard,
+    /// and disappears again once the mouse is used.
+    highlight: bool,
+}
 
 impl<'gc> FocusTracker<'gc> {
     pub fn new(gc_context: MutationContext<'gc, '_>) -> Self {
-        Self(GcCell::allocate(gc_context, None))
+        Self(GcCell::allocate(
+            gc_context,
+            FocusTrackerData {
+                focus: None,
+                highlight: false,
+            },
+        ))
     }
 
     pub fn get(&self) -> Option<DisplayObject<'gc>> {
-        *self.0.read()
+        self.0.read().focus
     }
 
     pub fn set(
@@ -21,7 +47,7 @@ impl<'gc> FocusTracker<'gc> {
         focused_element: Option<DisplayObject<'gc>>,
         context: &mut UpdateContext<'_, 'gc, '_>,
     ) {
-        let old = std::mem::replace(&mut *self.0.write(context.gc_context), focused_element);
+        let old = std::mem::replace(&mut self.0.write(context.gc_context).focus, focused_element);
 
         if old.is_none() && focused_element.is_none() {
             // We didn't have anything, we still don't, no change.
@@ -43,17 +69,209 @@ impl<'gc> FocusTracker<'gc> {
 
         log::info!("Focus is now on {:?}", focused_element);
 
-        let level0 = context.levels.get(&0).copied().unwrap();
-        Avm1::notify_system_listeners(
-            level0,
-            context.swf.version(),
-            context,
-            "Selection",
-            "onSetFocus",
-            &[
-                old.map(|v| v.object()).unwrap_or(Value::Null),
-                focused_element.map(|v| v.object()).unwrap_or(Value::Null),
-            ],
+        // Focus can change before `_level0` exists, e.g. while unloading;
+        // there is nobody to notify in that case.
+        if let Some(level0) = context.levels.get(&0).copied() {
+            Avm1::notify_system_listeners(
+                level0,
+                context.swf.version(),
+                context,
+                "Selection",
+                "onSetFocus",
+                &[
+                    old.map(|v| v.object()).unwrap_or(Value::Null),
+                    focused_element.map(|v| v.object()).unwrap_or(Value::Null),
+                ],
+            );
+        }
+    }
+
+    /// Hides the focus rectangle, e.g. after a mouse click.
+    pub fn reset_highlight(&self, gc_context: MutationContext<'gc, '_>) {
+        self.0.write(gc_context).highlight = false;
+    }
+
+    /// Moves focus to the next object in tab order, or to the previous one if
+    /// `reverse` is set, as when pressing Tab or Shift+Tab.
+    ///
+    /// Returns `false` if there was nothing to move focus to.
+    pub fn cycle(&self, context: &mut UpdateContext<'_, 'gc, '_>, reverse: bool) -> bool {
+        let tab_order = Self::tab_order(context);
+        if tab_order.is_empty() {
+            return false;
+        }
+
+        let current = self
+            .get()
+            .and_then(|focus| tab_order.iter().position(|o| o.as_ptr() == focus.as_ptr()));
+        let next = match (current, reverse) {
+            (Some(i), false) => (i + 1) % tab_order.len(),
+            (Some(i), true) => (i + tab_order.len() - 1) % tab_order.len(),
+            (None, false) => 0,
+            (None, true) => tab_order.len() - 1,
+        };
+        let next = tab_order[next];
+
+        // Buttons and clips display their "over" state while they have focus.
+        if let Some(old) = self.get() {
+            if old.as_edit_text().is_none() {
+                old.handle_clip_event(context, ClipEvent::RollOut);
+            }
+        }
+        self.set(Some(next), context);
+        self.0.write(context.gc_context).highlight = true;
+        if let Some(text) = next.as_edit_text() {
+            text.set_selection(
+                Some(TextSelection::for_range(0, text.text_length())),
+                context.gc_context,
+            );
+        } else {
+            next.handle_clip_event(context, ClipEvent::RollOver);
+        }
+        true
+    }
+
+    /// Builds the list of objects that Tab cycles through.
+    ///
+    /// If any object has an explicit `tabIndex`, only those objects are
+    /// included, sorted by index. Otherwise, objects are ordered by their
+    /// position on the stage, from top to bottom and left to right.
+    fn tab_order(context: &mut UpdateContext<'_, 'gc, '_>) -> Vec<DisplayObject<'gc>> {
+        let levels: Vec<DisplayObject<'gc>> = context.levels.values().copied().collect();
+        let mut activation = Activation::from_stub(
+            context.reborrow(),
+            ActivationIdentifier::root("[Tab Order]"),
         );
+        let mut candidates = vec![];
+        for level in levels {
+            Self::collect_tab_candidates(&mut activation, level, &mut candidates);
+        }
+
+        if candidates.iter().any(|(_, tab_index)| tab_index.is_some()) {
+            let mut tab_order: Vec<(DisplayObject<'gc>, f64)> = candidates
+                .into_iter()
+                .filter_map(|(object, tab_index)| Some((object, tab_index?)))
+                .collect();
+            tab_order
+                .sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+            tab_order.into_iter().map(|(object, _)| object).collect()
+        } else {
+            let mut tab_order: Vec<DisplayObject<'gc>> =
+                candidates.into_iter().map(|(object, _)| object).collect();
+            tab_order.sort_by_key(|object| {
+                let bounds = object.world_bounds();
+                (bounds.y_min, bounds.x_min)
+            });
+            tab_order
+        }
+    }
+
+    /// Appends the focusable objects in the display tree rooted at `object` to
+    /// `candidates`, along with their `tabIndex`, if any.
+    fn collect_tab_candidates(
+        activation: &mut Activation<'_, 'gc, '_>,
+        object: DisplayObject<'gc>,
+        candidates: &mut Vec<(DisplayObject<'gc>, Option<f64>)>,
+    ) {
+        if !object.visible() {
+            return;
+        }
+
+        if let Value::Object(script_object) = object.object() {
+            let tab_enabled = script_object
+                .get("tabEnabled", activation)
+                .unwrap_or(Value::Undefined);
+            let tab_enabled = match tab_enabled {
+                Value::Undefined => Self::is_tabbable_by_default(activation, object),
+                tab_enabled => tab_enabled.as_bool(activation.current_swf_version()),
+            };
+            if tab_enabled {
+                let tab_index = match script_object.get("tabIndex", activation) {
+                    Ok(Value::Number(tab_index)) if tab_index >= 0.0 => Some(tab_index),
+                    _ => None,
+                };
+                candidates.push((object, tab_index));
+            }
+        }
+
+        if let Some(clip) = object.as_movie_clip() {
+            if let Value::Object(script_object) = object.object() {
+                let tab_children = script_object.get("tabChildren", activation);
+                if let Ok(Value::Bool(false)) = tab_children {
+                    return;
+                }
+            }
+            let children: Vec<DisplayObject<'gc>> = clip.iter_render_list().collect();
+            for child in children {
+                Self::collect_tab_candidates(activation, child, candidates);
+            }
+        }
+    }
+
+    /// Whether `object` is included in the tab order when `tabEnabled` is not set.
+    fn is_tabbable_by_default(
+        activation: &mut Activation<'_, 'gc, '_>,
+        object: DisplayObject<'gc>,
+    ) -> bool {
+        if let Some(button) = object.as_button() {
+            button.enabled()
+        } else if let Some(text) = object.as_edit_text() {
+            text.is_editable()
+        } else if let Some(clip) = object.as_movie_clip() {
+            clip.enabled() && clip.is_button_mode(&mut activation.context)
+        } else {
+            false
+        }
+    }
+
+    /// Draws the yellow focus rectangle around the focused object.
+    ///
+    /// The rectangle is only drawn after focus was moved with the keyboard,
+    /// and can be disabled with the `_focusrect` property.
+    pub fn render_highlight(&self, context: &mut RenderContext<'_, 'gc>) {
+        let data = self.0.read();
+        let focus = match data.focus {
+            Some(focus) if data.highlight && focus.as_edit_text().is_none() => focus,
+            _ => return,
+        };
+
+        let focus_rect = focus
+            .focus_rect()
+            .or_else(|| focus.root().and_then(|root| root.focus_rect()))
+            .unwrap_or(true);
+        if !focus_rect || !focus.visible() || focus.removed() {
+            return;
+        }
+
+        let bounds = focus.world_bounds();
+        if !bounds.valid {
+            return;
+        }
+
+        let width = (bounds.x_max - bounds.x_min).to_pixels();
+        let height = (bounds.y_max - bounds.y_min).to_pixels();
+        let line = Twips::from_pixels(FOCUS_RECT_WIDTH);
+        let lines = [
+            (
+                bounds.x_min - line,
+                bounds.y_min - line,
+                width + 2.0 * FOCUS_RECT_WIDTH,
+                FOCUS_RECT_WIDTH,
+            ),
+            (
+                bounds.x_min - line,
+                bounds.y_max,
+                width + 2.0 * FOCUS_RECT_WIDTH,
+                FOCUS_RECT_WIDTH,
+            ),
+            (bounds.x_min - line, bounds.y_min, FOCUS_RECT_WIDTH, height),
+            (bounds.x_max, bounds.y_min, FOCUS_RECT_WIDTH, height),
+        ];
+        let color = Color::from_rgb(0xffff00, 255);
+        for &(x, y, width, height) in &lines {
+            let matrix = context.transform_stack.transform().matrix
+                * Matrix::create_box(width as f32, height as f32, 0.0, x, y);
+            context.renderer.draw_rect(color.clone(), &matrix);
+        }
     }
 }
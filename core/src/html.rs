@@ -1,14 +1,16 @@
 //! HTML related utilities
 
+mod css;
 mod dimensions;
 mod iterators;
 mod layout;
 mod text_format;
 
+pub use css::{Style, StyleSheet};
 pub use dimensions::BoxBounds;
 pub use dimensions::Position;
 pub use dimensions::Size;
-pub use layout::{LayoutBox, LayoutContent};
+pub use layout::{LayoutBox, LayoutContent, LayoutLine};
 pub use text_format::{FormatSpans, TextFormat, TextSpan};
 
 #[cfg(test)]
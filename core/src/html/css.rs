@@ -0,0 +1,325 @@
+//! CSS style sheets for HTML text
+
+use crate::html::text_format::TextFormat;
+use gc_arena::Collect;
+use std::collections::BTreeMap;
+
+/// How an element styled by a style sheet is laid out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Display {
+    /// The element starts a new line after its content.
+    Block,
+
+    /// The element flows with the surrounding text.
+    Inline,
+
+    /// The element and its content are not displayed at all.
+    None,
+}
+
+/// A single style, as defined by one or more CSS rules.
+///
+/// Properties are stored by their ActionScript names (e.g. `fontSize` rather
+/// than `font-size`), with their values left as unparsed strings. Only the
+/// properties that Flash Player supports are interpreted when the style is
+/// applied to text.
+#[derive(Clone, Debug, Default, PartialEq, Collect)]
+#[collect(require_static)]
+pub struct Style {
+    properties: Vec<(String, String)>,
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Iterate over the names and values of all properties in this style.
+    pub fn properties(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.properties
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Get the value of a property.
+    pub fn property(&self, name: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Set the value of a property, replacing any existing value.
+    ///
+    /// CSS property names, such as `font-size`, are converted to their
+    /// ActionScript equivalents.
+    pub fn set_property(&mut self, name: &str, value: &str) {
+        let name = css_name_to_camel_case(name.trim());
+        let value = value.trim().to_string();
+
+        if let Some((_, v)) = self.properties.iter_mut().find(|(n, _)| *n == name) {
+            *v = value;
+        } else {
+            self.properties.push((name, value));
+        }
+    }
+
+    /// The `display` of this style, if it specifies a valid one.
+    pub fn display(&self) -> Option<Display> {
+        match self.property("display")? {
+            "block" => Some(Display::Block),
+            "inline" => Some(Display::Inline),
+            "none" => Some(Display::None),
+            _ => None,
+        }
+    }
+
+    /// Apply the properties of this style on top of an existing text format.
+    pub fn apply_to(&self, tf: &mut TextFormat) {
+        for (name, value) in self.properties() {
+            match name {
+                "color" => {
+                    if let Some(color) = parse_color(value) {
+                        tf.color = Some(color);
+                    }
+                }
+                "fontFamily" => {
+                    if let Some(family) = value.split(',').next() {
+                        let family = family.trim().trim_matches(|c| c == '"' || c == '\'');
+                        tf.font = Some(match family {
+                            "mono" => "_typewriter".to_string(),
+                            "sans-serif" => "_sans".to_string(),
+                            "serif" => "_serif".to_string(),
+                            family => family.to_string(),
+                        });
+                    }
+                }
+                "fontSize" => {
+                    if let Some(size) = parse_number(value) {
+                        tf.size = Some(size);
+                    }
+                }
+                "fontStyle" => match value {
+                    "normal" => tf.italic = Some(false),
+                    "italic" => tf.italic = Some(true),
+                    _ => {}
+                },
+                "fontWeight" => match value {
+                    "normal" => tf.bold = Some(false),
+                    "bold" => tf.bold = Some(true),
+                    _ => {}
+                },
+                "kerning" => match value {
+                    "false" => tf.kerning = Some(false),
+                    "true" => tf.kerning = Some(true),
+                    _ => {}
+                },
+                "leading" => {
+                    if let Some(leading) = parse_number(value) {
+                        tf.leading = Some(leading);
+                    }
+                }
+                "letterSpacing" => {
+                    if let Some(letter_spacing) = parse_number(value) {
+                        tf.letter_spacing = Some(letter_spacing);
+                    }
+                }
+                "marginLeft" => {
+                    if let Some(margin) = parse_number(value) {
+                        tf.left_margin = Some(margin);
+                    }
+                }
+                "marginRight" => {
+                    if let Some(margin) = parse_number(value) {
+                        tf.right_margin = Some(margin);
+                    }
+                }
+                "textAlign" => match value {
+                    "left" => tf.align = Some(swf::TextAlign::Left),
+                    "center" => tf.align = Some(swf::TextAlign::Center),
+                    "right" => tf.align = Some(swf::TextAlign::Right),
+                    "justify" => tf.align = Some(swf::TextAlign::Justify),
+                    _ => {}
+                },
+                "textDecoration" => match value {
+                    "none" => tf.underline = Some(false),
+                    "underline" => tf.underline = Some(true),
+                    _ => {}
+                },
+                "textIndent" => {
+                    if let Some(indent) = parse_number(value) {
+                        tf.indent = Some(indent);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Construct a text format holding only the properties of this style.
+    pub fn text_format(&self) -> TextFormat {
+        let mut tf = TextFormat::default();
+        self.apply_to(&mut tf);
+        tf
+    }
+}
+
+/// A set of styles, keyed by their selector.
+///
+/// Selectors are matched case-insensitively, and may be a tag name (`p`), a
+/// class name (`.heading`), or a link state (`a:hover`).
+#[derive(Clone, Debug, Default, Collect)]
+#[collect(require_static)]
+pub struct StyleSheet {
+    styles: BTreeMap<String, Style>,
+}
+
+impl StyleSheet {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Parse CSS text, adding its rules to this style sheet.
+    ///
+    /// Each rule replaces any existing style with the same selector. If the
+    /// text is malformed, this style sheet is left unchanged and `false` is
+    /// returned.
+    pub fn parse_css(&mut self, css: &str) -> bool {
+        let css = strip_comments(css);
+        let mut parsed: Vec<(String, Style)> = Vec::new();
+        let mut rest = css.as_str();
+
+        while let Some(open) = rest.find('{') {
+            let close = match rest[open..].find('}') {
+                Some(close) => open + close,
+                None => return false,
+            };
+
+            let selectors = &rest[..open];
+            let declarations = &rest[open + 1..close];
+            if selectors.trim().is_empty() || declarations.contains('{') {
+                return false;
+            }
+
+            let mut style = Style::new();
+            for declaration in declarations.split(';') {
+                if declaration.trim().is_empty() {
+                    continue;
+                }
+
+                if let Some(colon) = declaration.find(':') {
+                    style.set_property(&declaration[..colon], &declaration[colon + 1..]);
+                } else {
+                    return false;
+                }
+            }
+
+            for selector in selectors.split(',') {
+                let selector = selector.trim();
+                if selector.is_empty() {
+                    return false;
+                }
+
+                parsed.push((selector.to_ascii_lowercase(), style.clone()));
+            }
+
+            rest = &rest[close + 1..];
+        }
+
+        if !rest.trim().is_empty() {
+            return false;
+        }
+
+        for (selector, style) in parsed {
+            self.styles.insert(selector, style);
+        }
+
+        true
+    }
+
+    /// Get the style with the given selector.
+    pub fn style(&self, selector: &str) -> Option<&Style> {
+        self.styles.get(&selector.to_ascii_lowercase())
+    }
+
+    /// Set the style with the given selector, or remove it if `None`.
+    pub fn set_style(&mut self, selector: &str, style: Option<Style>) {
+        let selector = selector.to_ascii_lowercase();
+        if let Some(style) = style {
+            self.styles.insert(selector, style);
+        } else {
+            self.styles.remove(&selector);
+        }
+    }
+
+    /// Iterate over the selectors of all styles in this style sheet.
+    pub fn style_names(&self) -> impl Iterator<Item = &str> {
+        self.styles.keys().map(|selector| selector.as_str())
+    }
+
+    /// Remove all styles from this style sheet.
+    pub fn clear(&mut self) {
+        self.styles.clear();
+    }
+}
+
+/// Remove all `/* ... */` comments from CSS text.
+fn strip_comments(css: &str) -> String {
+    let mut result = String::with_capacity(css.len());
+    let mut rest = css;
+
+    while let Some(start) = rest.find("/*") {
+        result.push_str(&rest[..start]);
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Convert a hyphenated CSS property name, such as `font-size`, into the
+/// camel case name used by ActionScript (`fontSize`).
+fn css_name_to_camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut upper = false;
+
+    for c in name.chars() {
+        if c == '-' {
+            upper = !result.is_empty();
+        } else if upper {
+            result.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+/// Parse a `#RRGGBB` color.
+fn parse_color(value: &str) -> Option<swf::Color> {
+    let hex = value.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+
+    let rgb = u32::from_str_radix(hex, 16).ok()?;
+    Some(swf::Color {
+        r: (rgb >> 16) as u8,
+        g: (rgb >> 8) as u8,
+        b: rgb as u8,
+        a: 255,
+    })
+}
+
+/// Parse a number, ignoring any unit that follows it (e.g. `12px`).
+fn parse_number(value: &str) -> Option<f64> {
+    let end = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+'))
+        .unwrap_or_else(|| value.len());
+    value[..end].parse().ok()
+}
@@ -58,6 +58,13 @@ pub struct LayoutContext<'a, 'gc> {
     /// The growing list of layout boxes to return when layout has finished.
     boxes: Vec<LayoutBox<'gc>>,
 
+    /// The lines laid out so far.
+    lines: Vec<LayoutLine>,
+
+    /// The text position that the current line starts at, if it turns out
+    /// to contain no text.
+    line_start: usize,
+
     /// The exterior bounds of all laid-out text, including left and right
     /// margins.
     ///
@@ -99,6 +106,8 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
             text,
             max_font_size: Default::default(),
             boxes: Vec::new(),
+            lines: Vec::new(),
+            line_start: 0,
             exterior_bounds: None,
             is_first_line: true,
             has_line_break: false,
@@ -311,6 +320,34 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
             Position::from((left_adjustment + align_adjustment, Twips::from_pixels(0.0)));
         line_bounds += Size::from((Twips::from_pixels(0.0), font_leading_adjustment));
 
+        let mut text_range = None;
+        for linebox in self.boxes.get(self.current_line..).unwrap() {
+            if let LayoutContent::Text { start, end, .. } = &linebox.content {
+                text_range = match text_range {
+                    Some((range_start, range_end)) => {
+                        Some((min(range_start, *start), max(range_end, *end)))
+                    }
+                    None => Some((*start, *end)),
+                };
+            }
+        }
+        let (start, end) = text_range.unwrap_or((self.line_start, self.line_start));
+
+        // Empty lines still occupy vertical space, so the extent of each line
+        // is measured from the cursor rather than from its boxes.
+        self.lines.push(LayoutLine {
+            bounds: BoxBounds::from_position_and_size(
+                Position::from((line_bounds.offset_x(), self.cursor.y())),
+                Size::from((
+                    line_bounds.width(),
+                    self.max_font_size + self.line_leading_adjustment(),
+                )),
+            ),
+            start,
+            end,
+        });
+        self.line_start = end;
+
         self.current_line = self.boxes.len();
 
         if let Some(eb) = &mut self.exterior_bounds {
@@ -328,6 +365,9 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
     fn explicit_newline(&mut self, context: &mut UpdateContext<'_, 'gc, '_>) {
         self.fixup_line(context, false, true);
 
+        // Skip over the newline character itself.
+        self.line_start += 1;
+
         self.cursor.set_x(Twips::from_pixels(0.0));
         self.cursor += (
             Twips::from_pixels(0.0),
@@ -522,16 +562,18 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
         (width, offset + self.cursor.x())
     }
 
-    /// Destroy the layout context, returning the newly constructed layout list.
+    /// Destroy the layout context, returning the newly constructed layout
+    /// list and lines.
     fn end_layout(
         mut self,
         context: &mut UpdateContext<'_, 'gc, '_>,
-    ) -> (Vec<LayoutBox<'gc>>, BoxBounds<Twips>) {
+    ) -> (Vec<LayoutBox<'gc>>, BoxBounds<Twips>, Vec<LayoutLine>) {
         self.fixup_line(context, !self.has_line_break, true);
 
         (
             self.boxes,
             self.exterior_bounds.unwrap_or_else(Default::default),
+            self.lines,
         )
     }
 
@@ -540,6 +582,41 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
     }
 }
 
+/// A `LayoutLine` represents a single line of text within a fully laid-out
+/// `EditText`.
+#[derive(Clone, Debug, Collect)]
+#[collect(require_static)]
+pub struct LayoutLine {
+    /// The bounds of the line.
+    ///
+    /// Each line spans the full height of its text, including leading, so
+    /// that lines can be stacked to determine the scroll position of a text
+    /// field.
+    bounds: BoxBounds<Twips>,
+
+    /// The start position of the text within this line.
+    start: usize,
+
+    /// The end position of the text within this line.
+    ///
+    /// Any newline character that ends the line is not included.
+    end: usize,
+}
+
+impl LayoutLine {
+    pub fn bounds(&self) -> BoxBounds<Twips> {
+        self.bounds
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn end(&self) -> usize {
+        self.end
+    }
+}
+
 /// A `LayoutBox` represents a single content box within a fully laid-out
 /// `EditText`.
 ///
@@ -656,7 +733,8 @@ impl<'gc> LayoutBox<'gc> {
     /// Construct a new layout hierarchy from text spans.
     ///
     /// The returned bounds will include both the text bounds itself, as well
-    /// as left and right margins on any of the lines.
+    /// as left and right margins on any of the lines. Each individual line is
+    /// also returned, in order.
     pub fn lower_from_text_spans(
         fs: &FormatSpans,
         context: &mut UpdateContext<'_, 'gc, '_>,
@@ -664,7 +742,7 @@ impl<'gc> LayoutBox<'gc> {
         bounds: Twips,
         is_word_wrap: bool,
         is_device_font: bool,
-    ) -> (Vec<LayoutBox<'gc>>, BoxBounds<Twips>) {
+    ) -> (Vec<LayoutBox<'gc>>, BoxBounds<Twips>, Vec<LayoutLine>) {
         let mut layout_context = LayoutContext::new(movie, bounds, fs.displayed_text());
 
         for (span_start, _end, span_text, span) in fs.iter_spans() {
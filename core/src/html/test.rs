@@ -1,5 +1,6 @@
 //! Tests for HTML module
 
+use crate::html::css::{Display, StyleSheet};
 use crate::html::dimensions::{BoxBounds, Position, Size};
 use crate::html::text_format::{FormatSpans, TextFormat, TextSpan};
 use swf::{Rectangle, Twips};
@@ -863,3 +864,49 @@ fn formatspans_replace_text_degenerate() {
     assert_eq!((0, 1), fs.get_span_boundaries(0, 5));
     assert_eq!((1, 2), fs.get_span_boundaries(5, 9));
 }
+
+#[test]
+fn stylesheet_parse_css() {
+    let mut css = StyleSheet::new();
+
+    assert!(css.parse_css(
+        "/* headings */ H1, .Title { font-size: 24px; color: #FF8000 }\na:hover{text-decoration:underline;}"
+    ));
+
+    let names: Vec<&str> = css.style_names().collect();
+    assert_eq!(names, vec![".title", "a:hover", "h1"]);
+
+    let heading = css.style("h1").unwrap();
+    assert_eq!(heading.property("fontSize"), Some("24px"));
+    assert_eq!(heading.display(), None);
+
+    let tf = heading.text_format();
+    assert_eq!(tf.size, Some(24.0));
+    assert_eq!(
+        tf.color,
+        Some(swf::Color {
+            r: 0xFF,
+            g: 0x80,
+            b: 0x00,
+            a: 0xFF
+        })
+    );
+    assert_eq!(tf.underline, None);
+    assert_eq!(
+        css.style("a:hover").unwrap().text_format().underline,
+        Some(true)
+    );
+}
+
+#[test]
+fn stylesheet_parse_css_invalid() {
+    let mut css = StyleSheet::new();
+
+    assert!(css.parse_css("p { display: none; }"));
+    assert!(!css.parse_css("p { display: block; } h1 { color: #000000"));
+    assert!(!css.parse_css("p { display }"));
+
+    // Failed parses leave existing styles alone.
+    assert_eq!(css.style("p").unwrap().display(), Some(Display::None));
+    assert!(css.style("h1").is_none());
+}
@@ -3,6 +3,7 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::{AvmString, Object, ScriptObject, TObject, Value};
 use crate::context::UpdateContext;
+use crate::html::css::{Display, StyleSheet};
 use crate::html::iterators::TextSpanIter;
 use crate::tag_utils::SwfMovie;
 use crate::xml::{Step, XMLDocument, XMLName, XMLNode};
@@ -1261,12 +1262,15 @@ impl FormatSpans {
 
     /// Lower an HTML tree into text-span representation.
     ///
-    /// This is the "legacy" implementation of this process: it only looks for
-    /// a handful of presentational attributes in the HTML tree to generate
-    /// styling. There's also a `lower_from_css` that respects both
-    /// presentational markup and CSS stylesheets.
-    pub fn lower_from_html(&mut self, tree: XMLDocument<'_>) {
+    /// Each element is styled by its presentational markup. If a CSS style
+    /// sheet is given, this is followed by the style for its tag name (and
+    /// `a:link`, for links), and finally by the style for its `class`
+    /// attribute. Styles also determine whether an element is displayed as a
+    /// block, which ends with a newline, or is hidden entirely.
+    pub fn lower_from_html(&mut self, tree: XMLDocument<'_>, style_sheet: Option<&StyleSheet>) {
         let mut format_stack = vec![self.default_format.clone()];
+        let mut display_stack = vec![];
+        let mut hidden_depth = 0;
         let mut last_successful_format = None;
 
         self.text = "".to_string();
@@ -1274,44 +1278,58 @@ impl FormatSpans {
 
         for step in tree.as_node().walk().unwrap() {
             match step {
-                Step::In(node)
-                    if node
+                Step::In(node) => {
+                    let tag_name = node
                         .tag_name()
-                        .unwrap()
-                        .node_name()
-                        .eq_ignore_ascii_case("sbr")
-                        || node
-                            .tag_name()
-                            .unwrap()
-                            .node_name()
-                            .eq_ignore_ascii_case("br") =>
-                {
-                    self.replace_text(
-                        self.text().len(),
-                        self.text().len(),
-                        "\n",
-                        format_stack.last(),
+                        .map(|name| name.node_name().to_ascii_lowercase())
+                        .unwrap_or_default();
+
+                    let mut tf = TextFormat::from_presentational_markup(
+                        node,
+                        format_stack
+                            .last()
+                            .cloned()
+                            .unwrap_or_else(Default::default),
                     );
+                    let mut display = match tag_name.as_str() {
+                        "p" | "li" => Display::Block,
+                        _ => Display::Inline,
+                    };
+
+                    let mut styles = vec![];
+                    if let Some(style_sheet) = style_sheet {
+                        styles.push(style_sheet.style(&tag_name));
+                        if tag_name == "a" && tf.url.as_deref().map_or(false, |url| !url.is_empty())
+                        {
+                            styles.push(style_sheet.style("a:link"));
+                        }
+                        if let Some(class) =
+                            node.attribute_value_ignore_ascii_case(&XMLName::from_str("class"))
+                        {
+                            styles.push(style_sheet.style(&format!(".{}", class.trim())));
+                        }
+                    }
+
+                    for style in styles.into_iter().flatten() {
+                        style.apply_to(&mut tf);
+                        display = style.display().unwrap_or(display);
+                    }
+
+                    if hidden_depth > 0 || display == Display::None {
+                        hidden_depth += 1;
+                    } else if tag_name == "br" || tag_name == "sbr" {
+                        self.replace_text(
+                            self.text().len(),
+                            self.text().len(),
+                            "\n",
+                            format_stack.last(),
+                        );
+                    }
+
+                    format_stack.push(tf);
+                    display_stack.push(display);
                 }
-                Step::Out(node)
-                    if node
-                        .tag_name()
-                        .unwrap()
-                        .node_name()
-                        .eq_ignore_ascii_case("sbr")
-                        || node
-                            .tag_name()
-                            .unwrap()
-                            .node_name()
-                            .eq_ignore_ascii_case("br") => {}
-                Step::In(node) => format_stack.push(TextFormat::from_presentational_markup(
-                    node,
-                    format_stack
-                        .last()
-                        .cloned()
-                        .unwrap_or_else(Default::default),
-                )),
-                Step::Around(node) if node.is_text() => {
+                Step::Around(node) if node.is_text() && hidden_depth == 0 => {
                     self.replace_text(
                         self.text.len(),
                         self.text.len(),
@@ -1320,34 +1338,57 @@ impl FormatSpans {
                     );
                     last_successful_format = format_stack.last().cloned();
                 }
-                Step::Out(node)
-                    if node
-                        .tag_name()
-                        .unwrap()
-                        .node_name()
-                        .eq_ignore_ascii_case("p")
-                        || node
-                            .tag_name()
-                            .unwrap()
-                            .node_name()
-                            .eq_ignore_ascii_case("li") =>
-                {
-                    self.replace_text(
-                        self.text.len(),
-                        self.text.len(),
-                        "\n",
-                        last_successful_format.as_ref(),
-                    );
-                    format_stack.pop();
-                }
                 Step::Out(_) => {
                     format_stack.pop();
+
+                    if hidden_depth > 0 {
+                        hidden_depth -= 1;
+                        display_stack.pop();
+                    } else if display_stack.pop() == Some(Display::Block) {
+                        self.replace_text(
+                            self.text.len(),
+                            self.text.len(),
+                            "\n",
+                            last_successful_format.as_ref(),
+                        );
+                    }
                 }
                 _ => {}
             };
         }
     }
 
+    /// Find the range of text covered by the link at a given position.
+    ///
+    /// Adjacent spans linking to the same URL are treated as a single link.
+    /// Returns `None` if the position is not within a link.
+    pub fn link_range(&self, position: usize) -> Option<(usize, usize)> {
+        let (index, offset) = self.resolve_position_as_span(position)?;
+        let url = &self.spans[index].url;
+        if url.is_empty() {
+            return None;
+        }
+
+        let mut start = position - offset;
+        let mut end = start + self.spans[index].span_length;
+
+        for span in self.spans[..index].iter().rev() {
+            if span.url != *url {
+                break;
+            }
+            start -= span.span_length;
+        }
+
+        for span in self.spans[index + 1..].iter() {
+            if span.url != *url {
+                break;
+            }
+            end += span.span_length;
+        }
+
+        Some((start, end))
+    }
+
     #[allow(clippy::float_cmp)]
     pub fn raise_to_html<'gc>(&self, mc: MutationContext<'gc, '_>) -> XMLDocument<'gc> {
         let document = XMLDocument::new(mc);
@@ -15,6 +15,7 @@ extern crate smallvec;
 #[macro_use]
 extern crate downcast_rs;
 
+mod amf;
 #[macro_use]
 mod avm1;
 mod avm2;
@@ -24,6 +25,7 @@ mod character;
 mod collect;
 pub mod color_transform;
 pub mod context;
+pub mod context_menu;
 mod drawing;
 mod ecma_conversions;
 pub mod events;
@@ -32,6 +34,7 @@ mod font;
 mod html;
 mod library;
 pub mod loader;
+mod mp3;
 mod player;
 mod prelude;
 pub mod property_map;
@@ -50,6 +53,6 @@ pub mod external;
 pub use chrono;
 pub use events::PlayerEvent;
 pub use indexmap;
-pub use player::Player;
+pub use player::{Player, DEVICE_FONT_TAG};
 pub use swf;
 pub use swf::Color;
@@ -1,11 +1,13 @@
 //! Management of async loaders
 
 use crate::avm1::activation::{Activation, ActivationIdentifier};
-use crate::avm1::{Avm1, AvmString, Object, TObject, Value};
+use crate::avm1::globals::sound::start_sound;
+use crate::avm1::{Avm1, AvmString, Object, ScriptObject, SoundObject, TObject, Value};
 use crate::avm2::Domain as Avm2Domain;
-use crate::backend::navigator::OwnedFuture;
-use crate::context::{ActionQueue, ActionType};
-use crate::display_object::{DisplayObject, MorphShape, TDisplayObject};
+use crate::backend::navigator::{ChunkHandler, NavigatorBackend, OwnedFuture, RequestOptions};
+use crate::context::{ActionQueue, ActionType, UpdateContext};
+use crate::display_object::{DisplayObject, MorphShape, MovieClip, TDisplayObject};
+use crate::mp3::Mp3Parser;
 use crate::player::{Player, NEWEST_PLAYER_VERSION};
 use crate::property_map::PropertyMap;
 use crate::tag_utils::SwfMovie;
@@ -14,8 +16,11 @@ use crate::xml::XMLNode;
 use encoding_rs::UTF_8;
 use gc_arena::{Collect, CollectionContext, MutationContext};
 use generational_arena::{Arena, Index};
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::string::FromUtf8Error;
 use std::sync::{Arc, Mutex, Weak};
+use swf::CharacterId;
 use thiserror::Error;
 use url::form_urlencoded;
 
@@ -41,6 +46,12 @@ pub enum Error {
     #[error("Non-XML loader spawned as XML loader")]
     NotXmlLoader,
 
+    #[error("Non-sound loader spawned as sound loader")]
+    NotSoundLoader,
+
+    #[error("Non-import loader spawned as import loader")]
+    NotImportAssetsLoader,
+
     #[error("Could not fetch movie {0}")]
     FetchError(String),
 
@@ -250,6 +261,61 @@ impl<'gc> LoadManager<'gc> {
 
         loader.xml_loader(player, fetch)
     }
+
+    /// Kick off an MP3 load into an AVM1 Sound object.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_sound_into_object(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: SoundObject<'gc>,
+        is_streaming: bool,
+        navigator: &dyn NavigatorBackend,
+        url: &str,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::Sound {
+            self_handle: None,
+            target_object,
+            is_streaming,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.sound_loader(player, navigator, url)
+    }
+
+    /// Kick off a shared library load for an `ImportAssets` tag.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    #[allow(clippy::too_many_arguments)]
+    pub fn load_import_assets(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_clip: DisplayObject<'gc>,
+        movie: Arc<SwfMovie>,
+        frame: u16,
+        imports: Vec<(CharacterId, String)>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+        url: String,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::ImportAssets {
+            self_handle: None,
+            target_clip,
+            movie,
+            frame,
+            imports,
+            url,
+            library_clip: None,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.import_assets_loader(player, fetch)
+    }
 }
 
 impl<'gc> Default for LoadManager<'gc> {
@@ -334,6 +400,45 @@ pub enum Loader<'gc> {
         /// The target node whose contents will be replaced with the parsed XML.
         target_node: XMLNode<'gc>,
     },
+
+    /// Loader that is loading an MP3 file into an AVM1 Sound object.
+    Sound {
+        /// The handle to refer to this loader instance.
+        self_handle: Option<Handle>,
+
+        /// The target Sound object to attach the loaded sound to.
+        target_object: SoundObject<'gc>,
+
+        /// Whether the sound should start playing as soon as it is loaded.
+        is_streaming: bool,
+    },
+
+    /// Loader that is loading a shared library for an `ImportAssets` tag.
+    ImportAssets {
+        /// The handle to refer to this loader instance.
+        self_handle: Option<Handle>,
+
+        /// The movie clip whose timeline contains the `ImportAssets` tag.
+        target_clip: DisplayObject<'gc>,
+
+        /// The movie containing the `ImportAssets` tag.
+        movie: Arc<SwfMovie>,
+
+        /// The frame containing the `ImportAssets` tag.
+        frame: u16,
+
+        /// The export names to import, and the character IDs to register them under.
+        imports: Vec<(CharacterId, String)>,
+
+        /// The URL of the shared library.
+        url: String,
+
+        /// The preloaded shared library, once it has been fetched.
+        ///
+        /// If the library imports assets itself, it stays here until those
+        /// have loaded as well.
+        library_clip: Option<MovieClip<'gc>>,
+    },
 }
 
 unsafe impl<'gc> Collect for Loader<'gc> {
@@ -351,6 +456,15 @@ unsafe impl<'gc> Collect for Loader<'gc> {
             Loader::Form { target_object, .. } => target_object.trace(cc),
             Loader::LoadVars { target_object, .. } => target_object.trace(cc),
             Loader::XML { target_node, .. } => target_node.trace(cc),
+            Loader::Sound { target_object, .. } => target_object.trace(cc),
+            Loader::ImportAssets {
+                target_clip,
+                library_clip,
+                ..
+            } => {
+                target_clip.trace(cc);
+                library_clip.trace(cc);
+            }
         }
     }
 }
@@ -367,6 +481,8 @@ impl<'gc> Loader<'gc> {
             Loader::Form { self_handle, .. } => *self_handle = Some(handle),
             Loader::LoadVars { self_handle, .. } => *self_handle = Some(handle),
             Loader::XML { self_handle, .. } => *self_handle = Some(handle),
+            Loader::Sound { self_handle, .. } => *self_handle = Some(handle),
+            Loader::ImportAssets { self_handle, .. } => *self_handle = Some(handle),
         }
     }
 
@@ -703,6 +819,226 @@ impl<'gc> Loader<'gc> {
         })
     }
 
+    /// Creates a future for a `Sound.loadSound` call.
+    ///
+    /// The file is handed to an `Mp3Parser` as it downloads, which updates the
+    /// sound's loaded byte count and calls `onID3` as soon as an ID3v2 tag has
+    /// arrived. The sound is only registered with the audio backend once it
+    /// has been fetched in full, so a streaming sound starts playing at that
+    /// point.
+    pub fn sound_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        navigator: &dyn NavigatorBackend,
+        url: &str,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::Sound { self_handle, .. } => self_handle.expect("Loader not self-introduced"),
+            _ => return Box::pin(async { Err(Error::NotSoundLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        let parser = Rc::new(RefCell::new(Mp3Parser::new()));
+        let on_chunk: ChunkHandler = {
+            let player = player.clone();
+            let parser = parser.clone();
+            let mut bytes_loaded: u32 = 0;
+            Box::new(move |chunk: &[u8], bytes_total: Option<u64>| {
+                let read_tag = parser.borrow_mut().push(chunk);
+                bytes_loaded = bytes_loaded.saturating_add(chunk.len() as u32);
+
+                player.lock().unwrap().update(|uc| {
+                    let sound_object = match uc.load_manager.get_loader(handle) {
+                        Some(&Loader::Sound { target_object, .. }) => target_object,
+                        None => return Err(Error::Cancelled),
+                        _ => return Err(Error::NotSoundLoader),
+                    };
+
+                    sound_object.set_bytes_loaded(uc.gc_context, Some(bytes_loaded));
+                    sound_object.set_bytes_total(uc.gc_context, bytes_total.map(|n| n as u32));
+
+                    if read_tag {
+                        let mut activation = Activation::from_stub(
+                            uc.reborrow(),
+                            ActivationIdentifier::root("[Sound Loader]"),
+                        );
+                        set_sound_id3(&mut activation, sound_object, parser.borrow().id3())?;
+                    }
+
+                    Ok(())
+                })
+            })
+        };
+        let fetch = navigator.fetch_chunked(url, RequestOptions::get(), on_chunk);
+
+        Box::pin(async move {
+            let result = fetch.await;
+
+            player.lock().unwrap().update(|uc| {
+                let (sound_object, is_streaming) = match uc.load_manager.get_loader(handle) {
+                    Some(&Loader::Sound {
+                        target_object,
+                        is_streaming,
+                        ..
+                    }) => (target_object, is_streaming),
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotSoundLoader),
+                };
+
+                let mut activation = Activation::from_stub(
+                    uc.reborrow(),
+                    ActivationIdentifier::root("[Sound Loader]"),
+                );
+                let object: Object<'_> = sound_object.into();
+
+                if result.is_err() {
+                    let _ = object.call_method("onLoad", &[false.into()], &mut activation);
+                    return Ok(());
+                }
+
+                // Without a `Content-Length`, the total is only known now.
+                let gc_context = activation.context.gc_context;
+                if sound_object.bytes_total().is_none() {
+                    sound_object.set_bytes_total(gc_context, sound_object.bytes_loaded());
+                }
+
+                let reported_tags = parser.borrow().id3().len();
+                let mp3 = match parser.replace(Mp3Parser::new()).finish() {
+                    Some(mp3) => mp3,
+                    None => {
+                        log::warn!("Sound.loadSound: Invalid MP3 file");
+                        let _ = object.call_method("onLoad", &[false.into()], &mut activation);
+                        return Ok(());
+                    }
+                };
+
+                // The audio backends expect MP3 data to begin with a latency seek.
+                let mut sound_data = vec![0, 0];
+                sound_data.extend_from_slice(&mp3.data);
+                let sound = activation.context.audio.register_sound(&swf::Sound {
+                    id: 0,
+                    format: mp3.format.clone(),
+                    num_samples: mp3.num_samples,
+                    data: &sound_data,
+                });
+                let sound = match sound {
+                    Ok(sound) => sound,
+                    Err(e) => {
+                        log::warn!("Sound.loadSound: Unable to register sound: {}", e);
+                        let _ = object.call_method("onLoad", &[false.into()], &mut activation);
+                        return Ok(());
+                    }
+                };
+
+                let duration = activation
+                    .context
+                    .audio
+                    .get_sound_duration(sound)
+                    .unwrap_or_else(|| {
+                        (u64::from(mp3.num_samples) * 1000 / u64::from(mp3.format.sample_rate))
+                            as u32
+                    });
+                sound_object.set_sound(gc_context, Some(sound));
+                sound_object.set_duration(gc_context, duration);
+                sound_object.set_position(gc_context, 0);
+
+                // An ID3v1 tag is only found once the whole file has arrived.
+                if mp3.id3.len() > reported_tags {
+                    set_sound_id3(&mut activation, sound_object, &mp3.id3)?;
+                }
+
+                let _ = object.call_method("onLoad", &[true.into()], &mut activation);
+
+                if is_streaming {
+                    start_sound(&mut activation.context, sound_object, sound, 0.0, 1);
+                }
+
+                Ok(())
+            })
+        })
+    }
+
+    /// Creates a future for loading the shared library of an `ImportAssets` tag.
+    ///
+    /// Once the library has loaded, the imported characters are copied into
+    /// the importing movie by `finish_import_assets`.
+    pub fn import_assets_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let (handle, url) = match self {
+            Loader::ImportAssets {
+                self_handle, url, ..
+            } => (
+                self_handle.expect("Loader not self-introduced"),
+                url.clone(),
+            ),
+            _ => return Box::pin(async { Err(Error::NotImportAssetsLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let data =
+                (fetch.await).and_then(|data| Ok(SwfMovie::from_data(&data, Some(url.clone()))?));
+
+            player.lock().unwrap().update(|uc| {
+                match uc.load_manager.get_loader(handle) {
+                    Some(Loader::ImportAssets { .. }) => {}
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotImportAssetsLoader),
+                }
+
+                match data {
+                    Ok(library_movie) => {
+                        let library_movie = Arc::new(library_movie);
+                        let domain =
+                            Avm2Domain::movie_domain(uc.gc_context, uc.avm2.global_domain());
+                        uc.library
+                            .library_for_movie_mut(library_movie.clone())
+                            .set_avm2_domain(domain);
+
+                        // Preload the library to register its characters and exports.
+                        // This also starts loading any libraries it imports from.
+                        let library_clip =
+                            MovieClip::from_movie(uc.gc_context, library_movie.clone());
+                        let mut morph_shapes = fnv::FnvHashMap::default();
+                        library_clip.preload(uc, &mut morph_shapes);
+
+                        // Finalize morph shapes.
+                        for (id, static_data) in morph_shapes {
+                            let morph_shape = MorphShape::new(uc.gc_context, static_data);
+                            uc.library
+                                .library_for_movie_mut(library_movie.clone())
+                                .register_character(
+                                    id,
+                                    crate::character::Character::MorphShape(morph_shape),
+                                );
+                        }
+
+                        if let Some(Loader::ImportAssets {
+                            library_clip: loaded,
+                            ..
+                        }) = uc.load_manager.get_loader_mut(handle)
+                        {
+                            *loaded = Some(library_clip);
+                        }
+                    }
+                    Err(e) => log::warn!("Unable to load shared library {}: {}", url, e),
+                }
+
+                finish_import_assets(uc, handle);
+                Ok(())
+            })
+        })
+    }
+
     /// Event handler morally equivalent to `onLoad` on a movie clip.
     ///
     /// Returns `true` if the loader has completed and should be removed.
@@ -849,3 +1185,104 @@ impl<'gc> Loader<'gc> {
         })
     }
 }
+
+/// Expose ID3 metadata read from an MP3 file on a `Sound` object, and call its
+/// `onID3` handler.
+fn set_sound_id3<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    sound_object: SoundObject<'gc>,
+    tags: &[(String, String)],
+) -> Result<(), Error> {
+    let gc_context = activation.context.gc_context;
+    let id3 = ScriptObject::object(
+        gc_context,
+        Some(activation.context.avm1.prototypes().object),
+    );
+    for (name, value) in tags {
+        id3.set(
+            name,
+            AvmString::new(gc_context, value.as_str()).into(),
+            activation,
+        )?;
+    }
+    sound_object.set_id3(gc_context, Some(id3.into()));
+
+    let object: Object<'gc> = sound_object.into();
+    let _ = object.call_method("onID3", &[], activation);
+    Ok(())
+}
+
+/// Completes the `ImportAssets` loader with the given handle.
+///
+/// The characters exported by the shared library under the imported names
+/// are registered in the library of the importing movie under the requested
+/// IDs, and the frame containing the tag is released, even if the library
+/// failed to load.
+///
+/// If the shared library is still loading libraries of its own, this does
+/// nothing: the characters it exports may come from those. The loader is
+/// completed once the last of them has loaded instead.
+fn finish_import_assets<'gc>(uc: &mut UpdateContext<'_, 'gc, '_>, handle: Handle) {
+    match uc.load_manager.get_loader(handle) {
+        Some(Loader::ImportAssets { library_clip, .. }) => {
+            if library_clip.map_or(false, |clip| clip.has_pending_imports()) {
+                return;
+            }
+        }
+        _ => return,
+    }
+
+    let (target_clip, movie, frame, imports, url, library_clip) =
+        match uc.load_manager.0.remove(handle) {
+            Some(Loader::ImportAssets {
+                target_clip,
+                movie,
+                frame,
+                imports,
+                url,
+                library_clip,
+                ..
+            }) => (target_clip, movie, frame, imports, url, library_clip),
+            _ => return,
+        };
+
+    if let Some(library_movie) = library_clip.and_then(|clip| clip.movie()) {
+        for (id, name) in imports {
+            let character = uc
+                .library
+                .library_for_movie_mut(library_movie.clone())
+                .character_by_export_name(&name)
+                .cloned();
+            if let Some(character) = character {
+                let library = uc.library.library_for_movie_mut(movie.clone());
+                library.register_character(id, character);
+                library.register_export(id, &name);
+            } else {
+                log::warn!("Shared library {} doesn't export {}", url, name);
+            }
+        }
+    }
+
+    // The clip may have been replaced by another movie in the meantime.
+    let clip = target_clip.as_movie_clip().unwrap();
+    if clip.movie().map_or(false, |m| Arc::ptr_eq(&m, &movie)) {
+        clip.finish_import(frame);
+    }
+
+    // The clip may be a shared library itself, whose importers were waiting for this.
+    let importers: Vec<Handle> = uc
+        .load_manager
+        .0
+        .iter()
+        .filter_map(|(importer, loader)| match loader {
+            Loader::ImportAssets {
+                library_clip: Some(library_clip),
+                ..
+            } if DisplayObject::ptr_eq((*library_clip).into(), target_clip) => Some(importer),
+            _ => None,
+        })
+        .collect();
+    for importer in importers {
+        finish_import_assets(uc, importer);
+    }
+}
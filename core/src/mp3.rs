@@ -0,0 +1,617 @@
+//! Parsing of standalone MP3 files, as loaded by `Sound.loadSound`.
+//!
+//! Sounds embedded in a SWF come with a `SoundFormat` and sample count, but
+//! MP3 files only describe themselves through their frame headers. Files may
+//! also carry ID3v1 and ID3v2 tags, which are exposed to ActionScript.
+
+use swf::{AudioCompression, SoundFormat};
+
+/// The names given by Flash to common ID3v2 frames.
+const ID3_NAMES: &[(&str, &str)] = &[
+    ("TIT2", "songname"),
+    ("TT2", "songname"),
+    ("TPE1", "artist"),
+    ("TP1", "artist"),
+    ("TALB", "album"),
+    ("TAL", "album"),
+    ("TYER", "year"),
+    ("TYE", "year"),
+    ("TDRC", "year"),
+    ("TCON", "genre"),
+    ("TCO", "genre"),
+    ("TRCK", "track"),
+    ("TRK", "track"),
+    ("COMM", "comment"),
+    ("COM", "comment"),
+];
+
+/// The length of an ID3v1 tag, which is found at the end of a file.
+const ID3V1_LEN: usize = 128;
+
+/// An MP3 file.
+#[derive(Debug, PartialEq)]
+pub struct Mp3File {
+    /// The format of the audio, taken from the first MP3 frame.
+    pub format: SoundFormat,
+
+    /// The number of samples in each channel.
+    pub num_samples: u32,
+
+    /// The MP3 frames, with any tags removed.
+    pub data: Vec<u8>,
+
+    /// ID3 metadata, as pairs of property name and value.
+    ///
+    /// ID3v2 frames are named by their frame ID (e.g. `TIT2`). Common frames
+    /// are also given the names used by ID3v1 (e.g. `songname`).
+    pub id3: Vec<(String, String)>,
+}
+
+impl Mp3File {
+    /// Parse an MP3 file. Returns `None` if it contains no MP3 frames.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let mut parser = Mp3Parser::new();
+        parser.push(data);
+        parser.finish()
+    }
+}
+
+/// Parses an MP3 file as it is downloaded.
+///
+/// An ID3v2 tag at the start of the file is read as soon as it has fully
+/// arrived, and frames are scanned as the data comes in. An ID3v1 tag can only
+/// be recognized once the file has ended, so the last 128 bytes received are
+/// not scanned until `finish` is called.
+#[derive(Default)]
+pub struct Mp3Parser {
+    /// The data received so far, minus any ID3v2 tag once it has been read.
+    data: Vec<u8>,
+
+    /// Whether the start of the file has been checked for an ID3v2 tag.
+    header_checked: bool,
+
+    /// How far `data` has been scanned for frames.
+    pos: usize,
+
+    /// The offset in `data` of the first MP3 frame.
+    first_frame: Option<usize>,
+
+    /// The format of the audio, taken from the first MP3 frame.
+    format: Option<SoundFormat>,
+
+    /// The number of samples in each channel, in the frames scanned so far.
+    num_samples: u32,
+
+    /// ID3 metadata read so far.
+    id3: Vec<(String, String)>,
+}
+
+impl Mp3Parser {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// ID3 metadata read so far.
+    pub fn id3(&self) -> &[(String, String)] {
+        &self.id3
+    }
+
+    /// Add the next chunk of the file.
+    ///
+    /// Returns `true` if this chunk completed an ID3v2 tag holding metadata.
+    pub fn push(&mut self, chunk: &[u8]) -> bool {
+        self.data.extend_from_slice(chunk);
+        let read_tag = self.read_id3v2(false);
+        if self.header_checked {
+            self.scan(self.data.len().saturating_sub(ID3V1_LEN));
+        }
+        read_tag
+    }
+
+    /// Finish parsing, once the whole file has arrived.
+    ///
+    /// Returns `None` if the file contained no MP3 frames.
+    pub fn finish(mut self) -> Option<Mp3File> {
+        self.read_id3v2(true);
+
+        let mut end = self.data.len();
+        if end >= ID3V1_LEN && &self.data[end - ID3V1_LEN..end - ID3V1_LEN + 3] == b"TAG" {
+            for (name, value) in parse_id3v1(&self.data[end - ID3V1_LEN..]) {
+                if !self.id3.iter().any(|(n, _)| *n == name) {
+                    self.id3.push((name, value));
+                }
+            }
+            end -= ID3V1_LEN;
+        }
+        self.data.truncate(end);
+        self.scan(end);
+
+        let first_frame = self.first_frame?;
+        self.data.drain(..first_frame);
+        Some(Mp3File {
+            format: self.format?,
+            num_samples: self.num_samples,
+            data: self.data,
+            id3: self.id3,
+        })
+    }
+
+    /// Read and remove the ID3v2 tag at the start of the file, if there is one.
+    ///
+    /// Unless the file has ended, this waits for the whole tag to arrive.
+    /// Returns `true` if a tag holding metadata was read.
+    fn read_id3v2(&mut self, at_end: bool) -> bool {
+        if self.header_checked {
+            return false;
+        }
+
+        if !at_end {
+            if self.data.len() < 10 {
+                return false;
+            }
+            if &self.data[0..3] == b"ID3" {
+                let footer = if self.data[5] & 0x10 != 0 { 10 } else { 0 };
+                let len = 10 + syncsafe(&self.data[6..10]) as usize + footer;
+                if self.data.len() < len {
+                    return false;
+                }
+            }
+        }
+
+        self.header_checked = true;
+        if let Some((tags, len)) = parse_id3v2(&self.data) {
+            self.id3 = tags;
+            self.data.drain(..len.min(self.data.len()));
+            !self.id3.is_empty()
+        } else {
+            false
+        }
+    }
+
+    /// Scan for MP3 frames with headers that start before `end`.
+    fn scan(&mut self, end: usize) {
+        while self.pos + 4 <= end {
+            match FrameHeader::parse(&self.data[self.pos..]) {
+                Some(header) => {
+                    if self.format.is_none() {
+                        self.format = Some(header.format());
+                        self.first_frame = Some(self.pos);
+                    }
+                    self.num_samples = self.num_samples.saturating_add(header.samples_per_frame());
+                    self.pos += header.frame_len();
+                }
+                None => self.pos += 1,
+            }
+        }
+    }
+}
+
+/// The header of an MPEG audio layer III frame.
+struct FrameHeader {
+    /// 1 for MPEG-1, 2 for MPEG-2 and 3 for MPEG-2.5.
+    version: u8,
+    bitrate: u32,
+    sample_rate: u32,
+    padding: bool,
+    is_stereo: bool,
+}
+
+impl FrameHeader {
+    fn parse(data: &[u8]) -> Option<Self> {
+        const MPEG1_BITRATES: [u32; 15] = [
+            0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+        ];
+        const MPEG2_BITRATES: [u32; 15] =
+            [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+        const SAMPLE_RATES: [u32; 3] = [44100, 48000, 32000];
+
+        if data.len() < 4 || data[0] != 0xFF || data[1] & 0xE0 != 0xE0 {
+            return None;
+        }
+
+        let version = match (data[1] >> 3) & 0b11 {
+            0b11 => 1,
+            0b10 => 2,
+            0b00 => 3,
+            _ => return None,
+        };
+
+        // Only layer III is supported.
+        if (data[1] >> 1) & 0b11 != 0b01 {
+            return None;
+        }
+
+        let bitrate_index = usize::from(data[2] >> 4);
+        let sample_rate_index = usize::from((data[2] >> 2) & 0b11);
+        if bitrate_index == 0 || bitrate_index == 15 || sample_rate_index == 3 {
+            return None;
+        }
+
+        let bitrate = if version == 1 {
+            MPEG1_BITRATES[bitrate_index]
+        } else {
+            MPEG2_BITRATES[bitrate_index]
+        };
+
+        Some(Self {
+            version,
+            bitrate,
+            sample_rate: SAMPLE_RATES[sample_rate_index] >> (version - 1),
+            padding: data[2] & 0b10 != 0,
+            is_stereo: data[3] >> 6 != 0b11,
+        })
+    }
+
+    fn samples_per_frame(&self) -> u32 {
+        if self.version == 1 {
+            1152
+        } else {
+            576
+        }
+    }
+
+    /// The length of the frame in bytes, including this header.
+    fn frame_len(&self) -> usize {
+        let len = self.samples_per_frame() / 8 * self.bitrate * 1000 / self.sample_rate;
+        len as usize + usize::from(self.padding)
+    }
+
+    fn format(&self) -> SoundFormat {
+        SoundFormat {
+            compression: AudioCompression::Mp3,
+            sample_rate: self.sample_rate as u16,
+            is_stereo: self.is_stereo,
+            is_16_bit: true,
+        }
+    }
+}
+
+/// Parse an ID3v1 tag, which is the last 128 bytes of a file.
+fn parse_id3v1(tag: &[u8]) -> Vec<(String, String)> {
+    let field = |range: std::ops::Range<usize>| {
+        let text = &tag[range];
+        let text = &text[..text.iter().position(|b| *b == 0).unwrap_or(text.len())];
+        decode_latin1(text).trim_end().to_string()
+    };
+
+    let mut tags = vec![
+        ("songname".to_string(), field(3..33)),
+        ("artist".to_string(), field(33..63)),
+        ("album".to_string(), field(63..93)),
+        ("year".to_string(), field(93..97)),
+        ("comment".to_string(), field(97..127)),
+    ];
+
+    // ID3v1.1 stores the track number in the last byte of the comment.
+    if tag[125] == 0 && tag[126] != 0 {
+        tags.push(("track".to_string(), tag[126].to_string()));
+    }
+
+    if tag[127] != 0xFF {
+        tags.push(("genre".to_string(), tag[127].to_string()));
+    }
+
+    tags.retain(|(_, value)| !value.is_empty());
+    tags
+}
+
+/// Parse an ID3v2 tag at the start of a file.
+///
+/// Returns the tags, along with the length of the tag in bytes.
+fn parse_id3v2(data: &[u8]) -> Option<(Vec<(String, String)>, usize)> {
+    if data.len() < 10 || &data[0..3] != b"ID3" {
+        return None;
+    }
+
+    let version = data[3];
+    let flags = data[5];
+    let size = syncsafe(&data[6..10]) as usize;
+    let footer = if flags & 0x10 != 0 { 10 } else { 0 };
+    let len = 10 + size + footer;
+
+    let body = data.get(10..10 + size)?;
+    let body = if flags & 0x80 != 0 && version < 4 {
+        remove_unsynchronization(body)
+    } else {
+        body.to_vec()
+    };
+
+    let mut pos = 0;
+    if flags & 0x40 != 0 && version >= 3 {
+        // Skip the extended header.
+        let size = body.get(0..4)?;
+        pos = if version == 3 {
+            4 + u32::from_be_bytes([size[0], size[1], size[2], size[3]]) as usize
+        } else {
+            syncsafe(size) as usize
+        };
+    }
+
+    let (id_len, header_len) = if version == 2 { (3, 6) } else { (4, 10) };
+    let mut tags = Vec::new();
+    while pos + header_len <= body.len() {
+        let header = &body[pos..pos + header_len];
+        if header[0] == 0 {
+            // Padding.
+            break;
+        }
+
+        let id = String::from_utf8_lossy(&header[..id_len]).into_owned();
+        let frame_size = match version {
+            2 => u32::from_be_bytes([0, header[3], header[4], header[5]]),
+            3 => u32::from_be_bytes([header[4], header[5], header[6], header[7]]),
+            _ => syncsafe(&header[4..8]),
+        } as usize;
+        let frame_flags = if version == 2 { 0 } else { header[9] };
+
+        pos += header_len;
+        let frame = match body.get(pos..pos + frame_size) {
+            Some(frame) => frame,
+            None => break,
+        };
+        pos += frame_size;
+
+        // Compressed and encrypted frames are not supported.
+        let (compressed, encrypted) = match version {
+            2 => (false, false),
+            3 => (frame_flags & 0x80 != 0, frame_flags & 0x40 != 0),
+            _ => (frame_flags & 0x08 != 0, frame_flags & 0x04 != 0),
+        };
+        if compressed || encrypted {
+            continue;
+        }
+
+        let mut frame = frame.to_vec();
+        if version >= 4 {
+            if frame_flags & 0x02 != 0 {
+                frame = remove_unsynchronization(&frame);
+            }
+            if frame_flags & 0x01 != 0 && frame.len() >= 4 {
+                // Skip the data length indicator.
+                frame.drain(..4);
+            }
+        }
+
+        let value = if id.starts_with('T') && id != "TXXX" && id != "TXX" {
+            frame
+                .split_first()
+                .map(|(encoding, text)| decode_text(*encoding, text).0)
+        } else if id == "COMM" || id == "COM" {
+            // Skip the language and short description.
+            frame.split_first().and_then(|(encoding, text)| {
+                let (_, description_len) = decode_text(*encoding, text.get(3..)?);
+                Some(decode_text(*encoding, text.get(3 + description_len..)?).0)
+            })
+        } else {
+            None
+        };
+
+        if let Some(value) = value {
+            if let Some((_, name)) = ID3_NAMES.iter().find(|(frame_id, _)| *frame_id == id) {
+                tags.retain(|(n, _)| n != name);
+                tags.push((name.to_string(), value.clone()));
+            }
+            tags.retain(|(n, _)| *n != id);
+            tags.push((id, value));
+        }
+    }
+
+    Some((tags, len))
+}
+
+/// Decode a 28-bit integer stored in 4 bytes, 7 bits per byte.
+fn syncsafe(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .fold(0, |value, byte| (value << 7) | u32::from(byte & 0x7F))
+}
+
+/// Undo ID3 unsynchronization, which inserts a zero byte after each 0xFF.
+fn remove_unsynchronization(data: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(data.len());
+    let mut last = 0;
+    for &byte in data {
+        if !(last == 0xFF && byte == 0) {
+            result.push(byte);
+        }
+        last = byte;
+    }
+    result
+}
+
+fn decode_latin1(data: &[u8]) -> String {
+    data.iter().map(|&b| char::from(b)).collect()
+}
+
+/// Decode a null-terminated ID3v2 string in the given encoding.
+///
+/// Returns the string, along with the number of bytes it occupied, including
+/// its terminator.
+fn decode_text(encoding: u8, data: &[u8]) -> (String, usize) {
+    match encoding {
+        // UTF-16, with or without a byte order mark.
+        1 | 2 => {
+            let end = data
+                .chunks(2)
+                .position(|c| c == [0, 0])
+                .map(|i| i * 2)
+                .unwrap_or(data.len() & !1);
+            let mut text = &data[..end];
+            let mut big_endian = encoding == 2;
+            if text.starts_with(&[0xFF, 0xFE]) {
+                big_endian = false;
+                text = &text[2..];
+            } else if text.starts_with(&[0xFE, 0xFF]) {
+                big_endian = true;
+                text = &text[2..];
+            }
+
+            let units: Vec<u16> = text
+                .chunks(2)
+                .map(|c| {
+                    if big_endian {
+                        u16::from_be_bytes([c[0], c[1]])
+                    } else {
+                        u16::from_le_bytes([c[0], c[1]])
+                    }
+                })
+                .collect();
+            (String::from_utf16_lossy(&units), (end + 2).min(data.len()))
+        }
+        _ => {
+            let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+            let text = if encoding == 3 {
+                String::from_utf8_lossy(&data[..end]).into_owned()
+            } else {
+                decode_latin1(&data[..end])
+            };
+            (text, (end + 1).min(data.len()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A silent MPEG-1 layer III frame at 128kbps, 44.1kHz, joint stereo.
+    fn frame() -> Vec<u8> {
+        let mut frame = vec![0; 417];
+        frame[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x44]);
+        frame
+    }
+
+    fn id3v2_frame(id: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut frame = id.to_vec();
+        frame.extend(&(data.len() as u32).to_be_bytes());
+        frame.extend(&[0, 0]);
+        frame.extend(data);
+        frame
+    }
+
+    #[test]
+    fn frames() {
+        let mut data = frame();
+        data.extend(frame());
+        data.extend(frame());
+
+        let mp3 = Mp3File::parse(&data).expect("Valid MP3");
+        assert_eq!(
+            mp3.format,
+            SoundFormat {
+                compression: AudioCompression::Mp3,
+                sample_rate: 44100,
+                is_stereo: true,
+                is_16_bit: true,
+            }
+        );
+        assert_eq!(mp3.num_samples, 3 * 1152);
+        assert_eq!(mp3.data.len(), data.len());
+        assert!(mp3.id3.is_empty());
+
+        assert_eq!(Mp3File::parse(b"not an mp3 file"), None);
+    }
+
+    #[test]
+    fn id3v1() {
+        let mut tag = vec![0; 128];
+        tag[..3].copy_from_slice(b"TAG");
+        tag[3..8].copy_from_slice(b"Title");
+        tag[33..39].copy_from_slice(b"Artist");
+        tag[93..97].copy_from_slice(b"2001");
+        tag[126] = 7;
+        tag[127] = 17;
+
+        let mut data = frame();
+        data.extend(&tag);
+
+        let mp3 = Mp3File::parse(&data).expect("Valid MP3");
+        assert_eq!(mp3.data.len(), 417);
+        assert_eq!(
+            mp3.id3,
+            vec![
+                ("songname".to_string(), "Title".to_string()),
+                ("artist".to_string(), "Artist".to_string()),
+                ("year".to_string(), "2001".to_string()),
+                ("track".to_string(), "7".to_string()),
+                ("genre".to_string(), "17".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_in_chunks() {
+        let mut frames = id3v2_frame(b"TIT2", b"\x00Title\x00");
+        frames.extend(&[0; 16]);
+
+        let mut data = b"ID3\x03\x00\x00".to_vec();
+        data.extend(&[0, 0, 0, frames.len() as u8]);
+        data.extend(&frames);
+        let tag_len = data.len();
+        data.extend(frame());
+        data.extend(frame());
+        let mut id3v1 = vec![0; 128];
+        id3v1[..3].copy_from_slice(b"TAG");
+        id3v1[33..39].copy_from_slice(b"Artist");
+        data.extend(&id3v1);
+
+        // The tag is read by the chunk which completes it.
+        let mut parser = Mp3Parser::new();
+        for (i, chunk) in data.chunks(7).enumerate() {
+            let end = (i + 1) * 7;
+            assert_eq!(
+                parser.push(chunk),
+                end >= tag_len && end - 7 < tag_len,
+                "chunk ending at {}",
+                end
+            );
+        }
+        assert_eq!(parser.id3().len(), 2);
+
+        let mp3 = parser.finish().expect("Valid MP3");
+        assert_eq!(Some(mp3), Mp3File::parse(&data));
+        let mp3 = Mp3File::parse(&data).unwrap();
+        assert_eq!(mp3.num_samples, 2 * 1152);
+        assert_eq!(mp3.data.len(), 2 * 417);
+        assert_eq!(
+            mp3.id3,
+            vec![
+                ("songname".to_string(), "Title".to_string()),
+                ("TIT2".to_string(), "Title".to_string()),
+                ("artist".to_string(), "Artist".to_string()),
+            ]
+        );
+
+        // Files too short to hold a tag are still parsed.
+        let mut parser = Mp3Parser::new();
+        parser.push(&frame()[..4]);
+        assert_eq!(parser.finish().map(|mp3| mp3.num_samples), Some(1152));
+    }
+
+    #[test]
+    fn id3v2() {
+        let mut frames = id3v2_frame(b"TIT2", b"\x00Title\x00");
+        frames.extend(id3v2_frame(b"TPE1", b"\x01\xFF\xFEA\x00r\x00t\x00"));
+        frames.extend(id3v2_frame(b"COMM", b"\x03engdesc\x00Comment"));
+        frames.extend(&[0; 16]);
+
+        let mut data = b"ID3\x03\x00\x00".to_vec();
+        data.extend(&[0, 0, 0, frames.len() as u8]);
+        data.extend(&frames);
+        data.extend(frame());
+
+        let mp3 = Mp3File::parse(&data).expect("Valid MP3");
+        assert_eq!(mp3.data.len(), 417);
+        assert_eq!(
+            mp3.id3,
+            vec![
+                ("songname".to_string(), "Title".to_string()),
+                ("TIT2".to_string(), "Title".to_string()),
+                ("artist".to_string(), "Art".to_string()),
+                ("TPE1".to_string(), "Art".to_string()),
+                ("comment".to_string(), "Comment".to_string()),
+                ("COMM".to_string(), "Comment".to_string()),
+            ]
+        );
+    }
+}
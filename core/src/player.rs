@@ -3,21 +3,27 @@ use crate::avm1::debug::VariableDumper;
 use crate::avm1::globals::system::SystemProperties;
 use crate::avm1::object::Object;
 use crate::avm1::property::Attribute;
-use crate::avm1::{Avm1, AvmString, ScriptObject, TObject, Timers, Value};
+use crate::avm1::{
+    Avm1, AvmString, LocalConnections, ScriptObject, TObject, Timers, Value, XmlSockets,
+};
 use crate::avm2::{Avm2, Domain as Avm2Domain};
 use crate::backend::{
     audio::{AudioBackend, AudioManager},
+    local_connection::LocalConnectionBackend,
     locale::LocaleBackend,
     log::LogBackend,
     navigator::{NavigatorBackend, RequestOptions},
     render::RenderBackend,
+    socket::SocketBackend,
     storage::StorageBackend,
     ui::{MouseCursor, UiBackend},
+    video::VideoBackend,
 };
-use crate::config::Letterbox;
+use crate::config::{Letterbox, StageAlign, StageScaleMode};
 use crate::context::{ActionQueue, ActionType, RenderContext, UpdateContext};
+use crate::context_menu::{ContextMenuCallback, ContextMenuItem, ContextMenuState};
 use crate::display_object::{EditText, MorphShape, MovieClip};
-use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult, KeyCode, PlayerEvent};
+use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult, KeyCode, MouseButton, PlayerEvent};
 use crate::external::Value as ExternalValue;
 use crate::external::{ExternalInterface, ExternalInterfaceProvider};
 use crate::focus_tracker::FocusTracker;
@@ -38,6 +44,7 @@ use std::ops::DerefMut;
 use std::sync::{Arc, Mutex, Weak};
 use std::time::Duration;
 
+/// The `DefineFont3` tag of the font used to display device text.
 pub static DEVICE_FONT_TAG: &[u8] = include_bytes!("../assets/noto-sans-definefont3.bin");
 
 /// The newest known Flash Player version, serves as a default to
@@ -79,6 +86,12 @@ struct GcRootData<'gc> {
 
     shared_objects: HashMap<String, Object<'gc>>,
 
+    /// Connected `LocalConnection` objects.
+    local_connections: LocalConnections<'gc>,
+
+    /// Open `XMLSocket` objects.
+    xml_sockets: XmlSockets<'gc>,
+
     /// Text fields with unbound variable bindings.
     unbound_text_fields: Vec<EditText<'gc>>,
 
@@ -93,6 +106,9 @@ struct GcRootData<'gc> {
 
     /// Manager of active sound instances.
     audio_manager: AudioManager<'gc>,
+
+    /// The context menu that is currently being displayed, if any.
+    current_context_menu: Option<ContextMenuState<'gc>>,
 }
 
 impl<'gc> GcRootData<'gc> {
@@ -110,10 +126,13 @@ impl<'gc> GcRootData<'gc> {
         &mut Option<DragObject<'gc>>,
         &mut LoadManager<'gc>,
         &mut HashMap<String, Object<'gc>>,
+        &mut LocalConnections<'gc>,
+        &mut XmlSockets<'gc>,
         &mut Vec<EditText<'gc>>,
         &mut Timers<'gc>,
         &mut ExternalInterface<'gc>,
         &mut AudioManager<'gc>,
+        &mut Option<ContextMenuState<'gc>>,
     ) {
         (
             &mut self.levels,
@@ -124,10 +143,13 @@ impl<'gc> GcRootData<'gc> {
             &mut self.drag_object,
             &mut self.load_manager,
             &mut self.shared_objects,
+            &mut self.local_connections,
+            &mut self.xml_sockets,
             &mut self.unbound_text_fields,
             &mut self.timers,
             &mut self.external_interface,
             &mut self.audio_manager,
+            &mut self.current_context_menu,
         )
     }
 }
@@ -137,11 +159,14 @@ make_arena!(GcArena, GcRoot);
 
 type Audio = Box<dyn AudioBackend>;
 type Navigator = Box<dyn NavigatorBackend>;
+type Sockets = Box<dyn SocketBackend>;
 type Renderer = Box<dyn RenderBackend>;
 type Storage = Box<dyn StorageBackend>;
+type LocalConnection = Box<dyn LocalConnectionBackend>;
 type Locale = Box<dyn LocaleBackend>;
 type Log = Box<dyn LogBackend>;
 type UI = Box<dyn UiBackend>;
+type Video = Box<dyn VideoBackend>;
 
 pub struct Player {
     /// The version of the player we're emulating.
@@ -165,8 +190,11 @@ pub struct Player {
 
     renderer: Renderer,
     audio: Audio,
+    video: Video,
     navigator: Navigator,
+    sockets: Sockets,
     storage: Storage,
+    local_connection: LocalConnection,
     locale: Locale,
     log: Log,
     ui: UI,
@@ -198,6 +226,11 @@ pub struct Player {
     movie_width: u32,
     movie_height: u32,
     letterbox: Letterbox,
+    scale_mode: StageScaleMode,
+    stage_align: StageAlign,
+
+    /// Whether the built-in items of the context menu are shown.
+    show_menu: bool,
 
     mouse_pos: (Twips, Twips),
     is_mouse_down: bool,
@@ -205,6 +238,10 @@ pub struct Player {
     /// The current mouse cursor icon.
     mouse_cursor: MouseCursor,
 
+    /// Whether the last key press moved focus with Tab, so the tab character
+    /// it also produces should not be typed into the newly focused text field.
+    swallow_tab_input: bool,
+
     system: SystemProperties,
 
     /// The current instance ID. Used to generate default `instanceN` names.
@@ -234,8 +271,11 @@ impl Player {
     pub fn new(
         renderer: Renderer,
         audio: Audio,
+        video: Video,
         navigator: Navigator,
+        sockets: Sockets,
         storage: Storage,
+        local_connection: LocalConnection,
         locale: Locale,
         log: Log,
         ui: UI,
@@ -276,11 +316,14 @@ impl Player {
                         action_queue: ActionQueue::new(),
                         load_manager: LoadManager::new(),
                         shared_objects: HashMap::new(),
+                        local_connections: LocalConnections::new(),
+                        xml_sockets: XmlSockets::new(),
                         unbound_text_fields: Vec::new(),
                         timers: Timers::new(),
                         external_interface: ExternalInterface::new(),
                         focus_tracker: FocusTracker::new(gc_context),
                         audio_manager: AudioManager::new(),
+                        current_context_menu: None,
                     },
                 ))
             }),
@@ -295,14 +338,20 @@ impl Player {
             viewport_width: movie_width,
             viewport_height: movie_height,
             letterbox: Letterbox::Fullscreen,
+            scale_mode: Default::default(),
+            stage_align: Default::default(),
+            show_menu: true,
 
             mouse_pos: (Twips::new(0), Twips::new(0)),
             is_mouse_down: false,
             mouse_cursor: MouseCursor::Arrow,
+            swallow_tab_input: false,
 
             renderer,
             audio,
+            video,
             navigator,
+            sockets,
             locale,
             log,
             ui,
@@ -311,6 +360,7 @@ impl Player {
             instance_counter: 0,
             time_til_next_timer: None,
             storage,
+            local_connection,
             max_execution_duration: Duration::from_secs(15),
             current_frame: None,
         };
@@ -521,7 +571,7 @@ impl Player {
             }
 
             self.update_timers(dt);
-            self.audio.tick();
+            self.audio.tick(dt);
         }
     }
 
@@ -581,8 +631,39 @@ impl Player {
     }
 
     fn should_letterbox(&self) -> bool {
-        self.letterbox == Letterbox::On
-            || (self.letterbox == Letterbox::Fullscreen && self.ui.is_fullscreen())
+        // Only `showAll` leaves margins around the movie.
+        self.scale_mode == StageScaleMode::ShowAll
+            && (self.letterbox == Letterbox::On
+                || (self.letterbox == Letterbox::Fullscreen && self.ui.is_fullscreen()))
+    }
+
+    pub fn scale_mode(&self) -> StageScaleMode {
+        self.scale_mode
+    }
+
+    /// Sets how the movie is scaled to fit the viewport, like the `scale` embed parameter.
+    pub fn set_scale_mode(&mut self, scale_mode: StageScaleMode) {
+        self.scale_mode = scale_mode;
+        self.build_matrices();
+    }
+
+    pub fn stage_align(&self) -> StageAlign {
+        self.stage_align
+    }
+
+    /// Sets how the movie is aligned within the viewport, like the `salign` embed parameter.
+    pub fn set_stage_align(&mut self, stage_align: StageAlign) {
+        self.stage_align = stage_align;
+        self.build_matrices();
+    }
+
+    pub fn show_menu(&self) -> bool {
+        self.show_menu
+    }
+
+    /// Sets whether the built-in items of the context menu are shown, like the `menu` embed parameter.
+    pub fn set_show_menu(&mut self, show_menu: bool) {
+        self.show_menu = show_menu;
     }
 
     pub fn warn_on_unsupported_content(&self) -> bool {
@@ -606,9 +687,28 @@ impl Player {
     }
 
     pub fn set_viewport_dimensions(&mut self, width: u32, height: u32) {
+        let is_resized = (width, height) != (self.viewport_width, self.viewport_height);
         self.viewport_width = width;
         self.viewport_height = height;
         self.build_matrices();
+
+        // The stage only changes size in `noScale` mode, so this is the only time
+        // that Flash broadcasts `Stage.onResize`.
+        if is_resized && self.scale_mode == StageScaleMode::NoScale {
+            self.update(|context| {
+                if let Some(root) = context.levels.get(&0).copied() {
+                    context.action_queue.queue_actions(
+                        root,
+                        ActionType::NotifyListeners {
+                            listener: "Stage",
+                            method: "onResize",
+                            args: vec![],
+                        },
+                        false,
+                    );
+                }
+            });
+        }
     }
 
     pub fn handle_event(&mut self, event: PlayerEvent) {
@@ -676,8 +776,8 @@ impl Player {
 
         // Update mouse position from mouse events.
         if let PlayerEvent::MouseMove { x, y }
-        | PlayerEvent::MouseDown { x, y }
-        | PlayerEvent::MouseUp { x, y } = event
+        | PlayerEvent::MouseDown { x, y, .. }
+        | PlayerEvent::MouseUp { x, y, .. } = event
         {
             self.mouse_pos =
                 self.inverse_view_matrix * (Twips::from_pixels(x), Twips::from_pixels(y));
@@ -686,6 +786,31 @@ impl Player {
             }
         }
 
+        // Extend the selection of a text field while dragging over it, or
+        // otherwise track the link under the mouse.
+        if let PlayerEvent::MouseMove { .. } = event {
+            let is_mouse_down = self.is_mouse_down;
+            let new_cursor = self.mutate_with_update_context(|context| {
+                let text = context.mouse_hovered_object?.as_edit_text()?;
+                if is_mouse_down {
+                    text.mouse_drag(context);
+                } else {
+                    text.mouse_move(context);
+                }
+                needs_render = true;
+
+                // The cursor changes as the mouse moves over links.
+                Some(text.mouse_cursor())
+            });
+
+            if let Some(new_cursor) = new_cursor {
+                if new_cursor != self.mouse_cursor {
+                    self.mouse_cursor = new_cursor;
+                    self.ui.set_mouse_cursor(new_cursor);
+                }
+            }
+        }
+
         // Propagate button events.
         let button_event = match event {
             // ASCII characters convert directly to keyPress button events.
@@ -722,10 +847,70 @@ impl Player {
             });
         }
 
-        if let PlayerEvent::TextInput { codepoint } = event {
+        if let PlayerEvent::KeyDown { key_code } = event {
             self.mutate_with_update_context(|context| {
                 if let Some(text) = context.focus_tracker.get().and_then(|o| o.as_edit_text()) {
-                    text.text_input(codepoint, context);
+                    text.key_down(key_code, context);
+                }
+            });
+        }
+
+        // Keyboard navigation: Tab moves focus, and Enter or Space activates the focused button.
+        if let PlayerEvent::KeyDown { key_code } = event {
+            let needs_render = self.mutate_with_update_context(|context| match key_code {
+                KeyCode::Tab => {
+                    let reverse = context.ui.is_key_down(KeyCode::Shift);
+                    let tracker = context.focus_tracker;
+                    tracker.cycle(context, reverse)
+                }
+                KeyCode::Return | KeyCode::Space => {
+                    let focus = context
+                        .focus_tracker
+                        .get()
+                        .filter(|o| o.as_edit_text().is_none() && !o.removed());
+                    if let Some(focus) = focus {
+                        focus.handle_clip_event(context, ClipEvent::Press);
+                        focus.handle_clip_event(context, ClipEvent::Release);
+                        Self::run_actions(context);
+                        true
+                    } else {
+                        false
+                    }
+                }
+                _ => false,
+            });
+            if needs_render {
+                self.needs_render = true;
+            }
+            self.swallow_tab_input = key_code == KeyCode::Tab && needs_render;
+        }
+
+        if let PlayerEvent::TextInput { codepoint } = event {
+            // The Tab key that moved focus also produces a tab character.
+            let swallow = codepoint == '\t' && std::mem::take(&mut self.swallow_tab_input);
+            if !swallow {
+                self.mutate_with_update_context(|context| {
+                    if let Some(text) = context.focus_tracker.get().and_then(|o| o.as_edit_text()) {
+                        text.text_input(codepoint, context);
+                    }
+                });
+            }
+        }
+
+        if let PlayerEvent::TextPaste { text } = &event {
+            self.mutate_with_update_context(|context| {
+                if let Some(edit_text) = context.focus_tracker.get().and_then(|o| o.as_edit_text())
+                {
+                    edit_text.paste(text, context);
+                }
+            });
+        }
+
+        // Scroll the text field under the mouse.
+        if let PlayerEvent::MouseWheel { delta } = event {
+            self.mutate_with_update_context(|context| {
+                if let Some(text) = context.mouse_hovered_object.and_then(|o| o.as_edit_text()) {
+                    text.mouse_wheel(delta.lines(), context);
                 }
             });
         }
@@ -743,11 +928,17 @@ impl Player {
                     Some(ClipEvent::MouseMove),
                     Some(("Mouse", "onMouseMove", vec![])),
                 ),
-                PlayerEvent::MouseUp { .. } => (
+                PlayerEvent::MouseUp {
+                    button: MouseButton::Left,
+                    ..
+                } => (
                     Some(ClipEvent::MouseUp),
                     Some(("Mouse", "onMouseUp", vec![])),
                 ),
-                PlayerEvent::MouseDown { .. } => (
+                PlayerEvent::MouseDown {
+                    button: MouseButton::Left,
+                    ..
+                } => (
                     Some(ClipEvent::MouseDown),
                     Some(("Mouse", "onMouseDown", vec![])),
                 ),
@@ -789,15 +980,22 @@ impl Player {
             }
 
             match event {
-                PlayerEvent::MouseDown { .. } => {
+                PlayerEvent::MouseDown {
+                    button: MouseButton::Left,
+                    ..
+                } => {
                     is_mouse_down = true;
                     needs_render = true;
+                    context.focus_tracker.reset_highlight(context.gc_context);
                     if let Some(node) = context.mouse_hovered_object {
                         node.handle_clip_event(context, ClipEvent::Press);
                     }
                 }
 
-                PlayerEvent::MouseUp { .. } => {
+                PlayerEvent::MouseUp {
+                    button: MouseButton::Left,
+                    ..
+                } => {
                     is_mouse_down = false;
                     needs_render = true;
                     if let Some(node) = context.mouse_hovered_object {
@@ -816,6 +1014,147 @@ impl Player {
         }
     }
 
+    /// Builds the context menu for the display object under the mouse cursor.
+    ///
+    /// The menu contains the custom items of the AVM1 `ContextMenu` attached to the
+    /// object or its nearest ancestor, followed by the built-in player items allowed by
+    /// `Stage.showMenu` and the menu's `builtInItems`. The frontend should display the
+    /// returned items and then call either `run_context_menu_callback` with the index of
+    /// the chosen item, or `clear_custom_menu_items` if the menu was dismissed.
+    pub fn prepare_context_menu(&mut self) -> Vec<ContextMenuItem> {
+        let mouse_pos = self.mouse_pos;
+        self.mutate_with_update_context(|context| {
+            let levels: Vec<DisplayObject<'_>> = context.levels.values().copied().collect();
+            let mut display_object = levels
+                .iter()
+                .rev()
+                .find_map(|level| Self::display_object_at(context, *level, mouse_pos))
+                .or_else(|| levels.first().copied());
+
+            let mut activation = Activation::from_stub(
+                context.reborrow(),
+                ActivationIdentifier::root("[Context Menu]"),
+            );
+
+            // Use the `menu` of the innermost object that has one.
+            let mut menu = None;
+            while let Some(current) = display_object {
+                if let Value::Object(object) = current.object() {
+                    if let Ok(Value::Object(context_menu)) = object.get("menu", &mut activation) {
+                        menu = Some((object, context_menu));
+                        break;
+                    }
+                }
+                display_object = current.parent();
+            }
+
+            // `ContextMenu.onSelect` is called before the menu is displayed,
+            // giving the movie a chance to update the items.
+            if let Some((object, context_menu)) = menu {
+                if let Ok(Value::Object(callback)) = context_menu.get("onSelect", &mut activation) {
+                    let args = [object.into(), context_menu.into()];
+                    if let Err(e) = callback.call(
+                        "[Context Menu Callback]",
+                        &mut activation,
+                        context_menu,
+                        None,
+                        &args,
+                    ) {
+                        log::error!("Error in ContextMenu.onSelect: {}", e);
+                    }
+                }
+            }
+
+            let state =
+                crate::avm1::globals::context_menu::make_context_menu_state(menu, &mut activation)
+                    .unwrap_or_else(|e| {
+                        log::error!("Error building context menu: {}", e);
+                        ContextMenuState::new()
+                    });
+            let items = state.info().to_vec();
+            *activation.context.current_context_menu = Some(state);
+            items
+        })
+    }
+
+    /// Runs the action of the context menu item at `index` of the menu returned by
+    /// `prepare_context_menu`, and closes the menu.
+    pub fn run_context_menu_callback(&mut self, index: usize) {
+        self.mutate_with_update_context(|context| {
+            let callback = context
+                .current_context_menu
+                .take()
+                .and_then(|menu| menu.callback(index));
+            let root = context.levels.get(&0).and_then(|root| root.as_movie_clip());
+
+            match (callback, root) {
+                (Some(ContextMenuCallback::Avm1 { item, target }), _) => {
+                    let mut activation = Activation::from_stub(
+                        context.reborrow(),
+                        ActivationIdentifier::root("[Context Menu Callback]"),
+                    );
+                    if let Ok(Value::Object(callback)) = item.get("onSelect", &mut activation) {
+                        let args = [target.into(), item.into()];
+                        if let Err(e) = callback.call(
+                            "[Context Menu Callback]",
+                            &mut activation,
+                            item,
+                            None,
+                            &args,
+                        ) {
+                            log::error!("Error in ContextMenuItem.onSelect: {}", e);
+                        }
+                    }
+                }
+                (Some(ContextMenuCallback::Play), Some(root)) => {
+                    if root.playing() {
+                        root.stop(context);
+                    } else {
+                        root.play(context);
+                    }
+                }
+                (Some(ContextMenuCallback::Rewind), Some(root)) => {
+                    root.goto_frame(context, 1, true)
+                }
+                (Some(ContextMenuCallback::Forward), Some(root)) => root.next_frame(context),
+                (Some(ContextMenuCallback::Back), Some(root)) => root.prev_frame(context),
+                _ => return,
+            }
+
+            Self::run_actions(context);
+            *context.needs_render = true;
+        });
+    }
+
+    /// Closes the context menu without running any of its items.
+    pub fn clear_custom_menu_items(&mut self) {
+        self.mutate_with_update_context(|context| {
+            *context.current_context_menu = None;
+        });
+    }
+
+    /// Returns the innermost visible display object rendered at `point`.
+    fn display_object_at<'gc>(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        display_object: DisplayObject<'gc>,
+        point: (Twips, Twips),
+    ) -> Option<DisplayObject<'gc>> {
+        if !display_object.visible() || !display_object.hit_test_shape(context, point) {
+            return None;
+        }
+
+        if let Some(container) = display_object.as_container() {
+            for child in container.iter_render_list().rev() {
+                let result = Self::display_object_at(context, child, point);
+                if result.is_some() {
+                    return result;
+                }
+            }
+        }
+
+        Some(display_object)
+    }
+
     /// Update dragged object, if any.
     fn update_drag(&mut self) {
         let mouse_pos = self.mouse_pos;
@@ -931,6 +1270,10 @@ impl Player {
 
     pub fn run_frame(&mut self) {
         self.update(|update_context| {
+            // Messages sent during the previous frame are delivered first.
+            LocalConnections::update_local_connections(update_context);
+            XmlSockets::update_sockets(update_context);
+
             // TODO: In what order are levels run?
             // NOTE: We have to copy all the layer pointers into a separate list
             // because level updates can create more levels, which we don't
@@ -947,6 +1290,15 @@ impl Player {
     }
 
     pub fn render(&mut self) {
+        self.render_with_overlay(|_| ());
+    }
+
+    /// Renders the player, then calls `overlay` to draw on top of it before the
+    /// frame is finished. The overlay is drawn in viewport pixels.
+    ///
+    /// This lets a frontend draw its own interface, such as a context menu,
+    /// into the same window as the movie.
+    pub fn render_with_overlay(&mut self, overlay: impl FnOnce(&mut dyn RenderBackend)) {
         let background_color = self
             .background_color
             .clone()
@@ -975,6 +1327,10 @@ impl Player {
             for (_depth, level) in root_data.levels.iter() {
                 level.render(&mut render_context);
             }
+
+            root_data
+                .focus_tracker
+                .render_highlight(&mut render_context);
         });
         transform_stack.pop();
 
@@ -982,6 +1338,8 @@ impl Player {
             self.draw_letterbox();
         }
 
+        overlay(self.renderer.deref_mut());
+
         self.renderer.end_frame();
         self.needs_render = false;
     }
@@ -1021,6 +1379,10 @@ impl Player {
         &mut self.storage
     }
 
+    pub fn sockets_mut(&mut self) -> &mut Sockets {
+        &mut self.sockets
+    }
+
     pub fn destroy(self) -> Renderer {
         self.renderer
     }
@@ -1155,20 +1517,47 @@ impl Player {
         let (movie_width, movie_height) = (self.movie_width as f32, self.movie_height as f32);
         let (viewport_width, viewport_height) =
             (self.viewport_width as f32, self.viewport_height as f32);
-        let movie_aspect = movie_width / movie_height;
-        let viewport_aspect = viewport_width / viewport_height;
-        let (scale, margin_width, margin_height) = if viewport_aspect > movie_aspect {
-            let scale = viewport_height / movie_height;
-            (scale, (viewport_width - movie_width * scale) / 2.0, 0.0)
-        } else {
-            let scale = viewport_width / movie_width;
-            (scale, 0.0, (viewport_height - movie_height * scale) / 2.0)
+        let (scale_x, scale_y) = match self.scale_mode {
+            StageScaleMode::ShowAll => {
+                let scale = (viewport_width / movie_width).min(viewport_height / movie_height);
+                (scale, scale)
+            }
+            StageScaleMode::NoBorder => {
+                let scale = (viewport_width / movie_width).max(viewport_height / movie_height);
+                (scale, scale)
+            }
+            StageScaleMode::ExactFit => {
+                (viewport_width / movie_width, viewport_height / movie_height)
+            }
+            StageScaleMode::NoScale => (1.0, 1.0),
         };
+
+        // Align the movie within any extra space; the top and left edges take priority.
+        let align_offset = |extra: f32, near: StageAlign, far: StageAlign| {
+            if self.stage_align.contains(near) {
+                0.0
+            } else if self.stage_align.contains(far) {
+                extra
+            } else {
+                extra / 2.0
+            }
+        };
+        let margin_width = align_offset(
+            viewport_width - movie_width * scale_x,
+            StageAlign::LEFT,
+            StageAlign::RIGHT,
+        );
+        let margin_height = align_offset(
+            viewport_height - movie_height * scale_y,
+            StageAlign::TOP,
+            StageAlign::BOTTOM,
+        );
+
         self.view_matrix = Matrix {
-            a: scale,
+            a: scale_x,
             b: 0.0,
             c: 0.0,
-            d: scale,
+            d: scale_y,
             tx: Twips::from_pixels(margin_width.into()),
             ty: Twips::from_pixels(margin_height.into()),
         };
@@ -1176,7 +1565,7 @@ impl Player {
         self.inverse_view_matrix.invert();
 
         self.view_bounds = if self.should_letterbox() {
-            // No letterbox: movie area
+            // Letterbox: movie area
             BoundingBox {
                 x_min: Twips::new(0),
                 y_min: Twips::new(0),
@@ -1186,13 +1575,11 @@ impl Player {
             }
         } else {
             // No letterbox: full visible stage area
-            let margin_width = f64::from(margin_width / scale);
-            let margin_height = f64::from(margin_height / scale);
             BoundingBox {
-                x_min: Twips::from_pixels(-margin_width),
-                y_min: Twips::from_pixels(-margin_height),
-                x_max: Twips::from_pixels(f64::from(self.movie_width) + margin_width),
-                y_max: Twips::from_pixels(f64::from(self.movie_height) + margin_height),
+                x_min: Twips::from_pixels(f64::from(-margin_width / scale_x)),
+                y_min: Twips::from_pixels(f64::from(-margin_height / scale_y)),
+                x_max: Twips::from_pixels(f64::from((viewport_width - margin_width) / scale_x)),
+                y_max: Twips::from_pixels(f64::from((viewport_height - margin_height) / scale_y)),
                 valid: true,
             }
         };
@@ -1212,16 +1599,23 @@ impl Player {
             background_color,
             renderer,
             audio,
+            video,
             navigator,
+            sockets,
             ui,
             rng,
             mouse_position,
             stage_width,
             stage_height,
+            viewport_size,
+            scale_mode,
+            stage_align,
+            show_menu,
             player,
             system_properties,
             instance_counter,
             storage,
+            local_connection,
             locale,
             logging,
             needs_render,
@@ -1234,16 +1628,26 @@ impl Player {
             &mut self.background_color,
             self.renderer.deref_mut(),
             self.audio.deref_mut(),
+            self.video.deref_mut(),
             self.navigator.deref_mut(),
+            self.sockets.deref_mut(),
             self.ui.deref_mut(),
             &mut self.rng,
             &self.mouse_pos,
             Twips::from_pixels(self.movie_width.into()),
             Twips::from_pixels(self.movie_height.into()),
+            (
+                Twips::from_pixels(self.viewport_width.into()),
+                Twips::from_pixels(self.viewport_height.into()),
+            ),
+            &mut self.scale_mode,
+            &mut self.stage_align,
+            &mut self.show_menu,
             self.self_reference.clone(),
             &mut self.system,
             &mut self.instance_counter,
             self.storage.deref_mut(),
+            self.local_connection.deref_mut(),
             self.locale.deref_mut(),
             self.log.deref_mut(),
             &mut self.needs_render,
@@ -1251,8 +1655,9 @@ impl Player {
             &mut self.current_frame,
             &mut self.time_offset,
         );
+        let old_layout = (*scale_mode, *stage_align);
 
-        self.gc_arena.mutate(|gc_context, gc_root| {
+        let ret = self.gc_arena.mutate(|gc_context, gc_root| {
             let mut root_data = gc_root.0.write(gc_context);
             let mouse_hovered_object = root_data.mouse_hovered_object;
             let focus_tracker = root_data.focus_tracker;
@@ -1265,10 +1670,13 @@ impl Player {
                 drag_object,
                 load_manager,
                 shared_objects,
+                local_connections,
+                xml_sockets,
                 unbound_text_fields,
                 timers,
                 external_interface,
                 audio_manager,
+                current_context_menu,
             ) = root_data.update_context_params();
 
             let mut update_context = UpdateContext {
@@ -1279,7 +1687,9 @@ impl Player {
                 rng,
                 renderer,
                 audio,
+                video,
                 navigator,
+                sockets,
                 ui,
                 action_queue,
                 gc_context,
@@ -1288,14 +1698,22 @@ impl Player {
                 mouse_position,
                 drag_object,
                 stage_size: (stage_width, stage_height),
+                viewport_size,
+                scale_mode,
+                stage_align,
+                show_menu,
+                current_context_menu,
                 player,
                 load_manager,
                 system: system_properties,
                 instance_counter,
                 storage,
+                local_connection,
                 locale,
                 log: logging,
                 shared_objects,
+                local_connections,
+                xml_sockets,
                 unbound_text_fields,
                 timers,
                 needs_render,
@@ -1322,7 +1740,15 @@ impl Player {
             root_data.mouse_hovered_object = update_context.mouse_hovered_object;
 
             ret
-        })
+        });
+
+        // The movie may have changed `Stage.scaleMode` or `Stage.align`.
+        if (self.scale_mode, self.stage_align) != old_layout {
+            self.build_matrices();
+            self.needs_render = true;
+        }
+
+        ret
     }
 
     /// Loads font data from the given buffer.
@@ -1434,50 +1860,31 @@ impl Player {
         let viewport_width = self.viewport_width as f32;
         let viewport_height = self.viewport_height as f32;
 
-        let margin_width = self.view_matrix.tx.to_pixels() as f32;
-        let margin_height = self.view_matrix.ty.to_pixels() as f32;
-        if margin_height > 0.0 {
-            self.renderer.draw_rect(
-                black.clone(),
-                &Matrix::create_box(
-                    viewport_width,
-                    margin_height,
-                    0.0,
-                    Twips::default(),
-                    Twips::default(),
-                ),
-            );
-            self.renderer.draw_rect(
-                black,
-                &Matrix::create_box(
-                    viewport_width,
-                    margin_height,
-                    0.0,
-                    Twips::default(),
-                    Twips::from_pixels((viewport_height - margin_height) as f64),
-                ),
-            );
-        } else if margin_width > 0.0 {
-            self.renderer.draw_rect(
-                black.clone(),
-                &Matrix::create_box(
-                    margin_width,
-                    viewport_height,
-                    0.0,
-                    Twips::default(),
-                    Twips::default(),
-                ),
-            );
-            self.renderer.draw_rect(
-                black,
-                &Matrix::create_box(
-                    margin_width,
-                    viewport_height,
-                    0.0,
-                    Twips::from_pixels((viewport_width - margin_width) as f64),
-                    Twips::default(),
-                ),
-            );
+        // The margins may be uneven depending on the stage alignment.
+        let left = self.view_matrix.tx.to_pixels() as f32;
+        let top = self.view_matrix.ty.to_pixels() as f32;
+        let right = left + self.movie_width as f32 * self.view_matrix.a;
+        let bottom = top + self.movie_height as f32 * self.view_matrix.d;
+
+        let bars = [
+            (0.0, 0.0, viewport_width, top),
+            (0.0, bottom, viewport_width, viewport_height),
+            (0.0, 0.0, left, viewport_height),
+            (right, 0.0, viewport_width, viewport_height),
+        ];
+        for &(x_min, y_min, x_max, y_max) in &bars {
+            if x_max > x_min && y_max > y_min {
+                self.renderer.draw_rect(
+                    black.clone(),
+                    &Matrix::create_box(
+                        x_max - x_min,
+                        y_max - y_min,
+                        0.0,
+                        Twips::from_pixels(x_min.into()),
+                        Twips::from_pixels(y_min.into()),
+                    ),
+                );
+            }
         }
     }
 }
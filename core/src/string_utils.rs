@@ -24,6 +24,24 @@ pub fn next_char_boundary(slice: &str, pos: usize) -> usize {
     }
 }
 
+/// Gets the byte position of the start of the word before `pos`
+/// Any non-word characters immediately before `pos` are skipped.
+pub fn prev_word_boundary(slice: &str, pos: usize) -> usize {
+    slice[..pos]
+        .trim_end_matches(|c: char| !c.is_alphanumeric())
+        .trim_end_matches(char::is_alphanumeric)
+        .len()
+}
+
+/// Gets the byte position of the start of the word after `pos`
+/// `pos` must already lie on a char boundary
+pub fn next_word_boundary(slice: &str, pos: usize) -> usize {
+    let rest = slice[pos..]
+        .trim_start_matches(char::is_alphanumeric)
+        .trim_start_matches(|c: char| !c.is_alphanumeric());
+    slice.len() - rest.len()
+}
+
 /// Creates a `String` from an iterator of UTF-16 code units.
 /// TODO: Unpaired surrogates will get replaced with the Unicode replacement character.
 pub fn utf16_iter_to_string<I: Iterator<Item = u16>>(it: I) -> String {
@@ -1556,3 +1574,39 @@ static LOWERCASE_TABLE: &[(u16, u16)] = &[
     (65337, 65369),
     (65338, 65370),
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn char_boundaries() {
+        assert_eq!(prev_char_boundary("aé", 3), 1);
+        assert_eq!(prev_char_boundary("aé", 1), 0);
+        assert_eq!(prev_char_boundary("aé", 0), 0);
+        assert_eq!(next_char_boundary("éa", 0), 2);
+        assert_eq!(next_char_boundary("éa", 2), 3);
+        assert_eq!(next_char_boundary("éa", 3), 3);
+    }
+
+    #[test]
+    fn prev_word_boundaries() {
+        assert_eq!(prev_word_boundary("hello world", 11), 6);
+        assert_eq!(prev_word_boundary("hello world", 8), 6);
+        assert_eq!(prev_word_boundary("hello world", 6), 0);
+        assert_eq!(prev_word_boundary("hello world  ", 13), 6);
+        assert_eq!(prev_word_boundary("hello, world", 7), 0);
+        assert_eq!(prev_word_boundary("naïve café", 12), 7);
+        assert_eq!(prev_word_boundary("", 0), 0);
+    }
+
+    #[test]
+    fn next_word_boundaries() {
+        assert_eq!(next_word_boundary("hello world", 0), 6);
+        assert_eq!(next_word_boundary("hello world", 3), 6);
+        assert_eq!(next_word_boundary("hello world", 6), 11);
+        assert_eq!(next_word_boundary("hello, world", 5), 7);
+        assert_eq!(next_word_boundary("naïve café", 0), 7);
+        assert_eq!(next_word_boundary("", 0), 0);
+    }
+}
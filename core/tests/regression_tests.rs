@@ -4,19 +4,24 @@
 
 use approx::assert_relative_eq;
 use ruffle_core::backend::{
-    audio::NullAudioBackend,
+    audio::{capture::CaptureAudioBackend, AudioBackend, NullAudioBackend},
+    local_connection::MemoryLocalConnectionBackend,
     locale::NullLocaleBackend,
     log::LogBackend,
     navigator::{NullExecutor, NullNavigatorBackend},
     render::NullRenderer,
+    socket::{NullSocketBackend, SocketBackend, SocketEvent, SocketHandle},
     storage::{MemoryStorageBackend, StorageBackend},
-    ui::NullUiBackend,
+    ui::{MouseCursor, NullUiBackend, UiBackend},
+    video::NullVideoBackend,
 };
 use ruffle_core::context::UpdateContext;
+use ruffle_core::context_menu::ContextMenuItem;
+use ruffle_core::events::{KeyCode, MouseButton};
 use ruffle_core::external::Value as ExternalValue;
 use ruffle_core::external::{ExternalInterfaceMethod, ExternalInterfaceProvider};
 use ruffle_core::tag_utils::SwfMovie;
-use ruffle_core::Player;
+use ruffle_core::{Player, PlayerEvent};
 use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::path::Path;
@@ -224,6 +229,7 @@ swf_tests! {
     (mcl_unloadclip, "avm1/mcl_unloadclip", 11),
     (mcl_getprogress, "avm1/mcl_getprogress", 6),
     (load_vars, "avm1/load_vars", 2),
+    (local_connection, "avm1/local_connection", 3),
     (loadvariables, "avm1/loadvariables", 3),
     (loadvariablesnum, "avm1/loadvariablesnum", 3),
     (loadvariables_method, "avm1/loadvariables_method", 3),
@@ -269,6 +275,7 @@ swf_tests! {
     (textfield_background_color, "avm1/textfield_background_color", 1),
     (textfield_border_color, "avm1/textfield_border_color", 1),
     (textfield_variable, "avm1/textfield_variable", 8),
+    (edittext_scroll, "avm1/edittext_scroll", 3),
     (error, "avm1/error", 1),
     (color_transform, "avm1/color_transform", 1),
     (with, "avm1/with", 1),
@@ -317,10 +324,18 @@ swf_tests! {
     (gradient_glow_filter, "avm1/gradient_glow_filter", 1),
     (bitmap_data, "avm1/bitmap_data", 1),
     (bitmap_data_noise, "avm1/bitmap_data_noise", 1),
+    (bitmap_data_threshold, "avm1/bitmap_data_threshold", 1),
+    (bitmap_data_palette_map, "avm1/bitmap_data_palette_map", 1),
+    (bitmap_data_merge, "avm1/bitmap_data_merge", 1),
+    (bitmap_data_hit_test, "avm1/bitmap_data_hit_test", 1),
+    (bitmap_data_apply_filter, "avm1/bitmap_data_apply_filter", 1),
+    (bitmap_data_pixel_dissolve, "avm1/bitmap_data_pixel_dissolve", 1),
     (array_call_method, "avm1/array_call_method", 1),
     (bad_placeobject_clipaction, "avm1/bad_placeobject_clipaction", 2),
     (bad_swf_tag_past_eof, "avm1/bad_swf_tag_past_eof", 1),
     (sound, "avm1/sound", 1),
+    (sound_load_sound, "avm1/sound_load_sound", 2),
+    (import_assets, "avm1/import_assets", 4),
     (action_to_integer, "avm1/action_to_integer", 1),
     (as3_hello_world, "avm2/hello_world", 1),
     (as3_function_call, "avm2/function_call", 1),
@@ -509,6 +524,28 @@ swf_tests! {
     (as3_string_length, "avm2/string_length", 1),
     (as3_string_char_at, "avm2/string_char_at", 1),
     (as3_string_char_code_at, "avm2/string_char_code_at", 1),
+    (as3_lookupswitch, "avm2/lookupswitch", 1),
+    (as3_hasnext2, "avm2/hasnext2", 1),
+    (as3_typeof, "avm2/typeof", 1),
+    (as3_coerce, "avm2/coerce", 1),
+    (as3_astype, "avm2/astype", 1),
+    (as3_astypelate, "avm2/astypelate", 1),
+    (as3_nop, "avm2/nop", 1),
+    (as3_dxns, "avm2/dxns", 1),
+    (as3_dxnslate, "avm2/dxnslate", 1),
+    (as3_escxelem, "avm2/escxelem", 1),
+    (as3_escxattr, "avm2/escxattr", 1),
+    (as3_getdescendants, "avm2/getdescendants", 1),
+    (as3_checkfilter, "avm2/checkfilter", 1),
+    (as3_xml_descendants, "avm2/xml_descendants", 1),
+    (as3_try_catch_finally, "avm2/try_catch_finally", 1),
+    (as3_exception_rethrow, "avm2/exception_rethrow", 1),
+    (as3_error_subclasses, "avm2/error_subclasses", 1),
+    (as3_bytearray, "avm2/bytearray", 1),
+    (as3_dictionary, "avm2/dictionary", 1),
+    (as3_timer, "avm2/timer", 10),
+    (as3_flash_utils_reflection, "avm2/flash_utils_reflection", 1),
+    (as3_vector, "avm2/vector", 1),
 }
 
 // TODO: These tests have some inaccuracies currently, so we use approx_eq to test that numeric values are close enough.
@@ -642,6 +679,179 @@ fn timeout_avm1() -> Result<(), Error> {
     )
 }
 
+#[test]
+fn xml_socket_avm1() -> Result<(), Error> {
+    test_swf(
+        "tests/swfs/avm1/xml_socket/test.swf",
+        3,
+        "tests/swfs/avm1/xml_socket/output.txt",
+        |player| {
+            let mut sockets: Box<dyn SocketBackend> = Box::new(EchoSocketBackend::default());
+            std::mem::swap(player.lock().unwrap().sockets_mut(), &mut sockets);
+            Ok(())
+        },
+        |_| Ok(()),
+    )
+}
+
+#[test]
+fn sound_capture_avm1() -> Result<(), Error> {
+    test_swf(
+        "tests/swfs/avm1/sound_capture/test.swf",
+        1,
+        "tests/swfs/avm1/sound_capture/output.txt",
+        |player| {
+            let mut audio: Box<dyn AudioBackend> = Box::new(CaptureAudioBackend::default());
+            std::mem::swap(player.lock().unwrap().audio_mut(), &mut audio);
+            Ok(())
+        },
+        |player| {
+            let mut player = player.lock().unwrap();
+            let audio = player
+                .audio_mut()
+                .downcast_mut::<CaptureAudioBackend>()
+                .unwrap();
+
+            // One frame at 10 FPS is 4410 sample frames, of which the first 1000 are the sound.
+            // A volume of 50 halves both channels, and a pan of 50 halves the left channel again.
+            let samples = audio.samples();
+            assert_eq!(samples.len(), 4410 * 2);
+            let (sound, silence) = samples.split_at(1000 * 2);
+            assert!(sound.chunks(2).all(|frame| frame == [2500, 4000]));
+            assert!(silence.iter().all(|&sample| sample == 0));
+            Ok(())
+        },
+    )
+}
+
+#[test]
+fn context_menu_select_avm1() -> Result<(), Error> {
+    test_swf(
+        "tests/swfs/avm1/context_menu_select/test.swf",
+        1,
+        "tests/swfs/avm1/context_menu_select/output.txt",
+        |_| Ok(()),
+        |player| {
+            let mut player = player.lock().unwrap();
+            let items = |items: Vec<ContextMenuItem>| -> Vec<(String, bool)> {
+                items
+                    .into_iter()
+                    .map(|item| (item.caption, item.enabled))
+                    .collect()
+            };
+
+            // The hidden item is left out, and the built-in items follow the custom ones.
+            assert_eq!(
+                items(player.prepare_context_menu()),
+                vec![
+                    ("First".to_string(), true),
+                    ("Disabled".to_string(), false),
+                    ("Play".to_string(), true),
+                    ("Rewind".to_string(), false),
+                    ("Forward".to_string(), true),
+                    ("Back".to_string(), false),
+                ]
+            );
+
+            // Choosing "First" calls `hideBuiltInItems`.
+            player.run_context_menu_callback(0);
+            assert_eq!(
+                items(player.prepare_context_menu()),
+                vec![("First".to_string(), true), ("Disabled".to_string(), false)]
+            );
+
+            // Disabled items do nothing.
+            player.run_context_menu_callback(1);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+fn tab_focus_avm1() -> Result<(), Error> {
+    test_swf(
+        "tests/swfs/avm1/tab_focus/test.swf",
+        1,
+        "tests/swfs/avm1/tab_focus/output.txt",
+        |player| {
+            let mut ui: Box<dyn UiBackend> = Box::new(KeyboardUiBackend::default());
+            std::mem::swap(player.lock().unwrap().ui_mut(), &mut ui);
+            Ok(())
+        },
+        |player| {
+            let mut player = player.lock().unwrap();
+            // Like the desktop and web players, Tab also sends a tab character,
+            // which must not end up in the newly focused text field.
+            let tab = |player: &mut Player| {
+                player.handle_event(PlayerEvent::KeyDown {
+                    key_code: KeyCode::Tab,
+                });
+                player.handle_event(PlayerEvent::TextInput { codepoint: '\t' });
+            };
+            tab(&mut player);
+            tab(&mut player);
+            tab(&mut player);
+            player.handle_event(PlayerEvent::TextInput { codepoint: 'a' });
+
+            player
+                .ui_mut()
+                .downcast_mut::<KeyboardUiBackend>()
+                .unwrap()
+                .keys_down
+                .push(KeyCode::Shift);
+            tab(&mut player);
+            tab(&mut player);
+            tab(&mut player);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+fn stage_resize_avm1() -> Result<(), Error> {
+    test_swf(
+        "tests/swfs/avm1/stage_resize/test.swf",
+        1,
+        "tests/swfs/avm1/stage_resize/output.txt",
+        |_| Ok(()),
+        |player| {
+            let mut player = player.lock().unwrap();
+            player.set_viewport_dimensions(640, 360);
+            // The stage didn't change size, so this shouldn't broadcast `onResize`.
+            player.set_viewport_dimensions(640, 360);
+            player.set_viewport_dimensions(320, 240);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+fn textfield_link_avm2() -> Result<(), Error> {
+    // Click an `event:` link, which should dispatch `TextEvent.LINK` to the text field.
+    test_swf(
+        "tests/swfs/avm2/textfield_link/test.swf",
+        1,
+        "tests/swfs/avm2/textfield_link/output.txt",
+        |_| Ok(()),
+        |player| {
+            let mut player = player.lock().unwrap();
+            let (x, y) = (30.0, 28.0);
+            player.handle_event(PlayerEvent::MouseMove { x, y });
+            player.handle_event(PlayerEvent::MouseDown {
+                x,
+                y,
+                button: MouseButton::Left,
+            });
+            player.handle_event(PlayerEvent::MouseUp {
+                x,
+                y,
+                button: MouseButton::Left,
+            });
+            Ok(())
+        },
+    )
+}
+
 /// Wrapper around string slice that makes debug output `{:?}` to print string same way as `{}`.
 /// Used in different `assert*!` macros in combination with `pretty_assertions` crate to make
 /// test failures to show nice diffs.
@@ -764,13 +974,15 @@ fn run_swf(
     let player = Player::new(
         Box::new(NullRenderer),
         Box::new(NullAudioBackend::new()),
+        Box::new(NullVideoBackend::new()),
         Box::new(NullNavigatorBackend::with_base_path(base_path, channel)),
+        Box::new(NullSocketBackend::new()),
         Box::new(MemoryStorageBackend::default()),
+        Box::new(MemoryLocalConnectionBackend::default()),
         Box::new(NullLocaleBackend::new()),
         Box::new(TestLogBackend::new(trace_output.clone())),
         Box::new(NullUiBackend::new()),
     )?;
-    player.lock().unwrap().set_root_movie(Arc::new(movie));
     player
         .lock()
         .unwrap()
@@ -778,9 +990,22 @@ fn run_swf(
 
     before_start(player.clone())?;
 
+    // Sounds are registered with the audio backend when the movie preloads,
+    // so any backend swapped in by `before_start` must already be in place.
+    player.lock().unwrap().set_root_movie(Arc::new(movie));
+
     for _ in 0..num_frames {
-        player.lock().unwrap().run_frame();
-        player.lock().unwrap().update_timers(frame_time);
+        let mut locked_player = player.lock().unwrap();
+        locked_player.run_frame();
+        locked_player.update_timers(frame_time);
+        // Tests that swap in a `CaptureAudioBackend` can check the mixed audio in `before_end`.
+        if let Some(audio) = locked_player
+            .audio_mut()
+            .downcast_mut::<CaptureAudioBackend>()
+        {
+            audio.advance(frame_time);
+        }
+        drop(locked_player);
         executor.poll_all().unwrap();
     }
 
@@ -808,6 +1033,84 @@ impl LogBackend for TestLogBackend {
     }
 }
 
+/// A socket backend which echoes everything sent to it.
+///
+/// Connections to `unreachable` fail, and sending `bye` closes the socket.
+#[derive(Default)]
+struct EchoSocketBackend {
+    next_handle: SocketHandle,
+    events: Vec<(SocketHandle, SocketEvent)>,
+}
+
+impl SocketBackend for EchoSocketBackend {
+    fn connect(&mut self, host: &str, _port: u16) -> SocketHandle {
+        let handle = self.next_handle;
+        self.next_handle += 1;
+        self.events
+            .push((handle, SocketEvent::Connect(host != "unreachable")));
+        handle
+    }
+
+    fn send(&mut self, handle: SocketHandle, data: &[u8]) {
+        self.events.push((handle, SocketEvent::Data(data.to_vec())));
+        if data == b"bye\0" {
+            self.events.push((handle, SocketEvent::Close));
+        }
+    }
+
+    fn close(&mut self, handle: SocketHandle) {
+        self.events.retain(|(h, _)| *h != handle);
+    }
+
+    fn poll(&mut self) -> Vec<(SocketHandle, SocketEvent)> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+/// A UI backend which reports the keys in `keys_down` as held.
+#[derive(Default)]
+struct KeyboardUiBackend {
+    keys_down: Vec<KeyCode>,
+}
+
+impl UiBackend for KeyboardUiBackend {
+    fn is_key_down(&self, key: KeyCode) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    fn last_key_code(&self) -> KeyCode {
+        KeyCode::Unknown
+    }
+
+    fn last_key_char(&self) -> Option<char> {
+        None
+    }
+
+    fn mouse_visible(&self) -> bool {
+        true
+    }
+
+    fn set_mouse_visible(&mut self, _visible: bool) {}
+
+    fn set_mouse_cursor(&mut self, _cursor: MouseCursor) {}
+
+    fn set_clipboard_content(&mut self, _content: String) {}
+
+    fn clipboard_content(&mut self) -> String {
+        String::new()
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        false
+    }
+
+    fn display_unsupported_message(&self) {}
+
+    fn message(&self, _message: &str) {}
+
+    fn display_context_menu(&mut self, _items: &[ContextMenuItem]) {}
+}
+
 #[derive(Default)]
 pub struct ExternalInterfaceTestProvider {}
 
@@ -0,0 +1,33 @@
+// Compiled into test.swf as the actions of frame 1.
+import flash.display.BitmapData;
+import flash.filters.BlurFilter;
+import flash.filters.ColorMatrixFilter;
+import flash.geom.Point;
+import flash.geom.Rectangle;
+
+function dump(bmp) {
+    for (var x = 0; x < bmp.width; x++) {
+        trace((bmp.getPixel32(x, 0) >>> 0).toString(16));
+    }
+}
+
+var src = new BitmapData(2, 1, false, 0x112233);
+var dst = new BitmapData(2, 1, false, 0);
+
+// Swap the red and blue channels.
+var swap = new ColorMatrixFilter([0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0]);
+trace(dst.applyFilter(src, src.rectangle, new Point(0, 0), swap));
+dump(dst);
+
+// Offset the red channel of one pixel, drawn next to where it came from.
+var offset = new ColorMatrixFilter([1, 0, 0, 0, 16, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0]);
+trace(dst.applyFilter(src, new Rectangle(0, 0, 1, 1), new Point(1, 0), offset));
+dump(dst);
+
+// Blurring a solid area leaves its middle unchanged, but fades its edges.
+var solid = new BitmapData(5, 5, true, 0xFF00FF00);
+var blurred = new BitmapData(5, 5, true, 0);
+trace(blurred.applyFilter(solid, solid.rectangle, new Point(0, 0), new BlurFilter(2, 2, 1)));
+trace((blurred.getPixel32(2, 2) >>> 0).toString(16));
+trace((blurred.getPixel32(0, 0) >>> 24) < 255);
+stop();
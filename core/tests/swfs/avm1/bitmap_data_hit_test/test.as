@@ -0,0 +1,25 @@
+// Compiled into test.swf as the actions of frame 1.
+import flash.display.BitmapData;
+import flash.geom.Point;
+import flash.geom.Rectangle;
+
+var bmp = new BitmapData(4, 4, true, 0);
+bmp.fillRect(new Rectangle(1, 1, 2, 2), 0xFFFF0000);
+bmp.setPixel32(0, 0, 0x80FF0000);
+
+// Points
+trace(bmp.hitTest(new Point(0, 0), 255, new Point(1, 1)));
+trace(bmp.hitTest(new Point(0, 0), 255, new Point(3, 3)));
+trace(bmp.hitTest(new Point(10, 10), 255, new Point(11, 12)));
+trace(bmp.hitTest(new Point(0, 0), 0x80, new Point(0, 0)));
+trace(bmp.hitTest(new Point(0, 0), 0x81, new Point(0, 0)));
+
+// Rectangles
+trace(bmp.hitTest(new Point(0, 0), 255, new Rectangle(3, 3, 2, 2)));
+trace(bmp.hitTest(new Point(0, 0), 255, new Rectangle(2, 2, 5, 5)));
+
+// Bitmaps
+var other = new BitmapData(2, 2, true, 0xFFFFFFFF);
+trace(bmp.hitTest(new Point(0, 0), 255, other, new Point(2, 2)));
+trace(bmp.hitTest(new Point(0, 0), 255, other, new Point(3, 3)));
+stop();
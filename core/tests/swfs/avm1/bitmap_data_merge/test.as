@@ -0,0 +1,22 @@
+// Compiled into test.swf as the actions of frame 1.
+import flash.display.BitmapData;
+import flash.geom.Point;
+import flash.geom.Rectangle;
+
+function dump(bmp) {
+    for (var x = 0; x < bmp.width; x++) {
+        trace((bmp.getPixel32(x, 0) >>> 0).toString(16));
+    }
+}
+
+var dst = new BitmapData(2, 1, false, 0);
+var src = new BitmapData(2, 1, false, 0xFFFFFF);
+dst.merge(src, new Rectangle(0, 0, 1, 1), new Point(0, 0), 128, 64, 256, 0);
+dump(dst);
+dst.merge(src, src.rectangle, new Point(1, 0), 0, 0, 0, 0);
+dump(dst);
+
+var clear = new BitmapData(1, 1, true, 0);
+clear.merge(new BitmapData(1, 1, true, 0xFFFFFFFF), new Rectangle(0, 0, 1, 1), new Point(0, 0), 256, 256, 256, 256);
+dump(clear);
+stop();
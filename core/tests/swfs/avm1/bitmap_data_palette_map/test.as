@@ -0,0 +1,30 @@
+// Compiled into test.swf as the actions of frame 1.
+import flash.display.BitmapData;
+import flash.geom.Point;
+
+function dump(bmp) {
+    for (var x = 0; x < bmp.width; x++) {
+        trace((bmp.getPixel32(x, 0) >>> 0).toString(16));
+    }
+}
+
+var src = new BitmapData(2, 1, false, 0x102030);
+src.setPixel(1, 0, 0xFF0000);
+var dst = new BitmapData(2, 1, false, 0);
+
+// Invert the red channel.
+var red = [];
+for (var i = 0; i < 256; i++) {
+    red[i] = (255 - i) << 16;
+}
+dst.paletteMap(src, src.rectangle, new Point(0, 0), red);
+dump(dst);
+
+// Replace the green channel with a constant blue value.
+var green = [];
+for (i = 0; i < 256; i++) {
+    green[i] = 0x80;
+}
+dst.paletteMap(src, src.rectangle, new Point(0, 0), null, green);
+dump(dst);
+stop();
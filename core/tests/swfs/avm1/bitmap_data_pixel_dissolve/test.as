@@ -0,0 +1,47 @@
+// Compiled into test.swf as the actions of frame 1.
+import flash.display.BitmapData;
+import flash.geom.Point;
+import flash.geom.Rectangle;
+
+function count(b, c) {
+    var n = 0;
+    var y = 0;
+    while (y < b.height) {
+        var x = 0;
+        while (x < b.width) {
+            if ((b.getPixel32(x, y) >>> 0) == c) {
+                n = n + 1;
+            }
+            x = x + 1;
+        }
+        y = y + 1;
+    }
+    return n;
+}
+
+// Each call continues where the last one left off, so no pixel is replaced twice
+// until all of them have been.
+var bmp = new BitmapData(5, 3, false, 0);
+var src = new BitmapData(5, 3, false, 0xFFFFFF);
+var seed = bmp.pixelDissolve(src, src.rectangle, new Point(0, 0), 0, 4);
+trace(typeof seed);
+trace(count(bmp, 0xFFFFFFFF));
+seed = bmp.pixelDissolve(src, src.rectangle, new Point(0, 0), seed, 4);
+trace(count(bmp, 0xFFFFFFFF));
+seed = bmp.pixelDissolve(src, src.rectangle, new Point(0, 0), seed, 4);
+trace(count(bmp, 0xFFFFFFFF));
+seed = bmp.pixelDissolve(src, src.rectangle, new Point(0, 0), seed, 4);
+trace(count(bmp, 0xFFFFFFFF));
+
+// Dissolving a bitmap into itself uses the fill color.
+bmp.pixelDissolve(bmp, bmp.rectangle, new Point(0, 0), seed, 15, 0xFFFF0000);
+trace(count(bmp, 0xFFFF0000));
+
+// The number of pixels is limited to the clipped area.
+var part = new BitmapData(5, 3, false, 0);
+part.pixelDissolve(src, new Rectangle(0, 0, 2, 2), new Point(3, 1), 7, 100);
+trace(count(part, 0xFFFFFFFF));
+trace((part.getPixel32(3, 1) >>> 0).toString(16));
+trace((part.getPixel32(4, 2) >>> 0).toString(16));
+trace((part.getPixel32(2, 1) >>> 0).toString(16));
+stop();
@@ -0,0 +1,26 @@
+// Compiled into test.swf as the actions of frame 1.
+import flash.display.BitmapData;
+import flash.geom.Point;
+import flash.geom.Rectangle;
+
+function dump(bmp) {
+    for (var x = 0; x < bmp.width; x++) {
+        trace((bmp.getPixel32(x, 0) >>> 0).toString(16));
+    }
+}
+
+var src = new BitmapData(4, 1, false, 0);
+src.setPixel(1, 0, 0x404040);
+src.setPixel(2, 0, 0x808080);
+src.setPixel(3, 0, 0xC0C0C0);
+var dst = new BitmapData(4, 1, true, 0);
+
+trace(dst.threshold(src, src.rectangle, new Point(0, 0), "<", 0xFF808080, 0xFFFF0000, 0xFFFFFFFF, false));
+dump(dst);
+trace(dst.threshold(src, src.rectangle, new Point(0, 0), ">=", 0x8000, 0xFF00FF00, 0x0000FF00, true));
+dump(dst);
+trace(dst.threshold(src, new Rectangle(1, 0, 2, 1), new Point(2, 0), "==", 0xFF404040, 0xFF0000FF));
+dump(dst);
+trace(dst.threshold(src, src.rectangle, new Point(0, 0), "<>", 0, 0xFFFFFFFF));
+dump(dst);
+stop();
@@ -0,0 +1,27 @@
+// The test harness opens the context menu, chooses "First", opens the menu
+// again and chooses "Disabled".
+
+// Frame 1
+var menu = new ContextMenu(function(obj, m) {
+    trace("menu.onSelect: " + (obj == _root) + " " + (m == menu));
+});
+var first = new ContextMenuItem("First", function(obj, item) {
+    trace("first.onSelect: " + item.caption + " " + (obj == _root));
+    menu.hideBuiltInItems();
+});
+menu.customItems.push(first);
+var hidden = new ContextMenuItem("Hidden", function(obj, item) {
+    trace("onSelect: " + item.caption);
+});
+hidden.visible = false;
+menu.customItems.push(hidden);
+var disabled = new ContextMenuItem("Disabled", function(obj, item) {
+    trace("onSelect: " + item.caption);
+}, true, false);
+menu.customItems.push(disabled);
+_root.menu = menu;
+trace("frame 1");
+stop();
+
+// Frame 2
+trace("frame 2");
@@ -0,0 +1,70 @@
+// Frame 1
+this.createTextField("field", 1, 0, 0, 100, 54);
+field.multiline = true;
+field.wordWrap = false;
+var text = "1";
+for (var i = 2; i <= 10; i++) {
+    text += "\n" + i;
+}
+field.text = text;
+field.onScroller = function(target) {
+    trace("onScroller: " + target._name);
+};
+trace("field.scroll: " + field.scroll);
+trace("field.maxscroll: " + field.maxscroll);
+trace("field.bottomScroll: " + field.bottomScroll);
+trace("// field.scroll = 5");
+field.scroll = 5;
+trace("field.scroll: " + field.scroll);
+trace("field.bottomScroll: " + field.bottomScroll);
+trace("// field.scroll = 5");
+field.scroll = 5;
+trace("field.scroll: " + field.scroll);
+trace("field.bottomScroll: " + field.bottomScroll);
+trace("// field.scroll = 100");
+field.scroll = 100;
+trace("field.scroll: " + field.scroll);
+trace("field.bottomScroll: " + field.bottomScroll);
+trace("// field.scroll = 0");
+field.scroll = 0;
+trace("field.scroll: " + field.scroll);
+trace("field.bottomScroll: " + field.bottomScroll);
+trace("// field.scroll = 8");
+field.scroll = 8;
+trace("field.scroll: " + field.scroll);
+trace("field.bottomScroll: " + field.bottomScroll);
+trace("field.hscroll: " + field.hscroll);
+trace("field.maxhscroll: " + field.maxhscroll);
+
+// Frame 2
+this.createTextField("line", 2, 0, 60, 50, 20);
+line.text = "The quick brown fox jumps over the lazy dog";
+line.onScroller = function(target) {
+    trace("onScroller: " + target._name);
+};
+trace("line.scroll: " + line.scroll);
+trace("line.maxscroll: " + line.maxscroll);
+trace("line.bottomScroll: " + line.bottomScroll);
+trace("line.hscroll: " + line.hscroll);
+trace("line.maxhscroll > 0: " + (line.maxhscroll > 0));
+trace("// line.hscroll = 5");
+line.hscroll = 5;
+trace("line.hscroll: " + line.hscroll);
+trace("// line.hscroll = 5");
+line.hscroll = 5;
+trace("line.hscroll: " + line.hscroll);
+trace("// line.hscroll = 100000");
+line.hscroll = 100000;
+trace("line.hscroll == line.maxhscroll: " + (line.hscroll == line.maxhscroll));
+trace("// line.hscroll = -5");
+line.hscroll = -5;
+trace("line.hscroll: " + line.hscroll);
+
+// Frame 3
+delete field.onScroller;
+trace("// field.text = \"1\\n2\"");
+field.text = "1\n2";
+trace("field.scroll: " + field.scroll);
+trace("field.maxscroll: " + field.maxscroll);
+trace("field.bottomScroll: " + field.bottomScroll);
+stop();
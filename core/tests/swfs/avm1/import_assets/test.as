@@ -0,0 +1,21 @@
+// library.swf exports "box", a 40x30 rectangle, and imports "inner", a
+// 20x10 rectangle, from nested.swf.
+// Frame 2 imports "box" and "inner" from library.swf and places both, so
+// playback waits on frame 1 until both libraries have loaded.
+
+// Frame 1
+trace("frame 1: " + _framesloaded + " / " + _totalframes);
+_root.onEnterFrame = function() {
+    trace("enterFrame: " + _root._currentframe + " " + _root._framesloaded);
+};
+
+// Frame 2
+// ImportAssets2 from library.swf: box, inner
+// PlaceObject2: box, inner
+trace("frame 2: " + _framesloaded);
+trace("box: " + box._width + "x" + box._height);
+trace("inner: " + inner._width + "x" + inner._height);
+
+// Frame 3
+trace("frame 3");
+stop();
@@ -0,0 +1,32 @@
+// Frame 1
+var receiver = new LocalConnection();
+receiver.greet = function(name, count) {
+    trace("greet: " + name + ", " + count);
+};
+trace("receiver.connect: " + receiver.connect("_test"));
+var other = new LocalConnection();
+trace("other.connect (name taken): " + other.connect("_test"));
+var sender = new LocalConnection();
+sender.onStatus = function(info) {
+    trace("sender.onStatus: " + info.level);
+};
+trace("sender.send: " + sender.send("_test", "greet", "hello", 42));
+var lost = new LocalConnection();
+lost.onStatus = function(info) {
+    trace("lost.onStatus: " + info.level);
+};
+trace("lost.send: " + lost.send("_missing", "greet", "nobody", 0));
+trace("end of frame 1");
+
+// Frame 2
+trace("frame 2");
+receiver.close();
+other.greet = function(name, count) {
+    trace("other.greet: " + name + ", " + count);
+};
+trace("other.connect: " + other.connect("_test"));
+trace("sender.send: " + sender.send("_test", "greet", "again", 1));
+
+// Frame 3
+trace("frame 3");
+stop();
@@ -0,0 +1,10 @@
+// "tone" is 1000 frames of 44.1kHz stereo PCM, with every left sample 10000
+// and every right sample 8000.
+var s = new Sound();
+s.attachSound("tone");
+s.setVolume(50);
+s.setPan(50);
+s.start();
+trace(s.getVolume());
+trace(s.getPan());
+stop();
@@ -0,0 +1,20 @@
+// test.mp3 is an ID3v2 tag followed by 40 silent frames, which is read in
+// several chunks.
+
+// Frame 1
+var s = new Sound();
+s.onID3 = function() {
+    trace("onID3: " + s.id3.songname + " by " + s.id3.artist);
+    trace("loading: " + (s.getBytesLoaded() < s.getBytesTotal()));
+    trace("total: " + s.getBytesTotal());
+};
+s.onLoad = function(success) {
+    trace("onLoad: " + success);
+    trace("loaded: " + s.getBytesLoaded() + " / " + s.getBytesTotal());
+};
+s.loadSound("test.mp3", false);
+trace("started: " + s.getBytesLoaded() + " / " + s.getBytesTotal());
+
+// Frame 2
+trace("frame 2");
+stop();
@@ -0,0 +1,10 @@
+// The test harness resizes the viewport to 640x360, then to the same size
+// again, then to 320x240.
+Stage.scaleMode = "noScale";
+var listener = {};
+listener.onResize = function() {
+    trace("onResize: " + Stage.width + "x" + Stage.height);
+};
+Stage.addListener(listener);
+trace("scaleMode: " + Stage.scaleMode);
+stop();
@@ -0,0 +1,23 @@
+// The test harness presses Tab three times and types "a", then presses
+// Shift+Tab three times.
+
+// Frame 1
+_root.createEmptyMovieClip("a", 1);
+_root.a.tabEnabled = true;
+_root.a.tabIndex = 1;
+_root.createEmptyMovieClip("b", 2);
+_root.b.tabEnabled = true;
+_root.b.tabIndex = 2;
+_root.createTextField("t", 3, 0, 0, 100, 20);
+_root.t.type = "input";
+_root.t.tabIndex = 3;
+_root.t.onChanged = function(tf) {
+    trace("changed: " + tf.text);
+};
+var listener = new Object();
+listener.onSetFocus = function(oldFocus, newFocus) {
+    trace("focus: " + Selection.getFocus());
+};
+Selection.addListener(listener);
+trace("frame 1");
+stop();
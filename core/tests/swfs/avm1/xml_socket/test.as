@@ -0,0 +1,33 @@
+// The test harness connects this movie to a socket backend which echoes
+// everything sent to it, fails to connect to "unreachable", and closes the
+// socket after receiving "bye".
+
+// Frame 1
+var socket = new XMLSocket();
+socket.onConnect = function(success) {
+    trace("socket.onConnect: " + success);
+    this.send("hello");
+    this.send("bye");
+};
+socket.onData = function(data) {
+    trace("socket.onData: " + data);
+};
+socket.onClose = function() {
+    trace("socket.onClose");
+};
+trace("socket.connect: " + socket.connect("localhost", 1024));
+var failing = new XMLSocket();
+failing.onConnect = function(success) {
+    trace("failing.onConnect: " + success);
+};
+trace("failing.connect: " + failing.connect("unreachable", 1024));
+var privileged = new XMLSocket();
+trace("privileged.connect: " + privileged.connect("localhost", 80));
+trace("end of frame 1");
+
+// Frame 2
+trace("frame 2");
+
+// Frame 3
+trace("frame 3");
+stop();
@@ -0,0 +1,16 @@
+package {
+	public class Test {
+	}
+}
+
+trace(5 as int);
+trace(5.5 as int);
+trace(5.5 as Number);
+trace(-1 as uint);
+trace("str" as String);
+trace(5 as String);
+trace(true as Boolean);
+trace([1, 2] as Array);
+trace({} as Array);
+trace({} as Object);
+trace(null as Object);
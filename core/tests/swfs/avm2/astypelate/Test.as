@@ -0,0 +1,21 @@
+package {
+	public class Test {
+	}
+}
+
+// `as` compiles to `astypelate` when the type is not known at compile time.
+function as_late(value, type:Class) {
+	return value as type;
+}
+
+trace(as_late(5, int));
+trace(as_late(5.5, int));
+trace(as_late(5.5, Number));
+trace(as_late(-1, uint));
+trace(as_late("str", String));
+trace(as_late(5, String));
+trace(as_late(true, Boolean));
+trace(as_late([1, 2], Array));
+trace(as_late({}, Array));
+trace(as_late({}, Object));
+trace(as_late(null, Object));
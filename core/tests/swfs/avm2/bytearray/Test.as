@@ -0,0 +1,62 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.utils.ByteArray;
+
+var b:ByteArray = new ByteArray();
+b.writeInt(0x01020304);
+b.writeShort(-2);
+b.writeByte(255);
+b.writeUTF("hello");
+b.writeDouble(1.5);
+trace(b.length);
+trace(b.position);
+trace(b.endian);
+
+b.position = 0;
+trace(b.readInt());
+trace(b.readShort());
+trace(b.readByte());
+trace(b.readUTF());
+trace(b.readDouble());
+trace(b.bytesAvailable);
+
+b.endian = "littleEndian";
+trace(b.endian);
+b.position = 0;
+trace(b.readInt());
+
+trace(b[0]);
+trace(b[1000]);
+b[1] = 0x7f;
+trace(b[1]);
+
+b.compress();
+b.uncompress();
+trace(b.length);
+trace(b.position);
+trace(b.readInt());
+
+b.clear();
+b.writeUTFBytes("abc");
+trace(b.toString());
+trace(b.length);
+
+try {
+	b.position = 1;
+	b.readInt();
+	trace("ERROR: no error thrown");
+} catch (e) {
+	trace(e.name);
+	trace(e.errorID);
+}
+
+try {
+	b.endian = "middleEndian";
+	trace("ERROR: no error thrown");
+} catch (e) {
+	trace(e.name);
+	trace(e.errorID);
+}
@@ -0,0 +1,22 @@
+package {
+	public class Test {
+	}
+}
+
+try {
+	var o = {};
+	o.(true);
+	trace("ERROR: no error thrown");
+} catch (e) {
+	trace(e.name);
+	trace(e.errorID);
+}
+
+try {
+	var s = "str";
+	s.(true);
+	trace("ERROR: no error thrown");
+} catch (e) {
+	trace(e.name);
+	trace(e.errorID);
+}
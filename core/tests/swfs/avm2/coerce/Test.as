@@ -0,0 +1,31 @@
+package {
+	public class Test {
+	}
+}
+
+var i:int = "12";
+trace(i);
+var u:uint = -1;
+trace(u);
+var n:Number = "1.5";
+trace(n);
+var b:Boolean = "";
+trace(b);
+var s:String = 5;
+trace(s);
+var sn:String = null;
+trace(sn);
+var o:Object = undefined;
+trace(o);
+var a:Array = [1, 2];
+trace(a);
+var an:Array = null;
+trace(an);
+
+try {
+	var ao:Array = {};
+	trace("ERROR: no error thrown");
+} catch (e) {
+	trace(e.name);
+	trace(e.errorID);
+}
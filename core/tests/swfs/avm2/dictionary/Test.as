@@ -0,0 +1,34 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.utils.Dictionary;
+
+var d:Dictionary = new Dictionary();
+var k1:Object = {};
+var k2:Object = {};
+
+d[k1] = "one";
+d[k2] = "two";
+d["str"] = "three";
+trace(d[k1]);
+trace(d[k2]);
+trace(d["str"]);
+
+// Object keys are distinct from their string representations.
+trace(d["[object Object]"]);
+
+d[k1] = "uno";
+trace(d[k1]);
+
+trace(k1 in d);
+trace(delete d[k1]);
+trace(k1 in d);
+trace(d[k1]);
+
+var count:int = 0;
+for each (var v in d) {
+	count++;
+}
+trace(count);
@@ -0,0 +1,7 @@
+package {
+	public class Test {
+	}
+}
+
+default xml namespace = "http://example.org/";
+trace("default xml namespace set");
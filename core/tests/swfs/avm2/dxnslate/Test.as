@@ -0,0 +1,8 @@
+package {
+	public class Test {
+	}
+}
+
+var uri = "http://example.org/";
+default xml namespace = uri;
+trace("default xml namespace set");
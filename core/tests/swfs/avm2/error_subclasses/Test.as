@@ -0,0 +1,50 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.errors.EOFError;
+import flash.errors.IOError;
+
+try {
+	throw new TypeError("type");
+} catch (e:RangeError) {
+	trace("ERROR: caught by the wrong handler");
+} catch (e:Error) {
+	trace("caught as Error");
+	trace(e);
+	trace(e is TypeError);
+	trace(e is Error);
+	trace(e is RangeError);
+	trace(e.name);
+	trace(e.message);
+	trace(e.errorID);
+}
+
+try {
+	throw new EOFError("eof");
+} catch (e:IOError) {
+	trace("caught as IOError");
+	trace(e);
+} catch (e:Error) {
+	trace("ERROR: caught by the wrong handler");
+}
+
+// Hand-edited: the type of the first handler is an undefined class,
+// which never matches.
+try {
+	throw new ArgumentError("argument");
+} catch (e:NoSuchError) {
+	trace("ERROR: caught by the wrong handler");
+} catch (e:ArgumentError) {
+	trace("caught as ArgumentError");
+	trace(e);
+}
+
+try {
+	throw new Error();
+} catch (e:Error) {
+	trace("caught as Error");
+	trace(e);
+}
+trace("done");
@@ -0,0 +1,10 @@
+package {
+	public class Test {
+	}
+}
+
+// The compiler emits `escxattr` when splicing values into XML attribute values, as in `<x a={s}/>`.
+// This test was hand-assembled to trace the escaped string directly.
+trace(escxattr("<a href=\"x\">&'\"\t\n\r</a>"));
+trace(escxattr(5));
+trace(escxattr(null));
@@ -0,0 +1,10 @@
+package {
+	public class Test {
+	}
+}
+
+// The compiler emits `escxelem` when splicing values into XML element content, as in `<x>{s}</x>`.
+// This test was hand-assembled to trace the escaped string directly.
+trace(escxelem("<a href=\"x\">&'\"\t</a>"));
+trace(escxelem(5));
+trace(escxelem(null));
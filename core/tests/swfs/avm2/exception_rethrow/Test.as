@@ -0,0 +1,33 @@
+package {
+	public class Test {
+	}
+}
+
+var thrown;
+try {
+	try {
+		thrown = new TypeError("inner");
+		throw thrown;
+	} catch (e:Error) {
+		trace("inner caught");
+		trace(e);
+		throw e;
+	}
+} catch (e:Error) {
+	trace("outer caught");
+	trace(e);
+	trace(e === thrown);
+}
+
+// A value that isn't an Error passes through typed handlers.
+try {
+	try {
+		throw "plain string";
+	} catch (e:Error) {
+		trace("ERROR: string caught as Error");
+	}
+} catch (e) {
+	trace("caught");
+	trace(e);
+}
+trace("done");
@@ -0,0 +1,35 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.utils.*;
+
+trace(getQualifiedClassName(new ByteArray()));
+trace(getQualifiedClassName(ByteArray));
+trace(getQualifiedClassName(5));
+trace(getQualifiedClassName(1.5));
+trace(getQualifiedClassName("str"));
+trace(getQualifiedClassName(null));
+trace(getQualifiedClassName({}));
+trace(getQualifiedClassName([]));
+
+trace(getQualifiedSuperclassName(new ByteArray()));
+trace(getQualifiedSuperclassName(new Timer(1)));
+trace(getQualifiedSuperclassName(Timer));
+trace(getQualifiedSuperclassName({}));
+
+trace(getDefinitionByName("flash.utils.ByteArray"));
+trace(getDefinitionByName("flash.utils::Timer"));
+trace(getDefinitionByName("Array"));
+
+trace(describeType(Endian));
+trace(describeType(null));
+
+try {
+	getDefinitionByName("does.not.Exist");
+	trace("ERROR: no error thrown");
+} catch (e) {
+	trace(e.name);
+	trace(e.errorID);
+}
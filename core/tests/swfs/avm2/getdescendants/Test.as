@@ -0,0 +1,22 @@
+package {
+	public class Test {
+	}
+}
+
+try {
+	var o = {};
+	o..child;
+	trace("ERROR: no error thrown");
+} catch (e) {
+	trace(e.name);
+	trace(e.errorID);
+}
+
+try {
+	var n = null;
+	n..child;
+	trace("ERROR: no error thrown");
+} catch (e) {
+	trace(e.name);
+	trace(e.errorID);
+}
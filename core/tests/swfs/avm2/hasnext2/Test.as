@@ -0,0 +1,20 @@
+package {
+	public class Test {
+	}
+}
+
+var o = {"key": "value"};
+
+for (var k in o) {
+	trace(k);
+}
+
+for each (var v in o) {
+	trace(v);
+}
+
+for each (var n in null) {
+	trace("ERROR: enumerated null");
+}
+
+trace("done");
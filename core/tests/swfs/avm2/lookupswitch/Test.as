@@ -0,0 +1,20 @@
+package {
+	public class Test {
+	}
+}
+
+for each (var value in [-1, 0, 1, 2, 3]) {
+	switch (value) {
+		case 0:
+			trace(value + ": zero");
+			break;
+		case 1:
+			trace(value + ": one");
+			break;
+		case 2:
+			trace(value + ": two");
+			break;
+		default:
+			trace(value + ": default");
+	}
+}
@@ -0,0 +1,9 @@
+package {
+	public class Test {
+	}
+}
+
+// `nop` is not emitted by the compiler and was inserted by hand between
+// the two traces below.
+trace("before");
+trace("after");
@@ -0,0 +1,20 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.TextEvent;
+	import flash.text.TextField;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			addFrameScript(0, function() {
+				// `field` is placed on the timeline with the HTML text
+				// <a href="event:hello">link</a>
+				var field = getChildByName("field");
+				trace(field.text);
+				trace(field is TextField);
+				field.addEventListener("link", function(event) {
+					trace(event.type + ": " + event.text);
+				});
+			});
+		}
+	}
+}
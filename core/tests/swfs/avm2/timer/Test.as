@@ -0,0 +1,30 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.utils.Timer;
+
+var t:Timer = new Timer(100, 3);
+
+var listener = function(e) {
+	trace(e.type);
+	trace(e.target.currentCount);
+	trace(e.target.running);
+};
+t.addEventListener("timer", listener);
+t.addEventListener("timerComplete", listener);
+
+trace(t.delay);
+trace(t.repeatCount);
+trace(t.running);
+t.start();
+trace(t.running);
+
+try {
+	t.delay = -1;
+	trace("ERROR: no error thrown");
+} catch (e) {
+	trace(e.name);
+	trace(e.errorID);
+}
@@ -0,0 +1,27 @@
+package {
+	public class Test {
+	}
+}
+
+trace("before");
+try {
+	try {
+		trace("in try");
+		throw new RangeError("boom");
+	} catch (e:TypeError) {
+		trace("ERROR: caught by the TypeError handler");
+	} finally {
+		trace("finally after error");
+	}
+	trace("ERROR: finally without error");
+} catch (e:Error) {
+	trace("outer caught");
+	trace(e);
+}
+
+try {
+	trace("in try without error");
+} finally {
+	trace("finally without error");
+}
+trace("done");
@@ -0,0 +1,15 @@
+package {
+	public class Test {
+	}
+}
+
+trace(typeof undefined);
+trace(typeof null);
+trace(typeof true);
+trace(typeof 5);
+trace(typeof 1.5);
+trace(typeof "string");
+trace(typeof {});
+trace(typeof []);
+trace(typeof trace);
+trace(typeof String);
@@ -0,0 +1,106 @@
+package {
+	public class Test {
+	}
+}
+
+import flash.utils.getQualifiedClassName;
+
+trace(Vector.<int>);
+trace(Vector.<int> === Vector.<int>);
+
+var v:Vector.<int> = new Vector.<int>();
+trace(v.push(1, 2.5, "3"));
+trace(v.length);
+trace(v);
+trace(v[1]);
+
+v.length = 5;
+trace(v);
+trace(v.indexOf(3));
+trace(v.lastIndexOf(0));
+
+trace(v.sort(Array.NUMERIC | Array.DESCENDING));
+trace(v.splice(1, 2, 7.9));
+trace(v);
+trace(v.slice(-2));
+trace(v.reverse());
+trace(v.pop());
+trace(v.shift());
+trace(v.unshift(9));
+trace(v);
+
+for each (var i in v) {
+	trace(i);
+}
+
+var c:Vector.<int> = v;
+trace(c);
+trace(getQualifiedClassName(v));
+
+var n:Vector.<Number> = new Vector.<Number>(2);
+trace(n);
+
+var s:Vector.<String> = new Vector.<String>(2);
+trace(s.push(5));
+trace(s);
+
+var u:Vector.<uint> = new Vector.<uint>();
+trace(u.push(-1));
+trace(u);
+trace(u.concat(u));
+
+var any:Vector.<*> = new Vector.<*>(1, true);
+trace(any.constructor);
+trace(any.fixed);
+
+v.fixed = true;
+
+try {
+	v[10];
+	trace("ERROR: no error thrown");
+} catch (e) {
+	trace(e.name);
+	trace(e.errorID);
+}
+
+try {
+	v.push(4);
+	trace("ERROR: no error thrown");
+} catch (e) {
+	trace(e.name);
+	trace(e.errorID);
+}
+
+try {
+	v.length = 0;
+	trace("ERROR: no error thrown");
+} catch (e) {
+	trace(e.name);
+	trace(e.errorID);
+}
+
+try {
+	var w:Vector.<int> = Object(u);
+	trace("ERROR: no error thrown");
+} catch (e) {
+	trace(e.name);
+	trace(e.errorID);
+}
+
+// The following type applications are rejected by the compiler, so the test
+// movie applies them with `applytype` directly.
+try {
+	Object(Array).<int>;
+	trace("ERROR: no error thrown");
+} catch (e) {
+	trace(e.name);
+	trace(e.errorID);
+}
+
+try {
+	Object(Vector).<int, int>;
+	trace("ERROR: no error thrown");
+} catch (e) {
+	trace(e.name);
+	trace(e.errorID);
+}
@@ -0,0 +1,17 @@
+package {
+	public class Test {
+	}
+}
+
+var x:XML = new XML('<root xmlns:a="http://example.org/a"><item>1</item><a:item>2</a:item><group><item>3</item></group></root>');
+namespace a = "http://example.org/a";
+
+trace(x..item.length());
+trace(x..item.toXMLString());
+trace(x..a::item);
+trace(x..item.(toString() != "1").toXMLString());
+
+default xml namespace = "http://example.org/a";
+
+trace(x..item.length());
+trace(x..item);
@@ -4,7 +4,7 @@ use ruffle_core::backend::audio::decoders::{
     self, AdpcmDecoder, Mp3Decoder, NellymoserDecoder, PcmDecoder, SeekableDecoder,
 };
 use ruffle_core::backend::audio::{
-    swf, AudioBackend, SoundHandle, SoundInstanceHandle, SoundTransform,
+    swf, AudioBackend, SoundHandle, SoundInstanceHandle, SoundPlayback, SoundTransform,
 };
 use ruffle_core::tag_utils::SwfSlice;
 use std::io::Cursor;
@@ -67,6 +67,13 @@ struct SoundInstance {
     left_transform: [f32; 2],
 
     right_transform: [f32; 2],
+
+    /// The number of sample frames output so far, at the output sample rate.
+    frames_played: u64,
+
+    /// The portion of the sound being played.
+    /// `None` if this is a stream sound.
+    playback: Option<SoundPlayback>,
 }
 
 impl CpalAudioBackend {
@@ -170,6 +177,10 @@ impl CpalAudioBackend {
             AudioCompression::Nellymoser => {
                 Box::new(NellymoserDecoder::new(data, format.sample_rate.into()))
             }
+            #[cfg(feature = "aac")]
+            AudioCompression::Aac => Box::new(decoders::AacDecoder::new(data)?),
+            #[cfg(feature = "speex")]
+            AudioCompression::Speex => Box::new(decoders::SpeexDecoder::new(data)?),
             _ => {
                 let msg = format!(
                     "start_stream: Unhandled audio compression {:?}",
@@ -186,7 +197,7 @@ impl CpalAudioBackend {
     /// TODO: Allow interpolator to be user-configurable?
     fn make_resampler<S: Send + dasp::signal::Signal<Frame = [i16; 2]>>(
         &self,
-        format: &swf::SoundFormat,
+        sample_rate: u16,
         mut signal: S,
     ) -> dasp::signal::interpolate::Converter<
         S,
@@ -198,7 +209,7 @@ impl CpalAudioBackend {
         dasp::signal::interpolate::Converter::from_hz_to_hz(
             signal,
             interpolator,
-            format.sample_rate.into(),
+            sample_rate.into(),
             self.output_config.sample_rate.0.into(),
         )
     }
@@ -213,6 +224,7 @@ impl CpalAudioBackend {
     ) -> Result<Box<dyn Send + dasp::signal::Signal<Frame = [i16; 2]>>, Error> {
         // Instantiate a decoder for the compression that the sound data uses.
         let decoder = Self::make_seekable_decoder(&sound.format, data)?;
+        let sample_rate = decoder.sample_rate();
 
         // Wrap the decoder in the event sound signal (controls looping/envelope)
         let signal = EventSoundSignal::new_with_settings(
@@ -223,7 +235,7 @@ impl CpalAudioBackend {
         );
         // Convert the `Decoder` to a `Signal`, and resample it the the output
         // sample rate.
-        let signal = self.make_resampler(&sound.format, signal);
+        let signal = self.make_resampler(sample_rate, signal);
         if let Some(envelope) = &settings.envelope {
             use dasp::Signal;
             let envelope_signal =
@@ -245,8 +257,9 @@ impl CpalAudioBackend {
 
         // Convert the `Decoder` to a `Signal`, and resample it the the output
         // sample rate.
+        let sample_rate = clip_stream_decoder.sample_rate();
         let signal = dasp::signal::from_iter(clip_stream_decoder);
-        let signal = Box::new(self.make_resampler(format, signal));
+        let signal = Box::new(self.make_resampler(sample_rate, signal));
         Ok(signal)
     }
 
@@ -262,8 +275,9 @@ impl CpalAudioBackend {
 
         // Convert the `Decoder` to a `Signal`, and resample it the the output
         // sample rate.
+        let sample_rate = decoder.sample_rate();
         let signal = dasp::signal::from_iter(decoder);
-        let signal = self.make_resampler(format, signal);
+        let signal = self.make_resampler(sample_rate, signal);
         Ok(Box::new(signal))
     }
 
@@ -294,6 +308,7 @@ impl CpalAudioBackend {
             for (_, sound) in sound_instances.iter_mut() {
                 if sound.active && !sound.signal.is_exhausted() {
                     let sound_frame = sound.signal.next();
+                    sound.frames_played += 1;
                     let [left_0, left_1] = sound_frame.mul_amp(sound.left_transform);
                     let [right_0, right_1] = sound_frame.mul_amp(sound.right_transform);
                     let sound_frame: Stereo<T::Signed> = [
@@ -365,6 +380,8 @@ impl AudioBackend for CpalAudioBackend {
             active: true,
             left_transform: [1.0, 0.0],
             right_transform: [0.0, 1.0],
+            frames_played: 0,
+            playback: None,
         });
         Ok(handle)
     }
@@ -398,6 +415,12 @@ impl AudioBackend for CpalAudioBackend {
             active: true,
             left_transform: [1.0, 0.0],
             right_transform: [0.0, 1.0],
+            frames_played: 0,
+            playback: Some(SoundPlayback::new(
+                settings,
+                sound.format.sample_rate,
+                sound.num_sample_frames,
+            )),
         });
         Ok(handle)
     }
@@ -421,12 +444,14 @@ impl AudioBackend for CpalAudioBackend {
 
     fn get_sound_position(&self, instance: SoundInstanceHandle) -> Option<u32> {
         let sound_instances = self.sound_instances.lock().unwrap();
-        if let Some(_instance) = sound_instances.get(instance) {
-            // TODO: Return actual position
-            Some(0)
-        } else {
-            None
-        }
+        sound_instances.get(instance).map(|instance| {
+            let elapsed = instance.frames_played as f64 * 1000.0
+                / f64::from(self.output_config.sample_rate.0);
+            match &instance.playback {
+                Some(playback) => playback.position(elapsed),
+                None => elapsed as u32,
+            }
+        })
     }
 
     fn get_sound_duration(&self, sound: SoundHandle) -> Option<u32> {
@@ -447,8 +472,6 @@ impl AudioBackend for CpalAudioBackend {
             instance.right_transform = [transform.left_to_right, transform.right_to_right];
         }
     }
-
-    fn tick(&mut self) {}
 }
 
 /// A dummy wrapper struct to implement `AsRef<[u8]>` for `Arc<Vec<u8>`.
@@ -485,13 +508,15 @@ impl EventSoundSignal {
         num_sample_frames: u32,
         skip_sample_frames: u16,
     ) -> Self {
+        // In and out points are in 44.1kHz samples regardless of the sound's sample rate.
         let skip_sample_frames = u32::from(skip_sample_frames);
-        let sample_divisor = 44100 / u32::from(decoder.sample_rate());
+        let sample_rate = u64::from(decoder.sample_rate());
+        let to_sample_frame = |n: u32| (u64::from(n) * sample_rate / 44100) as u32;
         let start_sample_frame =
-            settings.in_sample.unwrap_or(0) / sample_divisor + skip_sample_frames;
+            to_sample_frame(settings.in_sample.unwrap_or(0)) + skip_sample_frames;
         let end_sample_frame = settings
             .out_sample
-            .map(|n| n / sample_divisor)
+            .map(to_sample_frame)
             .unwrap_or(num_sample_frames)
             + skip_sample_frames;
 
@@ -0,0 +1,338 @@
+//! The context menu, drawn over the movie when right-clicking the player.
+
+use ruffle_core::backend::render::{RenderBackend, ShapeHandle, Transform};
+use ruffle_core::color_transform::ColorTransform;
+use ruffle_core::context_menu::ContextMenuItem;
+use ruffle_core::swf::{self, Color, Matrix, Twips};
+use std::collections::HashMap;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+
+/// The size of the text, in logical pixels.
+const FONT_SIZE: f64 = 12.0;
+
+/// The height of an item, in logical pixels.
+const ITEM_HEIGHT: f64 = 22.0;
+
+/// The height of the gap containing a separator line, in logical pixels.
+const SEPARATOR_HEIGHT: f64 = 7.0;
+
+/// The space to the left of the caption, which holds the check mark, in logical pixels.
+const LEFT_PADDING: f64 = 22.0;
+
+/// The space to the right of the caption, in logical pixels.
+const RIGHT_PADDING: f64 = 16.0;
+
+/// The width of the border around the menu, in logical pixels.
+const BORDER_WIDTH: f64 = 1.0;
+
+/// The minimum width of the menu, in logical pixels.
+const MIN_WIDTH: f64 = 120.0;
+
+const BORDER_COLOR: u32 = 0x808080;
+const BACKGROUND_COLOR: u32 = 0xf0f0f0;
+const HOVER_COLOR: u32 = 0x3399ff;
+const SEPARATOR_COLOR: u32 = 0xc8c8c8;
+const TEXT_COLOR: u32 = 0x000000;
+const HOVER_TEXT_COLOR: u32 = 0xffffff;
+const DISABLED_TEXT_COLOR: u32 = 0xa0a0a0;
+
+/// The size of an em in the coordinates of the device font. DefineFont3 stores
+/// coordinates at 20 times the scale of DefineFont1/2 (SWF19 p.164).
+const UNITS_PER_EM: f64 = 20480.0;
+
+/// The device font, used to draw the captions of menu items.
+///
+/// Glyphs are registered with the renderer the first time they are drawn.
+pub struct MenuFont {
+    font: swf::Font<'static>,
+    code_point_to_glyph: HashMap<u16, usize>,
+    shape_handles: HashMap<usize, ShapeHandle>,
+}
+
+impl MenuFont {
+    pub fn new() -> Result<Self, swf::error::Error> {
+        let mut reader = swf::read::Reader::new(ruffle_core::DEVICE_FONT_TAG, 8);
+        let font = reader.read_define_font_2(3)?;
+        let code_point_to_glyph = font
+            .glyphs
+            .iter()
+            .enumerate()
+            .map(|(i, glyph)| (glyph.code, i))
+            .collect();
+        Ok(Self {
+            font,
+            code_point_to_glyph,
+            shape_handles: HashMap::new(),
+        })
+    }
+
+    fn glyph_index(&self, c: char) -> Option<usize> {
+        // TODO: Properly handle code points outside the BMP.
+        self.code_point_to_glyph.get(&(c as u16)).copied()
+    }
+
+    /// The distance from the top of a line to its baseline, at the given font size.
+    fn ascent(&self, size: f64) -> f64 {
+        let ascent = self.font.layout.as_ref().map_or(0, |layout| layout.ascent);
+        f64::from(ascent) * size / UNITS_PER_EM
+    }
+
+    /// The distance from the baseline of a line to its bottom, at the given font size.
+    fn descent(&self, size: f64) -> f64 {
+        let descent = self.font.layout.as_ref().map_or(0, |layout| layout.descent);
+        f64::from(descent) * size / UNITS_PER_EM
+    }
+
+    /// The width of `text` at the given font size.
+    fn measure(&self, text: &str, size: f64) -> f64 {
+        let advance: i32 = text
+            .chars()
+            .filter_map(|c| self.glyph_index(c))
+            .map(|i| i32::from(self.font.glyphs[i].advance.unwrap_or(0)))
+            .sum();
+        f64::from(advance) * size / UNITS_PER_EM
+    }
+
+    /// Draws `text` with its baseline starting at (`x`, `y`).
+    fn draw_text(
+        &mut self,
+        renderer: &mut dyn RenderBackend,
+        text: &str,
+        (mut x, y): (f64, f64),
+        size: f64,
+        color: u32,
+    ) {
+        let color = Color::from_rgb(color, 255);
+        let color_transform = ColorTransform {
+            r_mult: f32::from(color.r) / 255.0,
+            g_mult: f32::from(color.g) / 255.0,
+            b_mult: f32::from(color.b) / 255.0,
+            ..Default::default()
+        };
+        // Glyph shapes are in twips, so an em is `UNITS_PER_EM / 20` pixels.
+        let scale = (size * 20.0 / UNITS_PER_EM) as f32;
+
+        for c in text.chars() {
+            let index = match self.glyph_index(c) {
+                Some(index) => index,
+                None => continue,
+            };
+            let glyph = &self.font.glyphs[index];
+            let shape_handle = *self
+                .shape_handles
+                .entry(index)
+                .or_insert_with(|| renderer.register_glyph_shape(glyph));
+            let transform = Transform {
+                matrix: Matrix::create_box(
+                    scale,
+                    scale,
+                    0.0,
+                    Twips::from_pixels(x),
+                    Twips::from_pixels(y),
+                ),
+                color_transform,
+            };
+            renderer.render_shape(shape_handle, &transform);
+            x += f64::from(glyph.advance.unwrap_or(0)) * size / UNITS_PER_EM;
+        }
+    }
+}
+
+/// An open context menu.
+///
+/// All positions are in physical pixels, relative to the window.
+pub struct ContextMenu {
+    items: Vec<ContextMenuItem>,
+
+    /// The top of each item, relative to the top of the menu.
+    item_tops: Vec<f64>,
+
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    scale_factor: f64,
+
+    /// The item under the mouse cursor.
+    hovered: Option<usize>,
+}
+
+impl ContextMenu {
+    /// Lays out a menu with its top-left corner at the mouse cursor, moved as
+    /// needed to fit inside the window.
+    pub fn new(
+        items: Vec<ContextMenuItem>,
+        position: PhysicalPosition<f64>,
+        window_size: PhysicalSize<u32>,
+        scale_factor: f64,
+        font: &MenuFont,
+    ) -> Self {
+        let mut item_tops = Vec::with_capacity(items.len());
+        let mut height = BORDER_WIDTH;
+        let mut width = MIN_WIDTH;
+        for (i, item) in items.iter().enumerate() {
+            if item.separator_before && i > 0 {
+                height += SEPARATOR_HEIGHT;
+            }
+            item_tops.push(height * scale_factor);
+            height += ITEM_HEIGHT;
+
+            let caption_width = font.measure(&item.caption, FONT_SIZE);
+            width = width.max(LEFT_PADDING + caption_width + RIGHT_PADDING);
+        }
+        height += BORDER_WIDTH;
+        width += 2.0 * BORDER_WIDTH;
+
+        let (width, height) = (width * scale_factor, height * scale_factor);
+        let max_x = (f64::from(window_size.width) - width).max(0.0);
+        let max_y = (f64::from(window_size.height) - height).max(0.0);
+        Self {
+            items,
+            item_tops,
+            x: position.x.min(max_x),
+            y: position.y.min(max_y),
+            width,
+            height,
+            scale_factor,
+            hovered: None,
+        }
+    }
+
+    /// The index of the item at the given position, if any.
+    fn item_at(&self, position: PhysicalPosition<f64>) -> Option<usize> {
+        let x = position.x - self.x;
+        let y = position.y - self.y;
+        if x < 0.0 || x >= self.width {
+            return None;
+        }
+        let item_height = ITEM_HEIGHT * self.scale_factor;
+        self.item_tops
+            .iter()
+            .position(|&top| y >= top && y < top + item_height)
+    }
+
+    /// Whether the given position is inside the menu.
+    pub fn contains(&self, position: PhysicalPosition<f64>) -> bool {
+        let x = position.x - self.x;
+        let y = position.y - self.y;
+        x >= 0.0 && x < self.width && y >= 0.0 && y < self.height
+    }
+
+    /// Highlights the enabled item under the mouse cursor.
+    /// Returns `true` if the menu needs to be redrawn.
+    pub fn set_mouse_position(&mut self, position: PhysicalPosition<f64>) -> bool {
+        let hovered = self.item_at(position).filter(|&i| self.items[i].enabled);
+        let changed = hovered != self.hovered;
+        self.hovered = hovered;
+        changed
+    }
+
+    /// The highlighted item, which is chosen when a mouse button is released.
+    ///
+    /// This is only set once the mouse moves over an enabled item, so that
+    /// releasing the button that opened the menu doesn't choose anything.
+    pub fn hovered_item(&self) -> Option<usize> {
+        self.hovered
+    }
+
+    pub fn render(&self, renderer: &mut dyn RenderBackend, font: &mut MenuFont) {
+        let scale_factor = self.scale_factor;
+        let border = BORDER_WIDTH * scale_factor;
+        draw_rect(
+            renderer,
+            BORDER_COLOR,
+            self.x,
+            self.y,
+            self.width,
+            self.height,
+        );
+        draw_rect(
+            renderer,
+            BACKGROUND_COLOR,
+            self.x + border,
+            self.y + border,
+            self.width - 2.0 * border,
+            self.height - 2.0 * border,
+        );
+
+        let item_width = self.width - 2.0 * border;
+        let item_height = ITEM_HEIGHT * scale_factor;
+        let font_size = FONT_SIZE * scale_factor;
+        // Center the text vertically within each item.
+        let baseline = (item_height + font.ascent(font_size) - font.descent(font_size)) / 2.0;
+        for (i, (item, &top)) in self.items.iter().zip(&self.item_tops).enumerate() {
+            let top = self.y + top;
+
+            if item.separator_before && i > 0 {
+                let separator_y = top - (SEPARATOR_HEIGHT * scale_factor / 2.0).ceil();
+                draw_rect(
+                    renderer,
+                    SEPARATOR_COLOR,
+                    self.x + border,
+                    separator_y,
+                    item_width,
+                    scale_factor.round(),
+                );
+            }
+
+            let text_color = if !item.enabled {
+                DISABLED_TEXT_COLOR
+            } else if self.hovered == Some(i) {
+                draw_rect(
+                    renderer,
+                    HOVER_COLOR,
+                    self.x + border,
+                    top,
+                    item_width,
+                    item_height,
+                );
+                HOVER_TEXT_COLOR
+            } else {
+                TEXT_COLOR
+            };
+
+            if item.checked {
+                let size = (6.0 * scale_factor).round();
+                draw_rect(
+                    renderer,
+                    text_color,
+                    self.x + border + (LEFT_PADDING * scale_factor - size) / 2.0,
+                    top + (item_height - size) / 2.0,
+                    size,
+                    size,
+                );
+            }
+
+            font.draw_text(
+                renderer,
+                &item.caption,
+                (
+                    self.x + border + LEFT_PADDING * scale_factor,
+                    top + baseline,
+                ),
+                font_size,
+                text_color,
+            );
+        }
+    }
+}
+
+fn draw_rect(
+    renderer: &mut dyn RenderBackend,
+    color: u32,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+) {
+    renderer.draw_rect(
+        Color::from_rgb(color, 255),
+        &Matrix::create_box(
+            width as f32,
+            height as f32,
+            0.0,
+            Twips::from_pixels(x),
+            Twips::from_pixels(y),
+        ),
+    );
+}
@@ -1,7 +1,16 @@
 //! Custom event type for desktop ruffle
 
+use ruffle_core::context_menu::ContextMenuItem;
+
 /// User-defined events.
 pub enum RuffleEvent {
     /// Indicates that one or more tasks are ready to poll on our executor.
     TaskPoll,
+
+    /// The player asked to display a context menu with these items.
+    OpenContextMenu(Vec<ContextMenuItem>),
+
+    /// The context menu was closed, with the index of the chosen item, or
+    /// `None` if it was dismissed.
+    ContextMenuClosed(Option<usize>),
 }
@@ -6,19 +6,27 @@
 #![windows_subsystem = "windows"]
 
 mod audio;
+mod context_menu;
 mod custom_event;
 mod executor;
 mod locale;
 mod navigator;
+mod socket;
 mod storage;
 mod task;
 mod ui;
 
+use crate::context_menu::{ContextMenu, MenuFont};
 use crate::custom_event::RuffleEvent;
 use crate::executor::GlutinAsyncExecutor;
 use clap::Clap;
 use isahc::{config::RedirectPolicy, prelude::*, HttpClient};
-use ruffle_core::{backend::audio::AudioBackend, config::Letterbox, Player};
+use ruffle_core::{
+    backend::audio::AudioBackend,
+    config::{Letterbox, StageAlign, StageScaleMode},
+    events::MouseButton as RuffleMouseButton,
+    Player,
+};
 use ruffle_render_wgpu::WgpuRenderBackend;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -34,8 +42,8 @@ use winit::dpi::{LogicalSize, PhysicalPosition};
 use winit::event::{
     ElementState, KeyboardInput, MouseButton, MouseScrollDelta, VirtualKeyCode, WindowEvent,
 };
-use winit::event_loop::{ControlFlow, EventLoop};
-use winit::window::{Fullscreen, Icon, WindowBuilder};
+use winit::event_loop::{ControlFlow, EventLoop, EventLoopProxy};
+use winit::window::{Fullscreen, Icon, Window, WindowBuilder};
 
 #[derive(Clap, Debug)]
 #[clap(
@@ -78,6 +86,14 @@ struct Opt {
     #[clap(long, case_insensitive = true)]
     proxy: Option<Url>,
 
+    /// (Optional) How the movie is scaled to fit the window: showAll, noBorder, exactFit or noScale
+    #[clap(long)]
+    scale: Option<StageScaleMode>,
+
+    /// (Optional) The edges of the window to align the movie to, such as "TL" for top-left
+    #[clap(long)]
+    align: Option<StageAlign>,
+
     /// (Optional) Replace all embedded http URLs with https
     #[clap(long, case_insensitive = true, takes_value = false)]
     upgrade_to_https: bool,
@@ -239,16 +255,40 @@ fn run_player(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
         opt.proxy,
         opt.upgrade_to_https,
     )); //TODO: actually implement this backend type
+    let sockets = Box::new(socket::TcpSocketBackend::new());
     let storage = Box::new(storage::DiskStorageBackend::new());
+    let local_connection =
+        Box::new(ruffle_core::backend::local_connection::MemoryLocalConnectionBackend::default());
     let locale = Box::new(locale::DesktopLocaleBackend::new());
     let log = Box::new(ruffle_core::backend::log::NullLogBackend::new());
-    let ui = Box::new(ui::DesktopUiBackend::new(window.clone()));
-    let player = Player::new(renderer, audio, navigator, storage, locale, log, ui)?;
+    let ui = Box::new(ui::DesktopUiBackend::new(
+        window.clone(),
+        event_loop.create_proxy(),
+    ));
+    let video = Box::new(ruffle_core::backend::video::SoftwareVideoBackend::new());
+    let player = Player::new(
+        renderer,
+        audio,
+        video,
+        navigator,
+        sockets,
+        storage,
+        local_connection,
+        locale,
+        log,
+        ui,
+    )?;
     {
         let mut player = player.lock().unwrap();
         player.set_root_movie(Arc::new(movie));
         player.set_is_playing(true); // Desktop player will auto-play.
         player.set_letterbox(Letterbox::On);
+        if let Some(scale_mode) = opt.scale {
+            player.set_scale_mode(scale_mode);
+        }
+        if let Some(stage_align) = opt.align {
+            player.set_stage_align(stage_align);
+        }
         player.set_viewport_dimensions(viewport_size.width, viewport_size.height);
     }
 
@@ -257,6 +297,10 @@ fn run_player(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
     let mut next_frame_time = Instant::now();
     let mut minimized = false;
     let mut fullscreen_down = false;
+    let mut context_menu_open = false;
+    let mut context_menu: Option<ContextMenu> = None;
+    let mut menu_font = MenuFont::new()?;
+    let event_loop_proxy = event_loop.create_proxy();
     loop {
         // Poll UI events
         event_loop.run(move |event, _window_target, control_flow| {
@@ -287,7 +331,11 @@ fn run_player(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
                 winit::event::Event::RedrawRequested(_) => {
                     // Don't render when minimized to avoid potential swap chain errors in `wgpu`.
                     if !minimized {
-                        player.lock().unwrap().render();
+                        player.lock().unwrap().render_with_overlay(|renderer| {
+                            if let Some(context_menu) = &context_menu {
+                                context_menu.render(renderer, &mut menu_font);
+                            }
+                        });
                     }
                 }
 
@@ -296,6 +344,8 @@ fn run_player(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
                         // TODO: Change this when winit adds a `Window::minimzed` or `WindowEvent::Minimize`.
                         minimized = size.width == 0 && size.height == 0;
 
+                        close_context_menu(&mut context_menu, None, &event_loop_proxy, &window);
+
                         let mut player_lock = player.lock().unwrap();
                         player_lock.set_viewport_dimensions(size.width, size.height);
                         player_lock
@@ -304,37 +354,86 @@ fn run_player(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
                         window.request_redraw();
                     }
                     WindowEvent::CursorMoved { position, .. } => {
-                        let mut player_lock = player.lock().unwrap();
                         mouse_pos = position;
-                        let event = ruffle_core::PlayerEvent::MouseMove {
-                            x: position.x,
-                            y: position.y,
-                        };
-                        player_lock.handle_event(event);
-                        if player_lock.needs_render() {
-                            window.request_redraw();
+                        // The movie doesn't see the mouse while the context menu is open.
+                        if let Some(context_menu) = &mut context_menu {
+                            if context_menu.set_mouse_position(position) {
+                                window.request_redraw();
+                            }
+                        } else {
+                            let mut player_lock = player.lock().unwrap();
+                            let event = ruffle_core::PlayerEvent::MouseMove {
+                                x: position.x,
+                                y: position.y,
+                            };
+                            player_lock.handle_event(event);
+                            if player_lock.needs_render() {
+                                window.request_redraw();
+                            }
+                        }
+                    }
+                    WindowEvent::MouseInput { state: pressed, .. } if context_menu.is_some() => {
+                        // Clicking outside the menu dismisses it, and releasing a
+                        // button over an item chooses it.
+                        if let Some(menu) = &context_menu {
+                            let index = menu.hovered_item();
+                            match pressed {
+                                ElementState::Pressed if !menu.contains(mouse_pos) => {
+                                    close_context_menu(
+                                        &mut context_menu,
+                                        None,
+                                        &event_loop_proxy,
+                                        &window,
+                                    );
+                                }
+                                ElementState::Released if index.is_some() => {
+                                    close_context_menu(
+                                        &mut context_menu,
+                                        index,
+                                        &event_loop_proxy,
+                                        &window,
+                                    );
+                                }
+                                _ => (),
+                            }
                         }
                     }
                     WindowEvent::MouseInput {
-                        button: MouseButton::Left,
+                        button,
                         state: pressed,
                         ..
                     } => {
-                        let mut player_lock = player.lock().unwrap();
-                        let event = if pressed == ElementState::Pressed {
-                            ruffle_core::PlayerEvent::MouseDown {
-                                x: mouse_pos.x,
-                                y: mouse_pos.y,
+                        if let Some(button) = ui::winit_to_ruffle_mouse_button(button) {
+                            let mut player_lock = player.lock().unwrap();
+                            let event = if pressed == ElementState::Pressed {
+                                ruffle_core::PlayerEvent::MouseDown {
+                                    x: mouse_pos.x,
+                                    y: mouse_pos.y,
+                                    button,
+                                }
+                            } else {
+                                ruffle_core::PlayerEvent::MouseUp {
+                                    x: mouse_pos.x,
+                                    y: mouse_pos.y,
+                                    button,
+                                }
+                            };
+                            player_lock.handle_event(event);
+
+                            // Right-clicking opens the context menu. The choice arrives
+                            // later as a `ContextMenuClosed` event.
+                            if button == RuffleMouseButton::Right
+                                && pressed == ElementState::Pressed
+                                && !context_menu_open
+                            {
+                                let items = player_lock.prepare_context_menu();
+                                player_lock.ui_mut().display_context_menu(&items);
+                                context_menu_open = true;
                             }
-                        } else {
-                            ruffle_core::PlayerEvent::MouseUp {
-                                x: mouse_pos.x,
-                                y: mouse_pos.y,
+
+                            if player_lock.needs_render() {
+                                window.request_redraw();
                             }
-                        };
-                        player_lock.handle_event(event);
-                        if player_lock.needs_render() {
-                            window.request_redraw();
                         }
                     }
                     WindowEvent::MouseWheel { delta, .. } => {
@@ -396,7 +495,14 @@ fn run_player(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
                             },
                         ..
                     } => {
-                        window.set_fullscreen(None);
+                        if context_menu.is_some() {
+                            close_context_menu(&mut context_menu, None, &event_loop_proxy, &window);
+                        } else {
+                            window.set_fullscreen(None);
+                        }
+                    }
+                    WindowEvent::Focused(false) => {
+                        close_context_menu(&mut context_menu, None, &event_loop_proxy, &window);
                     }
                     WindowEvent::KeyboardInput { .. } | WindowEvent::ReceivedCharacter(_) => {
                         let mut player_lock = player.lock().unwrap();
@@ -418,6 +524,27 @@ fn run_player(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
                     .lock()
                     .expect("active executor reference")
                     .poll_all(),
+                winit::event::Event::UserEvent(RuffleEvent::OpenContextMenu(items)) => {
+                    context_menu = Some(ContextMenu::new(
+                        items,
+                        mouse_pos,
+                        window.inner_size(),
+                        window.scale_factor(),
+                        &menu_font,
+                    ));
+                    window.request_redraw();
+                }
+                winit::event::Event::UserEvent(RuffleEvent::ContextMenuClosed(index)) => {
+                    context_menu_open = false;
+                    let mut player_lock = player.lock().unwrap();
+                    match index {
+                        Some(index) => player_lock.run_context_menu_callback(index),
+                        None => player_lock.clear_custom_menu_items(),
+                    }
+                    if player_lock.needs_render() {
+                        window.request_redraw();
+                    }
+                }
                 _ => (),
             }
 
@@ -429,6 +556,20 @@ fn run_player(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
     }
 }
 
+/// Closes the context menu, if it's open, and reports the chosen item to the
+/// event loop.
+fn close_context_menu(
+    context_menu: &mut Option<ContextMenu>,
+    index: Option<usize>,
+    event_loop: &EventLoopProxy<RuffleEvent>,
+    window: &Window,
+) {
+    if context_menu.take().is_some() {
+        let _ = event_loop.send_event(RuffleEvent::ContextMenuClosed(index));
+        window.request_redraw();
+    }
+}
+
 fn run_timedemo(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
     let movie_url = match &opt.input_path {
         Some(path) => {
@@ -460,11 +601,26 @@ fn run_timedemo(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
     let audio: Box<dyn AudioBackend> =
         Box::new(ruffle_core::backend::audio::NullAudioBackend::new());
     let navigator = Box::new(ruffle_core::backend::navigator::NullNavigatorBackend::new());
+    let sockets = Box::new(ruffle_core::backend::socket::NullSocketBackend::new());
     let storage = Box::new(ruffle_core::backend::storage::MemoryStorageBackend::default());
+    let local_connection =
+        Box::new(ruffle_core::backend::local_connection::MemoryLocalConnectionBackend::default());
     let locale = Box::new(locale::DesktopLocaleBackend::new());
     let log = Box::new(ruffle_core::backend::log::NullLogBackend::new());
     let ui = Box::new(ruffle_core::backend::ui::NullUiBackend::new());
-    let player = Player::new(renderer, audio, navigator, storage, locale, log, ui)?;
+    let video = Box::new(ruffle_core::backend::video::SoftwareVideoBackend::new());
+    let player = Player::new(
+        renderer,
+        audio,
+        video,
+        navigator,
+        sockets,
+        storage,
+        local_connection,
+        locale,
+        log,
+        ui,
+    )?;
     player.lock().unwrap().set_root_movie(Arc::new(movie));
     player.lock().unwrap().set_is_playing(true);
 
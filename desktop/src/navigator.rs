@@ -1,9 +1,15 @@
 //! Navigator backend for web
 
 use crate::custom_event::RuffleEvent;
-use isahc::{config::RedirectPolicy, prelude::*, AsyncReadResponseExt, HttpClient, Request};
+use futures::AsyncReadExt;
+use isahc::http::header::CONTENT_LENGTH;
+use isahc::{
+    config::RedirectPolicy, prelude::*, AsyncBody, AsyncReadResponseExt, HttpClient, Request,
+    Response,
+};
 use ruffle_core::backend::navigator::{
-    NavigationMethod, NavigatorBackend, OwnedFuture, RequestOptions,
+    read_file_chunked, ChunkHandler, NavigationMethod, NavigatorBackend, OwnedFuture,
+    RequestOptions,
 };
 use ruffle_core::indexmap::IndexMap;
 use ruffle_core::loader::Error;
@@ -62,6 +68,39 @@ impl ExternalNavigatorBackend {
             upgrade_to_https,
         }
     }
+
+    /// Resolve a URL to be fetched against the movie's URL.
+    fn fetch_url(&self, url: &str) -> Result<Url, Error> {
+        // TODO: honor sandbox type (local-with-filesystem, local-with-network, remote, ...)
+        match self.movie_url.clone().join(url) {
+            Ok(url) => Ok(self.pre_process_url(url)),
+            Err(e) => Err(Error::FetchError(format!("Invalid URL {}: {}", url, e))),
+        }
+    }
+}
+
+/// Send an HTTP request, returning the response once its headers have arrived.
+async fn send_request(
+    client: Option<Rc<HttpClient>>,
+    url: Url,
+    options: RequestOptions,
+) -> Result<Response<AsyncBody>, Error> {
+    let client = client.ok_or(Error::NetworkUnavailable)?;
+
+    let request = match options.method() {
+        NavigationMethod::GET => Request::get(url.to_string()),
+        NavigationMethod::POST => Request::post(url.to_string()),
+    };
+
+    let (body_data, _) = options.body().clone().unwrap_or_default();
+    let body = request
+        .body(body_data)
+        .map_err(|e| Error::FetchError(e.to_string()))?;
+
+    client
+        .send_async(body)
+        .await
+        .map_err(|e| Error::FetchError(e.to_string()))
 }
 
 impl NavigatorBackend for ExternalNavigatorBackend {
@@ -112,17 +151,11 @@ impl NavigatorBackend for ExternalNavigatorBackend {
     }
 
     fn fetch(&self, url: &str, options: RequestOptions) -> OwnedFuture<Vec<u8>, Error> {
-        // TODO: honor sandbox type (local-with-filesystem, local-with-network, remote, ...)
-        let full_url = match self.movie_url.clone().join(url) {
+        let processed_url = match self.fetch_url(url) {
             Ok(url) => url,
-            Err(e) => {
-                let msg = format!("Invalid URL {}: {}", url, e);
-                return Box::pin(async move { Err(Error::FetchError(msg)) });
-            }
+            Err(e) => return Box::pin(async move { Err(e) }),
         };
 
-        let processed_url = self.pre_process_url(full_url);
-
         let client = self.client.clone();
 
         match processed_url.scheme() {
@@ -131,22 +164,7 @@ impl NavigatorBackend for ExternalNavigatorBackend {
                     .map_err(Error::NetworkError)
             }),
             _ => Box::pin(async move {
-                let client = client.ok_or(Error::NetworkUnavailable)?;
-
-                let request = match options.method() {
-                    NavigationMethod::GET => Request::get(processed_url.to_string()),
-                    NavigationMethod::POST => Request::post(processed_url.to_string()),
-                };
-
-                let (body_data, _) = options.body().clone().unwrap_or_default();
-                let body = request
-                    .body(body_data)
-                    .map_err(|e| Error::FetchError(e.to_string()))?;
-
-                let mut response = client
-                    .send_async(body)
-                    .await
-                    .map_err(|e| Error::FetchError(e.to_string()))?;
+                let mut response = send_request(client, processed_url, options).await?;
 
                 let mut buffer = vec![];
                 response
@@ -158,6 +176,46 @@ impl NavigatorBackend for ExternalNavigatorBackend {
         }
     }
 
+    fn fetch_chunked(
+        &self,
+        url: &str,
+        options: RequestOptions,
+        mut on_chunk: ChunkHandler,
+    ) -> OwnedFuture<(), Error> {
+        let processed_url = match self.fetch_url(url) {
+            Ok(url) => url,
+            Err(e) => return Box::pin(async move { Err(e) }),
+        };
+
+        let client = self.client.clone();
+
+        match processed_url.scheme() {
+            "file" => Box::pin(async move {
+                read_file_chunked(processed_url.to_file_path().unwrap_or_default(), on_chunk)
+            }),
+            _ => Box::pin(async move {
+                let mut response = send_request(client, processed_url, options).await?;
+                let total = response
+                    .headers()
+                    .get(CONTENT_LENGTH)
+                    .and_then(|len| len.to_str().ok()?.parse().ok());
+
+                let mut buffer = vec![0; 8192];
+                loop {
+                    let len = response
+                        .body_mut()
+                        .read(&mut buffer)
+                        .await
+                        .map_err(|e| Error::FetchError(e.to_string()))?;
+                    if len == 0 {
+                        return Ok(());
+                    }
+                    on_chunk(&buffer[..len], total)?;
+                }
+            }),
+        }
+    }
+
     fn time_since_launch(&mut self) -> Duration {
         Instant::now().duration_since(self.start_time)
     }
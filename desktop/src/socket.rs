@@ -0,0 +1,162 @@
+//! TCP socket backend
+
+use ruffle_core::backend::socket::{SocketBackend, SocketEvent, SocketHandle};
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// How long to wait for a connection to be established.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// The sockets that have not been closed by the player, along with a queue of
+/// data to write to each one once it has connected.
+type Streams = Arc<Mutex<HashMap<SocketHandle, Option<Sender<Vec<u8>>>>>>;
+
+/// Socket backend which makes real TCP connections.
+///
+/// Each socket is connected and read on its own thread, which reports back
+/// through a channel that is drained when the player polls. Writes are queued
+/// to a second thread per socket, so that a slow peer never blocks the player.
+pub struct TcpSocketBackend {
+    next_handle: SocketHandle,
+    streams: Streams,
+    sender: Sender<(SocketHandle, SocketEvent)>,
+    receiver: Receiver<(SocketHandle, SocketEvent)>,
+}
+
+impl TcpSocketBackend {
+    pub fn new() -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            next_handle: 0,
+            streams: Arc::new(Mutex::new(HashMap::new())),
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl SocketBackend for TcpSocketBackend {
+    fn connect(&mut self, host: &str, port: u16) -> SocketHandle {
+        let handle = self.next_handle;
+        self.next_handle = self.next_handle.wrapping_add(1);
+        self.streams.lock().unwrap().insert(handle, None);
+
+        let host = host.to_string();
+        let streams = self.streams.clone();
+        let sender = self.sender.clone();
+        thread::spawn(move || run_socket(handle, &host, port, streams, sender));
+
+        handle
+    }
+
+    fn send(&mut self, handle: SocketHandle, data: &[u8]) {
+        if let Some(Some(writer)) = self.streams.lock().unwrap().get(&handle) {
+            let _ = writer.send(data.to_vec());
+        }
+    }
+
+    fn close(&mut self, handle: SocketHandle) {
+        // Dropping the write queue makes the writer thread flush it and then
+        // shut the socket down.
+        self.streams.lock().unwrap().remove(&handle);
+    }
+
+    fn poll(&mut self) -> Vec<(SocketHandle, SocketEvent)> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+impl Drop for TcpSocketBackend {
+    fn drop(&mut self) {
+        self.streams.lock().unwrap().clear();
+    }
+}
+
+/// Connect a socket, then report everything read from it until it closes.
+fn run_socket(
+    handle: SocketHandle,
+    host: &str,
+    port: u16,
+    streams: Streams,
+    sender: Sender<(SocketHandle, SocketEvent)>,
+) {
+    let stream = (host, port).to_socket_addrs().ok().and_then(|mut addrs| {
+        addrs.find_map(|addr| TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).ok())
+    });
+
+    let mut stream = match stream {
+        Some(stream) => stream,
+        None => {
+            log::warn!("Unable to connect to {}:{}", host, port);
+            if streams.lock().unwrap().remove(&handle).is_some() {
+                let _ = sender.send((handle, SocketEvent::Connect(false)));
+            }
+            return;
+        }
+    };
+
+    {
+        let mut streams = streams.lock().unwrap();
+        match (streams.get_mut(&handle), stream.try_clone()) {
+            (Some(entry), Ok(write_stream)) => {
+                let (writer, queue) = channel();
+                *entry = Some(writer);
+                thread::spawn(move || write_socket(write_stream, queue));
+            }
+            (Some(_), Err(e)) => {
+                log::warn!("Unable to connect to {}:{}: {}", host, port, e);
+                streams.remove(&handle);
+                let _ = sender.send((handle, SocketEvent::Connect(false)));
+                return;
+            }
+            // The socket was closed while connecting.
+            (None, _) => {
+                let _ = stream.shutdown(Shutdown::Both);
+                return;
+            }
+        }
+    }
+
+    let _ = sender.send((handle, SocketEvent::Connect(true)));
+
+    let mut buffer = [0; 4096];
+    loop {
+        match stream.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(len) => {
+                if sender
+                    .send((handle, SocketEvent::Data(buffer[..len].to_vec())))
+                    .is_err()
+                {
+                    // The backend is gone.
+                    return;
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(_) => break,
+        }
+    }
+
+    // Sockets closed by the player have already been removed.
+    if streams.lock().unwrap().remove(&handle).is_some() {
+        let _ = sender.send((handle, SocketEvent::Close));
+    }
+}
+
+/// Write everything queued for a socket, until the player closes it or the
+/// connection is lost, then shut the socket down.
+fn write_socket(mut stream: TcpStream, queue: Receiver<Vec<u8>>) {
+    for data in queue {
+        if let Err(e) = stream.write_all(&data) {
+            log::warn!("Unable to write to socket: {}", e);
+            break;
+        }
+    }
+
+    let _ = stream.shutdown(Shutdown::Both);
+}
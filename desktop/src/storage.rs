@@ -22,16 +22,22 @@ impl DiskStorageBackend {
 
         DiskStorageBackend { base_path }
     }
-}
 
-impl StorageBackend for DiskStorageBackend {
-    fn get_string(&self, name: &str) -> Option<String> {
-        let full_path = self.base_path.join(Path::new(name));
+    /// The path of a shared object, stored as a `.sol` file like in Flash Player.
+    fn sol_path(&self, name: &str) -> PathBuf {
+        self.base_path.join(Path::new(&format!("{}.sol", name)))
+    }
 
-        match File::open(full_path) {
+    /// The path used by older versions of Ruffle, without an extension.
+    fn legacy_path(&self, name: &str) -> PathBuf {
+        self.base_path.join(Path::new(name))
+    }
+
+    fn read_file(path: &Path) -> Option<Vec<u8>> {
+        match File::open(path) {
             Ok(mut file) => {
-                let mut buffer = String::new();
-                if let Err(r) = file.read_to_string(&mut buffer) {
+                let mut buffer = Vec::new();
+                if let Err(r) = file.read_to_end(&mut buffer) {
                     log::warn!("Unable to read file content {:?}", r);
                     None
                 } else {
@@ -44,9 +50,20 @@ impl StorageBackend for DiskStorageBackend {
             }
         }
     }
+}
+
+impl StorageBackend for DiskStorageBackend {
+    fn get(&self, name: &str) -> Option<Vec<u8>> {
+        let legacy_path = self.legacy_path(name);
+        if legacy_path.is_file() && !self.sol_path(name).exists() {
+            return Self::read_file(&legacy_path);
+        }
+
+        Self::read_file(&self.sol_path(name))
+    }
 
-    fn put_string(&mut self, name: &str, value: String) -> bool {
-        let full_path = self.base_path.join(Path::new(name));
+    fn put(&mut self, name: &str, value: &[u8]) -> bool {
+        let full_path = self.sol_path(name);
         if let Some(parent_dir) = full_path.parent() {
             if !parent_dir.exists() {
                 if let Err(r) = fs::create_dir_all(&parent_dir) {
@@ -58,10 +75,16 @@ impl StorageBackend for DiskStorageBackend {
 
         match File::create(full_path) {
             Ok(mut file) => {
-                if let Err(r) = file.write_all(value.as_bytes()) {
+                if let Err(r) = file.write_all(value) {
                     log::warn!("Unable to write file content {:?}", r);
                     false
                 } else {
+                    // The legacy file has been superseded, so it can be removed.
+                    let legacy_path = self.legacy_path(name);
+                    if legacy_path.is_file() {
+                        let _ = fs::remove_file(legacy_path);
+                    }
+
                     true
                 }
             }
@@ -73,7 +96,7 @@ impl StorageBackend for DiskStorageBackend {
     }
 
     fn remove_key(&mut self, name: &str) {
-        let full_path = self.base_path.join(Path::new(name));
-        let _ = fs::remove_file(full_path);
+        let _ = fs::remove_file(self.sol_path(name));
+        let _ = fs::remove_file(self.legacy_path(name));
     }
 }
@@ -1,10 +1,13 @@
+use crate::custom_event::RuffleEvent;
 use clipboard::{ClipboardContext, ClipboardProvider};
 use ruffle_core::backend::ui::{MouseCursor, UiBackend};
-use ruffle_core::events::{KeyCode, PlayerEvent};
+use ruffle_core::context_menu::ContextMenuItem;
+use ruffle_core::events::{KeyCode, MouseButton as RuffleMouseButton, PlayerEvent};
 use std::collections::HashSet;
 use std::rc::Rc;
 use tinyfiledialogs::{message_box_ok, MessageBoxIcon};
-use winit::event::{ElementState, ModifiersState, VirtualKeyCode, WindowEvent};
+use winit::event::{ElementState, ModifiersState, MouseButton, VirtualKeyCode, WindowEvent};
+use winit::event_loop::EventLoopProxy;
 use winit::window::Window;
 
 pub struct DesktopUiBackend {
@@ -14,10 +17,11 @@ pub struct DesktopUiBackend {
     last_key: KeyCode,
     last_char: Option<char>,
     clipboard: ClipboardContext,
+    event_loop: EventLoopProxy<RuffleEvent>,
 }
 
 impl DesktopUiBackend {
-    pub fn new(window: Rc<Window>) -> Self {
+    pub fn new(window: Rc<Window>, event_loop: EventLoopProxy<RuffleEvent>) -> Self {
         Self {
             window,
             keys_down: HashSet::new(),
@@ -25,6 +29,7 @@ impl DesktopUiBackend {
             last_key: KeyCode::Unknown,
             last_char: None,
             clipboard: ClipboardProvider::new().unwrap(),
+            event_loop,
         }
     }
 
@@ -82,6 +87,7 @@ impl UiBackend for DesktopUiBackend {
         match key {
             KeyCode::Unknown => false,
             KeyCode::Backspace => self.keys_down.contains(&VirtualKeyCode::Back),
+            KeyCode::Tab => self.keys_down.contains(&VirtualKeyCode::Tab),
             KeyCode::Return => self.keys_down.contains(&VirtualKeyCode::Return),
             KeyCode::Shift => {
                 self.keys_down.contains(&VirtualKeyCode::LShift)
@@ -219,6 +225,10 @@ impl UiBackend for DesktopUiBackend {
         self.clipboard.set_contents(content).unwrap();
     }
 
+    fn clipboard_content(&mut self) -> String {
+        self.clipboard.get_contents().unwrap_or_default()
+    }
+
     fn is_fullscreen(&self) -> bool {
         self.window.fullscreen().is_some()
     }
@@ -234,6 +244,32 @@ impl UiBackend for DesktopUiBackend {
     fn message(&self, message: &str) {
         message_box_ok("Ruffle", message, MessageBoxIcon::Info)
     }
+
+    fn display_context_menu(&mut self, items: &[ContextMenuItem]) {
+        if items.is_empty() {
+            let _ = self
+                .event_loop
+                .send_event(RuffleEvent::ContextMenuClosed(None));
+            return;
+        }
+
+        // The menu is drawn over the movie by the event loop, which reports the
+        // choice with a `ContextMenuClosed` event.
+        let _ = self
+            .event_loop
+            .send_event(RuffleEvent::OpenContextMenu(items.to_vec()));
+    }
+}
+
+/// Convert a winit `MouseButton` into a Ruffle `MouseButton`.
+/// Return `None` if there is no matching Flash mouse button.
+pub fn winit_to_ruffle_mouse_button(button: MouseButton) -> Option<RuffleMouseButton> {
+    match button {
+        MouseButton::Left => Some(RuffleMouseButton::Left),
+        MouseButton::Right => Some(RuffleMouseButton::Right),
+        MouseButton::Middle => Some(RuffleMouseButton::Middle),
+        MouseButton::Other(_) => None,
+    }
 }
 
 /// Convert a winit `VirtualKeyCode` into a Ruffle `KeyCode`.
@@ -241,6 +277,7 @@ impl UiBackend for DesktopUiBackend {
 fn winit_to_ruffle_key_code(key_code: VirtualKeyCode) -> Option<KeyCode> {
     Some(match key_code {
         VirtualKeyCode::Back => KeyCode::Backspace,
+        VirtualKeyCode::Tab => KeyCode::Tab,
         VirtualKeyCode::Return => KeyCode::Return,
         VirtualKeyCode::LShift | VirtualKeyCode::RShift => KeyCode::Shift,
         VirtualKeyCode::LControl | VirtualKeyCode::RControl => KeyCode::Control,
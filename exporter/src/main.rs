@@ -1,12 +1,16 @@
 use clap::Clap;
 use image::RgbaImage;
 use indicatif::{ProgressBar, ProgressStyle};
-use ruffle_core::backend::audio::NullAudioBackend;
+use ruffle_core::backend::audio::capture::CaptureAudioBackend;
+use ruffle_core::backend::audio::{AudioBackend, NullAudioBackend};
+use ruffle_core::backend::local_connection::MemoryLocalConnectionBackend;
 use ruffle_core::backend::locale::NullLocaleBackend;
 use ruffle_core::backend::log::NullLogBackend;
 use ruffle_core::backend::navigator::NullNavigatorBackend;
+use ruffle_core::backend::socket::NullSocketBackend;
 use ruffle_core::backend::storage::MemoryStorageBackend;
 use ruffle_core::backend::ui::NullUiBackend;
+use ruffle_core::backend::video::SoftwareVideoBackend;
 use ruffle_core::tag_utils::SwfMovie;
 use ruffle_core::Player;
 use ruffle_render_wgpu::clap::{GraphicsBackend, PowerPreference};
@@ -60,6 +64,11 @@ struct Opt {
     #[clap(short, long)]
     silent: bool,
 
+    /// Also export the audio played during the captured frames, as a WAV file
+    /// alongside the frames
+    #[clap(long)]
+    audio: bool,
+
     #[clap(flatten)]
     size: SizeOpt,
 
@@ -92,8 +101,10 @@ fn take_screenshot(
     skipframes: u32,
     progress: &Option<ProgressBar>,
     size: SizeOpt,
-) -> Result<(Descriptors, Vec<RgbaImage>), Box<dyn std::error::Error>> {
+    with_audio: bool,
+) -> Result<(Descriptors, Vec<RgbaImage>, Option<Vec<u8>>), Box<dyn std::error::Error>> {
     let movie = SwfMovie::from_path(&swf_path)?;
+    let frame_time = 1000.0 / movie.header().frame_rate as f64;
 
     let width = size.width.unwrap_or_else(|| movie.width());
     let width = (width as f32 * size.scale).round() as u32;
@@ -101,12 +112,21 @@ fn take_screenshot(
     let height = size.height.unwrap_or_else(|| movie.height());
     let height = (height as f32 * size.scale).round() as u32;
 
+    let audio: Box<dyn AudioBackend> = if with_audio {
+        Box::new(CaptureAudioBackend::default())
+    } else {
+        Box::new(NullAudioBackend::new())
+    };
+
     let target = TextureTarget::new(&descriptors.device, (width, height));
     let player = Player::new(
         Box::new(WgpuRenderBackend::new(descriptors, target)?),
-        Box::new(NullAudioBackend::new()),
+        audio,
+        Box::new(SoftwareVideoBackend::new()),
         Box::new(NullNavigatorBackend::new()),
+        Box::new(NullSocketBackend::new()),
         Box::new(MemoryStorageBackend::default()),
+        Box::new(MemoryLocalConnectionBackend::default()),
         Box::new(NullLocaleBackend::new()),
         Box::new(NullLogBackend::new()),
         Box::new(NullUiBackend::new()),
@@ -130,6 +150,18 @@ fn take_screenshot(
             ));
         }
         player.lock().unwrap().run_frame();
+        if let Some(audio) = player
+            .lock()
+            .unwrap()
+            .audio_mut()
+            .downcast_mut::<CaptureAudioBackend>()
+        {
+            audio.advance(frame_time);
+            if i < skipframes {
+                // Only keep the audio of captured frames.
+                audio.take_samples();
+            }
+        }
         if i >= skipframes {
             player.lock().unwrap().render();
             let mut player = player.lock().unwrap();
@@ -150,6 +182,19 @@ fn take_screenshot(
         }
     }
 
+    let wav = if let Some(audio) = player
+        .lock()
+        .unwrap()
+        .audio()
+        .downcast_ref::<CaptureAudioBackend>()
+    {
+        let mut wav = Vec::new();
+        audio.write_wav(&mut wav)?;
+        Some(wav)
+    } else {
+        None
+    };
+
     let descriptors = Arc::try_unwrap(player)
         .ok()
         .unwrap()
@@ -159,7 +204,7 @@ fn take_screenshot(
         .ok()
         .unwrap()
         .descriptors();
-    Ok((descriptors, result))
+    Ok((descriptors, result, wav))
 }
 
 fn find_files(root: &Path, with_progress: bool) -> Vec<DirEntry> {
@@ -222,13 +267,14 @@ fn capture_single_swf(descriptors: Descriptors, opt: &Opt) -> Result<(), Box<dyn
         None
     };
 
-    let (_, frames) = take_screenshot(
+    let (_, frames, wav) = take_screenshot(
         descriptors,
         &opt.swf,
         opt.frames,
         opt.skipframes,
         &progress,
         opt.size,
+        opt.audio,
     )?;
 
     if let Some(progress) = &progress {
@@ -245,6 +291,16 @@ fn capture_single_swf(descriptors: Descriptors, opt: &Opt) -> Result<(), Box<dyn
         }
     }
 
+    if let Some(wav) = wav {
+        let mut path = PathBuf::from(&output);
+        if frames.len() == 1 {
+            path.set_extension("wav");
+        } else {
+            path.push("audio.wav");
+        }
+        std::fs::write(&path, wav)?;
+    }
+
     let message = if frames.len() == 1 {
         format!(
             "Saved first frame of {} to {}",
@@ -288,13 +344,14 @@ fn capture_multiple_swfs(mut descriptors: Descriptors, opt: &Opt) -> Result<(),
     };
 
     for file in &files {
-        let (new_descriptors, frames) = take_screenshot(
+        let (new_descriptors, frames, wav) = take_screenshot(
             descriptors,
             &file.path(),
             opt.frames,
             opt.skipframes,
             &progress,
             opt.size,
+            opt.audio,
         )?;
         descriptors = new_descriptors;
 
@@ -316,6 +373,10 @@ fn capture_multiple_swfs(mut descriptors: Descriptors, opt: &Opt) -> Result<(),
                 let _ = create_dir_all(parent);
             }
             frames.get(0).unwrap().save(&destination)?;
+            if let Some(wav) = wav {
+                destination.set_extension("wav");
+                std::fs::write(&destination, wav)?;
+            }
         } else {
             let mut parent = PathBuf::from(&output);
             relative_path.set_extension("");
@@ -326,6 +387,11 @@ fn capture_multiple_swfs(mut descriptors: Descriptors, opt: &Opt) -> Result<(),
                 destination.push(format!("{}.png", frame));
                 image.save(&destination)?;
             }
+            if let Some(wav) = wav {
+                let mut destination = parent.clone();
+                destination.push("audio.wav");
+                std::fs::write(&destination, wav)?;
+            }
         }
     }
 
@@ -1,17 +1,18 @@
 use ruffle_core::backend::render::{
+    premultiply_alpha_rgba,
     swf::{self, CharacterId, GradientInterpolation, GradientSpread},
-    Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, Color, JpegTagFormat, MovieLibrary,
-    RenderBackend, ShapeHandle, Transform,
+    unmultiply_alpha_rgba, Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, Color, JpegTagFormat,
+    MovieLibrary, RenderBackend, ShapeHandle, Transform,
 };
 use ruffle_core::color_transform::ColorTransform;
 use ruffle_core::shape_utils::{DistilledShape, DrawCommand};
 use ruffle_core::swf::Matrix;
 use ruffle_web_common::JsResult;
 use std::convert::TryInto;
-use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen::{Clamped, JsCast, JsValue};
 use web_sys::{
     CanvasGradient, CanvasPattern, CanvasRenderingContext2d, Element, HtmlCanvasElement,
-    HtmlImageElement, Path2d, SvgsvgElement,
+    HtmlImageElement, ImageData, Path2d, SvgsvgElement,
 };
 
 type Error = Box<dyn std::error::Error>;
@@ -22,6 +23,7 @@ pub struct WebCanvasRenderBackend {
     root_canvas: HtmlCanvasElement,
     render_targets: Vec<(HtmlCanvasElement, CanvasRenderingContext2d)>,
     cur_render_target: usize,
+    blend_modes: Vec<swf::BlendMode>,
     color_matrix: Element,
     shapes: Vec<ShapeData>,
     bitmaps: Vec<BitmapData>,
@@ -30,6 +32,18 @@ pub struct WebCanvasRenderBackend {
     use_color_transform_hack: bool,
     pixelated_property_value: &'static str,
     deactivating_mask: bool,
+    offscreen: Option<Offscreen>,
+}
+
+/// The state of the main render target, saved while drawing into a bitmap.
+struct Offscreen {
+    bitmap: BitmapHandle,
+    canvas: HtmlCanvasElement,
+    context: CanvasRenderingContext2d,
+    render_targets: Vec<(HtmlCanvasElement, CanvasRenderingContext2d)>,
+    cur_render_target: usize,
+    viewport_width: u32,
+    viewport_height: u32,
 }
 
 /// Canvas-drawable shape data extracted from an SWF file.
@@ -110,6 +124,10 @@ struct BitmapData {
     width: u32,
     height: u32,
     data: String,
+
+    /// A canvas holding the pixels of bitmaps updated at runtime.
+    /// Unlike `image`, which loads asynchronously, it can be drawn from immediately.
+    canvas: Option<HtmlCanvasElement>,
 }
 
 impl WebCanvasRenderBackend {
@@ -204,6 +222,7 @@ impl WebCanvasRenderBackend {
             root_canvas: canvas.clone(),
             render_targets,
             cur_render_target: 0,
+            blend_modes: Vec::new(),
             color_matrix,
             context,
             shapes: vec![],
@@ -212,6 +231,7 @@ impl WebCanvasRenderBackend {
             viewport_height: 0,
             use_color_transform_hack: is_firefox,
             deactivating_mask: false,
+            offscreen: None,
 
             // For rendering non-smoothed bitmaps.
             // crisp-edges works in Firefox, pixelated works in Chrome (and others)?
@@ -382,6 +402,7 @@ impl WebCanvasRenderBackend {
             width: metadata.width.into(),
             height: metadata.height.into(),
             data: jpeg_encoded,
+            canvas: None,
         });
         Ok(BitmapInfo {
             handle,
@@ -403,6 +424,7 @@ impl WebCanvasRenderBackend {
             width,
             height,
             data: png,
+            canvas: None,
         });
 
         Ok(BitmapInfo {
@@ -511,6 +533,7 @@ impl RenderBackend for WebCanvasRenderBackend {
             width: swf_tag.width.into(),
             height: swf_tag.height.into(),
             data: png,
+            canvas: None,
         });
         Ok(BitmapInfo {
             handle,
@@ -546,9 +569,13 @@ impl RenderBackend for WebCanvasRenderBackend {
         self.set_transform(&transform.matrix);
         self.set_color_filter(transform);
         if let Some(bitmap) = self.bitmaps.get(bitmap.0) {
-            let _ = self
-                .context
-                .draw_image_with_html_image_element(&bitmap.image, 0.0, 0.0);
+            let _ = if let Some(canvas) = &bitmap.canvas {
+                self.context
+                    .draw_image_with_html_canvas_element(canvas, 0.0, 0.0)
+            } else {
+                self.context
+                    .draw_image_with_html_image_element(&bitmap.image, 0.0, 0.0)
+            };
         }
         self.clear_color_filter();
     }
@@ -695,43 +722,172 @@ impl RenderBackend for WebCanvasRenderBackend {
             .unwrap();
     }
 
-    fn get_bitmap_pixels(&mut self, bitmap: BitmapHandle) -> Option<Bitmap> {
-        let window = web_sys::window().unwrap();
-        let document = window.document().unwrap();
+    fn push_filter_layer(&mut self) {
+        // The filtered display object is rendered to its own render target,
+        // and the filters are applied in software when the layer is popped.
+        self.push_render_target();
+    }
 
-        let canvas: HtmlCanvasElement = document
-            .create_element("canvas")
-            .unwrap()
-            .dyn_into()
-            .unwrap();
+    fn pop_filter_layer(&mut self, filters: &[swf::Filter]) {
+        let (layer_canvas, layer_context) = self.pop_render_target();
+        let width = layer_canvas.width();
+        let height = layer_canvas.height();
 
-        let context: CanvasRenderingContext2d = canvas
-            .get_context("2d")
-            .unwrap()
-            .unwrap()
-            .dyn_into()
-            .unwrap();
+        if let Ok(image_data) = layer_context.get_image_data(0.0, 0.0, width.into(), height.into())
+        {
+            // Canvas image data has straight alpha, but the filters work on premultiplied alpha.
+            let mut rgba = image_data.data().to_vec();
+            premultiply_alpha_rgba(&mut rgba);
+            ruffle_core::bitmap::filters::apply_filters(width, height, &mut rgba, filters);
+            unmultiply_alpha_rgba(&mut rgba);
+
+            if let Ok(image_data) =
+                ImageData::new_with_u8_clamped_array_and_sh(Clamped(&mut rgba[..]), width, height)
+            {
+                layer_context
+                    .put_image_data(&image_data, 0.0, 0.0)
+                    .warn_on_error();
+            }
+        }
 
-        let bitmap = &self.bitmaps[bitmap.0];
+        self.context.reset_transform().warn_on_error();
+        self.context
+            .draw_image_with_html_canvas_element(&layer_canvas, 0.0, 0.0)
+            .warn_on_error();
+    }
 
-        canvas.set_width(bitmap.width);
-        canvas.set_height(bitmap.height);
+    fn push_blend_mode(&mut self, blend_mode: swf::BlendMode) {
+        self.blend_modes.push(blend_mode);
+        self.push_render_target();
+    }
 
-        context
-            .draw_image_with_html_image_element(&bitmap.image, 0.0, 0.0)
-            .unwrap();
+    fn pop_blend_mode(&mut self) {
+        let blend_mode = self.blend_modes.pop().unwrap_or(swf::BlendMode::Normal);
+        let (layer_canvas, _) = self.pop_render_target();
+
+        // TODO: Subtract and invert have no canvas equivalent, so they are drawn normally.
+        let operation = match blend_mode {
+            swf::BlendMode::Multiply => "multiply",
+            swf::BlendMode::Screen => "screen",
+            swf::BlendMode::Lighten => "lighten",
+            swf::BlendMode::Darken => "darken",
+            swf::BlendMode::Difference => "difference",
+            swf::BlendMode::Add => "lighter",
+            swf::BlendMode::Alpha => "destination-in",
+            swf::BlendMode::Erase => "destination-out",
+            swf::BlendMode::Overlay => "overlay",
+            swf::BlendMode::HardLight => "hard-light",
+            _ => "source-over",
+        };
 
-        if let Ok(bitmap_pixels) =
-            context.get_image_data(0.0, 0.0, bitmap.width as f64, bitmap.height as f64)
-        {
-            Some(Bitmap {
-                width: bitmap.width,
-                height: bitmap.height,
-                data: BitmapFormat::Rgba(bitmap_pixels.data().to_vec()),
-            })
+        self.context.reset_transform().warn_on_error();
+        self.context
+            .set_global_composite_operation(operation)
+            .warn_on_error();
+        self.context
+            .draw_image_with_html_canvas_element(&layer_canvas, 0.0, 0.0)
+            .warn_on_error();
+        self.context
+            .set_global_composite_operation("source-over")
+            .warn_on_error();
+    }
+
+    fn begin_offscreen(&mut self, bitmap: BitmapHandle) -> bool {
+        if self.offscreen.is_some() {
+            return false;
+        }
+
+        // Only bitmaps updated at runtime have pixels that can be drawn over right away.
+        let (canvas, context) = match self.bitmaps.get(bitmap.0).and_then(|b| b.canvas.as_ref()) {
+            Some(source) => {
+                let target = create_canvas(source.width(), source.height());
+                match target {
+                    Ok((canvas, context))
+                        if context
+                            .draw_image_with_html_canvas_element(source, 0.0, 0.0)
+                            .is_ok() =>
+                    {
+                        (canvas, context)
+                    }
+                    _ => return false,
+                }
+            }
+            None => return false,
+        };
+
+        let (width, height) = (canvas.width(), canvas.height());
+        let render_targets = std::mem::replace(
+            &mut self.render_targets,
+            vec![(canvas.clone(), context.clone())],
+        );
+        self.offscreen = Some(Offscreen {
+            bitmap,
+            canvas: std::mem::replace(&mut self.canvas, canvas),
+            context: std::mem::replace(&mut self.context, context),
+            render_targets,
+            cur_render_target: std::mem::replace(&mut self.cur_render_target, 0),
+            viewport_width: std::mem::replace(&mut self.viewport_width, width),
+            viewport_height: std::mem::replace(&mut self.viewport_height, height),
+        });
+        self.context.reset_transform().warn_on_error();
+        self.deactivating_mask = false;
+        true
+    }
+
+    fn end_offscreen(&mut self) {
+        let offscreen = if let Some(offscreen) = self.offscreen.take() {
+            offscreen
         } else {
-            None
+            return;
+        };
+
+        let (canvas, context) = self.render_targets[0].clone();
+        // Any render targets pushed while drawing into the bitmap are discarded.
+        for (target, _) in self.render_targets.drain(1..) {
+            target.remove();
         }
+
+        self.canvas = offscreen.canvas;
+        self.context = offscreen.context;
+        self.render_targets = offscreen.render_targets;
+        self.cur_render_target = offscreen.cur_render_target;
+        self.viewport_width = offscreen.viewport_width;
+        self.viewport_height = offscreen.viewport_height;
+        self.deactivating_mask = false;
+
+        let (width, height) = (canvas.width(), canvas.height());
+        match context.get_image_data(0.0, 0.0, width.into(), height.into()) {
+            Ok(image_data) => {
+                // Canvas image data has straight alpha, but bitmaps are premultiplied.
+                let mut rgba = image_data.data().to_vec();
+                premultiply_alpha_rgba(&mut rgba);
+                if let Err(e) = self.update_texture(offscreen.bitmap, width, height, rgba) {
+                    log::error!("Couldn't update offscreen bitmap: {}", e);
+                }
+            }
+            Err(_) => log::error!("Couldn't read offscreen bitmap"),
+        }
+    }
+
+    fn get_bitmap_pixels(&mut self, bitmap: BitmapHandle) -> Option<Bitmap> {
+        let bitmap = self.bitmaps.get(bitmap.0)?;
+        let (_canvas, context) = create_canvas(bitmap.width, bitmap.height).ok()?;
+
+        let drawn = if let Some(canvas) = &bitmap.canvas {
+            context.draw_image_with_html_canvas_element(canvas, 0.0, 0.0)
+        } else {
+            context.draw_image_with_html_image_element(&bitmap.image, 0.0, 0.0)
+        };
+        drawn.ok()?;
+
+        let bitmap_pixels = context
+            .get_image_data(0.0, 0.0, bitmap.width.into(), bitmap.height.into())
+            .ok()?;
+        Some(Bitmap {
+            width: bitmap.width,
+            height: bitmap.height,
+            data: BitmapFormat::Rgba(bitmap_pixels.data().to_vec()),
+        })
     }
 
     fn register_bitmap_raw(
@@ -756,6 +912,19 @@ impl RenderBackend for WebCanvasRenderBackend {
         height: u32,
         rgba: Vec<u8>,
     ) -> Result<BitmapHandle, Error> {
+        let mut straight_rgba = rgba.clone();
+        unmultiply_alpha_rgba(&mut straight_rgba);
+        let (canvas, context) = create_canvas(width, height)?;
+        let image_data = ImageData::new_with_u8_clamped_array_and_sh(
+            Clamped(&mut straight_rgba[..]),
+            width,
+            height,
+        )
+        .into_js_result()?;
+        context
+            .put_image_data(&image_data, 0.0, 0.0)
+            .into_js_result()?;
+
         let png = Self::bitmap_to_png_data_uri(Bitmap {
             width,
             height,
@@ -772,6 +941,7 @@ impl RenderBackend for WebCanvasRenderBackend {
                 width,
                 height,
                 data: png,
+                canvas: Some(canvas),
             },
         );
 
@@ -779,6 +949,29 @@ impl RenderBackend for WebCanvasRenderBackend {
     }
 }
 
+/// Creates a canvas that isn't attached to the document.
+fn create_canvas(
+    width: u32,
+    height: u32,
+) -> Result<(HtmlCanvasElement, CanvasRenderingContext2d), Error> {
+    let window = web_sys::window().ok_or("Expected window")?;
+    let document = window.document().ok_or("Expected document")?;
+    let canvas: HtmlCanvasElement = document
+        .create_element("canvas")
+        .into_js_result()?
+        .dyn_into()
+        .map_err(|_| "Expected HtmlCanvasElement")?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context: CanvasRenderingContext2d = canvas
+        .get_context("2d")
+        .into_js_result()?
+        .ok_or("Could not create context")?
+        .dyn_into()
+        .map_err(|_| "Expected CanvasRenderingContext2d")?;
+    Ok((canvas, context))
+}
+
 #[allow(clippy::cognitive_complexity)]
 fn swf_shape_to_svg(
     shape: DistilledShape,
@@ -0,0 +1,38 @@
+#version 100
+
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+    precision highp float;
+#else
+    precision mediump float;
+#endif
+
+// Loops need constant bounds in GLSL ES 1.0, so this is the largest radius.
+#define MAX_RADIUS 128
+
+uniform sampler2D u_texture;
+
+// The distance between two samples in texture coordinates.
+uniform vec2 u_step_size;
+// The number of samples taken on each side of the center.
+uniform float u_radius;
+
+varying vec2 frag_uv;
+
+// One pass of a box blur, in the direction of `u_step_size`.
+void main() {
+    vec4 sum = vec4(0.0);
+    for( int i = -MAX_RADIUS; i <= MAX_RADIUS; i++ ) {
+        float offset = float(i);
+        if( offset < -u_radius ) {
+            continue;
+        }
+        if( offset > u_radius ) {
+            break;
+        }
+        vec2 uv = frag_uv + u_step_size * offset;
+        // Everything outside of the layer is transparent.
+        vec2 inside = step(vec2(0.0), uv) * step(uv, vec2(1.0));
+        sum += texture2D(u_texture, uv) * inside.x * inside.y;
+    }
+    gl_FragColor = sum / (u_radius * 2.0 + 1.0);
+}
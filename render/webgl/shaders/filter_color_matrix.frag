@@ -0,0 +1,25 @@
+#version 100
+
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+    precision highp float;
+#else
+    precision mediump float;
+#endif
+
+uniform sampler2D u_texture;
+
+// Applied to the unmultiplied color.
+uniform mat4 u_color_matrix;
+// Added after the matrix, in the range 0.0 to 1.0.
+uniform vec4 u_color_offset;
+
+varying vec2 frag_uv;
+
+void main() {
+    vec4 color = texture2D(u_texture, frag_uv);
+    if( color.a > 0.0 ) {
+        color.rgb /= color.a;
+    }
+    vec4 result = clamp(u_color_matrix * color + u_color_offset, vec4(0.0), vec4(1.0));
+    gl_FragColor = vec4(result.rgb * result.a, result.a);
+}
@@ -0,0 +1,58 @@
+#version 100
+
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+    precision highp float;
+#else
+    precision mediump float;
+#endif
+
+// Up to 15x15 weights. Loops need constant bounds in GLSL ES 1.0.
+#define MAX_WEIGHTS 225
+
+uniform sampler2D u_texture;
+
+// The unmultiplied color used outside of the layer.
+uniform vec4 u_default_color;
+// The size of a pixel in texture coordinates.
+uniform vec2 u_step_size;
+// The number of columns and rows of the matrix.
+uniform vec2 u_matrix_size;
+uniform float u_divisor;
+// Added after dividing, in the range 0.0 to 1.0.
+uniform float u_bias;
+// 1.0 to extend the edges of the layer instead of using `u_default_color`.
+uniform float u_clamped;
+// 1.0 to keep the alpha of the layer.
+uniform float u_preserve_alpha;
+// The weights, row by row, packed four to a vector.
+uniform vec4 u_weights[57];
+
+varying vec2 frag_uv;
+
+void main() {
+    vec4 sum = vec4(0.0);
+    vec2 center = floor(u_matrix_size / 2.0);
+    float num_weights = u_matrix_size.x * u_matrix_size.y;
+    for( int i = 0; i < MAX_WEIGHTS; i++ ) {
+        float index = float(i);
+        if( index >= num_weights ) {
+            break;
+        }
+        float row = floor((index + 0.5) / u_matrix_size.x);
+        float col = index - row * u_matrix_size.x;
+        vec2 uv = frag_uv + (vec2(col, row) - center) * u_step_size;
+        vec2 inside = step(vec2(0.0), uv) * step(uv, vec2(1.0));
+        vec4 color = texture2D(u_texture, uv);
+        // Premultiplied colors never exceed alpha, so this leaves
+        // transparent pixels black.
+        color.rgb /= max(color.a, 1.0 / 255.0);
+        color = mix(u_default_color, color, max(inside.x * inside.y, u_clamped));
+        sum += color * u_weights[i / 4][i - (i / 4) * 4];
+    }
+
+    vec4 color = sum / u_divisor + u_bias;
+    vec4 center_color = texture2D(u_texture, frag_uv);
+    color.a = mix(color.a, center_color.a, u_preserve_alpha);
+    color = clamp(color, vec4(0.0), vec4(1.0));
+    gl_FragColor = vec4(color.rgb * color.a, color.a);
+}
@@ -0,0 +1,55 @@
+#version 100
+
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+    precision highp float;
+#else
+    precision mediump float;
+#endif
+
+uniform sampler2D u_texture;
+// The blurred layer that the glow or shadow is made from.
+uniform sampler2D u_blurred;
+// The colors of the effect, indexed by its strength.
+uniform sampler2D u_gradient;
+
+// The distance between the layer and the effect in texture coordinates.
+uniform vec2 u_offset;
+uniform float u_strength;
+// The number of texels in `u_gradient`.
+uniform float u_gradient_size;
+// 1.0 for bevels, 0.0 for glows and shadows.
+uniform float u_bevel;
+// 1.0 to make the effect from the inverted alpha of the layer.
+uniform float u_invert;
+uniform float u_inner;
+uniform float u_knockout;
+// 1.0 to draw the effect on top of the layer, used by bevels.
+uniform float u_on_top;
+
+varying vec2 frag_uv;
+
+float blurred_alpha(vec2 uv) {
+    // Everything outside of the layer is transparent.
+    vec2 inside = step(vec2(0.0), uv) * step(uv, vec2(1.0));
+    return texture2D(u_blurred, uv).a * inside.x * inside.y;
+}
+
+// Glows, drop shadows and bevels, including their gradient variants.
+void main() {
+    vec4 src = texture2D(u_texture, frag_uv);
+    float shaded = blurred_alpha(frag_uv - u_offset);
+    float lit = blurred_alpha(frag_uv + u_offset);
+
+    // Glows and shadows use the blurred alpha, bevels the difference between
+    // the highlight and the shadow side.
+    float shadow = min(mix(shaded, 1.0 - shaded, u_invert) * u_strength, 1.0);
+    float bevel_amount = clamp((lit - shaded) * u_strength, -1.0, 1.0);
+    float t = mix(shadow, (bevel_amount + 1.0) * 0.5, u_bevel);
+    vec2 gradient_uv = vec2((t * (u_gradient_size - 1.0) + 0.5) / u_gradient_size, 0.5);
+    vec4 effect = texture2D(u_gradient, gradient_uv);
+
+    vec4 inner_color = mix(effect * src.a + src * (1.0 - effect.a * src.a), effect * src.a, u_knockout);
+    vec4 outer_color = mix(src + effect * (1.0 - src.a), effect * (1.0 - src.a), u_knockout);
+    vec4 on_top_color = mix(effect + src * (1.0 - effect.a), effect, u_knockout);
+    gl_FragColor = mix(mix(outer_color, inner_color, u_inner), on_top_color, u_on_top);
+}
@@ -0,0 +1,380 @@
+//! Display object filters, applied to the layer that a filtered object was drawn into
+//! with a series of passes over the whole viewport.
+
+use crate::{
+    Error, Mesh, RenderTarget, ShaderProgram, ShaderUniform, WebGlRenderBackend,
+    FILTER_BLUR_FRAGMENT_GLSL, FILTER_COLOR_MATRIX_FRAGMENT_GLSL, FILTER_CONVOLUTION_FRAGMENT_GLSL,
+    FILTER_EFFECT_FRAGMENT_GLSL,
+};
+use ruffle_core::bitmap::filters::{EffectParams, Pixel};
+use ruffle_core::swf::{self, Filter};
+use ruffle_web_common::JsResult;
+use web_sys::{WebGlRenderingContext as Gl, WebGlShader, WebGlTexture};
+
+/// The largest blur radius supported by `filter_blur.frag`.
+const MAX_BLUR_RADIUS: f32 = 128.0;
+
+/// The largest number of weights in a convolution matrix supported by
+/// `filter_convolution.frag`, e.g. a 15x15 matrix.
+const MAX_CONVOLUTION_WEIGHTS: usize = 225;
+
+/// A filter shader, along with a quad to draw it with.
+pub struct FilterProgram {
+    program: ShaderProgram,
+    quad: Mesh,
+}
+
+pub struct FilterPrograms {
+    blur: FilterProgram,
+    effect: FilterProgram,
+    color_matrix: FilterProgram,
+    convolution: FilterProgram,
+}
+
+impl WebGlRenderBackend {
+    pub(crate) fn build_filter_programs(
+        &self,
+        texture_vertex: &WebGlShader,
+    ) -> Result<FilterPrograms, Error> {
+        let build = |glsl: &str| -> Result<FilterProgram, Error> {
+            let fragment = Self::compile_shader(&self.gl, Gl::FRAGMENT_SHADER, glsl)?;
+            let program = ShaderProgram::new(&self.gl, texture_vertex, &fragment)?;
+            let quad = self.build_quad_mesh(&program)?;
+            Ok(FilterProgram { program, quad })
+        };
+
+        Ok(FilterPrograms {
+            blur: build(FILTER_BLUR_FRAGMENT_GLSL)?,
+            effect: build(FILTER_EFFECT_FRAGMENT_GLSL)?,
+            color_matrix: build(FILTER_COLOR_MATRIX_FRAGMENT_GLSL)?,
+            convolution: build(FILTER_CONVOLUTION_FRAGMENT_GLSL)?,
+        })
+    }
+
+    /// Applies `filters` in order to a layer, returning the filtered layer.
+    /// Blending and the stencil test must be disabled.
+    pub(crate) fn apply_filters(
+        &self,
+        programs: &FilterPrograms,
+        target: RenderTarget,
+        filters: &[Filter],
+    ) -> RenderTarget {
+        let mut target = target;
+        for filter in filters {
+            match self.apply_filter(programs, &target.texture, filter) {
+                Ok(Some(filtered)) => {
+                    self.delete_render_target(target);
+                    target = filtered;
+                }
+                Ok(None) => (),
+                Err(e) => {
+                    log::warn!("Unable to apply filter: {}", e);
+                    break;
+                }
+            }
+        }
+        target
+    }
+
+    /// Returns `None` if the filter doesn't change the layer.
+    fn apply_filter(
+        &self,
+        programs: &FilterPrograms,
+        texture: &WebGlTexture,
+        filter: &Filter,
+    ) -> Result<Option<RenderTarget>, Error> {
+        let effect = match filter {
+            Filter::BlurFilter(filter) => {
+                return self.blur(
+                    programs,
+                    texture,
+                    filter.blur_x,
+                    filter.blur_y,
+                    filter.num_passes,
+                );
+            }
+            Filter::ColorMatrixFilter(filter) => {
+                return self.color_matrix(programs, texture, filter).map(Some);
+            }
+            Filter::ConvolutionFilter(filter) => {
+                return self.convolution(programs, texture, filter);
+            }
+            _ => match EffectParams::from_filter(filter) {
+                Some(effect) => effect,
+                None => return Ok(None),
+            },
+        };
+
+        let gradient = self.create_gradient_texture(&effect.colors)?;
+        let blurred = match self.blur(
+            programs,
+            texture,
+            effect.blur_x,
+            effect.blur_y,
+            effect.passes,
+        ) {
+            Ok(blurred) => blurred,
+            Err(e) => {
+                self.gl.delete_texture(Some(&gradient));
+                return Err(e);
+            }
+        };
+
+        let width = self.renderbuffer_width as f32;
+        let height = self.renderbuffer_height as f32;
+        let flag = |value: bool| if value { 1.0 } else { 0.0 };
+        let blurred_texture = blurred.as_ref().map_or(texture, |blurred| &blurred.texture);
+        let output = self.filter_pass(
+            &programs.effect,
+            &[texture, blurred_texture, &gradient],
+            |program| {
+                let gl = &self.gl;
+                // Rows of GL textures go from the bottom up.
+                program.uniform2fv(
+                    gl,
+                    ShaderUniform::Offset,
+                    &[
+                        effect.offset.0 as f32 / width,
+                        -effect.offset.1 as f32 / height,
+                    ],
+                );
+                program.uniform1f(gl, ShaderUniform::Strength, effect.strength);
+                program.uniform1f(gl, ShaderUniform::GradientSize, effect.colors.len() as f32);
+                program.uniform1f(gl, ShaderUniform::Bevel, flag(effect.is_bevel));
+                program.uniform1f(gl, ShaderUniform::Invert, flag(effect.invert));
+                program.uniform1f(gl, ShaderUniform::Inner, flag(effect.is_inner));
+                program.uniform1f(gl, ShaderUniform::Knockout, flag(effect.is_knockout));
+                program.uniform1f(gl, ShaderUniform::OnTop, flag(effect.is_on_top));
+            },
+        );
+
+        if let Some(blurred) = blurred {
+            self.delete_render_target(blurred);
+        }
+        self.gl.delete_texture(Some(&gradient));
+        output.map(Some)
+    }
+
+    /// Blurs a texture with `passes` iterations of a box blur, each made of a horizontal and
+    /// a vertical pass. Returns `None` if the blur doesn't change the texture.
+    fn blur(
+        &self,
+        programs: &FilterPrograms,
+        texture: &WebGlTexture,
+        blur_x: f64,
+        blur_y: f64,
+        passes: u8,
+    ) -> Result<Option<RenderTarget>, Error> {
+        let width = self.renderbuffer_width as f32;
+        let height = self.renderbuffer_height as f32;
+        // Flash specifies the blur amount as the width of the box in pixels.
+        let radius_x = ((blur_x.max(0.0) / 2.0).floor() as f32).min(MAX_BLUR_RADIUS);
+        let radius_y = ((blur_y.max(0.0) / 2.0).floor() as f32).min(MAX_BLUR_RADIUS);
+        let directions = [
+            ([1.0 / width, 0.0], radius_x),
+            ([0.0, 1.0 / height], radius_y),
+        ];
+
+        let mut blurred: Option<RenderTarget> = None;
+        for _ in 0..passes {
+            for &(step_size, radius) in &directions {
+                if radius <= 0.0 {
+                    continue;
+                }
+                let input = blurred.as_ref().map_or(texture, |blurred| &blurred.texture);
+                let output = self.filter_pass(&programs.blur, &[input], |program| {
+                    program.uniform2fv(&self.gl, ShaderUniform::StepSize, &step_size);
+                    program.uniform1f(&self.gl, ShaderUniform::Radius, radius);
+                });
+                if let Some(previous) = blurred.take() {
+                    self.delete_render_target(previous);
+                }
+                blurred = Some(output?);
+            }
+        }
+        Ok(blurred)
+    }
+
+    fn color_matrix(
+        &self,
+        programs: &FilterPrograms,
+        texture: &WebGlTexture,
+        filter: &swf::ColorMatrixFilter,
+    ) -> Result<RenderTarget, Error> {
+        let m = &filter.matrix;
+        // Each row of the filter's matrix calculates one output channel. GLSL matrices
+        // are column-major, so each column holds the weights of one input channel.
+        let mut matrix = [[0.0; 4]; 4];
+        for (input, column) in matrix.iter_mut().enumerate() {
+            for (output, weight) in column.iter_mut().enumerate() {
+                *weight = m[output * 5 + input] as f32;
+            }
+        }
+        // The offset column is in the 0-255 range.
+        let offset = [
+            (m[4] / 255.0) as f32,
+            (m[9] / 255.0) as f32,
+            (m[14] / 255.0) as f32,
+            (m[19] / 255.0) as f32,
+        ];
+
+        self.filter_pass(&programs.color_matrix, &[texture], |program| {
+            program.uniform_matrix4fv(&self.gl, ShaderUniform::ColorMatrix, &matrix);
+            program.uniform4fv(&self.gl, ShaderUniform::ColorOffset, &offset);
+        })
+    }
+
+    /// Returns `None` if the filter's matrix is empty or too large.
+    fn convolution(
+        &self,
+        programs: &FilterPrograms,
+        texture: &WebGlTexture,
+        filter: &swf::ConvolutionFilter,
+    ) -> Result<Option<RenderTarget>, Error> {
+        let cols = usize::from(filter.num_matrix_cols);
+        let rows = usize::from(filter.num_matrix_rows);
+        if cols == 0 || rows == 0 || filter.matrix.len() < cols * rows {
+            return Ok(None);
+        }
+        if cols * rows > MAX_CONVOLUTION_WEIGHTS {
+            log::warn!(
+                "Convolution filter with a {}x{} matrix is unsupported",
+                cols,
+                rows
+            );
+            return Ok(None);
+        }
+
+        let width = self.renderbuffer_width as f32;
+        let height = self.renderbuffer_height as f32;
+        let color = &filter.default_color;
+        let default_color = [
+            f32::from(color.r) / 255.0,
+            f32::from(color.g) / 255.0,
+            f32::from(color.b) / 255.0,
+            f32::from(color.a) / 255.0,
+        ];
+        // The shader packs the weights four to a vector.
+        let mut weights = vec![0.0; (MAX_CONVOLUTION_WEIGHTS + 3) / 4 * 4];
+        for (weight, value) in weights.iter_mut().zip(&filter.matrix[..cols * rows]) {
+            *weight = *value as f32;
+        }
+        let divisor = if filter.divisor != 0.0 {
+            filter.divisor as f32
+        } else {
+            1.0
+        };
+        let flag = |value: bool| if value { 1.0 } else { 0.0 };
+
+        let output = self.filter_pass(&programs.convolution, &[texture], |program| {
+            let gl = &self.gl;
+            program.uniform4fv(gl, ShaderUniform::DefaultColor, &default_color);
+            // Rows of GL textures go from the bottom up.
+            program.uniform2fv(gl, ShaderUniform::StepSize, &[1.0 / width, -1.0 / height]);
+            program.uniform2fv(gl, ShaderUniform::MatrixSize, &[cols as f32, rows as f32]);
+            program.uniform1f(gl, ShaderUniform::Divisor, divisor);
+            program.uniform1f(gl, ShaderUniform::Bias, filter.bias as f32 / 255.0);
+            program.uniform1f(gl, ShaderUniform::Clamped, flag(filter.is_clamped));
+            program.uniform1f(
+                gl,
+                ShaderUniform::PreserveAlpha,
+                flag(filter.is_preserve_alpha),
+            );
+            program.uniform4fv(gl, ShaderUniform::Weights, &weights);
+        })?;
+        Ok(Some(output))
+    }
+
+    /// Uploads the colors of an effect to a texture that is one pixel high.
+    fn create_gradient_texture(&self, colors: &[Pixel]) -> Result<WebGlTexture, Error> {
+        let rgba: Vec<u8> = colors
+            .iter()
+            .flat_map(|color| {
+                color
+                    .iter()
+                    .map(|c| (c.max(0.0).min(1.0) * 255.0).round() as u8)
+            })
+            .collect();
+
+        let gl = &self.gl;
+        let texture = gl.create_texture().ok_or("Unable to create texture")?;
+        gl.bind_texture(Gl::TEXTURE_2D, Some(&texture));
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+            Gl::TEXTURE_2D,
+            0,
+            Gl::RGBA as i32,
+            colors.len() as i32,
+            1,
+            0,
+            Gl::RGBA,
+            Gl::UNSIGNED_BYTE,
+            Some(&rgba),
+        )
+        .into_js_result()?;
+        // The gradient is sampled between its texels to interpolate the colors.
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MAG_FILTER, Gl::LINEAR as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MIN_FILTER, Gl::LINEAR as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_S, Gl::CLAMP_TO_EDGE as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_T, Gl::CLAMP_TO_EDGE as i32);
+        Ok(texture)
+    }
+
+    /// Runs a filter shader over the whole viewport, writing the result into a new target.
+    /// `textures` are bound to `u_texture`, `u_blurred` and `u_gradient`, in that order.
+    fn filter_pass(
+        &self,
+        filter_program: &FilterProgram,
+        textures: &[&WebGlTexture],
+        set_uniforms: impl FnOnce(&ShaderProgram),
+    ) -> Result<RenderTarget, Error> {
+        let target = self.create_render_target(false)?;
+        self.gl
+            .bind_framebuffer(Gl::FRAMEBUFFER, Some(&target.framebuffer));
+        self.gl.clear_color(0.0, 0.0, 0.0, 0.0);
+        self.gl.clear(Gl::COLOR_BUFFER_BIT);
+
+        let program = &filter_program.program;
+        self.gl.use_program(Some(&program.program));
+        program.uniform_matrix4fv(
+            &self.gl,
+            ShaderUniform::WorldMatrix,
+            &[
+                [2.0, 0.0, 0.0, 0.0],
+                [0.0, 2.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [-1.0, -1.0, 0.0, 1.0],
+            ],
+        );
+        program.uniform_matrix4fv(
+            &self.gl,
+            ShaderUniform::ViewMatrix,
+            &[
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        );
+        program.uniform_matrix3fv(
+            &self.gl,
+            ShaderUniform::TextureMatrix,
+            &[[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        );
+
+        for (unit, texture) in textures.iter().enumerate() {
+            self.gl.active_texture(Gl::TEXTURE0 + unit as u32);
+            self.gl.bind_texture(Gl::TEXTURE_2D, Some(texture));
+        }
+        self.gl.active_texture(Gl::TEXTURE0);
+        program.uniform1i(&self.gl, ShaderUniform::BitmapTexture, 0);
+        program.uniform1i(&self.gl, ShaderUniform::BlurredTexture, 1);
+        program.uniform1i(&self.gl, ShaderUniform::GradientTexture, 2);
+        set_uniforms(program);
+
+        let draw = &filter_program.quad.draws[0];
+        self.bind_vertex_array(Some(&draw.vao));
+        self.gl
+            .draw_elements_with_i32(Gl::TRIANGLES, draw.num_indices, Gl::UNSIGNED_INT, 0);
+        Ok(target)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::filters::FilterPrograms;
 use ruffle_core::backend::render::swf;
 use ruffle_core::backend::render::{
     srgb_to_linear, Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, Color, MovieLibrary,
@@ -15,6 +16,8 @@ use web_sys::{
     WebGlTexture, WebGlUniformLocation, WebGlVertexArrayObject, WebglDebugRendererInfo,
 };
 
+mod filters;
+
 type Error = Box<dyn std::error::Error>;
 
 const COLOR_VERTEX_GLSL: &str = include_str!("../shaders/color.vert");
@@ -22,6 +25,10 @@ const COLOR_FRAGMENT_GLSL: &str = include_str!("../shaders/color.frag");
 const TEXTURE_VERTEX_GLSL: &str = include_str!("../shaders/texture.vert");
 const GRADIENT_FRAGMENT_GLSL: &str = include_str!("../shaders/gradient.frag");
 const BITMAP_FRAGMENT_GLSL: &str = include_str!("../shaders/bitmap.frag");
+const FILTER_BLUR_FRAGMENT_GLSL: &str = include_str!("../shaders/filter_blur.frag");
+const FILTER_EFFECT_FRAGMENT_GLSL: &str = include_str!("../shaders/filter_effect.frag");
+const FILTER_COLOR_MATRIX_FRAGMENT_GLSL: &str = include_str!("../shaders/filter_color_matrix.frag");
+const FILTER_CONVOLUTION_FRAGMENT_GLSL: &str = include_str!("../shaders/filter_convolution.frag");
 const NUM_VERTEX_ATTRIBUTES: u32 = 2;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -50,6 +57,14 @@ pub struct WebGlRenderBackend {
     bitmap_program: ShaderProgram,
     gradient_program: ShaderProgram,
 
+    /// The shaders used to apply filters, or `None` if they couldn't be compiled,
+    /// in which case filtered objects are drawn unfiltered.
+    filter_programs: Option<FilterPrograms>,
+
+    /// The layers that filtered display objects are currently being drawn into.
+    /// A `None` layer couldn't be created, so its object is drawn directly.
+    filter_layers: Vec<Option<FilterLayer>>,
+
     shape_tessellator: ShapeTessellator,
 
     textures: Vec<Texture>,
@@ -73,6 +88,9 @@ pub struct WebGlRenderBackend {
     view_height: i32,
     view_matrix: [[f32; 4]; 4],
 
+    /// The bitmap being drawn into by `BitmapData.draw`, if any.
+    offscreen: Option<Offscreen>,
+
     bitmap_registry: HashMap<BitmapHandle, Bitmap>,
 }
 
@@ -182,7 +200,13 @@ impl WebGlRenderBackend {
         let gradient_program = ShaderProgram::new(&gl, &texture_vertex, &gradient_fragment)?;
 
         gl.enable(Gl::BLEND);
-        gl.blend_func(Gl::SRC_ALPHA, Gl::ONE_MINUS_SRC_ALPHA);
+        // Alpha is always blended as premultiplied, so that filter layers end up premultiplied.
+        gl.blend_func_separate(
+            Gl::SRC_ALPHA,
+            Gl::ONE_MINUS_SRC_ALPHA,
+            Gl::ONE,
+            Gl::ONE_MINUS_SRC_ALPHA,
+        );
 
         // Necessary to load RGB textures (alignment defaults to 4).
         gl.pixel_storei(Gl::UNPACK_ALIGNMENT, 1);
@@ -199,6 +223,9 @@ impl WebGlRenderBackend {
             gradient_program,
             bitmap_program,
 
+            filter_programs: None,
+            filter_layers: vec![],
+
             shape_tessellator: ShapeTessellator::new(),
 
             meshes: vec![],
@@ -210,6 +237,7 @@ impl WebGlRenderBackend {
             view_width: 1,
             view_height: 1,
             view_matrix: [[0.0; 4]; 4],
+            offscreen: None,
 
             mask_state: MaskState::NoMask,
             num_masks: 0,
@@ -228,6 +256,14 @@ impl WebGlRenderBackend {
         renderer.meshes.push(bitmap_quad_mesh);
         renderer.set_viewport_dimensions(1, 1);
 
+        match renderer.build_filter_programs(&texture_vertex) {
+            Ok(filter_programs) => renderer.filter_programs = Some(filter_programs),
+            Err(e) => log::warn!(
+                "Unable to create filter shaders, filters are disabled: {}",
+                e
+            ),
+        }
+
         Ok(renderer)
     }
 
@@ -464,6 +500,166 @@ impl WebGlRenderBackend {
         Ok(())
     }
 
+    /// Creates a texture the size of the viewport that can be drawn into.
+    fn create_render_target(&self, with_stencil: bool) -> Result<RenderTarget, Error> {
+        let gl = &self.gl;
+        let texture = gl.create_texture().ok_or("Unable to create texture")?;
+        gl.bind_texture(Gl::TEXTURE_2D, Some(&texture));
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+            Gl::TEXTURE_2D,
+            0,
+            Gl::RGBA as i32,
+            self.renderbuffer_width,
+            self.renderbuffer_height,
+            0,
+            Gl::RGBA,
+            Gl::UNSIGNED_BYTE,
+            None,
+        )
+        .into_js_result()?;
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MAG_FILTER, Gl::LINEAR as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MIN_FILTER, Gl::LINEAR as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_S, Gl::CLAMP_TO_EDGE as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_T, Gl::CLAMP_TO_EDGE as i32);
+
+        let framebuffer = gl
+            .create_framebuffer()
+            .ok_or("Unable to create framebuffer")?;
+        gl.bind_framebuffer(Gl::FRAMEBUFFER, Some(&framebuffer));
+        gl.framebuffer_texture_2d(
+            Gl::FRAMEBUFFER,
+            Gl::COLOR_ATTACHMENT0,
+            Gl::TEXTURE_2D,
+            Some(&texture),
+            0,
+        );
+
+        let stencil_renderbuffer = if with_stencil {
+            let renderbuffer = gl
+                .create_renderbuffer()
+                .ok_or("Unable to create renderbuffer")?;
+            gl.bind_renderbuffer(Gl::RENDERBUFFER, Some(&renderbuffer));
+            gl.renderbuffer_storage(
+                Gl::RENDERBUFFER,
+                Gl::STENCIL_INDEX8,
+                self.renderbuffer_width,
+                self.renderbuffer_height,
+            );
+            gl.framebuffer_renderbuffer(
+                Gl::FRAMEBUFFER,
+                Gl::STENCIL_ATTACHMENT,
+                Gl::RENDERBUFFER,
+                Some(&renderbuffer),
+            );
+            Some(renderbuffer)
+        } else {
+            None
+        };
+        gl.check_error("create_render_target")?;
+
+        Ok(RenderTarget {
+            framebuffer,
+            texture,
+            stencil_renderbuffer,
+        })
+    }
+
+    fn delete_render_target(&self, target: RenderTarget) {
+        self.gl.delete_framebuffer(Some(&target.framebuffer));
+        self.gl.delete_texture(Some(&target.texture));
+        if let Some(renderbuffer) = &target.stencil_renderbuffer {
+            self.gl.delete_renderbuffer(Some(renderbuffer));
+        }
+    }
+
+    /// Binds the framebuffer that is currently drawn into: the innermost filter layer,
+    /// or else the offscreen bitmap, the MSAA render buffer or the canvas.
+    fn bind_current_framebuffer(&self) {
+        let framebuffer = self
+            .filter_layers
+            .iter()
+            .rev()
+            .flatten()
+            .map(|layer| &layer.target.framebuffer)
+            .next()
+            .or_else(|| {
+                self.offscreen
+                    .as_ref()
+                    .map(|offscreen| &offscreen.framebuffer)
+            })
+            .or_else(|| {
+                self.msaa_buffers
+                    .as_ref()
+                    .map(|msaa_buffers| &msaa_buffers.render_framebuffer)
+            });
+        self.gl.bind_framebuffer(Gl::FRAMEBUFFER, framebuffer);
+    }
+
+    /// Creates a framebuffer that draws into the texture of `bitmap`, on top of its contents.
+    fn create_offscreen(&self, bitmap: BitmapHandle) -> Result<Offscreen, Error> {
+        let texture = self
+            .textures
+            .get(bitmap.0)
+            .ok_or("Bitmap is not registered")?;
+        let gl = &self.gl;
+
+        let framebuffer = gl
+            .create_framebuffer()
+            .ok_or("Unable to create framebuffer")?;
+        gl.bind_framebuffer(Gl::FRAMEBUFFER, Some(&framebuffer));
+        gl.framebuffer_texture_2d(
+            Gl::FRAMEBUFFER,
+            Gl::COLOR_ATTACHMENT0,
+            Gl::TEXTURE_2D,
+            Some(&texture.texture),
+            0,
+        );
+
+        let stencil_renderbuffer = gl
+            .create_renderbuffer()
+            .ok_or("Unable to create renderbuffer")?;
+        gl.bind_renderbuffer(Gl::RENDERBUFFER, Some(&stencil_renderbuffer));
+        gl.renderbuffer_storage(
+            Gl::RENDERBUFFER,
+            Gl::STENCIL_INDEX8,
+            texture.width as i32,
+            texture.height as i32,
+        );
+        gl.framebuffer_renderbuffer(
+            Gl::FRAMEBUFFER,
+            Gl::STENCIL_ATTACHMENT,
+            Gl::RENDERBUFFER,
+            Some(&stencil_renderbuffer),
+        );
+
+        let offscreen = Offscreen {
+            bitmap,
+            width: texture.width,
+            height: texture.height,
+            framebuffer,
+            stencil_renderbuffer,
+            view_width: self.view_width,
+            view_height: self.view_height,
+            renderbuffer_width: self.renderbuffer_width,
+            renderbuffer_height: self.renderbuffer_height,
+        };
+
+        if gl.check_framebuffer_status(Gl::FRAMEBUFFER) != Gl::FRAMEBUFFER_COMPLETE {
+            self.delete_offscreen(&offscreen);
+            self.bind_current_framebuffer();
+            return Err("Offscreen framebuffer is incomplete".into());
+        }
+        gl.check_error("create_offscreen")?;
+
+        Ok(offscreen)
+    }
+
+    fn delete_offscreen(&self, offscreen: &Offscreen) {
+        self.gl.delete_framebuffer(Some(&offscreen.framebuffer));
+        self.gl
+            .delete_renderbuffer(Some(&offscreen.stencil_renderbuffer));
+    }
+
     fn register_shape_internal(
         &mut self,
         shape: DistilledShape,
@@ -694,6 +890,58 @@ impl WebGlRenderBackend {
         }
     }
 
+    /// Draws a premultiplied texture over the whole viewport, using the current blend mode.
+    /// The view matrix of the bitmap program is changed, so `active_program` must be reset
+    /// before drawing anything else.
+    fn draw_fullscreen_texture(&self, texture: &WebGlTexture) {
+        let program = &self.bitmap_program;
+        self.gl.use_program(Some(&program.program));
+
+        // Scale to fill screen.
+        program.uniform_matrix4fv(
+            &self.gl,
+            ShaderUniform::WorldMatrix,
+            &[
+                [2.0, 0.0, 0.0, 0.0],
+                [0.0, 2.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [-1.0, -1.0, 0.0, 1.0],
+            ],
+        );
+        program.uniform_matrix4fv(
+            &self.gl,
+            ShaderUniform::ViewMatrix,
+            &[
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        );
+        program.uniform4fv(&self.gl, ShaderUniform::MultColor, &[1.0, 1.0, 1.0, 1.0]);
+        program.uniform4fv(&self.gl, ShaderUniform::AddColor, &[0.0, 0.0, 0.0, 0.0]);
+
+        program.uniform_matrix3fv(
+            &self.gl,
+            ShaderUniform::TextureMatrix,
+            &[[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        );
+
+        self.gl.active_texture(Gl::TEXTURE0);
+        self.gl.bind_texture(Gl::TEXTURE_2D, Some(texture));
+        program.uniform1i(&self.gl, ShaderUniform::BitmapTexture, 0);
+
+        // Render the quad.
+        let quad = &self.meshes[self.bitmap_quad_shape.0];
+        self.bind_vertex_array(Some(&quad.draws[0].vao));
+        self.gl.draw_elements_with_i32(
+            Gl::TRIANGLES,
+            quad.draws[0].num_indices,
+            Gl::UNSIGNED_INT,
+            0,
+        );
+    }
+
     fn register_bitmap(&mut self, bitmap: Bitmap) -> Result<BitmapInfo, Error> {
         let texture = self.gl.create_texture().unwrap();
         self.gl.bind_texture(Gl::TEXTURE_2D, Some(&texture));
@@ -901,54 +1149,7 @@ impl RenderBackend for WebGlRenderBackend {
                 self.gl.drawing_buffer_height(),
             );
 
-            let program = &self.bitmap_program;
-            self.gl.use_program(Some(&program.program));
-
-            // Scale to fill screen.
-            program.uniform_matrix4fv(
-                &self.gl,
-                ShaderUniform::WorldMatrix,
-                &[
-                    [2.0, 0.0, 0.0, 0.0],
-                    [0.0, 2.0, 0.0, 0.0],
-                    [0.0, 0.0, 1.0, 0.0],
-                    [-1.0, -1.0, 0.0, 1.0],
-                ],
-            );
-            program.uniform_matrix4fv(
-                &self.gl,
-                ShaderUniform::ViewMatrix,
-                &[
-                    [1.0, 0.0, 0.0, 0.0],
-                    [0.0, 1.0, 0.0, 0.0],
-                    [0.0, 0.0, 1.0, 0.0],
-                    [0.0, 0.0, 0.0, 1.0],
-                ],
-            );
-            program.uniform4fv(&self.gl, ShaderUniform::MultColor, &[1.0, 1.0, 1.0, 1.0]);
-            program.uniform4fv(&self.gl, ShaderUniform::AddColor, &[0.0, 0.0, 0.0, 0.0]);
-
-            program.uniform_matrix3fv(
-                &self.gl,
-                ShaderUniform::TextureMatrix,
-                &[[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
-            );
-
-            // Bind the framebuffer texture.
-            self.gl.active_texture(Gl2::TEXTURE0);
-            self.gl
-                .bind_texture(Gl2::TEXTURE_2D, Some(&msaa_buffers.framebuffer_texture));
-            program.uniform1i(&self.gl, ShaderUniform::BitmapTexture, 0);
-
-            // Render the quad.
-            let quad = &self.meshes[self.bitmap_quad_shape.0];
-            self.bind_vertex_array(Some(&quad.draws[0].vao));
-            self.gl.draw_elements_with_i32(
-                Gl::TRIANGLES,
-                quad.draws[0].num_indices,
-                Gl::UNSIGNED_INT,
-                0,
-            );
+            self.draw_fullscreen_texture(&msaa_buffers.framebuffer_texture);
         }
     }
 
@@ -1020,7 +1221,12 @@ impl RenderBackend for WebGlRenderBackend {
                 self.add_color = None;
 
                 if (src_blend, dst_blend) != self.blend_func {
-                    self.gl.blend_func(src_blend, dst_blend);
+                    self.gl.blend_func_separate(
+                        src_blend,
+                        dst_blend,
+                        Gl::ONE,
+                        Gl::ONE_MINUS_SRC_ALPHA,
+                    );
                     self.blend_func = (src_blend, dst_blend);
                 }
             }
@@ -1122,7 +1328,12 @@ impl RenderBackend for WebGlRenderBackend {
                 self.add_color = None;
 
                 if (src_blend, dst_blend) != self.blend_func {
-                    self.gl.blend_func(src_blend, dst_blend);
+                    self.gl.blend_func_separate(
+                        src_blend,
+                        dst_blend,
+                        Gl::ONE,
+                        Gl::ONE_MINUS_SRC_ALPHA,
+                    );
                     self.blend_func = (src_blend, dst_blend);
                 }
             }
@@ -1268,7 +1479,8 @@ impl RenderBackend for WebGlRenderBackend {
             self.add_color = None;
 
             if (src_blend, dst_blend) != self.blend_func {
-                self.gl.blend_func(src_blend, dst_blend);
+                self.gl
+                    .blend_func_separate(src_blend, dst_blend, Gl::ONE, Gl::ONE_MINUS_SRC_ALPHA);
                 self.blend_func = (src_blend, dst_blend);
             }
         };
@@ -1329,6 +1541,172 @@ impl RenderBackend for WebGlRenderBackend {
         self.mask_state_dirty = true;
     }
 
+    fn push_filter_layer(&mut self) {
+        let layer = if self.filter_programs.is_some() {
+            match self.create_render_target(true) {
+                Ok(target) => Some(FilterLayer {
+                    target,
+                    mask_state: self.mask_state,
+                    num_masks: self.num_masks,
+                }),
+                Err(e) => {
+                    log::warn!("Unable to create filter layer: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let created = layer.is_some();
+        self.filter_layers.push(layer);
+        if !created {
+            return;
+        }
+
+        // Masks of the parent don't apply while drawing into the layer.
+        self.mask_state = MaskState::NoMask;
+        self.num_masks = 0;
+        self.mask_state_dirty = true;
+        self.bind_current_framebuffer();
+        self.set_stencil_state();
+        self.gl.clear_color(0.0, 0.0, 0.0, 0.0);
+        self.gl.stencil_mask(0xff);
+        self.gl.clear(Gl::COLOR_BUFFER_BIT | Gl::STENCIL_BUFFER_BIT);
+    }
+
+    fn pop_filter_layer(&mut self, filters: &[swf::Filter]) {
+        let (layer, filter_programs) = match (self.filter_layers.pop(), &self.filter_programs) {
+            (Some(Some(layer)), Some(filter_programs)) => (layer, filter_programs),
+            _ => return,
+        };
+
+        // The passes replace every pixel of their output.
+        self.gl.disable(Gl::BLEND);
+        self.gl.disable(Gl::STENCIL_TEST);
+        self.gl.color_mask(true, true, true, true);
+        let target = self.apply_filters(filter_programs, layer.target, filters);
+        self.gl.enable(Gl::BLEND);
+
+        // Draw the filtered layer into its parent.
+        self.mask_state = layer.mask_state;
+        self.num_masks = layer.num_masks;
+        self.mask_state_dirty = true;
+        self.bind_current_framebuffer();
+        self.set_stencil_state();
+        if self.blend_func != (Gl::ONE, Gl::ONE_MINUS_SRC_ALPHA) {
+            self.gl.blend_func_separate(
+                Gl::ONE,
+                Gl::ONE_MINUS_SRC_ALPHA,
+                Gl::ONE,
+                Gl::ONE_MINUS_SRC_ALPHA,
+            );
+            self.blend_func = (Gl::ONE, Gl::ONE_MINUS_SRC_ALPHA);
+        }
+        self.draw_fullscreen_texture(&target.texture);
+        self.active_program = std::ptr::null();
+        self.delete_render_target(target);
+    }
+
+    fn push_blend_mode(&mut self, _blend_mode: swf::BlendMode) {
+        // TODO: Blend modes are not yet supported in the WebGL backend.
+        // Content is rendered with the normal blend mode.
+    }
+
+    fn pop_blend_mode(&mut self) {}
+
+    fn begin_offscreen(&mut self, bitmap: BitmapHandle) -> bool {
+        if self.offscreen.is_some() {
+            return false;
+        }
+        let offscreen = match self.create_offscreen(bitmap) {
+            Ok(offscreen) => offscreen,
+            Err(e) => {
+                log::warn!("Unable to draw into bitmap: {}", e);
+                return false;
+            }
+        };
+
+        let (width, height) = (offscreen.width as i32, offscreen.height as i32);
+        self.view_width = width;
+        self.view_height = height;
+        self.renderbuffer_width = width;
+        self.renderbuffer_height = height;
+        // Bitmaps are uploaded top row first, which is the bottom row of a framebuffer,
+        // so the view is flipped to keep the texture the right way up.
+        self.view_matrix = [
+            [2.0 / width as f32, 0.0, 0.0, 0.0],
+            [0.0, 2.0 / height as f32, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [-1.0, -1.0, 0.0, 1.0],
+        ];
+        self.offscreen = Some(offscreen);
+
+        self.active_program = std::ptr::null();
+        self.mask_state = MaskState::NoMask;
+        self.num_masks = 0;
+        self.mask_state_dirty = true;
+        self.mult_color = None;
+        self.add_color = None;
+
+        self.bind_current_framebuffer();
+        self.gl.viewport(0, 0, width, height);
+        self.set_stencil_state();
+        self.gl.stencil_mask(0xff);
+        self.gl.clear(Gl::STENCIL_BUFFER_BIT);
+        true
+    }
+
+    fn end_offscreen(&mut self) {
+        let offscreen = if let Some(offscreen) = self.offscreen.take() {
+            offscreen
+        } else {
+            return;
+        };
+
+        self.gl
+            .bind_framebuffer(Gl::FRAMEBUFFER, Some(&offscreen.framebuffer));
+        let mut rgba = vec![0; offscreen.width as usize * offscreen.height as usize * 4];
+        let result = self
+            .gl
+            .read_pixels_with_opt_u8_array(
+                0,
+                0,
+                offscreen.width as i32,
+                offscreen.height as i32,
+                Gl::RGBA,
+                Gl::UNSIGNED_BYTE,
+                Some(&mut rgba),
+            )
+            .into_js_result();
+        self.delete_offscreen(&offscreen);
+
+        self.view_width = offscreen.view_width;
+        self.view_height = offscreen.view_height;
+        self.renderbuffer_width = offscreen.renderbuffer_width;
+        self.renderbuffer_height = offscreen.renderbuffer_height;
+        self.build_matrices();
+        self.mask_state = MaskState::NoMask;
+        self.num_masks = 0;
+        self.mask_state_dirty = true;
+        self.bind_current_framebuffer();
+        self.gl
+            .viewport(0, 0, self.renderbuffer_width, self.renderbuffer_height);
+
+        match result {
+            Ok(()) => {
+                self.bitmap_registry.insert(
+                    offscreen.bitmap,
+                    Bitmap {
+                        width: offscreen.width,
+                        height: offscreen.height,
+                        data: BitmapFormat::Rgba(rgba),
+                    },
+                );
+            }
+            Err(e) => log::error!("Couldn't read offscreen bitmap: {}", e),
+        }
+    }
+
     fn get_bitmap_pixels(&mut self, bitmap: BitmapHandle) -> Option<Bitmap> {
         self.bitmap_registry.get(&bitmap).cloned()
     }
@@ -1377,6 +1755,15 @@ impl RenderBackend for WebGlRenderBackend {
             )
             .into_js_result()?;
 
+        self.bitmap_registry.insert(
+            handle,
+            Bitmap {
+                width,
+                height,
+                data: BitmapFormat::Rgba(rgba),
+            },
+        );
+
         Ok(handle)
     }
 }
@@ -1434,6 +1821,36 @@ struct MsaaBuffers {
     framebuffer_texture: WebGlTexture,
 }
 
+/// A layer that a filtered display object is drawn into.
+/// Unlike the MSAA render buffer, layers aren't multisampled.
+struct FilterLayer {
+    target: RenderTarget,
+
+    /// The mask state of the parent, restored when the layer is drawn into it.
+    mask_state: MaskState,
+    num_masks: u32,
+}
+
+/// A framebuffer drawing into a bitmap's texture, and the view it replaced.
+struct Offscreen {
+    bitmap: BitmapHandle,
+    width: u32,
+    height: u32,
+    framebuffer: WebGlFramebuffer,
+    stencil_renderbuffer: WebGlRenderbuffer,
+    view_width: i32,
+    view_height: i32,
+    renderbuffer_width: i32,
+    renderbuffer_height: i32,
+}
+
+/// A texture with a framebuffer to draw into it.
+struct RenderTarget {
+    framebuffer: WebGlFramebuffer,
+    texture: WebGlTexture,
+    stencil_renderbuffer: Option<WebGlRenderbuffer>,
+}
+
 // Because the shaders are currently simple and few in number, we are using a
 // straightforward shader model. We maintain an enum of every possible uniform,
 // and each shader tries to grab the location of each uniform.
@@ -1446,7 +1863,7 @@ struct ShaderProgram {
 }
 
 // These should match the uniform names in the shaders.
-const NUM_UNIFORMS: usize = 13;
+const NUM_UNIFORMS: usize = 34;
 const UNIFORM_NAMES: [&str; NUM_UNIFORMS] = [
     "world_matrix",
     "view_matrix",
@@ -1461,6 +1878,27 @@ const UNIFORM_NAMES: [&str; NUM_UNIFORMS] = [
     "u_focal_point",
     "u_interpolation",
     "u_texture",
+    "u_blurred",
+    "u_gradient",
+    "u_step_size",
+    "u_radius",
+    "u_offset",
+    "u_strength",
+    "u_gradient_size",
+    "u_bevel",
+    "u_invert",
+    "u_inner",
+    "u_knockout",
+    "u_on_top",
+    "u_color_matrix",
+    "u_color_offset",
+    "u_default_color",
+    "u_matrix_size",
+    "u_divisor",
+    "u_bias",
+    "u_clamped",
+    "u_preserve_alpha",
+    "u_weights",
 ];
 
 enum ShaderUniform {
@@ -1477,6 +1915,27 @@ enum ShaderUniform {
     GradientFocalPoint,
     GradientInterpolation,
     BitmapTexture,
+    BlurredTexture,
+    GradientTexture,
+    StepSize,
+    Radius,
+    Offset,
+    Strength,
+    GradientSize,
+    Bevel,
+    Invert,
+    Inner,
+    Knockout,
+    OnTop,
+    ColorMatrix,
+    ColorOffset,
+    DefaultColor,
+    MatrixSize,
+    Divisor,
+    Bias,
+    Clamped,
+    PreserveAlpha,
+    Weights,
 }
 
 impl ShaderProgram {
@@ -1542,6 +2001,10 @@ impl ShaderProgram {
         gl.uniform1i(self.uniforms[uniform as usize].as_ref(), value);
     }
 
+    fn uniform2fv(&self, gl: &Gl, uniform: ShaderUniform, values: &[f32]) {
+        gl.uniform2fv_with_f32_array(self.uniforms[uniform as usize].as_ref(), values);
+    }
+
     fn uniform4fv(&self, gl: &Gl, uniform: ShaderUniform, values: &[f32]) {
         gl.uniform4fv_with_f32_array(self.uniforms[uniform as usize].as_ref(), values);
     }
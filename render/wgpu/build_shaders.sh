@@ -4,3 +4,8 @@ glslangValidator -V ./shaders/color.vert -o ./shaders/color.vert.spv
 glslangValidator -V ./shaders/bitmap.frag -o ./shaders/bitmap.frag.spv
 glslangValidator -V ./shaders/gradient.frag -o ./shaders/gradient.frag.spv
 glslangValidator -V ./shaders/texture.vert -o ./shaders/texture.vert.spv
+glslangValidator -V ./shaders/filter_blur.frag -o ./shaders/filter_blur.frag.spv
+glslangValidator -V ./shaders/filter_effect.frag -o ./shaders/filter_effect.frag.spv
+glslangValidator -V ./shaders/filter_color_matrix.frag -o ./shaders/filter_color_matrix.frag.spv
+glslangValidator -V ./shaders/filter_convolution.frag -o ./shaders/filter_convolution.frag.spv
+glslangValidator -V ./shaders/blend.frag -o ./shaders/blend.frag.spv
@@ -0,0 +1,44 @@
+#version 450
+
+// Set 1: the layer being blended + blend parameters
+layout(set = 1, binding = 1) uniform texture2D t_color;
+// A copy of the parent's pixels that the layer is blended onto.
+layout(set = 1, binding = 2) uniform texture2D t_backdrop;
+
+layout(set = 1, binding = 3) uniform Blend {
+    // 0.0 for difference, 1.0 for overlay, 2.0 for hard light.
+    float mode;
+};
+
+// Set 2: sampler
+layout(set = 2, binding = 0) uniform sampler s_color;
+
+layout(location=0) in vec2 frag_uv;
+
+layout(location=0) out vec4 out_color;
+
+vec3 hard_light(vec3 src, vec3 dst) {
+    vec3 low = dst * 2.0 * src;
+    vec3 s = 2.0 * src - 1.0;
+    vec3 high = dst + s - dst * s;
+    return mix(low, high, step(vec3(0.5), src));
+}
+
+// Blend modes that need the color of the parent. The result replaces the parent's pixels.
+void main() {
+    vec4 src = textureLod(sampler2D(t_color, s_color), frag_uv, 0.0);
+    vec4 dst = textureLod(sampler2D(t_backdrop, s_color), frag_uv, 0.0);
+
+    // Premultiplied colors never exceed alpha, so transparent pixels stay black.
+    vec3 src_color = src.rgb / max(src.a, 1.0 / 255.0);
+    vec3 dst_color = dst.rgb / max(dst.a, 1.0 / 255.0);
+
+    vec3 difference = abs(src_color - dst_color);
+    vec3 overlay = hard_light(dst_color, src_color);
+    vec3 hard = hard_light(src_color, dst_color);
+    vec3 blended = mix(mix(difference, overlay, step(0.5, mode)), hard, step(1.5, mode));
+
+    vec3 color = src.rgb * (1.0 - dst.a) + dst.rgb * (1.0 - src.a) + src.a * dst.a * blended;
+    float alpha = src.a + dst.a - src.a * dst.a;
+    out_color = clamp(vec4(color, alpha), vec4(0.0), vec4(1.0));
+}
@@ -0,0 +1,30 @@
+#version 450
+
+// Set 1: the layer being filtered + filter parameters
+layout(set = 1, binding = 1) uniform texture2D t_color;
+
+layout(set = 1, binding = 3) uniform Filter {
+    // The distance between two samples in texture coordinates.
+    vec2 step_size;
+    // The number of samples taken on each side of the center.
+    float radius;
+};
+
+// Set 2: sampler
+layout(set = 2, binding = 0) uniform sampler s_color;
+
+layout(location=0) in vec2 frag_uv;
+
+layout(location=0) out vec4 out_color;
+
+// One pass of a box blur, in the direction of `step_size`.
+void main() {
+    vec4 sum = vec4(0.0);
+    for (float i = -radius; i <= radius; i += 1.0) {
+        vec2 uv = frag_uv + step_size * i;
+        // Everything outside of the layer is transparent.
+        vec2 inside = step(vec2(0.0), uv) * step(uv, vec2(1.0));
+        sum += textureLod(sampler2D(t_color, s_color), uv, 0.0) * inside.x * inside.y;
+    }
+    out_color = sum / (radius * 2.0 + 1.0);
+}
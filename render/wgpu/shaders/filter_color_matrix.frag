@@ -0,0 +1,27 @@
+#version 450
+
+// Set 1: the layer being filtered + filter parameters
+layout(set = 1, binding = 1) uniform texture2D t_color;
+
+layout(set = 1, binding = 3) uniform Filter {
+    // Applied to the unmultiplied color.
+    mat4 matrix;
+    // Added after the matrix, in the range 0.0 to 1.0.
+    vec4 offset;
+};
+
+// Set 2: sampler
+layout(set = 2, binding = 0) uniform sampler s_color;
+
+layout(location=0) in vec2 frag_uv;
+
+layout(location=0) out vec4 out_color;
+
+void main() {
+    vec4 color = textureLod(sampler2D(t_color, s_color), frag_uv, 0.0);
+    if( color.a > 0 ) {
+        color.rgb /= color.a;
+    }
+    vec4 result = clamp(matrix * color + offset, vec4(0.0), vec4(1.0));
+    out_color = vec4(result.rgb * result.a, result.a);
+}
@@ -0,0 +1,52 @@
+#version 450
+
+// Set 1: the layer being filtered + filter parameters
+layout(set = 1, binding = 1) uniform texture2D t_color;
+
+layout(set = 1, binding = 3) uniform Filter {
+    // The unmultiplied color used outside of the layer.
+    vec4 default_color;
+    // The size of a pixel in texture coordinates.
+    vec2 step_size;
+    // The number of columns and rows of the matrix.
+    vec2 matrix_size;
+    float divisor;
+    // Added after dividing, in the range 0.0 to 1.0.
+    float bias;
+    // 1.0 to extend the edges of the layer instead of using `default_color`.
+    float clamped;
+    // 1.0 to keep the alpha of the layer.
+    float preserve_alpha;
+    // Up to 15x15 weights, row by row.
+    float weights[225];
+};
+
+// Set 2: sampler
+layout(set = 2, binding = 0) uniform sampler s_color;
+
+layout(location=0) in vec2 frag_uv;
+
+layout(location=0) out vec4 out_color;
+
+void main() {
+    vec4 sum = vec4(0.0);
+    vec2 center = floor(matrix_size / 2.0);
+    for (float row = 0.0; row < matrix_size.y; row += 1.0) {
+        for (float col = 0.0; col < matrix_size.x; col += 1.0) {
+            vec2 uv = frag_uv + (vec2(col, row) - center) * step_size;
+            vec2 inside = step(vec2(0.0), uv) * step(uv, vec2(1.0));
+            vec4 color = textureLod(sampler2D(t_color, s_color), uv, 0.0);
+            // Premultiplied colors never exceed alpha, so this leaves
+            // transparent pixels black.
+            color.rgb /= max(color.a, 1.0 / 255.0);
+            color = mix(default_color, color, max(inside.x * inside.y, clamped));
+            sum += color * weights[int(row * matrix_size.x + col)];
+        }
+    }
+
+    vec4 color = sum / divisor + bias;
+    vec4 center_color = textureLod(sampler2D(t_color, s_color), frag_uv, 0.0);
+    color.a = mix(color.a, center_color.a, preserve_alpha);
+    color = clamp(color, vec4(0.0), vec4(1.0));
+    out_color = vec4(color.rgb * color.a, color.a);
+}
@@ -0,0 +1,57 @@
+#version 450
+
+// Set 1: the layer being filtered + filter parameters
+layout(set = 1, binding = 1) uniform texture2D t_color;
+// The blurred layer that the glow or shadow is made from.
+layout(set = 1, binding = 2) uniform texture2D t_blurred;
+// The colors of the effect, indexed by its strength.
+layout(set = 1, binding = 4) uniform texture2D t_gradient;
+
+layout(set = 1, binding = 3) uniform Filter {
+    // The distance between the layer and the effect in texture coordinates.
+    vec2 offset;
+    float strength;
+    // The number of texels in `t_gradient`.
+    float gradient_size;
+    // 1.0 for bevels, 0.0 for glows and shadows.
+    float bevel;
+    // 1.0 to make the effect from the inverted alpha of the layer.
+    float invert;
+    float inner;
+    float knockout;
+    // 1.0 to draw the effect on top of the layer, used by bevels.
+    float on_top;
+};
+
+// Set 2: sampler
+layout(set = 2, binding = 0) uniform sampler s_color;
+
+layout(location=0) in vec2 frag_uv;
+
+layout(location=0) out vec4 out_color;
+
+float blurred_alpha(vec2 uv) {
+    // Everything outside of the layer is transparent.
+    vec2 inside = step(vec2(0.0), uv) * step(uv, vec2(1.0));
+    return textureLod(sampler2D(t_blurred, s_color), uv, 0.0).a * inside.x * inside.y;
+}
+
+// Glows, drop shadows and bevels, including their gradient variants.
+void main() {
+    vec4 src = textureLod(sampler2D(t_color, s_color), frag_uv, 0.0);
+    float shaded = blurred_alpha(frag_uv - offset);
+    float lit = blurred_alpha(frag_uv + offset);
+
+    // Glows and shadows use the blurred alpha, bevels the difference between
+    // the highlight and the shadow side.
+    float shadow = min(mix(shaded, 1.0 - shaded, invert) * strength, 1.0);
+    float bevel_amount = clamp((lit - shaded) * strength, -1.0, 1.0);
+    float t = mix(shadow, (bevel_amount + 1.0) * 0.5, bevel);
+    vec2 gradient_uv = vec2((t * (gradient_size - 1.0) + 0.5) / gradient_size, 0.5);
+    vec4 effect = textureLod(sampler2D(t_gradient, s_color), gradient_uv, 0.0);
+
+    vec4 inner_color = mix(effect * src.a + src * (1.0 - effect.a * src.a), effect * src.a, knockout);
+    vec4 outer_color = mix(src + effect * (1.0 - src.a), effect * (1.0 - src.a), knockout);
+    vec4 on_top_color = mix(effect + src * (1.0 - effect.a), effect, knockout);
+    out_color = mix(mix(outer_color, inner_color, inner), on_top_color, on_top);
+}
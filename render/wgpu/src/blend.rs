@@ -0,0 +1,204 @@
+use crate::target::RenderTarget;
+use crate::utils::create_buffer_with_data;
+use crate::WgpuRenderBackend;
+use bytemuck::{Pod, Zeroable};
+use enum_map::Enum;
+use ruffle_core::swf::BlendMode;
+
+/// How a layer with a given blend mode is composited onto its parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendType {
+    /// The blend can be done with fixed-function blending while drawing the layer.
+    Trivial(TrivialBlend),
+
+    /// The blend needs to read the parent's pixels, so it is done in a shader that samples
+    /// a copy of them. The result then replaces the parent's pixels.
+    Complex(ComplexBlend),
+}
+
+impl From<BlendMode> for BlendType {
+    fn from(mode: BlendMode) -> Self {
+        match mode {
+            BlendMode::Normal | BlendMode::Layer => BlendType::Trivial(TrivialBlend::Normal),
+            BlendMode::Add => BlendType::Trivial(TrivialBlend::Add),
+            BlendMode::Subtract => BlendType::Trivial(TrivialBlend::Subtract),
+            BlendMode::Multiply => BlendType::Trivial(TrivialBlend::Multiply),
+            BlendMode::Screen => BlendType::Trivial(TrivialBlend::Screen),
+            BlendMode::Lighten => BlendType::Trivial(TrivialBlend::Lighten),
+            BlendMode::Darken => BlendType::Trivial(TrivialBlend::Darken),
+            BlendMode::Invert => BlendType::Trivial(TrivialBlend::Invert),
+            BlendMode::Alpha => BlendType::Trivial(TrivialBlend::Alpha),
+            BlendMode::Erase => BlendType::Trivial(TrivialBlend::Erase),
+            BlendMode::Difference => BlendType::Complex(ComplexBlend::Difference),
+            BlendMode::Overlay => BlendType::Complex(ComplexBlend::Overlay),
+            BlendMode::HardLight => BlendType::Complex(ComplexBlend::HardLight),
+        }
+    }
+}
+
+/// Blend modes that map onto fixed-function blend states.
+/// All colors are premultiplied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum TrivialBlend {
+    Normal,
+    /// Overwrites the destination; used to copy whole textures, such as a finished frame.
+    Replace,
+    Add,
+    Subtract,
+    Multiply,
+    Screen,
+    Lighten,
+    Darken,
+    /// Inverts the destination where the layer is opaque.
+    /// The layer must be drawn as solid white (see `TrivialBlend::color_adjustments`).
+    Invert,
+    Alpha,
+    Erase,
+}
+
+impl TrivialBlend {
+    /// Returns the color and alpha blend descriptors for this blend.
+    pub fn blend_descriptors(self) -> (wgpu::BlendDescriptor, wgpu::BlendDescriptor) {
+        use wgpu::BlendFactor::*;
+        use wgpu::BlendOperation::*;
+
+        let blend = |src_factor, dst_factor, operation| wgpu::BlendDescriptor {
+            src_factor,
+            dst_factor,
+            operation,
+        };
+        let normal_alpha = blend(One, OneMinusSrcAlpha, Add);
+
+        match self {
+            TrivialBlend::Normal => (blend(One, OneMinusSrcAlpha, Add), normal_alpha),
+            TrivialBlend::Replace => (blend(One, Zero, Add), blend(One, Zero, Add)),
+            TrivialBlend::Add => (blend(One, One, Add), normal_alpha),
+            TrivialBlend::Subtract => (blend(One, One, ReverseSubtract), normal_alpha),
+            // Exact when the destination is opaque, which is the common case.
+            TrivialBlend::Multiply => (blend(DstColor, OneMinusSrcAlpha, Add), normal_alpha),
+            TrivialBlend::Screen => (blend(One, OneMinusSrcColor, Add), normal_alpha),
+            TrivialBlend::Lighten => (blend(One, One, Max), normal_alpha),
+            TrivialBlend::Darken => (blend(One, One, Min), normal_alpha),
+            TrivialBlend::Invert => (blend(OneMinusDstColor, OneMinusSrcAlpha, Add), normal_alpha),
+            TrivialBlend::Alpha => (blend(Zero, SrcAlpha, Add), blend(Zero, SrcAlpha, Add)),
+            TrivialBlend::Erase => (
+                blend(Zero, OneMinusSrcAlpha, Add),
+                blend(Zero, OneMinusSrcAlpha, Add),
+            ),
+        }
+    }
+
+    /// Returns the multiply and add colors that the layer is drawn with.
+    pub fn color_adjustments(self) -> ([f32; 4], [f32; 4]) {
+        match self {
+            // Output the layer's alpha in every channel.
+            TrivialBlend::Invert => ([0.0, 0.0, 0.0, 1.0], [1.0, 1.0, 1.0, 0.0]),
+            _ => ([1.0, 1.0, 1.0, 1.0], [0.0, 0.0, 0.0, 0.0]),
+        }
+    }
+}
+
+/// Blend modes that can't be expressed with fixed-function blending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComplexBlend {
+    Difference,
+    Overlay,
+    HardLight,
+}
+
+impl ComplexBlend {
+    /// Returns the value of the `mode` uniform in `blend.frag` that selects this blend.
+    pub fn shader_mode(self) -> f32 {
+        match self {
+            ComplexBlend::Difference => 0.0,
+            ComplexBlend::Overlay => 1.0,
+            ComplexBlend::HardLight => 2.0,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct BlendUniform {
+    mode: f32,
+    _padding: [f32; 3],
+}
+
+unsafe impl Pod for BlendUniform {}
+unsafe impl Zeroable for BlendUniform {}
+
+impl<T: RenderTarget> WgpuRenderBackend<T> {
+    /// Copies the pixels of the current render target, which the layer that was just popped
+    /// is blended onto. `draw_encoder` must not have an active render pass.
+    pub(crate) fn copy_backdrop(&self, draw_encoder: &mut wgpu::CommandEncoder) -> wgpu::Texture {
+        let source = if let Some(parent) = self.layers.last() {
+            &parent.texture
+        } else if self.descriptors.msaa_sample_count >= 2 {
+            return self.resolve_frame_buffer(draw_encoder);
+        } else {
+            &self.frame_buffer
+        };
+
+        let (width, height) = self.target_size();
+        let extent = wgpu::Extent3d {
+            width,
+            height,
+            depth: 1,
+        };
+        let label = create_debug_label!("Blend backdrop texture");
+        let texture = self
+            .descriptors
+            .device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: label.as_deref(),
+                size: extent,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.target.format(),
+                usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+            });
+        draw_encoder.copy_texture_to_texture(
+            wgpu::TextureCopyView {
+                texture: source,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            wgpu::TextureCopyView {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            extent,
+        );
+        texture
+    }
+
+    /// Creates a bind group for drawing `layer_view` onto a copy of its parent with
+    /// `draw_complex_blend`.
+    pub(crate) fn create_complex_blend_bind_group(
+        &self,
+        layer_view: &wgpu::TextureView,
+        backdrop: &wgpu::Texture,
+        blend: ComplexBlend,
+    ) -> wgpu::BindGroup {
+        let uniform = BlendUniform {
+            mode: blend.shader_mode(),
+            _padding: [0.0; 3],
+        };
+        let uniform_buffer = create_buffer_with_data(
+            &self.descriptors.device,
+            bytemuck::cast_slice(&[uniform]),
+            wgpu::BufferUsage::UNIFORM,
+            create_debug_label!("Blend uniform buffer"),
+        );
+        let backdrop_view = backdrop.create_view(&Default::default());
+        self.create_filter_bind_group(
+            layer_view,
+            &backdrop_view,
+            layer_view,
+            &uniform_buffer,
+            std::mem::size_of::<BlendUniform>() as u64,
+        )
+    }
+}
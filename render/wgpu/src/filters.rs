@@ -0,0 +1,471 @@
+//! Display object filters, applied to the layer that a filtered object was drawn into
+//! with a series of render passes.
+
+use crate::target::RenderTarget;
+use crate::utils::create_buffer_with_data;
+use crate::{TextureTransforms, Transforms, WgpuRenderBackend};
+use bytemuck::{Pod, Zeroable};
+use ruffle_core::bitmap::filters::{EffectParams, Pixel};
+use ruffle_core::swf::{self, Filter};
+
+/// The largest number of weights in a convolution matrix supported by
+/// `filter_convolution.frag`, e.g. a 15x15 matrix.
+const MAX_CONVOLUTION_WEIGHTS: usize = 225;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct BlurUniform {
+    step_size: [f32; 2],
+    radius: f32,
+    _padding: f32,
+}
+
+unsafe impl Pod for BlurUniform {}
+unsafe impl Zeroable for BlurUniform {}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct EffectUniform {
+    offset: [f32; 2],
+    strength: f32,
+    gradient_size: f32,
+    bevel: f32,
+    invert: f32,
+    inner: f32,
+    knockout: f32,
+    on_top: f32,
+    _padding: [f32; 3],
+}
+
+unsafe impl Pod for EffectUniform {}
+unsafe impl Zeroable for EffectUniform {}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct ColorMatrixUniform {
+    matrix: [[f32; 4]; 4],
+    offset: [f32; 4],
+}
+
+unsafe impl Pod for ColorMatrixUniform {}
+unsafe impl Zeroable for ColorMatrixUniform {}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct ConvolutionUniform {
+    default_color: [f32; 4],
+    step_size: [f32; 2],
+    matrix_size: [f32; 2],
+    divisor: f32,
+    bias: f32,
+    clamped: f32,
+    preserve_alpha: f32,
+    /// Array elements in uniform buffers are 16-byte aligned, so only the first
+    /// component of each weight is used.
+    weights: [[f32; 4]; MAX_CONVOLUTION_WEIGHTS],
+}
+
+unsafe impl Pod for ConvolutionUniform {}
+unsafe impl Zeroable for ConvolutionUniform {}
+
+impl<T: RenderTarget> WgpuRenderBackend<T> {
+    /// Applies `filters` in order to a layer texture, returning the filtered texture.
+    /// The passes are recorded into `draw_encoder`, which must not have an active render pass.
+    pub(crate) fn apply_filters(
+        &self,
+        draw_encoder: &mut wgpu::CommandEncoder,
+        texture: wgpu::Texture,
+        filters: &[Filter],
+    ) -> wgpu::Texture {
+        filters.iter().fold(texture, |texture, filter| {
+            self.apply_filter(draw_encoder, texture, filter)
+        })
+    }
+
+    fn apply_filter(
+        &self,
+        draw_encoder: &mut wgpu::CommandEncoder,
+        texture: wgpu::Texture,
+        filter: &Filter,
+    ) -> wgpu::Texture {
+        let effect = match filter {
+            Filter::BlurFilter(filter) => {
+                return self
+                    .blur(
+                        draw_encoder,
+                        &texture,
+                        filter.blur_x,
+                        filter.blur_y,
+                        filter.num_passes,
+                    )
+                    .unwrap_or(texture);
+            }
+            Filter::ColorMatrixFilter(filter) => {
+                return self.color_matrix(draw_encoder, &texture, filter);
+            }
+            Filter::ConvolutionFilter(filter) => {
+                return self
+                    .convolution(draw_encoder, &texture, filter)
+                    .unwrap_or(texture);
+            }
+            _ => match EffectParams::from_filter(filter) {
+                Some(effect) => effect,
+                None => return texture,
+            },
+        };
+
+        let (width, height) = self.target_size();
+        let flag = |value: bool| if value { 1.0 } else { 0.0 };
+        let uniform = EffectUniform {
+            offset: [
+                effect.offset.0 as f32 / width as f32,
+                effect.offset.1 as f32 / height as f32,
+            ],
+            strength: effect.strength,
+            gradient_size: effect.colors.len() as f32,
+            bevel: flag(effect.is_bevel),
+            invert: flag(effect.invert),
+            inner: flag(effect.is_inner),
+            knockout: flag(effect.is_knockout),
+            on_top: flag(effect.is_on_top),
+            _padding: [0.0; 3],
+        };
+        let blurred = self.blur(
+            draw_encoder,
+            &texture,
+            effect.blur_x,
+            effect.blur_y,
+            effect.passes,
+        );
+        let gradient = self.create_gradient_texture(&effect.colors);
+        self.filter_pass(
+            draw_encoder,
+            &self.descriptors.pipelines.filter_pipelines.effect,
+            &texture,
+            blurred.as_ref(),
+            Some(&gradient),
+            bytemuck::cast_slice(&[uniform]),
+        )
+    }
+
+    /// Blurs a texture with `passes` iterations of a box blur, each made of a horizontal and
+    /// a vertical pass. Returns `None` if the blur doesn't change the texture.
+    fn blur(
+        &self,
+        draw_encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+        blur_x: f64,
+        blur_y: f64,
+        passes: u8,
+    ) -> Option<wgpu::Texture> {
+        let (width, height) = self.target_size();
+        // Flash specifies the blur amount as the width of the box in pixels.
+        let radius_x = (blur_x.max(0.0) / 2.0).floor() as f32;
+        let radius_y = (blur_y.max(0.0) / 2.0).floor() as f32;
+        let directions = [
+            ([1.0 / width as f32, 0.0], radius_x),
+            ([0.0, 1.0 / height as f32], radius_y),
+        ];
+
+        let mut blurred: Option<wgpu::Texture> = None;
+        for _ in 0..passes {
+            for &(step_size, radius) in &directions {
+                if radius <= 0.0 {
+                    continue;
+                }
+                let uniform = BlurUniform {
+                    step_size,
+                    radius,
+                    _padding: 0.0,
+                };
+                let output = self.filter_pass(
+                    draw_encoder,
+                    &self.descriptors.pipelines.filter_pipelines.blur,
+                    blurred.as_ref().unwrap_or(texture),
+                    None,
+                    None,
+                    bytemuck::cast_slice(&[uniform]),
+                );
+                blurred = Some(output);
+            }
+        }
+        blurred
+    }
+
+    fn color_matrix(
+        &self,
+        draw_encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+        filter: &swf::ColorMatrixFilter,
+    ) -> wgpu::Texture {
+        let m = &filter.matrix;
+        // Each row of the filter's matrix calculates one output channel. GLSL matrices
+        // are column-major, so each column holds the weights of one input channel.
+        let mut matrix = [[0.0; 4]; 4];
+        for (input, column) in matrix.iter_mut().enumerate() {
+            for (output, weight) in column.iter_mut().enumerate() {
+                *weight = m[output * 5 + input] as f32;
+            }
+        }
+        // The offset column is in the 0-255 range.
+        let offset = [
+            (m[4] / 255.0) as f32,
+            (m[9] / 255.0) as f32,
+            (m[14] / 255.0) as f32,
+            (m[19] / 255.0) as f32,
+        ];
+
+        self.filter_pass(
+            draw_encoder,
+            &self.descriptors.pipelines.filter_pipelines.color_matrix,
+            texture,
+            None,
+            None,
+            bytemuck::cast_slice(&[ColorMatrixUniform { matrix, offset }]),
+        )
+    }
+
+    /// Returns `None` if the filter's matrix is empty or too large.
+    fn convolution(
+        &self,
+        draw_encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+        filter: &swf::ConvolutionFilter,
+    ) -> Option<wgpu::Texture> {
+        let cols = usize::from(filter.num_matrix_cols);
+        let rows = usize::from(filter.num_matrix_rows);
+        if cols == 0 || rows == 0 || filter.matrix.len() < cols * rows {
+            return None;
+        }
+        if cols * rows > MAX_CONVOLUTION_WEIGHTS {
+            log::warn!(
+                "Convolution filter with a {}x{} matrix is unsupported",
+                cols,
+                rows
+            );
+            return None;
+        }
+
+        let (width, height) = self.target_size();
+        let color = &filter.default_color;
+        let mut weights = [[0.0; 4]; MAX_CONVOLUTION_WEIGHTS];
+        for (weight, value) in weights.iter_mut().zip(&filter.matrix[..cols * rows]) {
+            weight[0] = *value as f32;
+        }
+        let uniform = ConvolutionUniform {
+            default_color: [
+                f32::from(color.r) / 255.0,
+                f32::from(color.g) / 255.0,
+                f32::from(color.b) / 255.0,
+                f32::from(color.a) / 255.0,
+            ],
+            step_size: [1.0 / width as f32, 1.0 / height as f32],
+            matrix_size: [cols as f32, rows as f32],
+            divisor: if filter.divisor != 0.0 {
+                filter.divisor as f32
+            } else {
+                1.0
+            },
+            bias: filter.bias as f32 / 255.0,
+            clamped: if filter.is_clamped { 1.0 } else { 0.0 },
+            preserve_alpha: if filter.is_preserve_alpha { 1.0 } else { 0.0 },
+            weights,
+        };
+
+        Some(self.filter_pass(
+            draw_encoder,
+            &self.descriptors.pipelines.filter_pipelines.convolution,
+            texture,
+            None,
+            None,
+            bytemuck::cast_slice(&[uniform]),
+        ))
+    }
+
+    /// Uploads the colors of an effect to a texture that is one pixel high.
+    fn create_gradient_texture(&self, colors: &[Pixel]) -> wgpu::Texture {
+        let rgba: Vec<u8> = colors
+            .iter()
+            .flat_map(|color| {
+                color
+                    .iter()
+                    .map(|c| (c.max(0.0).min(1.0) * 255.0).round() as u8)
+            })
+            .collect();
+        let extent = wgpu::Extent3d {
+            width: colors.len() as u32,
+            height: 1,
+            depth: 1,
+        };
+
+        let label = create_debug_label!("Filter gradient texture");
+        let texture = self
+            .descriptors
+            .device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: label.as_deref(),
+                size: extent,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+            });
+
+        self.descriptors.queue.write_texture(
+            wgpu::TextureCopyView {
+                texture: &texture,
+                mip_level: 0,
+                origin: Default::default(),
+            },
+            &rgba,
+            wgpu::TextureDataLayout {
+                offset: 0,
+                bytes_per_row: 4 * extent.width,
+                rows_per_image: 0,
+            },
+            extent,
+        );
+
+        texture
+    }
+
+    /// Runs a filter shader over the whole of `texture`, writing the result into a new texture.
+    /// `second` and `gradient` are the extra inputs of the shader, if it uses them.
+    fn filter_pass(
+        &self,
+        draw_encoder: &mut wgpu::CommandEncoder,
+        pipeline: &wgpu::RenderPipeline,
+        texture: &wgpu::Texture,
+        second: Option<&wgpu::Texture>,
+        gradient: Option<&wgpu::Texture>,
+        uniform: &[u8],
+    ) -> wgpu::Texture {
+        let (width, height) = self.target_size();
+        let label = create_debug_label!("Filter output texture");
+        let output = self
+            .descriptors
+            .device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: label.as_deref(),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.target.format(),
+                usage: wgpu::TextureUsage::RENDER_ATTACHMENT | wgpu::TextureUsage::SAMPLED,
+            });
+        let output_view = output.create_view(&Default::default());
+
+        // Unused inputs still need something bound, so they get the filtered texture.
+        let view = texture.create_view(&Default::default());
+        let second_view = second.unwrap_or(texture).create_view(&Default::default());
+        let gradient_view = gradient.unwrap_or(texture).create_view(&Default::default());
+        let uniform_buffer = create_buffer_with_data(
+            &self.descriptors.device,
+            uniform,
+            wgpu::BufferUsage::UNIFORM,
+            create_debug_label!("Filter uniform buffer"),
+        );
+
+        let bind_group = self.create_filter_bind_group(
+            &view,
+            &second_view,
+            &gradient_view,
+            &uniform_buffer,
+            uniform.len() as u64,
+        );
+
+        let world_matrix = [
+            [width as f32, 0.0, 0.0, 0.0],
+            [0.0, height as f32, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+
+        let mut render_pass = draw_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                attachment: &output_view,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: true,
+                },
+                resolve_target: None,
+            }],
+            depth_stencil_attachment: None,
+            label: None,
+        });
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_push_constants(
+            wgpu::ShaderStage::VERTEX,
+            0,
+            bytemuck::cast_slice(&[Transforms { world_matrix }]),
+        );
+        render_pass.set_bind_group(0, self.descriptors.globals.bind_group(), &[]);
+        render_pass.set_bind_group(1, &bind_group, &[]);
+        render_pass.set_bind_group(
+            2,
+            self.descriptors.bitmap_samplers.get_bind_group(false, true),
+            &[],
+        );
+        render_pass.set_vertex_buffer(0, self.quad_vbo.slice(..));
+        render_pass.set_index_buffer(self.quad_ibo.slice(..), wgpu::IndexFormat::Uint32);
+        render_pass.draw_indexed(0..6, 0, 0..1);
+        drop(render_pass);
+
+        output
+    }
+
+    /// Creates a bind group with the inputs of a shader using the filter bind group layout.
+    pub(crate) fn create_filter_bind_group(
+        &self,
+        view: &wgpu::TextureView,
+        second_view: &wgpu::TextureView,
+        gradient_view: &wgpu::TextureView,
+        uniform_buffer: &wgpu::Buffer,
+        uniform_size: u64,
+    ) -> wgpu::BindGroup {
+        self.descriptors
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &self.descriptors.pipelines.filter_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::Buffer {
+                            buffer: &self.quad_tex_transforms,
+                            offset: 0,
+                            size: wgpu::BufferSize::new(
+                                std::mem::size_of::<TextureTransforms>() as u64
+                            ),
+                        },
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::TextureView(second_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 3,
+                        resource: wgpu::BindingResource::Buffer {
+                            buffer: uniform_buffer,
+                            offset: 0,
+                            size: wgpu::BufferSize::new(uniform_size),
+                        },
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 4,
+                        resource: wgpu::BindingResource::TextureView(gradient_view),
+                    },
+                ],
+                label: create_debug_label!("Filter bind group").as_deref(),
+            })
+    }
+}
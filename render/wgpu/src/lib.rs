@@ -10,19 +10,20 @@ use ruffle_core::backend::render::{
 };
 use ruffle_core::shape_utils::{DistilledShape, DrawPath};
 use std::borrow::Cow;
-use swf::{CharacterId, DefineBitsLossless, Glyph, GradientInterpolation};
+use swf::{BlendMode, CharacterId, DefineBitsLossless, Glyph, GradientInterpolation};
 use target::TextureTarget;
 
 use bytemuck::{Pod, Zeroable};
 use futures::executor::block_on;
 use raw_window_handle::HasRawWindowHandle;
 
+use crate::blend::{BlendType, TrivialBlend};
 use crate::pipelines::Pipelines;
 use crate::shapes::{Draw, DrawType, GradientUniforms, IncompleteDrawType, Mesh};
 use crate::target::{RenderTarget, RenderTargetFrame, SwapChainTarget};
 use crate::utils::{
     create_buffer_with_data, format_list, get_backend_names, gradient_spread_mode_index,
-    ruffle_path_to_lyon_path, swf_bitmap_to_gl_matrix, swf_to_gl_matrix,
+    ruffle_path_to_lyon_path, swf_bitmap_to_gl_matrix, swf_to_gl_matrix, BufferDimensions,
 };
 use enum_map::Enum;
 use ruffle_core::color_transform::ColorTransform;
@@ -33,6 +34,8 @@ type Error = Box<dyn std::error::Error>;
 mod utils;
 
 mod bitmaps;
+mod blend;
+mod filters;
 mod globals;
 mod pipelines;
 mod shapes;
@@ -85,6 +88,9 @@ impl Descriptors {
 pub struct WgpuRenderBackend<T: RenderTarget> {
     descriptors: Descriptors,
     target: T,
+    /// The color buffer that frames are drawn into. With MSAA it is resolved into the frame
+    /// output; otherwise it is copied to the output when the frame ends.
+    frame_buffer: wgpu::Texture,
     frame_buffer_view: wgpu::TextureView,
     depth_texture_view: wgpu::TextureView,
     current_frame: Option<Frame<'static, T>>,
@@ -96,11 +102,46 @@ pub struct WgpuRenderBackend<T: RenderTarget> {
     quad_ibo: wgpu::Buffer,
     quad_tex_transforms: wgpu::Buffer,
     bitmap_registry: HashMap<BitmapHandle, Bitmap>,
+    layers: Vec<Layer>,
+
+    /// Bind groups of the layers composited this frame.
+    /// These must be kept alive until the frame is submitted.
+    layer_bind_groups: Vec<wgpu::BindGroup>,
+
+    /// The bitmap being rendered into, if this is an offscreen frame.
+    offscreen: Option<Offscreen>,
+}
+
+/// A bitmap being rendered into between `begin_offscreen` and `end_offscreen`.
+/// The bitmap is drawn into the bottommost layer, which is read back when the frame ends.
+#[derive(Debug, Clone, Copy)]
+struct Offscreen {
+    bitmap: BitmapHandle,
+    width: u32,
+    height: u32,
+}
+
+/// An offscreen layer that a filtered or blended display object is rendered into.
+#[derive(Debug)]
+struct Layer {
+    /// The multisampled color buffer, if MSAA is enabled.
+    frame_buffer_view: Option<wgpu::TextureView>,
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    depth_texture_view: wgpu::TextureView,
+
+    /// How this layer is composited onto its parent when popped.
+    blend_type: BlendType,
+
+    /// The mask state of the parent target, restored when this layer is popped.
+    mask_state: MaskState,
+    num_masks: u32,
 }
 
 #[allow(dead_code)]
 struct Frame<'a, T: RenderTarget> {
-    frame_data: Box<(wgpu::CommandEncoder, T::Frame)>,
+    /// The draw encoder, and the frame output if drawing to the render target.
+    frame_data: Box<(wgpu::CommandEncoder, Option<T::Frame>)>,
 
     // TODO: This is a self-reference to the above, so we
     // use some unsafe to cast the lifetime away. We know this
@@ -248,7 +289,7 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             sample_count: descriptors.msaa_sample_count,
             dimension: wgpu::TextureDimension::D2,
             format: target.format(),
-            usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
+            usage: frame_buffer_usage(descriptors.msaa_sample_count),
         });
         let frame_buffer_view = frame_buffer.create_view(&Default::default());
 
@@ -274,6 +315,7 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
         Ok(Self {
             descriptors,
             target,
+            frame_buffer,
             frame_buffer_view,
             depth_texture_view,
             current_frame: None,
@@ -287,6 +329,9 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             quad_ibo,
             quad_tex_transforms,
             bitmap_registry: HashMap::new(),
+            layers: Vec::new(),
+            layer_bind_groups: Vec::new(),
+            offscreen: None,
         })
     }
 
@@ -774,6 +819,441 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
         }
     }
 
+    /// Begins a render pass into the current render target: the topmost layer, or the
+    /// frame itself if no layers are active.
+    /// If `clear` is `None`, the existing contents of the target are preserved.
+    fn begin_render_pass(
+        &self,
+        mut frame_data: Box<(wgpu::CommandEncoder, Option<T::Frame>)>,
+        clear: Option<wgpu::Color>,
+    ) -> Frame<'static, T> {
+        let (draw_encoder, frame_output) = &mut *frame_data;
+
+        let (color_attachment, resolve_target, depth_attachment) =
+            if let Some(layer) = self.layers.last() {
+                match &layer.frame_buffer_view {
+                    Some(frame_buffer_view) => (
+                        frame_buffer_view,
+                        Some(&layer.view),
+                        &layer.depth_texture_view,
+                    ),
+                    None => (&layer.view, None, &layer.depth_texture_view),
+                }
+            } else if self.descriptors.msaa_sample_count >= 2 {
+                // Offscreen frames always have a layer for the bitmap, so there must be an output.
+                let frame_output = frame_output
+                    .as_ref()
+                    .expect("Frame without a layer should have an output");
+                (
+                    &self.frame_buffer_view,
+                    Some(frame_output.view()),
+                    &self.depth_texture_view,
+                )
+            } else {
+                // The swap chain can't be read from, so without MSAA the frame is drawn into
+                // the frame buffer and copied to the output in `end_frame`.
+                (&self.frame_buffer_view, None, &self.depth_texture_view)
+            };
+
+        let (load, depth_load, stencil_load) = match clear {
+            Some(color) => (
+                wgpu::LoadOp::Clear(color),
+                wgpu::LoadOp::Clear(0.0),
+                wgpu::LoadOp::Clear(0),
+            ),
+            None => (wgpu::LoadOp::Load, wgpu::LoadOp::Load, wgpu::LoadOp::Load),
+        };
+
+        let render_pass = draw_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                attachment: color_attachment,
+                ops: wgpu::Operations { load, store: true },
+                resolve_target,
+            }],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
+                attachment: depth_attachment,
+                depth_ops: Some(wgpu::Operations {
+                    load: depth_load,
+                    store: true,
+                }),
+                stencil_ops: Some(wgpu::Operations {
+                    load: stencil_load,
+                    store: true,
+                }),
+            }),
+            label: None,
+        });
+
+        // Since RenderPass holds a reference to the CommandEncoder, we cast the lifetime
+        // away to allow for the self-referencing struct. draw_encoder is boxed so its
+        // address should remain stable.
+        Frame {
+            render_pass: unsafe {
+                std::mem::transmute::<_, wgpu::RenderPass<'static>>(render_pass)
+            },
+            frame_data,
+        }
+    }
+
+    fn create_layer(&self, blend_type: BlendType) -> Layer {
+        let (width, height) = self.target_size();
+        let extent = wgpu::Extent3d {
+            width,
+            height,
+            depth: 1,
+        };
+
+        let frame_buffer_view = if self.descriptors.msaa_sample_count >= 2 {
+            let label = create_debug_label!("Layer framebuffer texture");
+            let frame_buffer = self
+                .descriptors
+                .device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: label.as_deref(),
+                    size: extent,
+                    mip_level_count: 1,
+                    sample_count: self.descriptors.msaa_sample_count,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: self.target.format(),
+                    usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
+                });
+            Some(frame_buffer.create_view(&Default::default()))
+        } else {
+            None
+        };
+
+        let label = create_debug_label!("Layer texture");
+        let texture = self
+            .descriptors
+            .device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: label.as_deref(),
+                size: extent,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.target.format(),
+                usage: wgpu::TextureUsage::RENDER_ATTACHMENT
+                    | wgpu::TextureUsage::SAMPLED
+                    | wgpu::TextureUsage::COPY_SRC,
+            });
+        let view = texture.create_view(&Default::default());
+
+        let label = create_debug_label!("Layer depth texture");
+        let depth_texture = self
+            .descriptors
+            .device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: label.as_deref(),
+                size: extent,
+                mip_level_count: 1,
+                sample_count: self.descriptors.msaa_sample_count,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Depth24PlusStencil8,
+                usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
+            });
+        let depth_texture_view = depth_texture.create_view(&Default::default());
+
+        Layer {
+            frame_buffer_view,
+            texture,
+            view,
+            depth_texture_view,
+            blend_type,
+            mask_state: self.mask_state,
+            num_masks: self.num_masks,
+        }
+    }
+
+    /// Starts drawing into a new layer. The current render pass is finished, and drawing
+    /// continues in a fresh pass into the layer.
+    fn push_layer(&mut self, blend_type: BlendType) {
+        let frame = if let Some(frame) = self.current_frame.take() {
+            frame
+        } else {
+            return;
+        };
+
+        let Frame {
+            frame_data,
+            render_pass,
+        } = frame;
+        drop(render_pass);
+
+        let layer = self.create_layer(blend_type);
+        self.layers.push(layer);
+        self.mask_state = MaskState::NoMask;
+        self.num_masks = 0;
+
+        self.current_frame =
+            Some(self.begin_render_pass(frame_data, Some(wgpu::Color::TRANSPARENT)));
+    }
+
+    /// The size of the current render target: the bitmap being rendered into by an offscreen
+    /// frame, or the viewport otherwise.
+    fn target_size(&self) -> (u32, u32) {
+        match &self.offscreen {
+            Some(offscreen) => (offscreen.width, offscreen.height),
+            None => (self.target.width(), self.target.height()),
+        }
+    }
+
+    /// Copies the contents of a texture the size of the render target back to the CPU.
+    /// The pixels are returned in the render target's format, without row padding.
+    /// This submits all work recorded in `draw_encoder` so far and replaces it with a new encoder.
+    fn read_texture(
+        &self,
+        draw_encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+    ) -> Option<Vec<u8>> {
+        let (width, height) = self.target_size();
+        let dimensions = BufferDimensions::new(width as usize, height as usize);
+
+        let label = create_debug_label!("Layer readback buffer");
+        let buffer = self
+            .descriptors
+            .device
+            .create_buffer(&wgpu::BufferDescriptor {
+                label: label.as_deref(),
+                size: (dimensions.padded_bytes_per_row * dimensions.height) as u64,
+                usage: wgpu::BufferUsage::COPY_DST | wgpu::BufferUsage::MAP_READ,
+                mapped_at_creation: false,
+            });
+
+        draw_encoder.copy_texture_to_buffer(
+            wgpu::TextureCopyView {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            wgpu::BufferCopyView {
+                buffer: &buffer,
+                layout: wgpu::TextureDataLayout {
+                    offset: 0,
+                    bytes_per_row: dimensions.padded_bytes_per_row as u32,
+                    rows_per_image: 0,
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth: 1,
+            },
+        );
+
+        let label = create_debug_label!("Draw encoder");
+        let new_encoder =
+            self.descriptors
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: label.as_deref(),
+                });
+        let finished_encoder = std::mem::replace(draw_encoder, new_encoder);
+        self.descriptors
+            .queue
+            .submit(Some(finished_encoder.finish()));
+
+        let buffer_future = buffer.slice(..).map_async(wgpu::MapMode::Read);
+        self.descriptors.device.poll(wgpu::Maintain::Wait);
+        if let Err(e) = block_on(buffer_future) {
+            log::error!("Unknown error reading layer: {:?}", e);
+            return None;
+        }
+
+        let map = buffer.slice(..).get_mapped_range();
+        let mut pixels = Vec::with_capacity(dimensions.height * dimensions.unpadded_bytes_per_row);
+        for chunk in map.chunks(dimensions.padded_bytes_per_row) {
+            pixels.extend_from_slice(&chunk[..dimensions.unpadded_bytes_per_row]);
+        }
+        drop(map);
+        buffer.unmap();
+        Some(pixels)
+    }
+
+    /// Resolves the multisampled frame buffer into a new texture that can be sampled.
+    fn resolve_frame_buffer(&self, draw_encoder: &mut wgpu::CommandEncoder) -> wgpu::Texture {
+        let label = create_debug_label!("Resolved framebuffer texture");
+        let texture = self
+            .descriptors
+            .device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: label.as_deref(),
+                size: wgpu::Extent3d {
+                    width: self.target.width(),
+                    height: self.target.height(),
+                    depth: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.target.format(),
+                usage: wgpu::TextureUsage::RENDER_ATTACHMENT | wgpu::TextureUsage::SAMPLED,
+            });
+        let view = texture.create_view(&Default::default());
+
+        // An empty pass that only resolves the frame buffer into the new texture.
+        let render_pass = draw_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                attachment: &self.frame_buffer_view,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: true,
+                },
+                resolve_target: Some(&view),
+            }],
+            depth_stencil_attachment: None,
+            label: None,
+        });
+        drop(render_pass);
+
+        texture
+    }
+
+    /// Draws the single-sampled frame buffer onto the frame output.
+    /// `draw_encoder` must not have an active render pass.
+    fn copy_frame_buffer(
+        &self,
+        draw_encoder: &mut wgpu::CommandEncoder,
+        frame_output: &wgpu::TextureView,
+    ) {
+        let bind_group = self.create_texture_view_bind_group(&self.frame_buffer_view);
+        let mut render_pass = draw_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                attachment: frame_output,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: true,
+                },
+                resolve_target: None,
+            }],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
+                attachment: &self.depth_texture_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(0.0),
+                    store: true,
+                }),
+                stencil_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(0),
+                    store: true,
+                }),
+            }),
+            label: None,
+        });
+        self.draw_layer(&mut render_pass, &bind_group, TrivialBlend::Replace);
+    }
+
+    /// Creates a bind group for drawing a texture the size of the render target with
+    /// `draw_layer`.
+    fn create_texture_view_bind_group(&self, texture_view: &wgpu::TextureView) -> wgpu::BindGroup {
+        self.descriptors
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &self.descriptors.pipelines.bitmap_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::Buffer {
+                            buffer: &self.quad_tex_transforms,
+                            offset: 0,
+                            size: wgpu::BufferSize::new(
+                                std::mem::size_of::<TextureTransforms>() as u64
+                            ),
+                        },
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(texture_view),
+                    },
+                ],
+                label: create_debug_label!("Layer bind group").as_deref(),
+            })
+    }
+
+    /// Draws a layer onto the current target as a viewport-sized quad.
+    fn draw_layer<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        bind_group: &'a wgpu::BindGroup,
+        blend: TrivialBlend,
+    ) {
+        let (mult_color, add_color) = blend.color_adjustments();
+        render_pass.set_pipeline(
+            self.descriptors.pipelines.composite_pipelines[blend].pipeline_for(self.mask_state),
+        );
+        render_pass.set_push_constants(
+            wgpu::ShaderStage::FRAGMENT,
+            std::mem::size_of::<Transforms>() as u32,
+            bytemuck::cast_slice(&[ColorAdjustments {
+                mult_color,
+                add_color,
+            }]),
+        );
+        self.draw_target_quad(render_pass, bind_group);
+    }
+
+    /// Draws a layer blended with a copy of its parent onto the current target, replacing
+    /// the parent's pixels. The bind group is created by `create_complex_blend_bind_group`.
+    fn draw_complex_blend<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        bind_group: &'a wgpu::BindGroup,
+    ) {
+        render_pass.set_pipeline(
+            self.descriptors
+                .pipelines
+                .complex_blend_pipelines
+                .pipeline_for(self.mask_state),
+        );
+        self.draw_target_quad(render_pass, bind_group);
+    }
+
+    /// Draws a quad covering the current target with the pipeline already set on `render_pass`.
+    fn draw_target_quad<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        bind_group: &'a wgpu::BindGroup,
+    ) {
+        let (width, height) = self.target_size();
+        let (width, height) = (width as f32, height as f32);
+        let world_matrix = [
+            [width, 0.0, 0.0, 0.0],
+            [0.0, height, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+
+        render_pass.set_push_constants(
+            wgpu::ShaderStage::VERTEX,
+            0,
+            bytemuck::cast_slice(&[Transforms { world_matrix }]),
+        );
+        render_pass.set_bind_group(0, self.descriptors.globals.bind_group(), &[]);
+        render_pass.set_bind_group(1, bind_group, &[]);
+        render_pass.set_bind_group(
+            2,
+            self.descriptors
+                .bitmap_samplers
+                .get_bind_group(false, false),
+            &[],
+        );
+        render_pass.set_vertex_buffer(0, self.quad_vbo.slice(..));
+        render_pass.set_index_buffer(self.quad_ibo.slice(..), wgpu::IndexFormat::Uint32);
+
+        match self.mask_state {
+            MaskState::NoMask => (),
+            MaskState::DrawMaskStencil => {
+                debug_assert!(self.num_masks > 0);
+                render_pass.set_stencil_reference(self.num_masks - 1);
+            }
+            MaskState::DrawMaskedContent | MaskState::ClearMaskStencil => {
+                debug_assert!(self.num_masks > 0);
+                render_pass.set_stencil_reference(self.num_masks);
+            }
+        };
+
+        render_pass.draw_indexed(0..6, 0, 0..1);
+    }
+
     pub fn target(&self) -> &T {
         &self.target
     }
@@ -806,9 +1286,10 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
                 sample_count: self.descriptors.msaa_sample_count,
                 dimension: wgpu::TextureDimension::D2,
                 format: self.target.format(),
-                usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
+                usage: frame_buffer_usage(self.descriptors.msaa_sample_count),
             });
         self.frame_buffer_view = frame_buffer.create_view(&Default::default());
+        self.frame_buffer = frame_buffer;
 
         let label = create_debug_label!("Depth texture");
         let depth_texture = self
@@ -892,6 +1373,7 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
     fn begin_frame(&mut self, clear: Color) {
         self.mask_state = MaskState::NoMask;
         self.num_masks = 0;
+        self.layers.clear();
 
         let frame_output = match self.target.get_next_texture() {
             Ok(frame) => frame,
@@ -914,55 +1396,21 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
                 .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                     label: label.as_deref(),
                 });
-        let mut frame_data = Box::new((draw_encoder, frame_output));
+        let mut frame_data = Box::new((draw_encoder, Some(frame_output)));
 
         self.descriptors
             .globals
             .update_uniform(&self.descriptors.device, &mut frame_data.0);
 
-        let (color_attachment, resolve_target) = if self.descriptors.msaa_sample_count >= 2 {
-            (&self.frame_buffer_view, Some(frame_data.1.view()))
-        } else {
-            (frame_data.1.view(), None)
-        };
-
-        let render_pass = frame_data.0.begin_render_pass(&wgpu::RenderPassDescriptor {
-            color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
-                attachment: color_attachment,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color {
-                        r: f64::from(clear.r) / 255.0,
-                        g: f64::from(clear.g) / 255.0,
-                        b: f64::from(clear.b) / 255.0,
-                        a: f64::from(clear.a) / 255.0,
-                    }),
-                    store: true,
-                },
-                resolve_target,
-            }],
-            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
-                attachment: &self.depth_texture_view,
-                depth_ops: Some(wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(0.0),
-                    store: true,
-                }),
-                stencil_ops: Some(wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(0),
-                    store: true,
-                }),
-            }),
-            label: None,
-        });
-
-        // Since RenderPass holds a reference to the CommandEncoder, we cast the lifetime
-        // away to allow for the self-referencing struct. draw_encoder is boxed so its
-        // address should remain stable.
-        self.current_frame = Some(Frame {
-            render_pass: unsafe {
-                std::mem::transmute::<_, wgpu::RenderPass<'static>>(render_pass)
-            },
+        self.current_frame = Some(self.begin_render_pass(
             frame_data,
-        });
+            Some(wgpu::Color {
+                r: f64::from(clear.r) / 255.0,
+                g: f64::from(clear.g) / 255.0,
+                b: f64::from(clear.b) / 255.0,
+                a: f64::from(clear.a) / 255.0,
+            }),
+        ));
     }
 
     fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform, smoothing: bool) {
@@ -1229,13 +1677,22 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
             // Finalize render pass.
             drop(frame.render_pass);
 
-            let draw_encoder = frame.frame_data.0;
+            let (mut draw_encoder, frame_output) = *frame.frame_data;
+            if self.descriptors.msaa_sample_count < 2 {
+                // Any masks left active by unbalanced calls don't apply to the copy.
+                self.mask_state = MaskState::NoMask;
+                self.num_masks = 0;
+                if let Some(frame_output) = &frame_output {
+                    self.copy_frame_buffer(&mut draw_encoder, frame_output.view());
+                }
+            }
             self.target.submit(
                 &self.descriptors.device,
                 &self.descriptors.queue,
                 vec![draw_encoder.finish()],
             );
         }
+        self.layer_bind_groups.clear();
     }
 
     fn push_mask(&mut self) {
@@ -1266,6 +1723,192 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         };
     }
 
+    fn push_filter_layer(&mut self) {
+        self.push_layer(BlendType::Trivial(TrivialBlend::Normal));
+    }
+
+    fn pop_filter_layer(&mut self, filters: &[swf::Filter]) {
+        let frame = if let Some(frame) = self.current_frame.take() {
+            frame
+        } else {
+            return;
+        };
+        let layer = if let Some(layer) = self.layers.pop() {
+            layer
+        } else {
+            self.current_frame = Some(frame);
+            return;
+        };
+
+        let Frame {
+            mut frame_data,
+            render_pass,
+        } = frame;
+        drop(render_pass);
+
+        let Layer {
+            texture,
+            mask_state,
+            num_masks,
+            ..
+        } = layer;
+        let texture = self.apply_filters(&mut frame_data.0, texture, filters);
+        let bind_group =
+            self.create_texture_view_bind_group(&texture.create_view(&Default::default()));
+
+        self.mask_state = mask_state;
+        self.num_masks = num_masks;
+        let mut frame = self.begin_render_pass(frame_data, None);
+        self.layer_bind_groups.push(bind_group);
+        self.draw_layer(
+            &mut frame.get().render_pass,
+            self.layer_bind_groups.last().unwrap(),
+            TrivialBlend::Normal,
+        );
+        self.current_frame = Some(frame);
+    }
+
+    fn push_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.push_layer(BlendType::from(blend_mode));
+    }
+
+    fn pop_blend_mode(&mut self) {
+        let frame = if let Some(frame) = self.current_frame.take() {
+            frame
+        } else {
+            return;
+        };
+        let layer = if let Some(layer) = self.layers.pop() {
+            layer
+        } else {
+            self.current_frame = Some(frame);
+            return;
+        };
+
+        let Frame {
+            mut frame_data,
+            render_pass,
+        } = frame;
+        drop(render_pass);
+
+        self.mask_state = layer.mask_state;
+        self.num_masks = layer.num_masks;
+
+        let bind_group = match layer.blend_type {
+            BlendType::Trivial(_) => self.create_texture_view_bind_group(&layer.view),
+            BlendType::Complex(blend) => {
+                let backdrop = self.copy_backdrop(&mut frame_data.0);
+                self.create_complex_blend_bind_group(&layer.view, &backdrop, blend)
+            }
+        };
+
+        let mut frame = self.begin_render_pass(frame_data, None);
+        self.layer_bind_groups.push(bind_group);
+        let bind_group = self.layer_bind_groups.last().unwrap();
+        match layer.blend_type {
+            BlendType::Trivial(blend) => {
+                self.draw_layer(&mut frame.get().render_pass, bind_group, blend)
+            }
+            BlendType::Complex(_) => {
+                self.draw_complex_blend(&mut frame.get().render_pass, bind_group)
+            }
+        }
+        self.current_frame = Some(frame);
+    }
+
+    fn begin_offscreen(&mut self, bitmap: BitmapHandle) -> bool {
+        if self.current_frame.is_some() {
+            log::warn!("begin_offscreen: Can't render offscreen during a frame");
+            return false;
+        }
+        let (width, height) = if let Some(texture) = self.textures.get(bitmap.0) {
+            (texture.width, texture.height)
+        } else {
+            return false;
+        };
+
+        self.offscreen = Some(Offscreen {
+            bitmap,
+            width,
+            height,
+        });
+        self.mask_state = MaskState::NoMask;
+        self.num_masks = 0;
+        self.layers.clear();
+        let layer = self.create_layer(BlendType::Trivial(TrivialBlend::Normal));
+        self.layers.push(layer);
+
+        let label = create_debug_label!("Offscreen draw encoder");
+        let draw_encoder =
+            self.descriptors
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: label.as_deref(),
+                });
+        let mut frame_data = Box::new((draw_encoder, None));
+
+        self.descriptors.globals.set_resolution(width, height);
+        self.descriptors
+            .globals
+            .update_uniform(&self.descriptors.device, &mut frame_data.0);
+
+        // Start with the existing contents of the bitmap.
+        let mut frame = self.begin_render_pass(frame_data, Some(wgpu::Color::TRANSPARENT));
+        self.draw_layer(
+            &mut frame.get().render_pass,
+            &self.textures[bitmap.0].bind_group,
+            TrivialBlend::Replace,
+        );
+        self.current_frame = Some(frame);
+        true
+    }
+
+    fn end_offscreen(&mut self) {
+        let offscreen = if let Some(offscreen) = self.offscreen {
+            offscreen
+        } else {
+            return;
+        };
+        let frame = if let Some(frame) = self.current_frame.take() {
+            frame
+        } else {
+            self.offscreen = None;
+            return;
+        };
+
+        let Frame {
+            mut frame_data,
+            render_pass,
+        } = frame;
+        drop(render_pass);
+
+        // Any layers left unpopped are discarded; the bottommost one holds the bitmap.
+        self.layers.truncate(1);
+        let pixels = self
+            .layers
+            .pop()
+            .and_then(|layer| self.read_texture(&mut frame_data.0, &layer.texture));
+
+        self.offscreen = None;
+        self.mask_state = MaskState::NoMask;
+        self.num_masks = 0;
+        self.layer_bind_groups.clear();
+        self.descriptors
+            .globals
+            .set_resolution(self.target.width(), self.target.height());
+
+        if let Some(pixels) = pixels {
+            if let Err(e) = self.update_texture(
+                offscreen.bitmap,
+                offscreen.width,
+                offscreen.height,
+                bgra_to_rgba(&pixels),
+            ) {
+                log::error!("Couldn't update offscreen bitmap: {}", e);
+            }
+        }
+    }
+
     fn get_bitmap_pixels(&mut self, bitmap: BitmapHandle) -> Option<Bitmap> {
         self.bitmap_registry.get(&bitmap).cloned()
     }
@@ -1322,10 +1965,40 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
             extent,
         );
 
+        self.bitmap_registry.insert(
+            handle,
+            Bitmap {
+                width,
+                height,
+                data: BitmapFormat::Rgba(rgba),
+            },
+        );
+
         Ok(handle)
     }
 }
 
+/// Converts pixels read back from a render target, which are BGRA, to RGBA.
+/// The usage of the frame buffer. Without MSAA, it is also sampled and copied from, by
+/// complex blends and when the frame ends.
+fn frame_buffer_usage(msaa_sample_count: u32) -> wgpu::TextureUsage {
+    if msaa_sample_count >= 2 {
+        wgpu::TextureUsage::RENDER_ATTACHMENT
+    } else {
+        wgpu::TextureUsage::RENDER_ATTACHMENT
+            | wgpu::TextureUsage::SAMPLED
+            | wgpu::TextureUsage::COPY_SRC
+    }
+}
+
+fn bgra_to_rgba(pixels: &[u8]) -> Vec<u8> {
+    let mut rgba = Vec::with_capacity(pixels.len());
+    for pixel in pixels.chunks_exact(4) {
+        rgba.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+    }
+    rgba
+}
+
 fn create_quad_buffers(device: &wgpu::Device) -> (wgpu::Buffer, wgpu::Buffer, wgpu::Buffer) {
     let vertices = [
         GPUVertex {
@@ -1,3 +1,4 @@
+use crate::blend::TrivialBlend;
 use crate::{Error, GPUVertex, MaskState};
 use enum_map::{enum_map, EnumMap};
 use wgpu::vertex_attr_array;
@@ -14,8 +15,28 @@ pub struct Pipelines {
     pub bitmap_pipelines: ShapePipeline,
     pub bitmap_layout: wgpu::BindGroupLayout,
 
+    /// Pipelines used to composite a layer onto its parent, one per blend.
+    pub composite_pipelines: EnumMap<TrivialBlend, ShapePipeline>,
+
+    /// Pipelines used to blend a layer with a copy of its parent, for blends that need to read it.
+    /// These use the filter bind group layout, with the parent copy as the second texture.
+    pub complex_blend_pipelines: ShapePipeline,
+
     pub gradient_pipelines: ShapePipeline,
     pub gradient_layout: wgpu::BindGroupLayout,
+
+    pub filter_pipelines: FilterPipelines,
+    pub filter_layout: wgpu::BindGroupLayout,
+}
+
+/// Pipelines used to apply filters to a layer, one per shader.
+/// These draw into single-sampled textures without a depth buffer, replacing their contents.
+#[derive(Debug)]
+pub struct FilterPipelines {
+    pub blur: wgpu::RenderPipeline,
+    pub effect: wgpu::RenderPipeline,
+    pub color_matrix: wgpu::RenderPipeline,
+    pub convolution: wgpu::RenderPipeline,
 }
 
 impl ShapePipeline {
@@ -41,6 +62,18 @@ impl Pipelines {
             device.create_shader_module(&wgpu::include_spirv!("../shaders/gradient.frag.spv"));
         let bitmap_fs =
             device.create_shader_module(&wgpu::include_spirv!("../shaders/bitmap.frag.spv"));
+        let filter_blur_fs =
+            device.create_shader_module(&wgpu::include_spirv!("../shaders/filter_blur.frag.spv"));
+        let filter_effect_fs =
+            device.create_shader_module(&wgpu::include_spirv!("../shaders/filter_effect.frag.spv"));
+        let filter_color_matrix_fs = device.create_shader_module(&wgpu::include_spirv!(
+            "../shaders/filter_color_matrix.frag.spv"
+        ));
+        let filter_convolution_fs = device.create_shader_module(&wgpu::include_spirv!(
+            "../shaders/filter_convolution.frag.spv"
+        ));
+        let blend_fs =
+            device.create_shader_module(&wgpu::include_spirv!("../shaders/blend.frag.spv"));
 
         let vertex_buffers_description = [wgpu::VertexBufferDescriptor {
             stride: std::mem::size_of::<GPUVertex>() as u64,
@@ -99,6 +132,20 @@ impl Pipelines {
             &bitmap_bind_layout,
         );
 
+        let composite_pipelines = enum_map! {
+            blend => create_composite_pipeline(
+                &device,
+                &texture_vs,
+                &bitmap_fs,
+                msaa_sample_count,
+                &vertex_buffers_description,
+                sampler_layout,
+                globals_layout,
+                &bitmap_bind_layout,
+                blend,
+            ),
+        };
+
         let gradient_bind_layout_label = create_debug_label!("Gradient shape bind group");
         let gradient_bind_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -137,12 +184,97 @@ impl Pipelines {
             &gradient_bind_layout,
         );
 
+        let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStage::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                multisampled: false,
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+            },
+            count: None,
+        };
+        let filter_bind_layout_label = create_debug_label!("Filter bind group layout");
+        let filter_bind_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStage::VERTEX,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    // The layer being filtered.
+                    texture_entry(1),
+                    // A second input, such as the blurred layer for glows and shadows.
+                    texture_entry(2),
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 3,
+                        visibility: wgpu::ShaderStage::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    // The colors of glows, shadows and bevels.
+                    texture_entry(4),
+                ],
+                label: filter_bind_layout_label.as_deref(),
+            });
+
+        let filter_pipeline_layout_label = create_debug_label!("Filter pipeline layout");
+        let filter_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: filter_pipeline_layout_label.as_deref(),
+                bind_group_layouts: &[globals_layout, &filter_bind_layout, sampler_layout],
+                push_constant_ranges: &[wgpu::PushConstantRange {
+                    stages: wgpu::ShaderStage::VERTEX,
+                    range: 0..64,
+                }],
+            });
+
+        let filter_pipeline = |name, fragment_shader| {
+            create_filter_pipeline(
+                &device,
+                name,
+                &texture_vs,
+                fragment_shader,
+                &filter_pipeline_layout,
+                &vertex_buffers_description,
+            )
+        };
+        let filter_pipelines = FilterPipelines {
+            blur: filter_pipeline("blur", &filter_blur_fs),
+            effect: filter_pipeline("effect", &filter_effect_fs),
+            color_matrix: filter_pipeline("color matrix", &filter_color_matrix_fs),
+            convolution: filter_pipeline("convolution", &filter_convolution_fs),
+        };
+
+        let complex_blend_pipelines = create_complex_blend_pipeline(
+            &device,
+            &texture_vs,
+            &blend_fs,
+            msaa_sample_count,
+            &vertex_buffers_description,
+            &filter_pipeline_layout,
+        );
+
         Ok(Self {
             color_pipelines,
             bitmap_pipelines,
             bitmap_layout: bitmap_bind_layout,
+            composite_pipelines,
+            complex_blend_pipelines,
             gradient_pipelines,
             gradient_layout: gradient_bind_layout,
+            filter_pipelines,
+            filter_layout: filter_bind_layout,
         })
     }
 }
@@ -511,6 +643,64 @@ fn create_bitmap_pipeline(
     ShapePipeline { mask_pipelines }
 }
 
+#[allow(clippy::too_many_arguments)]
+fn create_composite_pipeline(
+    device: &wgpu::Device,
+    vertex_shader: &wgpu::ShaderModule,
+    fragment_shader: &wgpu::ShaderModule,
+    msaa_sample_count: u32,
+    vertex_buffers_description: &[wgpu::VertexBufferDescriptor<'_>],
+    sampler_layout: &wgpu::BindGroupLayout,
+    globals_layout: &wgpu::BindGroupLayout,
+    bitmap_bind_layout: &wgpu::BindGroupLayout,
+    blend: TrivialBlend,
+) -> ShapePipeline {
+    let pipeline_layout_label = create_debug_label!("Composite {:?} pipeline layout", blend);
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: pipeline_layout_label.as_deref(),
+        bind_group_layouts: &[globals_layout, bitmap_bind_layout, sampler_layout],
+        push_constant_ranges: &[
+            wgpu::PushConstantRange {
+                stages: wgpu::ShaderStage::VERTEX,
+                range: 0..64,
+            },
+            wgpu::PushConstantRange {
+                stages: wgpu::ShaderStage::FRAGMENT,
+                range: 64..96,
+            },
+        ],
+    });
+
+    let (color_blend, alpha_blend) = blend.blend_descriptors();
+    let mask_pipelines = enum_map! {
+        mask_state => {
+            let (stencil, write_mask) = mask_render_state(mask_state);
+            device.create_render_pipeline(&create_pipeline_descriptor(
+                create_debug_label!("Composite {:?} pipeline {:?}", blend, mask_state).as_deref(),
+                vertex_shader,
+                fragment_shader,
+                &pipeline_layout,
+                Some(wgpu::DepthStencilStateDescriptor {
+                    format: wgpu::TextureFormat::Depth24PlusStencil8,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::Always,
+                    stencil,
+                }),
+                &[wgpu::ColorStateDescriptor {
+                    format: wgpu::TextureFormat::Bgra8Unorm,
+                    color_blend: color_blend.clone(),
+                    alpha_blend: alpha_blend.clone(),
+                    write_mask,
+                }],
+                vertex_buffers_description,
+                msaa_sample_count,
+            ))
+        },
+    };
+
+    ShapePipeline { mask_pipelines }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn create_gradient_pipeline(
     device: &wgpu::Device,
@@ -671,6 +861,68 @@ fn create_gradient_pipeline(
     ShapePipeline { mask_pipelines }
 }
 
+fn create_complex_blend_pipeline(
+    device: &wgpu::Device,
+    vertex_shader: &wgpu::ShaderModule,
+    fragment_shader: &wgpu::ShaderModule,
+    msaa_sample_count: u32,
+    vertex_buffers_description: &[wgpu::VertexBufferDescriptor<'_>],
+    pipeline_layout: &wgpu::PipelineLayout,
+) -> ShapePipeline {
+    let mask_pipelines = enum_map! {
+        mask_state => {
+            let (stencil, write_mask) = mask_render_state(mask_state);
+            device.create_render_pipeline(&create_pipeline_descriptor(
+                create_debug_label!("Complex blend pipeline {:?}", mask_state).as_deref(),
+                vertex_shader,
+                fragment_shader,
+                pipeline_layout,
+                Some(wgpu::DepthStencilStateDescriptor {
+                    format: wgpu::TextureFormat::Depth24PlusStencil8,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::Always,
+                    stencil,
+                }),
+                &[wgpu::ColorStateDescriptor {
+                    format: wgpu::TextureFormat::Bgra8Unorm,
+                    color_blend: wgpu::BlendDescriptor::REPLACE,
+                    alpha_blend: wgpu::BlendDescriptor::REPLACE,
+                    write_mask,
+                }],
+                vertex_buffers_description,
+                msaa_sample_count,
+            ))
+        },
+    };
+
+    ShapePipeline { mask_pipelines }
+}
+
+fn create_filter_pipeline(
+    device: &wgpu::Device,
+    name: &str,
+    vertex_shader: &wgpu::ShaderModule,
+    fragment_shader: &wgpu::ShaderModule,
+    pipeline_layout: &wgpu::PipelineLayout,
+    vertex_buffers_description: &[wgpu::VertexBufferDescriptor<'_>],
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&create_pipeline_descriptor(
+        create_debug_label!("Filter {} pipeline", name).as_deref(),
+        vertex_shader,
+        fragment_shader,
+        pipeline_layout,
+        None,
+        &[wgpu::ColorStateDescriptor {
+            format: wgpu::TextureFormat::Bgra8Unorm,
+            color_blend: wgpu::BlendDescriptor::REPLACE,
+            alpha_blend: wgpu::BlendDescriptor::REPLACE,
+            write_mask: wgpu::ColorWrite::ALL,
+        }],
+        vertex_buffers_description,
+        1,
+    ))
+}
+
 fn mask_render_state(state: MaskState) -> (wgpu::StencilStateDescriptor, wgpu::ColorWrite) {
     let (stencil_state, color_write) = match state {
         MaskState::NoMask => (
@@ -0,0 +1,75 @@
+//! Regression test for `BitmapData.draw`, which needs a renderer to draw offscreen.
+//! This is skipped if no graphics adapter is available.
+
+use ruffle_core::backend::audio::NullAudioBackend;
+use ruffle_core::backend::local_connection::MemoryLocalConnectionBackend;
+use ruffle_core::backend::locale::NullLocaleBackend;
+use ruffle_core::backend::log::LogBackend;
+use ruffle_core::backend::navigator::NullNavigatorBackend;
+use ruffle_core::backend::socket::NullSocketBackend;
+use ruffle_core::backend::storage::MemoryStorageBackend;
+use ruffle_core::backend::ui::NullUiBackend;
+use ruffle_core::backend::video::NullVideoBackend;
+use ruffle_core::tag_utils::SwfMovie;
+use ruffle_core::Player;
+use ruffle_render_wgpu::WgpuRenderBackend;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+struct TestLogBackend {
+    trace_output: Rc<RefCell<Vec<String>>>,
+}
+
+impl LogBackend for TestLogBackend {
+    fn avm_trace(&self, message: &str) {
+        self.trace_output.borrow_mut().push(message.to_string());
+    }
+}
+
+#[test]
+fn bitmap_data_draw() {
+    let renderer = match WgpuRenderBackend::for_offscreen(
+        (100, 100),
+        wgpu::BackendBit::PRIMARY,
+        wgpu::PowerPreference::LowPower,
+        None,
+    ) {
+        Ok(renderer) => renderer,
+        Err(e) => {
+            eprintln!(
+                "Skipping BitmapData.draw test, couldn't create renderer: {}",
+                e
+            );
+            return;
+        }
+    };
+
+    let movie =
+        SwfMovie::from_path("tests/swfs/bitmap_data_draw/test.swf").expect("Test SWF should load");
+    let expected_output = std::fs::read_to_string("tests/swfs/bitmap_data_draw/output.txt")
+        .expect("Expected output should load")
+        .replace("\r\n", "\n");
+    let trace_output = Rc::new(RefCell::new(Vec::new()));
+
+    let player = Player::new(
+        Box::new(renderer),
+        Box::new(NullAudioBackend::new()),
+        Box::new(NullVideoBackend::new()),
+        Box::new(NullNavigatorBackend::new()),
+        Box::new(NullSocketBackend::new()),
+        Box::new(MemoryStorageBackend::default()),
+        Box::new(MemoryLocalConnectionBackend::default()),
+        Box::new(NullLocaleBackend::new()),
+        Box::new(TestLogBackend {
+            trace_output: trace_output.clone(),
+        }),
+        Box::new(NullUiBackend::new()),
+    )
+    .expect("Player should be created");
+    player.lock().unwrap().set_root_movie(Arc::new(movie));
+    player.lock().unwrap().run_frame();
+
+    let trace = trace_output.borrow().join("\n");
+    assert_eq!(trace.trim_end(), expected_output.trim_end());
+}
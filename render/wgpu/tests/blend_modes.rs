@@ -0,0 +1,163 @@
+//! Image regression tests for blend modes, rendered offscreen.
+//! These are skipped if no graphics adapter is available.
+
+use ruffle_core::backend::render::RenderBackend;
+use ruffle_core::swf::{BlendMode, Color, Matrix};
+use ruffle_render_wgpu::target::TextureTarget;
+use ruffle_render_wgpu::WgpuRenderBackend;
+
+const SIZE: u32 = 16;
+
+const BACKDROPS: &[Color] = &[
+    Color {
+        r: 255,
+        g: 128,
+        b: 0,
+        a: 255,
+    },
+    Color {
+        r: 32,
+        g: 200,
+        b: 96,
+        a: 255,
+    },
+];
+
+const SOURCES: &[Color] = &[
+    Color {
+        r: 64,
+        g: 192,
+        b: 255,
+        a: 255,
+    },
+    Color {
+        r: 200,
+        g: 16,
+        b: 128,
+        a: 255,
+    },
+];
+
+fn create_renderer() -> Option<WgpuRenderBackend<TextureTarget>> {
+    match WgpuRenderBackend::for_offscreen(
+        (SIZE, SIZE),
+        wgpu::BackendBit::PRIMARY,
+        wgpu::PowerPreference::LowPower,
+        None,
+    ) {
+        Ok(renderer) => Some(renderer),
+        Err(e) => {
+            eprintln!("Skipping blend mode test, couldn't create renderer: {}", e);
+            None
+        }
+    }
+}
+
+/// Draws `source` with `blend_mode` over `backdrop` and returns the resulting pixel.
+/// If `nested` is set, both are drawn inside another layer, so the blend reads a layer
+/// rather than the frame buffer.
+fn render(
+    renderer: &mut WgpuRenderBackend<TextureTarget>,
+    blend_mode: BlendMode,
+    backdrop: &Color,
+    source: &Color,
+    nested: bool,
+) -> [u8; 4] {
+    let full_rect = Matrix::scale(SIZE as f32, SIZE as f32);
+
+    renderer.begin_frame(Color {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 255,
+    });
+    if nested {
+        renderer.push_blend_mode(BlendMode::Layer);
+    }
+    renderer.draw_rect(backdrop.clone(), &full_rect);
+    renderer.push_blend_mode(blend_mode);
+    renderer.draw_rect(source.clone(), &full_rect);
+    renderer.pop_blend_mode();
+    if nested {
+        renderer.pop_blend_mode();
+    }
+    renderer.end_frame();
+
+    let image = renderer
+        .target()
+        .capture(renderer.device())
+        .expect("Frame should be captured");
+    image.get_pixel(SIZE / 2, SIZE / 2).0
+}
+
+/// Flash's blend of two opaque colors, one channel at a time.
+fn expected(blend_mode: BlendMode, backdrop: &Color, source: &Color) -> [u8; 4] {
+    fn hard_light(src: f32, dst: f32) -> f32 {
+        if src <= 0.5 {
+            dst * 2.0 * src
+        } else {
+            let src = 2.0 * src - 1.0;
+            dst + src - dst * src
+        }
+    }
+
+    let blend = |src: u8, dst: u8| {
+        let src = f32::from(src) / 255.0;
+        let dst = f32::from(dst) / 255.0;
+        let out = match blend_mode {
+            BlendMode::Difference => (src - dst).abs(),
+            BlendMode::Overlay => hard_light(dst, src),
+            BlendMode::HardLight => hard_light(src, dst),
+            _ => unreachable!(),
+        };
+        (out * 255.0).round() as u8
+    };
+
+    [
+        blend(source.r, backdrop.r),
+        blend(source.g, backdrop.g),
+        blend(source.b, backdrop.b),
+        255,
+    ]
+}
+
+fn test_blend_mode(blend_mode: BlendMode) {
+    let mut renderer = if let Some(renderer) = create_renderer() {
+        renderer
+    } else {
+        return;
+    };
+
+    for &nested in &[false, true] {
+        for backdrop in BACKDROPS {
+            for source in SOURCES {
+                let actual = render(&mut renderer, blend_mode, backdrop, source, nested);
+                let expected = expected(blend_mode, backdrop, source);
+                let matches = actual
+                    .iter()
+                    .zip(&expected)
+                    .all(|(&a, &e)| (i16::from(a) - i16::from(e)).abs() <= 2);
+                assert!(
+                    matches,
+                    "{:?} of {:?} over {:?} (nested: {}) should be {:?}, was {:?}",
+                    blend_mode, source, backdrop, nested, expected, actual
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn blend_mode_difference() {
+    test_blend_mode(BlendMode::Difference);
+}
+
+#[test]
+fn blend_mode_overlay() {
+    test_blend_mode(BlendMode::Overlay);
+}
+
+#[test]
+fn blend_mode_hard_light() {
+    test_blend_mode(BlendMode::HardLight);
+}
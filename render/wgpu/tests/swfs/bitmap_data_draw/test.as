@@ -0,0 +1,47 @@
+// Compiled into test.swf as the actions of frame 1.
+import flash.display.BitmapData;
+import flash.geom.ColorTransform;
+import flash.geom.Matrix;
+import flash.geom.Rectangle;
+
+var bmp = new BitmapData(4, 4, true, 0xFF0000FF);
+var src = new BitmapData(2, 2, true, 0xFFFF0000);
+
+function px(x, y) {
+    trace(bmp.getPixel(x, y).toString(16));
+}
+
+trace("// bmp.draw(src)");
+bmp.draw(src);
+px(0, 0); px(1, 1); px(3, 3);
+
+trace("// bmp.draw(src, translate(2, 2))");
+var m = new Matrix();
+m.translate(2, 2);
+bmp.draw(src, m);
+px(2, 2); px(3, 3); px(1, 2);
+
+trace("// bmp.draw(src, translate(0, 2), colorTransform)");
+var ct = new ColorTransform(0, 1, 1, 1, 0, 255, 0, 0);
+bmp.draw(src, new Matrix(1, 0, 0, 1, 0, 2), ct);
+px(0, 2); px(1, 3); px(0, 0);
+
+trace("// bmp.draw(src, scale(2), null, null, clipRect)");
+bmp.fillRect(bmp.rectangle, 0xFF000000);
+bmp.draw(src, new Matrix(2, 0, 0, 2, 0, 0), null, null, new Rectangle(0, 0, 1, 1));
+px(0, 0); px(1, 1); px(0, 1);
+
+trace("// bmp.draw(clip)");
+var clip = _root.createEmptyMovieClip("clip", 1);
+clip.beginFill(0x00FF00);
+clip.moveTo(0, 0);
+clip.lineTo(2, 0);
+clip.lineTo(2, 2);
+clip.lineTo(0, 2);
+clip.lineTo(0, 0);
+clip.endFill();
+clip._x = 50;
+bmp.fillRect(bmp.rectangle, 0xFF000000);
+bmp.draw(clip);
+px(0, 0); px(1, 1); px(3, 3);
+stop();
@@ -194,6 +194,17 @@ impl<R: Read> Reader<R> {
             0x1c => Multiname::MultinameLA {
                 namespace_set: self.read_index()?,
             },
+            0x1d => Multiname::TypeName {
+                base_type: self.read_index()?,
+                parameters: {
+                    let len = self.read_u30()? as usize;
+                    let mut parameters = Vec::with_capacity(len);
+                    for _ in 0..len {
+                        parameters.push(self.read_index()?);
+                    }
+                    parameters
+                },
+            },
             _ => return Err(Error::invalid_data("Invalid multiname kind")),
         })
     }
@@ -543,6 +554,9 @@ impl<R: Read> Reader<R> {
         let op = match opcode {
             OpCode::Add => Op::Add,
             OpCode::AddI => Op::AddI,
+            OpCode::ApplyType => Op::ApplyType {
+                num_types: self.read_u30()?,
+            },
             OpCode::AsType => Op::AsType {
                 type_name: self.read_index()?,
             },
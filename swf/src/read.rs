@@ -427,7 +427,9 @@ impl<'a> Reader<'a> {
             Some(TagCode::DefineText2) => {
                 Tag::DefineText(Box::new(tag_reader.read_define_text(2)?))
             }
-            Some(TagCode::DefineVideoStream) => tag_reader.read_define_video_stream()?,
+            Some(TagCode::DefineVideoStream) => {
+                Tag::DefineVideoStream(tag_reader.read_define_video_stream()?)
+            }
             Some(TagCode::EnableTelemetry) => {
                 tag_reader.read_u16()?; // Reserved
                 let password_hash = if length > 2 {
@@ -438,29 +440,11 @@ impl<'a> Reader<'a> {
                 Tag::EnableTelemetry { password_hash }
             }
             Some(TagCode::ImportAssets) => {
-                let url = tag_reader.read_string()?;
-                let num_imports = tag_reader.read_u16()?;
-                let mut imports = Vec::with_capacity(num_imports as usize);
-                for _ in 0..num_imports {
-                    imports.push(ExportedAsset {
-                        id: tag_reader.read_u16()?,
-                        name: tag_reader.read_string()?,
-                    });
-                }
+                let (url, imports) = tag_reader.read_import_assets()?;
                 Tag::ImportAssets { url, imports }
             }
             Some(TagCode::ImportAssets2) => {
-                let url = tag_reader.read_string()?;
-                tag_reader.read_u8()?; // Reserved; must be 1
-                tag_reader.read_u8()?; // Reserved; must be 0
-                let num_imports = tag_reader.read_u16()?;
-                let mut imports = Vec::with_capacity(num_imports as usize);
-                for _ in 0..num_imports {
-                    imports.push(ExportedAsset {
-                        id: tag_reader.read_u16()?,
-                        name: tag_reader.read_string()?,
-                    });
-                }
+                let (url, imports) = tag_reader.read_import_assets_2()?;
                 Tag::ImportAssets { url, imports }
             }
 
@@ -598,7 +582,7 @@ impl<'a> Reader<'a> {
 
             Some(TagCode::RemoveObject2) => Tag::RemoveObject(tag_reader.read_remove_object_2()?),
 
-            Some(TagCode::VideoFrame) => tag_reader.read_video_frame()?,
+            Some(TagCode::VideoFrame) => Tag::VideoFrame(tag_reader.read_video_frame()?),
             Some(TagCode::ProductInfo) => Tag::ProductInfo(tag_reader.read_product_info()?),
             _ => {
                 let data = tag_reader.read_slice_to_end();
@@ -1940,6 +1924,21 @@ impl<'a> Reader<'a> {
         Ok(exports)
     }
 
+    pub fn read_import_assets(&mut self) -> Result<(&'a SwfStr, ExportAssets<'a>)> {
+        let url = self.read_string()?;
+        // The list of imports has the same layout as an ExportAssets tag.
+        let imports = self.read_export_assets()?;
+        Ok((url, imports))
+    }
+
+    pub fn read_import_assets_2(&mut self) -> Result<(&'a SwfStr, ExportAssets<'a>)> {
+        let url = self.read_string()?;
+        self.read_u8()?; // Reserved; must be 1
+        self.read_u8()?; // Reserved; must be 0
+        let imports = self.read_export_assets()?;
+        Ok((url, imports))
+    }
+
     pub fn read_place_object(&mut self, tag_length: usize) -> Result<PlaceObject<'a>> {
         // TODO: What's a best way to know if the tag has a color transform?
         // You only know if there is still data remaining after the matrix.
@@ -2382,6 +2381,7 @@ impl<'a> Reader<'a> {
             4 => AudioCompression::Nellymoser16Khz,
             5 => AudioCompression::Nellymoser8Khz,
             6 => AudioCompression::Nellymoser,
+            10 => AudioCompression::Aac,
             11 => AudioCompression::Speex,
             _ => return Err(Error::invalid_data("Invalid audio format.")),
         };
@@ -2615,7 +2615,7 @@ impl<'a> Reader<'a> {
         })
     }
 
-    fn read_define_video_stream(&mut self) -> Result<Tag<'a>> {
+    pub fn read_define_video_stream(&mut self) -> Result<DefineVideoStream> {
         let id = self.read_character_id()?;
         let num_frames = self.read_u16()?;
         let width = self.read_u16()?;
@@ -2629,14 +2629,14 @@ impl<'a> Reader<'a> {
             5 => VideoCodec::VP6WithAlpha,
             _ => return Err(Error::invalid_data("Invalid video codec.")),
         };
-        Ok(Tag::DefineVideoStream(DefineVideoStream {
+        Ok(DefineVideoStream {
             id,
             num_frames,
             width,
             height,
             is_smoothed: flags & 0b1 != 0,
             codec,
-            deblocking: match flags & 0b100_0 {
+            deblocking: match flags & 0b111_0 {
                 0b000_0 => VideoDeblocking::UseVideoPacketValue,
                 0b001_0 => VideoDeblocking::None,
                 0b010_0 => VideoDeblocking::Level1,
@@ -2645,18 +2645,18 @@ impl<'a> Reader<'a> {
                 0b101_0 => VideoDeblocking::Level4,
                 _ => return Err(Error::invalid_data("Invalid video deblocking value.")),
             },
-        }))
+        })
     }
 
-    fn read_video_frame(&mut self) -> Result<Tag<'a>> {
+    pub fn read_video_frame(&mut self) -> Result<VideoFrame<'a>> {
         let stream_id = self.read_character_id()?;
         let frame_num = self.read_u16()?;
         let data = self.read_slice_to_end();
-        Ok(Tag::VideoFrame(VideoFrame {
+        Ok(VideoFrame {
             stream_id,
             frame_num,
             data,
-        }))
+        })
     }
 
     fn read_define_bits_jpeg_3(&mut self, version: u8) -> Result<Tag<'a>> {
@@ -913,6 +913,7 @@ pub enum AudioCompression {
     Nellymoser16Khz,
     Nellymoser8Khz,
     Nellymoser,
+    Aac,
     Speex,
 }
 
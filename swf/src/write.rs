@@ -2185,6 +2185,7 @@ impl<W: Write> Writer<W> {
                 AudioCompression::Nellymoser16Khz => 4,
                 AudioCompression::Nellymoser8Khz => 5,
                 AudioCompression::Nellymoser => 6,
+                AudioCompression::Aac => 10,
                 AudioCompression::Speex => 11,
             },
         )?;
@@ -124,6 +124,24 @@ export interface BaseLoadOptions {
      */
     letterbox?: Letterbox;
 
+    /**
+     * Controls how the movie is scaled to fit the Flash container, like the
+     * `scale` embed parameter. One of "showAll", "noBorder", "exactFit"
+     * or "noScale" (case-insensitive).
+     *
+     * @default "showAll"
+     */
+    scale?: string;
+
+    /**
+     * Controls how the movie is aligned within the Flash container, like the
+     * `salign` embed parameter. A combination of "T", "B", "L" and "R"
+     * (e.g. "TL"); the movie is centered along any axis that is not given.
+     *
+     * @default ""
+     */
+    salign?: string;
+
     /**
      * Controls the visibility of the unmute overlay when the player
      * is started muted.
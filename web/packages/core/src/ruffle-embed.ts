@@ -49,6 +49,8 @@ export class RuffleEmbed extends RufflePlayer {
                 ),
                 parameters: this.attributes.getNamedItem("flashvars")?.value,
                 backgroundColor: this.attributes.getNamedItem("bgcolor")?.value,
+                scale: this.attributes.getNamedItem("scale")?.value,
+                salign: this.attributes.getNamedItem("salign")?.value,
             });
         }
     }
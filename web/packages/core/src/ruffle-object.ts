@@ -112,6 +112,18 @@ export class RuffleObject extends RufflePlayer {
             this.getAttribute("bgcolor")
         );
 
+        const scale = findCaseInsensitive(
+            this.params,
+            "scale",
+            this.getAttribute("scale")
+        );
+
+        const salign = findCaseInsensitive(
+            this.params,
+            "salign",
+            this.getAttribute("salign")
+        );
+
         if (url) {
             const options: URLLoadOptions = { url };
             options.allowScriptAccess = isScriptAccessAllowed(
@@ -124,6 +136,12 @@ export class RuffleObject extends RufflePlayer {
             if (backgroundColor) {
                 options.backgroundColor = backgroundColor;
             }
+            if (scale) {
+                options.scale = scale;
+            }
+            if (salign) {
+                options.salign = salign;
+            }
 
             // Kick off the SWF download.
             this.load(options);
@@ -66,6 +66,31 @@ interface ContextMenuItem {
      * @default true
      */
     separator?: boolean;
+
+    /**
+     * Whether the item can be clicked
+     *
+     * @default true
+     */
+    enabled?: boolean;
+
+    /**
+     * Whether to show a check mark next to the item
+     *
+     * @default false
+     */
+    checked?: boolean;
+}
+
+/**
+ * A context menu item provided by the movie, as returned by
+ * `prepare_context_menu`.
+ */
+interface MovieContextMenuItem {
+    enabled: boolean;
+    separatorBefore: boolean;
+    checked: boolean;
+    caption: string;
 }
 
 /**
@@ -597,7 +622,23 @@ export class RufflePlayer extends HTMLElement {
     }
 
     private contextMenuItems(): ContextMenuItem[] {
-        const items = [];
+        const items: ContextMenuItem[] = [];
+
+        // Items of the movie come first, including the custom items of an
+        // ActionScript `ContextMenu`.
+        const movieItems: MovieContextMenuItem[] =
+            this.instance?.prepare_context_menu() ?? [];
+        movieItems.forEach((item, index) => {
+            const next = movieItems[index + 1];
+            items.push({
+                text: item.caption,
+                onClick: () => this.instance?.run_context_menu_callback(index),
+                separator: next === undefined || next.separatorBefore,
+                enabled: item.enabled,
+                checked: item.checked,
+            });
+        });
+
         if (this.fullscreenEnabled) {
             if (this.isFullscreen) {
                 items.push({
@@ -636,11 +677,21 @@ export class RufflePlayer extends HTMLElement {
         }
 
         // Populate context menu items.
-        for (const { text, onClick, separator } of this.contextMenuItems()) {
+        for (const {
+            text,
+            onClick,
+            separator,
+            enabled,
+            checked,
+        } of this.contextMenuItems()) {
             const menuItem = document.createElement("li");
-            menuItem.className = "menu_item active";
-            menuItem.textContent = text;
-            menuItem.addEventListener("click", onClick);
+            menuItem.textContent = checked === true ? `✓ ${text}` : text;
+            if (enabled !== false) {
+                menuItem.className = "menu_item active";
+                menuItem.addEventListener("click", onClick);
+            } else {
+                menuItem.className = "menu_item disabled";
+            }
             this.contextMenuElement.appendChild(menuItem);
 
             if (separator !== false) {
@@ -671,6 +722,10 @@ export class RufflePlayer extends HTMLElement {
     }
 
     private hideContextMenu(): void {
+        if (this.contextMenuElement.style.display === "block") {
+            // The menu was dismissed without choosing an item of the movie.
+            this.instance?.clear_custom_menu_items();
+        }
         this.contextMenuElement.style.display = "none";
     }
 
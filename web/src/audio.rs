@@ -1,9 +1,10 @@
 use fnv::FnvHashMap;
 use generational_arena::Arena;
 use ruffle_core::backend::audio::{
-    decoders::{AdpcmDecoder, Mp3Decoder, NellymoserDecoder},
+    decoders::{self, AdpcmDecoder, Mp3Decoder, NellymoserDecoder},
     swf::{self, AudioCompression},
-    AudioBackend, PreloadStreamHandle, SoundHandle, SoundInstanceHandle, SoundTransform,
+    AudioBackend, PreloadStreamHandle, SoundHandle, SoundInstanceHandle, SoundPlayback,
+    SoundTransform,
 };
 use ruffle_web_common::JsResult;
 use std::cell::{Cell, RefCell};
@@ -90,6 +91,13 @@ struct SoundInstance {
     /// either decoded on the fly with Decoder, or pre-decoded
     /// and played with and AudioBufferSourceNode.
     instance_type: SoundInstanceType,
+
+    /// The time of the `AudioContext` when this sound started playing, in seconds.
+    start_time: f64,
+
+    /// The portion of the sound being played.
+    /// `None` if this sound is played from its beginning with default settings.
+    playback: Option<SoundPlayback>,
 }
 
 /// The Drop impl ensures that the sound is stopped and remove from the audio context,
@@ -337,6 +345,10 @@ impl WebAudioBackend {
         settings: Option<&swf::SoundInfo>,
     ) -> Result<SoundInstanceHandle, Error> {
         let sound = self.sounds.get(handle).unwrap();
+        let start_time = self.context.current_time();
+        let playback = settings.map(|settings| {
+            SoundPlayback::new(settings, sound.format.sample_rate, sound.num_sample_frames)
+        });
         let handle = match &sound.source {
             SoundSource::AudioBuffer(audio_buffer) => {
                 let audio_buffer = audio_buffer.borrow();
@@ -365,7 +377,7 @@ impl WebAudioBackend {
                         node.start_with_when_and_grain_offset(0.0, start_sample_frame)
                             .warn_on_error();
 
-                        let current_time = self.context.current_time();
+                        let current_time = start_time;
 
                         // The length of the sound in the swf, or by the script playing it, doesn't
                         // always line up with the actual length of the sound.
@@ -420,6 +432,8 @@ impl WebAudioBackend {
                         buffer_source_node: buffer_source_node.clone(),
                         sound_transform_nodes: SoundTransformNodes::None,
                     }),
+                    start_time,
+                    playback,
                 };
                 let instance_handle = SOUND_INSTANCES.with(|instances| {
                     let mut instances = instances.borrow_mut();
@@ -441,6 +455,7 @@ impl WebAudioBackend {
                 instance_handle
             }
             SoundSource::Decoder(audio_data) => {
+                let mut sample_rate = sound.format.sample_rate;
                 let decoder: Decoder = match sound.format.compression {
                     AudioCompression::Adpcm => Box::new(AdpcmDecoder::new(
                         std::io::Cursor::new(audio_data.to_vec()),
@@ -456,26 +471,33 @@ impl WebAudioBackend {
                         std::io::Cursor::new(audio_data.to_vec()),
                         sound.format.sample_rate.into(),
                     )),
-                    compression => {
-                        return Err(format!("Unimplemented codec: {:?}", compression).into())
+                    _ => {
+                        let decoder = decoders::make_decoder(
+                            &sound.format,
+                            std::io::Cursor::new(audio_data.to_vec()),
+                        )?;
+                        // Some codecs, such as Speex, ignore the sample rate of the sound format.
+                        sample_rate = decoder.sample_rate();
+                        Box::new(decoder)
                     }
                 };
 
-                let decoder: Decoder =
-                    if sound.format.sample_rate != self.context.sample_rate() as u16 {
-                        Box::new(resample(
-                            decoder,
-                            sound.format.sample_rate,
-                            self.context.sample_rate() as u16,
-                        ))
-                    } else {
-                        decoder
-                    };
+                let decoder: Decoder = if sample_rate != self.context.sample_rate() as u16 {
+                    Box::new(resample(
+                        decoder,
+                        sample_rate,
+                        self.context.sample_rate() as u16,
+                    ))
+                } else {
+                    decoder
+                };
 
                 let instance = SoundInstance {
                     handle: Some(handle),
                     format: sound.format.clone(),
                     instance_type: SoundInstanceType::Decoder(decoder),
+                    start_time,
+                    playback,
                 };
                 SOUND_INSTANCES.with(|instances| {
                     let mut instances = instances.borrow_mut();
@@ -592,6 +614,7 @@ impl WebAudioBackend {
         self.left_samples.clear();
         self.right_samples.clear();
 
+        let mut decoded_sample_rate = format.sample_rate;
         match format.compression {
             AudioCompression::Uncompressed | AudioCompression::UncompressedUnknownEndian => {
                 use byteorder::{LittleEndian, ReadBytesExt};
@@ -645,13 +668,22 @@ impl WebAudioBackend {
                     self.right_samples.push(f32::from(r) / 32767.0);
                 }
             }
-            compression => return Err(format!("Unimplemented codec: {:?}", compression).into()),
+            _ => {
+                let decoder = decoders::make_decoder(format, audio_data)?;
+                // Some codecs, such as Speex, ignore the sample rate of the sound format.
+                decoded_sample_rate = decoder.sample_rate();
+                for frame in decoder {
+                    let (l, r) = (frame[0], frame[1]);
+                    self.left_samples.push(f32::from(l) / 32767.0);
+                    self.right_samples.push(f32::from(r) / 32767.0);
+                }
+            }
         }
 
         // This sucks. Firefox and Safari don't like low sample rates,
         // so manually multiply the samples.
-        let sample_rate = if format.sample_rate < self.min_sample_rate {
-            let sample_multiplier = self.min_sample_rate / format.sample_rate;
+        let sample_rate = if decoded_sample_rate < self.min_sample_rate {
+            let sample_multiplier = self.min_sample_rate / decoded_sample_rate;
             let mut samples = Vec::with_capacity(self.left_samples.len() * 2);
             for sample in &self.left_samples {
                 for _ in 0..sample_multiplier {
@@ -672,7 +704,7 @@ impl WebAudioBackend {
 
             self.min_sample_rate
         } else {
-            format.sample_rate
+            decoded_sample_rate
         };
 
         let num_sample_frames = self.left_samples.len() as u32;
@@ -892,6 +924,7 @@ impl AudioBackend for WebAudioBackend {
                 _ => {
                     // TODO: This is a guess and will vary slightly from block to block!
                     stream.num_sample_frames += stream.samples_per_block;
+                    stream.audio_data.extend_from_slice(audio_data);
                 }
             }
         }
@@ -1022,12 +1055,13 @@ impl AudioBackend for WebAudioBackend {
     fn get_sound_position(&self, instance: SoundInstanceHandle) -> Option<u32> {
         SOUND_INSTANCES.with(|instances| {
             let instances = instances.borrow();
-            if let Some(_instance) = instances.get(instance) {
-                // TODO: Return actual position
-                Some(0)
-            } else {
-                None
-            }
+            instances.get(instance).map(|instance| {
+                let elapsed = (self.context.current_time() - instance.start_time) * 1000.0;
+                match &instance.playback {
+                    Some(playback) => playback.position(elapsed),
+                    None => elapsed as u32,
+                }
+            })
         })
     }
 
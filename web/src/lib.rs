@@ -16,9 +16,12 @@ use generational_arena::{Arena, Index};
 use js_sys::{Array, Function, Object, Uint8Array};
 use ruffle_core::backend::{
     audio::{AudioBackend, NullAudioBackend},
+    local_connection::{LocalConnectionHub, MemoryLocalConnectionBackend},
     render::RenderBackend,
+    socket::NullSocketBackend,
     storage::{MemoryStorageBackend, StorageBackend},
     ui::UiBackend,
+    video::SoftwareVideoBackend,
 };
 use ruffle_core::config::Letterbox;
 use ruffle_core::context::UpdateContext;
@@ -37,8 +40,8 @@ use std::sync::{Arc, Mutex};
 use std::{cell::RefCell, error::Error, num::NonZeroI32};
 use wasm_bindgen::{prelude::*, JsCast, JsValue};
 use web_sys::{
-    AddEventListenerOptions, Element, Event, EventTarget, HtmlCanvasElement, HtmlElement,
-    KeyboardEvent, PointerEvent, WheelEvent,
+    AddEventListenerOptions, ClipboardEvent, Element, Event, EventTarget, HtmlCanvasElement,
+    HtmlElement, KeyboardEvent, PointerEvent, WheelEvent,
 };
 
 static RUFFLE_GLOBAL_PANIC: Once = Once::new();
@@ -50,6 +53,10 @@ thread_local! {
     static INSTANCES: RefCell<Arena<RefCell<RuffleInstance>>> = RefCell::new(Arena::new());
 
     static CURRENT_CONTEXT: RefCell<Option<*mut UpdateContext<'static, 'static, 'static>>> = RefCell::new(None);
+
+    /// Every instance on the page shares one set of local connections, so that
+    /// movies may communicate with each other.
+    static LOCAL_CONNECTIONS: LocalConnectionHub = LocalConnectionHub::default();
 }
 
 type AnimationHandler = Closure<dyn FnMut(f64)>;
@@ -73,6 +80,7 @@ struct RuffleInstance {
     mouse_wheel_callback: Option<Closure<dyn FnMut(WheelEvent)>>,
     key_down_callback: Option<Closure<dyn FnMut(KeyboardEvent)>>,
     key_up_callback: Option<Closure<dyn FnMut(KeyboardEvent)>>,
+    paste_callback: Option<Closure<dyn FnMut(ClipboardEvent)>>,
     unload_callback: Option<Closure<dyn FnMut(Event)>>,
     has_focus: bool,
     trace_observer: Arc<RefCell<JsValue>>,
@@ -127,6 +135,10 @@ pub struct Config {
 
     letterbox: Letterbox,
 
+    scale: Option<String>,
+
+    salign: Option<String>,
+
     #[serde(rename = "upgradeToHttps")]
     upgrade_to_https: bool,
 
@@ -143,6 +155,8 @@ impl Default for Config {
             allow_script_access: false,
             background_color: Default::default(),
             letterbox: Default::default(),
+            scale: None,
+            salign: None,
             upgrade_to_https: true,
             warn_on_unsupported_content: true,
             log_level: log::Level::Error,
@@ -242,6 +256,48 @@ impl Ruffle {
         })
     }
 
+    /// Builds the context menu for the object under the mouse cursor.
+    /// Returns an array of items to display before the player's own items.
+    pub fn prepare_context_menu(&mut self) -> JsValue {
+        INSTANCES.with(|instances| {
+            let instances = instances.borrow();
+            let instance = instances.get(self.0).unwrap();
+            let items = instance
+                .borrow()
+                .core
+                .lock()
+                .unwrap()
+                .prepare_context_menu();
+            JsValue::from_serde(&items).unwrap_or(JsValue::UNDEFINED)
+        })
+    }
+
+    pub fn run_context_menu_callback(&mut self, index: usize) {
+        INSTANCES.with(|instances| {
+            let instances = instances.borrow();
+            let instance = instances.get(self.0).unwrap();
+            instance
+                .borrow()
+                .core
+                .lock()
+                .unwrap()
+                .run_context_menu_callback(index);
+        });
+    }
+
+    pub fn clear_custom_menu_items(&mut self) {
+        INSTANCES.with(|instances| {
+            let instances = instances.borrow();
+            let instance = instances.get(self.0).unwrap();
+            instance
+                .borrow()
+                .core
+                .lock()
+                .unwrap()
+                .clear_custom_menu_items();
+        });
+    }
+
     pub fn destroy(&mut self) {
         // Remove instance from the active list.
         if let Some(instance) = INSTANCES.with(|instances| {
@@ -341,6 +397,15 @@ impl Ruffle {
                         .unwrap();
                     instance.key_up_callback = None;
                 }
+                if let Some(paste_callback) = &instance.paste_callback {
+                    window
+                        .remove_event_listener_with_callback(
+                            "paste",
+                            paste_callback.as_ref().unchecked_ref(),
+                        )
+                        .unwrap();
+                    instance.paste_callback = None;
+                }
                 if let Some(unload_callback) = &instance.unload_callback {
                     window
                         .remove_event_listener_with_callback(
@@ -446,6 +511,7 @@ impl Ruffle {
             allow_script_access,
             config.upgrade_to_https,
         ));
+        let sockets = Box::new(NullSocketBackend::new());
         let storage = match window.local_storage() {
             Ok(Some(s)) => {
                 Box::new(storage::LocalStorageBackend::new(s)) as Box<dyn StorageBackend>
@@ -455,17 +521,36 @@ impl Ruffle {
                 Box::new(MemoryStorageBackend::default())
             }
         };
+        let local_connection = Box::new(LOCAL_CONNECTIONS.with(MemoryLocalConnectionBackend::new));
         let locale = Box::new(locale::WebLocaleBackend::new());
         let trace_observer = Arc::new(RefCell::new(JsValue::UNDEFINED));
         let log = Box::new(log_adapter::WebLogBackend::new(trace_observer.clone()));
         let ui = Box::new(ui::WebUiBackend::new(js_player.clone(), &canvas));
-        let core = ruffle_core::Player::new(renderer, audio, navigator, storage, locale, log, ui)?;
+        let video = Box::new(SoftwareVideoBackend::new());
+        let core = ruffle_core::Player::new(
+            renderer,
+            audio,
+            video,
+            navigator,
+            sockets,
+            storage,
+            local_connection,
+            locale,
+            log,
+            ui,
+        )?;
         {
             let mut core = core.lock().unwrap();
             if let Some(color) = config.background_color.and_then(parse_html_color) {
                 core.set_background_color(Some(color));
             }
             core.set_letterbox(config.letterbox);
+            if let Some(scale_mode) = config.scale.and_then(|scale| scale.parse().ok()) {
+                core.set_scale_mode(scale_mode);
+            }
+            if let Some(stage_align) = config.salign.and_then(|salign| salign.parse().ok()) {
+                core.set_stage_align(stage_align);
+            }
             core.set_warn_on_unsupported_content(config.warn_on_unsupported_content);
         }
 
@@ -487,6 +572,7 @@ impl Ruffle {
             mouse_wheel_callback: None,
             key_down_callback: None,
             key_up_callback: None,
+            paste_callback: None,
             unload_callback: None,
             timestamp: None,
             has_focus: false,
@@ -561,8 +647,8 @@ impl Ruffle {
                     INSTANCES.with(move |instances| {
                         let instances = instances.borrow();
                         if let Some(instance) = instances.get(index) {
-                            // Only fire player mouse event for left clicks.
-                            if js_event.button() == 0 {
+                            if let Some(button) = ui::web_to_ruffle_mouse_button(js_event.button())
+                            {
                                 if let Some(target) = js_event.current_target() {
                                     let _ = target
                                         .unchecked_ref::<Element>()
@@ -572,6 +658,7 @@ impl Ruffle {
                                 let event = PlayerEvent::MouseDown {
                                     x: f64::from(js_event.offset_x()) * device_pixel_ratio,
                                     y: f64::from(js_event.offset_y()) * device_pixel_ratio,
+                                    button,
                                 };
                                 instance.borrow().core.lock().unwrap().handle_event(event);
                             }
@@ -653,8 +740,8 @@ impl Ruffle {
                         if let Some(instance) = instances.get(index) {
                             let instance = instance.borrow();
 
-                            // Only fire player mouse event for left clicks.
-                            if js_event.button() == 0 {
+                            if let Some(button) = ui::web_to_ruffle_mouse_button(js_event.button())
+                            {
                                 if let Some(target) = js_event.current_target() {
                                     let _ = target
                                         .unchecked_ref::<Element>()
@@ -663,6 +750,7 @@ impl Ruffle {
                                 let event = PlayerEvent::MouseUp {
                                     x: f64::from(js_event.offset_x()) * instance.device_pixel_ratio,
                                     y: f64::from(js_event.offset_y()) * instance.device_pixel_ratio,
+                                    button,
                                 };
                                 instance.core.lock().unwrap().handle_event(event);
                             }
@@ -739,16 +827,21 @@ impl Ruffle {
 
                                 let key_code = ui.last_key_code();
                                 let key_char = ui.last_key_char();
+                                let is_paste = ui::is_paste_shortcut(&js_event);
 
                                 if key_code != KeyCode::Unknown {
                                     core.handle_event(PlayerEvent::KeyDown { key_code });
                                 }
 
-                                if let Some(codepoint) = key_char {
+                                if let Some(codepoint) = key_char.filter(|_| !is_paste) {
                                     core.handle_event(PlayerEvent::TextInput { codepoint });
                                 }
 
-                                js_event.prevent_default();
+                                // Let the browser fire a `paste` event, which carries the
+                                // content of the clipboard.
+                                if !is_paste {
+                                    js_event.prevent_default();
+                                }
                             }
                         }
                     });
@@ -799,6 +892,37 @@ impl Ruffle {
                 instance.key_up_callback = Some(key_up_callback);
             }
 
+            // Create paste event handler.
+            {
+                let paste_callback = Closure::wrap(Box::new(move |js_event: ClipboardEvent| {
+                    INSTANCES.with(|instances| {
+                        if let Some(instance) = instances.borrow().get(index) {
+                            let instance = instance.borrow();
+                            if instance.has_focus {
+                                let text = js_event
+                                    .clipboard_data()
+                                    .and_then(|data| data.get_data("text/plain").ok())
+                                    .unwrap_or_default();
+                                let mut core = instance.core.lock().unwrap();
+                                core.handle_event(PlayerEvent::TextPaste { text });
+
+                                js_event.prevent_default();
+                            }
+                        }
+                    });
+                })
+                    as Box<dyn FnMut(ClipboardEvent)>);
+
+                window
+                    .add_event_listener_with_callback(
+                        "paste",
+                        paste_callback.as_ref().unchecked_ref(),
+                    )
+                    .unwrap();
+                let mut instance = instances.get(index).unwrap().borrow_mut();
+                instance.paste_callback = Some(paste_callback);
+            }
+
             {
                 let unload_callback = Closure::wrap(Box::new(move |_| {
                     INSTANCES.with(|instances| {
@@ -1,14 +1,15 @@
 //! Navigator backend for web
-use js_sys::{Array, ArrayBuffer, Uint8Array};
+use js_sys::{Array, ArrayBuffer, Function, Promise, Reflect, Uint8Array};
 use ruffle_core::backend::navigator::{
-    url_from_relative_url, NavigationMethod, NavigatorBackend, OwnedFuture, RequestOptions,
+    url_from_relative_url, ChunkHandler, NavigationMethod, NavigatorBackend, OwnedFuture,
+    RequestOptions,
 };
 use ruffle_core::indexmap::IndexMap;
 use ruffle_core::loader::Error;
 use std::borrow::Cow;
 use std::time::Duration;
 use url::Url;
-use wasm_bindgen::JsCast;
+use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_futures::{spawn_local, JsFuture};
 use web_sys::{window, Blob, BlobPropertyBag, Performance, Request, RequestInit, Response};
 
@@ -51,6 +52,61 @@ impl WebNavigatorBackend {
             log::error!("SWF tried to run a script, but script access is not allowed");
         }
     }
+
+    /// Apply any pre-processing to a URL to be fetched.
+    fn fetch_url(&self, url: &str) -> String {
+        if let Ok(parsed_url) = Url::parse(url) {
+            self.pre_process_url(parsed_url).to_string()
+        } else {
+            url.to_string()
+        }
+    }
+}
+
+/// Send a request, returning the response once its headers have arrived.
+async fn send_request(url: String, options: RequestOptions) -> Result<Response, Error> {
+    let mut init = RequestInit::new();
+
+    init.method(match options.method() {
+        NavigationMethod::GET => "GET",
+        NavigationMethod::POST => "POST",
+    });
+
+    if let Some((data, mime)) = options.body() {
+        let arraydata = ArrayBuffer::new(data.len() as u32);
+        let u8data = Uint8Array::new(&arraydata);
+
+        for (i, byte) in data.iter().enumerate() {
+            u8data.fill(*byte, i as u32, i as u32 + 1);
+        }
+
+        let blobparts = Array::new();
+        blobparts.push(&arraydata);
+
+        let mut blobprops = BlobPropertyBag::new();
+        blobprops.type_(mime);
+
+        let datablob = Blob::new_with_buffer_source_sequence_and_options(&blobparts, &blobprops)
+            .unwrap()
+            .dyn_into()
+            .unwrap();
+
+        init.body(Some(&datablob));
+    }
+
+    let request = Request::new_with_str_and_init(&url, &init)
+        .map_err(|_| Error::FetchError(format!("Unable to create request for {}", url)))?;
+
+    let window = web_sys::window().unwrap();
+    let fetchval = JsFuture::from(window.fetch_with_request(&request)).await;
+    if fetchval.is_err() {
+        return Err(Error::NetworkError(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "Could not fetch, got JS Error",
+        )));
+    }
+
+    Ok(fetchval.unwrap().dyn_into().unwrap())
 }
 
 impl NavigatorBackend for WebNavigatorBackend {
@@ -155,56 +211,10 @@ impl NavigatorBackend for WebNavigatorBackend {
     }
 
     fn fetch(&self, url: &str, options: RequestOptions) -> OwnedFuture<Vec<u8>, Error> {
-        let url = if let Ok(parsed_url) = Url::parse(url) {
-            self.pre_process_url(parsed_url).to_string()
-        } else {
-            url.to_string()
-        };
+        let url = self.fetch_url(url);
 
         Box::pin(async move {
-            let mut init = RequestInit::new();
-
-            init.method(match options.method() {
-                NavigationMethod::GET => "GET",
-                NavigationMethod::POST => "POST",
-            });
-
-            if let Some((data, mime)) = options.body() {
-                let arraydata = ArrayBuffer::new(data.len() as u32);
-                let u8data = Uint8Array::new(&arraydata);
-
-                for (i, byte) in data.iter().enumerate() {
-                    u8data.fill(*byte, i as u32, i as u32 + 1);
-                }
-
-                let blobparts = Array::new();
-                blobparts.push(&arraydata);
-
-                let mut blobprops = BlobPropertyBag::new();
-                blobprops.type_(mime);
-
-                let datablob =
-                    Blob::new_with_buffer_source_sequence_and_options(&blobparts, &blobprops)
-                        .unwrap()
-                        .dyn_into()
-                        .unwrap();
-
-                init.body(Some(&datablob));
-            }
-
-            let request = Request::new_with_str_and_init(&url, &init)
-                .map_err(|_| Error::FetchError(format!("Unable to create request for {}", url)))?;
-
-            let window = web_sys::window().unwrap();
-            let fetchval = JsFuture::from(window.fetch_with_request(&request)).await;
-            if fetchval.is_err() {
-                return Err(Error::NetworkError(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "Could not fetch, got JS Error",
-                )));
-            }
-
-            let resp: Response = fetchval.unwrap().dyn_into().unwrap();
+            let resp = send_request(url, options).await?;
             let data: ArrayBuffer = JsFuture::from(resp.array_buffer().unwrap())
                 .await
                 .unwrap()
@@ -218,6 +228,56 @@ impl NavigatorBackend for WebNavigatorBackend {
         })
     }
 
+    fn fetch_chunked(
+        &self,
+        url: &str,
+        options: RequestOptions,
+        mut on_chunk: ChunkHandler,
+    ) -> OwnedFuture<(), Error> {
+        let url = self.fetch_url(url);
+
+        Box::pin(async move {
+            let resp = send_request(url, options).await?;
+            let total = resp
+                .headers()
+                .get("Content-Length")
+                .ok()
+                .flatten()
+                .and_then(|len| len.parse().ok());
+
+            // `Response.body` isn't exposed by the stable `web-sys` bindings.
+            let read_error =
+                |_: JsValue| Error::FetchError("Unable to read response body".to_string());
+            let body = Reflect::get(&resp, &"body".into()).map_err(read_error)?;
+            if body.is_null() || body.is_undefined() {
+                return on_chunk(&[], total);
+            }
+            let get_reader: Function = Reflect::get(&body, &"getReader".into())
+                .and_then(|f| f.dyn_into())
+                .map_err(read_error)?;
+            let reader = get_reader.call0(&body).map_err(read_error)?;
+            let read: Function = Reflect::get(&reader, &"read".into())
+                .and_then(|f| f.dyn_into())
+                .map_err(read_error)?;
+
+            loop {
+                let promise: Promise = read
+                    .call0(&reader)
+                    .and_then(|p| p.dyn_into())
+                    .map_err(read_error)?;
+                let result = JsFuture::from(promise).await.map_err(read_error)?;
+                let done = Reflect::get(&result, &"done".into()).map_err(read_error)?;
+                if done.as_bool().unwrap_or(true) {
+                    return Ok(());
+                }
+                let chunk: Uint8Array = Reflect::get(&result, &"value".into())
+                    .and_then(|v| v.dyn_into())
+                    .map_err(read_error)?;
+                on_chunk(&chunk.to_vec(), total)?;
+            }
+        })
+    }
+
     fn spawn_future(&mut self, future: OwnedFuture<(), Error>) {
         spawn_local(async move {
             if let Err(e) = future.await {
@@ -12,12 +12,17 @@ impl LocalStorageBackend {
 }
 
 impl StorageBackend for LocalStorageBackend {
-    fn get_string(&self, name: &str) -> Option<String> {
-        self.storage.get(name).unwrap_or_default()
+    fn get(&self, name: &str) -> Option<Vec<u8>> {
+        let value = self.storage.get(name).unwrap_or_default()?;
+
+        // Local storage can only hold strings, so data is stored as base64.
+        // Older versions of Ruffle stored plain JSON, which is passed through
+        // as-is so that it can still be read.
+        Some(base64::decode(&value).unwrap_or_else(|_| value.into_bytes()))
     }
 
-    fn put_string(&mut self, name: &str, value: String) -> bool {
-        self.storage.set(name, &value).is_ok()
+    fn put(&mut self, name: &str, value: &[u8]) -> bool {
+        self.storage.set(name, &base64::encode(value)).is_ok()
     }
 
     fn remove_key(&mut self, name: &str) {
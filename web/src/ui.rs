@@ -1,8 +1,12 @@
 use super::JavascriptPlayer;
+use js_sys::{Function, Promise, Reflect};
 use ruffle_core::backend::ui::{MouseCursor, UiBackend};
-use ruffle_core::events::KeyCode;
+use ruffle_core::context_menu::ContextMenuItem;
+use ruffle_core::events::{KeyCode, MouseButton};
 use ruffle_web_common::JsResult;
 use std::collections::HashSet;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
 use web_sys::{HtmlCanvasElement, KeyboardEvent};
 
 /// An implementation of `UiBackend` utilizing `web_sys` bindings to input
@@ -69,6 +73,7 @@ impl UiBackend for WebUiBackend {
         match key {
             KeyCode::Unknown => false,
             KeyCode::Backspace => self.keys_down.contains("Backspace"),
+            KeyCode::Tab => self.keys_down.contains("Tab"),
             KeyCode::Return => self.keys_down.contains("Enter"),
             KeyCode::Shift => {
                 self.keys_down.contains("ShiftLeft") || self.keys_down.contains("ShiftRight")
@@ -193,8 +198,14 @@ impl UiBackend for WebUiBackend {
         self.update_mouse_cursor();
     }
 
-    fn set_clipboard_content(&mut self, _content: String) {
-        log::warn!("set clipboard not implemented");
+    fn set_clipboard_content(&mut self, content: String) {
+        write_clipboard_text(&content).warn_on_error();
+    }
+
+    fn clipboard_content(&mut self) -> String {
+        // The clipboard can only be read asynchronously, so pasted text is
+        // delivered by the `paste` event instead.
+        String::new()
     }
 
     fn is_fullscreen(&self) -> bool {
@@ -208,6 +219,48 @@ impl UiBackend for WebUiBackend {
     fn message(&self, message: &str) {
         self.js_player.display_message(message);
     }
+
+    fn display_context_menu(&mut self, _items: &[ContextMenuItem]) {
+        // The context menu is displayed by the JavaScript player in response to the
+        // `contextmenu` event, which runs the chosen item via `Ruffle`.
+    }
+}
+
+/// Write text to the system clipboard, using the asynchronous Clipboard API.
+fn write_clipboard_text(text: &str) -> Result<(), JsValue> {
+    let navigator = web_sys::window().ok_or("Expected window")?.navigator();
+
+    // `navigator.clipboard` is only available in secure contexts, and isn't
+    // exposed by the stable `web-sys` bindings.
+    let clipboard = Reflect::get(&navigator, &"clipboard".into())?;
+    if clipboard.is_undefined() {
+        return Err("The Clipboard API is unavailable".into());
+    }
+    let write_text: Function = Reflect::get(&clipboard, &"writeText".into())?.dyn_into()?;
+    let promise: Promise = write_text.call1(&clipboard, &text.into())?.dyn_into()?;
+
+    spawn_local(async move {
+        if let Err(e) = JsFuture::from(promise).await {
+            log::warn!("Unable to write to the clipboard: {:?}", e);
+        }
+    });
+    Ok(())
+}
+
+/// Returns whether a key press is the shortcut for pasting text.
+pub fn is_paste_shortcut(event: &KeyboardEvent) -> bool {
+    (event.ctrl_key() || event.meta_key()) && event.code() == "KeyV"
+}
+
+/// Convert a web `MouseEvent.button` value into a Ruffle `MouseButton`.
+/// Return `None` if there is no matching Flash mouse button.
+pub fn web_to_ruffle_mouse_button(button: i16) -> Option<MouseButton> {
+    match button {
+        0 => Some(MouseButton::Left),
+        1 => Some(MouseButton::Middle),
+        2 => Some(MouseButton::Right),
+        _ => None,
+    }
 }
 
 /// Convert a web `KeyboardEvent.code` value into a Ruffle `KeyCode`.
@@ -215,6 +268,7 @@ impl UiBackend for WebUiBackend {
 pub fn web_to_ruffle_key_code(key_code: &str) -> Option<KeyCode> {
     Some(match key_code {
         "Backspace" => KeyCode::Backspace,
+        "Tab" => KeyCode::Tab,
         "Enter" => KeyCode::Return,
         "ShiftLeft" | "ShiftRight" => KeyCode::Shift,
         "ControlLeft" | "ControlRight" => KeyCode::Control,